@@ -0,0 +1,58 @@
+/* The solvers of advent_of_code as a C library. Build it with
+ *
+ *     cargo rustc --lib --release --features ffi --crate-type cdylib
+ *
+ * and link target/release/libadvent_of_code.so (.dylib, .dll). The codes and
+ * signatures here only ever get added to; anything else bumps
+ * AOC_ABI_VERSION. */
+#ifndef ADVENT_OF_CODE_H
+#define ADVENT_OF_CODE_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define AOC_ABI_VERSION 1
+
+#define AOC_OK 0
+/* The input doesn't look like what the puzzle describes */
+#define AOC_ERROR_PARSE 1
+/* The input reads fine but has no answer */
+#define AOC_ERROR_UNSOLVABLE 2
+/* The day has no solution yet */
+#define AOC_ERROR_UNIMPLEMENTED 3
+/* There is no such year, day or part */
+#define AOC_ERROR_UNKNOWN 4
+/* A file couldn't be read or written */
+#define AOC_ERROR_IO 5
+/* A null input, or an input that isn't UTF-8 */
+#define AOC_ERROR_ARGUMENT 6
+/* The solver panicked, the message says where */
+#define AOC_ERROR_PANIC 7
+
+/* The version the library was built with, compare it with AOC_ABI_VERSION */
+uint32_t aoc_abi_version(void);
+
+/* Solves a part of a day on a NUL-terminated UTF-8 input. Returns AOC_OK and
+ * points *out at the answer, or an error code and points *out at the message.
+ * Drawn answers come as their lines. The string belongs to the caller, who
+ * frees it with aoc_string_free and never with free(). out can be NULL when
+ * only the code matters. Safe to call from several threads at once. */
+int aoc_solve(uint16_t year, uint8_t day, uint8_t part, const char *input,
+              char **out);
+
+/* Same as aoc_solve, on the example of the puzzle text. A few days ask
+ * something else of it than of the real inputs. */
+int aoc_solve_example(uint16_t year, uint8_t day, uint8_t part,
+                      const char *input, char **out);
+
+/* Frees a string from aoc_solve or aoc_solve_example, NULL is fine */
+void aoc_string_free(char *text);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
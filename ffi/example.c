@@ -0,0 +1,41 @@
+/* Solves a part of a day on a file, through the C interface:
+ *
+ *     cargo rustc --lib --release --features ffi --crate-type cdylib
+ *     cc ffi/example.c -Iffi -Ltarget/release -ladvent_of_code -o solve
+ *     LD_LIBRARY_PATH=target/release ./solve 2022 1 2 inputs/2022/day01.example.txt
+ */
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "advent_of_code.h"
+
+int main(int argc, char **argv) {
+    if (argc != 5) {
+        fprintf(stderr, "Usage: %s YEAR DAY PART INPUT\n", argv[0]);
+        return 2;
+    }
+    if (aoc_abi_version() != AOC_ABI_VERSION) {
+        fprintf(stderr, "Built for another version of the library\n");
+        return 2;
+    }
+
+    FILE *file = fopen(argv[4], "rb");
+    if (file == NULL) {
+        perror(argv[4]);
+        return 2;
+    }
+    fseek(file, 0, SEEK_END);
+    long length = ftell(file);
+    rewind(file);
+    char *input = malloc(length + 1);
+    input[fread(input, 1, length, file)] = '\0';
+    fclose(file);
+
+    char *out = NULL;
+    int code = aoc_solve(atoi(argv[1]), atoi(argv[2]), atoi(argv[3]), input, &out);
+    free(input);
+
+    printf("%s\n", out);
+    aoc_string_free(out);
+    return code;
+}
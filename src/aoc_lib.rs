@@ -1,5 +1,278 @@
+#[cfg(feature = "std")]
+use std::io::{BufRead, Write};
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "std")]
+use std::time::Duration;
+
+#[cfg(feature = "std")]
+use config::InputKind;
+
 // Days
-// Expose parts of the library
+/// nom parsers for the numbers found in the inputs
 pub mod jazz_parser;
 
-pub type DayFn = fn(&str);
+// Axis aligned boxes for the 3D volume puzzles
+#[cfg(feature = "std")]
+#[allow(dead_code)]
+pub mod cuboid;
+
+// Exports geometry so it can be looked at in a 3D viewer
+#[cfg(feature = "std")]
+pub mod mesh;
+
+/// Graph searches over implicit graphs, the neighbours come from a closure
+#[allow(dead_code)]
+pub mod search;
+
+// Finds where a simulation starts repeating so it can be skipped ahead
+pub mod cycles;
+
+/// Nodes of trees and graphs stored together and linked by index, for the
+/// days that build a lot of them
+pub mod arena;
+
+// Sets of integers stored as merged ranges
+#[allow(dead_code)]
+pub mod intervals;
+
+// Lists of numbers and other lists, compared like the distress signal packets
+#[cfg(feature = "std")]
+pub(crate) mod nested;
+
+/// Rectangular grids of cells, usable as graphs by the searches
+#[allow(dead_code)]
+pub mod grid;
+
+/// Points on a plane and in space, and the ways to turn them
+#[allow(dead_code)]
+pub mod geometry;
+
+// Reads the big letters some puzzles draw as their answer
+#[cfg(feature = "std")]
+#[allow(dead_code)]
+pub(crate) mod ocr;
+
+/// Numbers written in unusual positional systems
+#[allow(dead_code)]
+pub mod numbers;
+
+// Hashes for the puzzles that mine for digests with some zeros in front
+#[cfg(feature = "std")]
+#[allow(dead_code)]
+pub(crate) mod md5;
+
+// Wires and logic gates whose signals flow once every input is known
+#[cfg(feature = "std")]
+pub(crate) mod circuit;
+
+// Seeded random numbers, for the generated inputs and the tests that check a
+// property over many cases
+#[cfg(any(feature = "std", test))]
+#[allow(dead_code)]
+pub mod random;
+
+// Made up inputs of any size for stress tests and benchmarks
+#[cfg(feature = "std")]
+pub mod testgen;
+
+// Times a solver against the one it replaced, for the ignored speedup tests:
+// cargo test --release -- --ignored --nocapture speedup
+#[cfg(all(feature = "std", test))]
+#[allow(dead_code)]
+pub(crate) mod bench;
+
+// The table of examples from the puzzle texts and their answers
+#[cfg(feature = "std")]
+pub mod examples;
+
+// The inputs and answers aoc-cli downloaded, copied from and to its layout
+#[cfg(feature = "std")]
+pub mod aoc_cli;
+
+// The table of the solved days, their stars, answers and timings
+#[cfg(feature = "std")]
+pub mod report;
+
+// Renders checked against the accepted ones in snapshots/, by the tests of the
+// days that draw something
+#[cfg(all(feature = "std", test))]
+pub(crate) mod snapshot;
+
+// Animations of the days played in the terminal, for --visualize
+#[cfg(feature = "std")]
+pub mod viz;
+
+// Pictures and animations of grids saved as PNG and GIF files, for --images
+#[cfg(feature = "images")]
+pub mod images;
+
+// Solutions answered over HTTP as JSON, for the serve command
+#[cfg(feature = "serve")]
+pub mod serve;
+
+// Tables of grids, answers and counts in a Jupyter notebook on evcxr
+#[cfg(feature = "evcxr")]
+pub mod evcxr;
+
+// What the serve command answered, for Prometheus
+#[cfg(feature = "serve")]
+pub mod metrics;
+
+// The JSON a solve request gets back, over HTTP or in the browser
+#[cfg(feature = "std")]
+pub mod reply;
+
+// The C interface, for embedding the solvers in tools written in other
+// languages
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+// The functions a web page calls on the library built for WebAssembly
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+// Instant, or a stand-in always at zero in the browser where std has no clock
+#[cfg(feature = "std")]
+pub(crate) mod clock;
+
+// Answers and timings appended to a SQLite database, for --record
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+// JSON values, read and written, for the requests of the --stdio mode
+#[cfg(feature = "std")]
+pub mod json;
+
+// The --stdio mode, JSON-RPC requests on stdin answered on stdout
+#[cfg(feature = "std")]
+pub mod rpc;
+
+// Graphs written in the DOT language of Graphviz, for --export-graph
+#[cfg(feature = "std")]
+pub mod dot;
+
+// Events recorded step by step while solving, written as JSON lines for
+// --trace
+#[cfg(feature = "std")]
+pub mod trace;
+
+// Counters the long searches tick, shown by the runner with --progress
+#[cfg(feature = "std")]
+pub mod progress;
+
+// Indented breakdowns of how a day got to its answer, for --explain
+#[cfg(feature = "std")]
+pub mod explain;
+
+// What the days answer with, or why they couldn't
+#[cfg(feature = "std")]
+pub mod answer;
+#[cfg(feature = "std")]
+pub mod error;
+
+#[cfg(feature = "std")]
+pub use answer::{Answer, Solved};
+#[cfg(feature = "std")]
+pub use error::AocError;
+
+// The names the days import, all at once
+pub mod prelude;
+
+// Days split in a parsing and a solving phase, timed separately
+#[cfg(feature = "std")]
+pub mod solution;
+
+// The constants of a day that differ between the example and the real inputs
+#[cfg(feature = "std")]
+pub mod config;
+
+// Common interface of the years, so they can be listed in one place. It hands
+// the runner what the days registered and says what's missing as errors, the
+// same as the days it's made of.
+#[cfg(feature = "std")]
+#[cfg_attr(
+    not(test),
+    deny(clippy::print_stdout, clippy::print_stderr, clippy::dbg_macro)
+)]
+pub mod year;
+
+// Virtual machines for the "run this program" puzzles, not all of them have a
+// day using them yet
+#[cfg(feature = "std")]
+#[allow(dead_code)]
+pub mod vm;
+
+// Solves a part from the input the runner read, the runner prints the notes and
+// the answer or the error. The kind of input is for the days asking something
+// else of the example, the others ignore it.
+#[cfg(feature = "std")]
+pub type DayFn = fn(&str, InputKind) -> Result<Solved, AocError>;
+
+// The two parts of a day, either can be missing
+#[cfg(feature = "std")]
+pub type DaySpec = (Option<DayFn>, Option<DayFn>);
+
+// Solves a part with someone at the terminal, reading their commands and
+// writing the replies to what the runner hands over
+#[cfg(feature = "std")]
+pub type PlayFn = fn(&str, &mut dyn BufRead, &mut dyn Write) -> Result<Solved, AocError>;
+
+#[cfg(feature = "std")]
+pub type PlaySpec = (Option<PlayFn>, Option<PlayFn>);
+
+// Writes a picture of the solution to the given path, using the constants of
+// the kind of input like the parts do
+#[cfg(feature = "std")]
+pub type RenderFn = fn(&str, InputKind, &Path) -> Result<(), AocError>;
+
+// Plays an animation of the solution in the terminal, recording it to the path
+// if there is one
+#[cfg(feature = "std")]
+pub type VisualizeFn = fn(&str, InputKind, Option<&Path>) -> Result<(), AocError>;
+
+// Saves pictures of the solution, returns the files it wrote
+#[cfg(feature = "std")]
+pub type ExportFn = fn(&str) -> Result<Vec<PathBuf>, AocError>;
+
+// The graph the day is about, in the DOT language
+#[cfg(feature = "std")]
+pub type GraphFn = fn(&str) -> Result<String, AocError>;
+
+// Solves the day while telling the recorder what happens at every step
+#[cfg(feature = "std")]
+pub type TraceFn = fn(&str, &mut trace::Recorder) -> Result<(), AocError>;
+
+// Solves the day keeping the intermediate results that lead to the answer
+#[cfg(feature = "std")]
+pub type ExplainFn = fn(&str, InputKind) -> Result<explain::Explanation, AocError>;
+
+#[cfg(feature = "std")]
+pub fn fmt_time(ms: f64) -> String {
+    if ms <= 1.0 {
+        let micro_sec = ms * 1000.0;
+        return format!("{}µs", micro_sec.round());
+    }
+
+    if ms < 1000.0 {
+        let whole_ms = ms.floor();
+        let rem_ms = ms - whole_ms;
+        return format!("{}ms ", whole_ms) + &fmt_time(rem_ms);
+    }
+
+    let sec: f64 = ms / 1000.0;
+    if sec < 60.0 {
+        let whole_sec = sec.floor();
+        let rem_ms = ms - whole_sec * 1000.0;
+
+        return format!("{}s ", whole_sec) + &fmt_time(rem_ms);
+    }
+
+    let min: f64 = sec / 60.0;
+    format!("{}m ", min.floor()) + &fmt_time((sec % 60.0) * 1000.0)
+}
+
+#[cfg(feature = "std")]
+pub fn fmt_dur(dur: Duration) -> String {
+    fmt_time(dur.as_secs_f64() * 1000.0)
+}
@@ -0,0 +1,206 @@
+use std::fmt;
+
+// What a part answers with. Numbers are kept as numbers, so checking them
+// against the accepted answers doesn't depend on how they were printed.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Int(i64),
+    UInt(u64),
+    Text(String),
+    // Letters drawn on a screen, several lines of them
+    Grid(String),
+}
+
+// What two answers are compared by: numbers by their value whatever their type,
+// even written out as text, and drawings line by line without the blanks at the
+// end of the lines or around the drawing
+#[derive(PartialEq, Eq)]
+enum Key {
+    Number(i128),
+    Text(String),
+    Lines(Vec<String>),
+}
+
+fn lines(drawing: &str) -> Vec<String> {
+    let mut lines: Vec<String> = drawing
+        .lines()
+        .map(|line| line.trim_end().to_string())
+        .skip_while(|line| line.is_empty())
+        .collect();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+fn text_key(text: &str) -> Key {
+    if text.contains('\n') {
+        return Key::Lines(lines(text));
+    }
+    match text.trim().parse() {
+        Ok(number) => Key::Number(number),
+        Err(_) => Key::Text(text.trim().to_string()),
+    }
+}
+
+impl Answer {
+    fn key(&self) -> Key {
+        match self {
+            Answer::Int(number) => Key::Number(i128::from(*number)),
+            Answer::UInt(number) => Key::Number(i128::from(*number)),
+            Answer::Text(text) => text_key(text),
+            Answer::Grid(drawing) => Key::Lines(lines(drawing)),
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Answer {}
+
+impl PartialEq<&str> for Answer {
+    fn eq(&self, other: &&str) -> bool {
+        self.key() == text_key(other)
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Int(number) => write!(f, "{}", number),
+            Answer::UInt(number) => write!(f, "{}", number),
+            Answer::Text(text) => write!(f, "{}", text),
+            // On its own lines, so the first row lines up with the others
+            Answer::Grid(drawing) => write!(f, "\n{}", drawing.trim_end()),
+        }
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(answer: i64) -> Self {
+        Answer::Int(answer)
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(answer: i32) -> Self {
+        Answer::Int(i64::from(answer))
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(answer: isize) -> Self {
+        Answer::Int(answer as i64)
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(answer: u64) -> Self {
+        Answer::UInt(answer)
+    }
+}
+
+impl From<u16> for Answer {
+    fn from(answer: u16) -> Self {
+        Answer::UInt(u64::from(answer))
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(answer: u32) -> Self {
+        Answer::UInt(u64::from(answer))
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(answer: usize) -> Self {
+        Answer::UInt(answer as u64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(answer: String) -> Self {
+        Answer::Text(answer)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(answer: &str) -> Self {
+        Answer::Text(answer.to_string())
+    }
+}
+
+// What a part hands back to the runner: the answer, and the notes it took on the
+// way there, like the counts it went through, shown above the answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solved {
+    pub answer: Answer,
+    pub notes: Vec<String>,
+}
+
+impl Solved {
+    pub fn note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+}
+
+impl From<Answer> for Solved {
+    fn from(answer: Answer) -> Self {
+        Solved {
+            answer,
+            notes: Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for Solved {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for note in &self.notes {
+            writeln!(f, "{}", note)?;
+        }
+        write!(f, "{}", self.answer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_by_value() {
+        assert_eq!(Answer::from(7u64), Answer::from(7i64));
+        assert_eq!(Answer::from(7usize), "7");
+        assert_eq!(Answer::from(-3), " -3 ");
+        assert_ne!(Answer::from(7u32), "7.0");
+        assert_ne!(Answer::Int(-1), Answer::UInt(u64::MAX));
+        assert_eq!(Answer::UInt(u64::MAX).to_string(), "18446744073709551615");
+    }
+
+    #[test]
+    fn texts_and_drawings() {
+        assert_eq!(Answer::from("abc"), "abc ");
+        assert_ne!(Answer::from("abc"), "ABC");
+
+        let drawing = Answer::Grid(String::from("\n#..# \n#### \n\n"));
+        assert_eq!(drawing, Answer::Grid(String::from("#..#\n####")));
+        assert_eq!(drawing, Answer::from("#..#\n####\n"));
+        assert_ne!(drawing, Answer::Grid(String::from("#..#\n#..#")));
+        assert_eq!(drawing.to_string(), "\n\n#..# \n####");
+    }
+
+    #[test]
+    fn notes_above_the_answer() {
+        let solved = Solved::from(Answer::from(42u64))
+            .note("Sorted 12 lines")
+            .note(String::from("7 of them twice"));
+
+        assert_eq!(solved.answer, "42");
+        assert_eq!(solved.to_string(), "Sorted 12 lines\n7 of them twice\n42");
+        assert_eq!(Solved::from(Answer::from("abc")).to_string(), "abc");
+    }
+}
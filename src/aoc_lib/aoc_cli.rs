@@ -0,0 +1,246 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::aoc_lib::AocError;
+
+// The files aoc-cli (https://github.com/scarvalhojr/aoc-cli) downloads, where
+// the Rust template built around it keeps them: one directory per year, with
+// the input of each day and its puzzle text in Markdown. Solved puzzles end
+// with the accepted answers, which become our dayNN.expected. In the patterns
+// {year} is the year and {day} the day with two digits.
+pub const INPUTS: &str = "data/inputs/{day}.txt";
+pub const PUZZLES: &str = "data/puzzles/{day}.md";
+
+pub struct Layout<'a> {
+    pub root: &'a Path,
+    pub inputs: &'a str,
+    pub puzzles: &'a str,
+}
+
+fn fill(pattern: &str, year: u16, day: u8) -> String {
+    pattern
+        .replace("{year}", &year.to_string())
+        .replace("{day}", &format!("{:02}", day))
+}
+
+impl Layout<'_> {
+    pub fn input(&self, year: u16, day: u8) -> PathBuf {
+        self.root.join(fill(self.inputs, year, day))
+    }
+
+    pub fn puzzle(&self, year: u16, day: u8) -> PathBuf {
+        self.root.join(fill(self.puzzles, year, day))
+    }
+}
+
+// What an import or an export did, by day
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Copied {
+    pub inputs: Vec<u8>,
+    pub answers: Vec<u8>,
+    // Files that were there already and stayed as they were
+    pub kept: Vec<PathBuf>,
+    // Days without an input to copy
+    pub missing: Vec<u8>,
+}
+
+// The answers a solved puzzle text ends with, in the order of the parts. The
+// page says "Your puzzle answer was <code>…</code>." and aoc-cli turns the code
+// into backticks.
+pub fn answers(puzzle: &str) -> Vec<String> {
+    puzzle
+        .split("Your puzzle answer was")
+        .skip(1)
+        .filter_map(|after| {
+            let answer = match after.trim_start().strip_prefix('`') {
+                Some(quoted) => quoted.split('`').next()?,
+                None => after.split(['.', '\n']).next()?,
+            };
+            Some(answer.trim().to_string()).filter(|answer| !answer.is_empty())
+        })
+        .collect()
+}
+
+// Writes `contents` to `path` unless something is there and `force` is off,
+// says whether it did
+fn write(path: &Path, contents: &str, force: bool, copied: &mut Copied) -> Result<bool, AocError> {
+    if path.exists() && !force {
+        copied.kept.push(path.to_path_buf());
+        return Ok(false);
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)?;
+    Ok(true)
+}
+
+// The year's inputs and accepted answers from `layout` into `inputs`, as
+// YEAR/dayNN.txt and YEAR/dayNN.expected
+pub fn import(layout: &Layout, year: u16, inputs: &Path, force: bool) -> Result<Copied, AocError> {
+    let mut copied = Copied::default();
+    let ours = inputs.join(year.to_string());
+
+    for day in 1..=25 {
+        let Ok(input) = fs::read_to_string(layout.input(year, day)) else {
+            copied.missing.push(day);
+            continue;
+        };
+        let path = ours.join(format!("day{:02}.txt", day));
+        if write(&path, &input, force, &mut copied)? {
+            copied.inputs.push(day);
+        }
+
+        // A puzzle nobody solved yet has no answers to take
+        let puzzle = fs::read_to_string(layout.puzzle(year, day)).unwrap_or_default();
+        let accepted = answers(&puzzle);
+        if !accepted.is_empty() {
+            let expected = accepted.join("\n") + "\n";
+            let path = ours.join(format!("day{:02}.expected", day));
+            if write(&path, &expected, force, &mut copied)? {
+                copied.answers.push(day);
+            }
+        }
+    }
+
+    Ok(copied)
+}
+
+// The year's inputs from `inputs` to where `layout` keeps them. The answers stay
+// here, aoc-cli only has them inside the puzzle texts it downloads.
+pub fn export(layout: &Layout, year: u16, inputs: &Path, force: bool) -> Result<Copied, AocError> {
+    let mut copied = Copied::default();
+    let ours = inputs.join(year.to_string());
+
+    for day in 1..=25 {
+        let Ok(input) = fs::read_to_string(ours.join(format!("day{:02}.txt", day))) else {
+            copied.missing.push(day);
+            continue;
+        };
+        if write(&layout.input(year, day), &input, force, &mut copied)? {
+            copied.inputs.push(day);
+        }
+    }
+
+    Ok(copied)
+}
+
+// Where aoc-cli finds the session cookie it downloads with, in the order it
+// looks: the ADVENT_OF_CODE_SESSION variable, then a file in the home or the
+// configuration directory
+pub fn session() -> Option<String> {
+    if env::var_os("ADVENT_OF_CODE_SESSION").is_some() {
+        return Some(String::from("$ADVENT_OF_CODE_SESSION"));
+    }
+
+    let home = env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from);
+    let config = if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home.as_ref()
+            .map(|home| home.join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home.as_ref().map(|home| home.join(".config")))
+    };
+
+    let candidates = [
+        home.map(|home| home.join(".adventofcode.session")),
+        config.map(|config| config.join("adventofcode.session")),
+    ];
+    candidates
+        .into_iter()
+        .flatten()
+        .find(|file| file.is_file())
+        .map(|file| file.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOLVED: &str = "\
+## --- Day 1: Calorie Counting ---
+
+Find the Elf carrying the most Calories.
+
+Your puzzle answer was `70369`.
+
+## --- Part Two ---
+
+Your puzzle answer was `203002`.
+
+Both parts of this puzzle are complete! They provide two gold stars: \\*\\*
+";
+
+    #[test]
+    fn puzzle_answers() {
+        assert_eq!(answers(SOLVED), ["70369", "203002"]);
+        assert_eq!(answers("Your puzzle answer was EHZFZHCZ."), ["EHZFZHCZ"]);
+        assert!(answers("## --- Day 2: Rock Paper Scissors ---").is_empty());
+    }
+
+    #[test]
+    fn patterns() {
+        let layout = Layout {
+            root: Path::new("cache"),
+            inputs: "{year}/{day}/input",
+            puzzles: PUZZLES,
+        };
+        assert_eq!(layout.input(2022, 7), Path::new("cache/2022/07/input"));
+        assert_eq!(
+            layout.puzzle(2022, 7),
+            Path::new("cache/data/puzzles/07.md")
+        );
+    }
+
+    #[test]
+    fn both_ways() {
+        let scratch = env::temp_dir().join(format!("aoc-cli-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&scratch);
+        let theirs = scratch.join("theirs");
+        let ours = scratch.join("inputs");
+        let layout = Layout {
+            root: &theirs,
+            inputs: INPUTS,
+            puzzles: PUZZLES,
+        };
+        fs::create_dir_all(theirs.join("data/inputs")).unwrap();
+        fs::create_dir_all(theirs.join("data/puzzles")).unwrap();
+        fs::write(theirs.join("data/inputs/01.txt"), "1000\n2000\n").unwrap();
+        fs::write(theirs.join("data/puzzles/01.md"), SOLVED).unwrap();
+        fs::write(theirs.join("data/inputs/02.txt"), "A Y\n").unwrap();
+
+        let copied = import(&layout, 2022, &ours, false).unwrap();
+        assert_eq!((copied.inputs, copied.answers), (vec![1, 2], vec![1]));
+        assert_eq!(copied.missing, (3..=25).collect::<Vec<u8>>());
+        let expected = fs::read_to_string(ours.join("2022/day01.expected")).unwrap();
+        assert_eq!(expected, "70369\n203002\n");
+        assert_eq!(
+            fs::read_to_string(ours.join("2022/day02.txt")).unwrap(),
+            "A Y\n"
+        );
+
+        // What's already there stays, unless forced
+        fs::write(ours.join("2022/day02.txt"), "B Z\n").unwrap();
+        assert_eq!(import(&layout, 2022, &ours, false).unwrap().kept.len(), 3);
+        let elsewhere = scratch.join("elsewhere");
+        let exported = Layout {
+            root: &elsewhere,
+            ..layout
+        };
+        let copied = export(&exported, 2022, &ours, false).unwrap();
+        assert_eq!(copied.inputs, [1, 2]);
+        assert_eq!(
+            fs::read_to_string(elsewhere.join("data/inputs/02.txt")).unwrap(),
+            "B Z\n"
+        );
+        assert!(!elsewhere.join("data/puzzles").exists());
+
+        fs::remove_dir_all(&scratch).unwrap();
+    }
+}
@@ -0,0 +1,227 @@
+#![warn(missing_docs)]
+
+use alloc::vec::Vec;
+use core::ops::{Index, IndexMut};
+
+/// Where a value went in its [`Arena`], it means nothing to any other arena
+///
+/// ```
+/// use advent_of_code::aoc_lib::arena::{Arena, Id};
+///
+/// let mut arena = Arena::new();
+/// let first: Id = arena.alloc("first");
+/// assert_ne!(first, arena.alloc("second"));
+/// assert_eq!(arena[first], "first");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Id(u32);
+
+impl Id {
+    /// How many values went in the arena before this one, to keep more about
+    /// the values on the side in a plain vector
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::arena::Arena;
+    ///
+    /// let mut sizes = Arena::new();
+    /// let ids = [sizes.alloc(3), sizes.alloc(5)];
+    /// let doubled: Vec<u32> = sizes.iter().map(|(_, size)| size * 2).collect();
+    /// assert_eq!(doubled[ids[1].index()], 10);
+    /// ```
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// The nodes of a tree or a graph, stored next to each other and pointing at
+/// each other by [`Id`] instead of each one getting an allocation of its own.
+/// They are all freed at once, with the arena.
+///
+/// ```
+/// use advent_of_code::aoc_lib::arena::{Arena, Id};
+///
+/// enum Expression {
+///     Number(i64),
+///     Sum(Id, Id),
+/// }
+///
+/// fn evaluate(nodes: &Arena<Expression>, node: Id) -> i64 {
+///     match nodes[node] {
+///         Expression::Number(value) => value,
+///         Expression::Sum(left, right) => evaluate(nodes, left) + evaluate(nodes, right),
+///     }
+/// }
+///
+/// let mut nodes = Arena::new();
+/// let two = nodes.alloc(Expression::Number(2));
+/// let three = nodes.alloc(Expression::Number(3));
+/// let sum = nodes.alloc(Expression::Sum(two, three));
+/// assert_eq!(evaluate(&nodes, sum), 5);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Arena<T> {
+    values: Vec<T>,
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Arena { values: Vec::new() }
+    }
+}
+
+impl<T> Arena<T> {
+    /// An empty arena
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::arena::Arena;
+    ///
+    /// assert!(Arena::<u8>::new().is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Arena::default()
+    }
+
+    /// An empty arena with room for `capacity` values before it has to grow,
+    /// for when the size of the input tells how many nodes there will be
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::arena::Arena;
+    ///
+    /// let mut lines = Arena::with_capacity(2);
+    /// lines.alloc("$ cd /");
+    /// lines.alloc("$ ls");
+    /// assert_eq!(lines.len(), 2);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Arena {
+            values: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Moves `value` in the arena, returns where it went
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let id = arena.alloc(7);
+    /// arena[id] += 1;
+    /// assert_eq!(arena[id], 8);
+    /// ```
+    pub fn alloc(&mut self, value: T) -> Id {
+        let id = Id(u32::try_from(self.values.len()).expect("Arena full"));
+        self.values.push(value);
+        id
+    }
+
+    /// The value at `id`, None if it's not from this arena
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::arena::Arena;
+    ///
+    /// let mut big = Arena::new();
+    /// big.alloc('a');
+    /// let b = big.alloc('b');
+    /// assert_eq!(big.get(b), Some(&'b'));
+    ///
+    /// let mut small = Arena::new();
+    /// small.alloc('c');
+    /// assert_eq!(small.get(b), None);
+    /// ```
+    pub fn get(&self, id: Id) -> Option<&T> {
+        self.values.get(id.0 as usize)
+    }
+
+    /// The value at `id` to change it, None if it's not from this arena
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let id = arena.alloc(String::from("fo"));
+    /// arena.get_mut(id).unwrap().push('o');
+    /// assert_eq!(arena[id], "foo");
+    /// ```
+    pub fn get_mut(&mut self, id: Id) -> Option<&mut T> {
+        self.values.get_mut(id.0 as usize)
+    }
+
+    /// How many values went in the arena
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.alloc(());
+    /// assert_eq!(arena.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// True before the first value goes in
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// assert!(arena.is_empty());
+    /// arena.alloc(0);
+    /// assert!(!arena.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Every value with its id, in the order they went in or the other way
+    /// round
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let ids = [arena.alloc('x'), arena.alloc('y')];
+    /// let listed: Vec<_> = arena.iter().collect();
+    /// assert_eq!(listed, [(ids[0], &'x'), (ids[1], &'y')]);
+    /// assert_eq!(arena.iter().next_back(), Some((ids[1], &'y')));
+    /// ```
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (Id, &T)> {
+        self.values
+            .iter()
+            .enumerate()
+            .map(|(id, value)| (Id(id as u32), value))
+    }
+}
+
+impl<T> Index<Id> for Arena<T> {
+    type Output = T;
+
+    fn index(&self, id: Id) -> &T {
+        self.get(id)
+            .unwrap_or_else(|| panic!("{:?} is not in the arena", id))
+    }
+}
+
+impl<T> IndexMut<Id> for Arena<T> {
+    fn index_mut(&mut self, id: Id) -> &mut T {
+        self.get_mut(id)
+            .unwrap_or_else(|| panic!("{:?} is not in the arena", id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "Id(1) is not in the arena")]
+    fn foreign_id() {
+        let mut big = Arena::new();
+        big.alloc(1);
+        let second = big.alloc(2);
+
+        let mut small = Arena::new();
+        small.alloc(3);
+        small[second] += 1;
+    }
+}
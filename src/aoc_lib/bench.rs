@@ -0,0 +1,39 @@
+use std::fmt::Debug;
+use std::time::{Duration, Instant};
+
+use crate::aoc_lib::testgen;
+
+// The input the speedup tests time the days on, made up by the generator of the
+// day so it's the same everywhere. There is no skipping a benchmark for lack of
+// an input: a day without a generator fails the test.
+pub fn input(year: u16, day: u8, size: usize) -> String {
+    testgen::generate(year, day, size, 1)
+        .unwrap_or_else(|error| panic!("Nothing to time {} day {} on: {}", year, day, error))
+}
+
+pub fn timed<T>(run: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = run();
+    (result, start.elapsed())
+}
+
+// Runs the slower solver and then the one replacing it, which have to agree on
+// the answer and take less time
+pub fn faster<T: PartialEq + Debug>(
+    slow: (&str, impl FnOnce() -> T),
+    fast: (&str, impl FnOnce() -> T),
+) {
+    let (slow_answer, slow_time) = timed(slow.1);
+    let (fast_answer, fast_time) = timed(fast.1);
+
+    println!("{}: {:?}, {}: {:?}", slow.0, slow_time, fast.0, fast_time);
+    assert_eq!(slow_answer, fast_answer);
+    assert!(
+        fast_time < slow_time,
+        "{} took {:?}, no faster than {} in {:?}",
+        fast.0,
+        fast_time,
+        slow.0,
+        slow_time
+    );
+}
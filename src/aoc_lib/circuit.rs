@@ -0,0 +1,214 @@
+use hashbrown::{HashMap, HashSet};
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::{alpha1, u16, u32};
+use nom::combinator::{all_consuming, map};
+use nom::sequence::{preceded, separated_pair, tuple};
+use nom::IResult;
+
+// A signal is either fixed or whatever arrives on another wire
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Signal {
+    Value(u16),
+    Wire(String),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Gate {
+    Direct(Signal),
+    And(Signal, Signal),
+    Or(Signal, Signal),
+    LeftShift(Signal, u32),
+    RightShift(Signal, u32),
+    Not(Signal),
+}
+
+impl Gate {
+    fn inputs(&self) -> Vec<&Signal> {
+        match self {
+            Gate::Direct(signal)
+            | Gate::Not(signal)
+            | Gate::LeftShift(signal, _)
+            | Gate::RightShift(signal, _) => vec![signal],
+            Gate::And(left, right) | Gate::Or(left, right) => vec![left, right],
+        }
+    }
+}
+
+fn signal(input: &str) -> IResult<&str, Signal> {
+    alt((
+        map(u16, Signal::Value),
+        map(alpha1, |wire: &str| Signal::Wire(wire.to_string())),
+    ))(input)
+}
+
+fn gate(input: &str) -> IResult<&str, Gate> {
+    alt((
+        map(preceded(tag("NOT "), signal), Gate::Not),
+        map(separated_pair(signal, tag(" AND "), signal), |(l, r)| {
+            Gate::And(l, r)
+        }),
+        map(separated_pair(signal, tag(" OR "), signal), |(l, r)| {
+            Gate::Or(l, r)
+        }),
+        map(separated_pair(signal, tag(" LSHIFT "), u32), |(s, by)| {
+            Gate::LeftShift(s, by)
+        }),
+        map(separated_pair(signal, tag(" RSHIFT "), u32), |(s, by)| {
+            Gate::RightShift(s, by)
+        }),
+        map(signal, Gate::Direct),
+    ))(input)
+}
+
+// "x AND y -> d"
+fn connection(input: &str) -> IResult<&str, (Gate, &str)> {
+    all_consuming(map(tuple((gate, tag(" -> "), alpha1)), |(g, _, wire)| {
+        (g, wire)
+    }))(input)
+}
+
+// Wires driven by one gate each. Signals only flow once every input of a gate
+// is known, in whatever order the gates were listed.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Circuit {
+    gates: HashMap<String, Gate>,
+}
+
+impl Circuit {
+    pub fn parse(input: &str) -> Result<Circuit, String> {
+        let mut circuit = Circuit::default();
+        for line in input.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let (_, (gate, wire)) =
+                connection(line).map_err(|_| format!("\"{}\" is not a connection", line))?;
+            if circuit.gates.insert(wire.to_string(), gate).is_some() {
+                return Err(format!("Wire {} is driven twice", wire));
+            }
+        }
+
+        Ok(circuit)
+    }
+
+    // Replaces whatever drives `wire` with a fixed value
+    pub fn set(&mut self, wire: &str, value: u16) {
+        self.gates
+            .insert(wire.to_string(), Gate::Direct(Signal::Value(value)));
+    }
+
+    // Works through the wires with an explicit stack, so long chains don't run
+    // out of call stack, remembering every signal found on the way. A wire is
+    // "waiting" while the signals it needs are being worked out.
+    pub fn evaluate(&self, wire: &str) -> Result<u16, String> {
+        let mut known: HashMap<&str, u16> = HashMap::new();
+        let mut waiting: HashSet<&str> = HashSet::new();
+        let mut stack: Vec<(&str, bool)> = vec![(wire, false)];
+
+        while let Some((current, inputs_ready)) = stack.pop() {
+            if known.contains_key(current) {
+                continue;
+            }
+            let gate = self
+                .gates
+                .get(current)
+                .ok_or_else(|| format!("Nothing drives wire {}", current))?;
+
+            if !inputs_ready {
+                if !waiting.insert(current) {
+                    return Err(format!("Wire {} depends on itself", current));
+                }
+                stack.push((current, true));
+                for input in gate.inputs() {
+                    if let Signal::Wire(name) = input {
+                        if waiting.contains(name.as_str()) {
+                            return Err(format!("Wire {} depends on itself", name));
+                        }
+                        if !known.contains_key(name.as_str()) {
+                            stack.push((name, false));
+                        }
+                    }
+                }
+                continue;
+            }
+
+            let value = |signal: &Signal| match signal {
+                Signal::Value(value) => *value,
+                Signal::Wire(name) => known[name.as_str()],
+            };
+            let output = match gate {
+                Gate::Direct(signal) => value(signal),
+                Gate::And(left, right) => value(left) & value(right),
+                Gate::Or(left, right) => value(left) | value(right),
+                Gate::LeftShift(signal, by) => value(signal).checked_shl(*by).unwrap_or(0),
+                Gate::RightShift(signal, by) => value(signal).checked_shr(*by).unwrap_or(0),
+                Gate::Not(signal) => !value(signal),
+            };
+            known.insert(current, output);
+            waiting.remove(current);
+        }
+
+        Ok(known[wire])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT_STRING: &str = "123 -> x
+    456 -> y
+    x AND y -> d
+    x OR y -> e
+    x LSHIFT 2 -> f
+    y RSHIFT 2 -> g
+    NOT x -> h
+    NOT y -> i";
+
+    #[test]
+    fn simple_circuit() {
+        let circuit = Circuit::parse(INPUT_STRING).unwrap();
+        let signals: Vec<u16> = ["d", "e", "f", "g", "h", "i", "x", "y"]
+            .into_iter()
+            .map(|wire| circuit.evaluate(wire).unwrap())
+            .collect();
+
+        assert_eq!(signals, [72, 507, 492, 114, 65412, 65079, 123, 456]);
+    }
+
+    #[test]
+    fn wiring_order() {
+        // A long chain of wires, listed from the far end
+        let names: Vec<String> = (0..2000u32)
+            .map(|id| {
+                [id / 676, id / 26 % 26, id % 26]
+                    .iter()
+                    .map(|letter| char::from(b'a' + *letter as u8))
+                    .collect()
+            })
+            .collect();
+        let mut lines: Vec<String> = names
+            .windows(2)
+            .rev()
+            .map(|pair| format!("{} -> {}", pair[0], pair[1]))
+            .collect();
+        lines.push(format!("7 -> {}", names[0]));
+
+        let mut circuit = Circuit::parse(&lines.join("\n")).unwrap();
+        assert_eq!(circuit.evaluate(&names[1999]), Ok(7));
+        circuit.set(&names[1000], 3);
+        assert_eq!(circuit.evaluate(&names[1999]), Ok(3));
+    }
+
+    #[test]
+    fn broken_circuits() {
+        assert!(Circuit::parse("x XOR y -> z").is_err());
+        assert!(Circuit::parse("1 -> a\n2 -> a").is_err());
+
+        let looped = Circuit::parse("b -> a\nNOT a -> b").unwrap();
+        assert!(looped.evaluate("a").is_err());
+        assert!(Circuit::parse("b -> a").unwrap().evaluate("a").is_err());
+
+        // Shared inputs are not loops
+        let shared = Circuit::parse("b AND c -> a\nNOT b -> c\n5 -> b").unwrap();
+        assert_eq!(shared.evaluate("a"), Ok(5 & !5));
+    }
+}
@@ -0,0 +1,45 @@
+// Which input a part is solving, the caller says. Some puzzles ask something
+// else of the example than of the real inputs, like a smaller row or area to
+// look at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputKind {
+    #[default]
+    Real,
+    Example,
+}
+
+// A constant of a day with one value for the example and one for the real
+// inputs. The parts pick one with the kind of input they were handed, the
+// functions doing the work take the value itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DayConfig<T> {
+    pub example: T,
+    pub real: T,
+}
+
+impl<T: Copy> DayConfig<T> {
+    pub const fn new(example: T, real: T) -> Self {
+        DayConfig { example, real }
+    }
+
+    pub fn pick(&self, kind: InputKind) -> T {
+        match kind {
+            InputKind::Real => self.real,
+            InputKind::Example => self.example,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROW: DayConfig<i32> = DayConfig::new(10, 2000000);
+
+    #[test]
+    fn example_or_real() {
+        assert_eq!(ROW.pick(InputKind::Example), 10);
+        assert_eq!(ROW.pick(InputKind::Real), 2000000);
+        assert_eq!(ROW.pick(InputKind::default()), ROW.real);
+    }
+}
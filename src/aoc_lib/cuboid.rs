@@ -0,0 +1,366 @@
+use std::ops::{Range, RangeInclusive};
+
+use nalgebra::Point3;
+
+// Axis aligned box over half-open ranges: bottom_left is inside, top_right is
+// not, so two cuboids sharing a face don't overlap
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Cuboid {
+    top_right: Point3<i64>,
+    bottom_left: Point3<i64>,
+}
+
+impl Cuboid {
+    // Corners of the half-open ranges, top_right is one past the last cube
+    pub fn new(bottom_left: Point3<i64>, top_right: Point3<i64>) -> Self {
+        Self {
+            top_right,
+            bottom_left,
+        }
+    }
+
+    pub fn from_half_open([x, y, z]: [Range<i64>; 3]) -> Self {
+        Self::new(
+            Point3::new(x.start, y.start, z.start),
+            Point3::new(x.end, y.end, z.end),
+        )
+    }
+
+    // What the puzzles usually give: both ends included, so `x=10..12` is three
+    // cubes wide. A range with start > end gives an empty cuboid, an end of
+    // i64::MAX can't be represented.
+    pub fn from_inclusive([x, y, z]: [RangeInclusive<i64>; 3]) -> Self {
+        Self::from_half_open([
+            *x.start()..*x.end() + 1,
+            *y.start()..*y.end() + 1,
+            *z.start()..*z.end() + 1,
+        ])
+    }
+
+    pub fn ranges(&self) -> [Range<i64>; 3] {
+        [0, 1, 2].map(|axis| self.bottom_left[axis]..self.top_right[axis])
+    }
+
+    pub fn inclusive_ranges(&self) -> [RangeInclusive<i64>; 3] {
+        [0, 1, 2].map(|axis| self.bottom_left[axis]..=self.top_right[axis] - 1)
+    }
+
+    pub fn bottom_left(&self) -> &Point3<i64> {
+        &self.bottom_left
+    }
+
+    pub fn top_right(&self) -> &Point3<i64> {
+        &self.top_right
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..3).any(|axis| self.bottom_left[axis] >= self.top_right[axis])
+    }
+
+    pub fn contains(&self, point: &Point3<i64>) -> bool {
+        (0..3).all(|axis| (self.bottom_left[axis]..self.top_right[axis]).contains(&point[axis]))
+    }
+
+    // Every cube of self is also a cube of volume, empty cuboids are inside
+    // anything
+    pub fn inside_volume(&self, volume: &Cuboid) -> bool {
+        self.is_empty()
+            || (0..3).all(|axis| {
+                self.bottom_left[axis] >= volume.bottom_left[axis]
+                    && self.top_right[axis] <= volume.top_right[axis]
+            })
+    }
+
+    // Number of unit cubes, zero for empty cuboids. Each side fits in a u64,
+    // but three of them multiplied can still overflow a u128.
+    pub fn checked_volume(&self) -> Option<u128> {
+        if self.is_empty() {
+            return Some(0);
+        }
+
+        (0..3).try_fold(1u128, |volume, axis| {
+            let length = self.top_right[axis].abs_diff(self.bottom_left[axis]);
+            volume.checked_mul(u128::from(length))
+        })
+    }
+
+    pub fn volume(&self) -> u128 {
+        self.checked_volume()
+            .expect("Cuboid volume doesn't fit in a u128")
+    }
+
+    pub fn intersect(&self, other: &Self) -> Option<Self> {
+        let bottom_left = self.bottom_left.sup(&other.bottom_left);
+        let top_right = self.top_right.inf(&other.top_right);
+        let intersection = Self::new(bottom_left, top_right);
+
+        (!intersection.is_empty()).then_some(intersection)
+    }
+
+    // Cuts the cuboid with the plane `axis = at`, the plane itself ends up in
+    // the upper half
+    pub fn split(&self, axis: usize, at: i64) -> (Option<Self>, Option<Self>) {
+        let mut lower = self.clone();
+        let mut upper = self.clone();
+        lower.top_right[axis] = at.min(self.top_right[axis]);
+        upper.bottom_left[axis] = at.max(self.bottom_left[axis]);
+
+        (
+            (!lower.is_empty()).then_some(lower),
+            (!upper.is_empty()).then_some(upper),
+        )
+    }
+
+    // Whatever is left of self once other is carved out, as at most 6 disjoint
+    // slabs: two along x, then two along y and two along z inside the overlap
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        if self.intersect(other).is_none() {
+            return vec![self.clone()];
+        }
+
+        let mut pieces: Vec<Self> = Vec::new();
+        let mut remaining = self.clone();
+        for axis in 0..3 {
+            let (below, rest) = remaining.split(axis, other.bottom_left[axis]);
+            pieces.extend(below);
+            let (middle, above) = rest.unwrap().split(axis, other.top_right[axis]);
+            pieces.extend(above);
+            remaining = middle.unwrap();
+        }
+
+        pieces
+    }
+
+    // Keeps a set of disjoint pieces, every new cuboid is carved out of them
+    // before being added, so nothing is counted twice
+    pub fn union_volume(cuboids: &[Self]) -> u128 {
+        let mut pieces: Vec<Self> = Vec::new();
+
+        for cuboid in cuboids {
+            pieces = pieces
+                .iter()
+                .flat_map(|piece| piece.subtract(cuboid))
+                .collect();
+            pieces.push(cuboid.clone());
+        }
+
+        pieces.iter().map(|piece| piece.volume()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aoc_lib::random::{for_all, Xorshift};
+
+    // Small coordinates, so touching faces and zero thickness sides (empty
+    // cuboids) come up often, and the cubes can be counted one by one
+    const SPACE: i64 = 6;
+
+    fn random_cuboid(rng: &mut Xorshift) -> Cuboid {
+        let mut side = || {
+            let start = rng.range(-SPACE..=SPACE);
+            (start, rng.range(start..=(start + 4).min(SPACE + 1)))
+        };
+        let [x, y, z] = [side(), side(), side()];
+        Cuboid::new(Point3::new(x.0, y.0, z.0), Point3::new(x.1, y.1, z.1))
+    }
+
+    // Every unit cube of the space, by its bottom left corner
+    fn space() -> impl Iterator<Item = Point3<i64>> {
+        let side = -SPACE..=SPACE + 1;
+        itertools::iproduct!(side.clone(), side.clone(), side).map(|(x, y, z)| Point3::new(x, y, z))
+    }
+
+    fn count(inside: impl Fn(&Point3<i64>) -> bool) -> u128 {
+        space().filter(|point| inside(point)).count() as u128
+    }
+
+    fn cube(min: i64, max: i64) -> Cuboid {
+        Cuboid::new(Point3::new(min, min, min), Point3::new(max, max, max))
+    }
+
+    #[test]
+    fn intersection() {
+        let first = cube(0, 3);
+        let second = cube(2, 5);
+
+        assert_eq!(first.intersect(&second), Some(cube(2, 3)));
+        assert_eq!(first.intersect(&cube(3, 5)), None);
+    }
+
+    #[test]
+    fn split() {
+        let (lower, upper) = cube(0, 4).split(1, 1);
+
+        assert_eq!(lower.unwrap().volume(), 16);
+        assert_eq!(upper.unwrap().volume(), 48);
+
+        let (lower, upper) = cube(0, 4).split(2, 7);
+        assert_eq!(lower, Some(cube(0, 4)));
+        assert_eq!(upper, None);
+    }
+
+    #[test]
+    fn subtract_center() {
+        let pieces = cube(0, 3).subtract(&cube(1, 2));
+
+        assert_eq!(pieces.len(), 6);
+        assert_eq!(pieces.iter().map(|p| p.volume()).sum::<u128>(), 26);
+        for (id, piece) in pieces.iter().enumerate() {
+            assert!(piece.intersect(&cube(1, 2)).is_none());
+            for other in &pieces[id + 1..] {
+                assert!(piece.intersect(other).is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn subtract_edges() {
+        assert_eq!(cube(0, 3).subtract(&cube(5, 6)), vec![cube(0, 3)]);
+        assert!(cube(1, 2).subtract(&cube(0, 3)).is_empty());
+        assert!(cube(2, 2).subtract(&cube(5, 6)).is_empty());
+
+        let corner = cube(0, 3).subtract(&cube(2, 5));
+        assert_eq!(corner.len(), 3);
+        assert_eq!(corner.iter().map(|p| p.volume()).sum::<u128>(), 26);
+    }
+
+    #[test]
+    fn union() {
+        assert_eq!(Cuboid::union_volume(&[]), 0);
+        assert_eq!(Cuboid::union_volume(&[cube(0, 3), cube(0, 3)]), 27);
+        assert_eq!(Cuboid::union_volume(&[cube(0, 3), cube(2, 5)]), 53);
+        assert_eq!(
+            Cuboid::union_volume(&[cube(0, 3), cube(2, 5), cube(-10, 10)]),
+            8000
+        );
+    }
+
+    #[test]
+    fn inclusive_constructor() {
+        let cuboid = Cuboid::from_inclusive([10..=12, 10..=12, 10..=12]);
+
+        assert_eq!(cuboid, cube(10, 13));
+        assert_eq!(cuboid.volume(), 27);
+        assert_eq!(cuboid.inclusive_ranges(), [10..=12, 10..=12, 10..=12]);
+        assert_eq!(cuboid.ranges(), [10..13, 10..13, 10..13]);
+        assert_eq!(Cuboid::from_inclusive([0..=0, 0..=0, 0..=0]).volume(), 1);
+    }
+
+    #[test]
+    fn empty_cuboids() {
+        let reversed = Cuboid::from_inclusive([RangeInclusive::new(5, 1), 0..=3, 0..=3]);
+
+        assert!(reversed.is_empty());
+        assert_eq!(reversed.volume(), 0);
+        assert!(reversed.inside_volume(&cube(100, 101)));
+    }
+
+    #[test]
+    fn containment() {
+        let region = Cuboid::from_inclusive([-50..=50, -50..=50, -50..=50]);
+
+        assert!(region.contains(&Point3::new(50, -50, 0)));
+        assert!(!region.contains(&Point3::new(51, 0, 0)));
+        assert!(Cuboid::from_inclusive([-50..=50, 0..=50, 10..=10]).inside_volume(&region));
+        assert!(!Cuboid::from_inclusive([-50..=51, 0..=50, 10..=10]).inside_volume(&region));
+    }
+
+    #[test]
+    fn huge_volumes() {
+        let big = Cuboid::from_inclusive([
+            -3_000_000_000..=2_999_999_999,
+            -3_000_000_000..=2_999_999_999,
+            -3_000_000_000..=2_999_999_999,
+        ]);
+        assert_eq!(big.volume(), 216_000_000_000_000_000_000_000_000_000);
+
+        let everything = Cuboid::new(
+            Point3::new(i64::MIN, i64::MIN, i64::MIN),
+            Point3::new(i64::MAX, i64::MAX, i64::MAX),
+        );
+        assert_eq!(everything.checked_volume(), None);
+    }
+
+    #[test]
+    fn intersection_properties() {
+        for_all(
+            0xc0b01d,
+            500,
+            |rng| (random_cuboid(rng), random_cuboid(rng)),
+            |(first, second)| {
+                let common = first.intersect(second);
+                if common != second.intersect(first) {
+                    return Err(String::from("Not commutative"));
+                }
+                let volume = common.as_ref().map_or(0, |c| c.volume());
+                if volume > first.volume().min(second.volume()) {
+                    return Err(format!("Bigger than its sides: {}", volume));
+                }
+                let cubes = count(|p| first.contains(p) && second.contains(p));
+                match volume == cubes {
+                    true => Ok(()),
+                    false => Err(format!("Volume {} but {} cubes in common", volume, cubes)),
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn subtraction_properties() {
+        for_all(
+            0x5ab7,
+            500,
+            |rng| (random_cuboid(rng), random_cuboid(rng)),
+            |(first, second)| {
+                let pieces = first.subtract(second);
+                let common = first.intersect(second).map_or(0, |c| c.volume());
+                let left: u128 = pieces.iter().map(|p| p.volume()).sum();
+                if left + common != first.volume() {
+                    return Err(format!("{} left and {} in common", left, common));
+                }
+                for (id, piece) in pieces.iter().enumerate() {
+                    if piece.is_empty() || !piece.inside_volume(first) {
+                        return Err(format!("Bad piece {:?}", piece));
+                    }
+                    if piece.intersect(second).is_some() {
+                        return Err(format!("{:?} is still in the way", piece));
+                    }
+                    if pieces[id + 1..]
+                        .iter()
+                        .any(|other| piece.intersect(other).is_some())
+                    {
+                        return Err(format!("{:?} overlaps another piece", piece));
+                    }
+                }
+                Ok(())
+            },
+        );
+    }
+
+    #[test]
+    fn union_properties() {
+        for_all(
+            0x0410,
+            200,
+            |rng| {
+                let amount = rng.below(5) as usize;
+                (0..amount)
+                    .map(|_| random_cuboid(rng))
+                    .collect::<Vec<Cuboid>>()
+            },
+            |cuboids| {
+                let volume = Cuboid::union_volume(cuboids);
+                let cubes = count(|p| cuboids.iter().any(|c| c.contains(p)));
+                match volume == cubes {
+                    true => Ok(()),
+                    false => Err(format!("Union of {} but {} cubes", volume, cubes)),
+                }
+            },
+        );
+    }
+}
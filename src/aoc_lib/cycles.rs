@@ -0,0 +1,92 @@
+use core::hash::Hash;
+
+use hashbrown::HashMap;
+
+// States from `start` on repeat every `length` steps
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    // The step in the first loop that is in the same state as `step`
+    pub fn fold(&self, step: usize) -> usize {
+        if step < self.start {
+            step
+        } else {
+            self.start + (step - self.start) % self.length
+        }
+    }
+
+    // Value after `step` steps for something that grows by the same amount
+    // every loop, like a height or a count. `values` has to go at least up to
+    // the end of the first loop.
+    pub fn extrapolate(&self, values: &[i64], step: usize) -> i64 {
+        if step < values.len() {
+            return values[step];
+        }
+
+        let loops = ((step - self.start) / self.length) as i64;
+        let per_loop = values[self.start + self.length] - values[self.start];
+        values[self.fold(step)] + loops * per_loop
+    }
+}
+
+// Looks for the first state seen twice. The states are fingerprints of whatever
+// is simulated, one per step starting from step 0, and the iterator is only
+// consumed up to the repetition.
+pub fn find_cycle<K: Eq + Hash>(states: impl IntoIterator<Item = K>) -> Option<Cycle> {
+    let mut seen: HashMap<K, usize> = HashMap::new();
+
+    for (step, state) in states.into_iter().enumerate() {
+        if let Some(start) = seen.insert(state, step) {
+            return Some(Cycle {
+                start,
+                length: step - start,
+            });
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lead_in_and_loop() {
+        // 1, 2, then 3, 4, 5 forever
+        let states = [1, 2, 3, 4, 5]
+            .into_iter()
+            .chain([3, 4, 5].into_iter().cycle());
+        let cycle = find_cycle(states).unwrap();
+
+        assert_eq!(
+            cycle,
+            Cycle {
+                start: 2,
+                length: 3
+            }
+        );
+        assert_eq!(cycle.fold(1), 1);
+        assert_eq!(cycle.fold(5), 2);
+        assert_eq!(cycle.fold(1000), 4);
+        assert_eq!(find_cycle([1, 2, 3]), None);
+    }
+
+    #[test]
+    fn extrapolation() {
+        // Grows by 10 every 3 steps after the first two
+        let values = [0, 5, 7, 9, 12, 17, 19];
+        let cycle = Cycle {
+            start: 2,
+            length: 3,
+        };
+
+        assert_eq!(cycle.extrapolate(&values, 4), 12);
+        assert_eq!(cycle.extrapolate(&values, 8), 27);
+        assert_eq!(cycle.extrapolate(&values, 3002), 10007);
+    }
+}
@@ -0,0 +1,175 @@
+use std::fmt::Write;
+
+use petgraph::graph::Graph;
+use petgraph::visit::EdgeRef;
+use petgraph::EdgeType;
+
+// How a day wants its nodes, or its edges, drawn. Everything is optional, a
+// graph of plain () weights comes out as unlabelled dots and lines.
+pub trait DotStyle {
+    // Text in the node or next to the edge
+    fn label(&self) -> Option<String> {
+        None
+    }
+
+    // Any colour Graphviz knows, by name or as "#rrggbb"
+    fn colour(&self) -> Option<String> {
+        None
+    }
+
+    // Where neato should pin the node, for the graphs laid out on a grid
+    fn position(&self) -> Option<(f64, f64)> {
+        None
+    }
+}
+
+impl DotStyle for () {}
+
+// Quotes are added here, newlines become Graphviz's own \n
+fn quoted(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+// The attribute list of a node or an edge, empty when there's nothing to say
+fn attributes(style: &impl DotStyle, colour_key: &str) -> String {
+    let mut attributes = Vec::new();
+    if let Some(label) = style.label() {
+        attributes.push(format!("label={}", quoted(&label)));
+    }
+    if let Some(colour) = style.colour() {
+        attributes.push(format!("{}={}", colour_key, quoted(&colour)));
+    }
+    if let Some((x, y)) = style.position() {
+        // Adding zero turns -0 into 0, which reads better
+        attributes.push(format!("pos=\"{},{}!\"", x + 0.0, y + 0.0));
+    }
+
+    if attributes.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", attributes.join(", "))
+    }
+}
+
+// A graph written in the DOT language, for `dot -Tsvg` and friends
+pub trait ToDot {
+    fn to_dot(&self) -> String;
+}
+
+// Nodes are called n0, n1... after their index. Nodes without a label show
+// nothing at all, instead of their made up name.
+impl<N: DotStyle, E: DotStyle, Ty: EdgeType> ToDot for Graph<N, E, Ty> {
+    fn to_dot(&self) -> String {
+        let (kind, arrow) = if self.is_directed() {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+
+        let mut dot = String::new();
+        writeln!(dot, "{} {{", kind).unwrap();
+        writeln!(
+            dot,
+            "    node [shape=box, style=\"rounded,filled\", fillcolor=white, label=\"\"]"
+        )
+        .unwrap();
+        for node in self.node_indices() {
+            let style = attributes(&self[node], "fillcolor");
+            writeln!(dot, "    n{}{}", node.index(), style).unwrap();
+        }
+        for edge in self.edge_references() {
+            writeln!(
+                dot,
+                "    n{} {} n{}{}",
+                edge.source().index(),
+                arrow,
+                edge.target().index(),
+                attributes(edge.weight(), "color")
+            )
+            .unwrap();
+        }
+        writeln!(dot, "}}").unwrap();
+
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use petgraph::graph::{DiGraph, UnGraph};
+
+    struct Room(&'static str, bool);
+
+    impl DotStyle for Room {
+        fn label(&self) -> Option<String> {
+            Some(self.0.to_string())
+        }
+
+        fn colour(&self) -> Option<String> {
+            self.1.then(|| String::from("gold"))
+        }
+    }
+
+    struct Cost(u32);
+
+    impl DotStyle for Cost {
+        fn label(&self) -> Option<String> {
+            Some(self.0.to_string())
+        }
+    }
+
+    #[test]
+    fn directed() {
+        let mut graph = DiGraph::new();
+        let hall = graph.add_node(Room("Hall \"A\"", false));
+        let vault = graph.add_node(Room("Vault\nB", true));
+        graph.add_edge(hall, vault, Cost(3));
+
+        assert_eq!(
+            DiGraph::to_dot(&graph),
+            "digraph {\n    \
+                node [shape=box, style=\"rounded,filled\", fillcolor=white, label=\"\"]\n    \
+                n0 [label=\"Hall \\\"A\\\"\"]\n    \
+                n1 [label=\"Vault\\nB\", fillcolor=\"gold\"]\n    \
+                n0 -> n1 [label=\"3\"]\n\
+            }\n"
+        );
+    }
+
+    struct Cell(usize, usize);
+
+    impl DotStyle for Cell {
+        fn position(&self) -> Option<(f64, f64)> {
+            Some((self.1 as f64, -(self.0 as f64)))
+        }
+    }
+
+    #[test]
+    fn undirected() {
+        let mut graph = UnGraph::new_undirected();
+        let corner = graph.add_node(Cell(0, 0));
+        let below = graph.add_node(Cell(1, 0));
+        graph.add_edge(corner, below, ());
+
+        let dot = graph.to_dot();
+        let lines: Vec<&str> = dot.lines().collect();
+        assert_eq!(lines[0], "graph {");
+        assert_eq!(lines[2], "    n0 [pos=\"0,0!\"]");
+        assert_eq!(lines[3], "    n1 [pos=\"0,-1!\"]");
+        assert_eq!(lines[4], "    n0 -- n1");
+
+        let empty: DiGraph<(), ()> = DiGraph::new();
+        assert_eq!(empty.to_dot().lines().count(), 3);
+    }
+}
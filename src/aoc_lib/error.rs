@@ -0,0 +1,107 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+// Everything that can stop a day from getting to its answer
+#[derive(Debug)]
+pub enum AocError {
+    // The input doesn't look like what the puzzle describes
+    Parse(String),
+    // A file couldn't be read or written
+    Io(io::Error),
+    // The input reads fine but has no answer, like a maze without a way out
+    Unsolvable(String),
+    // The day has no solution yet
+    Unimplemented,
+    // There is no such year, day or part to run
+    Unknown(String),
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AocError::Parse(error) => write!(f, "Can't read the input: {}", error),
+            AocError::Io(error) => write!(f, "Error while reading or writing: {}", error),
+            AocError::Unsolvable(reason) => write!(f, "No answer: {}", reason),
+            AocError::Unimplemented => write!(f, "Not implemented yet"),
+            AocError::Unknown(what) => write!(f, "Unknown {}", what),
+        }
+    }
+}
+
+impl Error for AocError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AocError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for AocError {
+    fn from(error: io::Error) -> Self {
+        AocError::Io(error)
+    }
+}
+
+// The days that still report their errors as text, nearly always about an
+// input they can't read
+impl From<String> for AocError {
+    fn from(error: String) -> Self {
+        AocError::Parse(error)
+    }
+}
+
+// Failing to write a file, with the file: the error of the OS doesn't say which
+pub fn write_error(path: &Path, error: io::Error) -> AocError {
+    AocError::Io(io::Error::new(
+        error.kind(),
+        format!("{}: {}", path.display(), error),
+    ))
+}
+
+// Every puzzle comes with an input, an empty one is a download that failed or
+// the wrong file. The days are registered behind this check.
+pub fn expect_input(input: &str) -> Result<(), AocError> {
+    match input.trim().is_empty() {
+        true => Err(AocError::Parse(String::from("it's empty"))),
+        false => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages() {
+        assert_eq!(
+            AocError::Parse(String::from("no numbers")).to_string(),
+            "Can't read the input: no numbers"
+        );
+        assert_eq!(
+            AocError::Unsolvable(String::from("the exit is walled in")).to_string(),
+            "No answer: the exit is walled in"
+        );
+        assert_eq!(AocError::Unimplemented.to_string(), "Not implemented yet");
+        assert_eq!(
+            AocError::Unknown(String::from("day 26")).to_string(),
+            "Unknown day 26"
+        );
+
+        let missing = AocError::from(io::Error::new(io::ErrorKind::NotFound, "no such file"));
+        assert_eq!(
+            missing.to_string(),
+            "Error while reading or writing: no such file"
+        );
+        assert!(missing.source().is_some());
+    }
+
+    #[test]
+    fn empty_inputs() {
+        assert!(expect_input("1\n2\n").is_ok());
+        let empty = expect_input(" \n\n").unwrap_err();
+        assert_eq!(empty.to_string(), "Can't read the input: it's empty");
+    }
+}
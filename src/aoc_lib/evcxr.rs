@@ -0,0 +1,198 @@
+use std::collections::HashMap as StdHashMap;
+use std::fmt::Display;
+use std::hash::BuildHasher;
+
+use hashbrown::HashMap;
+
+use crate::aoc_lib::grid::Grid;
+use crate::aoc_lib::viz::svg::escape;
+use crate::aoc_lib::Answer;
+
+// Tables and drawings in a Jupyter notebook running the evcxr kernel, which
+// shows what a cell evaluates to with its `evcxr_display`. The trait has to be
+// in scope for the kernel to find it:
+//
+//     :dep advent_of_code = { path = ".", features = ["evcxr"] }
+//     use advent_of_code::aoc_lib::evcxr::EvcxrDisplay;
+//
+// The plain text is there for the kernels and exports without HTML.
+pub trait EvcxrDisplay {
+    fn html(&self) -> String;
+    fn text(&self) -> String;
+
+    fn evcxr_display(&self) {
+        print!("{}", content(&self.text(), &self.html()));
+    }
+}
+
+// The markers the kernel looks for on stdout, one block per MIME type
+fn content(text: &str, html: &str) -> String {
+    format!(
+        "EVCXR_BEGIN_CONTENT text/plain\n{}\nEVCXR_END_CONTENT\nEVCXR_BEGIN_CONTENT text/html\n{}\nEVCXR_END_CONTENT\n",
+        text, html
+    )
+}
+
+// Monospaced, so the rows of a drawing line up
+const CELL_STYLE: &str = "font-family: monospace; padding: 0 2px; text-align: center";
+
+// Characters next to each other like in the input, anything wider spaced and
+// lined up on the right
+impl<T: Display> EvcxrDisplay for Grid<T> {
+    fn html(&self) -> String {
+        let mut html = String::from("<table style=\"border-collapse: collapse\">\n");
+        for row in 0..self.height() {
+            html.push_str("<tr>");
+            for column in 0..self.width() {
+                let cell = escape(&self[(row, column)].to_string());
+                html.push_str(&format!("<td style=\"{}\">{}</td>", CELL_STYLE, cell));
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    fn text(&self) -> String {
+        let cells: Vec<String> = self.positions().map(|p| self[p].to_string()).collect();
+        let widest = cells.iter().map(|cell| cell.chars().count()).max();
+        let rows = cells.chunks(self.width().max(1)).map(|row| match widest {
+            Some(0 | 1) | None => row.concat(),
+            Some(widest) => {
+                let padded: Vec<String> = row
+                    .iter()
+                    .map(|cell| format!("{:>1$}", cell, widest))
+                    .collect();
+                padded.join(" ")
+            }
+        });
+        rows.collect::<Vec<String>>().join("\n")
+    }
+}
+
+impl EvcxrDisplay for Answer {
+    fn html(&self) -> String {
+        match self {
+            Answer::Grid(drawing) => format!("<pre>{}</pre>", escape(drawing.trim_end())),
+            answer => format!("<code>{}</code>", escape(&answer.to_string())),
+        }
+    }
+
+    fn text(&self) -> String {
+        match self {
+            Answer::Grid(drawing) => drawing.trim_end().to_string(),
+            answer => answer.to_string(),
+        }
+    }
+}
+
+// The most common first, the same counts in the order of their keys' text
+fn tally<'a, K: Display + 'a, V: Display + Ord + 'a>(
+    counts: impl Iterator<Item = (&'a K, &'a V)>,
+) -> Vec<(String, &'a V)> {
+    let mut rows: Vec<(String, &V)> = counts
+        .map(|(key, count)| (key.to_string(), count))
+        .collect();
+    rows.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+    rows
+}
+
+fn tally_html<V: Display>(rows: &[(String, &V)]) -> String {
+    let mut html = String::from("<table>\n<tr><th>Key</th><th>Count</th></tr>\n");
+    for (key, count) in rows {
+        html.push_str(&format!(
+            "<tr><td style=\"{}\">{}</td><td style=\"text-align: right\">{}</td></tr>\n",
+            CELL_STYLE,
+            escape(key),
+            count
+        ));
+    }
+    html.push_str("</table>");
+    html
+}
+
+fn tally_text<V: Display>(rows: &[(String, &V)]) -> String {
+    let counts: Vec<String> = rows.iter().map(|(_, count)| count.to_string()).collect();
+    let key_width = rows.iter().map(|(key, _)| key.chars().count()).max();
+    let count_width = counts.iter().map(|count| count.chars().count()).max();
+    let lines = rows.iter().zip(&counts).map(|((key, _), count)| {
+        format!(
+            "{:<2$}  {:>3$}",
+            key,
+            count,
+            key_width.unwrap_or(0),
+            count_width.unwrap_or(0)
+        )
+    });
+    lines.collect::<Vec<String>>().join("\n")
+}
+
+// Maps of counts, like the ones `Itertools::counts` gives, as a table of the
+// keys and their counts
+impl<K: Display, V: Display + Ord, S: BuildHasher> EvcxrDisplay for HashMap<K, V, S> {
+    fn html(&self) -> String {
+        tally_html(&tally(self.iter()))
+    }
+
+    fn text(&self) -> String {
+        tally_text(&tally(self.iter()))
+    }
+}
+
+impl<K: Display, V: Display + Ord, S: BuildHasher> EvcxrDisplay for StdHashMap<K, V, S> {
+    fn html(&self) -> String {
+        tally_html(&tally(self.iter()))
+    }
+
+    fn text(&self) -> String {
+        tally_text(&tally(self.iter()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+
+    #[test]
+    fn grids() {
+        let lights = Grid::parse("#.\n<#", |_, c| Ok(c)).unwrap();
+        assert_eq!(lights.text(), "#.\n<#");
+        let html = lights.html();
+        assert_eq!(html.matches("<tr>").count(), 2);
+        assert!(html.contains(">&lt;</td>"), "{}", html);
+
+        let heights = Grid::parse("19\n0a", |_, c| Ok(c.to_digit(16).unwrap() * 3)).unwrap();
+        assert_eq!(heights.text(), " 3 27\n 0 30");
+    }
+
+    #[test]
+    fn answers() {
+        assert_eq!(Answer::UInt(42).text(), "42");
+        assert_eq!(
+            Answer::Text(String::from("a<b")).html(),
+            "<code>a&lt;b</code>"
+        );
+        let drawing = Answer::Grid(String::from("#..#\n####\n\n"));
+        assert_eq!(drawing.text(), "#..#\n####");
+        assert_eq!(drawing.html(), "<pre>#..#\n####</pre>");
+        assert_eq!(
+            content(&drawing.text(), &drawing.html()),
+            "EVCXR_BEGIN_CONTENT text/plain\n#..#\n####\nEVCXR_END_CONTENT\nEVCXR_BEGIN_CONTENT text/html\n<pre>#..#\n####</pre>\nEVCXR_END_CONTENT\n"
+        );
+    }
+
+    #[test]
+    fn counters() {
+        let letters = "abracadabra".chars().counts();
+        assert_eq!(letters.text(), "a  5\nb  2\nr  2\nc  1\nd  1");
+        let html = letters.html();
+        assert!(html.starts_with("<table>\n<tr><th>Key</th><th>Count</th></tr>\n"));
+        assert_eq!(html.matches("<tr>").count(), 6);
+
+        let words: HashMap<&str, usize> = [("many", 12), ("few", 3)].into_iter().collect();
+        assert_eq!(words.text(), "many  12\nfew    3");
+        assert_eq!(HashMap::<char, usize>::new().text(), "");
+    }
+}
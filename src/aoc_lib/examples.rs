@@ -0,0 +1,227 @@
+use std::time::Duration;
+
+use crate::aoc_lib::clock::Instant;
+use crate::aoc_lib::config::InputKind;
+use crate::aoc_lib::year::{self, Parts};
+use crate::aoc_lib::{Answer, AocError, Solved};
+use crate::registry;
+
+// The examples from the puzzle texts and the answers they give, listed in
+// examples.toml. Only the bit of TOML the table needs is understood: a
+// `[YEAR.dayNN]` header for each day, then quoted `part1`, `part2` and
+// `input` values. The input defaults to inputs/YEAR/dayNN.example.txt.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Example {
+    pub year: u16,
+    pub day: u8,
+    // File in inputs/YEAR
+    pub input: String,
+    pub answers: [Option<String>; 2],
+}
+
+fn header(line: &str) -> Option<(u16, u8)> {
+    let (year, day) = line
+        .strip_prefix('[')?
+        .strip_suffix(']')?
+        .split_once(".day")?;
+
+    year.parse().ok().zip(day.parse().ok())
+}
+
+pub fn parse(table: &str) -> Result<Vec<Example>, String> {
+    let mut examples: Vec<Example> = Vec::new();
+    for (id, line) in table.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let error = |what: &str| format!("Line {}: {}, got \"{}\"", id + 1, what, line);
+
+        if line.starts_with('[') {
+            let (year, day) = header(line).ok_or_else(|| error("expected [YEAR.dayNN]"))?;
+            if examples.iter().any(|e| e.year == year && e.day == day) {
+                return Err(error("day listed twice"));
+            }
+            examples.push(Example {
+                year,
+                day,
+                input: format!("day{:02}.example.txt", day),
+                answers: [None, None],
+            });
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| error("expected key = \"value\""))?;
+        let value = value
+            .trim()
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .filter(|v| !v.contains('"'))
+            .ok_or_else(|| error("values are quoted strings"))?
+            .to_string();
+        let example = examples
+            .last_mut()
+            .ok_or_else(|| error("values go under a day"))?;
+        match key.trim() {
+            "input" => example.input = value,
+            "part1" => example.answers[0] = Some(value),
+            "part2" => example.answers[1] = Some(value),
+            _ => return Err(error("keys are input, part1 or part2")),
+        }
+    }
+
+    Ok(examples)
+}
+
+// A part of a solver run on the example, with the answer the table expects
+pub struct Checked {
+    pub solver: &'static str,
+    pub part: u8,
+    pub answer: Result<Solved, AocError>,
+    pub took: Duration,
+    pub expected: Option<Answer>,
+}
+
+impl Checked {
+    // Why the answer isn't the one in the table, if it isn't
+    pub fn failure(&self, example: &Example) -> Option<String> {
+        let expected = self.expected.as_ref()?;
+        let got = match &self.answer {
+            Ok(solved) if solved.answer == *expected => return None,
+            Ok(solved) => format!("answered {}", solved.answer),
+            Err(error) => error.to_string(),
+        };
+
+        Some(format!(
+            "{} day {} part {}, solver \"{}\": expected {}, {}",
+            example.year, example.day, self.part, self.solver, expected, got
+        ))
+    }
+}
+
+// Runs every solver of the day on the example input, the interactive ones
+// aside. The parts of the day are the "default" solver, like with --algo all.
+pub fn check(example: &Example, input: &str) -> Result<Vec<Checked>, AocError> {
+    let year = year::find(registry(), example.year)
+        .ok_or_else(|| AocError::Unknown(format!("year {}", example.year)))?;
+    let mut solvers = vec![("default", year.get_day(example.day)?)];
+    for variant in year.variants(example.day) {
+        if let Parts::Solve(parts) = variant.parts {
+            solvers.push((variant.name, parts));
+        }
+    }
+
+    let mut checked = Vec::new();
+    for (solver, (part1, part2)) in solvers {
+        for (part, run) in [(1, part1), (2, part2)] {
+            let Some(run) = run else {
+                continue;
+            };
+            let start = Instant::now();
+            let answer = run(input, InputKind::Example);
+            checked.push(Checked {
+                solver,
+                part,
+                answer,
+                took: start.elapsed(),
+                expected: example.answers[usize::from(part) - 1]
+                    .as_deref()
+                    .map(Answer::from),
+            });
+        }
+    }
+
+    Ok(checked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table() {
+        let examples = parse(
+            "# Comment
+            [2022.day01]
+            part1 = \"24000\"
+            part2 = \"45000\"
+
+            [2021.day22]
+            input = \"day22.small.txt\"
+            part1 = \"590784\"",
+        )
+        .unwrap();
+
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples[0].input, "day01.example.txt");
+        assert_eq!(examples[0].answers[1].as_deref(), Some("45000"));
+        assert_eq!(examples[1].input, "day22.small.txt");
+        assert_eq!(examples[1].answers, [Some(String::from("590784")), None]);
+    }
+
+    #[test]
+    fn broken_tables() {
+        assert!(parse("part1 = \"1\"").is_err());
+        assert!(parse("[2022.01]").is_err());
+        assert!(parse("[2022.day01]\npart3 = \"1\"").is_err());
+        assert!(parse("[2022.day01]\npart1 = 1").is_err());
+        assert!(parse("[2022.day01]\n[2022.day01]").is_err());
+    }
+
+    #[test]
+    fn failures() {
+        let example = &parse("[2022.day01]\npart1 = \"07\"").unwrap()[0];
+        let checked = |answer, expected: Option<&str>| Checked {
+            solver: "fast",
+            part: 1,
+            answer,
+            took: Duration::ZERO,
+            expected: expected.map(Answer::from),
+        };
+
+        // Numbers by value, and nothing to compare without an answer listed
+        assert_eq!(
+            checked(Ok(Solved::from(Answer::from(7))), Some("07")).failure(example),
+            None
+        );
+        assert_eq!(
+            checked(Ok(Solved::from(Answer::from(9))), None).failure(example),
+            None
+        );
+        // A note with the answer doesn't make up for the answer
+        let noted = Solved::from(Answer::from(9)).note("Most: 7");
+        assert_eq!(
+            checked(Ok(noted), Some("7")).failure(example).unwrap(),
+            "2022 day 1 part 1, solver \"fast\": expected 7, answered 9"
+        );
+        assert_eq!(
+            checked(Err(AocError::Unimplemented), Some("7"))
+                .failure(example)
+                .unwrap(),
+            "2022 day 1 part 1, solver \"fast\": expected 7, Not implemented yet"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "year2022")]
+    fn solvers_checked() {
+        let example = &parse("[2022.day14]\npart1 = \"24\"\npart2 = \"93\"").unwrap()[0];
+        let input = include_str!("../../inputs/2022/day14.example.txt");
+        let checked = check(example, input).unwrap();
+
+        // The day and its "fill" variant
+        assert_eq!(checked.len(), 4);
+        assert!(checked.iter().all(|part| part.failure(example).is_none()));
+
+        let wrong = &parse("[2022.day14]\npart1 = \"25\"").unwrap()[0];
+        let failures: Vec<String> = check(wrong, input)
+            .unwrap()
+            .iter()
+            .filter_map(|part| part.failure(wrong))
+            .collect();
+        assert_eq!(failures.len(), 2);
+        assert!(check(&parse("[1999.day01]").unwrap()[0], input).is_err());
+    }
+}
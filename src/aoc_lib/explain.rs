@@ -0,0 +1,63 @@
+use std::fmt;
+
+// How a day got to its answer: a line, and the lines going into more detail
+// about it, indented under it. Meant for checking a wrong answer on a real
+// input against what the puzzle text says should happen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    pub line: String,
+    pub details: Vec<Explanation>,
+}
+
+impl Explanation {
+    pub fn new(line: impl Into<String>) -> Self {
+        Self {
+            line: line.into(),
+            details: Vec::new(),
+        }
+    }
+
+    pub fn detail(mut self, detail: Explanation) -> Self {
+        self.details.push(detail);
+        self
+    }
+
+    pub fn push(&mut self, detail: Explanation) {
+        self.details.push(detail);
+    }
+
+    fn write(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        writeln!(f, "{}{}", "  ".repeat(depth), self.line)?;
+        for detail in self.details.iter() {
+            detail.write(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indented_report() {
+        let mut explanation = Explanation::new("Answer: 12");
+        explanation.push(
+            Explanation::new("First half: 5")
+                .detail(Explanation::new("2 + 3"))
+                .detail(Explanation::new("no carry")),
+        );
+        explanation.push(Explanation::new("Second half: 7"));
+
+        assert_eq!(
+            explanation.to_string(),
+            "Answer: 12\n  First half: 5\n    2 + 3\n    no carry\n  Second half: 7\n"
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use crate::aoc_lib::config::InputKind;
+use crate::aoc_lib::{Answer, AocError};
+use crate::find_part;
+
+// The C interface of the library, declared in ffi/advent_of_code.h. The
+// numbers below and the functions' signatures are part of it: new codes can be
+// added, the existing ones never change meaning.
+
+// Bumped only when something in the header changes in a way old callers would
+// notice
+pub const AOC_ABI_VERSION: u32 = 1;
+
+pub const AOC_OK: c_int = 0;
+// The input doesn't look like what the puzzle describes
+pub const AOC_ERROR_PARSE: c_int = 1;
+// The input reads fine but has no answer
+pub const AOC_ERROR_UNSOLVABLE: c_int = 2;
+// The day has no solution yet
+pub const AOC_ERROR_UNIMPLEMENTED: c_int = 3;
+// There is no such year, day or part
+pub const AOC_ERROR_UNKNOWN: c_int = 4;
+// A file couldn't be read or written
+pub const AOC_ERROR_IO: c_int = 5;
+// A null pointer, or an input that isn't UTF-8
+pub const AOC_ERROR_ARGUMENT: c_int = 6;
+// The solver panicked, the message says where
+pub const AOC_ERROR_PANIC: c_int = 7;
+
+fn error_code(error: &AocError) -> c_int {
+    match error {
+        AocError::Parse(_) => AOC_ERROR_PARSE,
+        AocError::Unsolvable(_) => AOC_ERROR_UNSOLVABLE,
+        AocError::Unimplemented => AOC_ERROR_UNIMPLEMENTED,
+        AocError::Unknown(_) => AOC_ERROR_UNKNOWN,
+        AocError::Io(_) => AOC_ERROR_IO,
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    match (
+        payload.downcast_ref::<&str>(),
+        payload.downcast_ref::<String>(),
+    ) {
+        (Some(message), _) => format!("The solver panicked: {}", message),
+        (_, Some(message)) => format!("The solver panicked: {}", message),
+        _ => String::from("The solver panicked"),
+    }
+}
+
+// What the answer or the error says, as a string only `aoc_string_free` may
+// free. C strings end at the first NUL, so none can be inside.
+fn owned(text: String) -> *mut c_char {
+    let text = CString::new(text.replace('\0', ""));
+    text.map_or(ptr::null_mut(), CString::into_raw)
+}
+
+fn solve(year: u16, day: u8, part: u8, input: &str, kind: InputKind) -> (c_int, String) {
+    let run = match find_part(year, day, part) {
+        Ok(run) => run,
+        Err(error) => return (error_code(&error), error.to_string()),
+    };
+
+    // Unwinding out of an extern "C" function aborts the caller's process
+    match panic::catch_unwind(AssertUnwindSafe(|| run(input, kind))) {
+        Ok(Ok(solved)) => match solved.answer {
+            // Without the line break that lines the drawing up in the terminal
+            Answer::Grid(drawing) => (AOC_OK, drawing.trim_end().to_string()),
+            answer => (AOC_OK, answer.to_string()),
+        },
+        Ok(Err(error)) => (error_code(&error), error.to_string()),
+        Err(payload) => (AOC_ERROR_PANIC, panic_message(payload.as_ref())),
+    }
+}
+
+/// The version of the interface the library was built with, to compare with
+/// `AOC_ABI_VERSION` in the header the caller was built with
+#[no_mangle]
+pub extern "C" fn aoc_abi_version() -> u32 {
+    AOC_ABI_VERSION
+}
+
+/// Solves a part of a day on `input`. Returns `AOC_OK` and points `out` at the
+/// answer, or returns an error code and points `out` at the message. Drawn
+/// answers come as their lines. Either way the string belongs to the caller,
+/// who gives it back with `aoc_string_free`.
+///
+/// # Safety
+///
+/// `input` has to be a NUL-terminated string that stays untouched during the
+/// call. `out` has to point at room for a pointer, or be null when only the
+/// code matters.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve(
+    year: u16,
+    day: u8,
+    part: u8,
+    input: *const c_char,
+    out: *mut *mut c_char,
+) -> c_int {
+    solve_into(year, day, part, input, InputKind::Real, out)
+}
+
+/// Same as `aoc_solve`, on the example of the puzzle text. A few days ask
+/// something else of it than of the real inputs.
+///
+/// # Safety
+///
+/// The same as for `aoc_solve`
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve_example(
+    year: u16,
+    day: u8,
+    part: u8,
+    input: *const c_char,
+    out: *mut *mut c_char,
+) -> c_int {
+    solve_into(year, day, part, input, InputKind::Example, out)
+}
+
+unsafe fn solve_into(
+    year: u16,
+    day: u8,
+    part: u8,
+    input: *const c_char,
+    kind: InputKind,
+    out: *mut *mut c_char,
+) -> c_int {
+    let (code, text) = match input.is_null() {
+        true => (
+            AOC_ERROR_ARGUMENT,
+            String::from("The input is a null pointer"),
+        ),
+        false => match CStr::from_ptr(input).to_str() {
+            Ok(input) => solve(year, day, part, input, kind),
+            Err(_) => (AOC_ERROR_ARGUMENT, String::from("The input is not UTF-8")),
+        },
+    };
+
+    if !out.is_null() {
+        *out = owned(text);
+    }
+    code
+}
+
+/// Frees a string `aoc_solve` or `aoc_solve_example` handed out, null is fine
+///
+/// # Safety
+///
+/// `text` has to come from one of those and not be freed already
+#[no_mangle]
+pub unsafe extern "C" fn aoc_string_free(text: *mut c_char) {
+    if !text.is_null() {
+        drop(CString::from_raw(text));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // What a C caller does
+    fn call(year: u16, day: u8, part: u8, input: &CStr) -> (c_int, String) {
+        unsafe {
+            let mut out = ptr::null_mut();
+            let code = aoc_solve(year, day, part, input.as_ptr(), &mut out);
+            let text = CStr::from_ptr(out).to_str().unwrap().to_string();
+            aoc_string_free(out);
+            (code, text)
+        }
+    }
+
+    #[test]
+    fn error_codes() {
+        assert_eq!(
+            call(1999, 1, 1, c""),
+            (AOC_ERROR_UNKNOWN, String::from("Unknown year 1999"))
+        );
+        assert_eq!(call(2022, 1, 1, c"\xff").0, AOC_ERROR_ARGUMENT);
+        unsafe {
+            assert_eq!(
+                aoc_solve(2022, 1, 1, ptr::null(), ptr::null_mut()),
+                AOC_ERROR_ARGUMENT
+            );
+            aoc_string_free(ptr::null_mut());
+        }
+        assert_eq!(aoc_abi_version(), AOC_ABI_VERSION);
+
+        let payload = panic::catch_unwind(|| panic!("no way")).unwrap_err();
+        assert_eq!(
+            panic_message(payload.as_ref()),
+            "The solver panicked: no way"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "year2022")]
+    fn answers() {
+        let input = CString::new(include_str!("../../inputs/2022/day01.example.txt")).unwrap();
+        assert_eq!(call(2022, 1, 2, &input), (AOC_OK, String::from("45000")));
+        assert_eq!(call(2022, 20, 1, &input).0, AOC_ERROR_UNIMPLEMENTED);
+
+        let (code, message) = call(2022, 21, 1, c"x");
+        assert_eq!(code, AOC_ERROR_PARSE);
+        assert!(!message.is_empty());
+
+        let input = CString::new(include_str!("../../inputs/2022/day15.example.txt")).unwrap();
+        unsafe {
+            let mut out = ptr::null_mut();
+            let code = aoc_solve_example(2022, 15, 1, input.as_ptr(), &mut out);
+            assert_eq!((code, CStr::from_ptr(out).to_str()), (AOC_OK, Ok("26")));
+            aoc_string_free(out);
+        }
+    }
+}
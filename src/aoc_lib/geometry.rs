@@ -0,0 +1,363 @@
+#![warn(missing_docs)]
+
+use alloc::vec::Vec;
+use core::ops::{Add, AddAssign, Neg, Sub};
+
+/// Integer point on a plane, y grows upwards
+///
+/// ```
+/// use advent_of_code::aoc_lib::geometry::Point2;
+///
+/// let mut head = Point2::default();
+/// head += Point2::new(2, 1);
+/// assert_eq!(head - Point2::new(0, 1), Point2::new(2, 0));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default, PartialOrd, Ord)]
+pub struct Point2 {
+    /// Grows to the right
+    pub x: i64,
+    /// Grows upwards
+    pub y: i64,
+}
+
+impl Point2 {
+    /// ```
+    /// use advent_of_code::aoc_lib::geometry::Point2;
+    ///
+    /// const ORIGIN: Point2 = Point2::new(0, 0);
+    /// assert_eq!(ORIGIN, Point2::default());
+    /// ```
+    pub const fn new(x: i64, y: i64) -> Point2 {
+        Point2 { x, y }
+    }
+
+    /// Distance moving along the axes only
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::geometry::Point2;
+    ///
+    /// assert_eq!(Point2::new(1, -2).manhattan(&Point2::new(-3, 1)), 7);
+    /// ```
+    pub fn manhattan(&self, other: &Point2) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// Distance when moving diagonally costs the same as moving straight
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::geometry::Point2;
+    ///
+    /// assert_eq!(Point2::new(1, -2).chebyshev(&Point2::new(-3, 1)), 4);
+    /// ```
+    pub fn chebyshev(&self, other: &Point2) -> u64 {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// The sign of each coordinate, a step of one towards where this points
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::geometry::Point2;
+    ///
+    /// assert_eq!(Point2::new(-7, 0).signum(), Point2::new(-1, 0));
+    /// ```
+    pub fn signum(&self) -> Point2 {
+        Point2::new(self.x.signum(), self.y.signum())
+    }
+
+    /// Where a knot tied to `leader` ends up: it stays put while the two still
+    /// touch, diagonals included, otherwise it moves one step towards it on
+    /// both axes
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::geometry::Point2;
+    ///
+    /// let tail = Point2::new(0, 0);
+    /// assert_eq!(tail.follow(&Point2::new(1, 1)), tail);
+    /// assert_eq!(tail.follow(&Point2::new(2, 1)), Point2::new(1, 1));
+    /// ```
+    pub fn follow(&self, leader: &Point2) -> Point2 {
+        if self.chebyshev(leader) <= 1 {
+            *self
+        } else {
+            *self + (*leader - *self).signum()
+        }
+    }
+}
+
+impl Add for Point2 {
+    type Output = Point2;
+
+    fn add(self, other: Point2) -> Point2 {
+        Point2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign for Point2 {
+    fn add_assign(&mut self, other: Point2) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point2 {
+    type Output = Point2;
+
+    fn sub(self, other: Point2) -> Point2 {
+        Point2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+/// Integer point in space
+///
+/// ```
+/// use advent_of_code::aoc_lib::geometry::Point3;
+///
+/// let beacon = Point3::new(1, 2, 3);
+/// assert_eq!(beacon + -beacon, Point3::default());
+/// assert_eq!(beacon - Point3::new(1, 1, 1), Point3::new(0, 1, 2));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default, PartialOrd, Ord)]
+pub struct Point3 {
+    /// First axis
+    pub x: i64,
+    /// Second axis
+    pub y: i64,
+    /// Third axis
+    pub z: i64,
+}
+
+impl Point3 {
+    /// ```
+    /// use advent_of_code::aoc_lib::geometry::Point3;
+    ///
+    /// const ORIGIN: Point3 = Point3::new(0, 0, 0);
+    /// assert_eq!(ORIGIN, Point3::default());
+    /// ```
+    pub const fn new(x: i64, y: i64, z: i64) -> Point3 {
+        Point3 { x, y, z }
+    }
+
+    /// Distance moving along the axes only
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::geometry::Point3;
+    ///
+    /// assert_eq!(Point3::new(1, 2, 3).manhattan(&Point3::new(-1, 2, 6)), 5);
+    /// ```
+    pub fn manhattan(&self, other: &Point3) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    /// Squared euclidean distance, exact and the same however the two are turned
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::geometry::Point3;
+    ///
+    /// assert_eq!(Point3::new(1, 2, 3).distance_squared(&Point3::new(-1, 2, 6)), 13);
+    /// ```
+    pub fn distance_squared(&self, other: &Point3) -> i64 {
+        let diff = *self - *other;
+        diff.x * diff.x + diff.y * diff.y + diff.z * diff.z
+    }
+
+    fn coordinates(&self) -> [i64; 3] {
+        [self.x, self.y, self.z]
+    }
+}
+
+impl Add for Point3 {
+    type Output = Point3;
+
+    fn add(self, other: Point3) -> Point3 {
+        Point3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Point3 {
+    type Output = Point3;
+
+    fn sub(self, other: Point3) -> Point3 {
+        Point3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Neg for Point3 {
+    type Output = Point3;
+
+    fn neg(self) -> Point3 {
+        Point3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+/// One of the 24 ways to turn something in space keeping it lined up with the
+/// axes. Every axis of the result is one of the original axes, maybe flipped.
+///
+/// ```
+/// use advent_of_code::aoc_lib::geometry::{Point3, Rotation};
+///
+/// // A beacon seen from every way a scanner can face
+/// let beacon = Point3::new(1, 2, 3);
+/// let seen: Vec<Point3> = Rotation::all().iter().map(|r| r.apply(beacon)).collect();
+/// assert!(seen.contains(&Point3::new(-2, 1, 3)));
+/// assert!(!seen.contains(&Point3::new(2, 1, 3)));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Rotation {
+    axes: [usize; 3],
+    signs: [i64; 3],
+}
+
+impl Rotation {
+    /// Leaves everything where it is
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::geometry::{Point3, Rotation};
+    ///
+    /// assert_eq!(Rotation::IDENTITY.apply(Point3::new(1, 2, 3)), Point3::new(1, 2, 3));
+    /// ```
+    pub const IDENTITY: Rotation = Rotation {
+        axes: [0, 1, 2],
+        signs: [1, 1, 1],
+    };
+
+    /// Axis permutations and flips with a determinant of 1, mirror images are
+    /// left out
+    ///
+    /// ```
+    /// use std::collections::HashSet;
+    ///
+    /// use advent_of_code::aoc_lib::geometry::{Point3, Rotation};
+    ///
+    /// let rotations = Rotation::all();
+    /// let beacon = Point3::new(1, 2, 3);
+    /// let seen: HashSet<Point3> = rotations.iter().map(|r| r.apply(beacon)).collect();
+    /// assert_eq!((rotations.len(), seen.len()), (24, 24));
+    /// ```
+    pub fn all() -> Vec<Rotation> {
+        const PERMUTATIONS: [([usize; 3], i64); 6] = [
+            ([0, 1, 2], 1),
+            ([1, 2, 0], 1),
+            ([2, 0, 1], 1),
+            ([0, 2, 1], -1),
+            ([2, 1, 0], -1),
+            ([1, 0, 2], -1),
+        ];
+
+        let mut rotations = Vec::with_capacity(24);
+        for (axes, parity) in PERMUTATIONS {
+            for flips in 0..8 {
+                let signs = [0, 1, 2].map(|bit| if flips & (1 << bit) == 0 { 1 } else { -1 });
+                if parity * signs.iter().product::<i64>() == 1 {
+                    rotations.push(Rotation { axes, signs });
+                }
+            }
+        }
+
+        rotations
+    }
+
+    /// Turns `point` around the origin
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::geometry::{Point3, Rotation};
+    ///
+    /// let beacon = Point3::new(1, 2, 3);
+    /// for rotation in Rotation::all() {
+    ///     let turned = rotation.apply(beacon);
+    ///     assert_eq!(turned.distance_squared(&Point3::default()), 14);
+    /// }
+    /// ```
+    pub fn apply(&self, point: Point3) -> Point3 {
+        let coordinates = point.coordinates();
+        let [x, y, z] = [0, 1, 2].map(|axis| coordinates[self.axes[axis]] * self.signs[axis]);
+        Point3::new(x, y, z)
+    }
+
+    /// Turning by `first` and then by `self`
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::geometry::{Point3, Rotation};
+    ///
+    /// let beacon = Point3::new(1, 2, 3);
+    /// let rotations = Rotation::all();
+    /// for (first, then) in rotations.iter().zip(rotations.iter().rev()) {
+    ///     assert_eq!(then.compose(first).apply(beacon), then.apply(first.apply(beacon)));
+    /// }
+    /// ```
+    pub fn compose(&self, first: &Rotation) -> Rotation {
+        Rotation {
+            axes: [0, 1, 2].map(|axis| first.axes[self.axes[axis]]),
+            signs: [0, 1, 2].map(|axis| self.signs[axis] * first.signs[self.axes[axis]]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hashbrown::HashSet;
+    use itertools::Itertools;
+
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let point = Point2::new(3, -4);
+
+        assert_eq!(point + Point2::new(-3, 4), Point2::default());
+        assert_eq!(point - point, Point2::default());
+        assert_eq!(point.manhattan(&Point2::default()), 7);
+        assert_eq!(point.chebyshev(&Point2::default()), 4);
+        assert_eq!(point.signum(), Point2::new(1, -1));
+    }
+
+    #[test]
+    fn following() {
+        let tail = Point2::default();
+
+        // Touching, overlapping or diagonal, nothing moves
+        for leader in [(0, 0), (1, 0), (1, 1), (-1, 1)] {
+            let leader = Point2::new(leader.0, leader.1);
+            assert_eq!(tail.follow(&leader), tail);
+        }
+        assert_eq!(tail.follow(&Point2::new(2, 0)), Point2::new(1, 0));
+        assert_eq!(tail.follow(&Point2::new(0, -2)), Point2::new(0, -1));
+        assert_eq!(tail.follow(&Point2::new(1, 2)), Point2::new(1, 1));
+        assert_eq!(tail.follow(&Point2::new(-2, -2)), Point2::new(-1, -1));
+    }
+
+    #[test]
+    fn space_arithmetic() {
+        let point = Point3::new(3, -4, 5);
+
+        assert_eq!(point + -point, Point3::default());
+        assert_eq!(point.manhattan(&Point3::default()), 12);
+        assert_eq!(point.distance_squared(&Point3::new(2, -4, 7)), 5);
+    }
+
+    #[test]
+    fn rotations() {
+        let rotations = Rotation::all();
+        let point = Point3::new(1, 2, 3);
+
+        // Every rotation puts the point somewhere else, at the same distance
+        // from the origin and from any other turned point
+        let turned: HashSet<Point3> = rotations.iter().map(|r| r.apply(point)).collect();
+        assert_eq!(turned.len(), 24);
+        let other = Point3::new(-7, 0, 4);
+        for rotation in rotations.iter() {
+            assert_eq!(
+                rotation
+                    .apply(point)
+                    .distance_squared(&rotation.apply(other)),
+                point.distance_squared(&other)
+            );
+        }
+        assert!(rotations.contains(&Rotation::IDENTITY));
+
+        // Turning twice is still one of the 24 rotations
+        for (first, second) in rotations.iter().cartesian_product(rotations.iter()) {
+            let composed = second.compose(first);
+            assert!(rotations.contains(&composed));
+            assert_eq!(composed.apply(point), second.apply(first.apply(point)));
+        }
+    }
+}
@@ -0,0 +1,296 @@
+#![warn(missing_docs)]
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::{Index, IndexMut};
+
+/// (row, column), with (0, 0) in the top left corner
+///
+/// ```
+/// use advent_of_code::aoc_lib::grid::{Grid, Position};
+///
+/// let grid = Grid::filled(3, 2, 0);
+/// let corner: Position = (1, 2);
+/// assert_eq!(grid.get(corner), Some(&0));
+/// assert_eq!(grid.get((2, 0)), None);
+/// ```
+pub type Position = (usize, usize);
+
+/// Rectangular grid of cells, stored row by row
+///
+/// ```
+/// use advent_of_code::aoc_lib::grid::Grid;
+///
+/// let mut lights = Grid::parse("#.\n.#", |_, c| Ok(c == '#')).unwrap();
+/// assert!(lights[(1, 1)] && !lights[(0, 1)]);
+///
+/// lights[(0, 1)] = true;
+/// assert_eq!(lights.positions().filter(|p| lights[*p]).count(), 3);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T: Clone> Grid<T> {
+    /// A grid with every cell set to `value`
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::grid::Grid;
+    ///
+    /// let grid = Grid::filled(4, 2, '.');
+    /// assert_eq!((grid.width(), grid.height()), (4, 2));
+    /// assert!(grid.positions().all(|p| grid[p] == '.'));
+    /// ```
+    pub fn filled(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// One row per non-empty line, leading and trailing spaces are ignored.
+    /// Rows of different widths are an error, as is anything `cell` refuses.
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::grid::Grid;
+    ///
+    /// let digit = |_, c: char| c.to_digit(10).ok_or_else(|| format!("'{}' is not a digit", c));
+    /// let heights = Grid::parse("123\n456", digit).unwrap();
+    /// assert_eq!(heights[(1, 0)], 4);
+    ///
+    /// assert!(Grid::parse("12\n3", digit).is_err());
+    /// assert!(Grid::parse("1x", digit).is_err());
+    ///
+    /// let ids = Grid::parse("..\n..", |(row, column), _| Ok(row * 10 + column)).unwrap();
+    /// assert_eq!(ids[(1, 1)], 11);
+    /// ```
+    pub fn parse(
+        input: &str,
+        mut cell: impl FnMut(Position, char) -> Result<T, String>,
+    ) -> Result<Grid<T>, String> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+
+        for line in input.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let row_width = line.chars().count();
+            if *width.get_or_insert(row_width) != row_width {
+                return Err(format!(
+                    "Row {} is {} cells wide instead of {}",
+                    height,
+                    row_width,
+                    width.unwrap()
+                ));
+            }
+            for (column, c) in line.chars().enumerate() {
+                cells.push(cell((height, column), c)?);
+            }
+            height += 1;
+        }
+
+        Ok(Grid {
+            cells,
+            width: width.ok_or_else(|| String::from("The grid is empty"))?,
+            height,
+        })
+    }
+
+    /// Cells in a row
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::grid::Grid;
+    ///
+    /// assert_eq!(Grid::filled(5, 3, 0).width(), 5);
+    /// ```
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Cells in a column
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::grid::Grid;
+    ///
+    /// assert_eq!(Grid::filled(5, 3, 0).height(), 3);
+    /// ```
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The cell at `position`, None outside of the grid
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::grid::Grid;
+    ///
+    /// let grid = Grid::parse("ab\ncd", |_, c| Ok(c)).unwrap();
+    /// assert_eq!(grid.get((1, 0)), Some(&'c'));
+    /// assert_eq!(grid.get((0, 2)), None);
+    /// ```
+    pub fn get(&self, (row, column): Position) -> Option<&T> {
+        if row < self.height && column < self.width {
+            self.cells.get(row * self.width + column)
+        } else {
+            None
+        }
+    }
+
+    /// The positions of each row, top to bottom
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::grid::Grid;
+    ///
+    /// let rows: Vec<_> = Grid::filled(2, 2, 0).rows().collect();
+    /// assert_eq!(rows, [vec![(0, 0), (0, 1)], vec![(1, 0), (1, 1)]]);
+    /// ```
+    pub fn rows(&self) -> impl Iterator<Item = Vec<Position>> {
+        let width = self.width;
+        (0..self.height).map(move |row| (0..width).map(|column| (row, column)).collect())
+    }
+
+    /// The positions of each column, left to right
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::grid::Grid;
+    ///
+    /// let columns: Vec<_> = Grid::filled(2, 2, 0).columns().collect();
+    /// assert_eq!(columns, [vec![(0, 0), (1, 0)], vec![(0, 1), (1, 1)]]);
+    /// ```
+    pub fn columns(&self) -> impl Iterator<Item = Vec<Position>> {
+        let height = self.height;
+        (0..self.width).map(move |column| (0..height).map(|row| (row, column)).collect())
+    }
+
+    /// Every position, row by row
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::grid::Grid;
+    ///
+    /// let positions: Vec<_> = Grid::filled(3, 2, 0).positions().collect();
+    /// assert_eq!(positions, [(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2)]);
+    /// ```
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |id| (id / width, id % width))
+    }
+
+    /// Cells up, down, left and right of `position` that are inside the grid
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::grid::Grid;
+    ///
+    /// let grid = Grid::filled(3, 3, 0);
+    /// let around: Vec<_> = grid.neighbours((1, 1)).collect();
+    /// assert_eq!(around, [(0, 1), (2, 1), (1, 0), (1, 2)]);
+    /// assert_eq!(grid.neighbours((0, 0)).count(), 2);
+    /// ```
+    pub fn neighbours(&self, (row, column): Position) -> impl Iterator<Item = Position> + '_ {
+        [
+            row.checked_sub(1).map(|up| (up, column)),
+            Some((row + 1, column)),
+            column.checked_sub(1).map(|left| (row, left)),
+            Some((row, column + 1)),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|position| self.get(*position).is_some())
+    }
+
+    /// Adapter for the searches in `aoc_lib::search`: the grid as a graph where
+    /// a cell leads to the neighbours that `allowed(from, to)` accepts
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::grid::Grid;
+    /// use advent_of_code::aoc_lib::search::bfs;
+    ///
+    /// // Climbing at most one step at a time, so the only way up spirals
+    /// let heights = Grid::parse("012\n543\n678", |_, c| Ok(c.to_digit(10).unwrap())).unwrap();
+    /// let climb = heights.moves(|from, to| *to <= from + 1);
+    /// assert_eq!(bfs((0, 0), climb, |p| heights[*p] == 8), Some((8, (2, 2))));
+    /// ```
+    pub fn moves<'a>(
+        &'a self,
+        allowed: impl Fn(&T, &T) -> bool + 'a,
+    ) -> impl FnMut(&Position) -> Vec<Position> + 'a {
+        move |position| {
+            let from = &self[*position];
+            self.neighbours(*position)
+                .filter(|next| allowed(from, &self[*next]))
+                .collect()
+        }
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &T {
+        self.get(position)
+            .unwrap_or_else(|| panic!("{:?} is outside of the grid", position))
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, (row, column): Position) -> &mut T {
+        assert!(
+            row < self.height && column < self.width,
+            "{:?} is outside of the grid",
+            (row, column)
+        );
+        &mut self.cells[row * self.width + column]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::aoc_lib::search::bfs;
+
+    fn digits(input: &str) -> Result<Grid<u32>, String> {
+        Grid::parse(input, |_, c| {
+            c.to_digit(10)
+                .ok_or_else(|| format!("'{}' is not a digit", c))
+        })
+    }
+
+    #[test]
+    fn parse() {
+        let grid = digits("123\n456").unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 0)], 4);
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.positions().last(), Some((1, 2)));
+        assert_eq!(grid.rows().nth(1), Some(vec![(1, 0), (1, 1), (1, 2)]));
+        assert_eq!(grid.columns().count(), 3);
+
+        let mut marks = Grid::filled(3, 2, false);
+        marks[(1, 2)] = true;
+        assert_eq!(marks.positions().filter(|p| marks[*p]).count(), 1);
+        assert!(digits("123\n45").is_err());
+        assert!(digits("1x3").is_err());
+        assert!(digits("").is_err());
+    }
+
+    #[test]
+    fn graph_adapter() {
+        let grid = digits("1239\n9949\n9999\n5677").unwrap();
+
+        assert_eq!(grid.neighbours((0, 0)).count(), 2);
+        assert_eq!(grid.neighbours((1, 1)).count(), 4);
+
+        // Only climbing by one at a time, 1 2 3 4 is the only way up
+        let climb = grid.moves(|from, to| *to == from + 1);
+        assert_eq!(bfs((0, 0), climb, |p| grid[*p] == 4), Some((3, (1, 2))));
+        let climb = grid.moves(|from, to| *to == from + 1);
+        assert_eq!(bfs((3, 0), climb, |p| grid[*p] == 9), None);
+    }
+}
@@ -0,0 +1,592 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use hashbrown::HashMap;
+
+use crate::aoc_lib::grid::Grid;
+
+// PNG pictures and animated GIFs of grids, for sharing what a solution does.
+// Both formats are written by hand with the simplest encoding they allow: the
+// PNG data is stored without compression, the GIF frames go through the LZW
+// the format requires.
+
+pub type Rgb = [u8; 3];
+
+// The colours the cells can be painted with, a cell is painted with the index
+// of its colour. GIFs can't have more than 256.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    colours: Vec<Rgb>,
+}
+
+impl Palette {
+    pub fn new(colours: &[Rgb]) -> Result<Self, String> {
+        if colours.is_empty() || colours.len() > 256 {
+            return Err(format!(
+                "A palette has from 1 to 256 colours, not {}",
+                colours.len()
+            ));
+        }
+
+        Ok(Self {
+            colours: colours.to_vec(),
+        })
+    }
+
+    // Bits per colour index, GIF wants at least 2
+    fn bits(&self) -> u8 {
+        let mut bits = 1;
+        while 1 << bits < self.colours.len() {
+            bits += 1;
+        }
+        bits
+    }
+}
+
+// The sparse grids of the days are maps from (row, column) to the cell, with
+// any coordinate. The grid covers all of them, with `empty` everywhere else.
+pub fn from_sparse<T: Clone>(cells: &HashMap<(i64, i64), T>, empty: T) -> Grid<T> {
+    let rows = cells.keys().map(|(row, _)| *row);
+    let columns = cells.keys().map(|(_, column)| *column);
+    let (Some(top), Some(bottom)) = (rows.clone().min(), rows.max()) else {
+        return Grid::filled(0, 0, empty);
+    };
+    let (left, right) = (columns.clone().min().unwrap(), columns.max().unwrap());
+
+    let width = (right - left + 1) as usize;
+    let height = (bottom - top + 1) as usize;
+    let mut grid = Grid::filled(width, height, empty);
+    for ((row, column), cell) in cells {
+        grid[((row - top) as usize, (column - left) as usize)] = cell.clone();
+    }
+
+    grid
+}
+
+// Writes the pictures of a day to target/viz/YEAR/DAY, every cell of the grids
+// becoming a square of `scale` pixels
+#[derive(Debug, Clone)]
+pub struct Exporter {
+    directory: PathBuf,
+    palette: Palette,
+    scale: usize,
+    // Between the frames of a GIF, in hundredths of a second
+    delay: u16,
+}
+
+impl Exporter {
+    pub fn new(year: u16, day: u8, palette: Palette) -> Self {
+        Self {
+            directory: PathBuf::from("target")
+                .join("viz")
+                .join(year.to_string())
+                .join(day.to_string()),
+            palette,
+            scale: 1,
+            delay: 10,
+        }
+    }
+
+    pub fn scale(self, scale: usize) -> Self {
+        Self {
+            scale: scale.max(1),
+            ..self
+        }
+    }
+
+    pub fn delay(self, centiseconds: u16) -> Self {
+        Self {
+            delay: centiseconds,
+            ..self
+        }
+    }
+
+    pub fn directory(self, directory: &Path) -> Self {
+        Self {
+            directory: directory.to_path_buf(),
+            ..self
+        }
+    }
+
+    // Colour indices of the scaled picture, row by row
+    fn pixels<T>(&self, grid: &Grid<T>, paint: &impl Fn(&T) -> u8) -> Result<Vec<u8>, String> {
+        let mut pixels = Vec::with_capacity(grid.width() * grid.height() * self.scale.pow(2));
+        for row in grid.rows() {
+            let mut line = Vec::with_capacity(row.len() * self.scale);
+            for position in row {
+                let colour = paint(&grid[position]);
+                if usize::from(colour) >= self.palette.colours.len() {
+                    return Err(format!("Colour {} is not in the palette", colour));
+                }
+                line.extend(std::iter::repeat_n(colour, self.scale));
+            }
+            for _ in 0..self.scale {
+                pixels.extend(&line);
+            }
+        }
+
+        Ok(pixels)
+    }
+
+    fn write(&self, name: &str, data: &[u8]) -> Result<PathBuf, String> {
+        let path = self.directory.join(name);
+        fs::create_dir_all(&self.directory)
+            .and_then(|_| fs::write(&path, data))
+            .map_err(|e| format!("Error while writing {}: {}", path.display(), e))?;
+
+        Ok(path)
+    }
+
+    pub fn png<T>(
+        &self,
+        name: &str,
+        grid: &Grid<T>,
+        paint: impl Fn(&T) -> u8,
+    ) -> Result<PathBuf, String> {
+        let pixels = self.pixels(grid, &paint)?;
+        let (width, height) = (grid.width() * self.scale, grid.height() * self.scale);
+
+        self.write(name, &encode_png(width, height, &pixels, &self.palette))
+    }
+
+    // Every frame has to be as big as the first one
+    pub fn gif<T>(
+        &self,
+        name: &str,
+        frames: impl IntoIterator<Item = Grid<T>>,
+        paint: impl Fn(&T) -> u8,
+    ) -> Result<PathBuf, String> {
+        let mut frames = frames.into_iter();
+        let first = frames
+            .next()
+            .ok_or_else(|| String::from("An animation needs at least one frame"))?;
+        let size = (first.width(), first.height());
+
+        let mut gif = GifWriter::new(size.0 * self.scale, size.1 * self.scale, &self.palette);
+        gif.frame(&self.pixels(&first, &paint)?, self.delay);
+        for (id, frame) in frames.enumerate() {
+            if (frame.width(), frame.height()) != size {
+                return Err(format!(
+                    "Frame {} is {}x{}, the first one {}x{}",
+                    id + 2,
+                    frame.width(),
+                    frame.height(),
+                    size.0,
+                    size.1
+                ));
+            }
+            gif.frame(&self.pixels(&frame, &paint)?, self.delay);
+        }
+
+        self.write(name, &gif.finish())
+    }
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in bytes {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in bytes {
+        a = (a + u32::from(*byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+// Stored deflate blocks, with the zlib header and checksum around them
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut output = vec![0x78, 0x01];
+    let mut blocks = data.chunks(u16::MAX as usize).peekable();
+    if blocks.peek().is_none() {
+        output.extend([1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        let length = block.len() as u16;
+        output.push(u8::from(blocks.peek().is_none()));
+        output.extend(length.to_le_bytes());
+        output.extend((!length).to_le_bytes());
+        output.extend(block);
+    }
+    output.extend(adler32(data).to_be_bytes());
+
+    output
+}
+
+fn png_chunk(output: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    output.extend((data.len() as u32).to_be_bytes());
+    let start = output.len();
+    output.extend(kind);
+    output.extend(data);
+    let crc = crc32(&output[start..]);
+    output.extend(crc.to_be_bytes());
+}
+
+// An 8 bit indexed PNG, `pixels` holds the colour indices row by row
+pub fn encode_png(width: usize, height: usize, pixels: &[u8], palette: &Palette) -> Vec<u8> {
+    let mut header = Vec::new();
+    header.extend((width as u32).to_be_bytes());
+    header.extend((height as u32).to_be_bytes());
+    // 8 bits, indexed colour, the only compression, filters and no interlacing
+    header.extend([8, 3, 0, 0, 0]);
+
+    // Every row starts with its filter, none
+    let mut scanlines = Vec::with_capacity(pixels.len() + height);
+    for row in pixels.chunks(width.max(1)) {
+        scanlines.push(0);
+        scanlines.extend(row);
+    }
+
+    let mut output = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+    png_chunk(&mut output, b"IHDR", &header);
+    png_chunk(&mut output, b"PLTE", &palette.colours.concat());
+    png_chunk(&mut output, b"IDAT", &zlib_stored(&scanlines));
+    png_chunk(&mut output, b"IEND", &[]);
+
+    output
+}
+
+// Codes of variable width, packed from the least significant bit
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u8,
+}
+
+impl BitWriter {
+    fn push(&mut self, code: u16, width: u8) {
+        self.buffer |= u32::from(code) << self.bits;
+        self.bits += width;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+const MAX_CODE: u16 = 4095;
+
+// GIF flavoured LZW: a clear code first, codes growing up to 12 bits and the
+// table starting over once it's full
+fn lzw(pixels: &[u8], min_width: u8) -> Vec<u8> {
+    let clear: u16 = 1 << min_width;
+    let end = clear + 1;
+    let mut writer = BitWriter {
+        bytes: Vec::new(),
+        buffer: 0,
+        bits: 0,
+    };
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next_code = end + 1;
+    let mut width = min_width + 1;
+
+    writer.push(clear, width);
+    let Some((&first, rest)) = pixels.split_first() else {
+        writer.push(end, width);
+        return writer.finish();
+    };
+
+    let mut prefix = u16::from(first);
+    for &pixel in rest {
+        if let Some(&code) = table.get(&(prefix, pixel)) {
+            prefix = code;
+            continue;
+        }
+
+        writer.push(prefix, width);
+        if next_code < MAX_CODE {
+            table.insert((prefix, pixel), next_code);
+            if next_code >= 1 << width {
+                width += 1;
+            }
+            next_code += 1;
+        } else {
+            writer.push(clear, width);
+            table.clear();
+            next_code = end + 1;
+            width = min_width + 1;
+        }
+        prefix = u16::from(pixel);
+    }
+    writer.push(prefix, width);
+    writer.push(end, width);
+
+    writer.finish()
+}
+
+// Builds an endlessly looping animation one frame at a time
+pub struct GifWriter {
+    output: Vec<u8>,
+    width: usize,
+    height: usize,
+    min_width: u8,
+}
+
+impl GifWriter {
+    pub fn new(width: usize, height: usize, palette: &Palette) -> Self {
+        let bits = palette.bits();
+        let mut output = b"GIF89a".to_vec();
+        output.extend((width as u16).to_le_bytes());
+        output.extend((height as u16).to_le_bytes());
+        // A global table of 2^bits colours, background and aspect ratio unset
+        output.extend([0xF0 | (bits - 1), 0, 0]);
+        for id in 0..1 << bits {
+            output.extend(palette.colours.get(id).unwrap_or(&[0, 0, 0]));
+        }
+        // The Netscape extension, looping forever
+        output.extend([0x21, 0xFF, 0x0B]);
+        output.extend(b"NETSCAPE2.0");
+        output.extend([3, 1, 0, 0, 0]);
+
+        Self {
+            output,
+            width,
+            height,
+            min_width: bits.max(2),
+        }
+    }
+
+    pub fn frame(&mut self, pixels: &[u8], centiseconds: u16) {
+        // Graphic control extension with the delay
+        self.output.extend([0x21, 0xF9, 4, 0]);
+        self.output.extend(centiseconds.to_le_bytes());
+        self.output.extend([0, 0]);
+
+        // Image descriptor covering the whole picture, no local colours
+        self.output.push(0x2C);
+        self.output.extend([0, 0, 0, 0]);
+        self.output.extend((self.width as u16).to_le_bytes());
+        self.output.extend((self.height as u16).to_le_bytes());
+        self.output.push(0);
+
+        self.output.push(self.min_width);
+        for block in lzw(pixels, self.min_width).chunks(255) {
+            self.output.push(block.len() as u8);
+            self.output.extend(block);
+        }
+        self.output.push(0);
+    }
+
+    pub fn finish(mut self) -> Vec<u8> {
+        self.output.push(0x3B);
+        self.output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Reads back what `lzw` wrote, the way a GIF viewer does
+    fn unlzw(bytes: &[u8], min_width: u8) -> Vec<u8> {
+        let clear = 1usize << min_width;
+        let mut bits = bytes
+            .iter()
+            .flat_map(|byte| (0..8).map(move |bit| (byte >> bit) & 1));
+        let mut read = |width: u8| -> usize {
+            (0..width).fold(0, |code, bit| {
+                code | (usize::from(bits.next().unwrap()) << bit)
+            })
+        };
+
+        let mut output = Vec::new();
+        let mut table: Vec<Vec<u8>> = Vec::new();
+        let mut width = min_width + 1;
+        let mut previous: Option<Vec<u8>> = None;
+        loop {
+            let code = read(width);
+            if code == clear {
+                table = (0..clear).map(|colour| vec![colour as u8]).collect();
+                table.extend([Vec::new(), Vec::new()]);
+                width = min_width + 1;
+                previous = None;
+                continue;
+            }
+            if code == clear + 1 {
+                return output;
+            }
+
+            let entry = match (table.get(code), &previous) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(previous)) => [&previous[..], &previous[..1]].concat(),
+                (None, None) => panic!("Code {} out of nowhere", code),
+            };
+            output.extend(&entry);
+            if let Some(previous) = previous {
+                table.push([&previous[..], &entry[..1]].concat());
+                if table.len() == 1 << width && width < 12 {
+                    width += 1;
+                }
+            }
+            previous = Some(entry);
+        }
+    }
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+        assert_eq!(
+            crc32(b"The quick brown fox jumps over the lazy dog"),
+            0x414F_A339
+        );
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+
+    #[test]
+    fn stored_blocks() {
+        let data: Vec<u8> = (0..70_000u32).map(|i| (i % 251) as u8).collect();
+        let zlib = zlib_stored(&data);
+
+        // Two blocks of 65535 and 4465 bytes, only the second one final
+        assert_eq!(zlib.len(), 2 + 5 + 65535 + 5 + 4465 + 4);
+        assert_eq!(zlib[2..7], [0, 0xFF, 0xFF, 0, 0]);
+        assert_eq!(zlib[65542..65547], [1, 0x71, 0x11, 0x8E, 0xEE]);
+        assert_eq!(zlib_stored(&[]), [0x78, 1, 1, 0, 0, 0xFF, 0xFF, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn png_layout() {
+        let palette = Palette::new(&[[0, 0, 0], [255, 128, 0]]).unwrap();
+        let png = encode_png(3, 2, &[0, 1, 0, 1, 1, 1], &palette);
+
+        assert_eq!(
+            png[..8],
+            [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n']
+        );
+        assert_eq!(png[12..16], *b"IHDR");
+        assert_eq!(png[16..29], [0, 0, 0, 3, 0, 0, 0, 2, 8, 3, 0, 0, 0]);
+        assert_eq!(png[37..41], *b"PLTE");
+        assert_eq!(png[41..47], [0, 0, 0, 255, 128, 0]);
+        assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]));
+
+        // The two rows, each after its filter byte
+        let idat = &png[59..png.len() - 16];
+        assert_eq!(idat[7..15], [0, 0, 1, 0, 0, 1, 1, 1]);
+    }
+
+    #[test]
+    fn lzw_round_trip() {
+        let mut rng = crate::aoc_lib::random::Xorshift::new(0x1F);
+        for min_width in [2, 3, 8] {
+            for length in [0, 1, 2, 100, 5000, 40_000] {
+                let colours = 1u64 << min_width;
+                let pixels: Vec<u8> = (0..length)
+                    .map(|i| match i % 3 {
+                        0 => rng.below(colours) as u8,
+                        _ => 1,
+                    })
+                    .collect();
+
+                assert_eq!(
+                    unlzw(&lzw(&pixels, min_width), min_width),
+                    pixels,
+                    "{} pixels of {} bits",
+                    length,
+                    min_width
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn gif_layout() {
+        let palette = Palette::new(&[[1, 2, 3], [4, 5, 6], [7, 8, 9]]).unwrap();
+        let mut gif = GifWriter::new(2, 2, &palette);
+        gif.frame(&[0, 1, 2, 1], 25);
+        let gif = gif.finish();
+
+        assert_eq!(gif[..6], *b"GIF89a");
+        assert_eq!(gif[6..13], [2, 0, 2, 0, 0xF1, 0, 0]);
+        assert_eq!(gif[13..25], [1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 0, 0]);
+        // Graphic control with 25 hundredths, then the image descriptor
+        assert_eq!(gif[44..52], [0x21, 0xF9, 4, 0, 25, 0, 0, 0]);
+        assert_eq!(gif[52], 0x2C);
+        assert_eq!(gif[62], 2);
+        assert_eq!(gif.last(), Some(&0x3B));
+
+        let length = usize::from(gif[63]);
+        assert_eq!(unlzw(&gif[64..64 + length], 2), [0, 1, 2, 1]);
+    }
+
+    #[test]
+    fn palettes() {
+        assert!(Palette::new(&[]).is_err());
+        assert!(Palette::new(&[[0, 0, 0]; 257]).is_err());
+        assert_eq!(Palette::new(&[[0, 0, 0]; 2]).unwrap().bits(), 1);
+        assert_eq!(Palette::new(&[[0, 0, 0]; 5]).unwrap().bits(), 3);
+        assert_eq!(Palette::new(&[[0, 0, 0]; 256]).unwrap().bits(), 8);
+    }
+
+    #[test]
+    fn sparse() {
+        let cells: HashMap<(i64, i64), char> = [((-1, 3), 'a'), ((1, 5), 'b')].into();
+        let grid = from_sparse(&cells, '.');
+
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert_eq!((grid[(0, 0)], grid[(2, 2)], grid[(1, 1)]), ('a', 'b', '.'));
+        assert_eq!(from_sparse(&HashMap::new(), '.').width(), 0);
+    }
+
+    #[test]
+    fn export() {
+        let directory = std::env::temp_dir().join("aoc_images");
+        let palette = Palette::new(&[[0, 0, 0], [255, 255, 255]]).unwrap();
+        let exporter = Exporter::new(2022, 14, palette)
+            .scale(3)
+            .delay(5)
+            .directory(&directory);
+        let frames: Vec<Grid<bool>> = (0..4)
+            .map(|lit| {
+                let mut grid = Grid::filled(4, 2, false);
+                grid[(lit / 4, lit % 4)] = true;
+                grid
+            })
+            .collect();
+        let paint = |lit: &bool| u8::from(*lit);
+
+        let png = exporter.png("lit.png", &frames[0], paint).unwrap();
+        let bytes = fs::read(&png).unwrap();
+        assert_eq!(bytes[16..24], [0, 0, 0, 12, 0, 0, 0, 6]);
+        let gif = exporter.gif("lit.gif", frames.clone(), paint).unwrap();
+        let bytes = fs::read(&gif).unwrap();
+        let controls = bytes.windows(3).filter(|w| *w == [0x21, 0xF9, 4]).count();
+        assert_eq!(controls, 4);
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(
+            exporter.png("wrong.png", &frames[0], |_| 2),
+            Err(String::from("Colour 2 is not in the palette"))
+        );
+        let mut uneven = frames;
+        uneven.push(Grid::filled(1, 1, false));
+        assert_eq!(
+            exporter.gif("wrong.gif", uneven, paint),
+            Err(String::from("Frame 5 is 1x1, the first one 4x2"))
+        );
+        assert!(exporter.gif("empty.gif", Vec::new(), paint).is_err());
+        assert_eq!(
+            Exporter::new(2021, 9, Palette::new(&[[0; 3]]).unwrap()).directory,
+            Path::new("target/viz/2021/9")
+        );
+    }
+}
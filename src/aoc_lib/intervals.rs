@@ -0,0 +1,131 @@
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+// Union of inclusive integer ranges, kept sorted and merged so that touching
+// or overlapping ranges become a single one
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct IntervalSet {
+    ranges: Vec<RangeInclusive<i64>>,
+}
+
+impl IntervalSet {
+    pub fn new() -> IntervalSet {
+        IntervalSet::default()
+    }
+
+    pub fn insert(&mut self, range: RangeInclusive<i64>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let (mut start, mut end) = range.into_inner();
+        // Everything that overlaps or touches the new range gets swallowed
+        let first = self.ranges.partition_point(|r| *r.end() < start - 1);
+        let last = self.ranges.partition_point(|r| *r.start() <= end + 1);
+        if first < last {
+            start = start.min(*self.ranges[first].start());
+            end = end.max(*self.ranges[last - 1].end());
+        }
+        self.ranges.splice(first..last, [start..=end]);
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        let index = self.ranges.partition_point(|r| *r.end() < value);
+        self.ranges
+            .get(index)
+            .is_some_and(|range| range.contains(&value))
+    }
+
+    // How many integers are in the set
+    pub fn count(&self) -> u64 {
+        self.ranges
+            .iter()
+            .map(|range| range.end().abs_diff(*range.start()) + 1)
+            .sum()
+    }
+
+    pub fn ranges(&self) -> &[RangeInclusive<i64>] {
+        &self.ranges
+    }
+}
+
+impl FromIterator<RangeInclusive<i64>> for IntervalSet {
+    fn from_iter<T: IntoIterator<Item = RangeInclusive<i64>>>(iter: T) -> Self {
+        let mut set = IntervalSet::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aoc_lib::random::for_all;
+
+    #[test]
+    fn merging() {
+        let set: IntervalSet = [10..=12, 0..=2, 4..=5, 3..=3, 11..=15]
+            .into_iter()
+            .collect();
+
+        assert_eq!(set.ranges(), &[0..=5, 10..=15]);
+        assert_eq!(set.count(), 12);
+        assert!(set.contains(5));
+        assert!(!set.contains(7));
+        assert!(!set.contains(16));
+    }
+
+    #[test]
+    fn swallowing() {
+        let mut set: IntervalSet = [0..=1, 5..=6, 9..=9, -3..=-2].into_iter().collect();
+        set.insert(1..=8);
+
+        assert_eq!(set.ranges(), &[-3..=-2, 0..=9]);
+        assert_eq!(set.count(), 12);
+
+        // Filling the gap joins the two sides
+        set.insert(-1..=-1);
+        assert_eq!(set.ranges(), &[-3..=9]);
+    }
+
+    #[test]
+    fn invariants() {
+        for_all(
+            0x1e7,
+            500,
+            |rng| {
+                let amount = rng.below(8) as usize;
+                (0..amount)
+                    .map(|_| {
+                        // Some reversed ranges, which are empty
+                        let start = rng.range(-20..=20);
+                        start..=start + rng.range(-2..=6)
+                    })
+                    .collect::<Vec<RangeInclusive<i64>>>()
+            },
+            |ranges| {
+                let set: IntervalSet = ranges.iter().cloned().collect();
+                for pair in set.ranges().windows(2) {
+                    if *pair[1].start() <= *pair[0].end() + 1 {
+                        return Err(format!("{:?} should be merged", pair));
+                    }
+                }
+                if set.ranges().iter().any(|range| range.is_empty()) {
+                    return Err(String::from("Empty range kept"));
+                }
+                let members: Vec<i64> = (-30..=30)
+                    .filter(|value| ranges.iter().any(|range| range.contains(value)))
+                    .collect();
+                if (-30..=30).any(|value| set.contains(value) != members.contains(&value)) {
+                    return Err(format!("Members should be {:?}", members));
+                }
+                match set.count() == members.len() as u64 {
+                    true => Ok(()),
+                    false => Err(format!("Counted {} for {:?}", set.count(), members)),
+                }
+            },
+        );
+    }
+}
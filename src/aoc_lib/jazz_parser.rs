@@ -1,32 +1,82 @@
+#![warn(missing_docs)]
+
 use nom::character::complete::{char, digit1};
-use nom::combinator::{map, opt};
+use nom::combinator::{map_res, opt, recognize};
 use nom::sequence::pair;
 use nom::IResult;
 
-pub fn _i64(input: &str) -> IResult<&str, i64> {
-    map(
-        pair(opt(char('-')), digit1),
-        |(sign, value): (Option<char>, &str)| {
-            let sign_mul = if sign.is_some() { -1 } else { 1 };
-            value.parse::<i64>().unwrap() * sign_mul
-        },
-    )(input)
+// Numbers too big for their type are errors, not panics
+
+/// A number with an optional minus sign
+///
+/// ```
+/// use advent_of_code::aoc_lib::jazz_parser;
+///
+/// assert_eq!(jazz_parser::i64("-42 degrees"), Ok((" degrees", -42)));
+/// assert!(jazz_parser::i64("99999999999999999999").is_err());
+/// ```
+pub fn i64(input: &str) -> IResult<&str, i64> {
+    map_res(recognize(pair(opt(char('-')), digit1)), str::parse)(input)
 }
 
+/// A number with an optional minus sign
+///
+/// ```
+/// use advent_of_code::aoc_lib::jazz_parser;
+///
+/// assert_eq!(jazz_parser::i32("7,-3"), Ok((",-3", 7)));
+/// assert!(jazz_parser::i32("3000000000").is_err());
+/// ```
 pub fn i32(input: &str) -> IResult<&str, i32> {
-    map(
-        pair(opt(char('-')), digit1),
-        |(sign, value): (Option<char>, &str)| {
-            let sign_mul = if sign.is_some() { -1 } else { 1 };
-            value.parse::<i32>().unwrap() * sign_mul
-        },
-    )(input)
+    map_res(recognize(pair(opt(char('-')), digit1)), str::parse)(input)
 }
 
+/// A number without a sign
+///
+/// ```
+/// use advent_of_code::aoc_lib::jazz_parser;
+///
+/// assert_eq!(jazz_parser::u64("17 apples"), Ok((" apples", 17)));
+/// assert!(jazz_parser::u64("-1").is_err());
+/// ```
 pub fn u64(input: &str) -> IResult<&str, u64> {
-    map(digit1, |s: &str| s.parse().unwrap())(input)
+    map_res(digit1, str::parse)(input)
 }
 
+/// A number without a sign, usually a count or an index. Like the others it
+/// goes inside bigger nom parsers.
+///
+/// ```
+/// use advent_of_code::aoc_lib::jazz_parser;
+/// use nom::bytes::complete::tag;
+/// use nom::multi::separated_list1;
+///
+/// let list = separated_list1(tag(","), jazz_parser::usize)("3,4,5");
+/// assert_eq!(list, Ok(("", vec![3, 4, 5])));
+/// ```
 pub fn usize(input: &str) -> IResult<&str, usize> {
-    map(digit1, |s: &str| s.parse().unwrap())(input)
+    map_res(digit1, str::parse)(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aoc_lib::random::fuzz;
+
+    #[test]
+    fn limits() {
+        assert_eq!(i64("-9223372036854775808,"), Ok((",", i64::MIN)));
+        assert!(i64("9223372036854775808").is_err());
+        assert_eq!(i32("-12x"), Ok(("x", -12)));
+        assert!(i32("-").is_err());
+        assert!(u64("-1").is_err());
+        assert!(usize("99999999999999999999999").is_err());
+    }
+
+    #[test]
+    fn random_text() {
+        fuzz(0x9a55, 2000, "-0123456789", |input| {
+            let _ = (i64(input), i32(input), u64(input), usize(input));
+        });
+    }
 }
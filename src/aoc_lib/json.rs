@@ -0,0 +1,143 @@
+use std::fmt;
+
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, multispace0};
+use nom::combinator::{all_consuming, map, value};
+use nom::multi::separated_list0;
+use nom::number::complete::double;
+use nom::sequence::{delimited, separated_pair};
+use nom::IResult;
+
+use crate::aoc_lib::trace::{json_string, quoted};
+
+// The whole of JSON, for the requests of the --stdio mode. The keys of an
+// object keep their order, and a key given twice is found first.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Text(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+fn space<'a>(c: char) -> impl FnMut(&'a str) -> IResult<&'a str, char> {
+    delimited(multispace0, char(c), multispace0)
+}
+
+fn json(input: &str) -> IResult<&str, Json> {
+    alt((
+        map(json_string, Json::Text),
+        value(Json::Null, tag("null")),
+        value(Json::Bool(true), tag("true")),
+        value(Json::Bool(false), tag("false")),
+        map(double, Json::Number),
+        map(
+            delimited(space('['), separated_list0(space(','), json), space(']')),
+            Json::Array,
+        ),
+        map(
+            delimited(
+                space('{'),
+                separated_list0(space(','), separated_pair(json_string, space(':'), json)),
+                space('}'),
+            ),
+            Json::Object,
+        ),
+    ))(input)
+}
+
+impl Json {
+    pub fn parse(text: &str) -> Result<Json, String> {
+        all_consuming(delimited(multispace0, json, multispace0))(text)
+            .map(|(_, parsed)| parsed)
+            .map_err(|_| String::from("Not JSON"))
+    }
+
+    // The value at `key`, if this is an object that has it
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    // Whole numbers that aren't negative, like the years, days and counts
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Json::Number(number) if *number >= 0.0 && number.fract() == 0.0 => Some(*number as u64),
+            _ => None,
+        }
+    }
+}
+
+// Compact, whole numbers without a fraction
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(flag) => write!(f, "{}", flag),
+            Json::Number(number) if !number.is_finite() => write!(f, "null"),
+            Json::Number(number) => write!(f, "{}", number),
+            Json::Text(text) => write!(f, "{}", quoted(text)),
+            Json::Array(values) => {
+                write!(f, "[")?;
+                for (index, value) in values.iter().enumerate() {
+                    let comma = if index > 0 { "," } else { "" };
+                    write!(f, "{}{}", comma, value)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (index, (name, value)) in fields.iter().enumerate() {
+                    let comma = if index > 0 { "," } else { "" };
+                    write!(f, "{}{}:{}", comma, quoted(name), value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let text = r#" {"id": 3, "params": {"input": "1\n2", "runs": [1, 2.5, -3e2]},
+            "ok": true, "none": null, "empty": {}} "#;
+        let parsed = Json::parse(text).unwrap();
+
+        assert_eq!(parsed.get("id").and_then(Json::as_u64), Some(3));
+        let params = parsed.get("params").unwrap();
+        assert_eq!(params.get("input").and_then(Json::as_str), Some("1\n2"));
+        assert_eq!(params.get("runs").and_then(Json::as_u64), None);
+        assert_eq!(parsed.get("missing"), None);
+        assert_eq!(
+            parsed.to_string(),
+            r#"{"id":3,"params":{"input":"1\n2","runs":[1,2.5,-300]},"ok":true,"none":null,"empty":{}}"#
+        );
+        assert_eq!(Json::parse(&parsed.to_string()), Ok(parsed));
+    }
+
+    #[test]
+    fn not_json() {
+        for text in ["", "{", r#"{"a" 1}"#, "[1,]", "nul", r#"{"a": 1} x"#] {
+            assert!(Json::parse(text).is_err(), "{}", text);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use std::fmt::Write;
+
+// Per-round shift amounts and the integer parts of the sines, from RFC 1321
+const SHIFTS: [u32; 64] = [
+    7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9,
+    14, 20, 5, 9, 14, 20, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 6, 10, 15,
+    21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+];
+
+const SINES: [u32; 64] = [
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+];
+
+fn compress(state: &mut [u32; 4], block: &[u8]) {
+    let mut words = [0u32; 16];
+    for (word, bytes) in words.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    }
+    let [mut a, mut b, mut c, mut d] = *state;
+
+    for round in 0..64 {
+        let (mixed, word) = match round / 16 {
+            0 => ((b & c) | (!b & d), round),
+            1 => ((d & b) | (!d & c), (5 * round + 1) % 16),
+            2 => (b ^ c ^ d, (3 * round + 5) % 16),
+            _ => (c ^ (b | !d), (7 * round) % 16),
+        };
+        let rotated = a
+            .wrapping_add(mixed)
+            .wrapping_add(SINES[round])
+            .wrapping_add(words[word])
+            .rotate_left(SHIFTS[round]);
+        (a, b, c, d) = (d, b.wrapping_add(rotated), b, c);
+    }
+
+    for (value, added) in state.iter_mut().zip([a, b, c, d]) {
+        *value = value.wrapping_add(added);
+    }
+}
+
+pub fn digest(data: &[u8]) -> [u8; 16] {
+    let mut state: [u32; 4] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+
+    // A single 1 bit, zeros up to 56 bytes into the last block and the length
+    // in bits
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64).wrapping_mul(8)).to_le_bytes());
+
+    for block in message.chunks_exact(64) {
+        compress(&mut state, block);
+    }
+
+    let mut output = [0u8; 16];
+    for (bytes, value) in output.chunks_exact_mut(4).zip(state) {
+        bytes.copy_from_slice(&value.to_le_bytes());
+    }
+
+    output
+}
+
+pub fn hex(digest: &[u8; 16]) -> String {
+    digest.iter().fold(String::new(), |mut text, byte| {
+        write!(text, "{:02x}", byte).unwrap();
+        text
+    })
+}
+
+// How many hex digits at the start of the digest are zeros
+pub fn leading_zero_digits(digest: &[u8; 16]) -> usize {
+    let mut zeros = 0;
+    for byte in digest {
+        if *byte == 0 {
+            zeros += 2;
+            continue;
+        }
+        if *byte < 0x10 {
+            zeros += 1;
+        }
+        break;
+    }
+
+    zeros
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rfc_vectors() {
+        let vectors = [
+            ("", "d41d8cd98f00b204e9800998ecf8427e"),
+            ("a", "0cc175b9c0f1b6a831c399e269772661"),
+            ("abc", "900150983cd24fb0d6963f7d28e17f72"),
+            ("message digest", "f96b697d7cb7938d525a2f31aaf161d0"),
+            (
+                "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+                "d174ab98d277d9f5a5611c2c9f419d9f",
+            ),
+            (
+                "12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+                "57edf4a22be3c955ac49da2e2107b67a",
+            ),
+        ];
+
+        for (text, expected) in vectors {
+            assert_eq!(hex(&digest(text.as_bytes())), expected, "MD5(\"{}\")", text);
+        }
+    }
+
+    #[test]
+    fn zero_digits() {
+        let mut value = [0xffu8; 16];
+        assert_eq!(leading_zero_digits(&value), 0);
+        value[0] = 0x0f;
+        assert_eq!(leading_zero_digits(&value), 1);
+        value[0] = 0;
+        value[1] = 0;
+        value[2] = 0x01;
+        assert_eq!(leading_zero_digits(&value), 5);
+        assert_eq!(leading_zero_digits(&[0; 16]), 32);
+    }
+}
@@ -0,0 +1,78 @@
+use std::fmt::Write;
+
+use crate::aoc_lib::cuboid::Cuboid;
+
+// Corners of a unit box, bit 0 is x, bit 1 is y and bit 2 is z
+const CORNERS: [[usize; 3]; 8] = [
+    [0, 0, 0],
+    [1, 0, 0],
+    [0, 1, 0],
+    [1, 1, 0],
+    [0, 0, 1],
+    [1, 0, 1],
+    [0, 1, 1],
+    [1, 1, 1],
+];
+
+// Counter-clockwise seen from outside, so the normals point away from the box
+const FACES: [[usize; 4]; 6] = [
+    [0, 2, 3, 1],
+    [4, 5, 7, 6],
+    [0, 1, 5, 4],
+    [2, 6, 7, 3],
+    [0, 4, 6, 2],
+    [1, 3, 7, 5],
+];
+
+// Wavefront OBJ with one closed box per cuboid. Faces shared by touching
+// cuboids are kept, viewers don't mind and it keeps every box independent.
+pub fn cuboids_to_obj(cuboids: &[Cuboid]) -> String {
+    let boxes: Vec<&Cuboid> = cuboids.iter().filter(|c| !c.is_empty()).collect();
+
+    let mut output = String::new();
+    writeln!(output, "# {} cuboids", boxes.len()).unwrap();
+    for (id, cuboid) in boxes.iter().enumerate() {
+        let corners = [cuboid.bottom_left(), cuboid.top_right()];
+        for corner in CORNERS {
+            let [x, y, z] = [0, 1, 2].map(|axis| corners[corner[axis]][axis]);
+            writeln!(output, "v {} {} {}", x, y, z).unwrap();
+        }
+
+        // OBJ indices start from 1
+        let offset = id * CORNERS.len() + 1;
+        for face in FACES {
+            let [a, b, c, d] = face.map(|vertex| vertex + offset);
+            writeln!(output, "f {} {} {} {}", a, b, c, d).unwrap();
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use nalgebra::Point3;
+
+    #[test]
+    fn single_box() {
+        let obj = cuboids_to_obj(&[Cuboid::from_inclusive([0..=1, -2..=0, 5..=5])]);
+        let lines: Vec<&str> = obj.lines().collect();
+
+        assert_eq!(lines.len(), 1 + 8 + 6);
+        assert_eq!(lines[1], "v 0 -2 5");
+        assert_eq!(lines[8], "v 2 1 6");
+        assert_eq!(lines[9], "f 1 3 4 2");
+    }
+
+    #[test]
+    fn indices_continue() {
+        let empty = Cuboid::new(Point3::new(3, 3, 3), Point3::new(3, 4, 4));
+        let cube = Cuboid::from_inclusive([0..=0, 0..=0, 0..=0]);
+        let obj = cuboids_to_obj(&[cube.clone(), empty, cube]);
+
+        assert_eq!(obj.lines().filter(|l| l.starts_with("v ")).count(), 16);
+        assert_eq!(obj.lines().last(), Some("f 10 12 16 14"));
+    }
+}
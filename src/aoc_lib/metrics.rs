@@ -0,0 +1,181 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+// The upper bounds of the buckets of the solve times in seconds, from the days
+// done in a blink to the brute forces
+const BUCKETS: [f64; 7] = [0.0001, 0.001, 0.01, 0.1, 1.0, 10.0, 60.0];
+
+#[derive(Default)]
+struct Part {
+    solves: u64,
+    // By the status the error was answered with
+    errors: BTreeMap<u16, u64>,
+    // How many solves took up to each bound and more than the one before, the
+    // ones past the last bound are only in the count
+    buckets: [u64; BUCKETS.len()],
+    seconds: f64,
+}
+
+#[derive(Default)]
+struct Counts {
+    // By year, day and part, only the ones that exist
+    parts: BTreeMap<(u16, u8, u8), Part>,
+    // Every request by its status, whatever it asked for
+    requests: BTreeMap<u16, u64>,
+}
+
+// What serve answered since it started, for GET /metrics in the text format of
+// Prometheus
+#[derive(Default)]
+pub struct Metrics {
+    counts: Mutex<Counts>,
+}
+
+impl Metrics {
+    pub fn request(&self, status: u16) {
+        let mut counts = self.counts.lock().unwrap();
+        *counts.requests.entry(status).or_default() += 1;
+    }
+
+    // A request for a part that exists, and how it went
+    pub fn solve(&self, (year, day, part): (u16, u8, u8), status: u16, took: Duration) {
+        let mut counts = self.counts.lock().unwrap();
+        let part = counts.parts.entry((year, day, part)).or_default();
+        if status != 200 {
+            *part.errors.entry(status).or_default() += 1;
+            return;
+        }
+
+        let seconds = took.as_secs_f64();
+        part.solves += 1;
+        part.seconds += seconds;
+        if let Some(bucket) = BUCKETS.iter().position(|bound| seconds <= *bound) {
+            part.buckets[bucket] += 1;
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let counts = self.counts.lock().unwrap();
+
+        let requests: Vec<String> = counts
+            .requests
+            .iter()
+            .map(|(status, count)| format!("aoc_requests_total{{status=\"{}\"}} {}", status, count))
+            .collect();
+
+        let mut solves = Vec::new();
+        let mut errors = Vec::new();
+        let mut durations = Vec::new();
+        for ((year, day, part), counted) in &counts.parts {
+            let labels = format!("year=\"{}\",day=\"{}\",part=\"{}\"", year, day, part);
+            solves.push(format!("aoc_solves_total{{{}}} {}", labels, counted.solves));
+            for (status, count) in &counted.errors {
+                errors.push(format!(
+                    "aoc_errors_total{{{},status=\"{}\"}} {}",
+                    labels, status, count
+                ));
+            }
+
+            // Each bucket counts everything up to its bound
+            let mut below = 0;
+            for (bound, count) in BUCKETS.iter().zip(counted.buckets) {
+                below += count;
+                durations.push(format!(
+                    "aoc_solve_duration_seconds_bucket{{{},le=\"{}\"}} {}",
+                    labels, bound, below
+                ));
+            }
+            durations.push(format!(
+                "aoc_solve_duration_seconds_bucket{{{},le=\"+Inf\"}} {}",
+                labels, counted.solves
+            ));
+            durations.push(format!(
+                "aoc_solve_duration_seconds_sum{{{}}} {}",
+                labels, counted.seconds
+            ));
+            durations.push(format!(
+                "aoc_solve_duration_seconds_count{{{}}} {}",
+                labels, counted.solves
+            ));
+        }
+
+        let mut text = String::new();
+        family(
+            &mut text,
+            "aoc_requests_total",
+            "counter",
+            "Requests answered, by HTTP status.",
+            &requests,
+        );
+        family(
+            &mut text,
+            "aoc_solves_total",
+            "counter",
+            "Parts solved, by year, day and part.",
+            &solves,
+        );
+        family(
+            &mut text,
+            "aoc_errors_total",
+            "counter",
+            "Parts that got no answer, by year, day, part and HTTP status.",
+            &errors,
+        );
+        family(
+            &mut text,
+            "aoc_solve_duration_seconds",
+            "histogram",
+            "How long the parts that got an answer took.",
+            &durations,
+        );
+        text
+    }
+}
+
+// The lines of a metric, which have to come together after its HELP and TYPE
+fn family(text: &mut String, name: &str, kind: &str, help: &str, lines: &[String]) {
+    let _ = writeln!(text, "# HELP {} {}", name, help);
+    let _ = writeln!(text, "# TYPE {} {}", name, kind);
+    for line in lines {
+        let _ = writeln!(text, "{}", line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders() {
+        let metrics = Metrics::default();
+        metrics.request(200);
+        metrics.request(200);
+        metrics.request(404);
+        metrics.solve((2022, 1, 2), 200, Duration::from_micros(50));
+        metrics.solve((2022, 1, 2), 200, Duration::from_millis(5));
+        metrics.solve((2022, 1, 2), 200, Duration::from_secs(100));
+        metrics.solve((2022, 21, 1), 500, Duration::ZERO);
+
+        let text = metrics.render();
+        let lines: Vec<&str> = text.lines().collect();
+        for expected in [
+            "# TYPE aoc_requests_total counter",
+            r#"aoc_requests_total{status="200"} 2"#,
+            r#"aoc_requests_total{status="404"} 1"#,
+            r#"aoc_solves_total{year="2022",day="1",part="2"} 3"#,
+            r#"aoc_solves_total{year="2022",day="21",part="1"} 0"#,
+            r#"aoc_errors_total{year="2022",day="21",part="1",status="500"} 1"#,
+            "# TYPE aoc_solve_duration_seconds histogram",
+            r#"aoc_solve_duration_seconds_bucket{year="2022",day="1",part="2",le="0.0001"} 1"#,
+            r#"aoc_solve_duration_seconds_bucket{year="2022",day="1",part="2",le="0.001"} 1"#,
+            r#"aoc_solve_duration_seconds_bucket{year="2022",day="1",part="2",le="0.01"} 2"#,
+            r#"aoc_solve_duration_seconds_bucket{year="2022",day="1",part="2",le="60"} 2"#,
+            r#"aoc_solve_duration_seconds_bucket{year="2022",day="1",part="2",le="+Inf"} 3"#,
+            r#"aoc_solve_duration_seconds_count{year="2022",day="1",part="2"} 3"#,
+        ] {
+            assert!(lines.contains(&expected), "{} not in\n{}", expected, text);
+        }
+    }
+}
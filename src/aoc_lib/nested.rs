@@ -0,0 +1,94 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::slice;
+
+use itertools::Itertools;
+use nom::branch::alt;
+use nom::character::complete::{char, u64};
+use nom::combinator::{all_consuming, map};
+use nom::multi::separated_list0;
+use nom::sequence::delimited;
+use nom::IResult;
+
+// Arbitrarily nested lists of numbers, written like "[1,[2,[]],3]"
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Value {
+    Int(u64),
+    List(Vec<Value>),
+}
+
+// Numbers compare as numbers and lists element by element. When a number is
+// compared to a list it's treated as a list holding only that number.
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Value::Int(left), Value::Int(right)) => left.cmp(right),
+            (Value::List(left), Value::List(right)) => left.cmp(right),
+            (Value::Int(_), Value::List(right)) => slice::from_ref(self).cmp(right.as_slice()),
+            (Value::List(left), Value::Int(_)) => left.as_slice().cmp(slice::from_ref(other)),
+        }
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Int(value) => write!(f, "{}", value),
+            Value::List(values) => write!(f, "[{}]", values.iter().join(",")),
+        }
+    }
+}
+
+pub fn value(input: &str) -> IResult<&str, Value> {
+    alt((
+        map(u64, Value::Int),
+        map(
+            delimited(char('['), separated_list0(char(','), value), char(']')),
+            Value::List,
+        ),
+    ))(input)
+}
+
+// A whole line holding a single value
+pub fn parse(input: &str) -> Result<Value, String> {
+    all_consuming(value)(input.trim())
+        .map(|(_, value)| value)
+        .map_err(|_| format!("Invalid nested value: \"{}\"", input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for text in ["[]", "[[[]]]", "7", "[1,[2,[3,[4,[5,6,7]]]],8,9]"] {
+            assert_eq!(parse(text).unwrap().to_string(), text);
+        }
+        assert!(parse("[1,2").is_err());
+        assert!(parse("[1,,2]").is_err());
+        assert!(parse("[1] [2]").is_err());
+    }
+
+    #[test]
+    fn ordering() {
+        let ordered = |left: &str, right: &str| parse(left).unwrap() < parse(right).unwrap();
+
+        assert!(ordered("[1,1,3,1,1]", "[1,1,5,1,1]"));
+        assert!(ordered("[[1],[2,3,4]]", "[[1],4]"));
+        assert!(!ordered("[9]", "[[8,7,6]]"));
+        assert!(ordered("[[4,4],4,4]", "[[4,4],4,4,4]"));
+        assert!(ordered("[]", "[3]"));
+        assert!(!ordered("[[[]]]", "[[]]"));
+        assert_eq!(
+            parse("[[2]]").unwrap().cmp(&parse("2").unwrap()),
+            Ordering::Equal
+        );
+    }
+}
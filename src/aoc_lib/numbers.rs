@@ -0,0 +1,146 @@
+#![warn(missing_docs)]
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Positional numbers where the digits are centred on zero, like balanced
+/// ternary. The digits are given from the lowest value up, so with "=-012" the
+/// base is 5 and '=' is worth -2. Only odd bases can be balanced.
+///
+/// ```
+/// use advent_of_code::aoc_lib::numbers::BalancedBase;
+///
+/// let snafu = BalancedBase::new("=-012");
+/// let total: i64 = ["1=-0-2", "12111", "2=0="].iter().map(|n| snafu.decode(n).unwrap()).sum();
+/// assert_eq!((total, snafu.encode(total).as_str()), (2851, "10=-01"));
+/// ```
+pub struct BalancedBase<'a> {
+    digits: &'a str,
+}
+
+impl<'a> BalancedBase<'a> {
+    /// Panics with an even number of digits
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::numbers::BalancedBase;
+    ///
+    /// let ternary = BalancedBase::new("-0+");
+    /// assert_eq!(ternary.decode("+-"), Ok(2));
+    /// ```
+    ///
+    /// ```should_panic
+    /// use advent_of_code::aoc_lib::numbers::BalancedBase;
+    ///
+    /// BalancedBase::new("-0+#");
+    /// ```
+    pub fn new(digits: &'a str) -> BalancedBase<'a> {
+        assert!(
+            digits.chars().count() % 2 == 1,
+            "A balanced base needs an odd number of digits"
+        );
+        BalancedBase { digits }
+    }
+
+    fn base(&self) -> i64 {
+        self.digits.chars().count() as i64
+    }
+
+    fn offset(&self) -> i64 {
+        self.base() / 2
+    }
+
+    /// The value of `text`, an error for unknown digits and for values that
+    /// don't fit in an i64
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::numbers::BalancedBase;
+    ///
+    /// let snafu = BalancedBase::new("=-012");
+    /// assert_eq!(snafu.decode("1=-0-2"), Ok(1747));
+    /// assert_eq!(snafu.decode("-"), Ok(-1));
+    /// assert!(snafu.decode("1=3").is_err());
+    /// ```
+    pub fn decode(&self, text: &str) -> Result<i64, String> {
+        if text.is_empty() {
+            return Err(String::from("An empty string is not a number"));
+        }
+
+        // A prefix times the base can step out of i64 before the next digit
+        // brings it back, so the sum is kept in i128 until the end
+        let too_big = || format!("\"{}\" doesn't fit in 64 bits", text);
+        let value = text.chars().try_fold(0i128, |value, c| {
+            let digit = self
+                .digits
+                .chars()
+                .position(|d| d == c)
+                .ok_or_else(|| format!("Unknown digit '{}' in \"{}\"", c, text))?
+                as i128
+                - self.offset() as i128;
+            value
+                .checked_mul(self.base() as i128)
+                .and_then(|value| value.checked_add(digit))
+                .ok_or_else(too_big)
+        })?;
+
+        i64::try_from(value).map_err(|_| too_big())
+    }
+
+    /// `value` written with the fewest digits, "0" for zero
+    ///
+    /// ```
+    /// use advent_of_code::aoc_lib::numbers::BalancedBase;
+    ///
+    /// let snafu = BalancedBase::new("=-012");
+    /// assert_eq!(snafu.encode(1747), "1=-0-2");
+    /// assert_eq!(snafu.encode(-3), "-2");
+    /// assert_eq!(snafu.encode(0), "0");
+    /// ```
+    pub fn encode(&self, value: i64) -> String {
+        let digits: Vec<char> = self.digits.chars().collect();
+        let (base, offset) = (self.base() as i128, self.offset() as i128);
+
+        // Shift every digit up by the offset so it can be picked with a plain
+        // remainder, then take the shift back out of what's left. i128 so that
+        // the shift can't overflow near the edges of i64.
+        let mut left = value as i128;
+        let mut encoded = Vec::new();
+        loop {
+            let digit = (left + offset).rem_euclid(base);
+            encoded.push(digits[digit as usize]);
+            left = (left + offset - digit) / base;
+            if left == 0 {
+                break;
+            }
+        }
+
+        encoded.iter().rev().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn balanced_ternary() {
+        let ternary = BalancedBase::new("-0+");
+
+        assert_eq!(ternary.encode(0), "0");
+        assert_eq!(ternary.encode(8), "+0-");
+        assert_eq!(ternary.encode(-8), "-0+");
+        assert_eq!(ternary.decode("+-+"), Ok(7));
+        assert!(ternary.decode("+2").is_err());
+        assert!(ternary.decode("").is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let quinary = BalancedBase::new("=-012");
+
+        for value in (-1000..1000).chain([i64::MIN, i64::MAX]) {
+            assert_eq!(quinary.decode(&quinary.encode(value)), Ok(value));
+        }
+        assert!(quinary.decode("2222222222222222222222222222").is_err());
+    }
+}
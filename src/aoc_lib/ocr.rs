@@ -0,0 +1,112 @@
+use hashbrown::HashMap;
+use itertools::Itertools;
+
+// The capital letters the puzzles draw on their screens. Every letter is four
+// pixels wide and six tall, with an empty column between two letters.
+const HEIGHT: usize = 6;
+const LETTER_WIDTH: usize = 4;
+const GLYPHS: [(char, &str); 17] = [
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('I', ".###..#...#...#...#..###"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Z', "####...#..#..#..#...####"),
+];
+
+// Reads the letters off a screen of `width` pixels per row, stored row by row
+pub fn decode(pixels: &[bool], width: usize) -> Result<String, String> {
+    if width == 0 || pixels.len() != width * HEIGHT {
+        return Err(format!(
+            "Expected {} rows of {} pixels, got {} pixels",
+            HEIGHT,
+            width,
+            pixels.len()
+        ));
+    }
+
+    let glyphs: HashMap<Vec<bool>, char> = GLYPHS
+        .iter()
+        .map(|(letter, pattern)| (pattern.chars().map(|c| c == '#').collect(), *letter))
+        .collect();
+
+    (0..width)
+        .step_by(LETTER_WIDTH + 1)
+        .enumerate()
+        .map(|(id, left)| {
+            let letter = (0..HEIGHT)
+                .flat_map(|row| (left..left + LETTER_WIDTH).map(move |col| (row, col)))
+                .map(|(row, col)| col < width && pixels[row * width + col])
+                .collect_vec();
+            glyphs
+                .get(&letter)
+                .copied()
+                .ok_or_else(|| format!("Letter {} is not one I know", id + 1))
+        })
+        .collect()
+}
+
+// Same as `decode`, for screens drawn with '#' and '.'
+pub fn decode_text(screen: &str) -> Result<String, String> {
+    let rows = screen
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect_vec();
+    let width = rows.first().map_or(0, |row| row.len());
+    if rows.iter().any(|row| row.len() != width) {
+        return Err(String::from("All the rows of the screen must be as wide"));
+    }
+
+    decode(
+        &rows
+            .iter()
+            .flat_map(|row| row.chars())
+            .map(|c| c == '#')
+            .collect_vec(),
+        width,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn banner() {
+        let screen = "###..####.#..#.####.
+                      #..#.#....#.#.....#.
+                      #..#.###..##.....#..
+                      ###..#....#.#...#...
+                      #.#..#....#.#..#....
+                      #..#.####.#..#.####.";
+
+        assert_eq!(decode_text(screen), Ok(String::from("REKZ")));
+    }
+
+    #[test]
+    fn every_letter() {
+        for (letter, pattern) in GLYPHS {
+            let pixels = pattern.chars().map(|c| c == '#').collect_vec();
+            assert_eq!(decode(&pixels, LETTER_WIDTH), Ok(letter.to_string()));
+        }
+    }
+
+    #[test]
+    fn unreadable() {
+        assert!(decode_text("#...\n#...\n#...").is_err());
+        assert!(decode_text("####\n####\n####\n####\n####\n####").is_err());
+        assert!(decode(&[false; 12], 2).is_err());
+    }
+}
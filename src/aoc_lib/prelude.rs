@@ -0,0 +1,20 @@
+// What the days import, in a single `use crate::aoc_lib::prelude::*;`. The
+// modules behind it can move things around as long as the names here stay, and
+// tools using the library can pick up the same line.
+
+// Reporting an answer and registering the day
+#[cfg(feature = "std")]
+pub use crate::aoc_lib::solution::{Solution, SolutionBorrowed};
+#[cfg(feature = "std")]
+pub use crate::aoc_lib::year::aoc_day;
+#[cfg(feature = "std")]
+pub use crate::aoc_lib::{Answer, AocError, Solved};
+
+// The shared algorithms, also there without std
+pub use crate::aoc_lib::arena::{Arena, Id};
+pub use crate::aoc_lib::cycles::{find_cycle, Cycle};
+pub use crate::aoc_lib::geometry::{Point2, Point3, Rotation};
+pub use crate::aoc_lib::grid::{Grid, Position};
+pub use crate::aoc_lib::intervals::IntervalSet;
+pub use crate::aoc_lib::jazz_parser;
+pub use crate::aoc_lib::search::{a_star, bfs, dijkstra};
@@ -0,0 +1,238 @@
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// Where the status line goes, once the runner attached one
+type Screen = Box<dyn Write + Send>;
+
+#[derive(Default)]
+struct Shared {
+    count: AtomicU64,
+    message: Mutex<String>,
+    screen: Mutex<Option<Screen>>,
+    finished: AtomicBool,
+}
+
+// How far a long search got. Clones share the same counter, and ticking it is a
+// single atomic add, so the threads of a parallel search can all tick the same
+// one.
+#[derive(Clone, Default)]
+pub struct Progress {
+    shared: Arc<Shared>,
+}
+
+// The one the runner shows, if it shows one
+static ATTACHED: Mutex<Option<Progress>> = Mutex::new(None);
+
+impl Progress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // What the runner is showing, or a counter nobody looks at. Solvers get it
+    // once before their main loop, not on every tick.
+    pub fn attached() -> Self {
+        ATTACHED.lock().unwrap().clone().unwrap_or_default()
+    }
+
+    pub fn tick(&self) {
+        self.add(1);
+    }
+
+    pub fn add(&self, amount: u64) {
+        self.shared.count.fetch_add(amount, Ordering::Relaxed);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.shared.count.load(Ordering::Relaxed)
+    }
+
+    // Shown after the count, like the best answer found so far
+    pub fn set_message(&self, message: impl Into<String>) {
+        *self.shared.message.lock().unwrap() = message.into();
+    }
+
+    pub fn message(&self) -> String {
+        self.shared.message.lock().unwrap().clone()
+    }
+
+    // Clears the status line for good. Solvers call it before printing their
+    // answer, or the answer would end up after the status.
+    pub fn finish(&self) {
+        self.shared.finished.store(true, Ordering::Relaxed);
+        if let Some(screen) = self.shared.screen.lock().unwrap().as_mut() {
+            // Nobody to show it to is no reason to stop the solver
+            let _ = write!(screen, "{}", CLEAR_LINE).and_then(|_| screen.flush());
+        }
+    }
+
+    // Nothing is drawn once the solver finished
+    fn draw(&self, line: &str) {
+        let mut screen = self.shared.screen.lock().unwrap();
+        if self.shared.finished.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Some(screen) = screen.as_mut() {
+            let _ = write!(screen, "{}{}", CLEAR_LINE, line).and_then(|_| screen.flush());
+        }
+    }
+}
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+const REDRAW: Duration = Duration::from_millis(100);
+
+// Goes back to the start of the line and clears it
+const CLEAR_LINE: &str = "\r\x1b[K";
+
+// The spinner, how many states were explored and how fast
+pub fn status_line(frame: usize, count: u64, elapsed: Duration, message: &str) -> String {
+    let seconds = elapsed.as_secs_f64();
+    let rate = if seconds > 0.0 {
+        (count as f64 / seconds).round() as u64
+    } else {
+        0
+    };
+
+    let mut line = format!(
+        "{} {} explored, {}/s",
+        SPINNER[frame % SPINNER.len()],
+        count,
+        rate
+    );
+    if !message.is_empty() {
+        line.push_str(", ");
+        line.push_str(message);
+    }
+    line
+}
+
+// Redraws the status line of the attached progress until it's dropped, then
+// clears it and detaches the progress. The first line is drawn after a tenth of
+// a second, the quick parts never show one.
+pub struct Spinner {
+    progress: Progress,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+pub fn attach(output: impl Write + Send + 'static) -> Spinner {
+    let progress = Progress::new();
+    *progress.shared.screen.lock().unwrap() = Some(Box::new(output));
+    *ATTACHED.lock().unwrap() = Some(progress.clone());
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let stopped = Arc::clone(&stop);
+    let shown = progress.clone();
+    let thread = thread::spawn(move || {
+        let start = Instant::now();
+        let mut frame = 0;
+        loop {
+            thread::park_timeout(REDRAW);
+            if stopped.load(Ordering::Relaxed) {
+                break;
+            }
+            shown.draw(&status_line(
+                frame,
+                shown.count(),
+                start.elapsed(),
+                &shown.message(),
+            ));
+            frame += 1;
+        }
+    });
+
+    Spinner {
+        progress,
+        stop,
+        thread: Some(thread),
+    }
+}
+
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            let _ = thread.join();
+        }
+        self.progress.finish();
+        *ATTACHED.lock().unwrap() = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_counter() {
+        let progress = Progress::new();
+        let clone = progress.clone();
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..1000 {
+                        clone.tick();
+                    }
+                });
+            }
+        });
+        progress.add(5);
+
+        assert_eq!(progress.count(), 4005);
+        clone.set_message("cheapest so far: 12");
+        assert_eq!(progress.message(), "cheapest so far: 12");
+    }
+
+    #[test]
+    fn status_lines() {
+        assert_eq!(
+            status_line(0, 1500, Duration::from_millis(500), ""),
+            "⠋ 1500 explored, 3000/s"
+        );
+        assert_eq!(
+            status_line(11, 7, Duration::ZERO, "best 44"),
+            "⠙ 7 explored, 0/s, best 44"
+        );
+    }
+
+    // Writes into a buffer the test can still look at
+    #[derive(Clone, Default)]
+    struct Terminal(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Terminal {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn attached_while_shown() {
+        let output = Terminal::default();
+        let spinner = attach(output.clone());
+        let progress = Progress::attached();
+        progress.add(42);
+        progress.set_message("halfway");
+        thread::sleep(REDRAW * 3);
+        progress.finish();
+        let finished = output.0.lock().unwrap().len();
+        thread::sleep(REDRAW * 2);
+        drop(spinner);
+
+        // Detached again, ticks go nowhere
+        assert_eq!(Progress::attached().count(), 0);
+        let shown = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        assert!(shown.contains(" 42 explored, "));
+        assert!(shown.contains(", halfway"));
+        // Nothing drawn after finishing, the spinner only clears the line again
+        assert!(shown[..finished].ends_with(CLEAR_LINE));
+        assert_eq!(&shown[finished..], CLEAR_LINE);
+    }
+}
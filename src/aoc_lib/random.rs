@@ -0,0 +1,176 @@
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+use std::panic::{self, RefUnwindSafe};
+
+// Xorshift generator, plenty for test cases and always the same sequence for
+// the same seed, so a failure can be replayed
+pub struct Xorshift {
+    state: u64,
+}
+
+impl Xorshift {
+    pub fn new(seed: u64) -> Xorshift {
+        // An all zero state would only ever give zeros
+        Xorshift { state: seed.max(1) }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    // Anything in 0..bound, bound must not be zero
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        // Only the whole i64 range is too wide to count
+        let offset = match range.end().abs_diff(*range.start()).checked_add(1) {
+            Some(width) => self.below(width),
+            None => self.next_u64(),
+        };
+        range.start().wrapping_add_unsigned(offset)
+    }
+}
+
+// Checks a property on `cases` generated values, failing with the first case
+// that breaks it and the seed that made it
+pub fn for_all<T: Debug>(
+    seed: u64,
+    cases: usize,
+    mut generate: impl FnMut(&mut Xorshift) -> T,
+    mut property: impl FnMut(&T) -> Result<(), String>,
+) {
+    let mut rng = Xorshift::new(seed);
+    for case in 0..cases {
+        let value = generate(&mut rng);
+        if let Err(error) = property(&value) {
+            panic!(
+                "Case {} of seed {:#x} fails: {}\n{:#?}",
+                case, seed, error, value
+            );
+        }
+    }
+}
+
+// A solver by name, for `cross_check`
+pub type NamedSolver<A> = (&'static str, fn(&str) -> A);
+
+// Differential testing of the solvers of a day: every one of them gets the
+// same generated inputs and they all have to give the same answer
+pub fn cross_check<A: PartialEq + Debug>(
+    seed: u64,
+    cases: usize,
+    generate: impl FnMut(&mut Xorshift) -> String,
+    solvers: &[NamedSolver<A>],
+) {
+    for_all(seed, cases, generate, |input| {
+        let answers: Vec<(&str, A)> = solvers
+            .iter()
+            .map(|(name, solver)| (*name, solver(input)))
+            .collect();
+        match answers.windows(2).all(|pair| pair[0].1 == pair[1].1) {
+            true => Ok(()),
+            false => Err(format!("Solvers disagree: {:?}", answers)),
+        }
+    });
+}
+
+// Text made mostly of what a parser expects, so the cases get past the first
+// few tokens, with arbitrary bytes mixed in
+pub fn text(rng: &mut Xorshift, alphabet: &str, max_length: u64) -> String {
+    let alphabet = alphabet.as_bytes();
+    let bytes: Vec<u8> = (0..rng.below(max_length + 1))
+        .map(|_| match rng.below(8) {
+            0 => rng.below(256) as u8,
+            _ => alphabet[rng.below(alphabet.len() as u64) as usize],
+        })
+        .collect();
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+// Feeds generated text to a parser, which can reject it but must not panic
+pub fn fuzz(seed: u64, cases: usize, alphabet: &str, parse: impl Fn(&str) + RefUnwindSafe) {
+    let mut rng = Xorshift::new(seed);
+    for case in 0..cases {
+        let input = text(&mut rng, alphabet, 200);
+        if panic::catch_unwind(|| parse(&input)).is_err() {
+            panic!("Case {} of seed {:#x} panics on {:?}", case, seed, input);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeatable() {
+        let mut first = Xorshift::new(42);
+        let mut second = Xorshift::new(42);
+        let values: Vec<u64> = (0..10).map(|_| first.next_u64()).collect();
+
+        assert_eq!(
+            values,
+            (0..10).map(|_| second.next_u64()).collect::<Vec<u64>>()
+        );
+        assert_ne!(Xorshift::new(0).next_u64(), 0);
+    }
+
+    #[test]
+    fn bounds() {
+        let mut rng = Xorshift::new(7);
+        for _ in 0..1000 {
+            assert!((-3..=3).contains(&rng.range(-3..=3)));
+            assert!(rng.below(5) < 5);
+        }
+        assert_eq!(rng.range(i64::MIN..=i64::MIN), i64::MIN);
+        assert_eq!(rng.range(4..=4), 4);
+        rng.range(i64::MIN..=i64::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "Case 0 of seed 0x1 fails: odd")]
+    fn reports_failures() {
+        for_all(
+            1,
+            10,
+            |_| 3,
+            |n| match n % 2 {
+                0 => Ok(()),
+                _ => Err(String::from("odd")),
+            },
+        );
+    }
+
+    #[test]
+    fn generated_text() {
+        let mut rng = Xorshift::new(3);
+        let texts: Vec<String> = (0..100).map(|_| text(&mut rng, "ab", 10)).collect();
+
+        assert!(texts.iter().all(|text| text.chars().count() <= 10));
+        assert!(texts.iter().any(|text| text.contains('a')));
+        fuzz(3, 100, "ab", |input| assert!(input.len() < 1000));
+    }
+
+    #[test]
+    #[should_panic(expected = "Case 0 of seed 0x2 panics on")]
+    fn reports_panics() {
+        fuzz(2, 10, "ab", |_| panic!("always"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Solvers disagree: [(\"len\", 2), (\"bytes\", 3)]")]
+    fn disagreeing_solvers() {
+        let solvers: [NamedSolver<usize>; 2] = [
+            ("len", |input| input.chars().count()),
+            ("bytes", |input| input.len()),
+        ];
+        cross_check(5, 10, |_| String::from("ab"), &solvers[..1]);
+        cross_check(5, 10, |_| String::from("aé"), &solvers);
+    }
+}
@@ -0,0 +1,146 @@
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::aoc_lib::clock::Instant;
+use crate::aoc_lib::config::InputKind;
+use crate::aoc_lib::trace::quoted;
+use crate::aoc_lib::{fmt_dur, Answer, AocError, DayFn, Solved};
+use crate::find_part;
+
+// What a solve request gets back: an HTTP status and a JSON object, the same
+// from the server and from the browser. Only the metrics of the server are
+// something else.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    pub fn json(status: u16, body: String) -> Self {
+        Response {
+            status,
+            content_type: "application/json",
+            body,
+        }
+    }
+
+    pub fn error(status: u16, message: &str) -> Self {
+        Response::json(status, format!("{{\"error\":{}}}", quoted(message)))
+    }
+}
+
+// Numbers stay numbers in the JSON, everything else is a string
+pub fn answer_json(answer: &Answer) -> String {
+    match answer {
+        Answer::Int(number) => number.to_string(),
+        Answer::UInt(number) => number.to_string(),
+        Answer::Text(text) | Answer::Grid(text) => quoted(text),
+    }
+}
+
+fn error_status(error: &AocError) -> u16 {
+    match error {
+        AocError::Parse(_) => 400,
+        AocError::Unsolvable(_) => 422,
+        AocError::Unknown(_) => 404,
+        AocError::Unimplemented => 501,
+        AocError::Io(_) => 500,
+    }
+}
+
+// Why a part has no answer: the HTTP status that fits and the message
+pub type Failure = (u16, String);
+
+fn failure(error: AocError) -> Failure {
+    (error_status(&error), error.to_string())
+}
+
+// The function for a part, or a 404 or a 501
+pub fn find(year: u16, day: u8, part: u8) -> Result<DayFn, Failure> {
+    find_part(year, day, part).map_err(failure)
+}
+
+// Runs a part on `input`. A solver that panics is an error like any other here
+// instead of a dropped connection.
+// Panics abort in the browser, the page has to catch those.
+pub fn attempt(run: DayFn, input: &str, kind: InputKind) -> Result<Solved, Failure> {
+    match panic::catch_unwind(AssertUnwindSafe(|| run(input, kind))) {
+        Ok(solved) => solved.map_err(failure),
+        Err(_) => Err((500, String::from("The solver crashed on this input"))),
+    }
+}
+
+// The JSON object of a solved part, with its notes and how long it took
+pub fn solved(
+    year: u16,
+    day: u8,
+    part: u8,
+    input: &str,
+    kind: InputKind,
+) -> Result<String, Failure> {
+    let run = find(year, day, part)?;
+
+    let start = Instant::now();
+    let solved = attempt(run, input, kind)?;
+    let elapsed = start.elapsed();
+
+    let notes: Vec<String> = solved.notes.iter().map(|note| quoted(note)).collect();
+    Ok(format!(
+        "{{\"year\":{},\"day\":{},\"part\":{},\"answer\":{},\"notes\":[{}],\"micros\":{},\"took\":{}}}",
+        year,
+        day,
+        part,
+        answer_json(&solved.answer),
+        notes.join(","),
+        elapsed.as_micros(),
+        quoted(&fmt_dur(elapsed))
+    ))
+}
+
+// Solves a part on `input` and says how it went
+pub fn solve(year: u16, day: u8, part: u8, input: &str, kind: InputKind) -> Response {
+    match solved(year, day, part, input, kind) {
+        Ok(body) => Response::json(200, body),
+        Err((status, message)) => Response::error(status, &message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            solve(1999, 1, 1, "", InputKind::Real),
+            Response::error(404, "Unknown year 1999")
+        );
+        assert_eq!(
+            Response::error(400, "no \"quotes\"").body,
+            r#"{"error":"no \"quotes\""}"#
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "year2022")]
+    fn answers() {
+        let input = include_str!("../../inputs/2022/day01.example.txt");
+        let response = solve(2022, 1, 2, input, InputKind::Real);
+        assert_eq!(response.status, 200, "{}", response.body);
+        assert!(response
+            .body
+            .starts_with(r#"{"year":2022,"day":1,"part":2,"answer":45000,"notes":["#));
+        assert!(response.body.contains(r#""micros":"#));
+
+        assert_eq!(solve(2022, 20, 1, input, InputKind::Real).status, 501);
+        assert_eq!(solve(2022, 21, 1, "x", InputKind::Real).status, 400);
+    }
+
+    #[test]
+    fn crashes() {
+        let crash: DayFn = |_, _| panic!("no way");
+        let failure = attempt(crash, "", InputKind::Real).unwrap_err();
+        assert_eq!(failure.0, 500);
+    }
+}
@@ -0,0 +1,178 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use hashbrown::HashMap;
+
+use crate::aoc_lib::fmt_dur;
+use crate::aoc_lib::year::Year;
+
+// What the completion table says about a solved day. The stars are the
+// accepted answers in inputs/YEAR/dayNN.expected, the timings the last run of
+// each part on the real input that --record kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub year: u16,
+    pub day: u8,
+    pub answers: Vec<String>,
+    pub took: [Option<Duration>; 2],
+}
+
+// The last time of each part by year, day and part
+pub type Timings = HashMap<(u16, u8, u8), Duration>;
+
+// Every registered day of `years`, in order
+pub fn rows(years: &[&dyn Year], inputs: &Path, timings: &Timings) -> Vec<Row> {
+    let mut rows = Vec::new();
+    for year in years {
+        let mut days: Vec<u8> = year.solved().iter().map(|entry| entry.day).collect();
+        days.sort_unstable();
+        for day in days {
+            let expected = inputs
+                .join(year.year().to_string())
+                .join(format!("day{:02}.expected", day));
+            let answers = fs::read_to_string(expected).unwrap_or_default();
+            rows.push(Row {
+                year: year.year(),
+                day,
+                answers: answers
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .take(2)
+                    .map(String::from)
+                    .collect(),
+                took: [1, 2].map(|part| timings.get(&(year.year(), day, part)).copied()),
+            });
+        }
+    }
+    rows
+}
+
+// Days with at least one accepted answer and stars by year, in order
+fn totals(rows: &[Row]) -> Vec<(u16, usize, usize)> {
+    let mut totals: Vec<(u16, usize, usize)> = Vec::new();
+    for row in rows {
+        let solved = usize::from(!row.answers.is_empty());
+        match totals.last_mut() {
+            Some((year, days, stars)) if *year == row.year => {
+                *days += solved;
+                *stars += row.answers.len();
+            }
+            _ => totals.push((row.year, solved, row.answers.len())),
+        }
+    }
+    totals
+}
+
+// One line per year, for the terminal
+pub fn summary(rows: &[Row]) -> String {
+    let lines: Vec<String> = totals(rows)
+        .into_iter()
+        .map(|(year, days, stars)| format!("{}: {} days solved, {} stars", year, days, stars))
+        .collect();
+    lines.join("\n")
+}
+
+// A pipe would end the cell
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+// The completion table, with the answers hidden behind a check mark when
+// `redact` is on, for a README that shouldn't give them away
+pub fn markdown(rows: &[Row], redact: bool) -> String {
+    let mut table = vec![
+        String::from("<!-- Written by `advent_of_code report --markdown`, edits get lost -->"),
+        String::new(),
+        String::from("| Year | Day | Stars | Part 1 | Part 2 | Part 1 time | Part 2 time |"),
+        String::from("| ---: | --: | :---- | :----- | :----- | ----------: | ----------: |"),
+    ];
+    for row in rows {
+        let answer = |part: usize| match row.answers.get(part) {
+            Some(_) if redact => String::from("✓"),
+            Some(answer) => format!("`{}`", cell(answer)),
+            None => String::new(),
+        };
+        let took = |part: usize| row.took[part].map(fmt_dur).unwrap_or_default();
+        table.push(format!(
+            "| {} | {} | {} | {} | {} | {} | {} |",
+            row.year,
+            row.day,
+            "★".repeat(row.answers.len()),
+            answer(0),
+            answer(1),
+            took(0),
+            took(1)
+        ));
+    }
+
+    table.push(String::new());
+    for (year, days, stars) in totals(rows) {
+        table.push(format!("- {}: {} days solved, {} stars", year, days, stars));
+    }
+    table.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(year: u16, day: u8, answers: &[&str], took: [Option<u64>; 2]) -> Row {
+        Row {
+            year,
+            day,
+            answers: answers.iter().map(|answer| answer.to_string()).collect(),
+            took: took.map(|micros| micros.map(Duration::from_micros)),
+        }
+    }
+
+    #[test]
+    fn tables() {
+        let rows = [
+            row(2021, 1, &["1655", "1683"], [Some(40), None]),
+            row(2022, 1, &["24000", "a|b"], [Some(40), Some(120)]),
+            row(2022, 2, &[], [None, None]),
+        ];
+
+        let table = markdown(&rows, false);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[4], "| 2021 | 1 | ★★ | `1655` | `1683` | 40µs |  |");
+        assert_eq!(
+            lines[5],
+            "| 2022 | 1 | ★★ | `24000` | `a\\|b` | 40µs | 120µs |"
+        );
+        assert_eq!(lines[6], "| 2022 | 2 |  |  |  |  |  |");
+        assert_eq!(
+            lines[8..],
+            [
+                "- 2021: 1 days solved, 2 stars",
+                "- 2022: 1 days solved, 2 stars"
+            ]
+        );
+
+        let redacted = markdown(&rows, true);
+        assert!(!redacted.contains("24000"));
+        assert!(redacted.contains("| 2022 | 1 | ★★ | ✓ | ✓ | 40µs | 120µs |"));
+        assert_eq!(
+            summary(&rows),
+            "2021: 1 days solved, 2 stars\n2022: 1 days solved, 2 stars"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "year2022")]
+    fn from_the_registry() {
+        let years: Vec<&dyn Year> = crate::registry()
+            .iter()
+            .copied()
+            .filter(|year| year.year() == 2022)
+            .collect();
+        let timings = Timings::from([((2022, 1, 2), Duration::from_millis(3))]);
+        // No accepted answers in there
+        let rows = rows(&years, Path::new("/nowhere"), &timings);
+
+        assert!(rows.windows(2).all(|pair| pair[0].day < pair[1].day));
+        assert_eq!(rows[0], row(2022, 1, &[], [None, Some(3000)]));
+    }
+}
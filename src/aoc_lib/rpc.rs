@@ -0,0 +1,519 @@
+use std::io::{BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use hashbrown::HashMap;
+
+use crate::aoc_lib::clock::Instant;
+use crate::aoc_lib::config::InputKind;
+use crate::aoc_lib::json::Json;
+use crate::aoc_lib::reply::{self, answer_json, Failure};
+use crate::aoc_lib::{Answer, AocError, DayFn};
+use crate::registry;
+
+// The --stdio mode: JSON-RPC 2.0, one request per line on stdin and one
+// response per line on stdout, for editor plugins and runners that want to
+// keep the process around. The methods are
+//
+//     list                                    -> [{"year", "day", "solvers"}]
+//     solve {year, day, part, input}          -> what serve answers with
+//     bench {year, day, part, input, runs}    -> the answer and the timings
+//     cancel {id}                             -> whether it was still running
+//
+// Solve and bench take `"example": true` too, for the input of the puzzle text.
+// Solves and benches run on their own threads, so a list or a cancel gets
+// through while they do. A cancelled request answers right away with an error,
+// its thread finishes in the background and its answer is dropped.
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+// A part without an answer, the status serve would answer with is in the data
+const NO_ANSWER: i64 = -32000;
+// Same code as the Language Server Protocol
+const CANCELLED: i64 = -32800;
+
+// More than enough for a benchmark, and a cancel stops it anyway
+const MAX_RUNS: u64 = 1000;
+
+#[derive(Debug, PartialEq)]
+struct RpcError {
+    code: i64,
+    message: String,
+    data: Option<Json>,
+}
+
+fn error(code: i64, message: impl Into<String>) -> RpcError {
+    RpcError {
+        code,
+        message: message.into(),
+        data: None,
+    }
+}
+
+impl From<Failure> for RpcError {
+    fn from((status, message): Failure) -> Self {
+        RpcError {
+            code: NO_ANSWER,
+            message,
+            data: Some(Json::Object(vec![(
+                String::from("status"),
+                Json::Number(f64::from(status)),
+            )])),
+        }
+    }
+}
+
+// The JSON of the result, or why there is none
+type Outcome = Result<String, RpcError>;
+
+// A bench that ran all of its runs, for --record to keep
+pub struct Benched<'a> {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub answer: &'a Answer,
+    pub runs: usize,
+    pub mean: Duration,
+}
+
+// Called from the thread of the bench, once it's done
+pub type OnBench = Box<dyn Fn(&Benched) + Send + Sync>;
+
+struct Connection {
+    output: Mutex<Box<dyn Write + Send>>,
+    // The solves and benches still running, by the JSON of their id
+    running: Mutex<HashMap<String, Arc<AtomicBool>>>,
+    on_bench: Option<OnBench>,
+}
+
+impl Connection {
+    // A request without an id is a notification, it gets no response
+    fn respond(&self, id: Option<&Json>, outcome: Outcome) {
+        let Some(id) = id else {
+            return;
+        };
+        let line = match outcome {
+            Ok(result) => format!(
+                "{{\"jsonrpc\":\"2.0\",\"id\":{},\"result\":{}}}",
+                id, result
+            ),
+            Err(error) => {
+                let mut fields = vec![
+                    (String::from("code"), Json::Number(error.code as f64)),
+                    (String::from("message"), Json::Text(error.message)),
+                ];
+                fields.extend(error.data.map(|data| (String::from("data"), data)));
+                format!(
+                    "{{\"jsonrpc\":\"2.0\",\"id\":{},\"error\":{}}}",
+                    id,
+                    Json::Object(fields)
+                )
+            }
+        };
+
+        // Nobody is left to tell when the output is gone
+        let mut output = self.output.lock().unwrap();
+        let _ = writeln!(output, "{}", line).and_then(|_| output.flush());
+    }
+
+    // Answers a request that ran on its own thread, unless it was cancelled
+    fn finish(&self, id: Option<&Json>, done: &AtomicBool, outcome: Outcome) {
+        if let Some(id) = id {
+            self.running.lock().unwrap().remove(&id.to_string());
+        }
+        if !done.swap(true, Ordering::SeqCst) {
+            self.respond(id, outcome);
+        }
+    }
+
+    // Answers the request `id` with a cancellation, if it's still running
+    fn cancel(&self, id: &Json) -> bool {
+        let running = self.running.lock().unwrap().remove(&id.to_string());
+        let cancelled = running.is_some_and(|done| !done.swap(true, Ordering::SeqCst));
+        if cancelled {
+            self.respond(Some(id), Err(error(CANCELLED, "Request cancelled")));
+        }
+        cancelled
+    }
+}
+
+fn list() -> Outcome {
+    let number = |value: u64| Json::Number(value as f64);
+    let days = registry()
+        .iter()
+        .flat_map(|year| year.solved())
+        .map(|entry| {
+            let solvers = entry
+                .variants
+                .iter()
+                .map(|variant| Json::Text(variant.name.to_string()))
+                .collect();
+            Json::Object(vec![
+                (String::from("year"), number(u64::from(entry.year))),
+                (String::from("day"), number(u64::from(entry.day))),
+                (String::from("solvers"), Json::Array(solvers)),
+            ])
+        })
+        .collect();
+
+    Ok(Json::Array(days).to_string())
+}
+
+// What solve and bench are asked to run
+struct Part {
+    year: u16,
+    day: u8,
+    part: u8,
+    input: String,
+    kind: InputKind,
+}
+
+fn number<T: TryFrom<u64>>(params: &Json, name: &str) -> Result<T, RpcError> {
+    params
+        .get(name)
+        .and_then(Json::as_u64)
+        .and_then(|number| T::try_from(number).ok())
+        .ok_or_else(|| error(INVALID_PARAMS, format!("\"{}\" is not a number", name)))
+}
+
+fn part_params(params: &Json) -> Result<Part, RpcError> {
+    let input = params.get("input").and_then(Json::as_str);
+    let kind = match params.get("example") {
+        None | Some(Json::Bool(false)) => InputKind::Real,
+        Some(Json::Bool(true)) => InputKind::Example,
+        Some(_) => return Err(error(INVALID_PARAMS, "\"example\" is not true or false")),
+    };
+    Ok(Part {
+        year: number(params, "year")?,
+        day: number(params, "day")?,
+        part: number(params, "part")?,
+        input: input
+            .ok_or_else(|| error(INVALID_PARAMS, "\"input\" is not a string"))?
+            .to_string(),
+        kind,
+    })
+}
+
+fn micros(duration: Duration) -> Json {
+    Json::Number(duration.as_micros() as f64)
+}
+
+// Runs the part until it has run `runs` times or gets cancelled
+fn bench(
+    connection: &Connection,
+    run: DayFn,
+    part: &Part,
+    runs: u64,
+    done: &AtomicBool,
+) -> Outcome {
+    let mut times = Vec::new();
+    let mut answer = None;
+    while times.len() < runs as usize && !done.load(Ordering::SeqCst) {
+        let start = Instant::now();
+        answer = Some(reply::attempt(run, &part.input, part.kind)?.answer);
+        times.push(start.elapsed());
+    }
+
+    let (Some(answer), Some(fastest), Some(slowest)) =
+        (answer, times.iter().min(), times.iter().max())
+    else {
+        return Err(error(CANCELLED, "Request cancelled"));
+    };
+    let mean = times.iter().sum::<Duration>() / times.len() as u32;
+    // A cancelled bench didn't run all of its runs
+    if let (Some(on_bench), false) = (&connection.on_bench, done.load(Ordering::SeqCst)) {
+        on_bench(&Benched {
+            year: part.year,
+            day: part.day,
+            part: part.part,
+            answer: &answer,
+            runs: times.len(),
+            mean,
+        });
+    }
+
+    Ok(format!(
+        "{{\"answer\":{},\"runs\":{},\"min_micros\":{},\"mean_micros\":{},\"max_micros\":{}}}",
+        answer_json(&answer),
+        times.len(),
+        micros(*fastest),
+        micros(mean),
+        micros(*slowest)
+    ))
+}
+
+// Answers the requests on `input` until it ends, then waits for the ones still
+// running
+pub fn serve(
+    input: impl BufRead,
+    output: impl Write + Send + 'static,
+    on_bench: Option<OnBench>,
+) -> Result<(), AocError> {
+    let connection = Arc::new(Connection {
+        output: Mutex::new(Box::new(output)),
+        running: Mutex::new(HashMap::new()),
+        on_bench,
+    });
+    let mut workers: Vec<(Arc<AtomicBool>, JoinHandle<()>)> = Vec::new();
+
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Ok(request) = Json::parse(&line) else {
+            connection.respond(Some(&Json::Null), Err(error(PARSE_ERROR, "Not JSON")));
+            continue;
+        };
+        let id = request.get("id").cloned();
+        let Some(method) = request.get("method").and_then(Json::as_str) else {
+            let missing = error(INVALID_REQUEST, "The request has no method");
+            connection.respond(Some(id.as_ref().unwrap_or(&Json::Null)), Err(missing));
+            continue;
+        };
+        let params = request
+            .get("params")
+            .cloned()
+            .unwrap_or(Json::Object(Vec::new()));
+
+        let (part, runs) = match method {
+            "list" => {
+                connection.respond(id.as_ref(), list());
+                continue;
+            }
+            "cancel" => {
+                let outcome = match params.get("id") {
+                    Some(target) => Ok(Json::Bool(connection.cancel(target)).to_string()),
+                    None => Err(error(INVALID_PARAMS, "Cancel which \"id\"?")),
+                };
+                connection.respond(id.as_ref(), outcome);
+                continue;
+            }
+            "solve" => (part_params(&params), None),
+            "bench" => {
+                let runs = match params.get("runs") {
+                    None => Ok(10),
+                    Some(runs) => runs
+                        .as_u64()
+                        .filter(|runs| (1..=MAX_RUNS).contains(runs))
+                        .ok_or_else(|| {
+                            error(
+                                INVALID_PARAMS,
+                                format!("\"runs\" goes from 1 to {}", MAX_RUNS),
+                            )
+                        }),
+                };
+                (part_params(&params), Some(runs))
+            }
+            other => {
+                let unknown = error(METHOD_NOT_FOUND, format!("No method \"{}\"", other));
+                connection.respond(id.as_ref(), Err(unknown));
+                continue;
+            }
+        };
+        let (part, runs) = match (part, runs.transpose()) {
+            (Ok(part), Ok(runs)) => (part, runs),
+            (Err(invalid), _) | (_, Err(invalid)) => {
+                connection.respond(id.as_ref(), Err(invalid));
+                continue;
+            }
+        };
+
+        let done = Arc::new(AtomicBool::new(false));
+        if let Some(id) = &id {
+            let mut running = connection.running.lock().unwrap();
+            running.insert(id.to_string(), Arc::clone(&done));
+        }
+        let worker = {
+            let (connection, done) = (Arc::clone(&connection), Arc::clone(&done));
+            thread::spawn(move || {
+                let outcome = match (reply::find(part.year, part.day, part.part), runs) {
+                    (Err(failure), _) => Err(RpcError::from(failure)),
+                    (Ok(run), Some(runs)) => bench(&connection, run, &part, runs, &done),
+                    (Ok(_), None) => {
+                        { reply::solved(part.year, part.day, part.part, &part.input, part.kind) }
+                            .map_err(RpcError::from)
+                    }
+                };
+                connection.finish(id.as_ref(), &done, outcome);
+            })
+        };
+        workers.push((done, worker));
+    }
+
+    // The cancelled ones are left to finish on their own
+    for (done, worker) in workers {
+        if !done.load(Ordering::SeqCst) {
+            let _ = worker.join();
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+    #[cfg(any(feature = "year2021", feature = "year2022"))]
+    use crate::aoc_lib::trace::quoted;
+
+    // Everything written, kept for the test to read once `serve` is done
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn session(requests: &[String]) -> Vec<Json> {
+        let output = Shared::default();
+        serve(requests.join("\n").as_bytes(), output.clone(), None).unwrap();
+
+        let written = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        written
+            .lines()
+            .map(|line| Json::parse(line).unwrap())
+            .collect()
+    }
+
+    fn by_id(responses: &[Json], id: u64) -> &Json {
+        responses
+            .iter()
+            .find(|response| response.get("id").and_then(Json::as_u64) == Some(id))
+            .unwrap_or_else(|| panic!("No response to {} in {:?}", id, responses))
+    }
+
+    fn code(response: &Json) -> Option<f64> {
+        match response.get("error")?.get("code")? {
+            Json::Number(code) => Some(*code),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn bad_requests() {
+        let responses = session(&[
+            String::from("{"),
+            String::from(r#"{"jsonrpc":"2.0","id":1}"#),
+            String::from(r#"{"jsonrpc":"2.0","id":2,"method":"fly"}"#),
+            String::from(r#"{"jsonrpc":"2.0","id":3,"method":"solve","params":{"year":2022}}"#),
+            String::from(
+                r#"{"jsonrpc":"2.0","id":4,"method":"solve","params":{"year":1999,"day":1,"part":1,"input":""}}"#,
+            ),
+            String::from(r#"{"jsonrpc":"2.0","id":5,"method":"cancel","params":{"id":77}}"#),
+            String::from(r#"{"jsonrpc":"2.0","method":"list"}"#),
+        ]);
+
+        assert_eq!(responses.len(), 6, "{:?}", responses);
+        assert_eq!(responses[0].get("id"), Some(&Json::Null));
+        assert_eq!(code(&responses[0]), Some(PARSE_ERROR as f64));
+        assert_eq!(code(by_id(&responses, 1)), Some(INVALID_REQUEST as f64));
+        assert_eq!(code(by_id(&responses, 2)), Some(METHOD_NOT_FOUND as f64));
+        assert_eq!(code(by_id(&responses, 3)), Some(INVALID_PARAMS as f64));
+        let unknown = by_id(&responses, 4).get("error").unwrap();
+        assert_eq!(
+            unknown.get("message").and_then(Json::as_str),
+            Some("Unknown year 1999")
+        );
+        assert_eq!(
+            unknown.get("data").unwrap().to_string(),
+            r#"{"status":404}"#
+        );
+        assert_eq!(by_id(&responses, 5).get("result"), Some(&Json::Bool(false)));
+    }
+
+    #[test]
+    #[cfg(feature = "year2022")]
+    fn solve_and_bench() {
+        let input = quoted(include_str!("../../inputs/2022/day01.example.txt"));
+        let responses = session(&[
+            format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"solve","params":{{"year":2022,"day":1,"part":2,"input":{}}}}}"#,
+                input
+            ),
+            format!(
+                r#"{{"jsonrpc":"2.0","id":2,"method":"bench","params":{{"year":2022,"day":1,"part":1,"input":{},"runs":3}}}}"#,
+                input
+            ),
+            String::from(r#"{"jsonrpc":"2.0","id":3,"method":"list"}"#),
+            format!(
+                r#"{{"jsonrpc":"2.0","id":4,"method":"solve","params":{{"year":2022,"day":15,"part":1,"input":{},"example":true}}}}"#,
+                quoted(include_str!("../../inputs/2022/day15.example.txt"))
+            ),
+            format!(
+                r#"{{"jsonrpc":"2.0","id":5,"method":"solve","params":{{"year":2022,"day":1,"part":1,"input":{},"example":1}}}}"#,
+                input
+            ),
+        ]);
+
+        let solved = by_id(&responses, 1).get("result").unwrap();
+        assert_eq!(solved.get("answer").and_then(Json::as_u64), Some(45000));
+        let benched = by_id(&responses, 2).get("result").unwrap();
+        assert_eq!(benched.get("answer").and_then(Json::as_u64), Some(24000));
+        assert_eq!(benched.get("runs").and_then(Json::as_u64), Some(3));
+        let Some(Json::Array(days)) = by_id(&responses, 3).get("result") else {
+            panic!("The days should be a list");
+        };
+        assert!(days
+            .iter()
+            .any(|day| day.get("year").and_then(Json::as_u64) == Some(2022)
+                && day.get("day").and_then(Json::as_u64) == Some(1)));
+        let example = by_id(&responses, 4).get("result").unwrap();
+        assert_eq!(example.get("answer").and_then(Json::as_u64), Some(26));
+        assert_eq!(code(by_id(&responses, 5)), Some(INVALID_PARAMS as f64));
+    }
+
+    #[test]
+    #[cfg(feature = "year2022")]
+    fn benches_told() {
+        let input = quoted(include_str!("../../inputs/2022/day01.example.txt"));
+        let request = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"bench","params":{{"year":2022,"day":1,"part":2,"input":{},"runs":4}}}}"#,
+            input
+        );
+        let told = Arc::new(Mutex::new(Vec::new()));
+        let on_bench: OnBench = {
+            let told = Arc::clone(&told);
+            Box::new(move |benched: &Benched| {
+                let what = (benched.year, benched.day, benched.part, benched.runs);
+                told.lock()
+                    .unwrap()
+                    .push((what, benched.answer.to_string()));
+            })
+        };
+        serve(request.as_bytes(), io::sink(), Some(on_bench)).unwrap();
+
+        let told = told.lock().unwrap();
+        assert_eq!(told[..], [((2022, 1, 2, 4), String::from("45000"))]);
+    }
+
+    #[test]
+    #[cfg(feature = "year2021")]
+    fn cancel() {
+        // Long enough to still be going when the cancel comes in
+        let input = quoted(include_str!("../../inputs/2021/day23.example.txt"));
+        let responses = session(&[
+            format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"bench","params":{{"year":2021,"day":23,"part":2,"input":{},"runs":1000}}}}"#,
+                input
+            ),
+            String::from(r#"{"jsonrpc":"2.0","id":2,"method":"cancel","params":{"id":1}}"#),
+        ]);
+
+        assert_eq!(responses.len(), 2, "{:?}", responses);
+        assert_eq!(code(by_id(&responses, 1)), Some(CANCELLED as f64));
+        assert_eq!(by_id(&responses, 2).get("result"), Some(&Json::Bool(true)));
+    }
+}
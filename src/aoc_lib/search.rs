@@ -0,0 +1,506 @@
+#![warn(missing_docs)]
+
+use alloc::collections::{BinaryHeap, VecDeque};
+use core::cmp::Reverse;
+use core::hash::Hash;
+
+use hashbrown::{HashMap, HashSet};
+
+/// Breadth first search over an implicit graph, returns the number of steps to
+/// the first node accepted by `goal` together with the node itself
+///
+/// ```
+/// use advent_of_code::aoc_lib::search::bfs;
+///
+/// // Adding one or doubling, 1 -> 2 -> 4 -> 5 -> 10
+/// assert_eq!(bfs(1u64, |n| [n + 1, n * 2], |n| *n == 10), Some((4, 10)));
+///
+/// // Doubling alone never gets there, and stops at 100
+/// let doubling = |n: &u64| (*n < 100).then_some(n * 2);
+/// assert_eq!(bfs(1, doubling, |n| *n == 10), None);
+/// ```
+pub fn bfs<N, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut goal: impl FnMut(&N) -> bool,
+) -> Option<(usize, N)>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut seen: HashSet<N> = HashSet::from([start.clone()]);
+    let mut queue: VecDeque<(usize, N)> = VecDeque::from([(0, start)]);
+
+    while let Some((steps, node)) = queue.pop_front() {
+        if goal(&node) {
+            return Some((steps, node));
+        }
+        for next in neighbours(&node) {
+            if seen.insert(next.clone()) {
+                queue.push_back((steps + 1, next));
+            }
+        }
+    }
+
+    None
+}
+
+/// What a search went through, to see where it spent its time. Heatmaps of
+/// these show how much of the map a heuristic saves from being looked at.
+///
+/// ```
+/// use advent_of_code::aoc_lib::search::{bfs_explored, Explored};
+///
+/// // A dead end: 1 leads nowhere, the start gets expanded and then 1
+/// let (found, explored): (_, Explored<u8, usize>) = bfs_explored(0u8, |n| (*n == 0).then_some(1), |_| false);
+/// assert_eq!(found, None);
+/// assert_eq!(explored.expansions.len(), 2);
+/// assert_eq!(explored.costs[&1], 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explored<N: Eq + Hash, C> {
+    /// How many times each node was taken off the queue and expanded
+    pub expansions: HashMap<N, usize>,
+    /// Best cost known for every node reached, final for the expanded ones
+    pub costs: HashMap<N, C>,
+}
+
+/// [`bfs`] that also tells which nodes it expanded, and how many steps away
+/// every node it reached is
+///
+/// ```
+/// use advent_of_code::aoc_lib::search::bfs_explored;
+///
+/// let (found, explored) = bfs_explored(1u64, |n| [n + 1, n * 2], |n| *n == 4);
+/// assert_eq!(found, Some((2, 4)));
+/// // 1, then 2 from the first step, then 3 and 4 from the second
+/// assert_eq!(explored.expansions.len(), 4);
+/// assert_eq!(explored.costs[&6], 3);
+/// ```
+pub fn bfs_explored<N, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut goal: impl FnMut(&N) -> bool,
+) -> (Option<(usize, N)>, Explored<N, usize>)
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut explored = Explored {
+        expansions: HashMap::new(),
+        costs: HashMap::from([(start.clone(), 0)]),
+    };
+    let mut queue: VecDeque<(usize, N)> = VecDeque::from([(0, start)]);
+
+    while let Some((steps, node)) = queue.pop_front() {
+        *explored.expansions.entry(node.clone()).or_default() += 1;
+        if goal(&node) {
+            return (Some((steps, node)), explored);
+        }
+        for next in neighbours(&node) {
+            if !explored.costs.contains_key(&next) {
+                explored.costs.insert(next.clone(), steps + 1);
+                queue.push_back((steps + 1, next));
+            }
+        }
+    }
+
+    (None, explored)
+}
+
+/// A* over an implicit graph with weighted edges. The heuristic must never
+/// overestimate the remaining cost; nodes found again with a lower cost are
+/// queued again, so it doesn't have to be consistent.
+///
+/// ```
+/// use advent_of_code::aoc_lib::search::a_star;
+///
+/// // An open plane where every step costs one, so the Manhattan distance is
+/// // a good guess
+/// let steps = |(x, y): &(i64, i64)| [(x + 1, *y), (x - 1, *y), (*x, y + 1), (*x, y - 1)].map(|p| (p, 1));
+/// let guess = |(x, y): &(i64, i64)| x.abs_diff(3) + y.abs_diff(-4);
+/// assert_eq!(a_star((0, 0), steps, guess, |p| *p == (3, -4)), Some((7, (3, -4))));
+/// ```
+pub fn a_star<N, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    heuristic: impl FnMut(&N) -> u64,
+    goal: impl FnMut(&N) -> bool,
+) -> Option<(u64, N)>
+where
+    N: Eq + Hash + Clone + Ord,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    search_weighted(start, neighbours, heuristic, goal, |_| ()).0
+}
+
+/// [`a_star`] that also tells how many times it expanded every node, and the
+/// cheapest cost it knows for every node it reached
+///
+/// ```
+/// use advent_of_code::aoc_lib::search::{a_star_explored, dijkstra_explored};
+///
+/// // Walking right along a line, with a perfect guess A* never looks back
+/// let steps = |x: &i64| [(x + 1, 1), (x - 1, 1)];
+/// let (found, guided) = a_star_explored(0, steps, |x| x.abs_diff(5), |x| *x == 5);
+/// let (_, blind) = dijkstra_explored(0, steps, |x| *x == 5);
+/// assert_eq!(found, Some((5, 5)));
+/// assert_eq!(guided.expansions.len(), 6);
+/// // Dijkstra goes as far left as right, -5 comes first on a tie
+/// assert_eq!(blind.expansions.len(), 11);
+/// assert_eq!(blind.costs[&-5], 5);
+/// ```
+pub fn a_star_explored<N, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    heuristic: impl FnMut(&N) -> u64,
+    goal: impl FnMut(&N) -> bool,
+) -> (Option<(u64, N)>, Explored<N, u64>)
+where
+    N: Eq + Hash + Clone + Ord,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    let mut expansions: HashMap<N, usize> = HashMap::new();
+    let (found, costs) = search_weighted(start, neighbours, heuristic, goal, |node| {
+        *expansions.entry(node.clone()).or_default() += 1;
+    });
+
+    (found, Explored { expansions, costs })
+}
+
+// The A* both flavours share, `expanded` sees every node taken off the queue
+// that's not stale. The costs are handed back for the explored flavour.
+fn search_weighted<N, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> u64,
+    mut goal: impl FnMut(&N) -> bool,
+    mut expanded: impl FnMut(&N),
+) -> (Option<(u64, N)>, HashMap<N, u64>)
+where
+    N: Eq + Hash + Clone + Ord,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    let mut best: HashMap<N, u64> = HashMap::from([(start.clone(), 0)]);
+    let mut queue: BinaryHeap<Reverse<(u64, u64, N)>> = BinaryHeap::new();
+    queue.push(Reverse((heuristic(&start), 0, start)));
+
+    while let Some(Reverse((_, cost, node))) = queue.pop() {
+        if best.get(&node).is_some_and(|known| *known < cost) {
+            continue;
+        }
+        expanded(&node);
+        if goal(&node) {
+            return (Some((cost, node)), best);
+        }
+        for (next, step_cost) in neighbours(&node) {
+            let next_cost = cost + step_cost;
+            if best.get(&next).is_none_or(|known| next_cost < *known) {
+                best.insert(next.clone(), next_cost);
+                queue.push(Reverse((next_cost + heuristic(&next), next_cost, next)));
+            }
+        }
+    }
+
+    (None, best)
+}
+
+/// A* without a heuristic, for when there's nothing better than zero to guess
+///
+/// ```
+/// use advent_of_code::aoc_lib::search::dijkstra;
+///
+/// // The long way round is cheaper than the direct road
+/// let roads = |town: &char| match town {
+///     'a' => vec![('b', 1), ('c', 10)],
+///     'b' => vec![('c', 2)],
+///     _ => vec![],
+/// };
+/// assert_eq!(dijkstra('a', roads, |town| *town == 'c'), Some((3, 'c')));
+/// assert_eq!(dijkstra('c', roads, |town| *town == 'a'), None);
+/// ```
+pub fn dijkstra<N, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    goal: impl FnMut(&N) -> bool,
+) -> Option<(u64, N)>
+where
+    N: Eq + Hash + Clone + Ord,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    a_star(start, neighbours, |_| 0, goal)
+}
+
+/// [`dijkstra`] that also tells what it explored, like [`a_star_explored`]
+///
+/// ```
+/// use advent_of_code::aoc_lib::search::dijkstra_explored;
+///
+/// let roads = |town: &char| match town {
+///     'a' => vec![('b', 1), ('c', 10)],
+///     'b' => vec![('c', 2)],
+///     _ => vec![],
+/// };
+/// let (found, explored) = dijkstra_explored('a', roads, |town| *town == 'c');
+/// assert_eq!(found, Some((3, 'c')));
+/// assert_eq!(explored.costs[&'c'], 3);
+/// assert_eq!(explored.expansions.len(), 3);
+/// ```
+pub fn dijkstra_explored<N, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    goal: impl FnMut(&N) -> bool,
+) -> (Option<(u64, N)>, Explored<N, u64>)
+where
+    N: Eq + Hash + Clone + Ord,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    a_star_explored(start, neighbours, |_| 0, goal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aoc_lib::random::{for_all, Xorshift};
+
+    // Directed graph as the edges leaving each node, with their costs
+    type Graph = Vec<Vec<(usize, u64)>>;
+
+    fn edges(graph: &Graph) -> impl FnMut(&usize) -> Vec<(usize, u64)> + '_ {
+        |node| graph[*node].clone()
+    }
+
+    fn steps(graph: &Graph) -> impl FnMut(&usize) -> Vec<usize> + '_ {
+        |node| graph[*node].iter().map(|(next, _)| *next).collect()
+    }
+
+    // Moves on a 10x10 grid with a wall on column 5, open only on the last row
+    fn grid_moves((x, y): &(i32, i32)) -> Vec<(i32, i32)> {
+        [(1, 0), (-1, 0), (0, 1), (0, -1)]
+            .iter()
+            .map(|(dx, dy)| (x + dx, y + dy))
+            .filter(|(x, y)| (0..10).contains(x) && (0..10).contains(y))
+            .filter(|(x, y)| *x != 5 || *y == 9)
+            .collect()
+    }
+
+    #[test]
+    fn breadth_first() {
+        let found = bfs((0, 0), grid_moves, |node| *node == (9, 0));
+        assert_eq!(found, Some((27, (9, 0))));
+
+        assert_eq!(bfs((0, 0), grid_moves, |node| *node == (20, 0)), None);
+    }
+
+    #[test]
+    fn weighted() {
+        // Going right costs 5, everything else 1
+        let weighted = |node: &(i32, i32)| {
+            grid_moves(node)
+                .into_iter()
+                .map(|next| (next, if next.0 > node.0 { 5 } else { 1 }))
+                .collect::<Vec<_>>()
+        };
+        let manhattan = |(x, y): &(i32, i32)| (x.abs_diff(9) + y.abs_diff(0)) as u64;
+
+        let plain = dijkstra((0, 0), weighted, |node| *node == (9, 0));
+        let guided = a_star((0, 0), weighted, manhattan, |node| *node == (9, 0));
+        assert_eq!(plain, Some((9 * 5 + 18, (9, 0))));
+        assert_eq!(guided, plain);
+    }
+
+    #[test]
+    fn explored() {
+        let manhattan = |(x, y): &(i32, i32)| (x.abs_diff(9) + y.abs_diff(9)) as u64;
+        let unit = |node: &(i32, i32)| grid_moves(node).into_iter().map(|next| (next, 1));
+        let goal = |node: &(i32, i32)| *node == (9, 9);
+
+        let (found, steps) = bfs_explored((0, 0), grid_moves, goal);
+        assert_eq!(found, bfs((0, 0), grid_moves, goal));
+        assert_eq!(steps.costs[&(9, 9)], 18);
+
+        let (plain, blind) = dijkstra_explored((0, 0), unit, goal);
+        let (guided, focused) = a_star_explored((0, 0), unit, manhattan, goal);
+        assert_eq!(plain, Some((18, (9, 9))));
+        assert_eq!(guided, plain);
+        // The heuristic is consistent, nothing is expanded twice, and it keeps
+        // A* away from the corner behind the start
+        assert!(focused.expansions.values().all(|times| *times == 1));
+        assert_eq!((focused.expansions.len(), blind.expansions.len()), (55, 61));
+        assert!(focused
+            .expansions
+            .keys()
+            .all(|node| focused.costs.contains_key(node)));
+
+        // Nothing to find, every square but the wall gets expanded
+        let (found, everything) = dijkstra_explored((0, 0), unit, |_| false);
+        assert_eq!(found, None);
+        assert_eq!(everything.expansions.len(), 91);
+        assert_eq!(everything.costs[&(9, 0)], 27);
+    }
+
+    #[test]
+    fn known_paths() {
+        // The direct edges are the expensive way round: 0 -> 1 -> 3 -> 4
+        // costs 2 + 1 + 3, the edge 0 -> 4 costs 10
+        let graph: Graph = vec![
+            vec![(1, 2), (2, 5), (4, 10)],
+            vec![(2, 4), (3, 1)],
+            vec![(4, 2)],
+            vec![(2, 0), (4, 3)],
+            vec![],
+        ];
+
+        assert_eq!(dijkstra(0, edges(&graph), |n| *n == 4), Some((5, 4)));
+        assert_eq!(dijkstra(0, edges(&graph), |n| *n == 2), Some((3, 2)));
+        assert_eq!(bfs(0, steps(&graph), |n| *n == 4), Some((1, 4)));
+        assert_eq!(bfs(0, steps(&graph), |n| *n == 3), Some((2, 3)));
+
+        // The closest of several goals wins
+        assert_eq!(dijkstra(0, edges(&graph), |n| *n >= 3), Some((3, 3)));
+        assert_eq!(bfs(0, steps(&graph), |n| *n >= 2), Some((1, 2)));
+    }
+
+    #[test]
+    fn start_is_goal() {
+        let graph: Graph = vec![vec![(0, 1), (1, 1)], vec![]];
+
+        assert_eq!(bfs(0, steps(&graph), |n| *n == 0), Some((0, 0)));
+        assert_eq!(dijkstra(0, edges(&graph), |n| *n == 0), Some((0, 0)));
+        assert_eq!(a_star(0, edges(&graph), |_| 7, |n| *n == 0), Some((0, 0)));
+    }
+
+    #[test]
+    fn unreachable_goals() {
+        // Two islands with a one way bridge from 2 to 0
+        let graph: Graph = vec![
+            vec![(1, 1)],
+            vec![(0, 1)],
+            vec![(3, 1), (0, 4)],
+            vec![(2, 1)],
+        ];
+
+        assert_eq!(bfs(0, steps(&graph), |n| *n == 3), None);
+        assert_eq!(dijkstra(0, edges(&graph), |n| *n == 2), None);
+        assert_eq!(a_star(1, edges(&graph), |_| 0, |n| *n > 1), None);
+        assert_eq!(dijkstra(3, edges(&graph), |n| *n == 1), Some((6, 1)));
+        assert_eq!(dijkstra(0, edges(&graph), |_| false), None);
+    }
+
+    #[test]
+    fn inconsistent_heuristic() {
+        // Admissible everywhere, but it drops by 4 along the edge 1 -> 2 that
+        // costs 1, so 2 is first reached through the worse path via 3 and has
+        // to be expanded again when 1 gets its turn
+        let graph: Graph = vec![
+            vec![(1, 1), (3, 1)],
+            vec![(2, 1)],
+            vec![(4, 3)],
+            vec![(2, 3)],
+            vec![],
+        ];
+        let heuristic = |node: &usize| [0, 4, 0, 0, 0][*node];
+
+        assert_eq!(
+            a_star(0, edges(&graph), heuristic, |n| *n == 4),
+            Some((5, 4))
+        );
+    }
+
+    // Cheapest cost between every pair of nodes, None when there's no way
+    fn floyd_warshall(graph: &Graph) -> Vec<Vec<Option<u64>>> {
+        let size = graph.len();
+        let mut distances = vec![vec![None; size]; size];
+        for (node, edges) in graph.iter().enumerate() {
+            distances[node][node] = Some(0);
+            for (next, cost) in edges {
+                let known = distances[node][*next].unwrap_or(u64::MAX);
+                distances[node][*next] = Some(known.min(*cost));
+            }
+        }
+        for middle in 0..size {
+            for from in 0..size {
+                for to in 0..size {
+                    if let (Some(first), Some(second)) =
+                        (distances[from][middle], distances[middle][to])
+                    {
+                        let known = distances[from][to].unwrap_or(u64::MAX);
+                        distances[from][to] = Some(known.min(first + second));
+                    }
+                }
+            }
+        }
+
+        distances
+    }
+
+    // Up to 10 nodes with a few random edges each, some of them free, and
+    // random goals
+    fn random_graph(rng: &mut Xorshift) -> (Graph, Vec<bool>) {
+        let size = 1 + rng.below(10) as usize;
+        let graph = (0..size)
+            .map(|_| {
+                (0..rng.below(4))
+                    .map(|_| (rng.below(size as u64) as usize, rng.below(10)))
+                    .collect()
+            })
+            .collect();
+        let goals = (0..size).map(|_| rng.below(4) == 0).collect();
+
+        (graph, goals)
+    }
+
+    #[test]
+    fn random_graphs() {
+        for_all(0x5ea2c4, 2000, random_graph, |(graph, goals)| {
+            let distances = floyd_warshall(graph);
+            let to_goal = |node: usize| {
+                (0..graph.len())
+                    .filter(|goal| goals[*goal])
+                    .filter_map(|goal| distances[node][goal])
+                    .min()
+            };
+            let is_goal = |node: &usize| goals[*node];
+
+            let expected = to_goal(0);
+            let found = dijkstra(0, edges(graph), is_goal);
+            if found.map(|(cost, _)| cost) != expected {
+                return Err(format!("Dijkstra found {:?}, not {:?}", found, expected));
+            }
+            if found.is_some_and(|(cost, node)| !goals[node] || distances[0][node] != Some(cost)) {
+                return Err(format!("Dijkstra stopped on {:?}", found));
+            }
+
+            // Any heuristic up to the real cost, consistent or not. Nodes that
+            // can't reach a goal can claim anything.
+            let mut rng = Xorshift::new(graph.len() as u64);
+            let guesses: Vec<u64> = (0..graph.len())
+                .map(|node| match to_goal(node) {
+                    Some(cost) => rng.below(cost + 1),
+                    None => rng.below(1000),
+                })
+                .collect();
+            let guided = a_star(0, edges(graph), |node| guesses[*node], is_goal);
+            if guided.map(|(cost, _)| cost) != expected {
+                return Err(format!("A* with {:?} found {:?}", guesses, guided));
+            }
+
+            // Every edge costs one step for the breadth first search
+            let unit: Graph = graph
+                .iter()
+                .map(|edges| edges.iter().map(|(next, _)| (*next, 1)).collect())
+                .collect();
+            let expected = floyd_warshall(&unit)[0]
+                .iter()
+                .enumerate()
+                .filter(|(goal, _)| goals[*goal])
+                .filter_map(|(_, steps)| *steps)
+                .min();
+            let found = bfs(0, steps(graph), is_goal);
+            match found.map(|(steps, _)| steps as u64) == expected {
+                true => Ok(()),
+                false => Err(format!("BFS found {:?}, not {:?}", found, expected)),
+            }
+        });
+    }
+}
@@ -0,0 +1,291 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::aoc_lib::config::InputKind;
+use crate::aoc_lib::metrics::Metrics;
+use crate::aoc_lib::reply::{find, solve, Response};
+use crate::aoc_lib::AocError;
+
+// Bigger than any puzzle input by far, a body past this is a mistake
+const MAX_BODY: usize = 16 * 1024 * 1024;
+// The request line and the headers together, a client needs a few hundred bytes
+const MAX_HEADER: u64 = 64 * 1024;
+// How long a client can stay silent before its connection is dropped
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+// The connections answered at once, the next ones wait for a free handler
+const HANDLERS: usize = 8;
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        422 => "Unprocessable Entity",
+        500 => "Internal Server Error",
+        _ => "Not Implemented",
+    }
+}
+
+// POST /solve/{year}/{day}/{part} with the input as the body, `?example` when
+// it's the one of the puzzle text, and GET /metrics for Prometheus
+pub fn route(metrics: &Metrics, method: &str, path: &str, body: &str) -> Response {
+    let (path, query) = path.split_once('?').unwrap_or((path, ""));
+    let kind = match query.split('&').any(|param| param == "example") {
+        true => InputKind::Example,
+        false => InputKind::Real,
+    };
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let (year, day, part) = match (&segments[..], method) {
+        (["solve", year, day, part], "POST") => (*year, *day, *part),
+        (["solve", _, _, _], _) => return Response::error(405, "Send the input with a POST"),
+        (["metrics"], "GET") => {
+            return Response {
+                status: 200,
+                content_type: "text/plain; version=0.0.4",
+                body: metrics.render(),
+            }
+        }
+        (["metrics"], _) => return Response::error(405, "Read the metrics with a GET"),
+        _ => return Response::error(404, &format!("Nothing at {}", path)),
+    };
+
+    match (year.parse(), day.parse(), part.parse()) {
+        (Ok(year), Ok(day), Ok(part)) => {
+            let start = Instant::now();
+            let response = solve(year, day, part, body, kind);
+            // Only the parts that exist, anyone can ask for any other
+            if find(year, day, part).is_ok() {
+                metrics.solve((year, day, part), response.status, start.elapsed());
+            }
+            response
+        }
+        _ => Response::error(
+            404,
+            &format!("\"{}/{}/{}\" is not a year, day and part", year, day, part),
+        ),
+    }
+}
+
+// One line of the head of the request, at most what is left of MAX_HEADER
+fn read_head_line(head: &mut impl BufRead, what: &str) -> Result<String, Response> {
+    let mut line = String::new();
+    head.read_line(&mut line)
+        .map_err(|_| Response::error(400, what))?;
+    if !line.ends_with('\n') && !line.is_empty() {
+        return Err(Response::error(431, "The headers are too long"));
+    }
+
+    Ok(line)
+}
+
+// The request line and the body, the only headers that matter say how long the
+// body is
+fn read_request(reader: &mut impl BufRead) -> Result<(String, String, String), Response> {
+    let bad = |what: &str| Response::error(400, what);
+
+    let mut head = reader.by_ref().take(MAX_HEADER);
+    let request_line = read_head_line(&mut head, "Can't read the request")?;
+    let mut words = request_line.split_whitespace();
+    let (Some(method), Some(path)) = (words.next(), words.next()) else {
+        return Err(bad("Not an HTTP request"));
+    };
+
+    let mut length = None;
+    loop {
+        let header = read_head_line(&mut head, "Can't read the headers")?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                let value = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| bad("Bad Content-Length"))?;
+                length = Some(value);
+            }
+        }
+    }
+
+    let body = match (method, length) {
+        (_, Some(length)) if length > MAX_BODY => {
+            return Err(Response::error(413, "That's no puzzle input"))
+        }
+        (_, Some(length)) => {
+            let mut body = vec![0; length];
+            reader
+                .read_exact(&mut body)
+                .map_err(|_| bad("The body is shorter than its Content-Length"))?;
+            String::from_utf8(body).map_err(|_| bad("The input is not UTF-8"))?
+        }
+        ("POST", None) => return Err(Response::error(411, "Missing Content-Length")),
+        (_, None) => String::new(),
+    };
+
+    Ok((method.to_string(), path.to_string(), body))
+}
+
+// Anyone can call it from a page served somewhere else, it only ever solves
+fn write_response(stream: &mut impl Write, response: &Response) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason(response.status),
+        response.content_type,
+        response.body.len(),
+        response.body
+    )?;
+    stream.flush()
+}
+
+fn handle(stream: TcpStream, metrics: &Metrics) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let response = match read_request(&mut reader) {
+        Ok((method, path, body)) => route(metrics, &method, &path, &body),
+        Err(response) => response,
+    };
+    metrics.request(response.status);
+
+    write_response(&mut &stream, &response)
+}
+
+// Answers the connections on HANDLERS threads, until the listener fails. A
+// client that goes away halfway or stays silent only loses its own answer.
+pub fn serve(listener: TcpListener) -> Result<(), AocError> {
+    let metrics = Arc::new(Metrics::default());
+    // Accepting stops while the handlers are busy and as many wait here
+    let (accepted, waiting) = mpsc::sync_channel::<TcpStream>(HANDLERS);
+    let waiting = Arc::new(Mutex::new(waiting));
+    for _ in 0..HANDLERS {
+        let (metrics, waiting) = (Arc::clone(&metrics), Arc::clone(&waiting));
+        thread::spawn(move || loop {
+            let next = waiting.lock().map(|waiting| waiting.recv());
+            let Ok(Ok(stream)) = next else {
+                break;
+            };
+            let _ = handle(stream, &metrics);
+        });
+    }
+
+    for stream in listener.incoming() {
+        if accepted.send(stream?).is_err() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes() {
+        let metrics = Metrics::default();
+        let route = |method, path| route(&metrics, method, path, "");
+        assert_eq!(route("GET", "/").status, 404);
+        assert_eq!(route("GET", "/solve/2022/1/1").status, 405);
+        assert_eq!(route("POST", "/solve/2022/one/1").status, 404);
+        assert_eq!(route("POST", "/metrics").status, 405);
+        assert_eq!(
+            route("POST", "/solve/1999/1/1"),
+            Response::error(404, "Unknown year 1999")
+        );
+
+        // Nothing asked for a part that exists
+        let response = route("GET", "/metrics");
+        assert_eq!(response.status, 200);
+        assert!(response.content_type.starts_with("text/plain"));
+        assert!(!response.body.contains("year="), "{}", response.body);
+    }
+
+    #[test]
+    fn long_headers() {
+        let read = |request: String| read_request(&mut io::Cursor::new(request));
+        let padding = "a".repeat(MAX_HEADER as usize);
+        let long_line = read(format!("GET /{} HTTP/1.1\r\n\r\n", padding));
+        assert_eq!(long_line.unwrap_err().status, 431);
+        let long_header = read(format!("GET / HTTP/1.1\r\nX: {}\r\n\r\n", padding));
+        assert_eq!(long_header.unwrap_err().status, 431);
+
+        let (method, path, body) = read(String::from("GET /metrics HTTP/1.1\r\n\r\n")).unwrap();
+        assert_eq!((&method[..], &path[..], &body[..]), ("GET", "/metrics", ""));
+    }
+
+    #[test]
+    #[cfg(feature = "year2022")]
+    fn query_ignored() {
+        let input = include_str!("../../inputs/2022/day01.example.txt");
+        let response = route(
+            &Metrics::default(),
+            "POST",
+            "/solve/2022/1/2?from=page",
+            input,
+        );
+        assert_eq!(response.status, 200, "{}", response.body);
+        assert!(response.body.contains(r#""answer":45000"#));
+    }
+
+    #[test]
+    #[cfg(feature = "year2022")]
+    fn example_query() {
+        let input = include_str!("../../inputs/2022/day15.example.txt");
+        let route = |path| route(&Metrics::default(), "POST", path, input).body;
+        assert!(route("/solve/2022/15/1?from=page&example").contains(r#""answer":26"#));
+        assert!(route("/solve/2022/15/1").contains(r#""answer":0"#));
+    }
+
+    #[test]
+    #[cfg(feature = "year2022")]
+    fn over_tcp() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || serve(listener));
+
+        let ask = |request: &str| {
+            let mut stream = TcpStream::connect(address).unwrap();
+            stream.write_all(request.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let input = include_str!("../../inputs/2022/day01.example.txt");
+        let response = ask(&format!(
+            "POST /solve/2022/1/1 HTTP/1.1\r\nHost: x\r\ncontent-length: {}\r\n\r\n{}",
+            input.len(),
+            input
+        ));
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        assert!(response.contains(r#""answer":24000"#), "{}", response);
+
+        let response = ask("POST /solve/2022/1/1 HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 411 "), "{}", response);
+
+        let response = ask("GET /metrics HTTP/1.1\r\n\r\n");
+        assert!(
+            response.contains("Content-Type: text/plain"),
+            "{}",
+            response
+        );
+        for counted in [
+            r#"aoc_requests_total{status="200"} 1"#,
+            r#"aoc_requests_total{status="411"} 1"#,
+            r#"aoc_solves_total{year="2022",day="1",part="1"} 1"#,
+            r#"aoc_solve_duration_seconds_count{year="2022",day="1",part="1"} 1"#,
+        ] {
+            assert!(response.contains(counted), "{}", response);
+        }
+    }
+}
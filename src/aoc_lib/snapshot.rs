@@ -0,0 +1,81 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+// Where the accepted snapshots live, one file each
+fn snapshot_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("snapshots")
+        .join(format!("{}.snap", name))
+}
+
+// The lines that differ, numbered, as "-" for the snapshot and "+" for the
+// new render
+fn diff(expected: &str, actual: &str) -> String {
+    let (expected, actual): (Vec<&str>, Vec<&str>) =
+        (expected.lines().collect(), actual.lines().collect());
+    let mut output = String::new();
+    for line in 0..expected.len().max(actual.len()) {
+        let (old, new) = (expected.get(line), actual.get(line));
+        if old == new {
+            continue;
+        }
+        if let Some(old) = old {
+            output += &format!("{:4} - {}\n", line + 1, old);
+        }
+        if let Some(new) = new {
+            output += &format!("{:4} + {}\n", line + 1, new);
+        }
+    }
+
+    output
+}
+
+// Compares a render with the accepted one in snapshots/<name>.snap. A new or
+// changed render is written next to it as <name>.snap.new for review, and
+// UPDATE_SNAPSHOTS=1 accepts it instead.
+pub fn assert_snapshot(name: &str, actual: &str) {
+    let path = snapshot_path(name);
+    if env::var("UPDATE_SNAPSHOTS").as_deref() == Ok("1") {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let pending = path.with_extension("snap.new");
+    match fs::read_to_string(&path) {
+        Ok(expected) if expected.replace("\r\n", "\n") == actual => {
+            let _ = fs::remove_file(pending);
+        }
+        Ok(expected) => {
+            fs::write(&pending, actual).unwrap();
+            panic!(
+                "Render \"{}\" changed, new version in {}\n{}",
+                name,
+                pending.display(),
+                diff(&expected, actual)
+            );
+        }
+        Err(_) => {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&pending, actual).unwrap();
+            panic!(
+                "No snapshot for \"{}\" yet, review {} or rerun with UPDATE_SNAPSHOTS=1",
+                name,
+                pending.display()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changed_lines() {
+        assert_eq!(diff("a\nb\nc", "a\nx\nc"), "   2 - b\n   2 + x\n");
+        assert_eq!(diff("a", "a\nb"), "   2 + b\n");
+        assert_eq!(diff("a\nb", "a\nb"), "");
+    }
+}
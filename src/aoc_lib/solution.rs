@@ -0,0 +1,238 @@
+use std::time::Duration;
+
+use crate::aoc_lib::clock::Instant;
+use crate::aoc_lib::config::InputKind;
+use crate::aoc_lib::error::expect_input;
+use crate::aoc_lib::{fmt_dur, Answer, AocError, DaySpec, Solved};
+
+// A day split in reading the input and solving the two parts on what was read,
+// so the input is parsed once and the two phases can be timed separately
+pub trait Solution {
+    type Parsed;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError>;
+
+    fn part1(parsed: &Self::Parsed) -> Answer;
+    fn part2(parsed: &Self::Parsed) -> Answer;
+}
+
+// The same split for the days whose parsed input borrows from the input, names
+// and words kept as slices of it instead of copies. Both parts run on the input
+// the runner read, which lives until they are done.
+pub trait SolutionBorrowed<'a> {
+    type Parsed;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, AocError>;
+
+    fn part1(parsed: &Self::Parsed) -> Answer;
+    fn part2(parsed: &Self::Parsed) -> Answer;
+}
+
+// What a part answered and how long the two phases took
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Timed {
+    pub answer: Answer,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+fn timed<P>(
+    parse: impl FnOnce() -> Result<P, AocError>,
+    part: impl FnOnce(&P) -> Answer,
+) -> Result<Timed, AocError> {
+    let parse_start = Instant::now();
+    let parsed = parse()?;
+    let parse_time = parse_start.elapsed();
+
+    let solve_start = Instant::now();
+    let answer = part(&parsed);
+    let solve_time = solve_start.elapsed();
+
+    Ok(Timed {
+        answer,
+        parse_time,
+        solve_time,
+    })
+}
+
+pub fn solve_timed<S: Solution>(
+    input: &str,
+    part: fn(&S::Parsed) -> Answer,
+) -> Result<Timed, AocError> {
+    timed(|| S::parse(input), part)
+}
+
+pub fn solve_timed_borrowed<'a, S: SolutionBorrowed<'a>>(
+    input: &'a str,
+    part: fn(&S::Parsed) -> Answer,
+) -> Result<Timed, AocError> {
+    timed(|| S::parse(input), part)
+}
+
+// Both answers from a single parse, for the tests
+pub fn answers<S: Solution>(input: &str) -> Result<(Answer, Answer), AocError> {
+    let parsed = S::parse(input)?;
+
+    Ok((S::part1(&parsed), S::part2(&parsed)))
+}
+
+pub fn answers_borrowed<'a, S: SolutionBorrowed<'a>>(
+    input: &'a str,
+) -> Result<(Answer, Answer), AocError> {
+    let parsed = S::parse(input)?;
+
+    Ok((S::part1(&parsed), S::part2(&parsed)))
+}
+
+// The answer, with how long the two phases took as a note
+fn with_timings(timed: Timed) -> Solved {
+    Solved::from(timed.answer).note(format!(
+        "Parsing took {}, solving took {}",
+        fmt_dur(timed.parse_time),
+        fmt_dur(timed.solve_time)
+    ))
+}
+
+pub fn part1<S: Solution>(input: &str, _kind: InputKind) -> Result<Solved, AocError> {
+    expect_input(input)?;
+    solve_timed::<S>(input, S::part1).map(with_timings)
+}
+
+pub fn part2<S: Solution>(input: &str, _kind: InputKind) -> Result<Solved, AocError> {
+    expect_input(input)?;
+    solve_timed::<S>(input, S::part2).map(with_timings)
+}
+
+pub fn part1_borrowed<S: for<'a> SolutionBorrowed<'a>>(
+    input: &str,
+    _kind: InputKind,
+) -> Result<Solved, AocError> {
+    expect_input(input)?;
+    solve_timed_borrowed::<S>(input, S::part1).map(with_timings)
+}
+
+pub fn part2_borrowed<S: for<'a> SolutionBorrowed<'a>>(
+    input: &str,
+    _kind: InputKind,
+) -> Result<Solved, AocError> {
+    expect_input(input)?;
+    solve_timed_borrowed::<S>(input, S::part2).map(with_timings)
+}
+
+// The two `DayFn`s of a `Solution`, what `aoc_day!(YEAR, DAY, solution = ...)`
+// registers. A `Solution` solves the example like any other input.
+pub const fn day<S: Solution>() -> DaySpec {
+    (Some(part1::<S>), Some(part2::<S>))
+}
+
+// Same for a `SolutionBorrowed`, `aoc_day!(YEAR, DAY, solution_borrowed = ...)`
+pub const fn day_borrowed<S: for<'a> SolutionBorrowed<'a>>() -> DaySpec {
+    (Some(part1_borrowed::<S>), Some(part2_borrowed::<S>))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Parsed = Vec<u64>;
+
+        fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+            input
+                .split_whitespace()
+                .map(|n| {
+                    n.parse()
+                        .map_err(|_| AocError::Parse(format!("\"{}\" is not a number", n)))
+                })
+                .collect()
+        }
+
+        fn part1(numbers: &Self::Parsed) -> Answer {
+            Answer::from(numbers.iter().sum::<u64>())
+        }
+
+        fn part2(numbers: &Self::Parsed) -> Answer {
+            match numbers.iter().max() {
+                Some(largest) => Answer::from(*largest),
+                None => Answer::from("No numbers at all"),
+            }
+        }
+    }
+
+    // The words stay in the input
+    struct Words;
+
+    impl<'a> SolutionBorrowed<'a> for Words {
+        type Parsed = Vec<&'a str>;
+
+        fn parse(input: &'a str) -> Result<Self::Parsed, AocError> {
+            Ok(input.split_whitespace().collect())
+        }
+
+        fn part1(words: &Self::Parsed) -> Answer {
+            Answer::from(words.len())
+        }
+
+        fn part2(words: &Self::Parsed) -> Answer {
+            match words.iter().max_by_key(|word| word.len()) {
+                Some(longest) => Answer::from(*longest),
+                None => Answer::from("No words at all"),
+            }
+        }
+    }
+
+    #[test]
+    fn two_phases() {
+        let timed = solve_timed::<Sum>("1 2 3", Sum::part1).unwrap();
+        assert_eq!(timed.answer, Answer::UInt(6));
+        assert_eq!(
+            solve_timed::<Sum>("1 2 3", Sum::part2).unwrap().answer,
+            Answer::UInt(3)
+        );
+        assert_eq!(
+            solve_timed::<Sum>("1 two 3", Sum::part1)
+                .unwrap_err()
+                .to_string(),
+            "Can't read the input: \"two\" is not a number"
+        );
+
+        assert_eq!(
+            answers::<Sum>("").unwrap(),
+            (Answer::UInt(0), Answer::from("No numbers at all"))
+        );
+
+        // The adapters are plain day functions
+        let (first, second) = day::<Sum>();
+        let solved = first.unwrap()("4 5", InputKind::Real).unwrap();
+        assert_eq!(solved.answer, Answer::UInt(9));
+        assert!(solved.notes[0].starts_with("Parsing took "));
+        assert!(matches!(
+            second.unwrap()("x", InputKind::Example),
+            Err(AocError::Parse(_))
+        ));
+        // `answers` is fine with no numbers, the day isn't
+        let empty = first.unwrap()("", InputKind::Real).unwrap_err();
+        assert_eq!(empty.to_string(), "Can't read the input: it's empty");
+    }
+    #[test]
+    fn borrowed_input() {
+        let input = String::from("a quick red fox");
+        let parsed = Words::parse(&input).unwrap();
+        assert_eq!(parsed[1].as_ptr(), input[2..].as_ptr());
+
+        assert_eq!(
+            answers_borrowed::<Words>(&input).unwrap(),
+            (Answer::UInt(4), Answer::from("quick"))
+        );
+        let timed = solve_timed_borrowed::<Words>(&input, Words::part2).unwrap();
+        assert_eq!(timed.answer, "quick");
+
+        let (first, _) = day_borrowed::<Words>();
+        assert_eq!(
+            first.unwrap()(&input, InputKind::Real).unwrap().answer,
+            Answer::UInt(4)
+        );
+    }
+}
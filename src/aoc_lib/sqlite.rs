@@ -0,0 +1,377 @@
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+use std::path::Path;
+use std::process::Command;
+use std::ptr;
+use std::time::Duration;
+
+use crate::aoc_lib::report::Timings;
+
+// The answers and timings of runs, appended to a SQLite database for --record,
+// one row per part:
+//
+//     CREATE TABLE runs (
+//         id          INTEGER PRIMARY KEY,
+//         recorded_at TEXT NOT NULL,    -- UTC, like 2023-12-01T05:00:00.000Z
+//         git_commit  TEXT,             -- HEAD of the checkout, NULL outside one
+//         kind        TEXT NOT NULL,    -- 'run', 'bench' or 'verify'
+//         year        INTEGER NOT NULL,
+//         day         INTEGER NOT NULL,
+//         part        INTEGER NOT NULL,
+//         solver      TEXT NOT NULL,    -- 'default' or the name of a variant
+//         input       TEXT NOT NULL,    -- 'real', 'example', a file or how it was generated
+//         answer      TEXT,             -- NULL when the part failed
+//         error       TEXT,             -- why it failed, NULL when it didn't
+//         runs        INTEGER NOT NULL, -- how many times a bench ran it, 1 otherwise
+//         micros      INTEGER NOT NULL  -- how long it took, the mean of a bench
+//     );
+//
+// Columns are only ever added at the end, so queries written for an older
+// database keep working. Links to the system's libsqlite3, there is no SQLite
+// crate among the dependencies.
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY,
+    recorded_at TEXT NOT NULL,
+    git_commit TEXT,
+    kind TEXT NOT NULL,
+    year INTEGER NOT NULL,
+    day INTEGER NOT NULL,
+    part INTEGER NOT NULL,
+    solver TEXT NOT NULL,
+    input TEXT NOT NULL,
+    answer TEXT,
+    error TEXT,
+    runs INTEGER NOT NULL,
+    micros INTEGER NOT NULL
+)";
+
+const INSERT: &str = "INSERT INTO runs (recorded_at, git_commit, kind, year, day, part,
+    solver, input, answer, error, runs, micros)
+    VALUES (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'), ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
+
+const LATEST: &str = "SELECT year, day, part, micros FROM runs WHERE id IN (
+    SELECT max(id) FROM runs
+    WHERE kind = 'run' AND solver = 'default' AND input = 'real' AND error IS NULL
+    GROUP BY year, day, part)";
+
+#[repr(C)]
+struct Handle {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+struct Statement {
+    _private: [u8; 0],
+}
+
+const SQLITE_OK: c_int = 0;
+const SQLITE_ROW: c_int = 100;
+const SQLITE_DONE: c_int = 101;
+const SQLITE_OPEN_READWRITE: c_int = 0x2;
+const SQLITE_OPEN_CREATE: c_int = 0x4;
+// Has SQLite copy the text it's given, SQLITE_TRANSIENT in sqlite3.h
+const TRANSIENT: isize = -1;
+// check-examples runs the examples in parallel, each one writing its own rows
+const BUSY_MILLIS: c_int = 10_000;
+
+#[link(name = "sqlite3")]
+extern "C" {
+    fn sqlite3_open_v2(
+        filename: *const c_char,
+        handle: *mut *mut Handle,
+        flags: c_int,
+        vfs: *const c_char,
+    ) -> c_int;
+    fn sqlite3_close(handle: *mut Handle) -> c_int;
+    fn sqlite3_errmsg(handle: *mut Handle) -> *const c_char;
+    fn sqlite3_busy_timeout(handle: *mut Handle, millis: c_int) -> c_int;
+    fn sqlite3_exec(
+        handle: *mut Handle,
+        sql: *const c_char,
+        callback: *const c_void,
+        argument: *mut c_void,
+        error: *mut *mut c_char,
+    ) -> c_int;
+    fn sqlite3_prepare_v2(
+        handle: *mut Handle,
+        sql: *const c_char,
+        length: c_int,
+        statement: *mut *mut Statement,
+        tail: *mut *const c_char,
+    ) -> c_int;
+    fn sqlite3_bind_int64(statement: *mut Statement, index: c_int, value: i64) -> c_int;
+    fn sqlite3_bind_null(statement: *mut Statement, index: c_int) -> c_int;
+    fn sqlite3_bind_text(
+        statement: *mut Statement,
+        index: c_int,
+        text: *const c_char,
+        length: c_int,
+        destructor: isize,
+    ) -> c_int;
+    fn sqlite3_step(statement: *mut Statement) -> c_int;
+    fn sqlite3_column_count(statement: *mut Statement) -> c_int;
+    fn sqlite3_column_int64(statement: *mut Statement, column: c_int) -> i64;
+    fn sqlite3_finalize(statement: *mut Statement) -> c_int;
+}
+
+// A value for one of the question marks of a statement
+enum Bind<'a> {
+    Int(i64),
+    Text(Option<&'a str>),
+}
+
+// One part that ran, as it goes in the table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run<'a> {
+    pub kind: &'a str,
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub solver: &'a str,
+    pub input: &'a str,
+    // The answer, or why there is none
+    pub answer: Result<String, String>,
+    pub runs: u64,
+    pub took: Duration,
+}
+
+pub struct Database {
+    handle: *mut Handle,
+    commit: Option<String>,
+}
+
+// SQLite serializes the calls on a connection itself, in its default threading
+// mode
+unsafe impl Send for Database {}
+
+// The commit checked out where the runs happen, so they can be compared across
+// commits
+fn current_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+    let commit = String::from_utf8(output.stdout).ok()?;
+    (output.status.success() && !commit.trim().is_empty()).then(|| commit.trim().to_string())
+}
+
+fn text(text: &str) -> Result<CString, String> {
+    CString::new(text).map_err(|_| format!("\"{}\" has a NUL inside", text.escape_default()))
+}
+
+impl Database {
+    // Opens the database at `path`, creating it and the table when they are not
+    // there yet
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let name = text(&path.to_string_lossy())?;
+        let mut handle = ptr::null_mut();
+        let flags = SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE;
+        let opened = unsafe { sqlite3_open_v2(name.as_ptr(), &mut handle, flags, ptr::null()) };
+
+        // Even a failed open gives a handle, to read the error from and close
+        let database = Database {
+            handle,
+            commit: current_commit(),
+        };
+        if opened != SQLITE_OK {
+            return Err(database.failure(&format!("Can't open {}", path.display())));
+        }
+        unsafe { sqlite3_busy_timeout(handle, BUSY_MILLIS) };
+        database.execute(SCHEMA)?;
+
+        Ok(database)
+    }
+
+    fn failure(&self, what: &str) -> String {
+        if self.handle.is_null() {
+            return String::from(what);
+        }
+        let message = unsafe { CStr::from_ptr(sqlite3_errmsg(self.handle)) };
+        format!("{}: {}", what, message.to_string_lossy())
+    }
+
+    fn execute(&self, sql: &str) -> Result<(), String> {
+        let sql = text(sql)?;
+        let executed = unsafe {
+            sqlite3_exec(
+                self.handle,
+                sql.as_ptr(),
+                ptr::null(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+            )
+        };
+        match executed {
+            SQLITE_OK => Ok(()),
+            _ => Err(self.failure("Can't set up the database")),
+        }
+    }
+
+    // Runs `sql` with `binds` in place of its question marks, and gives the
+    // rows it returns, all numbers
+    fn run(&self, sql: &str, binds: &[Bind]) -> Result<Vec<Vec<i64>>, String> {
+        let sql = text(sql)?;
+        let mut statement = ptr::null_mut();
+        let prepared = unsafe {
+            sqlite3_prepare_v2(
+                self.handle,
+                sql.as_ptr(),
+                -1,
+                &mut statement,
+                ptr::null_mut(),
+            )
+        };
+        if prepared != SQLITE_OK {
+            return Err(self.failure("Can't prepare the statement"));
+        }
+
+        let result = unsafe { self.bind_and_step(statement, binds) };
+        unsafe { sqlite3_finalize(statement) };
+        result
+    }
+
+    unsafe fn bind_and_step(
+        &self,
+        statement: *mut Statement,
+        binds: &[Bind],
+    ) -> Result<Vec<Vec<i64>>, String> {
+        for (index, bind) in binds.iter().enumerate() {
+            let index = index as c_int + 1;
+            let bound = match bind {
+                Bind::Int(value) => sqlite3_bind_int64(statement, index, *value),
+                Bind::Text(None) => sqlite3_bind_null(statement, index),
+                Bind::Text(Some(value)) => {
+                    let length = c_int::try_from(value.len()).map_err(|_| "Text too long")?;
+                    let value = value.as_ptr() as *const c_char;
+                    sqlite3_bind_text(statement, index, value, length, TRANSIENT)
+                }
+            };
+            if bound != SQLITE_OK {
+                return Err(self.failure("Can't bind a value"));
+            }
+        }
+
+        let mut rows = Vec::new();
+        loop {
+            match sqlite3_step(statement) {
+                SQLITE_ROW => {
+                    let columns = 0..sqlite3_column_count(statement);
+                    let row = columns.map(|column| sqlite3_column_int64(statement, column));
+                    rows.push(row.collect());
+                }
+                SQLITE_DONE => return Ok(rows),
+                _ => return Err(self.failure("Can't record the run")),
+            }
+        }
+    }
+
+    pub fn record(&self, run: &Run) -> Result<(), String> {
+        let (answer, error) = match &run.answer {
+            Ok(answer) => (Some(answer.as_str()), None),
+            Err(error) => (None, Some(error.as_str())),
+        };
+        let micros = i64::try_from(run.took.as_micros()).unwrap_or(i64::MAX);
+        self.run(
+            INSERT,
+            &[
+                Bind::Text(self.commit.as_deref()),
+                Bind::Text(Some(run.kind)),
+                Bind::Int(i64::from(run.year)),
+                Bind::Int(i64::from(run.day)),
+                Bind::Int(i64::from(run.part)),
+                Bind::Text(Some(run.solver)),
+                Bind::Text(Some(run.input)),
+                Bind::Text(answer),
+                Bind::Text(error),
+                Bind::Int(run.runs as i64),
+                Bind::Int(micros),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    // How long the default solvers took on the real inputs, the last time they
+    // got an answer, for the report
+    pub fn timings(&self) -> Result<Timings, String> {
+        let rows = self.run(LATEST, &[])?;
+        let timings = rows.into_iter().filter_map(|row| match row[..] {
+            [year, day, part, micros] => Some((
+                (
+                    u16::try_from(year).ok()?,
+                    u8::try_from(day).ok()?,
+                    u8::try_from(part).ok()?,
+                ),
+                Duration::from_micros(u64::try_from(micros).ok()?),
+            )),
+            _ => None,
+        });
+        Ok(timings.collect())
+    }
+}
+
+impl Drop for Database {
+    fn drop(&mut self) {
+        unsafe { sqlite3_close(self.handle) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn appends() {
+        let path = env::temp_dir().join(format!("aoc-runs-{}.sqlite", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut run = Run {
+            kind: "run",
+            year: 2022,
+            day: 1,
+            part: 2,
+            solver: "default",
+            input: "example",
+            answer: Ok(String::from("45000")),
+            runs: 1,
+            took: Duration::from_micros(120),
+        };
+        Database::open(&path).unwrap().record(&run).unwrap();
+
+        // A second session adds to the first
+        let database = Database::open(&path).unwrap();
+        run.answer = Err(String::from("No 'answer' here"));
+        database.record(&run).unwrap();
+        let count = |sql: &str| database.run(sql, &[]).unwrap();
+        assert_eq!(count("SELECT count(*) FROM runs"), [[2]]);
+        assert_eq!(
+            count("SELECT micros FROM runs WHERE answer = '45000'"),
+            [[120]]
+        );
+        assert_eq!(
+            count("SELECT id FROM runs WHERE error = 'No ''answer'' here'"),
+            [[2]]
+        );
+        assert_eq!(
+            count("SELECT count(*) FROM runs WHERE recorded_at LIKE '20%Z'"),
+            [[2]]
+        );
+
+        // Only the last run of a default solver on the real input has a time
+        assert!(database.timings().unwrap().is_empty());
+        run.input = "real";
+        run.answer = Ok(String::from("45000"));
+        database.record(&run).unwrap();
+        run.took = Duration::from_micros(90);
+        database.record(&run).unwrap();
+        let timings = database.timings().unwrap();
+        assert_eq!(timings.len(), 1);
+        assert_eq!(timings[&(2022, 1, 2)], Duration::from_micros(90));
+
+        drop(database);
+        fs::remove_file(&path).unwrap();
+        assert!(Database::open(Path::new("/nowhere/at/all.sqlite")).is_err());
+    }
+}
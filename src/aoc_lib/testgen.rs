@@ -0,0 +1,526 @@
+use itertools::Itertools;
+
+use hashbrown::HashSet;
+
+use crate::aoc_lib::geometry::{Point3, Rotation};
+use crate::aoc_lib::random::Xorshift;
+
+// Makes up an input of roughly `size` units, what a unit is depends on the day
+pub type GenerateFn = fn(&mut Xorshift, usize) -> String;
+
+// Synthetic inputs for a day, for stress tests and benchmarks bigger (or
+// smaller) than the official input
+#[derive(Debug, Clone, Copy)]
+pub struct Generator {
+    pub year: u16,
+    pub day: u8,
+    // What the size counts, for the help messages
+    pub unit: &'static str,
+    pub generate: GenerateFn,
+}
+
+pub const GENERATORS: &[Generator] = &[
+    Generator {
+        year: 2021,
+        day: 19,
+        unit: "scanners",
+        generate: scanners,
+    },
+    Generator {
+        year: 2021,
+        day: 22,
+        unit: "reboot steps",
+        generate: reboot_steps,
+    },
+    Generator {
+        year: 2021,
+        day: 23,
+        unit: "amphipods per room",
+        generate: burrow,
+    },
+    Generator {
+        year: 2022,
+        day: 6,
+        unit: "characters",
+        generate: datastream,
+    },
+    Generator {
+        year: 2022,
+        day: 7,
+        unit: "directories",
+        generate: terminal,
+    },
+    Generator {
+        year: 2022,
+        day: 8,
+        unit: "trees per side",
+        generate: forest,
+    },
+    Generator {
+        year: 2022,
+        day: 12,
+        unit: "squares per side",
+        generate: heightmap,
+    },
+    Generator {
+        year: 2022,
+        day: 14,
+        unit: "rock paths",
+        generate: rock_paths,
+    },
+    Generator {
+        year: 2022,
+        day: 16,
+        unit: "valves",
+        generate: volcano,
+    },
+    Generator {
+        year: 2022,
+        day: 21,
+        unit: "monkeys",
+        generate: monkeys,
+    },
+];
+
+pub fn find(year: u16, day: u8) -> Option<&'static Generator> {
+    GENERATORS
+        .iter()
+        .find(|generator| generator.year == year && generator.day == day)
+}
+
+// The same year, day, size and seed always give the same input
+pub fn generate(year: u16, day: u8, size: usize, seed: u64) -> Result<String, String> {
+    let Some(generator) = find(year, day) else {
+        let known = GENERATORS
+            .iter()
+            .map(|g| format!("{} day {} ({})", g.year, g.day, g.unit))
+            .join(", ");
+        return Err(format!(
+            "No input generator for {} day {}, try one of: {}",
+            year, day, known
+        ));
+    };
+
+    Ok((generator.generate)(&mut Xorshift::new(seed), size))
+}
+
+// 2021 day 19: every scanner but the first put next to one before it, close
+// enough to share at least twelve beacons, with the beacons around it topped
+// up to 26. Each reports the beacons within 1000 on every axis, turned by one of
+// the 24 rotations.
+pub fn scanners(rng: &mut Xorshift, size: usize) -> String {
+    let count = size.max(2);
+    let rotations = Rotation::all();
+    let within = |scanner: Point3, beacon: &Point3| {
+        let offset = *beacon - scanner;
+        offset.x.abs() <= 1000 && offset.y.abs() <= 1000 && offset.z.abs() <= 1000
+    };
+    // A random point in the box between the corners, both included
+    let pick = |rng: &mut Xorshift, low: Point3, high: Point3| {
+        Point3::new(
+            rng.range(low.x..=high.x),
+            rng.range(low.y..=high.y),
+            rng.range(low.z..=high.z),
+        )
+    };
+    let reach = Point3::new(1000, 1000, 1000);
+
+    let mut positions = vec![Point3::default()];
+    let mut beacons: HashSet<Point3> = HashSet::new();
+    for id in 0..count {
+        let scanner = match id {
+            0 => Point3::default(),
+            _ => {
+                let anchor = positions[rng.below(id as u64) as usize];
+                let step = Point3::new(500, 500, 500);
+                let scanner = pick(rng, anchor - step, anchor + step);
+                positions.push(scanner);
+
+                let low = Point3::new(
+                    anchor.x.max(scanner.x),
+                    anchor.y.max(scanner.y),
+                    anchor.z.max(scanner.z),
+                ) - reach;
+                let high = Point3::new(
+                    anchor.x.min(scanner.x),
+                    anchor.y.min(scanner.y),
+                    anchor.z.min(scanner.z),
+                ) + reach;
+                while beacons
+                    .iter()
+                    .filter(|beacon| within(anchor, beacon) && within(scanner, beacon))
+                    .count()
+                    < 12
+                {
+                    beacons.insert(pick(rng, low, high));
+                }
+                scanner
+            }
+        };
+        while beacons
+            .iter()
+            .filter(|beacon| within(scanner, beacon))
+            .count()
+            < 26
+        {
+            beacons.insert(pick(rng, scanner - reach, scanner + reach));
+        }
+    }
+
+    positions
+        .iter()
+        .enumerate()
+        .map(|(id, scanner)| {
+            let rotation = rotations[rng.below(rotations.len() as u64) as usize];
+            let seen = beacons
+                .iter()
+                .filter(|beacon| within(*scanner, beacon))
+                .sorted()
+                .map(|beacon| {
+                    let seen = rotation.apply(*beacon - *scanner);
+                    format!("{},{},{}", seen.x, seen.y, seen.z)
+                })
+                .join("\n");
+            format!("--- scanner {} ---\n{}", id, seen)
+        })
+        .join("\n\n")
+}
+
+// 2021 day 22: steps spread on a region that grows with their number, so they
+// keep overlapping. One in four sits inside the initialization area.
+pub fn reboot_steps(rng: &mut Xorshift, size: usize) -> String {
+    let spread = 10 * size.max(1) as i64;
+    (0..size)
+        .map(|_| {
+            let power = if rng.below(3) == 0 { "off" } else { "on" };
+            let (reach, length) = match rng.below(4) {
+                0 => (50, 25),
+                _ => (spread, spread / 2),
+            };
+            let mut axis = |name: char| {
+                let start = rng.range(-reach..=reach - 1);
+                let end = (start + rng.range(0..=length)).min(reach);
+                format!("{}={}..{}", name, start, end)
+            };
+            format!("{} {},{},{}", power, axis('x'), axis('y'), axis('z'))
+        })
+        .join("\n")
+}
+
+// 2021 day 23: every room filled, the amphipods shuffled between them
+pub fn burrow(rng: &mut Xorshift, size: usize) -> String {
+    let depth = size.max(1);
+    let mut amphipods = ['A', 'B', 'C', 'D'].repeat(depth);
+    for last in (1..amphipods.len()).rev() {
+        amphipods.swap(last, rng.below(last as u64 + 1) as usize);
+    }
+
+    let mut lines = vec![String::from("#############"), String::from("#...........#")];
+    for (row, cells) in amphipods.chunks(4).enumerate() {
+        let (outside, inside) = match row {
+            0 => ("###", "###"),
+            _ => ("  #", "#"),
+        };
+        lines.push(format!("{}{}{}", outside, cells.iter().join("#"), inside));
+    }
+    lines.push(String::from("  #########"));
+
+    lines.join("\n")
+}
+
+// 2022 day 6: letters picked among three, so no four in a row ever differ,
+// with the whole alphabet shuffled at the end for both markers to be there
+pub fn datastream(rng: &mut Xorshift, size: usize) -> String {
+    let mut alphabet: Vec<char> = ('a'..='z').collect();
+    for last in (1..alphabet.len()).rev() {
+        alphabet.swap(last, rng.below(last as u64 + 1) as usize);
+    }
+
+    let noise = size.saturating_sub(alphabet.len());
+    (0..noise)
+        .map(|_| alphabet[rng.below(3) as usize])
+        .chain(alphabet.iter().copied())
+        .collect()
+}
+
+// 2022 day 8: a square of trees of any height
+pub fn forest(rng: &mut Xorshift, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| char::from(b'0' + rng.below(10) as u8))
+                .collect::<String>()
+        })
+        .join("\n")
+}
+
+// 2022 day 12: a square at least 26 wide going up a letter at a time from the
+// start on the left edge to the top in the middle of the right one, the same
+// distance away. One square in eight sinks lower, but never on the row of the
+// start and the top, so the climb is always there.
+pub fn heightmap(rng: &mut Xorshift, size: usize) -> String {
+    let side = size.max(26);
+    let middle = side / 2;
+    (0..side)
+        .map(|y| {
+            (0..side)
+                .map(|x| {
+                    let distance = (side - 1 - x) + y.abs_diff(middle);
+                    let height = 25 - (distance * 25 / (side - 1)).min(25);
+                    let height = match y != middle && rng.below(8) == 0 {
+                        true => rng.below(height as u64 + 1) as usize,
+                        false => height,
+                    };
+                    match (x, y == middle) {
+                        (0, true) => 'S',
+                        (x, true) if x == side - 1 => 'E',
+                        _ => char::from(b'a' + height as u8),
+                    }
+                })
+                .collect::<String>()
+        })
+        .join("\n")
+}
+
+// Lowercase names for the ids, four letters from "aaaa" on. The first letter
+// stays before 'h', so no name is ever "humn" or "root".
+fn lowercase_name(id: usize) -> String {
+    [id / 17576 % 7, id / 676 % 26, id / 26 % 26, id % 26]
+        .iter()
+        .map(|letter| char::from(b'a' + *letter as u8))
+        .collect()
+}
+
+// 2022 day 7: the listing of a whole disk, every directory below one picked at
+// random among the ones before it so the tree is a few dozen levels deep at
+// most, each with up to three files. The directories are visited depth first,
+// listed once and left with "cd ..".
+pub fn terminal(rng: &mut Xorshift, size: usize) -> String {
+    let count = size.max(1);
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); count];
+    for directory in 1..count {
+        children[rng.below(directory as u64) as usize].push(directory);
+    }
+
+    let mut lines = vec![String::from("$ cd /")];
+    // A directory to list, or None to go back up from one
+    let mut stack = vec![Some(0)];
+    while let Some(visit) = stack.pop() {
+        let Some(directory) = visit else {
+            lines.push(String::from("$ cd .."));
+            continue;
+        };
+        if directory != 0 {
+            lines.push(format!("$ cd {}", lowercase_name(directory)));
+        }
+        lines.push(String::from("$ ls"));
+        for child in &children[directory] {
+            lines.push(format!("dir {}", lowercase_name(*child)));
+        }
+        for file in 0..rng.below(4) {
+            let size = rng.range(1000..=300_000);
+            lines.push(format!("{} {}.txt", size, lowercase_name(file as usize)));
+        }
+        for child in children[directory].iter().rev() {
+            stack.push(None);
+            stack.push(Some(*child));
+        }
+    }
+
+    lines.join("\n")
+}
+
+// 2022 day 21: a balanced tree of sums, differences and a few products with
+// the human on a random leaf, under a root whose other side is a single monkey
+// with the same number. Every number is known while building, so the human's
+// number comes back out exactly, and the products stay far from overflowing.
+pub fn monkeys(rng: &mut Xorshift, size: usize) -> String {
+    fn build(
+        rng: &mut Xorshift,
+        leaves: usize,
+        human: &mut Option<usize>,
+        jobs: &mut Vec<String>,
+    ) -> (String, i64) {
+        let name = lowercase_name(jobs.len());
+        jobs.push(String::new());
+        let id = jobs.len() - 1;
+
+        if leaves == 1 {
+            let value = rng.range(1..=20);
+            let name = match human.as_mut() {
+                Some(0) => {
+                    *human = None;
+                    String::from("humn")
+                }
+                Some(left) => {
+                    *left -= 1;
+                    name
+                }
+                None => name,
+            };
+            jobs[id] = format!("{}: {}", name, value);
+            return (name, value);
+        }
+
+        let (left, a) = build(rng, leaves / 2, human, jobs);
+        let (right, b) = build(rng, leaves - leaves / 2, human, jobs);
+        let small = |n: i64| n != 0 && n.abs() <= 1_000_000;
+        let (operator, value) = match rng.below(4) {
+            0 if small(a) && small(b) => ('*', a * b),
+            1 | 2 => ('+', a + b),
+            _ => ('-', a - b),
+        };
+        jobs[id] = format!("{}: {} {} {}", name, left, operator, right);
+        (name, value)
+    }
+
+    let leaves = (size / 2).max(1);
+    let mut human = Some(rng.below(leaves as u64) as usize);
+    let mut jobs = Vec::with_capacity(2 * leaves + 1);
+    let (left, value) = build(rng, leaves, &mut human, &mut jobs);
+    let right = lowercase_name(jobs.len());
+    jobs.push(format!("{}: {}", right, value));
+    jobs.push(format!("root: {} + {}", left, right));
+
+    for last in (1..jobs.len()).rev() {
+        jobs.swap(last, rng.below(last as u64 + 1) as usize);
+    }
+    jobs.join("\n")
+}
+
+// 2022 day 14: paths of one to three straight segments under the source,
+// spreading sideways and down as there are more of them. The puzzle has no
+// negative coordinates, so they stop before a path or the sand piling up on
+// the floor of part two would reach past x = 0.
+pub fn rock_paths(rng: &mut Xorshift, size: usize) -> String {
+    let width = (10 + 3 * size as i64).min(480);
+    let depth = (4 + 2 * size as i64).min(450);
+    (0..size)
+        .map(|_| {
+            let mut corner = (rng.range(500 - width..=500 + width), rng.range(2..=depth));
+            let mut path = vec![corner];
+            for _ in 0..=rng.below(3) {
+                let step = rng.range(1..=6);
+                corner = match rng.below(3) {
+                    0 => (corner.0 + step, corner.1),
+                    1 => (corner.0 - step, corner.1),
+                    _ => (corner.0, corner.1 + step),
+                };
+                path.push(corner);
+            }
+            path.iter()
+                .map(|(x, y)| format!("{},{}", x, y))
+                .join(" -> ")
+        })
+        .join("\n")
+}
+
+// 2022 day 16: a tree of tunnels from AA with a few shortcuts, a third of the
+// valves stuck at zero like AA itself. At most 676 valves, the two letter names
+// run out after that.
+pub fn volcano(rng: &mut Xorshift, size: usize) -> String {
+    let count = size.clamp(2, 26 * 26);
+    let name = |id: usize| {
+        [id / 26, id % 26]
+            .iter()
+            .map(|letter| char::from(b'A' + *letter as u8))
+            .collect::<String>()
+    };
+
+    let mut tunnels: Vec<Vec<usize>> = vec![Vec::new(); count];
+    let shortcuts = rng.below(count as u64 / 2 + 1) as usize;
+    for valve in 1..count + shortcuts {
+        let (from, to) = match valve < count {
+            true => (valve, rng.below(valve as u64) as usize),
+            false => (
+                rng.below(count as u64) as usize,
+                rng.below(count as u64) as usize,
+            ),
+        };
+        if from != to && !tunnels[from].contains(&to) {
+            tunnels[from].push(to);
+            tunnels[to].push(from);
+        }
+    }
+
+    (0..count)
+        .map(|valve| {
+            let flow = match (valve, rng.below(3)) {
+                (0, _) | (_, 0) => 0,
+                _ => rng.below(25) + 1,
+            };
+            let exits = tunnels[valve].iter().map(|exit| name(*exit)).join(", ");
+            let tunnels = match tunnels[valve].len() {
+                1 => "tunnel leads to valve",
+                _ => "tunnels lead to valves",
+            };
+            format!(
+                "Valve {} has flow rate={}; {} {}",
+                name(valve),
+                flow,
+                tunnels,
+                exits
+            )
+        })
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeatable() {
+        for generator in GENERATORS {
+            let (year, day) = (generator.year, generator.day);
+            assert_eq!(generate(year, day, 5, 1), generate(year, day, 5, 1));
+            assert_ne!(generate(year, day, 5, 1), generate(year, day, 5, 2));
+        }
+        assert!(generate(2021, 1, 5, 1).is_err());
+    }
+
+    #[test]
+    fn sizes() {
+        let mut rng = Xorshift::new(9);
+        assert_eq!(reboot_steps(&mut rng, 40).lines().count(), 40);
+        assert_eq!(burrow(&mut rng, 3).lines().count(), 6);
+        assert_eq!(rock_paths(&mut rng, 7).lines().count(), 7);
+        assert_eq!(volcano(&mut rng, 30).lines().count(), 30);
+        assert!(reboot_steps(&mut rng, 0).is_empty());
+
+        let listing = terminal(&mut rng, 50);
+        assert_eq!(listing.matches("\ndir ").count(), 49);
+        assert_eq!(listing.matches("$ cd ..").count(), 49);
+        assert_eq!(datastream(&mut rng, 100).len(), 100);
+        assert_eq!(forest(&mut rng, 12).lines().count(), 12);
+        assert_eq!(heightmap(&mut rng, 5).lines().count(), 26);
+        let reports = scanners(&mut rng, 6);
+        assert_eq!(reports.matches("--- scanner").count(), 6);
+        assert!(reports
+            .split("\n\n")
+            .all(|report| report.lines().count() > 26));
+
+        let troop = monkeys(&mut rng, 40);
+        assert_eq!(troop.lines().count(), 41);
+        assert_eq!(troop.matches("humn:").count(), 1);
+        assert_eq!(troop.matches("root:").count(), 1);
+    }
+
+    #[test]
+    fn rocks_right_of_the_origin() {
+        let paths = rock_paths(&mut Xorshift::new(2), 1000);
+        let mut coordinates = paths
+            .lines()
+            .flat_map(|path| path.split(" -> "))
+            .flat_map(|corner| corner.split(','));
+        assert!(coordinates.all(|c| c.parse::<u64>().is_ok()), "{}", paths);
+    }
+
+    #[test]
+    fn amphipods_per_room() {
+        let diagram = burrow(&mut Xorshift::new(4), 4);
+        for kind in ['A', 'B', 'C', 'D'] {
+            assert_eq!(diagram.matches(kind).count(), 4, "{}", diagram);
+        }
+    }
+}
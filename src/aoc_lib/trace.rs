@@ -0,0 +1,468 @@
+use std::fmt;
+use std::io::{self, Write};
+
+use hashbrown::HashMap;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, multispace0};
+use nom::combinator::{all_consuming, map, value};
+use nom::multi::separated_list0;
+use nom::number::complete::double;
+use nom::sequence::{delimited, separated_pair, tuple};
+use nom::IResult;
+
+use crate::aoc_lib::jazz_parser::i64 as signed;
+
+// Something worth seeing happened while solving. The points are whatever the
+// day uses, usually x and y of a grid.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    // Something called `id` went from one point to another
+    Move {
+        id: String,
+        from: (i64, i64),
+        to: (i64, i64),
+    },
+    // A cell now holds `value`
+    SetCell {
+        at: (i64, i64),
+        value: String,
+    },
+    // A number worth following, like how many cells were visited so far
+    Metric {
+        name: String,
+        value: f64,
+    },
+}
+
+// An event and the step of the solution it happened in
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub step: u64,
+    pub event: Event,
+}
+
+// A JSON string, quotes included
+pub fn quoted(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+// JSON has no infinities and no NaN
+fn number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        String::from("null")
+    }
+}
+
+// One line of JSON, the step always comes first:
+// {"step":4,"event":"move","id":"head","from":[0,0],"to":[1,0]}
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{{\"step\":{},", self.step)?;
+        match &self.event {
+            Event::Move { id, from, to } => write!(
+                f,
+                "\"event\":\"move\",\"id\":{},\"from\":[{},{}],\"to\":[{},{}]}}",
+                quoted(id),
+                from.0,
+                from.1,
+                to.0,
+                to.1
+            ),
+            Event::SetCell { at, value } => write!(
+                f,
+                "\"event\":\"set_cell\",\"at\":[{},{}],\"value\":{}}}",
+                at.0,
+                at.1,
+                quoted(value)
+            ),
+            Event::Metric { name, value } => write!(
+                f,
+                "\"event\":\"metric\",\"name\":{},\"value\":{}}}",
+                quoted(name),
+                number(*value)
+            ),
+        }
+    }
+}
+
+// The values a trace line can hold, not the whole of JSON
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Text(String),
+    Number(f64),
+    Point(i64, i64),
+    Null,
+}
+
+pub(crate) fn json_string(input: &str) -> IResult<&str, String> {
+    let error = |input| nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Char));
+    let Some(mut rest) = input.strip_prefix('"') else {
+        return Err(error(input));
+    };
+
+    let mut text = String::new();
+    loop {
+        let mut chars = rest.chars();
+        match chars.next() {
+            None => return Err(error(rest)),
+            Some('"') => return Ok((chars.as_str(), text)),
+            Some('\\') => {
+                let escaped = match chars.next() {
+                    Some('"') => '"',
+                    Some('\\') => '\\',
+                    Some('/') => '/',
+                    Some('n') => '\n',
+                    Some('r') => '\r',
+                    Some('t') => '\t',
+                    Some('u') => {
+                        let code = chars.as_str().get(..4).ok_or_else(|| error(rest))?;
+                        let c = u32::from_str_radix(code, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| error(rest))?;
+                        chars = chars.as_str()[4..].chars();
+                        c
+                    }
+                    _ => return Err(error(rest)),
+                };
+                text.push(escaped);
+            }
+            Some(c) => text.push(c),
+        }
+        rest = chars.as_str();
+    }
+}
+
+fn json_value(input: &str) -> IResult<&str, Value> {
+    let space = |c| delimited(multispace0, char(c), multispace0);
+    alt((
+        map(json_string, Value::Text),
+        value(Value::Null, tag("null")),
+        map(
+            tuple((space('['), signed, space(','), signed, space(']'))),
+            |(_, x, _, y, _)| Value::Point(x, y),
+        ),
+        map(double, Value::Number),
+    ))(input)
+}
+
+fn json_object(input: &str) -> IResult<&str, Vec<(String, Value)>> {
+    let space = |c| delimited(multispace0, char(c), multispace0);
+    delimited(
+        space('{'),
+        separated_list0(
+            space(','),
+            separated_pair(json_string, space(':'), json_value),
+        ),
+        space('}'),
+    )(input)
+}
+
+impl Record {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let (_, fields) = all_consuming(json_object)(line.trim())
+            .map_err(|e| format!("Not a trace line: {}", e))?;
+        let fields: HashMap<String, Value> = fields.into_iter().collect();
+
+        let field = |name: &str| {
+            fields
+                .get(name)
+                .ok_or_else(|| format!("Missing \"{}\"", name))
+        };
+        let text = |name: &str| match field(name)? {
+            Value::Text(text) => Ok(text.clone()),
+            other => Err(format!("\"{}\" should be a string, not {:?}", name, other)),
+        };
+        let point = |name: &str| match field(name)? {
+            Value::Point(x, y) => Ok((*x, *y)),
+            other => Err(format!("\"{}\" should be [x, y], not {:?}", name, other)),
+        };
+
+        let step = match field("step")? {
+            Value::Number(step) if *step >= 0.0 && step.fract() == 0.0 => *step as u64,
+            other => return Err(format!("\"step\" should be a count, not {:?}", other)),
+        };
+        let event = match text("event")?.as_str() {
+            "move" => Event::Move {
+                id: text("id")?,
+                from: point("from")?,
+                to: point("to")?,
+            },
+            "set_cell" => Event::SetCell {
+                at: point("at")?,
+                value: text("value")?,
+            },
+            "metric" => Event::Metric {
+                name: text("name")?,
+                value: match field("value")? {
+                    Value::Number(value) => *value,
+                    Value::Null => f64::NAN,
+                    other => return Err(format!("\"value\" should be a number, not {:?}", other)),
+                },
+            },
+            other => return Err(format!("Unknown event \"{}\"", other)),
+        };
+
+        Ok(Record { step, event })
+    }
+}
+
+// A whole trace, blank lines are skipped
+pub fn read(trace: &str) -> Result<Vec<Record>, String> {
+    trace
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(id, line)| Record::parse(line).map_err(|e| format!("Line {}: {}", id + 1, e)))
+        .collect()
+}
+
+// Handed to the days that can be traced with --trace. A day only says what
+// happens, the recorder writes it down, and one that records nothing costs
+// close to nothing. Writing errors are kept until `finish`, so tracing a
+// solution doesn't clutter it with error handling.
+pub struct Recorder<'a> {
+    output: Option<Box<dyn Write + 'a>>,
+    step: u64,
+    written: usize,
+    error: Option<io::Error>,
+}
+
+impl<'a> Recorder<'a> {
+    pub fn new(output: impl Write + 'a) -> Self {
+        Self {
+            output: Some(Box::new(output)),
+            step: 0,
+            written: 0,
+            error: None,
+        }
+    }
+
+    // Throws every event away, for running a traced solution on its own
+    pub fn disabled() -> Self {
+        Self {
+            output: None,
+            step: 0,
+            written: 0,
+            error: None,
+        }
+    }
+
+    // False when the events go nowhere, so the costly ones can be skipped
+    pub fn is_enabled(&self) -> bool {
+        self.output.is_some()
+    }
+
+    pub fn step(&self) -> u64 {
+        self.step
+    }
+
+    // The events recorded after this belong to the next step
+    pub fn next_step(&mut self) {
+        self.step += 1;
+    }
+
+    pub fn record(&mut self, event: Event) {
+        let Some(output) = &mut self.output else {
+            return;
+        };
+        if self.error.is_some() {
+            return;
+        }
+
+        let record = Record {
+            step: self.step,
+            event,
+        };
+        match writeln!(output, "{}", record) {
+            Ok(()) => self.written += 1,
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    pub fn moved(&mut self, id: &str, from: (i64, i64), to: (i64, i64)) {
+        self.record(Event::Move {
+            id: id.to_string(),
+            from,
+            to,
+        });
+    }
+
+    pub fn set_cell(&mut self, at: (i64, i64), value: &str) {
+        self.record(Event::SetCell {
+            at,
+            value: value.to_string(),
+        });
+    }
+
+    pub fn metric(&mut self, name: &str, value: f64) {
+        self.record(Event::Metric {
+            name: name.to_string(),
+            value,
+        });
+    }
+
+    // Flushes the output, returns how many events were written
+    pub fn finish(mut self) -> io::Result<usize> {
+        if let Some(output) = &mut self.output {
+            if let Err(error) = output.flush() {
+                self.error.get_or_insert(error);
+            }
+        }
+
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.written),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn every_event() -> Vec<Record> {
+        vec![
+            Record {
+                step: 0,
+                event: Event::SetCell {
+                    at: (500, 9),
+                    value: String::from("rock"),
+                },
+            },
+            Record {
+                step: 1,
+                event: Event::Move {
+                    id: String::from("knot \"9\"\n"),
+                    from: (-1, 0),
+                    to: (0, -1),
+                },
+            },
+            Record {
+                step: 1,
+                event: Event::Metric {
+                    name: String::from("visited"),
+                    value: 2.5,
+                },
+            },
+        ]
+    }
+
+    #[test]
+    fn lines() {
+        let lines: Vec<String> = every_event().iter().map(Record::to_string).collect();
+
+        assert_eq!(
+            lines,
+            [
+                r#"{"step":0,"event":"set_cell","at":[500,9],"value":"rock"}"#,
+                r#"{"step":1,"event":"move","id":"knot \"9\"\n","from":[-1,0],"to":[0,-1]}"#,
+                r#"{"step":1,"event":"metric","name":"visited","value":2.5}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let trace: String = every_event()
+            .iter()
+            .map(|record| format!("{}\n", record))
+            .collect();
+
+        assert_eq!(read(&trace).unwrap(), every_event());
+        // Any order, any spacing and escapes written by someone else
+        assert_eq!(
+            Record::parse(
+                r#" { "value" : "A\/b" , "at" : [ 1 , -2 ], "event": "set_cell", "step": 3 } "#
+            ),
+            Ok(Record {
+                step: 3,
+                event: Event::SetCell {
+                    at: (1, -2),
+                    value: String::from("A/b")
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn not_traces() {
+        assert!(read("{\"step\":0}\n\nnonsense")
+            .unwrap_err()
+            .starts_with("Line 1: Missing"));
+        assert!(read("\n{\"step\":0}").unwrap_err().starts_with("Line 2: "));
+        assert!(Record::parse(r#"{"step":-1,"event":"metric","name":"a","value":1}"#).is_err());
+        assert!(Record::parse(r#"{"step":1,"event":"jump","id":"a"}"#).is_err());
+        assert!(Record::parse(r#"{"step":1,"event":"set_cell","at":5,"value":"a"}"#).is_err());
+        assert!(Record::parse(r#"{"step":1,"event":"move","id":"unterminated}"#).is_err());
+
+        let infinite = Record {
+            step: 0,
+            event: Event::Metric {
+                name: String::from("x"),
+                value: f64::INFINITY,
+            },
+        };
+        let parsed = Record::parse(&infinite.to_string()).unwrap();
+        assert!(matches!(parsed.event, Event::Metric { value, .. } if value.is_nan()));
+    }
+
+    #[test]
+    fn recording() {
+        let mut output = Vec::new();
+        let mut recorder = Recorder::new(&mut output);
+        assert!(recorder.is_enabled());
+        recorder.set_cell((1, 2), "#");
+        recorder.next_step();
+        recorder.moved("head", (0, 0), (1, 0));
+        recorder.metric("count", 3.0);
+        assert_eq!(recorder.step(), 1);
+        assert_eq!(recorder.finish().unwrap(), 3);
+
+        let records = read(&String::from_utf8(output).unwrap()).unwrap();
+        let steps: Vec<u64> = records.iter().map(|record| record.step).collect();
+        assert_eq!(steps, [0, 1, 1]);
+
+        let mut nowhere = Recorder::disabled();
+        assert!(!nowhere.is_enabled());
+        nowhere.metric("count", 1.0);
+        assert_eq!(nowhere.finish().unwrap(), 0);
+    }
+
+    struct Broken;
+
+    impl Write for Broken {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::other("disk full"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn write_errors() {
+        let mut recorder = Recorder::new(Broken);
+        recorder.metric("a", 1.0);
+        recorder.metric("b", 2.0);
+
+        assert_eq!(recorder.finish().unwrap_err().to_string(), "disk full");
+    }
+}
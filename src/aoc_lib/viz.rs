@@ -0,0 +1,472 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::iter;
+use std::path::Path;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use crate::aoc_lib::error::{write_error, AocError};
+
+pub mod cast;
+pub mod dots;
+pub mod heatmap;
+pub mod svg;
+pub mod voxels;
+
+use cast::Cast;
+
+// One picture of an animation, with a line saying what's going on in it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub caption: String,
+    pub picture: String,
+}
+
+// Days that can be watched with --visualize. The frames are produced lazily,
+// a simulation only runs as far as someone watches it.
+pub trait Visualize {
+    fn frames(&self) -> impl Iterator<Item = Frame>;
+}
+
+// The terminal has to stay in line mode, so every command is followed by Enter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    // Toggles between playing and paused
+    Pause,
+    // Pauses and shows the next frame
+    Step,
+    Faster,
+    Slower,
+    Quit,
+}
+
+impl Control {
+    // An empty line steps, so holding Enter goes through the frames by hand
+    pub fn parse(line: &str) -> Option<Self> {
+        match line.trim() {
+            "p" | "pause" | "play" => Some(Control::Pause),
+            "" | "s" | "step" => Some(Control::Step),
+            "+" | "f" | "faster" => Some(Control::Faster),
+            "-" | "l" | "slower" => Some(Control::Slower),
+            "q" | "quit" => Some(Control::Quit),
+            _ => None,
+        }
+    }
+}
+
+const START_DELAY: Duration = Duration::from_millis(100);
+const MIN_DELAY: Duration = Duration::from_millis(1);
+const MAX_DELAY: Duration = Duration::from_secs(2);
+
+// Clears the screen and moves the cursor to the top left corner
+const CLEAR: &str = "\x1b[2J\x1b[H";
+
+// Goes through the frames one at a time, the last one stays on screen until
+// the player is closed
+pub struct Player<I: Iterator<Item = Frame>> {
+    frames: I,
+    current: Frame,
+    shown: usize,
+    finished: bool,
+    playing: bool,
+    delay: Duration,
+}
+
+impl<I: Iterator<Item = Frame>> Player<I> {
+    pub fn new(mut frames: I) -> Self {
+        let (current, finished) = match frames.next() {
+            Some(first) => (first, false),
+            None => (
+                Frame {
+                    caption: String::from("Nothing to show"),
+                    picture: String::new(),
+                },
+                true,
+            ),
+        };
+
+        Self {
+            frames,
+            current,
+            shown: 1,
+            finished,
+            playing: true,
+            delay: START_DELAY,
+        }
+    }
+
+    pub fn current(&self) -> &Frame {
+        &self.current
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing && !self.finished
+    }
+
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    // Moves to the next frame, false if there are no more
+    pub fn advance(&mut self) -> bool {
+        if self.finished {
+            return false;
+        }
+
+        match self.frames.next() {
+            Some(frame) => {
+                self.current = frame;
+                self.shown += 1;
+            }
+            None => self.finished = true,
+        }
+        !self.finished
+    }
+
+    // Returns false when it's time to close the player
+    pub fn control(&mut self, control: Control) -> bool {
+        match control {
+            Control::Pause => self.playing = !self.playing,
+            Control::Step => {
+                self.playing = false;
+                self.advance();
+            }
+            Control::Faster => self.delay = (self.delay / 2).max(MIN_DELAY),
+            Control::Slower => self.delay = (self.delay * 2).min(MAX_DELAY),
+            Control::Quit => return false,
+        }
+
+        true
+    }
+
+    // The whole screen for the current frame, with the state of the player and
+    // the commands at the bottom
+    pub fn screen(&self) -> String {
+        let state = match (self.finished, self.playing) {
+            (true, _) => String::from("last frame"),
+            (false, true) => format!("playing, {}ms per frame", self.delay.as_millis()),
+            (false, false) => String::from("paused"),
+        };
+
+        format!(
+            "{}{}\n\n{}\n\nFrame {}, {}\n[p]lay/pause, [s]tep or Enter, [+] faster, [-] slower, [q]uit: ",
+            CLEAR,
+            self.current.caption,
+            self.current.picture.trim_end(),
+            self.shown,
+            state
+        )
+    }
+
+    // Reads the commands on another thread, so the frames keep coming while
+    // nobody types anything. Once the input is closed it plays to the end.
+    pub fn run(
+        mut self,
+        input: impl Read + Send + 'static,
+        mut output: impl Write,
+    ) -> io::Result<()> {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(input).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if let Some(control) = Control::parse(&line) {
+                    if sender.send(control).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        let mut input_open = true;
+        loop {
+            write!(output, "{}", self.screen())?;
+            output.flush()?;
+
+            let control = match (self.is_playing(), input_open) {
+                (true, true) => match receiver.recv_timeout(self.delay) {
+                    Ok(control) => Some(control),
+                    Err(RecvTimeoutError::Timeout) => None,
+                    Err(RecvTimeoutError::Disconnected) => {
+                        input_open = false;
+                        None
+                    }
+                },
+                (true, false) => {
+                    thread::sleep(self.delay);
+                    None
+                }
+                (false, true) => receiver.recv().ok(),
+                (false, false) => None,
+            };
+
+            match control {
+                Some(control) if !self.control(control) => break,
+                Some(_) => (),
+                None if self.is_playing() => {
+                    self.advance();
+                }
+                // Paused or at the end, with nobody left to say what to do
+                None => break,
+            }
+        }
+
+        writeln!(output)
+    }
+}
+
+// Escape sequences take no room on screen, they start with ESC and end with a
+// letter
+pub fn visible_width(line: &str) -> usize {
+    let mut width = 0;
+    let mut escaping = false;
+    for c in line.chars() {
+        match c {
+            '\x1b' => escaping = true,
+            c if escaping => escaping = !c.is_ascii_alphabetic(),
+            '\r' => (),
+            _ => width += 1,
+        }
+    }
+    width
+}
+
+// Two animations of the same day in lockstep, their pictures next to each
+// other under their names, for watching two ways of solving it race. Frames
+// are matched by their number; the one done first stays on its last frame.
+pub struct SideBySide<L: Visualize, R: Visualize> {
+    pub left: (&'static str, L),
+    pub right: (&'static str, R),
+}
+
+// Room between the two pictures
+const GUTTER: usize = 4;
+
+impl<L: Visualize, R: Visualize> SideBySide<L, R> {
+    // One side of a frame, its name and caption on top of the picture
+    fn column(name: &str, frame: &Option<Frame>, done: bool) -> Vec<String> {
+        let Some(frame) = frame else {
+            return vec![format!("{}: nothing to show", name)];
+        };
+        let state = if done { ", done" } else { "" };
+
+        iter::once(format!("{}{}: {}", name, state, frame.caption))
+            .chain(iter::once(String::new()))
+            .chain(frame.picture.trim_end().lines().map(String::from))
+            .collect()
+    }
+}
+
+impl<L: Visualize, R: Visualize> Visualize for SideBySide<L, R> {
+    fn frames(&self) -> impl Iterator<Item = Frame> {
+        let (left_name, right_name) = (self.left.0, self.right.0);
+        let mut left = self.left.1.frames().fuse();
+        let mut right = self.right.1.frames().fuse();
+        let (mut last_left, mut last_right) = (None, None);
+        // Only ever grows, so the right picture doesn't jump around
+        let mut width = 0;
+        let mut step = 0;
+
+        iter::from_fn(move || {
+            let (next_left, next_right) = (left.next(), right.next());
+            if next_left.is_none() && next_right.is_none() {
+                return None;
+            }
+            let (left_done, right_done) = (next_left.is_none(), next_right.is_none());
+            last_left = next_left.or(last_left.take());
+            last_right = next_right.or(last_right.take());
+            step += 1;
+
+            let left_lines = Self::column(left_name, &last_left, left_done);
+            let right_lines = Self::column(right_name, &last_right, right_done);
+            width = left_lines
+                .iter()
+                .map(|line| visible_width(line))
+                .fold(width, usize::max);
+
+            let mut picture = String::new();
+            for line in 0..left_lines.len().max(right_lines.len()) {
+                let left_line = left_lines.get(line).map_or("", String::as_str);
+                let right_line = right_lines.get(line).map_or("", String::as_str);
+                picture.push_str(left_line);
+                if !right_line.is_empty() {
+                    let padding = width - visible_width(left_line) + GUTTER;
+                    picture.push_str(&" ".repeat(padding));
+                    picture.push_str(right_line);
+                }
+                picture.push('\n');
+            }
+
+            Some(Frame {
+                caption: format!("Step {}, {} against {}", step, left_name, right_name),
+                picture,
+            })
+        })
+    }
+}
+
+// Plays the frames in this terminal, and records them as an asciicast if
+// there's a path to save it to
+pub fn show(animation: &impl Visualize, cast: Option<&Path>) -> Result<(), AocError> {
+    let player = Player::new(animation.frames());
+    let Some(path) = cast else {
+        return Ok(player.run(io::stdin(), io::stdout())?);
+    };
+
+    let mut recording = Cast::new(io::stdout());
+    player.run(io::stdin(), &mut recording)?;
+    let file = File::create(path).map_err(|e| write_error(path, e))?;
+    recording
+        .finish(BufWriter::new(file))
+        .map_err(|e| write_error(path, e))?;
+    println!("Wrote {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Counter(usize);
+
+    impl Visualize for Counter {
+        fn frames(&self) -> impl Iterator<Item = Frame> {
+            (1..=self.0).map(|count| Frame {
+                caption: format!("Count {}", count),
+                picture: "#".repeat(count),
+            })
+        }
+    }
+
+    #[test]
+    fn controls() {
+        assert_eq!(Control::parse("p"), Some(Control::Pause));
+        assert_eq!(Control::parse(""), Some(Control::Step));
+        assert_eq!(Control::parse(" + "), Some(Control::Faster));
+        assert_eq!(Control::parse("slower"), Some(Control::Slower));
+        assert_eq!(Control::parse("q"), Some(Control::Quit));
+        assert_eq!(Control::parse("rewind"), None);
+    }
+
+    #[test]
+    fn stepping() {
+        let mut player = Player::new(Counter(3).frames());
+        assert!(player.is_playing());
+        assert_eq!(player.current().caption, "Count 1");
+
+        assert!(player.control(Control::Step));
+        assert!(!player.is_playing());
+        assert_eq!(player.current().picture, "##");
+        assert!(player.control(Control::Pause));
+        assert!(player.is_playing());
+
+        assert!(player.advance());
+        assert!(!player.advance());
+        assert!(!player.is_playing());
+        assert_eq!(player.current().caption, "Count 3");
+        assert!(player.screen().contains("Frame 3, last frame"));
+        assert!(!player.control(Control::Quit));
+    }
+
+    #[test]
+    fn speed() {
+        let mut player = Player::new(Counter(1).frames());
+        for _ in 0..20 {
+            player.control(Control::Faster);
+        }
+        assert_eq!(player.delay(), MIN_DELAY);
+        for _ in 0..20 {
+            player.control(Control::Slower);
+        }
+        assert_eq!(player.delay(), MAX_DELAY);
+    }
+
+    #[test]
+    fn nothing_to_show() {
+        let player = Player::new(Counter(0).frames());
+
+        assert!(!player.is_playing());
+        assert!(player.screen().contains("Nothing to show"));
+    }
+
+    #[test]
+    fn plays_to_the_end() {
+        // No input at all, so it plays every frame and stops at the last one
+        let mut output = Vec::new();
+        let mut player = Player::new(Counter(3).frames());
+        for _ in 0..6 {
+            player.control(Control::Faster);
+        }
+        player.run(io::empty(), &mut output).unwrap();
+
+        // The last frame is drawn again once it's known to be the last
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches(CLEAR).count(), 4);
+        assert!(output.contains("Count 3\n\n###\n\nFrame 3, last frame"));
+    }
+
+    #[test]
+    fn recorded() {
+        let mut terminal = Vec::new();
+        let mut recording = Cast::new(&mut terminal);
+        let mut player = Player::new(Counter(2).frames());
+        for _ in 0..6 {
+            player.control(Control::Faster);
+        }
+        player.run(io::empty(), &mut recording).unwrap();
+
+        // One event per screen, the new line at the end is only taken by finish
+        let times: Vec<f64> = recording.events().iter().map(|(time, _)| *time).collect();
+        assert_eq!(times.len(), 3);
+        assert!(times.windows(2).all(|pair| pair[0] <= pair[1]));
+        let mut file = Vec::new();
+        recording.finish(&mut file).unwrap();
+        assert_eq!(String::from_utf8(file).unwrap().lines().count(), 5);
+    }
+
+    #[test]
+    fn side_by_side() {
+        let race = SideBySide {
+            left: ("slow", Counter(3)),
+            right: ("fast", Counter(1)),
+        };
+        let frames: Vec<Frame> = race.frames().collect();
+
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].caption, "Step 1, slow against fast");
+        assert_eq!(
+            frames[0].picture,
+            "slow: Count 1    fast: Count 1\n\n#                #\n"
+        );
+        // The right one is done, the left one got wider
+        assert_eq!(
+            frames[2].picture,
+            "slow: Count 3    fast, done: Count 1\n\n###              #\n"
+        );
+
+        let lonely = SideBySide {
+            left: ("none", Counter(0)),
+            right: ("one", Counter(1)),
+        };
+        let frames: Vec<Frame> = lonely.frames().collect();
+        assert_eq!(frames.len(), 1);
+        assert!(frames[0]
+            .picture
+            .starts_with("none: nothing to show    one: Count 1\n"));
+    }
+
+    #[test]
+    fn quits() {
+        // Without the quit this would step on the last frame forever
+        let input = b"q\n".chain(io::repeat(b'\n'));
+        let mut output = Vec::new();
+        Player::new(Counter(1).frames())
+            .run(input, &mut output)
+            .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches(CLEAR).count(), 1);
+    }
+}
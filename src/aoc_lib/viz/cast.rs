@@ -0,0 +1,142 @@
+use std::io::{self, Write};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use super::visible_width;
+use crate::aoc_lib::trace::quoted;
+
+// Sits between the player and the terminal and keeps a copy of everything
+// shown, with the time it was shown at. `finish` saves it as an asciicast v2
+// file, which asciinema can play back and blogs can embed.
+pub struct Cast<W: Write> {
+    output: W,
+    start: Instant,
+    pending: Vec<u8>,
+    events: Vec<(f64, String)>,
+}
+
+impl<W: Write> Cast<W> {
+    pub fn new(output: W) -> Self {
+        Self {
+            output,
+            start: Instant::now(),
+            pending: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+
+    // Everything written since the last flush becomes one event, so a whole
+    // screen shows up at once when played back
+    fn take_pending(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+
+        // The terminal turns \n into \r\n on its own, a recording has to do it
+        let text = String::from_utf8_lossy(&self.pending).replace('\n', "\r\n");
+        self.events.push((self.start.elapsed().as_secs_f64(), text));
+        self.pending.clear();
+    }
+
+    pub fn events(&self) -> &[(f64, String)] {
+        &self.events
+    }
+
+    // The header line and then one line per event
+    pub fn finish(mut self, mut file: impl Write) -> io::Result<()> {
+        self.take_pending();
+        let (width, height) = size(self.events.iter().map(|(_, text)| text.as_str()));
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+
+        writeln!(
+            file,
+            "{{\"version\": 2, \"width\": {}, \"height\": {}, \"timestamp\": {}}}",
+            width, height, timestamp
+        )?;
+        for (time, text) in self.events.iter() {
+            writeln!(file, "[{:.6}, \"o\", {}]", time, quoted(text))?;
+        }
+        file.flush()
+    }
+}
+
+impl<W: Write> Write for Cast<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.output.write(buf)?;
+        self.pending.extend_from_slice(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.take_pending();
+        self.output.flush()
+    }
+}
+
+// The smallest terminal every screen fits in, screens start by clearing the
+// last one
+fn size<'a>(events: impl Iterator<Item = &'a str>) -> (usize, usize) {
+    let mut width = 1;
+    let mut height = 1;
+    let mut lines = 0;
+    for text in events {
+        for (id, screen) in text.split(super::CLEAR).enumerate() {
+            if id > 0 {
+                lines = 0;
+            }
+            for (id, line) in screen.split('\n').enumerate() {
+                if id > 0 {
+                    lines += 1;
+                }
+                width = width.max(visible_width(line));
+            }
+            height = height.max(lines + 1);
+        }
+    }
+    (width, height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aoc_lib::viz::CLEAR;
+
+    #[test]
+    fn widths() {
+        assert_eq!(visible_width("\x1b[1;31m#\x1b[0m.."), 3);
+        assert_eq!(visible_width("abc\r"), 3);
+        assert_eq!(
+            size(["hello\nworld!", &format!("{}a\nb\nc", CLEAR)].into_iter()),
+            (6, 3)
+        );
+        assert_eq!(size(std::iter::empty()), (1, 1));
+    }
+
+    #[test]
+    fn recording() {
+        let mut terminal = Vec::new();
+        let mut cast = Cast::new(&mut terminal);
+        writeln!(cast, "{}first", CLEAR).unwrap();
+        write!(cast, "screen").unwrap();
+        cast.flush().unwrap();
+        write!(cast, "{}\"é\"", CLEAR).unwrap();
+        assert_eq!(cast.events().len(), 1);
+
+        let mut file = Vec::new();
+        cast.finish(&mut file).unwrap();
+        let file = String::from_utf8(file).unwrap();
+        let lines: Vec<&str> = file.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with(r#"{"version": 2, "width": 6, "height": 2, "timestamp": "#));
+        assert!(lines[1].starts_with('['));
+        assert!(lines[1].ends_with(r#", "o", "\u001b[2J\u001b[Hfirst\r\nscreen"]"#));
+        assert!(lines[2].ends_with(r#", "o", "\u001b[2J\u001b[H\"é\""]"#));
+        // The terminal got everything as it was
+        assert_eq!(
+            String::from_utf8(terminal).unwrap(),
+            format!("{}first\nscreen{}\"é\"", CLEAR, CLEAR)
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use std::sync::Mutex;
+
+use ansi_term::{Colour, Style};
+
+use crate::aoc_lib::grid::Grid;
+
+// How many cells of a grid go into one character of the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Resolution {
+    // One cell per character
+    #[default]
+    Cells,
+    // Two cells on top of each other, for the fonts without Braille patterns
+    HalfBlocks,
+    // Two cells across and four down, as the dots of a Braille pattern
+    Braille,
+}
+
+impl Resolution {
+    // Cells across and down in a character
+    pub fn cells_per_character(&self) -> (usize, usize) {
+        match self {
+            Resolution::Cells => (1, 1),
+            Resolution::HalfBlocks => (1, 2),
+            Resolution::Braille => (2, 4),
+        }
+    }
+}
+
+// What the runner picked with --dots, the animations look it up when they draw
+static CHOSEN: Mutex<Resolution> = Mutex::new(Resolution::Cells);
+
+pub fn choose(resolution: Resolution) {
+    *CHOSEN.lock().unwrap() = resolution;
+}
+
+pub fn chosen() -> Resolution {
+    *CHOSEN.lock().unwrap()
+}
+
+// The bit of the dot in the Braille pattern, by row and column in the block
+const BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+const BRAILLE_BLANK: u32 = 0x2800;
+
+fn paint_with(paint: bool, style: Style, text: &str) -> String {
+    match paint {
+        true => style.paint(text).to_string(),
+        false => text.to_string(),
+    }
+}
+
+// A character of Braille dots, coloured like most of its dots, the first one
+// seen when there's a tie
+fn braille_character(dots: &[Option<Colour>], bits: u32, paint: bool) -> String {
+    if bits == 0 {
+        return String::from(" ");
+    }
+    let character = char::from_u32(BRAILLE_BLANK + bits).unwrap().to_string();
+    let lit: Vec<Colour> = dots.iter().flatten().copied().collect();
+    let colour = lit
+        .iter()
+        .copied()
+        .max_by_key(|colour| {
+            let count = lit.iter().filter(|other| *other == colour).count();
+            let first = lit.iter().position(|other| other == colour).unwrap();
+            (count, usize::MAX - first)
+        })
+        .unwrap();
+
+    paint_with(paint, colour.normal(), &character)
+}
+
+// The top half in the foreground, the bottom half in the background when the
+// two don't match
+fn half_block_character(top: Option<Colour>, bottom: Option<Colour>, paint: bool) -> String {
+    match (top, bottom) {
+        (None, None) => String::from(" "),
+        (Some(top), None) => paint_with(paint, top.normal(), "▀"),
+        (None, Some(bottom)) => paint_with(paint, bottom.normal(), "▄"),
+        (Some(top), Some(bottom)) if top == bottom => paint_with(paint, top.normal(), "█"),
+        (Some(top), Some(bottom)) => paint_with(paint, top.on(bottom), "▀"),
+    }
+}
+
+// The grid packed as tight as `resolution` allows, cells `colour` gives no
+// colour stay blank. The cells past the edges of the grid count as blank too,
+// so any size works. With `paint` off only the shapes are left.
+pub fn pack<T>(
+    resolution: Resolution,
+    grid: &Grid<T>,
+    colour: impl Fn(&T) -> Option<Colour>,
+    paint: bool,
+) -> String {
+    let (across, down) = resolution.cells_per_character();
+    let cell = |row: usize, column: usize| grid.get((row, column)).and_then(&colour);
+
+    let mut output = String::new();
+    for top in (0..grid.height()).step_by(down) {
+        for left in (0..grid.width()).step_by(across) {
+            output += &match resolution {
+                Resolution::Cells => match cell(top, left) {
+                    Some(colour) => paint_with(paint, colour.normal(), "█"),
+                    None => String::from(" "),
+                },
+                Resolution::HalfBlocks => {
+                    half_block_character(cell(top, left), cell(top + 1, left), paint)
+                }
+                Resolution::Braille => {
+                    let mut dots = Vec::new();
+                    let mut bits = 0;
+                    for (row, row_bits) in BRAILLE_DOTS.iter().enumerate() {
+                        for (column, bit) in row_bits.iter().enumerate() {
+                            let dot = cell(top + row, left + column);
+                            if dot.is_some() {
+                                bits |= bit;
+                            }
+                            dots.push(dot);
+                        }
+                    }
+                    braille_character(&dots, bits, paint)
+                }
+            };
+        }
+        output.push('\n');
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Colour = Colour::Red;
+    const BLUE: Colour = Colour::Blue;
+
+    fn shape() -> Grid<Option<Colour>> {
+        let rows = "#..#\n.#..\n..#.\n#...\n##.#";
+        Grid::parse(rows, |_, c| Ok((c == '#').then_some(RED))).unwrap()
+    }
+
+    #[test]
+    fn braille() {
+        let packed = pack(Resolution::Braille, &shape(), |cell| *cell, false);
+
+        // Five rows need two lines, the second only has its top dots
+        assert_eq!(packed, "⡑⠌\n⠉⠈\n");
+        assert!(pack(Resolution::Braille, &shape(), |cell| *cell, true)
+            .contains(&RED.paint("⡑").to_string()));
+    }
+
+    #[test]
+    fn half_blocks() {
+        let mut grid = shape();
+        grid[(1, 0)] = Some(BLUE);
+        let packed = pack(Resolution::HalfBlocks, &grid, |cell| *cell, false);
+
+        assert_eq!(packed, "▀▄ ▀\n▄ ▀ \n▀▀ ▀\n");
+        let painted = pack(Resolution::HalfBlocks, &grid, |cell| *cell, true);
+        assert!(painted.starts_with(&RED.on(BLUE).paint("▀").to_string()));
+    }
+
+    #[test]
+    fn cells() {
+        let packed = pack(Resolution::Cells, &shape(), |cell| *cell, false);
+
+        assert_eq!(packed.lines().count(), 5);
+        assert_eq!(packed.lines().next(), Some("█  █"));
+        assert_eq!(Resolution::Braille.cells_per_character(), (2, 4));
+    }
+
+    #[test]
+    fn coloured_like_most_dots() {
+        let dots = [Some(BLUE), None, Some(RED), Some(RED)];
+
+        assert_eq!(
+            braille_character(&dots, 1, true),
+            RED.paint("⠁").to_string()
+        );
+        assert_eq!(
+            braille_character(&dots[..3], 1, true),
+            BLUE.paint("⠁").to_string()
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use hashbrown::HashMap;
+
+use super::svg::{Shape, Svg};
+use crate::aoc_lib::grid::{Grid, Position};
+
+// Cold to hot, close to matplotlib's viridis, which still reads fine printed in
+// grey or for the colour blind
+const RAMP: [(u8, u8, u8); 5] = [
+    (68, 1, 84),
+    (59, 82, 139),
+    (33, 145, 140),
+    (94, 201, 98),
+    (253, 231, 37),
+];
+
+// The colour `fraction` of the way up the ramp, as "#rrggbb". Anything outside
+// 0..=1 gets the colour at the closest end.
+pub fn colour(fraction: f64) -> String {
+    let position = fraction.clamp(0.0, 1.0) * (RAMP.len() - 1) as f64;
+    let low = (position.floor() as usize).min(RAMP.len() - 2);
+    let mix = position - low as f64;
+    let channel = |from: u8, to: u8| (from as f64 + (to as f64 - from as f64) * mix).round() as u8;
+
+    let ((r, g, b), (to_r, to_g, to_b)) = (RAMP[low], RAMP[low + 1]);
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        channel(r, to_r),
+        channel(g, to_g),
+        channel(b, to_b)
+    )
+}
+
+fn cell(position: Position) -> Shape {
+    let (row, column) = position;
+    Shape::Rect {
+        corner: (column as f64, row as f64),
+        width: 1.0,
+        height: 1.0,
+    }
+}
+
+// The grid painted with `paint`, and every overlay above it in its own layer,
+// a value per cell coloured from zero to the highest of the overlay. Cells
+// without a value are left clear, so hiding the layers in turn shows where two
+// searches went and where they didn't.
+pub fn heatmap<T>(
+    base: &Grid<T>,
+    paint: impl Fn(&T) -> String,
+    overlays: &[(&str, &HashMap<Position, u64>)],
+) -> Svg {
+    let mut svg = Svg::new();
+    svg.layer("map", "none", "none", 1.0);
+    for position in base.positions() {
+        svg.add_filled(cell(position), &paint(&base[position]), None);
+    }
+
+    for (name, values) in overlays {
+        svg.layer(name, "none", "none", 0.8);
+        let highest = values.values().copied().max().unwrap_or(0).max(1);
+        let mut cells: Vec<(&Position, &u64)> = values.iter().collect();
+        cells.sort_unstable();
+        for (&(row, column), &value) in cells {
+            svg.add_filled(
+                cell((row, column)),
+                &colour(value as f64 / highest as f64),
+                Some(&format!("row {}, column {}: {}", row, column, value)),
+            );
+        }
+    }
+
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ramp() {
+        assert_eq!(colour(0.0), "#440154");
+        assert_eq!(colour(1.0), "#fde725");
+        assert_eq!(colour(0.5), "#21918c");
+        assert_eq!(colour(-3.0), colour(0.0));
+        assert_eq!(colour(7.0), colour(1.0));
+        // Halfway between the first two stops
+        assert_eq!(colour(0.125), "#402a70");
+    }
+
+    #[test]
+    fn layers() {
+        let base = Grid::parse("#.\n..", |_, c| Ok(c == '#')).unwrap();
+        let steps = HashMap::from([((0, 1), 0), ((1, 1), 2), ((1, 0), 1)]);
+        let svg = heatmap(
+            &base,
+            |wall| String::from(if *wall { "black" } else { "white" }),
+            &[("Steps", &steps)],
+        )
+        .to_string();
+        let lines: Vec<&str> = svg.lines().collect();
+
+        assert_eq!(lines.len(), 1 + 6 + 5 + 1);
+        assert!(lines[2].starts_with(r#"  <rect x="0" y="0" width="1" height="1" fill="black""#));
+        assert!(lines[7].contains(r#"inkscape:label="Steps""#));
+        // In row order, the highest value the hottest
+        assert!(lines[8].contains(r##"fill="#440154""##));
+        assert!(lines[8].contains("<title>row 0, column 1: 0</title>"));
+        assert!(lines[10].contains(r##"fill="#fde725""##));
+        assert!(lines[10].contains("<title>row 1, column 1: 2</title>"));
+    }
+}
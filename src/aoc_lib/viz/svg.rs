@@ -0,0 +1,337 @@
+use std::fmt;
+
+// A point of the drawing, y grows downwards like on screen
+pub type Point = (f64, f64);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Rect {
+        corner: Point,
+        width: f64,
+        height: f64,
+    },
+    Polygon(Vec<Point>),
+    Polyline(Vec<Point>),
+    Circle {
+        centre: Point,
+        radius: f64,
+    },
+}
+
+impl Shape {
+    // Smallest and largest corner of the box around the shape
+    fn bounds(&self) -> Option<(Point, Point)> {
+        let corners = match self {
+            Shape::Rect {
+                corner: (x, y),
+                width,
+                height,
+            } => vec![(*x, *y), (x + width, y + height)],
+            Shape::Polygon(points) | Shape::Polyline(points) => points.clone(),
+            Shape::Circle {
+                centre: (x, y),
+                radius,
+            } => vec![(x - radius, y - radius), (x + radius, y + radius)],
+        };
+
+        corners
+            .into_iter()
+            .fold(None, |bounds, (x, y)| match bounds {
+                None => Some(((x, y), (x, y))),
+                Some(((min_x, min_y), (max_x, max_y))) => {
+                    Some(((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y))))
+                }
+            })
+    }
+
+    fn tag(&self) -> (&'static str, String) {
+        let points = |points: &[Point]| {
+            points
+                .iter()
+                .map(|(x, y)| format!("{},{}", x, y))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        match self {
+            Shape::Rect {
+                corner: (x, y),
+                width,
+                height,
+            } => (
+                "rect",
+                format!(
+                    r#"x="{}" y="{}" width="{}" height="{}""#,
+                    x, y, width, height
+                ),
+            ),
+            Shape::Polygon(corners) => ("polygon", format!(r#"points="{}""#, points(corners))),
+            Shape::Polyline(corners) => (
+                "polyline",
+                format!(r#"points="{}" fill="none""#, points(corners)),
+            ),
+            Shape::Circle {
+                centre: (x, y),
+                radius,
+            } => ("circle", format!(r#"cx="{}" cy="{}" r="{}""#, x, y, radius)),
+        }
+    }
+}
+
+// Text can't go in the file as it is, the markup characters have to be escaped
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Ids can't have spaces, "Part 1 tail" becomes "part-1-tail"
+fn identifier(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[derive(Debug, Clone)]
+struct Layer {
+    name: String,
+    fill: String,
+    stroke: String,
+    opacity: f64,
+    elements: Vec<String>,
+}
+
+// How wide the picture is shown, the drawing is scaled to fit
+const WIDTH: f64 = 1000.0;
+
+// A static drawing whose shapes go in named layers, which Inkscape and other
+// editors can show and hide one at a time. A shape can have a tooltip, which
+// browsers show while hovering it. Lines keep the same width at any zoom, so
+// the coordinates can be whatever the day uses.
+#[derive(Debug, Clone, Default)]
+pub struct Svg {
+    layers: Vec<Layer>,
+    bounds: Option<(Point, Point)>,
+}
+
+impl Svg {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The shapes added after this go in a new layer, drawn above the previous
+    // ones. `fill` and `stroke` are SVG colours, "none" leaves them out.
+    pub fn layer(&mut self, name: &str, fill: &str, stroke: &str, opacity: f64) -> &mut Self {
+        self.layers.push(Layer {
+            name: name.to_string(),
+            fill: fill.to_string(),
+            stroke: stroke.to_string(),
+            opacity,
+            elements: Vec::new(),
+        });
+        self
+    }
+
+    // Shapes added before any layer go in one called "drawing"
+    pub fn add(&mut self, shape: Shape, tooltip: Option<&str>) -> &mut Self {
+        self.push(shape, None, tooltip)
+    }
+
+    // A shape with its own fill instead of the layer's, for the drawings where
+    // colour means something, like heatmaps
+    pub fn add_filled(&mut self, shape: Shape, fill: &str, tooltip: Option<&str>) -> &mut Self {
+        self.push(shape, Some(fill), tooltip)
+    }
+
+    fn push(&mut self, shape: Shape, fill: Option<&str>, tooltip: Option<&str>) -> &mut Self {
+        if self.layers.is_empty() {
+            self.layer("drawing", "none", "black", 1.0);
+        }
+
+        if let Some((low, high)) = shape.bounds() {
+            self.bounds = Some(match self.bounds {
+                None => (low, high),
+                Some((min, max)) => (
+                    (min.0.min(low.0), min.1.min(low.1)),
+                    (max.0.max(high.0), max.1.max(high.1)),
+                ),
+            });
+        }
+
+        let (tag, mut attributes) = shape.tag();
+        if let Some(fill) = fill {
+            attributes.push_str(&format!(r#" fill="{}""#, escape(fill)));
+        }
+        let element = match tooltip {
+            Some(tooltip) => format!(
+                r#"<{} {} vector-effect="non-scaling-stroke"><title>{}</title></{}>"#,
+                tag,
+                attributes,
+                escape(tooltip),
+                tag
+            ),
+            None => format!(
+                r#"<{} {} vector-effect="non-scaling-stroke"/>"#,
+                tag, attributes
+            ),
+        };
+        self.layers.last_mut().unwrap().elements.push(element);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bounds.is_none()
+    }
+
+    // Corners of the box around everything drawn so far
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        self.bounds
+    }
+}
+
+impl fmt::Display for Svg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // A margin around the shapes, so the lines on the edges aren't cut
+        let ((min_x, min_y), (max_x, max_y)) = self.bounds.unwrap_or(((0.0, 0.0), (1.0, 1.0)));
+        let margin = ((max_x - min_x).max(max_y - min_y) / 50.0).max(1.0);
+        let (x, y) = (min_x - margin, min_y - margin);
+        let (width, height) = (max_x - min_x + 2.0 * margin, max_y - min_y + 2.0 * margin);
+
+        writeln!(
+            f,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape" viewBox="{} {} {} {}" width="{}" height="{}">"#,
+            x,
+            y,
+            width,
+            height,
+            WIDTH,
+            (WIDTH * height / width).round()
+        )?;
+
+        for layer in self.layers.iter() {
+            writeln!(
+                f,
+                r#"<g id="{}" inkscape:groupmode="layer" inkscape:label="{}" fill="{}" stroke="{}" opacity="{}">"#,
+                identifier(&layer.name),
+                escape(&layer.name),
+                escape(&layer.fill),
+                escape(&layer.stroke),
+                layer.opacity
+            )?;
+            for element in layer.elements.iter() {
+                writeln!(f, "  {}", element)?;
+            }
+            writeln!(f, "</g>")?;
+        }
+
+        writeln!(f, "</svg>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escaping() {
+        assert_eq!(
+            escape(r#"<a & "b" 'c'>"#),
+            "&lt;a &amp; &quot;b&quot; &apos;c&apos;&gt;"
+        );
+        assert_eq!(escape("x=1, y=-2"), "x=1, y=-2");
+        assert_eq!(identifier("Part 1: tail & head"), "part-1-tail-head");
+    }
+
+    #[test]
+    fn bounds() {
+        let mut svg = Svg::new();
+        assert!(svg.is_empty());
+
+        svg.add(
+            Shape::Rect {
+                corner: (0.0, 0.0),
+                width: 10.0,
+                height: 5.0,
+            },
+            None,
+        )
+        .add(
+            Shape::Circle {
+                centre: (20.0, 0.0),
+                radius: 2.0,
+            },
+            None,
+        )
+        .add(Shape::Polyline(vec![(0.0, -40.0), (5.0, 30.0)]), None);
+
+        assert_eq!(svg.bounds(), Some(((0.0, -40.0), (22.0, 30.0))));
+        // Margins of 70 / 50 all around, half as high as wide once scaled
+        assert!(svg
+            .to_string()
+            .starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape" viewBox="-1.4 -41.4 24.8 72.8" width="1000" height="2935">"#));
+    }
+
+    #[test]
+    fn layers() {
+        let mut svg = Svg::new();
+        svg.add(
+            Shape::Polygon(vec![(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)]),
+            None,
+        )
+        .layer("cells & more", "red", "none", 0.5)
+        .add(
+            Shape::Rect {
+                corner: (1.0, 2.0),
+                width: 1.0,
+                height: 1.0,
+            },
+            Some("x=1, y<2"),
+        );
+
+        let svg = svg.to_string();
+        let lines: Vec<&str> = svg.lines().collect();
+        assert_eq!(lines.len(), 8);
+        assert_eq!(
+            lines[1],
+            r#"<g id="drawing" inkscape:groupmode="layer" inkscape:label="drawing" fill="none" stroke="black" opacity="1">"#
+        );
+        assert_eq!(
+            lines[2],
+            r#"  <polygon points="0,0 1,0 0,1" vector-effect="non-scaling-stroke"/>"#
+        );
+        assert_eq!(
+            lines[4],
+            r#"<g id="cells-more" inkscape:groupmode="layer" inkscape:label="cells &amp; more" fill="red" stroke="none" opacity="0.5">"#
+        );
+        assert_eq!(
+            lines[5],
+            r#"  <rect x="1" y="2" width="1" height="1" vector-effect="non-scaling-stroke"><title>x=1, y&lt;2</title></rect>"#
+        );
+        assert_eq!(lines[7], "</svg>");
+
+        let mut svg = Svg::new();
+        svg.add_filled(Shape::Polygon(vec![(0.0, 0.0)]), "#ff0000", None);
+        assert!(svg.to_string().contains(
+            r##"<polygon points="0,0" fill="#ff0000" vector-effect="non-scaling-stroke"/>"##
+        ));
+    }
+
+    #[test]
+    fn nothing_drawn() {
+        let svg = Svg::new().to_string();
+
+        assert!(svg.contains(r#"viewBox="-1 -1 3 3" width="1000" height="1000""#));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+}
@@ -0,0 +1,471 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use itertools::Itertools;
+
+use crate::aoc_lib::cuboid::Cuboid;
+
+// A rectangle of the outer surface, on the plane `axis = at`. The other two
+// axes come in cyclic order: for z they're x then y, for x they're y then z.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Quad {
+    pub axis: usize,
+    pub at: i64,
+    // Whether the filled side is below the plane, so the face looks up the axis
+    pub facing_up: bool,
+    pub first: (i64, i64),
+    pub second: (i64, i64),
+}
+
+impl Quad {
+    fn point(&self, first: i64, second: i64) -> [i64; 3] {
+        let mut point = [0; 3];
+        point[self.axis] = self.at;
+        point[(self.axis + 1) % 3] = first;
+        point[(self.axis + 2) % 3] = second;
+        point
+    }
+
+    // Counter-clockwise seen from outside, so the normal points away from the
+    // filled side
+    pub fn corners(&self) -> [[i64; 3]; 4] {
+        let (low, high) = (self.first.0, self.first.1);
+        let (near, far) = (self.second.0, self.second.1);
+        let mut corners = [
+            self.point(low, near),
+            self.point(high, near),
+            self.point(high, far),
+            self.point(low, far),
+        ];
+        if !self.facing_up {
+            corners.reverse();
+        }
+        corners
+    }
+
+    pub fn normal(&self) -> [i64; 3] {
+        let mut normal = [0; 3];
+        normal[self.axis] = if self.facing_up { 1 } else { -1 };
+        normal
+    }
+
+    pub fn area(&self) -> u128 {
+        u128::from(self.first.1.abs_diff(self.first.0))
+            * u128::from(self.second.1.abs_diff(self.second.0))
+    }
+}
+
+// The faces of one plane, which cuboids end on it and which start from it
+#[derive(Default)]
+struct Plane<'a> {
+    ending: Vec<&'a Cuboid>,
+    starting: Vec<&'a Cuboid>,
+}
+
+// Cells of the plane on a grid made of the sides of the rectangles, marked
+// when one of the cuboids covers them
+fn covered(cuboids: &[&Cuboid], axis: usize, firsts: &[i64], seconds: &[i64]) -> Vec<Vec<bool>> {
+    let mut cells = vec![vec![false; seconds.len() - 1]; firsts.len() - 1];
+    for cuboid in cuboids {
+        let ranges = cuboid.ranges();
+        let first = &ranges[(axis + 1) % 3];
+        let second = &ranges[(axis + 2) % 3];
+        let rows =
+            firsts.binary_search(&first.start).unwrap()..firsts.binary_search(&first.end).unwrap();
+        let columns = seconds.binary_search(&second.start).unwrap()
+            ..seconds.binary_search(&second.end).unwrap();
+        for row in rows {
+            cells[row][columns.clone()].fill(true);
+        }
+    }
+    cells
+}
+
+// Merges the cells of a plane in as few rectangles as it can, greedily: a
+// rectangle grows along the row as far as it goes, then takes the rows after
+// for as long as they're all free over the same span
+fn merge(mut free: Vec<Vec<bool>>, emit: &mut impl FnMut((usize, usize), (usize, usize))) {
+    for row in 0..free.len() {
+        let mut column = 0;
+        while column < free[row].len() {
+            if !free[row][column] {
+                column += 1;
+                continue;
+            }
+
+            let end = (column..free[row].len())
+                .find(|&next| !free[row][next])
+                .unwrap_or(free[row].len());
+            let last_row = (row + 1..free.len())
+                .find(|&next| !free[next][column..end].iter().all(|cell| *cell))
+                .unwrap_or(free.len());
+            for taken in free[row..last_row].iter_mut() {
+                taken[column..end].fill(false);
+            }
+
+            emit((row, last_row), (column, end));
+            column = end;
+        }
+    }
+}
+
+// The outer surface of a shape made of cuboids, which must not overlap, as few
+// quads as the greedy merging finds. Faces between touching cuboids are
+// inside the shape and left out. Only the cuboids with a face on a plane are
+// looked at for it, so huge coordinates cost nothing.
+pub fn surface(cuboids: &[Cuboid]) -> Vec<Quad> {
+    let mut planes: BTreeMap<(usize, i64), Plane> = BTreeMap::new();
+    for cuboid in cuboids.iter().filter(|cuboid| !cuboid.is_empty()) {
+        for axis in 0..3 {
+            planes
+                .entry((axis, cuboid.top_right()[axis]))
+                .or_default()
+                .ending
+                .push(cuboid);
+            planes
+                .entry((axis, cuboid.bottom_left()[axis]))
+                .or_default()
+                .starting
+                .push(cuboid);
+        }
+    }
+
+    let mut quads = Vec::new();
+    for ((axis, at), plane) in planes {
+        let sides = |offset: usize| {
+            plane
+                .ending
+                .iter()
+                .chain(plane.starting.iter())
+                .flat_map(|cuboid| {
+                    let range = cuboid.ranges()[(axis + offset) % 3].clone();
+                    [range.start, range.end]
+                })
+                .sorted_unstable()
+                .dedup()
+                .collect_vec()
+        };
+        let (firsts, seconds) = (sides(1), sides(2));
+        let below = covered(&plane.ending, axis, &firsts, &seconds);
+        let above = covered(&plane.starting, axis, &firsts, &seconds);
+
+        // Where one side is filled and the other isn't. A cuboid crossing the
+        // plane fills both sides, but then it can't overlap the ones ending or
+        // starting there, so it doesn't change a thing.
+        for facing_up in [true, false] {
+            let free = below
+                .iter()
+                .zip(above.iter())
+                .map(|(below, above)| {
+                    below
+                        .iter()
+                        .zip(above.iter())
+                        .map(|(below, above)| {
+                            if facing_up {
+                                *below && !*above
+                            } else {
+                                *above && !*below
+                            }
+                        })
+                        .collect_vec()
+                })
+                .collect_vec();
+            merge(free, &mut |(row, last_row), (column, end)| {
+                quads.push(Quad {
+                    axis,
+                    at,
+                    facing_up,
+                    first: (firsts[row], firsts[last_row]),
+                    second: (seconds[column], seconds[end]),
+                })
+            });
+        }
+    }
+
+    quads
+}
+
+// One unit cube per voxel, for the shapes made of single cubes
+pub fn unit_cubes(voxels: impl IntoIterator<Item = [i64; 3]>) -> Vec<Cuboid> {
+    voxels
+        .into_iter()
+        .map(|[x, y, z]| Cuboid::from_inclusive([x..=x, y..=y, z..=z]))
+        .collect()
+}
+
+// Wavefront OBJ, the quads share no vertices so every face stays flat shaded
+pub fn to_obj(quads: &[Quad]) -> String {
+    let mut output = String::new();
+    writeln!(output, "# {} quads", quads.len()).unwrap();
+    for quad in quads {
+        for [x, y, z] in quad.corners() {
+            writeln!(output, "v {} {} {}", x, y, z).unwrap();
+        }
+    }
+    for [x, y, z] in [0, 1, 2].map(|axis| {
+        let mut normal = [0; 3];
+        normal[axis] = 1;
+        normal
+    }) {
+        writeln!(output, "vn {} {} {}", x, y, z).unwrap();
+        writeln!(output, "vn {} {} {}", -x, -y, -z).unwrap();
+    }
+
+    // OBJ indices start from 1, normals go +x, -x, +y, -y, +z, -z
+    for (id, quad) in quads.iter().enumerate() {
+        let normal = quad.axis * 2 + usize::from(!quad.facing_up) + 1;
+        let [a, b, c, d] = [1, 2, 3, 4].map(|corner| id * 4 + corner);
+        writeln!(
+            output,
+            "f {}//{} {}//{} {}//{} {}//{}",
+            a, normal, b, normal, c, normal, d, normal
+        )
+        .unwrap();
+    }
+
+    output
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (id, byte)| {
+            group | u32::from(*byte) << (16 - 8 * id)
+        });
+        for id in 0..4 {
+            if id <= chunk.len() {
+                encoded.push(BASE64[(group >> (18 - 6 * id) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+// What glTF calls a FLOAT and an UNSIGNED_INT, and the buffers of vertices and
+// of indices
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+// A glTF 2.0 scene with a single mesh, two triangles per quad. The buffer is
+// embedded as base64, so the one .gltf file is all a viewer needs.
+pub fn to_gltf(quads: &[Quad]) -> String {
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut indices = Vec::new();
+    let mut low = [f32::MAX; 3];
+    let mut high = [f32::MIN; 3];
+    for (id, quad) in quads.iter().enumerate() {
+        let normal = quad.normal().map(|side| side as f32);
+        for corner in quad.corners() {
+            let corner = corner.map(|coordinate| coordinate as f32);
+            for axis in 0..3 {
+                low[axis] = low[axis].min(corner[axis]);
+                high[axis] = high[axis].max(corner[axis]);
+            }
+            positions.extend(corner);
+            normals.extend(normal);
+        }
+        let first = (id * 4) as u32;
+        indices.extend([0, 1, 2, 0, 2, 3].map(|corner| first + corner));
+    }
+    if quads.is_empty() {
+        (low, high) = ([0.0; 3], [0.0; 3]);
+    }
+
+    let mut buffer: Vec<u8> = Vec::new();
+    buffer.extend(positions.iter().flat_map(|value| value.to_le_bytes()));
+    buffer.extend(normals.iter().flat_map(|value| value.to_le_bytes()));
+    buffer.extend(indices.iter().flat_map(|value| value.to_le_bytes()));
+    let vertices_length = positions.len() * 4;
+    let indices_length = indices.len() * 4;
+    let vertices = quads.len() * 4;
+    let list = |values: [f32; 3]| values.iter().join(",");
+
+    let mut gltf = String::new();
+    writeln!(gltf, "{{").unwrap();
+    writeln!(
+        gltf,
+        r#"  "asset": {{"version": "2.0", "generator": "Jazzinghen's Advent of Code"}},"#
+    )
+    .unwrap();
+    writeln!(gltf, r#"  "scene": 0,"#).unwrap();
+    writeln!(gltf, r#"  "scenes": [{{"nodes": [0]}}],"#).unwrap();
+    writeln!(gltf, r#"  "nodes": [{{"mesh": 0}}],"#).unwrap();
+    writeln!(
+        gltf,
+        r#"  "meshes": [{{"primitives": [{{"attributes": {{"POSITION": 0, "NORMAL": 1}}, "indices": 2}}]}}],"#
+    )
+    .unwrap();
+    writeln!(
+        gltf,
+        r#"  "buffers": [{{"byteLength": {}, "uri": "data:application/octet-stream;base64,{}"}}],"#,
+        buffer.len(),
+        base64(&buffer)
+    )
+    .unwrap();
+    writeln!(gltf, r#"  "bufferViews": ["#).unwrap();
+    writeln!(
+        gltf,
+        r#"    {{"buffer": 0, "byteOffset": 0, "byteLength": {}, "target": {}}},"#,
+        vertices_length, ARRAY_BUFFER
+    )
+    .unwrap();
+    writeln!(
+        gltf,
+        r#"    {{"buffer": 0, "byteOffset": {}, "byteLength": {}, "target": {}}},"#,
+        vertices_length, vertices_length, ARRAY_BUFFER
+    )
+    .unwrap();
+    writeln!(
+        gltf,
+        r#"    {{"buffer": 0, "byteOffset": {}, "byteLength": {}, "target": {}}}"#,
+        vertices_length * 2,
+        indices_length,
+        ELEMENT_ARRAY_BUFFER
+    )
+    .unwrap();
+    writeln!(gltf, "  ],").unwrap();
+    writeln!(gltf, r#"  "accessors": ["#).unwrap();
+    writeln!(
+        gltf,
+        r#"    {{"bufferView": 0, "componentType": {}, "count": {}, "type": "VEC3", "min": [{}], "max": [{}]}},"#,
+        FLOAT,
+        vertices,
+        list(low),
+        list(high)
+    )
+    .unwrap();
+    writeln!(
+        gltf,
+        r#"    {{"bufferView": 1, "componentType": {}, "count": {}, "type": "VEC3"}},"#,
+        FLOAT, vertices
+    )
+    .unwrap();
+    writeln!(
+        gltf,
+        r#"    {{"bufferView": 2, "componentType": {}, "count": {}, "type": "SCALAR"}}"#,
+        UNSIGNED_INT,
+        indices.len()
+    )
+    .unwrap();
+    writeln!(gltf, "  ]").unwrap();
+    writeln!(gltf, "}}").unwrap();
+
+    gltf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total_area(quads: &[Quad]) -> u128 {
+        quads.iter().map(Quad::area).sum()
+    }
+
+    #[test]
+    fn single_cube() {
+        let quads = surface(&unit_cubes([[0, 0, 0]]));
+
+        assert_eq!(quads.len(), 6);
+        assert_eq!(total_area(&quads), 6);
+        // Every face looks away from the cube
+        for quad in quads.iter() {
+            let [a, b, c, _] = quad.corners();
+            let edge = |from: [i64; 3], to: [i64; 3]| [0, 1, 2].map(|axis| to[axis] - from[axis]);
+            let (u, v) = (edge(a, b), edge(b, c));
+            let cross = [
+                u[1] * v[2] - u[2] * v[1],
+                u[2] * v[0] - u[0] * v[2],
+                u[0] * v[1] - u[1] * v[0],
+            ];
+            assert_eq!(cross, quad.normal());
+        }
+    }
+
+    #[test]
+    fn merged_faces() {
+        // A 3x2x1 slab of unit cubes has the surface of a single box
+        let slab = unit_cubes((0..3).flat_map(|x| (0..2).map(move |y| [x, y, 0])));
+        let quads = surface(&slab);
+
+        assert_eq!(quads.len(), 6);
+        assert_eq!(total_area(&quads), 2 * (6 + 3 + 2));
+        assert!(quads.contains(&Quad {
+            axis: 2,
+            at: 1,
+            facing_up: true,
+            first: (0, 3),
+            second: (0, 2),
+        }));
+    }
+
+    #[test]
+    fn hidden_faces() {
+        // The droplet of 2022 day 18: two cubes side by side show 10 faces
+        assert_eq!(
+            total_area(&surface(&unit_cubes([[1, 1, 1], [2, 1, 1]]))),
+            10
+        );
+
+        // Two touching boxes of different sizes only share part of a face
+        let quads = surface(&[
+            Cuboid::from_inclusive([0..=1, 0..=1, 0..=1]),
+            Cuboid::from_inclusive([2..=2, 0..=0, 0..=0]),
+        ]);
+        assert_eq!(total_area(&quads), 24 + 6 - 2);
+        assert!(surface(&[]).is_empty());
+    }
+
+    #[test]
+    fn huge_cuboids() {
+        let quads = surface(&[Cuboid::from_inclusive([
+            -50_000..=50_000,
+            0..=99_999,
+            7..=7,
+        ])]);
+
+        assert_eq!(quads.len(), 6);
+        assert_eq!(
+            total_area(&quads),
+            2 * (100_001 * 100_000 + 100_001 + 100_000)
+        );
+    }
+
+    #[test]
+    fn obj() {
+        let obj = to_obj(&surface(&unit_cubes([[0, 0, 0]])));
+        let lines: Vec<&str> = obj.lines().collect();
+
+        assert_eq!(lines.len(), 1 + 24 + 6 + 6);
+        assert_eq!(lines[0], "# 6 quads");
+        assert_eq!(lines[25], "vn 1 0 0");
+        assert_eq!(lines[26], "vn -1 0 0");
+        assert!(lines[31..].iter().all(|line| line.starts_with("f ")));
+    }
+
+    #[test]
+    fn encoding() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64(&[0xfb, 0xff]), "+/8=");
+    }
+
+    #[test]
+    fn gltf() {
+        let gltf = to_gltf(&surface(&unit_cubes([[0, 0, 0], [1, 0, 0]])));
+
+        // 24 vertices of 6 floats and 36 indices, 4 bytes each
+        assert!(gltf.contains(r#""byteLength": 720, "#));
+        assert!(gltf.contains(r#""count": 24, "type": "VEC3", "min": [0,0,0], "max": [2,1,1]"#));
+        assert!(gltf.contains(r#""count": 36, "type": "SCALAR""#));
+        assert!(to_gltf(&[]).contains(r#""min": [0,0,0], "max": [0,0,0]"#));
+    }
+}
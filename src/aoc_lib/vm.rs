@@ -0,0 +1,50 @@
+use nom::combinator::all_consuming;
+use nom::IResult;
+
+pub mod debugger;
+pub mod disasm;
+
+// 2016 days 12, 23 and 25
+pub mod assembunny;
+// 2018 days 16, 19 and 21
+pub mod elfcode;
+// 2019 days 2, 5, 7 and 9
+pub mod intcode;
+// 2020 day 8
+pub mod handheld;
+// 2021 day 24
+pub mod alu;
+
+// All the instruction sets are one instruction per line, so the only thing that
+// changes between machines is the parser for the single line
+pub fn parse_program<I>(
+    input: &str,
+    instruction: impl Fn(&str) -> IResult<&str, I>,
+) -> Result<Vec<I>, String> {
+    input
+        .lines()
+        .map(|l| l.trim())
+        .enumerate()
+        .filter(|(_, l)| !l.is_empty())
+        .map(|(line_id, line)| {
+            all_consuming(&instruction)(line)
+                .map(|(_, instr)| instr)
+                .map_err(|_| format!("Invalid instruction on line {}: \"{}\"", line_id + 1, line))
+        })
+        .collect()
+}
+
+// Every copy of the program with exactly one instruction replaced, for the
+// "one instruction is corrupted" kind of puzzles
+pub fn single_mutations<'a, I: Clone>(
+    program: &'a [I],
+    mutate: impl Fn(&I) -> Option<I> + 'a,
+) -> impl Iterator<Item = Vec<I>> + 'a {
+    program.iter().enumerate().filter_map(move |(id, instr)| {
+        mutate(instr).map(|mutated| {
+            let mut new_program = program.to_vec();
+            new_program[id] = mutated;
+            new_program
+        })
+    })
+}
@@ -0,0 +1,589 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::{one_of, space1};
+use nom::combinator::map;
+use nom::sequence::{preceded, separated_pair};
+use nom::IResult;
+
+use crate::aoc_lib::vm::debugger::Debuggable;
+use crate::aoc_lib::vm::disasm::{Flow, Listing};
+use crate::aoc_lib::{jazz_parser, vm};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Operand {
+    Register(usize),
+    Value(i64),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Instruction {
+    Inp(usize),
+    Add(usize, Operand),
+    Mul(usize, Operand),
+    Div(usize, Operand),
+    Mod(usize, Operand),
+    Eql(usize, Operand),
+}
+
+fn register_name(reg: usize) -> char {
+    (b'w' + reg as u8) as char
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operand::Register(reg) => write!(f, "{}", register_name(*reg)),
+            Operand::Value(val) => write!(f, "{}", val),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (name, target, source) = match self {
+            Instruction::Inp(target) => return write!(f, "inp {}", register_name(*target)),
+            Instruction::Add(target, source) => ("add", target, source),
+            Instruction::Mul(target, source) => ("mul", target, source),
+            Instruction::Div(target, source) => ("div", target, source),
+            Instruction::Mod(target, source) => ("mod", target, source),
+            Instruction::Eql(target, source) => ("eql", target, source),
+        };
+        write!(f, "{} {} {}", name, register_name(*target), source)
+    }
+}
+
+fn register(input: &str) -> IResult<&str, usize> {
+    map(one_of("wxyz"), |reg| reg as usize - 'w' as usize)(input)
+}
+
+fn operand(input: &str) -> IResult<&str, Operand> {
+    alt((
+        map(register, Operand::Register),
+        map(jazz_parser::i64, Operand::Value),
+    ))(input)
+}
+
+fn binary(name: &'static str) -> impl Fn(&str) -> IResult<&str, (usize, Operand)> {
+    move |input| preceded(tag(name), separated_pair(register, space1, operand))(input)
+}
+
+fn instruction(input: &str) -> IResult<&str, Instruction> {
+    alt((
+        map(preceded(tag("inp "), register), Instruction::Inp),
+        map(binary("add "), |(target, source)| {
+            Instruction::Add(target, source)
+        }),
+        map(binary("mul "), |(target, source)| {
+            Instruction::Mul(target, source)
+        }),
+        map(binary("div "), |(target, source)| {
+            Instruction::Div(target, source)
+        }),
+        map(binary("mod "), |(target, source)| {
+            Instruction::Mod(target, source)
+        }),
+        map(binary("eql "), |(target, source)| {
+            Instruction::Eql(target, source)
+        }),
+    ))(input)
+}
+
+pub fn parse_program(input: &str) -> Result<Vec<Instruction>, String> {
+    vm::parse_program(input, instruction)
+}
+
+#[derive(Debug, Clone)]
+pub struct Machine {
+    program: Vec<Instruction>,
+    registers: [i64; 4],
+    pc: usize,
+    input: VecDeque<i64>,
+    fault: Option<String>,
+}
+
+impl Machine {
+    pub fn new(program: Vec<Instruction>, input: &[i64]) -> Self {
+        Self {
+            program,
+            registers: [0; 4],
+            pc: 0,
+            input: input.iter().copied().collect(),
+            fault: None,
+        }
+    }
+
+    pub fn register(&self, name: char) -> i64 {
+        self.registers[name as usize - 'w' as usize]
+    }
+
+    pub fn registers(&self) -> &[i64; 4] {
+        &self.registers
+    }
+
+    pub fn current_instruction(&self) -> Option<Instruction> {
+        self.program.get(self.pc).copied()
+    }
+
+    fn value(&self, operand: Operand) -> i64 {
+        match operand {
+            Operand::Register(reg) => self.registers[reg],
+            Operand::Value(val) => val,
+        }
+    }
+
+    // Returns Ok(false) once the program is over. Reading past the end of the
+    // input and the divisions the puzzle declares invalid are errors.
+    pub fn execute(&mut self) -> Result<bool, String> {
+        if let Some(fault) = &self.fault {
+            return Err(fault.clone());
+        }
+        let Some(current) = self.current_instruction() else {
+            return Ok(false);
+        };
+
+        let result = match current {
+            Instruction::Inp(target) => self
+                .input
+                .pop_front()
+                .map(|value| (target, value))
+                .ok_or_else(|| String::from("Input exhausted")),
+            Instruction::Add(target, source) => {
+                Ok((target, self.registers[target] + self.value(source)))
+            }
+            Instruction::Mul(target, source) => {
+                Ok((target, self.registers[target] * self.value(source)))
+            }
+            Instruction::Div(target, source) => match self.value(source) {
+                0 => Err(String::from("Division by zero")),
+                divisor => Ok((target, self.registers[target] / divisor)),
+            },
+            Instruction::Mod(target, source) => {
+                let (value, modulo) = (self.registers[target], self.value(source));
+                if value < 0 || modulo <= 0 {
+                    Err(format!("Invalid modulo {} % {}", value, modulo))
+                } else {
+                    Ok((target, value % modulo))
+                }
+            }
+            Instruction::Eql(target, source) => Ok((
+                target,
+                (self.registers[target] == self.value(source)) as i64,
+            )),
+        };
+
+        match result {
+            Ok((target, value)) => {
+                self.registers[target] = value;
+                self.pc += 1;
+                Ok(true)
+            }
+            Err(fault) => {
+                let fault = format!("{} at instruction {}", fault, self.pc);
+                self.fault = Some(fault.clone());
+                Err(fault)
+            }
+        }
+    }
+
+    pub fn run(&mut self) -> Result<(), String> {
+        while self.execute()? {}
+
+        Ok(())
+    }
+}
+
+impl Debuggable for Machine {
+    type Instruction = Instruction;
+
+    fn pc(&self) -> i64 {
+        self.pc as i64
+    }
+
+    fn current_instruction(&self) -> Option<Instruction> {
+        self.current_instruction()
+    }
+
+    fn register_dump(&self) -> Vec<(String, i64)> {
+        ('w'..='z')
+            .zip(self.registers)
+            .map(|(name, value)| (name.to_string(), value))
+            .collect()
+    }
+
+    // A faulted machine just stops, the fault is still there for `run`
+    fn step(&mut self) -> bool {
+        self.execute().unwrap_or(false)
+    }
+
+    fn parse_instruction(line: &str) -> Option<Instruction> {
+        vm::parse_program(line, instruction).ok()?.pop()
+    }
+}
+
+// The ALU has no jumps at all
+impl Listing for Vec<Instruction> {
+    fn listing(&self) -> Vec<(String, Flow)> {
+        self.iter()
+            .map(|instr| (instr.to_string(), Flow::Next))
+            .collect()
+    }
+}
+
+// A MONAD model number is valid if z is zero at the end
+pub fn validate(program: &[Instruction], digits: &[u8]) -> Result<bool, String> {
+    let input: Vec<i64> = digits.iter().map(|&d| i64::from(d)).collect();
+    let mut machine = Machine::new(program.to_vec(), &input);
+    machine.run()?;
+
+    Ok(machine.register('z') == 0)
+}
+
+// MONAD is the same 18 instructions per digit, only three constants change:
+// whether z is divided by 26 (pop), the value compared against the digit and
+// the offset added to the digit before it's pushed on the base 26 "stack" in z
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Block {
+    pub pops: bool,
+    pub check: i64,
+    pub offset: i64,
+}
+
+const BLOCK_LENGTH: usize = 18;
+
+pub fn extract_blocks(program: &[Instruction]) -> Result<Vec<Block>, String> {
+    use Instruction::{Add, Div, Inp};
+    use Operand::Value;
+
+    if program.is_empty() || !program.len().is_multiple_of(BLOCK_LENGTH) {
+        return Err(format!(
+            "Program length {} is not a multiple of {}",
+            program.len(),
+            BLOCK_LENGTH
+        ));
+    }
+
+    program
+        .chunks(BLOCK_LENGTH)
+        .enumerate()
+        .map(
+            |(block_id, block)| match (block[0], block[4], block[5], block[15]) {
+                (
+                    Inp(0),
+                    Div(3, Value(divisor @ (1 | 26))),
+                    Add(1, Value(check)),
+                    Add(2, Value(offset)),
+                ) => Ok(Block {
+                    pops: divisor == 26,
+                    check,
+                    offset,
+                }),
+                _ => Err(format!(
+                    "Block {} doesn't look like a MONAD digit check",
+                    block_id
+                )),
+            },
+        )
+        .collect()
+}
+
+// Every pushing block is paired with a popping one, which only keeps z from
+// growing if digit[pop] == digit[push] + offset[push] + check[pop]. The pairs
+// come out as (push, pop, difference).
+pub fn digit_pairs(blocks: &[Block]) -> Result<Vec<(usize, usize, i64)>, String> {
+    let mut pairs: Vec<(usize, usize, i64)> = Vec::new();
+    let mut stack: Vec<(usize, i64)> = Vec::new();
+
+    for (block_id, block) in blocks.iter().enumerate() {
+        if !block.pops {
+            stack.push((block_id, block.offset));
+            continue;
+        }
+
+        let (push_id, push_offset) = stack
+            .pop()
+            .ok_or_else(|| format!("Block {} pops from an empty stack", block_id))?;
+        let target_diff = push_offset + block.check;
+        if target_diff.abs() > 8 {
+            return Err(format!(
+                "Blocks {} and {} can never be satisfied",
+                push_id, block_id
+            ));
+        }
+
+        pairs.push((push_id, block_id, target_diff));
+    }
+
+    if let Some((push_id, _)) = stack.pop() {
+        return Err(format!("Block {} is never popped", push_id));
+    }
+
+    Ok(pairs)
+}
+
+// Each pair can be maximised or minimised on its own
+pub fn solve(blocks: &[Block]) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let mut min_code: Vec<u8> = vec![0; blocks.len()];
+    let mut max_code: Vec<u8> = vec![0; blocks.len()];
+
+    for (push_id, pop_id, target_diff) in digit_pairs(blocks)? {
+        let (min, max) = if target_diff > 0 {
+            ((1, 1 + target_diff), (9 - target_diff, 9))
+        } else {
+            ((1 - target_diff, 1), (9, 9 + target_diff))
+        };
+
+        min_code[push_id] = min.0 as u8;
+        min_code[pop_id] = min.1 as u8;
+        max_code[push_id] = max.0 as u8;
+        max_code[pop_id] = max.1 as u8;
+    }
+
+    Ok((min_code, max_code))
+}
+
+fn monad_block(pops: bool, check: i64, offset: i64) -> String {
+    format!(
+        "inp w
+        mul x 0
+        add x z
+        mod x 26
+        div z {}
+        add x {}
+        eql x w
+        eql x 0
+        mul y 0
+        add y 25
+        mul y x
+        add y 1
+        mul z y
+        mul y 0
+        add y w
+        add y {}
+        mul y x
+        add z y",
+        if pops { 26 } else { 1 },
+        check,
+        offset
+    )
+}
+
+// Same push/pop shape as a real input, with made up constants, for the tests
+// of the days running MONAD
+pub fn synthetic_monad() -> String {
+    let params = [
+        (false, 12, 4),
+        (false, 11, 10),
+        (false, 14, 12),
+        (true, -6, 14),
+        (true, -6, 6),
+        (false, 13, 16),
+        (true, -9, 1),
+        (false, 15, 7),
+        (false, 10, 8),
+        (true, -5, 11),
+        (true, -9, 8),
+        (false, 14, 15),
+        (true, -8, 3),
+        (true, -2, 1),
+    ];
+
+    params
+        .iter()
+        .map(|&(pops, check, offset)| monad_block(pops, check, offset))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let program = parse_program("inp w\nadd z w\nmod z 2\neql x -3").unwrap();
+
+        assert_eq!(
+            program,
+            vec![
+                Instruction::Inp(0),
+                Instruction::Add(3, Operand::Register(0)),
+                Instruction::Mod(3, Operand::Value(2)),
+                Instruction::Eql(1, Operand::Value(-3)),
+            ]
+        );
+    }
+
+    #[test]
+    fn binary_conversion() {
+        let program = parse_program(
+            "inp w
+            add z w
+            mod z 2
+            div w 2
+            add y w
+            mod y 2
+            div w 2
+            add x w
+            mod x 2
+            div w 2
+            mod w 2",
+        )
+        .unwrap();
+
+        let mut machine = Machine::new(program, &[7]);
+        machine.run().unwrap();
+
+        assert_eq!(machine.registers(), &[0, 1, 1, 1]);
+    }
+
+    #[test]
+    fn faults() {
+        let program = parse_program("inp w\ninp x").unwrap();
+        assert!(Machine::new(program, &[1]).run().is_err());
+
+        let program = parse_program("div w 0").unwrap();
+        assert!(Machine::new(program, &[]).run().is_err());
+
+        let program = parse_program("add w -1\nmod w 3").unwrap();
+        assert!(Machine::new(program, &[]).run().is_err());
+    }
+
+    #[test]
+    fn blocks() {
+        let program = parse_program(&synthetic_monad()).unwrap();
+        let blocks = extract_blocks(&program).unwrap();
+
+        assert_eq!(blocks.len(), 14);
+        assert_eq!(
+            blocks[3],
+            Block {
+                pops: true,
+                check: -6,
+                offset: 14
+            }
+        );
+        assert!(extract_blocks(&program[1..]).is_err());
+    }
+
+    #[test]
+    fn solve_synthetic() {
+        let program = parse_program(&synthetic_monad()).unwrap();
+        let blocks = extract_blocks(&program).unwrap();
+        let (min, max) = solve(&blocks).unwrap();
+
+        assert!(validate(&program, &min).unwrap());
+        assert!(validate(&program, &max).unwrap());
+        assert!(min < max);
+
+        // Bumping the first digit breaks its pair
+        let mut broken = max.clone();
+        broken[0] = if broken[0] == 9 { 8 } else { broken[0] + 1 };
+        assert!(!validate(&program, &broken).unwrap());
+    }
+
+    #[test]
+    fn unbalanced() {
+        let blocks = [Block {
+            pops: false,
+            check: 10,
+            offset: 3,
+        }];
+
+        assert!(solve(&blocks).is_err());
+    }
+
+    // Runs `source` with w = 7, x = -3 and z = 2, returns the registers or
+    // the fault
+    fn run_from(source: &str, input: &[i64]) -> Result<[i64; 4], String> {
+        let mut program = parse_program("add w 7\nadd x -3\nadd z 2").unwrap();
+        program.extend(parse_program(source)?);
+        let mut machine = Machine::new(program, input);
+        machine.run().map(|_| *machine.registers())
+    }
+
+    #[test]
+    fn every_instruction() {
+        let cases = [
+            ("inp y", [7, -3, 5, 2]),
+            ("inp w\ninp w", [6, -3, 0, 2]),
+            ("add w x", [4, -3, 0, 2]),
+            ("add x -4", [7, -7, 0, 2]),
+            ("mul w x", [-21, -3, 0, 2]),
+            ("mul z 0", [7, -3, 0, 0]),
+            ("div w z", [3, -3, 0, 2]),
+            ("div x z", [7, -1, 0, 2]),
+            ("div w -2", [-3, -3, 0, 2]),
+            ("mod w z", [1, -3, 0, 2]),
+            ("mod w 7", [0, -3, 0, 2]),
+            ("eql w 7", [1, -3, 0, 2]),
+            ("eql x w", [7, 0, 0, 2]),
+            ("eql y y", [7, -3, 1, 2]),
+        ];
+
+        for (source, registers) in cases {
+            assert_eq!(run_from(source, &[5, 6]), Ok(registers), "{}", source);
+        }
+    }
+
+    #[test]
+    fn fault_messages() {
+        let faults = [
+            ("inp w\ninp x\ninp y", "Input exhausted at instruction 5"),
+            ("div w 0", "Division by zero at instruction 3"),
+            ("div z y", "Division by zero at instruction 3"),
+            ("mod x 2", "Invalid modulo -3 % 2 at instruction 3"),
+            ("mod w 0", "Invalid modulo 7 % 0 at instruction 3"),
+            ("mod w x", "Invalid modulo 7 % -3 at instruction 3"),
+        ];
+
+        for (source, fault) in faults {
+            assert_eq!(run_from(source, &[1, 2]), Err(String::from(fault)));
+        }
+
+        // A faulted machine stays faulted, and keeps its registers
+        let mut machine = Machine::new(parse_program("add w 1\ndiv w 0").unwrap(), &[]);
+        assert!(machine.run().is_err());
+        assert_eq!(
+            machine.execute(),
+            Err(String::from("Division by zero at instruction 1"))
+        );
+        assert_eq!(machine.registers(), &[1, 0, 0, 0]);
+        assert!(!<Machine as Debuggable>::step(&mut machine));
+    }
+
+    #[test]
+    fn source_round_trip() {
+        let source = "inp w
+            inp z
+            add x w
+            add y -12
+            mul z 26
+            mul w y
+            div z 26
+            div y 1
+            mod x 26
+            mod y z
+            eql x w
+            eql x 0";
+        let program = parse_program(source).unwrap();
+
+        let lines: Vec<String> = source.lines().map(|l| l.trim().to_string()).collect();
+        let printed: Vec<String> = program.iter().map(|i| i.to_string()).collect();
+        assert_eq!(printed, lines);
+        assert_eq!(parse_program(&printed.join("\n")), Ok(program.clone()));
+
+        let listing = program.listing();
+        assert!(listing.iter().all(|(_, flow)| *flow == Flow::Next));
+        for instruction in program {
+            assert_eq!(
+                <Machine as Debuggable>::parse_instruction(&instruction.to_string()),
+                Some(instruction)
+            );
+        }
+        assert_eq!(<Machine as Debuggable>::parse_instruction("inp 3"), None);
+        assert!(parse_program("add v 1").is_err());
+    }
+}
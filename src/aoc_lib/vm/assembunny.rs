@@ -0,0 +1,681 @@
+use std::fmt;
+
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::{one_of, space1};
+use nom::combinator::map;
+use nom::sequence::{preceded, separated_pair};
+use nom::IResult;
+
+use crate::aoc_lib::vm::debugger::Debuggable;
+use crate::aoc_lib::vm::disasm::{Flow, Listing};
+use crate::aoc_lib::{jazz_parser, vm};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Operand {
+    Register(usize),
+    Value(i64),
+}
+
+// Targets are operands too, since a `tgl` can turn `jnz 1 3` into `cpy 1 3`,
+// which is then skipped as invalid
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Instruction {
+    Cpy(Operand, Operand),
+    Inc(Operand),
+    Dec(Operand),
+    Jnz(Operand, Operand),
+    Tgl(Operand),
+    Out(Operand),
+}
+
+impl Instruction {
+    pub fn toggled(&self) -> Self {
+        match *self {
+            Self::Inc(target) => Self::Dec(target),
+            Self::Dec(target) | Self::Tgl(target) | Self::Out(target) => Self::Inc(target),
+            Self::Jnz(value, target) => Self::Cpy(value, target),
+            Self::Cpy(value, target) => Self::Jnz(value, target),
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operand::Register(reg) => write!(f, "{}", (b'a' + *reg as u8) as char),
+            Operand::Value(val) => write!(f, "{}", val),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Instruction::Cpy(value, target) => write!(f, "cpy {} {}", value, target),
+            Instruction::Inc(target) => write!(f, "inc {}", target),
+            Instruction::Dec(target) => write!(f, "dec {}", target),
+            Instruction::Jnz(value, offset) => write!(f, "jnz {} {}", value, offset),
+            Instruction::Tgl(offset) => write!(f, "tgl {}", offset),
+            Instruction::Out(value) => write!(f, "out {}", value),
+        }
+    }
+}
+
+fn operand(input: &str) -> IResult<&str, Operand> {
+    alt((
+        map(one_of("abcd"), |reg| {
+            Operand::Register(reg as usize - 'a' as usize)
+        }),
+        map(jazz_parser::i64, Operand::Value),
+    ))(input)
+}
+
+fn operand_pair(input: &str) -> IResult<&str, (Operand, Operand)> {
+    separated_pair(operand, space1, operand)(input)
+}
+
+fn instruction(input: &str) -> IResult<&str, Instruction> {
+    alt((
+        map(preceded(tag("cpy "), operand_pair), |(value, target)| {
+            Instruction::Cpy(value, target)
+        }),
+        map(preceded(tag("inc "), operand), Instruction::Inc),
+        map(preceded(tag("dec "), operand), Instruction::Dec),
+        map(preceded(tag("jnz "), operand_pair), |(value, offset)| {
+            Instruction::Jnz(value, offset)
+        }),
+        map(preceded(tag("tgl "), operand), Instruction::Tgl),
+        map(preceded(tag("out "), operand), Instruction::Out),
+    ))(input)
+}
+
+pub fn parse_program(input: &str) -> Result<Vec<Instruction>, String> {
+    vm::parse_program(input, instruction)
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Shortcut {
+    // target += source, source = 0
+    Add {
+        target: usize,
+        source: usize,
+    },
+    // target += factor * outer, counter = 0, outer = 0
+    Multiply {
+        target: usize,
+        factor: Operand,
+        counter: usize,
+        outer: usize,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Machine {
+    program: Vec<Instruction>,
+    registers: [i64; 4],
+    pc: i64,
+    output: Vec<i64>,
+    optimize: bool,
+    steps: usize,
+}
+
+impl Machine {
+    pub fn new(program: Vec<Instruction>) -> Self {
+        Self {
+            program,
+            registers: [0; 4],
+            pc: 0,
+            output: Vec::new(),
+            optimize: true,
+            steps: 0,
+        }
+    }
+
+    // Running without the peephole pass is only really useful to check that the
+    // shortcuts don't change the results
+    pub fn unoptimized(program: Vec<Instruction>) -> Self {
+        Self {
+            optimize: false,
+            ..Self::new(program)
+        }
+    }
+
+    pub fn register(&self, name: char) -> i64 {
+        self.registers[name as usize - 'a' as usize]
+    }
+
+    pub fn set_register(&mut self, name: char, value: i64) {
+        self.registers[name as usize - 'a' as usize] = value;
+    }
+
+    pub fn output(&self) -> &[i64] {
+        &self.output
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn is_halted(&self) -> bool {
+        usize::try_from(self.pc)
+            .map(|pc| pc >= self.program.len())
+            .unwrap_or(true)
+    }
+
+    fn value(&self, operand: Operand) -> i64 {
+        match operand {
+            Operand::Register(reg) => self.registers[reg],
+            Operand::Value(val) => val,
+        }
+    }
+
+    fn instruction_at(&self, pc: i64) -> Option<Instruction> {
+        usize::try_from(pc)
+            .ok()
+            .and_then(|id| self.program.get(id))
+            .copied()
+    }
+
+    // Matches `inc target; dec source; jnz source -2` (in either order)
+    fn add_loop_at(&self, pc: i64) -> Option<(usize, usize)> {
+        use Instruction::{Dec, Inc, Jnz};
+        use Operand::Register;
+
+        let (target, source) = match (self.instruction_at(pc)?, self.instruction_at(pc + 1)?) {
+            (Inc(Register(target)), Dec(Register(source)))
+            | (Dec(Register(source)), Inc(Register(target))) => (target, source),
+            _ => return None,
+        };
+        let closes_loop = self.instruction_at(pc + 2)? == Jnz(Register(source), Operand::Value(-2));
+
+        (closes_loop && target != source).then_some((target, source))
+    }
+
+    // Since `tgl` can rewrite the program while it runs the patterns have to be
+    // checked against the current state of the code every time
+    fn find_shortcut(&self) -> Option<(Shortcut, i64)> {
+        use Instruction::{Cpy, Dec, Jnz};
+        use Operand::Register;
+
+        // cpy factor counter; <add loop over counter>; dec outer; jnz outer -5
+        if let Some((target, counter)) = self.add_loop_at(self.pc + 1) {
+            if let (Some(Cpy(factor, Register(copy_target))), Some(Dec(Register(outer)))) = (
+                self.instruction_at(self.pc),
+                self.instruction_at(self.pc + 4),
+            ) {
+                let closes_loop = self.instruction_at(self.pc + 5)
+                    == Some(Jnz(Register(outer), Operand::Value(-5)));
+                let registers_distinct = target != outer && counter != outer;
+                let factor_independent = factor != Register(counter)
+                    && factor != Register(outer)
+                    && factor != Register(target);
+
+                if closes_loop
+                    && copy_target == counter
+                    && registers_distinct
+                    && factor_independent
+                    && self.value(factor) > 0
+                    && self.registers[outer] > 0
+                {
+                    let shortcut = Shortcut::Multiply {
+                        target,
+                        factor,
+                        counter,
+                        outer,
+                    };
+                    return Some((shortcut, 6));
+                }
+            }
+        }
+
+        let (target, source) = self.add_loop_at(self.pc)?;
+        (self.registers[source] > 0).then_some((Shortcut::Add { target, source }, 3))
+    }
+
+    fn apply_shortcut(&mut self, shortcut: Shortcut) {
+        match shortcut {
+            Shortcut::Add { target, source } => {
+                self.registers[target] += self.registers[source];
+                self.registers[source] = 0;
+            }
+            Shortcut::Multiply {
+                target,
+                factor,
+                counter,
+                outer,
+            } => {
+                self.registers[target] += self.value(factor) * self.registers[outer];
+                self.registers[counter] = 0;
+                self.registers[outer] = 0;
+            }
+        }
+    }
+
+    // Executes a single instruction (or a whole recognised loop), returns false
+    // when the program counter left the program
+    pub fn step(&mut self) -> bool {
+        let Some(current) = self.instruction_at(self.pc) else {
+            return false;
+        };
+
+        self.steps += 1;
+
+        if self.optimize {
+            if let Some((shortcut, length)) = self.find_shortcut() {
+                self.apply_shortcut(shortcut);
+                self.pc += length;
+                return true;
+            }
+        }
+
+        let mut next_pc = self.pc + 1;
+
+        match current {
+            Instruction::Cpy(value, Operand::Register(target)) => {
+                self.registers[target] = self.value(value);
+            }
+            Instruction::Inc(Operand::Register(target)) => self.registers[target] += 1,
+            Instruction::Dec(Operand::Register(target)) => self.registers[target] -= 1,
+            Instruction::Jnz(value, offset) if self.value(value) != 0 => {
+                next_pc = self.pc + self.value(offset);
+            }
+            Instruction::Tgl(offset) => {
+                let target_pc = self.pc + self.value(offset);
+                if let Some(target) = usize::try_from(target_pc)
+                    .ok()
+                    .and_then(|id| self.program.get_mut(id))
+                {
+                    *target = target.toggled();
+                }
+            }
+            Instruction::Out(value) => {
+                let out_value = self.value(value);
+                self.output.push(out_value);
+            }
+            // Toggling can produce instructions that write into a constant, those are skipped
+            _ => {}
+        }
+
+        self.pc = next_pc;
+        true
+    }
+
+    pub fn run(&mut self) {
+        while self.step() {}
+    }
+
+    // Day 25 programs never halt, so we stop as soon as we have enough of the signal
+    pub fn run_until_output(&mut self, length: usize) -> &[i64] {
+        while self.output.len() < length && self.step() {}
+
+        &self.output
+    }
+}
+
+impl Debuggable for Machine {
+    type Instruction = Instruction;
+
+    fn pc(&self) -> i64 {
+        self.pc
+    }
+
+    fn current_instruction(&self) -> Option<Instruction> {
+        self.instruction_at(self.pc)
+    }
+
+    fn register_dump(&self) -> Vec<(String, i64)> {
+        ('a'..='d')
+            .zip(self.registers)
+            .map(|(name, value)| (name.to_string(), value))
+            .collect()
+    }
+
+    fn step(&mut self) -> bool {
+        self.step()
+    }
+
+    fn parse_instruction(line: &str) -> Option<Instruction> {
+        vm::parse_program(line, instruction).ok()?.pop()
+    }
+}
+
+// Toggles can still rewrite any of these, the listing is for the code as loaded
+impl Listing for Vec<Instruction> {
+    fn listing(&self) -> Vec<(String, Flow)> {
+        self.iter()
+            .enumerate()
+            .map(|(pc, instr)| {
+                let flow = match *instr {
+                    Instruction::Jnz(Operand::Value(0), _) => Flow::Next,
+                    Instruction::Jnz(value, Operand::Value(offset)) => Flow::Jump {
+                        target: pc as i64 + offset,
+                        conditional: matches!(value, Operand::Register(_)),
+                    },
+                    Instruction::Jnz(_, Operand::Register(_)) => Flow::Computed,
+                    _ => Flow::Next,
+                };
+                (instr.to_string(), flow)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let program = parse_program(
+            "cpy 41 a
+            inc a
+            jnz c -2
+            tgl -1
+            out b",
+        )
+        .unwrap();
+
+        let ref_program = vec![
+            Instruction::Cpy(Operand::Value(41), Operand::Register(0)),
+            Instruction::Inc(Operand::Register(0)),
+            Instruction::Jnz(Operand::Register(2), Operand::Value(-2)),
+            Instruction::Tgl(Operand::Value(-1)),
+            Instruction::Out(Operand::Register(1)),
+        ];
+
+        assert_eq!(program, ref_program);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse_program("cpy 41 a\nmul a b").unwrap_err();
+
+        assert_eq!(error, "Invalid instruction on line 2: \"mul a b\"");
+    }
+
+    #[test]
+    fn simple_monorail() {
+        let program = parse_program(
+            "cpy 41 a
+            inc a
+            inc a
+            dec a
+            jnz a 2
+            dec a",
+        )
+        .unwrap();
+
+        let mut machine = Machine::new(program);
+        machine.run();
+
+        assert_eq!(machine.register('a'), 42);
+    }
+
+    #[test]
+    fn simple_toggle() {
+        let program = parse_program(
+            "cpy 2 a
+            tgl a
+            tgl a
+            tgl a
+            cpy 1 a
+            dec a
+            dec a",
+        )
+        .unwrap();
+
+        let mut machine = Machine::new(program);
+        machine.run();
+
+        assert_eq!(machine.register('a'), 3);
+    }
+
+    #[test]
+    fn add_loop_shortcut() {
+        let program = parse_program(
+            "cpy 1000 b
+            cpy 7 a
+            inc a
+            dec b
+            jnz b -2",
+        )
+        .unwrap();
+
+        let mut fast = Machine::new(program.clone());
+        fast.run();
+        let mut slow = Machine::unoptimized(program);
+        slow.run();
+
+        assert_eq!(fast.register('a'), 1007);
+        assert_eq!(fast.registers, slow.registers);
+        assert!(fast.steps() < slow.steps());
+    }
+
+    #[test]
+    fn multiply_loop_shortcut() {
+        let program = parse_program(
+            "cpy 12 b
+            cpy 11 d
+            cpy b c
+            inc a
+            dec c
+            jnz c -2
+            dec d
+            jnz d -5",
+        )
+        .unwrap();
+
+        let mut fast = Machine::new(program.clone());
+        fast.run();
+        let mut slow = Machine::unoptimized(program);
+        slow.run();
+
+        assert_eq!(fast.register('a'), 132);
+        assert_eq!(fast.registers, slow.registers);
+        assert_eq!(fast.steps(), 3);
+    }
+
+    #[test]
+    fn multiply_by_the_target_is_not_shortcut() {
+        // The factor grows with every round of the outer loop, so this doubles
+        // `a` twice instead of multiplying it by two
+        let program = parse_program(
+            "cpy 2 a
+            cpy 2 d
+            cpy a c
+            inc a
+            dec c
+            jnz c -2
+            dec d
+            jnz d -5",
+        )
+        .unwrap();
+
+        let mut fast = Machine::new(program.clone());
+        fast.run();
+        let mut slow = Machine::unoptimized(program);
+        slow.run();
+
+        assert_eq!(slow.register('a'), 8);
+        assert_eq!(fast.registers, slow.registers);
+    }
+
+    #[test]
+    fn toggled_loop_is_not_shortcut() {
+        // The `tgl` turns the `dec b` into an `inc b`, so the loop would never
+        // end if we just applied the addition blindly. Limit the run instead.
+        let program = parse_program(
+            "cpy 3 b
+            tgl 2
+            inc a
+            dec b
+            jnz b -2",
+        )
+        .unwrap();
+
+        let mut machine = Machine::new(program);
+        for _ in 0..20 {
+            machine.step();
+        }
+
+        assert!(machine.register('b') > 3);
+        assert!(!machine.is_halted());
+    }
+
+    #[test]
+    fn clock_signal() {
+        let program = parse_program(
+            "cpy a d
+            out d
+            dec d
+            jnz 1 -2",
+        )
+        .unwrap();
+
+        let mut machine = Machine::new(program);
+        machine.set_register('a', 1);
+
+        assert_eq!(machine.run_until_output(4), &[1, 0, -1, -2]);
+    }
+
+    // Runs the program unoptimized from the given registers, returns them at
+    // the end and the pc the program left from
+    fn run_from(source: &str, registers: [i64; 4]) -> ([i64; 4], i64) {
+        let mut machine = Machine::unoptimized(parse_program(source).unwrap());
+        machine.registers = registers;
+        machine.run();
+        (machine.registers, machine.pc)
+    }
+
+    #[test]
+    fn every_instruction() {
+        assert_eq!(run_from("cpy 7 b", [0; 4]), ([0, 7, 0, 0], 1));
+        assert_eq!(run_from("cpy c a", [0, 0, -3, 0]), ([-3, 0, -3, 0], 1));
+        assert_eq!(run_from("inc d", [0; 4]), ([0, 0, 0, 1], 1));
+        assert_eq!(run_from("dec a", [0; 4]), ([-1, 0, 0, 0], 1));
+
+        // Taken, not taken, backwards out of the program and through a register
+        assert_eq!(run_from("jnz 1 2\ninc a", [0; 4]), ([0; 4], 2));
+        assert_eq!(run_from("jnz 0 2\ninc a", [0; 4]), ([1, 0, 0, 0], 2));
+        assert_eq!(run_from("jnz b -1", [0, -4, 0, 0]), ([0, -4, 0, 0], -1));
+        assert_eq!(
+            run_from("jnz 1 c\ninc a\ninc a", [0, 0, 2, 0]),
+            ([1, 0, 2, 0], 3)
+        );
+        let mut machine = Machine::new(parse_program("jnz 1 0").unwrap());
+        for _ in 0..10 {
+            assert!(machine.step());
+        }
+        assert_eq!(machine.pc, 0);
+
+        let mut machine = Machine::new(parse_program("out 3\nout a\nout -1").unwrap());
+        machine.set_register('a', 12);
+        machine.run();
+        assert_eq!(machine.output(), &[3, 12, -1]);
+    }
+
+    #[test]
+    fn toggle_edges() {
+        // Out of the program on both sides, nothing happens
+        assert_eq!(run_from("tgl 5\ninc a", [0; 4]), ([1, 0, 0, 0], 2));
+        assert_eq!(run_from("tgl -1\ninc a", [0; 4]), ([1, 0, 0, 0], 2));
+
+        // Toggling itself turns the tgl into an inc, which runs next time around
+        let mut machine = Machine::unoptimized(parse_program("tgl 0").unwrap());
+        machine.step();
+        assert_eq!(machine.program, vec![Instruction::Inc(Operand::Value(0))]);
+
+        // Instructions writing into a constant are skipped
+        assert_eq!(run_from("tgl 1\njnz 5 3\ninc b", [0; 4]), ([0, 1, 0, 0], 3));
+        assert_eq!(run_from("tgl 1\ntgl 3\ninc b", [0; 4]), ([0, 1, 0, 0], 3));
+        assert_eq!(run_from("inc 3\ndec -1\ncpy 1 2", [0; 4]), ([0; 4], 3));
+
+        // A jnz toggled from a cpy can jump on a register offset
+        assert_eq!(
+            run_from("tgl 1\ncpy 1 b\ninc a\ninc a", [0, 2, 0, 0]),
+            ([1, 2, 0, 0], 4)
+        );
+    }
+
+    #[test]
+    fn toggle_table() {
+        use Instruction::*;
+        use Operand::{Register, Value};
+
+        let (a, one) = (Register(0), Value(1));
+        let table = [
+            (Cpy(one, a), Jnz(one, a)),
+            (Jnz(one, a), Cpy(one, a)),
+            (Inc(a), Dec(a)),
+            (Dec(a), Inc(a)),
+            (Tgl(a), Inc(a)),
+            (Out(one), Inc(one)),
+        ];
+
+        for (instruction, toggled) in table {
+            assert_eq!(instruction.toggled(), toggled);
+        }
+    }
+
+    #[test]
+    fn source_round_trip() {
+        let source = "cpy 41 a
+            cpy -7 d
+            inc b
+            dec c
+            jnz 0 0
+            jnz c -2
+            jnz 1 d
+            tgl -1
+            tgl b
+            out 3
+            out a";
+        let program = parse_program(source).unwrap();
+
+        let lines: Vec<String> = source.lines().map(|l| l.trim().to_string()).collect();
+        let printed: Vec<String> = program.iter().map(|i| i.to_string()).collect();
+        assert_eq!(printed, lines);
+        assert_eq!(parse_program(&printed.join("\n")), Ok(program.clone()));
+
+        let listed: Vec<String> = program
+            .listing()
+            .into_iter()
+            .map(|(text, _)| text)
+            .collect();
+        assert_eq!(listed, printed);
+        for instruction in program {
+            assert_eq!(
+                <Machine as Debuggable>::parse_instruction(&instruction.to_string()),
+                Some(instruction)
+            );
+        }
+    }
+
+    #[test]
+    fn listing_flow() {
+        let program = parse_program("jnz 0 5\njnz a -1\njnz 1 3\njnz 1 a\ninc a").unwrap();
+        let flows: Vec<Flow> = program
+            .listing()
+            .into_iter()
+            .map(|(_, flow)| flow)
+            .collect();
+
+        assert_eq!(
+            flows,
+            vec![
+                Flow::Next,
+                Flow::Jump {
+                    target: 0,
+                    conditional: true
+                },
+                Flow::Jump {
+                    target: 5,
+                    conditional: false
+                },
+                Flow::Computed,
+                Flow::Next,
+            ]
+        );
+    }
+}
@@ -0,0 +1,326 @@
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::{self, BufRead, BufWriter, Write};
+use std::path::Path;
+
+// What the debugger needs to know about a machine, every VM implements this so
+// the reverse engineering days can all be poked at the same way
+pub trait Debuggable {
+    type Instruction: Debug + PartialEq + Clone;
+
+    fn pc(&self) -> i64;
+    fn current_instruction(&self) -> Option<Self::Instruction>;
+    fn register_dump(&self) -> Vec<(String, i64)>;
+    // Returns false when the machine can't execute anything anymore
+    fn step(&mut self) -> bool;
+    // Used by the interactive prompt to set instruction breakpoints
+    fn parse_instruction(line: &str) -> Option<Self::Instruction>;
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Breakpoint<I> {
+    Pc(i64),
+    Instruction(I),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Stop {
+    Breakpoint(usize),
+    Halted,
+    StepLimit,
+}
+
+pub struct Debugger<M: Debuggable> {
+    machine: M,
+    breakpoints: Vec<Breakpoint<M::Instruction>>,
+    trace: Option<Box<dyn Write>>,
+}
+
+impl<M: Debuggable> Debugger<M> {
+    pub fn new(machine: M) -> Self {
+        Self {
+            machine,
+            breakpoints: Vec::new(),
+            trace: None,
+        }
+    }
+
+    pub fn machine(&self) -> &M {
+        &self.machine
+    }
+
+    pub fn into_machine(self) -> M {
+        self.machine
+    }
+
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint<M::Instruction>) {
+        if !self.breakpoints.contains(&breakpoint) {
+            self.breakpoints.push(breakpoint);
+        }
+    }
+
+    pub fn remove_breakpoint(&mut self, breakpoint: &Breakpoint<M::Instruction>) -> bool {
+        let before = self.breakpoints.len();
+        self.breakpoints.retain(|b| b != breakpoint);
+
+        before != self.breakpoints.len()
+    }
+
+    pub fn breakpoints(&self) -> &[Breakpoint<M::Instruction>] {
+        &self.breakpoints
+    }
+
+    // Every executed instruction gets a line with the state *before* running it
+    pub fn trace_to(&mut self, writer: impl Write + 'static) {
+        self.trace = Some(Box::new(writer));
+    }
+
+    pub fn trace_to_file(&mut self, path: &Path) -> io::Result<()> {
+        self.trace_to(BufWriter::new(File::create(path)?));
+        Ok(())
+    }
+
+    pub fn stop_tracing(&mut self) -> io::Result<()> {
+        match self.trace.take() {
+            Some(mut writer) => writer.flush(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn registers(&self) -> String {
+        self.machine
+            .register_dump()
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    fn state_line(&self) -> String {
+        let instruction = self
+            .machine
+            .current_instruction()
+            .map(|i| format!("{:?}", i))
+            .unwrap_or_else(|| String::from("<halted>"));
+
+        format!(
+            "{:>5}: {:<30} | {}",
+            self.machine.pc(),
+            instruction,
+            self.registers()
+        )
+    }
+
+    fn hit_breakpoint(&self) -> Option<usize> {
+        let current = self.machine.current_instruction();
+
+        self.breakpoints.iter().position(|b| match b {
+            Breakpoint::Pc(pc) => *pc == self.machine.pc(),
+            Breakpoint::Instruction(instr) => current.as_ref() == Some(instr),
+        })
+    }
+
+    pub fn step(&mut self) -> io::Result<bool> {
+        if self.trace.is_some() {
+            let line = self.state_line();
+            if let Some(writer) = self.trace.as_mut() {
+                writeln!(writer, "{}", line)?;
+            }
+        }
+
+        Ok(self.machine.step())
+    }
+
+    // Always executes at least one instruction, otherwise continuing from a
+    // breakpoint would never move
+    pub fn resume(&mut self, limit: Option<usize>) -> io::Result<Stop> {
+        let mut executed: usize = 0;
+
+        loop {
+            if limit.is_some_and(|limit| executed >= limit) {
+                return Ok(Stop::StepLimit);
+            }
+            if !self.step()? {
+                return Ok(Stop::Halted);
+            }
+            executed += 1;
+
+            if let Some(id) = self.hit_breakpoint() {
+                return Ok(Stop::Breakpoint(id));
+            }
+        }
+    }
+
+    fn report_stop(&self, stop: Stop, output: &mut impl Write) -> io::Result<()> {
+        match stop {
+            Stop::Breakpoint(id) => {
+                writeln!(output, "Breakpoint {} ({:?})", id, self.breakpoints[id])?
+            }
+            Stop::Halted => writeln!(output, "Halted")?,
+            Stop::StepLimit => {}
+        }
+
+        writeln!(output, "{}", self.state_line())
+    }
+
+    fn parse_breakpoint(argument: &str) -> Option<Breakpoint<M::Instruction>> {
+        argument
+            .parse::<i64>()
+            .ok()
+            .map(Breakpoint::Pc)
+            .or_else(|| M::parse_instruction(argument).map(Breakpoint::Instruction))
+    }
+
+    // A tiny gdb-like prompt. Commands:
+    //   s [n]      step n instructions (default 1)
+    //   c [n]      continue until a breakpoint, halt or n instructions
+    //   b <what>   break on a pc or on an instruction ("b 12", "b jmp +3")
+    //   d <what>   remove a breakpoint
+    //   l          list breakpoints
+    //   r          dump the registers
+    //   q          quit
+    pub fn interactive(&mut self, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+        writeln!(output, "{}", self.state_line())?;
+
+        for line in input.lines() {
+            let line = line?;
+            let line = line.trim();
+            let (command, argument) = line.split_once(' ').unwrap_or((line, ""));
+            let argument = argument.trim();
+            let count = argument.parse::<usize>().ok();
+
+            match command {
+                "s" | "step" => {
+                    let stop = self.resume(Some(count.unwrap_or(1)))?;
+                    self.report_stop(stop, &mut output)?;
+                }
+                "c" | "continue" => {
+                    let stop = self.resume(count)?;
+                    self.report_stop(stop, &mut output)?;
+                }
+                "b" | "break" => match Self::parse_breakpoint(argument) {
+                    Some(breakpoint) => self.add_breakpoint(breakpoint),
+                    None => writeln!(output, "Invalid breakpoint: \"{}\"", argument)?,
+                },
+                "d" | "delete" => {
+                    let removed = Self::parse_breakpoint(argument)
+                        .is_some_and(|breakpoint| self.remove_breakpoint(&breakpoint));
+                    if !removed {
+                        writeln!(output, "No such breakpoint: \"{}\"", argument)?;
+                    }
+                }
+                "l" | "list" => {
+                    for (id, breakpoint) in self.breakpoints.iter().enumerate() {
+                        writeln!(output, "{}: {:?}", id, breakpoint)?;
+                    }
+                }
+                "r" | "registers" => writeln!(output, "{}", self.registers())?,
+                "q" | "quit" => break,
+                "" => {}
+                _ => writeln!(output, "Unknown command: \"{}\"", command)?,
+            }
+        }
+
+        self.stop_tracing()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::aoc_lib::vm::{elfcode, handheld};
+
+    static CONSOLE_STRING: &str = "nop +0
+    acc +1
+    jmp +4
+    acc +3
+    jmp -3
+    acc -99
+    acc +1
+    jmp -4
+    acc +6";
+
+    fn console_debugger() -> Debugger<handheld::Console> {
+        let program = handheld::parse_program(CONSOLE_STRING).unwrap();
+        Debugger::new(handheld::Console::new(program))
+    }
+
+    #[test]
+    fn pc_breakpoint() {
+        let mut debugger = console_debugger();
+        debugger.add_breakpoint(Breakpoint::Pc(4));
+
+        assert_eq!(debugger.resume(None).unwrap(), Stop::Breakpoint(0));
+        assert_eq!(debugger.machine().pc(), 4);
+        assert_eq!(debugger.registers(), "acc=5");
+    }
+
+    #[test]
+    fn instruction_breakpoint() {
+        let mut debugger = console_debugger();
+        debugger.add_breakpoint(Breakpoint::Instruction(handheld::Instruction::Jmp(-4)));
+
+        assert_eq!(debugger.resume(None).unwrap(), Stop::Breakpoint(0));
+        assert_eq!(debugger.machine().pc(), 7);
+        // Continuing has to leave the breakpoint before checking again
+        assert_eq!(debugger.resume(Some(3)).unwrap(), Stop::StepLimit);
+    }
+
+    #[test]
+    fn run_to_halt() {
+        let program = elfcode::parse_program(
+            "#ip 0
+            seti 5 0 1
+            seti 6 0 2
+            addi 0 1 0
+            addr 1 2 3
+            setr 1 0 0
+            seti 8 0 4
+            seti 9 0 5",
+        )
+        .unwrap();
+        let mut debugger = Debugger::new(elfcode::Machine::new(program));
+
+        assert_eq!(debugger.resume(None).unwrap(), Stop::Halted);
+        assert_eq!(debugger.registers(), "r0=6 r1=5 r2=6 r3=0 r4=0 r5=9");
+    }
+
+    #[test]
+    fn trace_file() {
+        let path = std::env::temp_dir().join("aoc_debugger_trace.txt");
+        let mut debugger = console_debugger();
+        debugger.trace_to_file(&path).unwrap();
+        debugger.resume(Some(3)).unwrap();
+        debugger.stop_tracing().unwrap();
+
+        let trace = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let pcs: Vec<&str> = trace
+            .lines()
+            .map(|l| l.split(':').next().unwrap().trim())
+            .collect();
+        assert_eq!(pcs, vec!["0", "1", "2"]);
+        assert!(trace.lines().nth(1).unwrap().ends_with("acc=0"));
+    }
+
+    #[test]
+    fn interactive_session() {
+        let mut debugger = console_debugger();
+        let commands = "b jmp -4\nb 6\nl\nc\nr\nd 6\nx\ns 2\nq\ns\n";
+        let mut output: Vec<u8> = Vec::new();
+
+        debugger
+            .interactive(commands.as_bytes(), &mut output)
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.contains("0: Instruction(Jmp(-4))\n1: Pc(6)\n"));
+        assert!(output.contains("Breakpoint 1 (Pc(6))"));
+        assert!(output.contains("Unknown command: \"x\""));
+        assert_eq!(debugger.breakpoints().len(), 1);
+        // Quitting stops before the last step
+        assert_eq!(debugger.machine().pc(), 7);
+    }
+}
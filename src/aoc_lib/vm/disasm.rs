@@ -0,0 +1,199 @@
+use std::fmt::Write;
+
+use hashbrown::HashMap;
+use itertools::Itertools;
+
+// How an instruction hands over control, only static targets can be labelled
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Flow {
+    Next,
+    Jump { target: i64, conditional: bool },
+    // Target depends on a register or on self modifying code
+    Computed,
+}
+
+// Every VM program can list itself as source text plus control flow
+pub trait Listing {
+    fn listing(&self) -> Vec<(String, Flow)>;
+
+    // Directives that are not instructions, like the elfcode `#ip`
+    fn header(&self) -> Option<String> {
+        None
+    }
+}
+
+// Backward jumps are loops, covering everything from the target to the jump
+fn find_loops(listing: &[(String, Flow)]) -> Vec<(i64, i64)> {
+    listing
+        .iter()
+        .enumerate()
+        .filter_map(|(pc, (_, flow))| match *flow {
+            Flow::Jump { target, .. } if target <= pc as i64 && target >= 0 => {
+                Some((target, pc as i64))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+pub fn render(program: &impl Listing) -> String {
+    let listing = program.listing();
+    let length = listing.len() as i64;
+
+    let labels: HashMap<i64, String> = listing
+        .iter()
+        .filter_map(|(_, flow)| match flow {
+            Flow::Jump { target, .. } if (0..length).contains(target) => Some(*target),
+            _ => None,
+        })
+        .sorted()
+        .dedup()
+        .enumerate()
+        .map(|(id, target)| (target, format!("L{}", id)))
+        .collect();
+    let loops = find_loops(&listing);
+    let text_width = listing
+        .iter()
+        .map(|(text, _)| text.len())
+        .max()
+        .unwrap_or(0);
+
+    let mut output = String::new();
+    if let Some(header) = program.header() {
+        writeln!(output, "{}", header).unwrap();
+    }
+    for (pc, (text, flow)) in listing.iter().enumerate() {
+        let pc = pc as i64;
+        let depth = loops
+            .iter()
+            .filter(|(start, end)| (*start..=*end).contains(&pc))
+            .count();
+        let label = labels
+            .get(&pc)
+            .map(|label| format!("{}:", label))
+            .unwrap_or_default();
+
+        let comment = match *flow {
+            Flow::Next => String::new(),
+            Flow::Computed => String::from("; computed jump"),
+            Flow::Jump {
+                target,
+                conditional,
+            } => {
+                let destination = labels
+                    .get(&target)
+                    .cloned()
+                    .unwrap_or_else(|| String::from("exit"));
+                let kind = match (target <= pc && target >= 0, conditional) {
+                    (true, true) => "loop while",
+                    (true, false) => "loop forever",
+                    (false, true) => "branch",
+                    (false, false) => "jump",
+                };
+                format!("; {} -> {}", kind, destination)
+            }
+        };
+
+        let line = format!(
+            "{:>4} {:<5} {}{:<width$} {}",
+            pc,
+            label,
+            "| ".repeat(depth),
+            text,
+            comment,
+            width = text_width
+        );
+        writeln!(output, "{}", line.trim_end()).unwrap();
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::aoc_lib::vm::{assembunny, elfcode, handheld};
+
+    #[test]
+    fn assembunny_loops() {
+        let program = assembunny::parse_program(
+            "cpy 12 b
+            cpy 11 d
+            cpy b c
+            inc a
+            dec c
+            jnz c -2
+            dec d
+            jnz d -5
+            jnz 1 3
+            tgl a
+            jnz a c",
+        )
+        .unwrap();
+
+        let expected = "   0       cpy 12 b
+   1       cpy 11 d
+   2 L0:   | cpy b c
+   3 L1:   | | inc a
+   4       | | dec c
+   5       | | jnz c -2 ; loop while -> L1
+   6       | dec d
+   7       | jnz d -5 ; loop while -> L0
+   8       jnz 1 3  ; jump -> exit
+   9       tgl a
+  10       jnz a c  ; computed jump
+";
+
+        assert_eq!(render(&program), expected);
+    }
+
+    #[test]
+    fn handheld_labels() {
+        let program = handheld::parse_program("nop +0\njmp +2\nacc -1\njmp -3").unwrap();
+        let output = render(&program);
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines[0], "   0 L0:   | nop +0");
+        assert_eq!(lines[1], "   1       | jmp +2 ; jump -> L1");
+        assert_eq!(lines[3], "   3 L1:   | jmp -3 ; loop forever -> L0");
+    }
+
+    #[test]
+    fn elfcode_ip_writes() {
+        let program = elfcode::parse_program(
+            "#ip 2
+            seti 5 0 2
+            addi 2 1 2
+            eqrr 1 3 4
+            addr 4 2 2
+            seti 0 0 2
+            addi 1 1 1",
+        )
+        .unwrap();
+
+        let flows: Vec<Flow> = program.listing().into_iter().map(|(_, f)| f).collect();
+
+        assert_eq!(
+            flows,
+            vec![
+                Flow::Jump {
+                    target: 6,
+                    conditional: false
+                },
+                Flow::Jump {
+                    target: 3,
+                    conditional: false
+                },
+                Flow::Next,
+                Flow::Computed,
+                Flow::Jump {
+                    target: 1,
+                    conditional: false
+                },
+                Flow::Next,
+            ]
+        );
+        assert!(render(&program).starts_with("#ip 2\n"));
+    }
+}
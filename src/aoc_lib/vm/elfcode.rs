@@ -0,0 +1,717 @@
+use std::fmt;
+
+use hashbrown::HashSet;
+use nom::bytes::complete::tag;
+use nom::character::complete::{alpha1, char, space0, space1};
+use nom::combinator::{all_consuming, map_opt, map_res};
+use nom::multi::separated_list1;
+use nom::sequence::{delimited, preceded, tuple};
+use nom::IResult;
+
+use crate::aoc_lib::vm::debugger::Debuggable;
+use crate::aoc_lib::vm::disasm::{Flow, Listing};
+use crate::aoc_lib::{jazz_parser, vm};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Opcode {
+    Addr,
+    Addi,
+    Mulr,
+    Muli,
+    Banr,
+    Bani,
+    Borr,
+    Bori,
+    Setr,
+    Seti,
+    Gtir,
+    Gtri,
+    Gtrr,
+    Eqir,
+    Eqri,
+    Eqrr,
+}
+
+impl Opcode {
+    pub const ALL: [Opcode; 16] = [
+        Opcode::Addr,
+        Opcode::Addi,
+        Opcode::Mulr,
+        Opcode::Muli,
+        Opcode::Banr,
+        Opcode::Bani,
+        Opcode::Borr,
+        Opcode::Bori,
+        Opcode::Setr,
+        Opcode::Seti,
+        Opcode::Gtir,
+        Opcode::Gtri,
+        Opcode::Gtrr,
+        Opcode::Eqir,
+        Opcode::Eqri,
+        Opcode::Eqrr,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Opcode::Addr => "addr",
+            Opcode::Addi => "addi",
+            Opcode::Mulr => "mulr",
+            Opcode::Muli => "muli",
+            Opcode::Banr => "banr",
+            Opcode::Bani => "bani",
+            Opcode::Borr => "borr",
+            Opcode::Bori => "bori",
+            Opcode::Setr => "setr",
+            Opcode::Seti => "seti",
+            Opcode::Gtir => "gtir",
+            Opcode::Gtri => "gtri",
+            Opcode::Gtrr => "gtrr",
+            Opcode::Eqir => "eqir",
+            Opcode::Eqri => "eqri",
+            Opcode::Eqrr => "eqrr",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|opcode| opcode.name() == name)
+    }
+
+    // The value that ends up in register C, None if A or B reference a
+    // register that doesn't exist
+    fn compute(&self, registers: &[i64], a: i64, b: i64) -> Option<i64> {
+        let reg = |id: i64| {
+            usize::try_from(id)
+                .ok()
+                .and_then(|id| registers.get(id))
+                .copied()
+        };
+
+        let value = match self {
+            Opcode::Addr => reg(a)? + reg(b)?,
+            Opcode::Addi => reg(a)? + b,
+            Opcode::Mulr => reg(a)? * reg(b)?,
+            Opcode::Muli => reg(a)? * b,
+            Opcode::Banr => reg(a)? & reg(b)?,
+            Opcode::Bani => reg(a)? & b,
+            Opcode::Borr => reg(a)? | reg(b)?,
+            Opcode::Bori => reg(a)? | b,
+            Opcode::Setr => reg(a)?,
+            Opcode::Seti => a,
+            Opcode::Gtir => (a > reg(b)?) as i64,
+            Opcode::Gtri => (reg(a)? > b) as i64,
+            Opcode::Gtrr => (reg(a)? > reg(b)?) as i64,
+            Opcode::Eqir => (a == reg(b)?) as i64,
+            Opcode::Eqri => (reg(a)? == b) as i64,
+            Opcode::Eqrr => (reg(a)? == reg(b)?) as i64,
+        };
+
+        Some(value)
+    }
+
+    // Returns None if any of the registers is out of bounds, leaving the
+    // registers untouched
+    pub fn apply(&self, registers: &mut [i64], [a, b, c]: [i64; 3]) -> Option<()> {
+        let value = self.compute(registers, a, b)?;
+        let target = registers.get_mut(usize::try_from(c).ok()?)?;
+        *target = value;
+
+        Some(())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    pub args: [i64; 3],
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let [a, b, c] = self.args;
+        write!(f, "{} {} {} {}", self.opcode.name(), a, b, c)
+    }
+}
+
+// A captured execution from the wrist device manual: the registers before and
+// after running a single instruction with an unknown opcode number
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Sample {
+    pub before: Vec<i64>,
+    pub instruction: [i64; 4],
+    pub after: Vec<i64>,
+}
+
+impl Sample {
+    pub fn matching_opcodes(&self) -> impl Iterator<Item = Opcode> + '_ {
+        let [_, a, b, c] = self.instruction;
+
+        Opcode::ALL.into_iter().filter(move |opcode| {
+            let mut registers = self.before.clone();
+            opcode.apply(&mut registers, [a, b, c]).is_some() && registers == self.after
+        })
+    }
+}
+
+fn register_list(input: &str) -> IResult<&str, Vec<i64>> {
+    delimited(
+        char('['),
+        separated_list1(tuple((char(','), space0)), jazz_parser::i64),
+        char(']'),
+    )(input)
+}
+
+fn numeric_instruction(input: &str) -> IResult<&str, [i64; 4]> {
+    map_opt(separated_list1(space1, jazz_parser::i64), |values| {
+        <[i64; 4]>::try_from(values).ok()
+    })(input)
+}
+
+fn parse_line<'a, O>(
+    line: &'a str,
+    parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> Result<O, String> {
+    all_consuming(parser)(line)
+        .map(|(_, value)| value)
+        .map_err(|_| format!("Invalid manual line: \"{}\"", line))
+}
+
+// The manual is a list of samples followed by a test program written with
+// opcode numbers instead of names
+pub fn parse_manual(input: &str) -> Result<(Vec<Sample>, Vec<[i64; 4]>), String> {
+    let mut samples: Vec<Sample> = Vec::new();
+    let mut program: Vec<[i64; 4]> = Vec::new();
+
+    let mut lines = input.lines().map(|l| l.trim()).filter(|l| !l.is_empty());
+    while let Some(line) = lines.next() {
+        if line.starts_with("Before:") {
+            let (Some(instruction), Some(after)) = (lines.next(), lines.next()) else {
+                return Err(format!("Incomplete sample starting at \"{}\"", line));
+            };
+
+            samples.push(Sample {
+                before: parse_line(
+                    line,
+                    preceded(tuple((tag("Before:"), space1)), register_list),
+                )?,
+                instruction: parse_line(instruction, numeric_instruction)?,
+                after: parse_line(
+                    after,
+                    preceded(tuple((tag("After:"), space1)), register_list),
+                )?,
+            });
+        } else {
+            program.push(parse_line(line, numeric_instruction)?);
+        }
+    }
+
+    Ok((samples, program))
+}
+
+// Same elimination as the "which field is which" puzzles: an opcode number with
+// a single candidate left fixes that opcode, which is then removed from all the
+// other numbers
+pub fn resolve_opcodes(samples: &[Sample]) -> Result<[Opcode; 16], String> {
+    let mut candidates: Vec<HashSet<Opcode>> = vec![Opcode::ALL.into_iter().collect(); 16];
+
+    for sample in samples {
+        let number = usize::try_from(sample.instruction[0])
+            .ok()
+            .filter(|n| *n < 16)
+            .ok_or_else(|| format!("Invalid opcode number {}", sample.instruction[0]))?;
+        let matching: HashSet<Opcode> = sample.matching_opcodes().collect();
+        candidates[number].retain(|opcode| matching.contains(opcode));
+    }
+
+    let mut resolved: [Option<Opcode>; 16] = [None; 16];
+    while let Some(number) = (0..16).find(|&n| resolved[n].is_none() && candidates[n].len() == 1) {
+        let opcode = *candidates[number].iter().next().unwrap();
+        resolved[number] = Some(opcode);
+        for other in candidates.iter_mut() {
+            other.remove(&opcode);
+        }
+    }
+
+    let mut opcodes = [Opcode::Addr; 16];
+    for (number, opcode) in resolved.into_iter().enumerate() {
+        opcodes[number] =
+            opcode.ok_or_else(|| format!("Couldn't resolve opcode number {}", number))?;
+    }
+
+    Ok(opcodes)
+}
+
+pub fn decode(program: &[[i64; 4]], opcodes: &[Opcode; 16]) -> Result<Vec<Instruction>, String> {
+    program
+        .iter()
+        .map(|&[number, a, b, c]| {
+            usize::try_from(number)
+                .ok()
+                .and_then(|n| opcodes.get(n))
+                .map(|&opcode| Instruction {
+                    opcode,
+                    args: [a, b, c],
+                })
+                .ok_or_else(|| format!("Invalid opcode number {}", number))
+        })
+        .collect()
+}
+
+fn instruction(input: &str) -> IResult<&str, Instruction> {
+    let (input, opcode) = map_opt(alpha1, Opcode::from_name)(input)?;
+    let (input, (_, a, _, b, _, c)) = tuple((
+        space1,
+        jazz_parser::i64,
+        space1,
+        jazz_parser::i64,
+        space1,
+        jazz_parser::i64,
+    ))(input)?;
+
+    Ok((
+        input,
+        Instruction {
+            opcode,
+            args: [a, b, c],
+        },
+    ))
+}
+
+fn ip_declaration(input: &str) -> IResult<&str, usize> {
+    map_res(preceded(tag("#ip "), jazz_parser::usize), |register| {
+        if register < REGISTER_COUNT {
+            Ok(register)
+        } else {
+            Err("Instruction pointer bound to a non existing register")
+        }
+    })(input)
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Program {
+    pub ip_register: Option<usize>,
+    pub instructions: Vec<Instruction>,
+}
+
+// Days 19 and 21 start with an `#ip n` line, the rest is one instruction per line
+pub fn parse_program(input: &str) -> Result<Program, String> {
+    let input = input.trim_start();
+    let (ip_register, body) = match input.split_once('\n') {
+        Some((first, rest)) if first.trim().starts_with("#ip") => {
+            let register = all_consuming(ip_declaration)(first.trim())
+                .map(|(_, register)| register)
+                .map_err(|_| {
+                    format!(
+                        "Invalid instruction pointer declaration: \"{}\"",
+                        first.trim()
+                    )
+                })?;
+            (Some(register), rest)
+        }
+        _ => (None, input),
+    };
+
+    Ok(Program {
+        ip_register,
+        instructions: vm::parse_program(body, instruction)?,
+    })
+}
+
+pub const REGISTER_COUNT: usize = 6;
+
+#[derive(Debug, Clone)]
+pub struct Machine {
+    program: Program,
+    registers: [i64; REGISTER_COUNT],
+    pc: i64,
+    steps: usize,
+}
+
+impl Machine {
+    pub fn new(program: Program) -> Self {
+        Self {
+            program,
+            registers: [0; REGISTER_COUNT],
+            pc: 0,
+            steps: 0,
+        }
+    }
+
+    pub fn registers(&self) -> &[i64; REGISTER_COUNT] {
+        &self.registers
+    }
+
+    pub fn register(&self, id: usize) -> i64 {
+        self.registers[id]
+    }
+
+    pub fn set_register(&mut self, id: usize, value: i64) {
+        self.registers[id] = value;
+    }
+
+    pub fn pc(&self) -> i64 {
+        self.pc
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.current_instruction().is_none()
+    }
+
+    pub fn current_instruction(&self) -> Option<Instruction> {
+        usize::try_from(self.pc)
+            .ok()
+            .and_then(|pc| self.program.instructions.get(pc))
+            .copied()
+    }
+
+    // With a bound register the pc is written to it before the instruction and
+    // read back afterwards, so programs can jump by writing into it
+    pub fn step(&mut self) -> bool {
+        let Some(current) = self.current_instruction() else {
+            return false;
+        };
+
+        if let Some(ip) = self.program.ip_register {
+            self.registers[ip] = self.pc;
+        }
+
+        // The register ids are checked at parse time only for the ip, a
+        // program writing to a non existing register is just broken
+        current
+            .opcode
+            .apply(&mut self.registers, current.args)
+            .expect("Instruction referencing a non existing register");
+
+        if let Some(ip) = self.program.ip_register {
+            self.pc = self.registers[ip];
+        }
+        self.pc += 1;
+        self.steps += 1;
+
+        true
+    }
+
+    pub fn run(&mut self) {
+        while self.step() {}
+    }
+}
+
+impl Debuggable for Machine {
+    type Instruction = Instruction;
+
+    fn pc(&self) -> i64 {
+        self.pc
+    }
+
+    fn current_instruction(&self) -> Option<Instruction> {
+        self.current_instruction()
+    }
+
+    fn register_dump(&self) -> Vec<(String, i64)> {
+        self.registers
+            .iter()
+            .enumerate()
+            .map(|(id, value)| (format!("r{}", id), *value))
+            .collect()
+    }
+
+    fn step(&mut self) -> bool {
+        self.step()
+    }
+
+    fn parse_instruction(line: &str) -> Option<Instruction> {
+        vm::parse_program(line, instruction).ok()?.pop()
+    }
+}
+
+// Jumps are writes into the bound register, the pc gets incremented after them
+impl Listing for Program {
+    fn listing(&self) -> Vec<(String, Flow)> {
+        self.instructions
+            .iter()
+            .enumerate()
+            .map(|(pc, instr)| {
+                let [a, b, c] = instr.args;
+                let flow = match self.ip_register.map(|ip| ip as i64) {
+                    Some(ip) if c == ip => match instr.opcode {
+                        Opcode::Seti => Flow::Jump {
+                            target: a + 1,
+                            conditional: false,
+                        },
+                        Opcode::Addi if a == ip => Flow::Jump {
+                            target: pc as i64 + b + 1,
+                            conditional: false,
+                        },
+                        _ => Flow::Computed,
+                    },
+                    _ => Flow::Next,
+                };
+                (instr.to_string(), flow)
+            })
+            .collect()
+    }
+
+    fn header(&self) -> Option<String> {
+        self.ip_register.map(|ip| format!("#ip {}", ip))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    static MANUAL_STRING: &str = "Before: [3, 2, 1, 1]
+    9 2 1 2
+    After:  [3, 2, 2, 1]
+
+
+
+    9 2 1 2
+    9 0 0 3";
+
+    static PROGRAM_STRING: &str = "#ip 0
+    seti 5 0 1
+    seti 6 0 2
+    addi 0 1 0
+    addr 1 2 3
+    setr 1 0 0
+    seti 8 0 4
+    seti 9 0 5";
+
+    #[test]
+    fn parse_manual_sections() {
+        let (samples, program) = parse_manual(MANUAL_STRING).unwrap();
+
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].before, vec![3, 2, 1, 1]);
+        assert_eq!(samples[0].instruction, [9, 2, 1, 2]);
+        assert_eq!(samples[0].after, vec![3, 2, 2, 1]);
+        assert_eq!(program, vec![[9, 2, 1, 2], [9, 0, 0, 3]]);
+    }
+
+    #[test]
+    fn simple_sample() {
+        let (samples, _) = parse_manual(MANUAL_STRING).unwrap();
+        let matching: Vec<Opcode> = samples[0].matching_opcodes().collect();
+
+        assert_eq!(matching, vec![Opcode::Addi, Opcode::Mulr, Opcode::Seti]);
+    }
+
+    #[test]
+    fn resolve_scrambled() {
+        // Number the opcodes backwards and generate enough samples to pin each
+        // one down
+        let scrambled: Vec<Opcode> = Opcode::ALL.into_iter().rev().collect();
+        let starts = [[3, 2, 1, 1], [5, 9, 0, 2], [1, 4, 7, 3], [12, 6, 3, 3]];
+        let arguments = [[0, 1, 2], [2, 3, 0], [3, 0, 1], [1, 2, 3], [2, 2, 0]];
+
+        let mut samples: Vec<Sample> = Vec::new();
+        for (number, opcode) in scrambled.iter().enumerate() {
+            for (start, args) in starts.iter().cartesian_product(arguments.iter()) {
+                let mut after = start.to_vec();
+                opcode.apply(&mut after, *args).unwrap();
+
+                samples.push(Sample {
+                    before: start.to_vec(),
+                    instruction: [number as i64, args[0], args[1], args[2]],
+                    after,
+                });
+            }
+        }
+
+        let opcodes = resolve_opcodes(&samples).unwrap();
+
+        assert_eq!(opcodes.to_vec(), scrambled);
+    }
+
+    #[test]
+    fn unresolvable() {
+        assert!(resolve_opcodes(&[]).is_err());
+    }
+
+    #[test]
+    fn decode_program() {
+        let mut opcodes = Opcode::ALL;
+        opcodes.reverse();
+
+        let decoded = decode(&[[6, 1, 2, 3]], &opcodes).unwrap();
+
+        assert_eq!(decoded[0].opcode, Opcode::Seti);
+        assert!(decode(&[[16, 1, 2, 3]], &opcodes).is_err());
+    }
+
+    #[test]
+    fn parse() {
+        let program = parse_program(PROGRAM_STRING).unwrap();
+
+        assert_eq!(program.ip_register, Some(0));
+        assert_eq!(program.instructions.len(), 7);
+        assert_eq!(
+            program.instructions[2],
+            Instruction {
+                opcode: Opcode::Addi,
+                args: [0, 1, 0]
+            }
+        );
+    }
+
+    #[test]
+    fn parse_error() {
+        assert!(parse_program("#ip 6\nseti 5 0 1").is_err());
+        assert_eq!(
+            parse_program("seti 5 0 1\nmodr 1 2 3").unwrap_err(),
+            "Invalid instruction on line 2: \"modr 1 2 3\""
+        );
+    }
+
+    #[test]
+    fn bound_ip() {
+        let program = parse_program(PROGRAM_STRING).unwrap();
+        let mut machine = Machine::new(program);
+        machine.run();
+
+        assert_eq!(machine.registers(), &[6, 5, 6, 0, 0, 9]);
+        assert_eq!(machine.pc(), 7);
+        assert_eq!(machine.steps(), 5);
+    }
+
+    #[test]
+    fn every_opcode() {
+        // A reads 6 from a register, B reads 3 from a register, both 1 and 3
+        // as values
+        let different = [9, 9, 18, 18, 2, 2, 7, 7, 6, 1, 0, 1, 1, 0, 0, 0];
+        // Everything reads 3
+        let same = [6, 6, 9, 9, 3, 3, 3, 3, 3, 3, 0, 0, 0, 1, 1, 1];
+
+        for (id, opcode) in Opcode::ALL.into_iter().enumerate() {
+            let mut registers = [0, 6, 0, 3];
+            assert_eq!(opcode.apply(&mut registers, [1, 3, 2]), Some(()));
+            assert_eq!(registers, [0, 6, different[id], 3], "{}", opcode.name());
+
+            let mut registers = [0, 0, 0, 3];
+            assert_eq!(opcode.apply(&mut registers, [3, 3, 0]), Some(()));
+            assert_eq!(registers, [same[id], 0, 0, 3], "{}", opcode.name());
+        }
+    }
+
+    #[test]
+    fn missing_registers() {
+        for opcode in Opcode::ALL {
+            let mut registers = [1, 2, 3, 4];
+            for args in [[0, 1, 4], [0, 1, -1]] {
+                assert_eq!(opcode.apply(&mut registers, args), None);
+            }
+            assert_eq!(registers, [1, 2, 3, 4]);
+        }
+
+        // Only the arguments read as registers have to exist
+        let mut registers = [1, 2, 3, 4];
+        assert_eq!(Opcode::Seti.apply(&mut registers, [99, 99, 0]), Some(()));
+        assert_eq!(Opcode::Gtir.apply(&mut registers, [99, 1, 1]), Some(()));
+        assert_eq!(Opcode::Eqri.apply(&mut registers, [2, -99, 2]), Some(()));
+        assert_eq!(registers, [99, 1, 0, 4]);
+        assert_eq!(Opcode::Setr.apply(&mut registers, [4, 0, 0]), None);
+        assert_eq!(Opcode::Gtir.apply(&mut registers, [0, -1, 0]), None);
+        assert_eq!(Opcode::Addi.apply(&mut registers, [-1, 0, 0]), None);
+    }
+
+    #[test]
+    fn names() {
+        for opcode in Opcode::ALL {
+            assert_eq!(Opcode::from_name(opcode.name()), Some(opcode));
+        }
+        assert_eq!(Opcode::from_name("modr"), None);
+    }
+
+    #[test]
+    fn machine_edges() {
+        // Without a bound register there are no jumps at all
+        let program = parse_program("seti 5 0 0\naddi 0 1 0").unwrap();
+        let mut machine = Machine::new(program);
+        machine.run();
+        assert_eq!((machine.registers()[0], machine.pc()), (6, 2));
+
+        // Jumping before the start halts too
+        let program = parse_program("#ip 1\nseti -5 0 1\nseti 1 0 0").unwrap();
+        let mut machine = Machine::new(program);
+        assert!(machine.step());
+        assert!(machine.is_halted());
+        assert!(!machine.step());
+        assert_eq!((machine.pc(), machine.steps()), (-4, 1));
+
+        // The bound register starts from whatever it holds, the pc wins
+        let program = parse_program("#ip 2\naddr 2 2 3\nseti 0 0 4").unwrap();
+        let mut machine = Machine::new(program);
+        machine.set_register(2, 100);
+        machine.run();
+        assert_eq!(machine.registers(), &[0, 0, 1, 0, 0, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Instruction referencing a non existing register")]
+    fn missing_register_at_runtime() {
+        let program = parse_program("addi 6 1 0").unwrap();
+        Machine::new(program).run();
+    }
+
+    #[test]
+    fn source_round_trip() {
+        let mut source = vec![String::from("#ip 4")];
+        for (id, opcode) in Opcode::ALL.into_iter().enumerate() {
+            source.push(format!(
+                "{} {} {} {}",
+                opcode.name(),
+                id % 6,
+                -(id as i64),
+                5
+            ));
+        }
+        let program = parse_program(&source.join("\n")).unwrap();
+
+        let mut printed: Vec<String> = program.header().into_iter().collect();
+        printed.extend(program.instructions.iter().map(|i| i.to_string()));
+        assert_eq!(printed, source);
+        assert_eq!(parse_program(&printed.join("\n")), Ok(program.clone()));
+
+        let listed: Vec<String> = program
+            .listing()
+            .into_iter()
+            .map(|(text, _)| text)
+            .collect();
+        assert_eq!(listed, printed[1..]);
+        for instruction in program.instructions {
+            assert_eq!(
+                <Machine as Debuggable>::parse_instruction(&instruction.to_string()),
+                Some(instruction)
+            );
+        }
+    }
+
+    #[test]
+    fn listing_flow() {
+        let source = "seti 7 0 1\naddi 1 2 1\naddr 1 2 1\nseti 7 0 2\naddi 2 2 2";
+        let flows = |source: &str| -> Vec<Flow> {
+            let program = parse_program(source).unwrap();
+            program
+                .listing()
+                .into_iter()
+                .map(|(_, flow)| flow)
+                .collect()
+        };
+        let jump = |target| Flow::Jump {
+            target,
+            conditional: false,
+        };
+
+        assert_eq!(
+            flows(&format!("#ip 1\n{}", source)),
+            vec![jump(8), jump(4), Flow::Computed, Flow::Next, Flow::Next]
+        );
+        assert_eq!(flows(source), vec![Flow::Next; 5]);
+    }
+}
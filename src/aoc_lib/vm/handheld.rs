@@ -0,0 +1,326 @@
+use std::fmt;
+
+use hashbrown::HashSet;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::char;
+use nom::combinator::{map, opt};
+use nom::sequence::preceded;
+use nom::IResult;
+
+use crate::aoc_lib::vm::debugger::Debuggable;
+use crate::aoc_lib::vm::disasm::{Flow, Listing};
+use crate::aoc_lib::{jazz_parser, vm};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Instruction {
+    Acc(i64),
+    Jmp(i64),
+    Nop(i64),
+}
+
+impl Instruction {
+    // The corruption only ever swaps jumps and no-ops
+    pub fn flipped(&self) -> Option<Self> {
+        match *self {
+            Self::Acc(_) => None,
+            Self::Jmp(offset) => Some(Self::Nop(offset)),
+            Self::Nop(offset) => Some(Self::Jmp(offset)),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Instruction::Acc(value) => write!(f, "acc {:+}", value),
+            Instruction::Jmp(offset) => write!(f, "jmp {:+}", offset),
+            Instruction::Nop(value) => write!(f, "nop {:+}", value),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Termination {
+    Halted(i64),
+    Looped(i64),
+}
+
+fn signed_value(input: &str) -> IResult<&str, i64> {
+    preceded(opt(char('+')), jazz_parser::i64)(input)
+}
+
+fn instruction(input: &str) -> IResult<&str, Instruction> {
+    alt((
+        map(preceded(tag("acc "), signed_value), Instruction::Acc),
+        map(preceded(tag("jmp "), signed_value), Instruction::Jmp),
+        map(preceded(tag("nop "), signed_value), Instruction::Nop),
+    ))(input)
+}
+
+pub fn parse_program(input: &str) -> Result<Vec<Instruction>, String> {
+    vm::parse_program(input, instruction)
+}
+
+#[derive(Debug, Clone)]
+pub struct Console {
+    program: Vec<Instruction>,
+    accumulator: i64,
+    pc: i64,
+}
+
+impl Console {
+    pub fn new(program: Vec<Instruction>) -> Self {
+        Self {
+            program,
+            accumulator: 0,
+            pc: 0,
+        }
+    }
+
+    pub fn accumulator(&self) -> i64 {
+        self.accumulator
+    }
+
+    pub fn pc(&self) -> i64 {
+        self.pc
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.current_instruction().is_none()
+    }
+
+    pub fn current_instruction(&self) -> Option<Instruction> {
+        usize::try_from(self.pc)
+            .ok()
+            .and_then(|pc| self.program.get(pc))
+            .copied()
+    }
+
+    // Returns false once the program counter left the program
+    pub fn step(&mut self) -> bool {
+        let Some(current) = self.current_instruction() else {
+            return false;
+        };
+
+        match current {
+            Instruction::Acc(value) => {
+                self.accumulator += value;
+                self.pc += 1;
+            }
+            Instruction::Jmp(offset) => self.pc += offset,
+            Instruction::Nop(_) => self.pc += 1,
+        }
+
+        true
+    }
+
+    // Runs until the program either halts or is about to execute an
+    // instruction for the second time
+    pub fn run(&mut self) -> Termination {
+        let mut visited: HashSet<i64> = HashSet::new();
+
+        while visited.insert(self.pc) {
+            if !self.step() {
+                return Termination::Halted(self.accumulator);
+            }
+        }
+
+        Termination::Looped(self.accumulator)
+    }
+}
+
+impl Debuggable for Console {
+    type Instruction = Instruction;
+
+    fn pc(&self) -> i64 {
+        self.pc
+    }
+
+    fn current_instruction(&self) -> Option<Instruction> {
+        self.current_instruction()
+    }
+
+    fn register_dump(&self) -> Vec<(String, i64)> {
+        vec![(String::from("acc"), self.accumulator)]
+    }
+
+    fn step(&mut self) -> bool {
+        self.step()
+    }
+
+    fn parse_instruction(line: &str) -> Option<Instruction> {
+        vm::parse_program(line, instruction).ok()?.pop()
+    }
+}
+
+impl Listing for Vec<Instruction> {
+    fn listing(&self) -> Vec<(String, Flow)> {
+        self.iter()
+            .enumerate()
+            .map(|(pc, instr)| {
+                let flow = match instr {
+                    Instruction::Jmp(offset) => Flow::Jump {
+                        target: pc as i64 + offset,
+                        conditional: false,
+                    },
+                    _ => Flow::Next,
+                };
+                (instr.to_string(), flow)
+            })
+            .collect()
+    }
+}
+
+// Flips one jump or no-op at a time until the program terminates, returning
+// the accumulator value of the fixed program
+pub fn repair(program: &[Instruction]) -> Option<i64> {
+    vm::single_mutations(program, Instruction::flipped).find_map(|candidate| {
+        match Console::new(candidate).run() {
+            Termination::Halted(accumulator) => Some(accumulator),
+            Termination::Looped(_) => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT_STRING: &str = "nop +0
+    acc +1
+    jmp +4
+    acc +3
+    jmp -3
+    acc -99
+    acc +1
+    jmp -4
+    acc +6";
+
+    #[test]
+    fn parse() {
+        let program = parse_program(INPUT_STRING).unwrap();
+
+        assert_eq!(program.len(), 9);
+        assert_eq!(program[0], Instruction::Nop(0));
+        assert_eq!(program[4], Instruction::Jmp(-3));
+        assert_eq!(program[5], Instruction::Acc(-99));
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse_program("nop +0\nadd +1").unwrap_err();
+
+        assert_eq!(error, "Invalid instruction on line 2: \"add +1\"");
+
+        // Blank lines still count
+        let error = parse_program("nop +0\n\nadd +1").unwrap_err();
+        assert_eq!(error, "Invalid instruction on line 3: \"add +1\"");
+    }
+
+    #[test]
+    fn simple_loop() {
+        let program = parse_program(INPUT_STRING).unwrap();
+        let mut console = Console::new(program);
+
+        assert_eq!(console.run(), Termination::Looped(5));
+        assert_eq!(console.pc(), 1);
+    }
+
+    #[test]
+    fn simple_repair() {
+        let program = parse_program(INPUT_STRING).unwrap();
+
+        assert_eq!(repair(&program), Some(8));
+    }
+
+    #[test]
+    fn unrepairable() {
+        let program = parse_program("jmp +0\njmp +0").unwrap();
+
+        assert_eq!(repair(&program), None);
+    }
+
+    #[test]
+    fn mutations() {
+        let program = parse_program(INPUT_STRING).unwrap();
+        let mutated = vm::single_mutations(&program, Instruction::flipped).count();
+
+        assert_eq!(mutated, 4);
+    }
+
+    #[test]
+    fn every_instruction() {
+        let program = parse_program("acc +7\nacc -2\nnop -100\njmp +2\nacc +1000\njmp -6").unwrap();
+        let mut console = Console::new(program);
+
+        let mut trace = vec![(console.pc(), console.accumulator())];
+        while console.step() {
+            trace.push((console.pc(), console.accumulator()));
+        }
+        assert_eq!(trace, vec![(0, 0), (1, 7), (2, 5), (3, 5), (5, 5), (-1, 5)]);
+    }
+
+    #[test]
+    fn termination_edges() {
+        // Halting is leaving the program on either side, or never entering it
+        let halts = |source: &str| Console::new(parse_program(source).unwrap()).run();
+        assert_eq!(halts("acc +3\njmp +1"), Termination::Halted(3));
+        assert_eq!(halts("acc +3\njmp +10"), Termination::Halted(3));
+        assert_eq!(halts("acc +3\njmp -1\nacc +5"), Termination::Looped(3));
+        assert_eq!(halts("acc +3\njmp -5"), Termination::Halted(3));
+        assert_eq!(halts("jmp +0"), Termination::Looped(0));
+        assert_eq!(Console::new(Vec::new()).run(), Termination::Halted(0));
+
+        // The loop is caught before the repeated instruction runs
+        let mut console = Console::new(parse_program("acc +1\nacc +1\njmp -1").unwrap());
+        assert_eq!(console.run(), Termination::Looped(2));
+        assert_eq!(console.pc(), 1);
+        assert!(!console.is_halted());
+
+        let mut console = Console::new(parse_program("jmp -1").unwrap());
+        assert!(console.step());
+        assert!(console.is_halted());
+        assert!(!console.step());
+        assert_eq!(console.pc(), -1);
+    }
+
+    #[test]
+    fn flipping() {
+        assert_eq!(Instruction::Acc(4).flipped(), None);
+        assert_eq!(Instruction::Jmp(-4).flipped(), Some(Instruction::Nop(-4)));
+        assert_eq!(Instruction::Nop(0).flipped(), Some(Instruction::Jmp(0)));
+    }
+
+    #[test]
+    fn source_round_trip() {
+        let program = parse_program("acc +0\nacc -0\njmp 12\nnop -3\nacc +9999").unwrap();
+        let printed: Vec<String> = program.iter().map(|i| i.to_string()).collect();
+
+        // Signs are always printed, zero is positive
+        assert_eq!(
+            printed,
+            ["acc +0", "acc +0", "jmp +12", "nop -3", "acc +9999"]
+        );
+        assert_eq!(parse_program(&printed.join("\n")), Ok(program.clone()));
+
+        let listing = program.listing();
+        let listed: Vec<&String> = listing.iter().map(|(text, _)| text).collect();
+        assert_eq!(listed, printed.iter().collect::<Vec<_>>());
+        assert_eq!(
+            listing[2].1,
+            Flow::Jump {
+                target: 14,
+                conditional: false
+            }
+        );
+        assert_eq!(listing[3].1, Flow::Next);
+        for instruction in program {
+            assert_eq!(
+                <Console as Debuggable>::parse_instruction(&instruction.to_string()),
+                Some(instruction)
+            );
+        }
+        assert_eq!(<Console as Debuggable>::parse_instruction("acc"), None);
+    }
+}
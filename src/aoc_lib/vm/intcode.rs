@@ -0,0 +1,720 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, space1};
+use nom::combinator::{all_consuming, map, opt};
+use nom::multi::separated_list0;
+use nom::sequence::{delimited, preceded, tuple};
+use nom::IResult;
+
+use crate::aoc_lib::vm::debugger::Debuggable;
+use crate::aoc_lib::{jazz_parser, vm};
+
+// An argument and how to read it, the mode comes from the digits of the opcode
+// above the hundreds
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Param {
+    Position(i64),
+    Immediate(i64),
+    // Relative to the base moved around by `arb`
+    Relative(i64),
+}
+
+// The last parameter of the arithmetic, comparison and input instructions is
+// where the result goes
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Instruction {
+    Add(Param, Param, Param),
+    Mul(Param, Param, Param),
+    Input(Param),
+    Output(Param),
+    JumpIfTrue(Param, Param),
+    JumpIfFalse(Param, Param),
+    LessThan(Param, Param, Param),
+    Equals(Param, Param, Param),
+    AdjustBase(Param),
+    Halt,
+}
+
+impl Instruction {
+    // Memory cells taken by the instruction, opcode included
+    pub fn length(&self) -> usize {
+        match self {
+            Instruction::Add(..)
+            | Instruction::Mul(..)
+            | Instruction::LessThan(..)
+            | Instruction::Equals(..) => 4,
+            Instruction::JumpIfTrue(..) | Instruction::JumpIfFalse(..) => 3,
+            Instruction::Input(_) | Instruction::Output(_) | Instruction::AdjustBase(_) => 2,
+            Instruction::Halt => 1,
+        }
+    }
+
+    // The memory cells `decode` turns back into this instruction
+    pub fn encode(&self) -> Vec<i64> {
+        let (opcode, params) = match *self {
+            Instruction::Add(a, b, to) => (1, vec![a, b, to]),
+            Instruction::Mul(a, b, to) => (2, vec![a, b, to]),
+            Instruction::Input(to) => (3, vec![to]),
+            Instruction::Output(value) => (4, vec![value]),
+            Instruction::JumpIfTrue(value, target) => (5, vec![value, target]),
+            Instruction::JumpIfFalse(value, target) => (6, vec![value, target]),
+            Instruction::LessThan(a, b, to) => (7, vec![a, b, to]),
+            Instruction::Equals(a, b, to) => (8, vec![a, b, to]),
+            Instruction::AdjustBase(offset) => (9, vec![offset]),
+            Instruction::Halt => (99, vec![]),
+        };
+
+        let mut modes = 100;
+        let mut encoded = vec![opcode];
+        for param in params {
+            let (mode, raw) = match param {
+                Param::Position(address) => (0, address),
+                Param::Immediate(value) => (1, value),
+                Param::Relative(offset) => (2, offset),
+            };
+            encoded[0] += mode * modes;
+            modes *= 10;
+            encoded.push(raw);
+        }
+
+        encoded
+    }
+}
+
+impl fmt::Display for Param {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Param::Position(address) => write!(f, "[{}]", address),
+            Param::Immediate(value) => write!(f, "{}", value),
+            Param::Relative(offset) => write!(f, "[rb{:+}]", offset),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Instruction::Add(a, b, to) => write!(f, "add {} {} {}", a, b, to),
+            Instruction::Mul(a, b, to) => write!(f, "mul {} {} {}", a, b, to),
+            Instruction::Input(to) => write!(f, "in {}", to),
+            Instruction::Output(value) => write!(f, "out {}", value),
+            Instruction::JumpIfTrue(value, target) => write!(f, "jnz {} {}", value, target),
+            Instruction::JumpIfFalse(value, target) => write!(f, "jz {} {}", value, target),
+            Instruction::LessThan(a, b, to) => write!(f, "lt {} {} {}", a, b, to),
+            Instruction::Equals(a, b, to) => write!(f, "eq {} {} {}", a, b, to),
+            Instruction::AdjustBase(offset) => write!(f, "arb {}", offset),
+            Instruction::Halt => write!(f, "hlt"),
+        }
+    }
+}
+
+// Parses the listing syntax back, for the debugger breakpoints and `assemble`
+fn param(input: &str) -> IResult<&str, Param> {
+    alt((
+        map(
+            delimited(
+                tag("[rb"),
+                preceded(opt(char('+')), jazz_parser::i64),
+                char(']'),
+            ),
+            Param::Relative,
+        ),
+        map(
+            delimited(char('['), jazz_parser::i64, char(']')),
+            Param::Position,
+        ),
+        map(jazz_parser::i64, Param::Immediate),
+    ))(input)
+}
+
+fn instruction(input: &str) -> IResult<&str, Instruction> {
+    let (rest, (mnemonic, params)) = tuple((
+        alt((
+            tag("add"),
+            tag("mul"),
+            tag("in"),
+            tag("out"),
+            tag("jnz"),
+            tag("jz"),
+            tag("lt"),
+            tag("eq"),
+            tag("arb"),
+            tag("hlt"),
+        )),
+        opt(preceded(space1, separated_list0(space1, param))),
+    ))(input)?;
+
+    let params = params.unwrap_or_default();
+    let decoded = match (mnemonic, &params[..]) {
+        ("add", &[a, b, to]) => Instruction::Add(a, b, to),
+        ("mul", &[a, b, to]) => Instruction::Mul(a, b, to),
+        ("in", &[to]) => Instruction::Input(to),
+        ("out", &[value]) => Instruction::Output(value),
+        ("jnz", &[value, target]) => Instruction::JumpIfTrue(value, target),
+        ("jz", &[value, target]) => Instruction::JumpIfFalse(value, target),
+        ("lt", &[a, b, to]) => Instruction::LessThan(a, b, to),
+        ("eq", &[a, b, to]) => Instruction::Equals(a, b, to),
+        ("arb", &[offset]) => Instruction::AdjustBase(offset),
+        ("hlt", &[]) => Instruction::Halt,
+        _ => {
+            return Err(nom::Err::Error(nom::error::Error::new(
+                input,
+                nom::error::ErrorKind::Verify,
+            )))
+        }
+    };
+
+    Ok((rest, decoded))
+}
+
+// Turns a listing, one instruction per line, back into a program
+pub fn assemble(source: &str) -> Result<Vec<i64>, String> {
+    let instructions = vm::parse_program(source, instruction)?;
+
+    Ok(instructions.iter().flat_map(Instruction::encode).collect())
+}
+
+// Programs are a single line of comma separated integers
+pub fn parse_program(input: &str) -> Result<Vec<i64>, String> {
+    input
+        .trim()
+        .split(',')
+        .map(|cell| {
+            cell.trim()
+                .parse()
+                .map_err(|_| format!("\"{}\" is not an Intcode value", cell.trim()))
+        })
+        .collect()
+}
+
+// Why the machine gave control back
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Stop {
+    Halted,
+    // Nothing left to read, push more input and run again
+    NeedsInput,
+}
+
+#[derive(Debug, Clone)]
+pub struct Machine {
+    memory: Vec<i64>,
+    pc: usize,
+    relative_base: i64,
+    input: VecDeque<i64>,
+    output: VecDeque<i64>,
+    halted: bool,
+}
+
+impl Machine {
+    pub fn new(program: &[i64]) -> Self {
+        Self {
+            memory: program.to_vec(),
+            pc: 0,
+            relative_base: 0,
+            input: VecDeque::new(),
+            output: VecDeque::new(),
+            halted: false,
+        }
+    }
+
+    pub fn with_input(program: &[i64], input: &[i64]) -> Self {
+        let mut machine = Self::new(program);
+        machine.input.extend(input);
+        machine
+    }
+
+    pub fn push_input(&mut self, value: i64) {
+        self.input.push_back(value);
+    }
+
+    // Everything written since the last time the output was taken
+    pub fn take_output(&mut self) -> Vec<i64> {
+        self.output.drain(..).collect()
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    // Memory is unbounded, everything past the program reads as 0
+    pub fn read(&self, address: usize) -> i64 {
+        self.memory.get(address).copied().unwrap_or(0)
+    }
+
+    pub fn write(&mut self, address: usize, value: i64) {
+        if address >= self.memory.len() {
+            self.memory.resize(address + 1, 0);
+        }
+        self.memory[address] = value;
+    }
+
+    pub fn decode(&self, pc: usize) -> Result<Instruction, String> {
+        let opcode = self.read(pc);
+        let param = |id: usize| -> Result<Param, String> {
+            let raw = self.read(pc + id);
+            match opcode / 10i64.pow(id as u32 + 1) % 10 {
+                0 => Ok(Param::Position(raw)),
+                1 => Ok(Param::Immediate(raw)),
+                2 => Ok(Param::Relative(raw)),
+                mode => Err(format!(
+                    "Unknown mode {} in opcode {} at {}",
+                    mode, opcode, pc
+                )),
+            }
+        };
+
+        Ok(match opcode % 100 {
+            1 => Instruction::Add(param(1)?, param(2)?, param(3)?),
+            2 => Instruction::Mul(param(1)?, param(2)?, param(3)?),
+            3 => Instruction::Input(param(1)?),
+            4 => Instruction::Output(param(1)?),
+            5 => Instruction::JumpIfTrue(param(1)?, param(2)?),
+            6 => Instruction::JumpIfFalse(param(1)?, param(2)?),
+            7 => Instruction::LessThan(param(1)?, param(2)?, param(3)?),
+            8 => Instruction::Equals(param(1)?, param(2)?, param(3)?),
+            9 => Instruction::AdjustBase(param(1)?),
+            99 => Instruction::Halt,
+            _ => return Err(format!("Unknown opcode {} at {}", opcode, pc)),
+        })
+    }
+
+    fn address(&self, param: Param) -> Result<usize, String> {
+        let address = match param {
+            Param::Position(address) => address,
+            Param::Relative(offset) => self.relative_base + offset,
+            Param::Immediate(_) => {
+                return Err(format!("Writing to an immediate at {}", self.pc));
+            }
+        };
+
+        usize::try_from(address).map_err(|_| format!("Negative address {} at {}", address, self.pc))
+    }
+
+    fn value(&self, param: Param) -> Result<i64, String> {
+        match param {
+            Param::Immediate(value) => Ok(value),
+            _ => Ok(self.read(self.address(param)?)),
+        }
+    }
+
+    fn jump(&mut self, target: Param) -> Result<(), String> {
+        let target = self.value(target)?;
+        self.pc =
+            usize::try_from(target).map_err(|_| format!("Jump to {} at {}", target, self.pc))?;
+        Ok(())
+    }
+
+    // Runs a single instruction, None means it can carry on
+    pub fn step(&mut self) -> Result<Option<Stop>, String> {
+        if self.halted {
+            return Ok(Some(Stop::Halted));
+        }
+
+        let current = self.decode(self.pc)?;
+        let next_pc = self.pc + current.length();
+        match current {
+            Instruction::Add(a, b, to) => {
+                let sum = self.value(a)? + self.value(b)?;
+                self.write(self.address(to)?, sum);
+            }
+            Instruction::Mul(a, b, to) => {
+                let product = self.value(a)? * self.value(b)?;
+                self.write(self.address(to)?, product);
+            }
+            Instruction::Input(to) => {
+                let Some(value) = self.input.pop_front() else {
+                    return Ok(Some(Stop::NeedsInput));
+                };
+                self.write(self.address(to)?, value);
+            }
+            Instruction::Output(value) => {
+                let value = self.value(value)?;
+                self.output.push_back(value);
+            }
+            Instruction::JumpIfTrue(value, target) => {
+                if self.value(value)? != 0 {
+                    return self.jump(target).map(|_| None);
+                }
+            }
+            Instruction::JumpIfFalse(value, target) => {
+                if self.value(value)? == 0 {
+                    return self.jump(target).map(|_| None);
+                }
+            }
+            Instruction::LessThan(a, b, to) => {
+                let less = self.value(a)? < self.value(b)?;
+                self.write(self.address(to)?, i64::from(less));
+            }
+            Instruction::Equals(a, b, to) => {
+                let equal = self.value(a)? == self.value(b)?;
+                self.write(self.address(to)?, i64::from(equal));
+            }
+            Instruction::AdjustBase(offset) => self.relative_base += self.value(offset)?,
+            Instruction::Halt => {
+                self.halted = true;
+                return Ok(Some(Stop::Halted));
+            }
+        }
+        self.pc = next_pc;
+
+        Ok(None)
+    }
+
+    // Runs until the program halts or waits for input that isn't there
+    pub fn run(&mut self) -> Result<Stop, String> {
+        loop {
+            if let Some(stop) = self.step()? {
+                return Ok(stop);
+            }
+        }
+    }
+
+    // Runs just until the next value comes out, for machines wired together
+    pub fn next_output(&mut self) -> Result<Option<i64>, String> {
+        while self.output.is_empty() {
+            if self.step()?.is_some() {
+                return Ok(None);
+            }
+        }
+
+        Ok(self.output.pop_front())
+    }
+}
+
+impl Debuggable for Machine {
+    type Instruction = Instruction;
+
+    fn pc(&self) -> i64 {
+        self.pc as i64
+    }
+
+    fn current_instruction(&self) -> Option<Instruction> {
+        self.decode(self.pc).ok()
+    }
+
+    fn register_dump(&self) -> Vec<(String, i64)> {
+        vec![(String::from("rb"), self.relative_base)]
+    }
+
+    fn step(&mut self) -> bool {
+        matches!(self.step(), Ok(None))
+    }
+
+    fn parse_instruction(line: &str) -> Option<Instruction> {
+        all_consuming(instruction)(line.trim()).ok().map(|(_, i)| i)
+    }
+}
+
+// Feeds all of `input` at once and collects everything written until it halts
+pub fn execute(program: &[i64], input: &[i64]) -> Result<Vec<i64>, String> {
+    let mut machine = Machine::with_input(program, input);
+    match machine.run()? {
+        Stop::Halted => Ok(machine.take_output()),
+        Stop::NeedsInput => Err(String::from("The program wants more input")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn position_mode() {
+        let mut machine = Machine::new(&parse_program("1,9,10,3,2,3,11,0,99,30,40,50").unwrap());
+
+        assert_eq!(machine.run(), Ok(Stop::Halted));
+        assert_eq!(machine.read(0), 3500);
+        assert_eq!(machine.read(3), 70);
+        assert_eq!(machine.read(1000), 0);
+    }
+
+    #[test]
+    fn immediate_mode_and_io() {
+        // Outputs whatever it reads
+        let echo = parse_program("3,0,4,0,99").unwrap();
+        assert_eq!(execute(&echo, &[42]), Ok(vec![42]));
+        assert!(execute(&echo, &[]).is_err());
+
+        // Is the input equal to 8? Position and immediate mode
+        let equal_position = parse_program("3,9,8,9,10,9,4,9,99,-1,8").unwrap();
+        let equal_immediate = parse_program("3,3,1108,-1,8,3,4,3,99").unwrap();
+        for program in [&equal_position, &equal_immediate] {
+            assert_eq!(execute(program, &[8]), Ok(vec![1]));
+            assert_eq!(execute(program, &[7]), Ok(vec![0]));
+        }
+
+        // Jumps: 0 if the input was 0, 1 otherwise
+        let jumps = parse_program("3,3,1105,-1,9,1101,0,0,12,4,12,99,1").unwrap();
+        assert_eq!(execute(&jumps, &[0]), Ok(vec![0]));
+        assert_eq!(execute(&jumps, &[5]), Ok(vec![1]));
+    }
+
+    #[test]
+    fn relative_mode() {
+        let quine =
+            parse_program("109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99").unwrap();
+        assert_eq!(execute(&quine, &[]), Ok(quine.clone()));
+
+        let big = parse_program("104,1125899906842624,99").unwrap();
+        assert_eq!(execute(&big, &[]), Ok(vec![1125899906842624]));
+        let sixteen_digits = parse_program("1102,34915192,34915192,7,4,7,99,0").unwrap();
+        assert_eq!(execute(&sixteen_digits, &[]), Ok(vec![1219070632396864]));
+    }
+
+    #[test]
+    fn paused_machine() {
+        // Doubles every input until it reads a 0
+        let program = parse_program("3,20,1006,20,14,102,2,20,21,4,21,1105,1,0,99").unwrap();
+        let mut machine = Machine::new(&program);
+
+        assert_eq!(machine.run(), Ok(Stop::NeedsInput));
+        machine.push_input(21);
+        assert_eq!(machine.next_output(), Ok(Some(42)));
+        assert_eq!(machine.next_output(), Ok(None));
+        assert!(!machine.is_halted());
+        machine.push_input(0);
+        assert_eq!(machine.run(), Ok(Stop::Halted));
+        assert!(machine.is_halted());
+    }
+
+    #[test]
+    fn faults_and_listing() {
+        assert!(execute(&[1101, 1, 1, 5, 42], &[]).is_err());
+        assert!(execute(&[11101, 1, 1, 5, 99], &[]).is_err());
+        assert!(execute(&[1, -1, 0, 0, 99], &[]).is_err());
+        assert!(parse_program("1,2,x").is_err());
+
+        let machine = Machine::new(&[21101, 5, -3, 7, 204, -2, 99]);
+        let first = machine.decode(0).unwrap();
+        let second = machine.decode(4).unwrap();
+        assert_eq!(first.to_string(), "add 5 -3 [rb+7]");
+        assert_eq!(second.to_string(), "out [rb-2]");
+        for decoded in [first, second, Instruction::Halt] {
+            assert_eq!(
+                <Machine as Debuggable>::parse_instruction(&decoded.to_string()),
+                Some(decoded)
+            );
+        }
+        assert_eq!(<Machine as Debuggable>::parse_instruction("add 1"), None);
+    }
+
+    // Every way to write each instruction: any mode for what is read, position
+    // or relative for what is written. With the relative base at 50, the first
+    // parameter read is 3 in every mode and the second one is 7.
+    fn every_encoding() -> Vec<Instruction> {
+        let first = [Param::Position(20), Param::Immediate(3), Param::Relative(1)];
+        let second = [Param::Position(21), Param::Immediate(7), Param::Relative(2)];
+        let written = [Param::Position(30), Param::Relative(-10)];
+
+        let mut all = vec![Instruction::Halt];
+        for a in first {
+            all.extend([Instruction::Output(a), Instruction::AdjustBase(a)]);
+            for b in second {
+                all.extend([
+                    Instruction::JumpIfTrue(a, b),
+                    Instruction::JumpIfFalse(a, b),
+                ]);
+                for to in written {
+                    all.extend([
+                        Instruction::Add(a, b, to),
+                        Instruction::Mul(a, b, to),
+                        Instruction::LessThan(a, b, to),
+                        Instruction::Equals(a, b, to),
+                    ]);
+                }
+            }
+        }
+        all.extend(written.map(Instruction::Input));
+
+        all
+    }
+
+    // Moves the relative base to 50 and runs `instruction` once
+    fn run_once(instruction: Instruction) -> (Machine, Option<Stop>) {
+        let mut program = vec![0; 60];
+        program[..2].copy_from_slice(&[109, 50]);
+        let encoded = instruction.encode();
+        program[2..2 + encoded.len()].copy_from_slice(&encoded);
+        program[20] = 3;
+        program[21] = 7;
+        program[51] = 3;
+        program[52] = 7;
+
+        let mut machine = Machine::with_input(&program, &[42]);
+        assert_eq!(machine.step(), Ok(None));
+        let stop = machine.step().unwrap();
+        (machine, stop)
+    }
+
+    #[test]
+    fn every_mode() {
+        for instruction in every_encoding() {
+            let (written, expected) = match instruction {
+                Instruction::Add(.., to) => (Some(to), 10),
+                Instruction::Mul(.., to) => (Some(to), 21),
+                Instruction::LessThan(.., to) => (Some(to), 1),
+                Instruction::Equals(.., to) => (Some(to), 0),
+                Instruction::Input(to) => (Some(to), 42),
+                _ => (None, 0),
+            };
+            let (mut machine, stop) = run_once(instruction);
+
+            assert_eq!(stop.is_some(), instruction == Instruction::Halt);
+            match instruction {
+                Instruction::Output(_) => assert_eq!(machine.take_output(), vec![3]),
+                Instruction::AdjustBase(_) => assert_eq!(machine.relative_base, 53),
+                Instruction::JumpIfTrue(..) => assert_eq!(machine.pc, 7),
+                Instruction::JumpIfFalse(..) => assert_eq!(machine.pc, 2 + 3),
+                Instruction::Halt => assert!(machine.is_halted()),
+                _ => {
+                    let address = match written {
+                        Some(Param::Relative(offset)) => (50 + offset) as usize,
+                        _ => 30,
+                    };
+                    assert_eq!(machine.read(address), expected, "{}", instruction);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn encode_decode_round_trip() {
+        for instruction in every_encoding() {
+            let encoded = instruction.encode();
+            assert_eq!(encoded.len(), instruction.length());
+            assert_eq!(Machine::new(&encoded).decode(0), Ok(instruction));
+            assert_eq!(
+                <Machine as Debuggable>::parse_instruction(&instruction.to_string()),
+                Some(instruction)
+            );
+        }
+    }
+
+    #[test]
+    fn assemble_disassemble_round_trip() {
+        let program = parse_program("3,20,1006,20,14,102,2,20,21,4,21,1105,1,0,99").unwrap();
+        let machine = Machine::new(&program);
+
+        let mut listing = Vec::new();
+        let mut pc = 0;
+        while pc < program.len() {
+            let instruction = machine.decode(pc).unwrap();
+            listing.push(instruction.to_string());
+            pc += instruction.length();
+        }
+
+        assert_eq!(listing[2], "mul 2 [20] [21]");
+        assert_eq!(assemble(&listing.join("\n")), Ok(program));
+        assert_eq!(
+            assemble("add 1 2 [3]\nmul 1").unwrap_err(),
+            "Invalid instruction on line 2: \"mul 1\""
+        );
+    }
+
+    #[test]
+    fn relative_base_edges() {
+        // The base can go below zero as long as the addresses don't
+        let program = [109, -5, 204, 5, 99];
+        assert_eq!(execute(&program, &[]), Ok(vec![109]));
+        assert_eq!(
+            execute(&[109, -5, 204, 0, 99], &[]),
+            Err(String::from("Negative address -5 at 2"))
+        );
+        assert_eq!(
+            execute(&[109, -1, 203, 0, 99], &[7]),
+            Err(String::from("Negative address -1 at 2"))
+        );
+        assert_eq!(
+            execute(&[109, 2, 22101, 1, 0, -3, 99], &[]),
+            Err(String::from("Negative address -1 at 2"))
+        );
+
+        // Relative and position reads past the end of the program are 0
+        assert_eq!(
+            execute(&[109, 1000, 204, 0, 4, 500, 99], &[]),
+            Ok(vec![0, 0])
+        );
+    }
+
+    #[test]
+    fn fault_messages() {
+        assert_eq!(
+            execute(&[11101, 1, 1, 5, 99], &[]),
+            Err(String::from("Writing to an immediate at 0"))
+        );
+        assert_eq!(
+            execute(&[103, 0, 99], &[1]),
+            Err(String::from("Writing to an immediate at 0"))
+        );
+        assert_eq!(
+            execute(&[301, 0, 0, 0, 99], &[]),
+            Err(String::from("Unknown mode 3 in opcode 301 at 0"))
+        );
+        assert_eq!(
+            execute(&[30001, 0, 0, 0, 99], &[]),
+            Err(String::from("Unknown mode 3 in opcode 30001 at 0"))
+        );
+        assert_eq!(
+            execute(&[42], &[]),
+            Err(String::from("Unknown opcode 42 at 0"))
+        );
+        assert_eq!(
+            execute(&[-1], &[]),
+            Err(String::from("Unknown opcode -1 at 0"))
+        );
+        assert_eq!(
+            execute(&[1105, 1, -1], &[]),
+            Err(String::from("Jump to -1 at 0"))
+        );
+        assert_eq!(
+            execute(&[1, -1, 0, 0, 99], &[]),
+            Err(String::from("Negative address -1 at 0"))
+        );
+
+        // Running off the end of the program reads the zeroes after it
+        assert_eq!(
+            execute(&[1101, 1, 1, 9], &[]),
+            Err(String::from("Unknown opcode 0 at 4"))
+        );
+    }
+
+    #[test]
+    fn machine_state_edges() {
+        // A fault leaves the machine where it was, so it shows up again
+        let mut machine = Machine::new(&[4, 2, 42]);
+        assert_eq!(machine.step(), Ok(None));
+        assert!(machine.step().is_err());
+        assert_eq!(machine.run(), Err(String::from("Unknown opcode 42 at 2")));
+        assert_eq!(machine.take_output(), vec![42]);
+
+        // Waiting for input doesn't move past the `in`
+        let mut machine = Machine::new(&[3, 5, 4, 5, 99, 0]);
+        assert_eq!(machine.step(), Ok(Some(Stop::NeedsInput)));
+        assert_eq!(machine.step(), Ok(Some(Stop::NeedsInput)));
+        machine.push_input(-8);
+        assert_eq!(machine.run(), Ok(Stop::Halted));
+        assert_eq!(machine.take_output(), vec![-8]);
+        assert_eq!(machine.step(), Ok(Some(Stop::Halted)));
+        assert_eq!(machine.next_output(), Ok(None));
+
+        // Writes far past the end grow the memory
+        let mut machine = Machine::new(&[1101, 2, 3, 10_000, 99]);
+        assert_eq!(machine.run(), Ok(Stop::Halted));
+        assert_eq!(machine.read(10_000), 5);
+
+        // Self modifying code: the add turns the `hlt` right after it into an
+        // `out` of the value that follows
+        let program = [1101, 100, 4, 4, 99, 55, 99];
+        assert_eq!(execute(&program, &[]), Ok(vec![55]));
+
+        // A jump to itself is fine, it just never ends
+        let mut machine = Machine::new(&[1105, 1, 0]);
+        for _ in 0..100 {
+            assert_eq!(machine.step(), Ok(None));
+        }
+        assert_eq!(machine.pc, 0);
+    }
+}
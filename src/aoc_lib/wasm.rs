@@ -0,0 +1,110 @@
+use std::cell::RefCell;
+use std::{mem, slice, str};
+
+use crate::aoc_lib::config::InputKind;
+use crate::aoc_lib::reply::{solve, Response};
+
+// A web page can only hand numbers to these, so the text goes through the
+// module's memory: the page asks for room with `wasm_alloc`, writes the input
+// there and calls `wasm_solve`, then reads as many bytes as that returned from
+// `wasm_reply`. web/aoc.js wraps all of it in `solve(year, day, part, input)`.
+// wasm-bindgen would write that glue, but it isn't among the dependencies the
+// crate builds offline with, and this needs nothing but the standard library.
+
+thread_local! {
+    // The JSON of the last solve, kept until the next one so the page can read it
+    static REPLY: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+// Room for `length` bytes of input, given back with `wasm_free`
+#[no_mangle]
+pub extern "C" fn wasm_alloc(length: usize) -> *mut u8 {
+    let mut room = Vec::<u8>::with_capacity(length);
+    let pointer = room.as_mut_ptr();
+    mem::forget(room);
+    pointer
+}
+
+/// # Safety
+///
+/// `pointer` and `length` have to be the ones of a `wasm_alloc` not freed yet
+#[no_mangle]
+pub unsafe extern "C" fn wasm_free(pointer: *mut u8, length: usize) {
+    drop(Vec::from_raw_parts(pointer, 0, length));
+}
+
+/// Solves a part of a real input and keeps the reply, the same JSON `serve`
+/// answers with. Returns how many bytes long the reply is.
+///
+/// # Safety
+///
+/// `input` has to point at `length` bytes, like the room `wasm_alloc` gave
+#[no_mangle]
+pub unsafe extern "C" fn wasm_solve(
+    year: u32,
+    day: u32,
+    part: u32,
+    input: *const u8,
+    length: usize,
+) -> usize {
+    let bytes = slice::from_raw_parts(input, length);
+    let response = match (
+        u16::try_from(year),
+        u8::try_from(day),
+        u8::try_from(part),
+        str::from_utf8(bytes),
+    ) {
+        (_, _, _, Err(_)) => Response::error(400, "The input is not UTF-8"),
+        (Ok(year), Ok(day), Ok(part), Ok(input)) => solve(year, day, part, input, InputKind::Real),
+        _ => Response::error(404, &format!("Unknown day {} of {}", day, year)),
+    };
+
+    REPLY.with(|reply| {
+        *reply.borrow_mut() = response.body;
+        reply.borrow().len()
+    })
+}
+
+// Where the reply of the last `wasm_solve` starts
+#[no_mangle]
+pub extern "C" fn wasm_reply() -> *const u8 {
+    REPLY.with(|reply| reply.borrow().as_ptr())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // What the page does, with the memory of the test instead of the module's
+    fn call(year: u32, day: u32, part: u32, input: &[u8]) -> String {
+        unsafe {
+            let room = wasm_alloc(input.len());
+            room.copy_from_nonoverlapping(input.as_ptr(), input.len());
+            let length = wasm_solve(year, day, part, room, input.len());
+            wasm_free(room, input.len());
+
+            let reply = slice::from_raw_parts(wasm_reply(), length);
+            String::from_utf8(reply.to_vec()).unwrap()
+        }
+    }
+
+    #[test]
+    fn through_memory() {
+        assert_eq!(
+            call(2022, 300, 1, b""),
+            r#"{"error":"Unknown day 300 of 2022"}"#
+        );
+        assert_eq!(
+            call(2022, 1, 1, b"\xff"),
+            r#"{"error":"The input is not UTF-8"}"#
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "year2022")]
+    fn solves() {
+        let input = include_bytes!("../../inputs/2022/day01.example.txt");
+        assert!(call(2022, 1, 1, input).contains(r#""answer":24000"#));
+        assert!(call(2022, 1, 2, input).contains(r#""answer":45000"#));
+    }
+}
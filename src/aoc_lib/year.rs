@@ -0,0 +1,437 @@
+use crate::aoc_lib::{
+    AocError, DaySpec, ExplainFn, ExportFn, GraphFn, PlaySpec, RenderFn, TraceFn, VisualizeFn,
+};
+
+// Another way of solving a day, picked by name with --algo
+#[derive(Debug, Clone, Copy)]
+pub struct Variant {
+    pub name: &'static str,
+    pub parts: Parts,
+}
+
+// Interactive solvers wait for someone at the keyboard, the runner hands them
+// the terminal and leaves them out of --algo all
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parts {
+    Solve(DaySpec),
+    Play(PlaySpec),
+}
+
+// What a day can do besides solving, for the runner flags of the same name
+#[derive(Debug, Clone, Copy)]
+pub struct Hooks {
+    // --render
+    pub render: Option<RenderFn>,
+    // --visualize, and --visualize --compare to watch two solvers race
+    pub visualize: Option<VisualizeFn>,
+    pub compare: Option<VisualizeFn>,
+    // --images
+    pub export: Option<ExportFn>,
+    // --trace
+    pub trace: Option<TraceFn>,
+    // --export-graph
+    pub graph: Option<GraphFn>,
+    // --explain
+    pub explain: Option<ExplainFn>,
+}
+
+impl Hooks {
+    pub const NONE: Hooks = Hooks {
+        render: None,
+        visualize: None,
+        compare: None,
+        export: None,
+        trace: None,
+        graph: None,
+        explain: None,
+    };
+}
+
+// A solved day, declared next to its solution with `aoc_day!`
+#[derive(Debug, Clone, Copy)]
+pub struct Day {
+    pub year: u16,
+    pub day: u8,
+    pub parts: DaySpec,
+    pub variants: &'static [Variant],
+    pub hooks: Hooks,
+}
+
+// Registers the day of the module it's called in, either from two part
+// functions, from a single one or from a `Solution` or `SolutionBorrowed`. A
+// part returns its `Answer`, or a `Solved` when it has notes to go with it, or
+// an `AocError`; an empty input is an `AocError::Parse` before it gets to them.
+// Parts that ask something else of the example take the `InputKind` they are
+// solving after the input, listed as `input_kind`. Alternative solvers follow a
+// semicolon, with one or two parts each, marked `interactive` when they talk to someone at the terminal, through
+// the reader and the writer the runner passes after the input. The hooks of
+// `Hooks` go in the same list, each with its function:
+//
+//     aoc_day!(2022, 18, part1, part2);
+//     aoc_day!(2022, 25, only_part);
+//     aoc_day!(2022, 1, solution = CalorieCounting);
+//     aoc_day!(2022, 7, solution_borrowed = NoSpaceLeft);
+//     aoc_day!(2022, 15, input_kind = (part1, part2));
+//     aoc_day!(2021, 23, part1, part2; replay = (replay_part1, replay_part2));
+//     aoc_day!(2021, 23, part1, part2; play = interactive(play_part1, play_part2));
+//     aoc_day!(2022, 15, part1, part2; render = render);
+//     aoc_day!(2022, 14, part1, part2; #[cfg(feature = "images")] export = export);
+#[macro_export]
+macro_rules! aoc_day {
+    (@adapt $part:expr) => {
+        |input: &str, _: $crate::aoc_lib::config::InputKind| {
+            $crate::aoc_lib::error::expect_input(input)?;
+            ($part)(input).map($crate::aoc_lib::Solved::from)
+        }
+    };
+    (@adapt_kind $part:expr) => {
+        |input: &str, kind: $crate::aoc_lib::config::InputKind| {
+            $crate::aoc_lib::error::expect_input(input)?;
+            ($part)(input, kind).map($crate::aoc_lib::Solved::from)
+        }
+    };
+    (@parts $part:expr) => {
+        (Some($crate::aoc_lib::year::aoc_day!(@adapt $part)), None)
+    };
+    (@parts $part1:expr, $part2:expr) => {
+        (
+            Some($crate::aoc_lib::year::aoc_day!(@adapt $part1)),
+            Some($crate::aoc_lib::year::aoc_day!(@adapt $part2)),
+        )
+    };
+    (@adapt_play $part:expr) => {
+        |input: &str, commands: &mut dyn ::std::io::BufRead, replies: &mut dyn ::std::io::Write| {
+            $crate::aoc_lib::error::expect_input(input)?;
+            ($part)(input, commands, replies).map($crate::aoc_lib::Solved::from)
+        }
+    };
+    (@play $part:expr) => {
+        (Some($crate::aoc_lib::year::aoc_day!(@adapt_play $part)), None)
+    };
+    (@play $part1:expr, $part2:expr) => {
+        (
+            Some($crate::aoc_lib::year::aoc_day!(@adapt_play $part1)),
+            Some($crate::aoc_lib::year::aoc_day!(@adapt_play $part2)),
+        )
+    };
+    (@variant; $($part:expr),+) => {
+        $crate::aoc_lib::year::Parts::Solve($crate::aoc_lib::year::aoc_day!(@parts $($part),+))
+    };
+    (@variant interactive; $($part:expr),+) => {
+        $crate::aoc_lib::year::Parts::Play($crate::aoc_lib::year::aoc_day!(@play $($part),+))
+    };
+    // The variants and the hooks are picked out of the same list, each skipping
+    // what the other takes
+    (@variants [$($done:expr),*]) => {
+        &[$($done),*]
+    };
+    (@variants [$($done:expr),*]
+        $name:ident = $($flag:ident)? ($($variant:expr),+) $(, $($rest:tt)*)?) => {
+        $crate::aoc_lib::year::aoc_day!(@variants [$($done,)* $crate::aoc_lib::year::Variant {
+            name: stringify!($name),
+            parts: $crate::aoc_lib::year::aoc_day!(@variant $($flag)?; $($variant),+),
+        }] $($($rest)*)?)
+    };
+    (@variants [$($done:expr),*]
+        $(#[$meta:meta])* $hook:ident = $function:path $(, $($rest:tt)*)?) => {
+        $crate::aoc_lib::year::aoc_day!(@variants [$($done),*] $($($rest)*)?)
+    };
+    (@hooks $hooks:expr;) => {
+        $hooks
+    };
+    (@hooks $hooks:expr;
+        $name:ident = $($flag:ident)? ($($variant:expr),+) $(, $($rest:tt)*)?) => {
+        $crate::aoc_lib::year::aoc_day!(@hooks $hooks; $($($rest)*)?)
+    };
+    (@hooks $hooks:expr;
+        $(#[$meta:meta])* $hook:ident = $function:path $(, $($rest:tt)*)?) => {
+        $crate::aoc_lib::year::aoc_day!(@hooks {
+            let hooks = $hooks;
+            $(#[$meta])*
+            let hooks = $crate::aoc_lib::year::Hooks {
+                $hook: Some($function),
+                ..hooks
+            };
+            hooks
+        }; $($($rest)*)?)
+    };
+    (@entry $year:literal, $day:literal, $parts:expr $(; $($items:tt)*)?) => {
+        pub const DAY: $crate::aoc_lib::year::Day = $crate::aoc_lib::year::Day {
+            year: $year,
+            day: $day,
+            parts: $parts,
+            variants: $crate::aoc_lib::year::aoc_day!(@variants [] $($($items)*)?),
+            hooks: $crate::aoc_lib::year::aoc_day!(
+                @hooks $crate::aoc_lib::year::Hooks::NONE; $($($items)*)?
+            ),
+        };
+    };
+    ($year:literal, $day:literal, solution = $solution:ty $(; $($variants:tt)*)?) => {
+        $crate::aoc_lib::year::aoc_day!(
+            @entry $year, $day, $crate::aoc_lib::solution::day::<$solution>() $(; $($variants)*)?
+        );
+    };
+    ($year:literal, $day:literal, solution_borrowed = $solution:ty $(; $($variants:tt)*)?) => {
+        $crate::aoc_lib::year::aoc_day!(
+            @entry $year, $day, $crate::aoc_lib::solution::day_borrowed::<$solution>()
+            $(; $($variants)*)?
+        );
+    };
+    ($year:literal, $day:literal, input_kind = ($part1:expr, $part2:expr) $(; $($variants:tt)*)?) => {
+        $crate::aoc_lib::year::aoc_day!(
+            @entry $year, $day, (
+                Some($crate::aoc_lib::year::aoc_day!(@adapt_kind $part1)),
+                Some($crate::aoc_lib::year::aoc_day!(@adapt_kind $part2)),
+            ) $(; $($variants)*)?
+        );
+    };
+    ($year:literal, $day:literal, $part1:expr, $part2:expr $(; $($variants:tt)*)?) => {
+        $crate::aoc_lib::year::aoc_day!(
+            @entry $year, $day, $crate::aoc_lib::year::aoc_day!(@parts $part1, $part2)
+            $(; $($variants)*)?
+        );
+    };
+    ($year:literal, $day:literal, $part:expr $(; $($variants:tt)*)?) => {
+        $crate::aoc_lib::year::aoc_day!(
+            @entry $year, $day, $crate::aoc_lib::year::aoc_day!(@parts $part) $(; $($variants)*)?
+        );
+    };
+}
+pub use aoc_day;
+
+// Declares the modules of the solved days and collects what they registered,
+// so a day can't be added without also showing up in its year
+#[macro_export]
+macro_rules! solved_days {
+    ($($module:ident),* $(,)?) => {
+        $(mod $module;)*
+
+        const SOLVED: &[$crate::aoc_lib::year::Day] = &[$($module::DAY),*];
+    };
+}
+pub use solved_days;
+
+// One year of puzzles. The binary only talks to the years through this, so a
+// new year just needs an impl and an entry in the registry.
+pub trait Year: Sync {
+    fn year(&self) -> u16;
+
+    // Every day registered with `aoc_day!`
+    fn solved(&self) -> &'static [Day];
+
+    // How many of the 25 days have an actual solution
+    fn days_implemented(&self) -> u32 {
+        self.solved().len() as u32
+    }
+
+    // The parts of a registered day. The other days of the calendar are still
+    // to be solved, anything else isn't a day.
+    fn get_day(&self, day: u8) -> Result<DaySpec, AocError> {
+        match self.solved().iter().find(|entry| entry.day == day) {
+            Some(entry) => {
+                debug_assert_eq!(entry.year, self.year(), "Day {} is in the wrong year", day);
+                Ok(entry.parts)
+            }
+            None if (1..=25).contains(&day) => Err(AocError::Unimplemented),
+            None => Err(AocError::Unknown(format!("day {}", day))),
+        }
+    }
+
+    // Alternative solvers for the days that have more than one approach
+    fn variants(&self, day: u8) -> &'static [Variant] {
+        self.solved()
+            .iter()
+            .find(|entry| entry.day == day)
+            .map_or(&[], |entry| entry.variants)
+    }
+
+    fn get_variant(&self, day: u8, algo: &str) -> Result<Parts, AocError> {
+        let variants = self.variants(day);
+        match variants.iter().find(|variant| variant.name == algo) {
+            Some(variant) => Ok(variant.parts),
+            None if variants.is_empty() => Err(AocError::Unknown(format!(
+                "algorithm \"{}\", day {} has no alternative solvers",
+                algo, day
+            ))),
+            None => {
+                let names: Vec<&str> = variants.iter().map(|variant| variant.name).collect();
+                Err(AocError::Unknown(format!(
+                    "algorithm \"{}\" for day {}, try one of: {}",
+                    algo,
+                    day,
+                    names.join(", ")
+                )))
+            }
+        }
+    }
+
+    // Whatever else the day registered, nothing for the days without an entry
+    fn hooks(&self, day: u8) -> Hooks {
+        self.solved()
+            .iter()
+            .find(|entry| entry.day == day)
+            .map_or(Hooks::NONE, |entry| entry.hooks)
+    }
+}
+
+// Finds a year among the registered ones
+pub fn find<'a>(registry: &[&'a dyn Year], year: u16) -> Option<&'a dyn Year> {
+    registry.iter().find(|entry| entry.year() == year).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, Write};
+
+    use super::*;
+
+    use crate::aoc_lib::config::InputKind;
+    use crate::aoc_lib::{Answer, Solved};
+
+    fn first(_input: &str) -> Result<Answer, AocError> {
+        Ok(Answer::from(1))
+    }
+
+    fn second(_input: &str) -> Result<Solved, AocError> {
+        Ok(Solved::from(Answer::from(2)).note("Second"))
+    }
+
+    fn graph(_input: &str) -> Result<String, AocError> {
+        Ok(String::from("digraph {}"))
+    }
+
+    // Answers with what was typed
+    fn ask(
+        input: &str,
+        commands: &mut dyn BufRead,
+        replies: &mut dyn Write,
+    ) -> Result<Answer, AocError> {
+        let mut line = String::new();
+        commands.read_line(&mut line)?;
+        writeln!(replies, "{} {}", input, line.trim())?;
+        Ok(Answer::from(line.trim()))
+    }
+
+    mod day03 {
+        use super::{ask, first, graph, second};
+
+        // The explainer doesn't exist, it's only there to be left out
+        aoc_day!(
+            1999,
+            3,
+            first;
+            fast = (second),
+            graph = graph,
+            both = (first, second),
+            #[cfg(any())]
+            explain = explain,
+            ask = interactive(ask),
+        );
+    }
+
+    // Says which input it was handed
+    fn which(_input: &str, kind: InputKind) -> Result<Answer, AocError> {
+        Ok(Answer::from(format!("{:?}", kind).as_str()))
+    }
+
+    mod day04 {
+        use super::which;
+
+        aoc_day!(1999, 4, input_kind = (which, which));
+    }
+
+    const SOLVED: &[Day] = &[day03::DAY];
+
+    struct Sparse;
+
+    impl Year for Sparse {
+        fn year(&self) -> u16 {
+            1999
+        }
+
+        fn solved(&self) -> &'static [Day] {
+            SOLVED
+        }
+    }
+
+    #[test]
+    fn lookup() {
+        let registry: [&dyn Year; 1] = [&Sparse];
+
+        assert_eq!(find(&registry, 1999).map(|year| year.year()), Some(1999));
+        assert!(find(&registry, 2000).is_none());
+        assert_eq!(Sparse.days_implemented(), 1);
+        assert_eq!(
+            Sparse.get_variant(1, "fast").unwrap_err().to_string(),
+            "Unknown algorithm \"fast\", day 1 has no alternative solvers"
+        );
+        assert_eq!(
+            Sparse.get_variant(3, "slow").unwrap_err().to_string(),
+            "Unknown algorithm \"slow\" for day 3, try one of: fast, both, ask"
+        );
+        let hooks = Sparse.hooks(1);
+        assert!(hooks.render.is_none() && hooks.visualize.is_none() && hooks.compare.is_none());
+        assert!(hooks.export.is_none() && hooks.trace.is_none() && hooks.graph.is_none());
+        assert!(hooks.explain.is_none());
+    }
+
+    #[test]
+    fn registered_days() {
+        let (part1, part2) = Sparse.get_day(3).unwrap();
+        assert!(part1.is_some() && part2.is_none());
+        assert_eq!(
+            part1.unwrap()("1", InputKind::Real).unwrap().answer,
+            Answer::Int(1)
+        );
+        // Nothing to read is an error before the part runs
+        assert!(matches!(
+            part1.unwrap()("\n", InputKind::Real),
+            Err(AocError::Parse(_))
+        ));
+
+        let names: Vec<&str> = Sparse.variants(3).iter().map(|v| v.name).collect();
+        assert_eq!(names, ["fast", "both", "ask"]);
+        assert!(matches!(
+            Sparse.get_variant(3, "fast"),
+            Ok(Parts::Solve((Some(_), None)))
+        ));
+        let Ok(Parts::Solve((_, Some(both)))) = Sparse.get_variant(3, "both") else {
+            panic!("Both parts of \"both\" should be there");
+        };
+        assert_eq!(both("2", InputKind::Real).unwrap().notes, ["Second"]);
+        assert!(Sparse.variants(4).is_empty());
+
+        // Only the parts asking for it see the kind of input
+        let (Some(part1), Some(part2)) = day04::DAY.parts else {
+            panic!("Day 4 has both parts");
+        };
+        assert_eq!(
+            part1("4", InputKind::Example).unwrap().answer,
+            Answer::from("Example")
+        );
+        assert_eq!(
+            part2("4", InputKind::Real).unwrap().answer,
+            Answer::from("Real")
+        );
+
+        // The interactive solver gets whatever the runner hands it
+        let Ok(Parts::Play((Some(ask), None))) = Sparse.get_variant(3, "ask") else {
+            panic!("\"ask\" should be interactive");
+        };
+        let mut replies = Vec::new();
+        let solved = ask("Hello", &mut "there\n".as_bytes(), &mut replies).unwrap();
+        assert_eq!(solved.answer, Answer::from("there"));
+        assert_eq!(replies, b"Hello there\n");
+
+        let hooks = Sparse.hooks(3);
+        assert_eq!(hooks.graph.unwrap()("").unwrap(), "digraph {}");
+        assert!(hooks.render.is_none() && hooks.explain.is_none());
+
+        // Unsolved days are there to be solved, days outside the calendar aren't
+        assert!(matches!(Sparse.get_day(4), Err(AocError::Unimplemented)));
+        assert_eq!(
+            Sparse.get_day(26).unwrap_err().to_string(),
+            "Unknown day 26"
+        );
+    }
+}
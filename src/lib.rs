@@ -0,0 +1,216 @@
+// Helpers used by a single year are dead code when that year is left out
+#![cfg_attr(
+    not(all(
+        feature = "year2015",
+        feature = "year2019",
+        feature = "year2020",
+        feature = "year2021",
+        feature = "year2022",
+        feature = "year2023"
+    )),
+    allow(dead_code)
+)]
+// With no year at all there is nothing to register either
+#![cfg_attr(
+    not(any(
+        feature = "year2015",
+        feature = "year2019",
+        feature = "year2020",
+        feature = "year2021",
+        feature = "year2022",
+        feature = "year2023"
+    )),
+    allow(unused_imports, unused_macros)
+)]
+// Without std only the shared algorithms are left, on core and alloc. The unit
+// tests still get std, for the helpers they share.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+// The days and the helpers they share, as a library so other tools can run the
+// solutions too, and so the examples in the documentation run with `cargo
+// test`. The command line in main.rs uses it like any other crate.
+pub mod aoc_lib;
+
+// The days hand anything they want shown back to the runner, as a `Solved` with
+// notes or a drawing, and never print it themselves
+#[cfg(feature = "year2015")]
+#[cfg_attr(
+    not(test),
+    deny(clippy::print_stdout, clippy::print_stderr, clippy::dbg_macro)
+)]
+pub mod year_2015;
+#[cfg(feature = "year2019")]
+#[cfg_attr(
+    not(test),
+    deny(clippy::print_stdout, clippy::print_stderr, clippy::dbg_macro)
+)]
+pub mod year_2019;
+#[cfg(feature = "year2020")]
+#[cfg_attr(
+    not(test),
+    deny(clippy::print_stdout, clippy::print_stderr, clippy::dbg_macro)
+)]
+pub mod year_2020;
+#[cfg(feature = "year2021")]
+#[cfg_attr(
+    not(test),
+    deny(clippy::print_stdout, clippy::print_stderr, clippy::dbg_macro)
+)]
+pub mod year_2021;
+#[cfg(feature = "year2022")]
+#[cfg_attr(
+    not(test),
+    deny(clippy::print_stdout, clippy::print_stderr, clippy::dbg_macro)
+)]
+pub mod year_2022;
+#[cfg(feature = "year2023")]
+#[cfg_attr(
+    not(test),
+    deny(clippy::print_stdout, clippy::print_stderr, clippy::dbg_macro)
+)]
+pub mod year_2023;
+
+#[cfg(feature = "std")]
+use aoc_lib::config::InputKind;
+#[cfg(feature = "std")]
+use aoc_lib::year::{self, Year};
+#[cfg(feature = "std")]
+use aoc_lib::{Answer, AocError, DayFn};
+
+// Every year that can be run, a new year only has to be added here
+#[cfg(feature = "std")]
+pub fn registry() -> &'static [&'static dyn Year] {
+    static YEARS: &[&dyn Year] = &[
+        #[cfg(feature = "year2015")]
+        &year_2015::Year2015,
+        #[cfg(feature = "year2019")]
+        &year_2019::Year2019,
+        #[cfg(feature = "year2020")]
+        &year_2020::Year2020,
+        #[cfg(feature = "year2021")]
+        &year_2021::Year2021,
+        #[cfg(feature = "year2022")]
+        &year_2022::Year2022,
+        #[cfg(feature = "year2023")]
+        &year_2023::Year2023,
+    ];
+    YEARS
+}
+
+// The answer of one part of a day, for the tools that want it without going
+// through the command line. `kind` says whether `input` is the example.
+#[cfg(feature = "std")]
+pub fn solve(
+    year: u16,
+    day: u8,
+    part: u8,
+    input: &str,
+    kind: InputKind,
+) -> Result<Answer, AocError> {
+    find_part(year, day, part)?(input, kind).map(|solved| solved.answer)
+}
+
+// The function solving one part of a day, for the tools that want the notes
+// too or time it themselves
+#[cfg(feature = "std")]
+pub fn find_part(year: u16, day: u8, part: u8) -> Result<DayFn, AocError> {
+    let Some(found) = year::find(registry(), year) else {
+        return Err(AocError::Unknown(format!("year {}", year)));
+    };
+    let parts = found.get_day(day)?;
+    let to_run = match part {
+        1 => parts.0,
+        2 => parts.1,
+        _ => None,
+    };
+
+    to_run.ok_or_else(|| AocError::Unknown(format!("part {} of day {}", part, day)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn registered_days() {
+        for year in registry() {
+            let mut days: Vec<u8> = year.solved().iter().map(|entry| entry.day).collect();
+            for entry in year.solved() {
+                assert_eq!(
+                    entry.year,
+                    year.year(),
+                    "Day {} is in the wrong year",
+                    entry.day
+                );
+                assert!(
+                    (1..=25).contains(&entry.day),
+                    "Day {} is not in December",
+                    entry.day
+                );
+            }
+            for entry in year.solved() {
+                let mut names: Vec<&str> = entry.variants.iter().map(|v| v.name).collect();
+                names.sort_unstable();
+                names.dedup();
+                assert_eq!(
+                    names.len(),
+                    entry.variants.len(),
+                    "Day {} repeats a solver",
+                    entry.day
+                );
+                assert!(
+                    !names.contains(&"all"),
+                    "Day {} shadows --algo all",
+                    entry.day
+                );
+            }
+            days.sort_unstable();
+            days.dedup();
+            assert_eq!(
+                days.len(),
+                year.solved().len(),
+                "{} has a day twice",
+                year.year()
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "year2022")]
+    fn solve_a_part() {
+        let input = include_str!("../inputs/2022/day01.example.txt");
+
+        assert_eq!(
+            solve(2022, 1, 1, input, InputKind::Example).unwrap(),
+            Answer::UInt(24000)
+        );
+        assert_eq!(
+            solve(2022, 1, 2, input, InputKind::Example).unwrap(),
+            Answer::UInt(45000)
+        );
+        assert!(matches!(
+            solve(2022, 1, 3, input, InputKind::Example),
+            Err(AocError::Unknown(_))
+        ));
+        assert!(matches!(
+            solve(2022, 20, 1, input, InputKind::Example),
+            Err(AocError::Unimplemented)
+        ));
+        assert_eq!(
+            solve(1999, 1, 1, input, InputKind::Example)
+                .unwrap_err()
+                .to_string(),
+            "Unknown year 1999"
+        );
+
+        // Day 15 looks at another row of the example than of the real inputs
+        let input = include_str!("../inputs/2022/day15.example.txt");
+        let example = solve(2022, 15, 1, input, InputKind::Example);
+        assert_eq!(example.unwrap().to_string(), "26");
+        let real = solve(2022, 15, 1, input, InputKind::Real);
+        assert_eq!(real.unwrap().to_string(), "0");
+    }
+}
@@ -1,99 +1,872 @@
+use std::fs::File;
+use std::io::{self, BufWriter};
+#[cfg(feature = "serve")]
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process;
 use std::time::{Duration, Instant};
 use std::{env, fs};
 
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
+use rayon::prelude::*;
 
-mod aoc_lib;
-use aoc_lib::DayFn;
-
-mod year_2021;
-mod year_2022;
-mod year_2023;
+use advent_of_code::{aoc_lib, registry};
+use aoc_lib::aoc_cli::{self, Copied, Layout};
+use aoc_lib::config::InputKind;
+use aoc_lib::error::write_error;
+use aoc_lib::progress;
+use aoc_lib::report::{self, Timings};
+use aoc_lib::rpc;
+#[cfg(feature = "serve")]
+use aoc_lib::serve;
+#[cfg(feature = "sqlite")]
+use aoc_lib::sqlite::{self, Database};
+use aoc_lib::trace::Recorder;
+use aoc_lib::viz::dots::{self, Resolution};
+use aoc_lib::vm::{alu, assembunny, disasm, elfcode, handheld};
+use aoc_lib::year::{self, Parts};
+use aoc_lib::{examples, testgen};
+use aoc_lib::{fmt_dur, Answer, AocError, DayFn, PlayFn, Solved, TraceFn};
 
 #[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
+#[clap(args_conflicts_with_subcommands = true)]
 struct CLIConfig {
     /// Day to run
-    #[clap(value_parser)]
-    day: u8,
+    #[clap(value_parser, required_unless_present = "stdio")]
+    day: Option<u8>,
 
     /// Year to get the day from
     #[clap(short, long, value_parser, default_value_t = 2021)]
     year: u16,
+
+    /// Run an alternative solver instead of the default one, "all" runs all of them
+    #[clap(short, long, value_parser)]
+    algo: Option<String>,
+
+    /// Write a visualization of the day to this file instead of solving it
+    #[clap(short, long, value_parser)]
+    render: Option<PathBuf>,
+
+    /// Play an animation of the day in the terminal instead of solving it
+    #[clap(long, value_parser, conflicts_with = "render")]
+    visualize: bool,
+
+    /// Play two solvers of the day side by side, step for step
+    #[clap(long, value_parser, requires = "visualize")]
+    compare: bool,
+
+    /// Also record the animation in this file, as an asciicast for asciinema
+    #[clap(long, value_parser, requires = "visualize")]
+    cast: Option<PathBuf>,
+
+    /// Pack the cells of the animation tighter, so large grids fit in the terminal
+    #[clap(long, value_enum, requires = "visualize")]
+    dots: Option<Dots>,
+
+    /// Save pictures of the day in target/viz/YEAR/DAY instead of solving it
+    #[cfg(feature = "images")]
+    #[clap(long, value_parser, conflicts_with_all = ["render", "visualize", "trace", "export_graph", "explain"])]
+    images: bool,
+
+    /// Solve the day and record every step in this file, as JSON lines
+    #[clap(long, value_parser, conflicts_with_all = ["render", "visualize"])]
+    trace: Option<PathBuf>,
+
+    /// Write the graph of the day to this file in the DOT language of Graphviz
+    #[clap(long, value_parser, conflicts_with_all = ["render", "visualize", "trace"])]
+    export_graph: Option<PathBuf>,
+
+    /// Break the answer of the day down into the steps that lead to it
+    #[clap(long, value_parser, conflicts_with_all = ["render", "visualize", "trace", "export_graph"])]
+    explain: bool,
+
+    /// Show how far the long searches got while they run
+    #[clap(short, long, value_parser)]
+    progress: bool,
+
+    /// Use the example from the puzzle text, inputs/YEAR/dayNN.example.txt, or
+    /// solve the --input file as an example
+    #[clap(short, long, value_parser)]
+    example: bool,
+
+    /// Solve a made up input of this size instead, for the days with a generator
+    #[clap(short, long, value_parser, conflicts_with = "example")]
+    generated: Option<usize>,
+
+    /// Read the input from this file instead of inputs/YEAR/dayNN.txt
+    #[clap(short, long, value_parser, conflicts_with = "generated")]
+    input: Option<PathBuf>,
+
+    /// Seed of the generated input, the same seed always gives the same input
+    #[clap(long, value_parser, default_value_t = 1, requires = "generated")]
+    seed: u64,
+
+    /// Keep answering JSON-RPC requests on stdin, one per line, until it closes
+    #[clap(long, value_parser, conflicts_with = "day")]
+    stdio: bool,
+
+    /// Append the answers and timings to this SQLite database, the benches of
+    /// --stdio too
+    #[cfg(feature = "sqlite")]
+    #[clap(long, value_parser)]
+    record: Option<PathBuf>,
+
+    #[clap(subcommand)]
+    command: Option<Command>,
 }
 
-fn get_day(year: u16, day: u8) -> (Option<DayFn>, Option<DayFn>) {
-    return match year {
-        2021 => year_2021::get_day(day),
-        2022 => year_2022::get_day(day),
-        2023 => year_2023::get_day(day),
-        _ => {
-            println!("Unknown year: {}", year);
-            return (None, None);
+#[derive(Subcommand)]
+enum Command {
+    /// List the solved days of every year, with their alternative solvers
+    List,
+    /// Run every solver on the examples in examples.toml and check the answers
+    CheckExamples {
+        /// Append the answers and timings to this SQLite database
+        #[cfg(feature = "sqlite")]
+        #[clap(long, value_parser)]
+        record: Option<PathBuf>,
+    },
+    /// Pretty print a VM program with labels and loops
+    Disasm {
+        /// Instruction set the program is written in
+        #[clap(value_enum)]
+        machine: Machine,
+
+        /// File containing the program
+        #[clap(value_parser)]
+        file: PathBuf,
+    },
+    /// Copy the inputs and accepted answers of a year from the files aoc-cli
+    /// downloaded, to inputs/YEAR
+    Import {
+        /// Directory aoc-cli downloaded the year to
+        #[clap(value_parser)]
+        dir: PathBuf,
+
+        /// Year the files are from
+        #[clap(short, long, value_parser)]
+        year: u16,
+
+        /// Where the inputs are in the directory, {day} has two digits
+        #[clap(long, value_parser, default_value = aoc_cli::INPUTS)]
+        inputs: String,
+
+        /// Where the puzzle texts with the accepted answers are in the directory
+        #[clap(long, value_parser, default_value = aoc_cli::PUZZLES)]
+        puzzles: String,
+
+        /// Overwrite the inputs and answers already there
+        #[clap(long, value_parser)]
+        force: bool,
+    },
+    /// Copy the inputs of a year to where aoc-cli would download them
+    Export {
+        /// Directory to keep the year in
+        #[clap(value_parser)]
+        dir: PathBuf,
+
+        /// Year to copy
+        #[clap(short, long, value_parser)]
+        year: u16,
+
+        /// Where the inputs go in the directory, {day} has two digits
+        #[clap(long, value_parser, default_value = aoc_cli::INPUTS)]
+        inputs: String,
+
+        /// Overwrite the inputs already there
+        #[clap(long, value_parser)]
+        force: bool,
+    },
+    /// Count the stars of the solved days, or write a table of them in Markdown
+    Report {
+        /// Write the table of the days with their stars, answers and timings to
+        /// this file
+        #[clap(long, value_parser)]
+        markdown: Option<PathBuf>,
+
+        /// Only say which parts have an answer in the table, not what it is
+        #[clap(long, value_parser, requires = "markdown")]
+        redact: bool,
+
+        /// Take the timings from the database --record appends to
+        #[cfg(feature = "sqlite")]
+        #[clap(long, value_parser)]
+        record: Option<PathBuf>,
+    },
+    /// Answer POST /solve/YEAR/DAY/PART with the input as the body, in JSON, and
+    /// GET /metrics for Prometheus
+    #[cfg(feature = "serve")]
+    Serve {
+        /// Address and port to listen on
+        #[clap(long, value_parser, default_value = "127.0.0.1:8080")]
+        address: String,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Dots {
+    /// Two cells across and four down in a Braille pattern
+    Braille,
+    /// Two cells on top of each other, for fonts without Braille patterns
+    Half,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Machine {
+    Assembunny,
+    Elfcode,
+    Handheld,
+    Alu,
+}
+
+fn disassemble(machine: Machine, file: &PathBuf) -> Result<String, String> {
+    let input = fs::read_to_string(file)
+        .map_err(|e| format!("Error while reading {}: {}", file.display(), e))?;
+
+    Ok(match machine {
+        Machine::Assembunny => disasm::render(&assembunny::parse_program(&input)?),
+        Machine::Elfcode => disasm::render(&elfcode::parse_program(&input)?),
+        Machine::Handheld => disasm::render(&handheld::parse_program(&input)?),
+        Machine::Alu => disasm::render(&alu::parse_program(&input)?),
+    })
+}
+
+fn main() {
+    // Get day string
+    let user_config = CLIConfig::parse();
+
+    match &user_config.command {
+        Some(Command::Disasm { machine, file }) => {
+            match disassemble(*machine, file) {
+                Ok(listing) => print!("{}", listing),
+                Err(error) => println!("{}", error),
+            }
+            return;
+        }
+        Some(Command::List) => {
+            list_days();
+            return;
+        }
+        Some(Command::Report {
+            markdown, redact, ..
+        }) => {
+            if let Err(error) = write_report(&user_config, markdown.as_deref(), *redact) {
+                println!("{}", error);
+                process::exit(1);
+            }
+            return;
+        }
+        Some(Command::Import {
+            dir,
+            year,
+            inputs,
+            puzzles,
+            force,
+        }) => {
+            let layout = Layout {
+                root: dir,
+                inputs,
+                puzzles,
+            };
+            let imported = aoc_cli::import(&layout, *year, &PathBuf::from("inputs"), *force);
+            match imported {
+                Ok(copied) => report_copy(&copied, "from", dir),
+                Err(error) => {
+                    println!("{}", error);
+                    process::exit(1);
+                }
+            }
+            return;
+        }
+        Some(Command::Export {
+            dir,
+            year,
+            inputs,
+            force,
+        }) => {
+            let layout = Layout {
+                root: dir,
+                inputs,
+                puzzles: aoc_cli::PUZZLES,
+            };
+            let exported = aoc_cli::export(&layout, *year, &PathBuf::from("inputs"), *force);
+            match exported {
+                Ok(copied) => report_copy(&copied, "to", dir),
+                Err(error) => {
+                    println!("{}", error);
+                    process::exit(1);
+                }
+            }
+            return;
+        }
+        #[cfg(feature = "serve")]
+        Some(Command::Serve { address }) => {
+            let listening = TcpListener::bind(address).map_err(AocError::from);
+            if let Err(error) = listening.and_then(|listener| {
+                println!("Solving on http://{}/solve/YEAR/DAY/PART", address);
+                serve::serve(listener)
+            }) {
+                println!("{}", error);
+                process::exit(1);
+            }
+            return;
+        }
+        Some(Command::CheckExamples { .. }) => match check_examples(&user_config) {
+            Ok(true) => return,
+            Ok(false) => process::exit(1),
+            Err(error) => {
+                println!("{}", error);
+                process::exit(1);
+            }
+        },
+        None => (),
+    }
+    if user_config.stdio {
+        let on_bench = match bench_recorder(&user_config) {
+            Ok(on_bench) => on_bench,
+            Err(error) => {
+                eprintln!("{}", error);
+                process::exit(1);
+            }
+        };
+        if let Err(error) = rpc::serve(io::stdin().lock(), io::stdout(), on_bench) {
+            eprintln!("{}", error);
+            process::exit(1);
         }
+        return;
+    }
+    let day = user_config.day.unwrap();
+
+    let Some(year) = year::find(registry(), user_config.year) else {
+        println!("Unknown year: {}", user_config.year);
+        for known in registry() {
+            println!(
+                "{}: {} days implemented",
+                known.year(),
+                known.days_implemented()
+            );
+        }
+        return;
+    };
+
+    // Read input file, or make one up
+    let input = match user_config.generated {
+        Some(size) => {
+            let seed = user_config.seed;
+            match testgen::generate(user_config.year, day, size, seed) {
+                Ok(input) => {
+                    println!("Generated an input of size {} with seed {}", size, seed);
+                    println!();
+                    input
+                }
+                Err(error) => {
+                    println!("{}", error);
+                    return;
+                }
+            }
+        }
+        None => {
+            let cwd = env::current_dir().unwrap();
+            let filename = match &user_config.input {
+                Some(file) => cwd.join(file),
+                None => cwd
+                    .join("inputs")
+                    .join(format!("{}", user_config.year))
+                    .join(if user_config.example {
+                        format!("day{:02}.example.txt", day)
+                    } else {
+                        format!("day{:02}.txt", day)
+                    }),
+            };
+            println!("Reading {}", filename.display());
+            println!();
+            match fs::read_to_string(filename) {
+                Ok(input) => input,
+                Err(error) => {
+                    println!("{}", AocError::from(error));
+                    return;
+                }
+            }
+        }
+    };
+
+    // The days whose example asks for other constants get told which it is
+    let kind = match user_config.example {
+        true => InputKind::Example,
+        false => InputKind::Real,
+    };
+
+    let hooks = year.hooks(day);
+    if let Some(path) = &user_config.render {
+        if let Some(render) = or_say(hooks.render, "Nothing to render", day) {
+            match render(&input, kind, path) {
+                Ok(()) => println!("Wrote {}", path.display()),
+                Err(error) => println!("{}", error),
+            }
+        }
+        return;
+    }
+
+    #[cfg(feature = "images")]
+    if user_config.images {
+        if let Some(export) = or_say(hooks.export, "No images", day) {
+            match export(&input) {
+                Ok(files) => {
+                    for file in files {
+                        println!("Wrote {}", file.display());
+                    }
+                }
+                Err(error) => println!("{}", error),
+            }
+        }
+        return;
+    }
+
+    if let Some(path) = &user_config.export_graph {
+        if let Some(graph) = or_say(hooks.graph, "No graph", day) {
+            let written = graph(&input)
+                .and_then(|dot| fs::write(path, dot).map_err(|e| write_error(path, e)));
+            match written {
+                Ok(()) => println!("Wrote {}", path.display()),
+                Err(error) => println!("{}", error),
+            }
+        }
+        return;
+    }
+
+    if user_config.explain {
+        if let Some(explain) = or_say(hooks.explain, "Nothing to explain", day) {
+            match explain(&input, kind) {
+                Ok(explanation) => print!("{}", explanation),
+                Err(error) => println!("{}", error),
+            }
+        }
+        return;
+    }
+
+    if let Some(path) = &user_config.trace {
+        if let Some(trace) = or_say(hooks.trace, "Nothing to trace", day) {
+            match write_trace(trace, &input, path) {
+                Ok(events) => println!("Wrote {} events to {}", events, path.display()),
+                Err(error) => println!("{}", error),
+            }
+        }
+        return;
+    }
+
+    if user_config.visualize {
+        dots::choose(match user_config.dots {
+            Some(Dots::Braille) => Resolution::Braille,
+            Some(Dots::Half) => Resolution::HalfBlocks,
+            None => Resolution::Cells,
+        });
+        let visualizer = if user_config.compare {
+            or_say(hooks.compare, "Nothing to compare", day)
+        } else {
+            or_say(hooks.visualize, "Nothing to visualize", day)
+        };
+        if let Some(visualize) = visualizer {
+            if let Err(error) = visualize(&input, kind, user_config.cast.as_deref()) {
+                println!("{}", error);
+            }
+        }
+        return;
+    }
+
+    // Get corresponding function, "all" runs every solver of the day one after
+    // the other so their answers can be compared
+    let to_run = match user_config.algo.as_deref() {
+        Some("all") | None => year.get_day(day).map(Parts::Solve),
+        Some(algo) => year.get_variant(day, algo),
     };
+    let to_run = match to_run {
+        Ok(to_run) => to_run,
+        Err(error) => {
+            println!("{}", error);
+            return;
+        }
+    };
+
+    #[cfg_attr(not(feature = "sqlite"), allow(unused_variables))]
+    let solvers = match user_config.algo.as_deref() {
+        Some("all") => {
+            let mut solvers = vec![(
+                "default",
+                run_solver(to_run, &input, kind, user_config.progress),
+            )];
+            for variant in year.variants(day) {
+                println!();
+                if let Parts::Play(_) = variant.parts {
+                    println!("Solver \"{}\" is interactive, skipped", variant.name);
+                    continue;
+                }
+                println!("Solver \"{}\"", variant.name);
+                let ran = run_solver(variant.parts, &input, kind, user_config.progress);
+                solvers.push((variant.name, ran));
+            }
+            solvers
+        }
+        algo => vec![(
+            algo.unwrap_or("default"),
+            run_solver(to_run, &input, kind, user_config.progress),
+        )],
+    };
+
+    #[cfg(feature = "sqlite")]
+    if let Some(path) = &user_config.record {
+        if let Err(error) = record(path, &user_config, day, &solvers) {
+            println!("{}", error);
+            process::exit(1);
+        }
+    }
 }
 
-fn fmt_time(ms: f64) -> String {
-    if ms <= 1.0 {
-        let micro_sec = ms * 1000.0;
-        return format!("{}µs", micro_sec.round());
+// Where the input came from, in the rows of --record
+#[cfg(feature = "sqlite")]
+fn input_name(user_config: &CLIConfig) -> String {
+    match (user_config.generated, &user_config.input) {
+        (Some(size), _) => format!("generated {} seed {}", size, user_config.seed),
+        (None, Some(file)) => file.display().to_string(),
+        (None, None) if user_config.example => String::from("example"),
+        (None, None) => String::from("real"),
     }
+}
 
-    if ms < 1000.0 {
-        let whole_ms = ms.floor();
-        let rem_ms = ms - whole_ms;
-        return format!("{}ms ", whole_ms) + &fmt_time(rem_ms);
+#[cfg(feature = "sqlite")]
+fn record(
+    path: &Path,
+    user_config: &CLIConfig,
+    day: u8,
+    solvers: &[(&str, Vec<Ran>)],
+) -> Result<(), String> {
+    let database = Database::open(path)?;
+    let input = input_name(user_config);
+    for (solver, parts) in solvers {
+        for (part, answer, took) in parts {
+            database.record(&sqlite::Run {
+                kind: "run",
+                year: user_config.year,
+                day,
+                part: *part,
+                solver,
+                input: &input,
+                answer: answer.clone(),
+                runs: 1,
+                took: *took,
+            })?;
+        }
     }
 
-    let sec: f64 = ms / 1000.0;
-    if sec < 60.0 {
-        let whole_sec = sec.floor();
-        let rem_ms = ms - whole_sec * 1000.0;
+    Ok(())
+}
+
+// The benches of --stdio go in the database of --record, if there's one. A
+// bench that can't be recorded still answers, and says why on stderr.
+#[cfg(feature = "sqlite")]
+fn bench_recorder(user_config: &CLIConfig) -> Result<Option<rpc::OnBench>, String> {
+    let Some(path) = &user_config.record else {
+        return Ok(None);
+    };
+    let database = std::sync::Mutex::new(Database::open(path)?);
+    Ok(Some(Box::new(move |benched: &rpc::Benched| {
+        let recorded = database.lock().unwrap().record(&sqlite::Run {
+            kind: "bench",
+            year: benched.year,
+            day: benched.day,
+            part: benched.part,
+            solver: "default",
+            input: "stdio",
+            answer: Ok(plain(benched.answer)),
+            runs: benched.runs as u64,
+            took: benched.mean,
+        });
+        if let Err(error) = recorded {
+            eprintln!("{}", error);
+        }
+    })))
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn bench_recorder(_: &CLIConfig) -> Result<Option<rpc::OnBench>, String> {
+    Ok(None)
+}
 
-        return format!("{}s ", whole_sec) + &fmt_time(rem_ms);
+// The hook of the day, or the reason there's nothing to run when the day has
+// none
+fn or_say<T>(hook: Option<T>, nothing: &str, day: u8) -> Option<T> {
+    if hook.is_none() {
+        println!("{} for day {}", nothing, day);
     }
+    hook
+}
 
-    let min: f64 = sec / 60.0;
-    format!("{}m ", min.floor()) + &fmt_time((sec % 60.0) * 1000.0)
+fn write_trace(trace: TraceFn, input: &str, path: &PathBuf) -> Result<usize, AocError> {
+    let file = File::create(path).map_err(|e| write_error(path, e))?;
+    let mut recorder = Recorder::new(BufWriter::new(file));
+    trace(input, &mut recorder)?;
+    recorder.finish().map_err(|e| write_error(path, e))
 }
 
-fn fmt_dur(dur: Duration) -> String {
-    fmt_time(dur.as_secs_f64() * 1000.0)
+// Runs every solver of the registered days on their example, and checks the
+// answers against the table
+fn check_examples(user_config: &CLIConfig) -> Result<bool, String> {
+    let table = fs::read_to_string("examples.toml")
+        .map_err(|e| format!("Error while reading examples.toml: {}", e))?;
+    let registered = |example: &&examples::Example| {
+        year::find(registry(), example.year)
+            .is_some_and(|year| year.solved().iter().any(|day| day.day == example.day))
+    };
+    let to_check: Vec<examples::Example> = examples::parse(&table)?
+        .into_iter()
+        .filter(|example| registered(&example))
+        .collect();
+
+    let checked: Vec<Checked> = to_check
+        .par_iter()
+        .map(|example| {
+            let input = PathBuf::from("inputs")
+                .join(example.year.to_string())
+                .join(&example.input);
+            let parts = fs::read_to_string(&input)
+                .map_err(AocError::from)
+                .and_then(|text| examples::check(example, &text));
+            (example, input, parts)
+        })
+        .collect();
+
+    let mut failures = Vec::new();
+    for (example, _, parts) in &checked {
+        match parts {
+            Ok(parts) => failures.extend(parts.iter().filter_map(|part| part.failure(example))),
+            Err(error) => failures.push(format!("{} day {}: {}", example.year, example.day, error)),
+        }
+    }
+    record_examples(user_config, &checked)?;
+
+    for failure in &failures {
+        println!("{}", failure);
+    }
+    println!(
+        "Checked the examples of {} days, {} answers wrong",
+        to_check.len(),
+        failures.len()
+    );
+
+    Ok(failures.is_empty())
 }
 
-fn main() {
-    // Get day string
-    let user_config = CLIConfig::parse();
+// An example, its file and how its solvers did
+type Checked<'a> = (
+    &'a examples::Example,
+    PathBuf,
+    Result<Vec<examples::Checked>, AocError>,
+);
+
+// The answers of check-examples go in the database as "verify" rows
+#[cfg(feature = "sqlite")]
+fn record_examples(user_config: &CLIConfig, checked: &[Checked]) -> Result<(), String> {
+    let Some(Command::CheckExamples { record: Some(path) }) = &user_config.command else {
+        return Ok(());
+    };
+    let database = Database::open(path)?;
+    for (example, input, parts) in checked {
+        for part in parts.iter().flatten() {
+            database.record(&sqlite::Run {
+                kind: "verify",
+                year: example.year,
+                day: example.day,
+                part: part.part,
+                solver: part.solver,
+                input: &input.display().to_string(),
+                answer: match &part.answer {
+                    Ok(solved) => Ok(plain(&solved.answer)),
+                    Err(error) => Err(error.to_string()),
+                },
+                runs: 1,
+                took: part.took,
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn record_examples(_: &CLIConfig, _: &[Checked]) -> Result<(), String> {
+    Ok(())
+}
+
+// The stars of every year, and the whole table in `markdown` if there's one
+fn write_report(
+    user_config: &CLIConfig,
+    markdown: Option<&Path>,
+    redact: bool,
+) -> Result<(), String> {
+    let rows = report::rows(registry(), Path::new("inputs"), &timings(user_config)?);
+    println!("{}", report::summary(&rows));
 
-    // Read input file
-    let cwd = env::current_dir().unwrap();
-    let filename = cwd
-        .join("inputs")
-        .join(format!("{}", user_config.year))
-        .join(format!("day{:02}.txt", user_config.day));
-    println!("Reading {}", filename.display());
-    println!();
-    let input = fs::read_to_string(filename).expect("Error while reading");
+    if let Some(path) = markdown {
+        fs::write(path, report::markdown(&rows, redact))
+            .map_err(|e| format!("Error while writing {}: {}", path.display(), e))?;
+        println!("Wrote {}", path.display());
+    }
+    Ok(())
+}
+
+// The timings of the report, from the database of --record if there's one
+#[cfg(feature = "sqlite")]
+fn timings(user_config: &CLIConfig) -> Result<Timings, String> {
+    match &user_config.command {
+        Some(Command::Report {
+            record: Some(path), ..
+        }) => {
+            // Opening it would make an empty one
+            if !path.is_file() {
+                return Err(format!("No database at {}", path.display()));
+            }
+            Database::open(path)?.timings()
+        }
+        _ => Ok(Timings::new()),
+    }
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn timings(_: &CLIConfig) -> Result<Timings, String> {
+    Ok(Timings::new())
+}
+
+fn days(days: &[u8]) -> String {
+    days.iter()
+        .map(u8::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
 
-    // Get corresponding function
-    let to_run = get_day(user_config.year, user_config.day);
+// What import and export copied, and what they left alone
+fn report_copy(copied: &Copied, direction: &str, dir: &Path) {
+    println!(
+        "Copied {} inputs and {} answers {} {}",
+        copied.inputs.len(),
+        copied.answers.len(),
+        direction,
+        dir.display()
+    );
+    for kept in &copied.kept {
+        println!("Kept {}, --force overwrites it", kept.display());
+    }
+    if copied.missing.is_empty() {
+        return;
+    }
+
+    println!("No input for days {}", days(&copied.missing));
+    // Only an import can get the missing ones from aoc-cli
+    if direction == "from" {
+        match aoc_cli::session() {
+            Some(session) => println!("aoc-cli can download them, with the session in {}", session),
+            None => println!(
+                "aoc-cli needs a session cookie in ~/.adventofcode.session to download them"
+            ),
+        }
+    }
+}
+
+// One line per solved day: year, day and the names of the other solvers
+fn list_days() {
+    for year in registry() {
+        for entry in year.solved() {
+            let names: Vec<&str> = entry.variants.iter().map(|v| v.name).collect();
+            let line = format!("{} {:02} {}", entry.year, entry.day, names.join(" "));
+            println!("{}", line.trim_end());
+        }
+    }
+}
+
+// The answer on a single line, or the drawing without the line before it
+fn plain(answer: &Answer) -> String {
+    match answer {
+        Answer::Grid(drawing) => drawing.trim_end().to_string(),
+        answer => answer.to_string(),
+    }
+}
+
+// A part that ran: which one, its answer or why it has none, and how long it took
+type Ran = (u8, Result<String, String>, Duration);
+
+// Each part gets its own progress line, gone by the time the answer is printed
+fn run_part(
+    part: impl Fn(&str) -> Result<Solved, AocError>,
+    input: &str,
+    progress: bool,
+) -> Result<String, String> {
+    let spinner = progress.then(|| progress::attach(io::stderr()));
+    let answer = part(input);
+    drop(spinner);
+
+    match answer {
+        Ok(solved) => {
+            println!("{}", solved);
+            Ok(plain(&solved.answer))
+        }
+        Err(error) => {
+            println!("{}", error);
+            Err(error.to_string())
+        }
+    }
+}
+
+fn run_parts<F>(to_run: (Option<F>, Option<F>), input: &str, progress: bool) -> Vec<Ran>
+where
+    F: Fn(&str) -> Result<Solved, AocError>,
+{
+    let mut ran = Vec::new();
 
     // Time it
     if let Some(part_one) = to_run.0 {
         println!("Running Part 1 =============================================");
         let part1_start = Instant::now();
-        part_one(&input);
+        let answer = run_part(part_one, input, progress);
         let part1_dur = part1_start.elapsed();
         println!("Took {}", fmt_dur(part1_dur));
         println!();
+        ran.push((1, answer, part1_dur));
     }
 
     if let Some(part_two) = to_run.1 {
         println!("Running Part 2 =============================================");
         let part2_start = Instant::now();
-        part_two(&input);
+        let answer = run_part(part_two, input, progress);
         let part2_dur = part2_start.elapsed();
         println!("Took {}", fmt_dur(part2_dur));
+        ran.push((2, answer, part2_dur));
+    }
+
+    ran
+}
+
+// The interactive solvers get the terminal, without a spinner drawing over it
+fn run_solver(parts: Parts, input: &str, kind: InputKind, progress: bool) -> Vec<Ran> {
+    match parts {
+        Parts::Solve((part1, part2)) => run_parts(
+            (
+                part1.map(|part| of_kind(part, kind)),
+                part2.map(|part| of_kind(part, kind)),
+            ),
+            input,
+            progress,
+        ),
+        Parts::Play((part1, part2)) => run_parts(
+            (part1.map(at_terminal), part2.map(at_terminal)),
+            input,
+            false,
+        ),
     }
 }
+
+fn of_kind(part: DayFn, kind: InputKind) -> impl Fn(&str) -> Result<Solved, AocError> {
+    move |input| part(input, kind)
+}
+
+fn at_terminal(part: PlayFn) -> impl Fn(&str) -> Result<Solved, AocError> {
+    move |input| part(input, &mut io::stdin().lock(), &mut io::stdout())
+}
@@ -0,0 +1,82 @@
+use crate::aoc_lib::prelude::*;
+
+fn parse_directions(input: &str) -> Result<Vec<i64>, String> {
+    input
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '(' => Ok(1),
+            ')' => Ok(-1),
+            _ => Err(format!("'{}' is not a direction", c)),
+        })
+        .collect()
+}
+
+// Position of the first direction that takes Santa below the ground floor,
+// counting from 1
+fn basement_entry(directions: &[i64]) -> Option<usize> {
+    directions
+        .iter()
+        .scan(0, |floor, step| {
+            *floor += step;
+            Some(*floor)
+        })
+        .position(|floor| floor < 0)
+        .map(|id| id + 1)
+}
+
+pub struct NotQuiteLisp;
+
+impl Solution for NotQuiteLisp {
+    type Parsed = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(parse_directions(input)?)
+    }
+
+    fn part1(directions: &Self::Parsed) -> Answer {
+        Answer::from(directions.iter().sum::<i64>())
+    }
+
+    fn part2(directions: &Self::Parsed) -> Answer {
+        match basement_entry(directions) {
+            Some(position) => Answer::from(position),
+            None => Answer::from("Santa never goes into the basement"),
+        }
+    }
+}
+
+aoc_day!(2015, 1, solution = NotQuiteLisp);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn final_floor() {
+        let examples = [
+            ("(())", 0),
+            ("()()", 0),
+            ("(((", 3),
+            ("(()(()(", 3),
+            ("))(((((", 3),
+            ("())", -1),
+            ("))(", -1),
+            (")))", -3),
+            (")())())", -3),
+        ];
+
+        for (directions, floor) in examples {
+            let steps = parse_directions(directions).unwrap();
+            assert_eq!(steps.iter().sum::<i64>(), floor, "{}", directions);
+        }
+        assert!(parse_directions("(x)").is_err());
+    }
+
+    #[test]
+    fn basement() {
+        assert_eq!(basement_entry(&parse_directions(")").unwrap()), Some(1));
+        assert_eq!(basement_entry(&parse_directions("()())").unwrap()), Some(5));
+        assert_eq!(basement_entry(&parse_directions("(((").unwrap()), None);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::aoc_lib::prelude::*;
+
+// Sorted, so the two shortest sides come first
+type Present = [u64; 3];
+
+fn parse_presents(input: &str) -> Result<Vec<Present>, String> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|l| {
+            let sides: Vec<u64> = l
+                .split('x')
+                .map(|side| {
+                    side.parse()
+                        .map_err(|_| format!("\"{}\" is not a present", l))
+                })
+                .collect::<Result<_, String>>()?;
+            let mut present: Present = sides
+                .try_into()
+                .map_err(|_| format!("\"{}\" doesn't have three sides", l))?;
+            present.sort_unstable();
+            Ok(present)
+        })
+        .collect()
+}
+
+// All the sides plus the smallest one as slack
+fn paper(&[short, middle, long]: &Present) -> u64 {
+    2 * (short * middle + middle * long + long * short) + short * middle
+}
+
+// The smallest perimeter plus the volume for the bow
+fn ribbon(&[short, middle, long]: &Present) -> u64 {
+    2 * (short + middle) + short * middle * long
+}
+
+pub struct NoMath;
+
+impl Solution for NoMath {
+    type Parsed = Vec<Present>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(parse_presents(input)?)
+    }
+
+    fn part1(presents: &Self::Parsed) -> Answer {
+        Answer::from(presents.iter().map(paper).sum::<u64>())
+    }
+
+    fn part2(presents: &Self::Parsed) -> Answer {
+        Answer::from(presents.iter().map(ribbon).sum::<u64>())
+    }
+}
+
+aoc_day!(2015, 2, solution = NoMath);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT_STRING: &str = include_str!("../../inputs/2015/day02.example.txt");
+
+    #[test]
+    fn wrapping() {
+        let presents = parse_presents(INPUT_STRING).unwrap();
+
+        assert_eq!(presents, vec![[2, 3, 4], [1, 1, 10]]);
+        assert_eq!(presents.iter().map(paper).collect::<Vec<u64>>(), [58, 43]);
+        assert_eq!(presents.iter().map(ribbon).collect::<Vec<u64>>(), [34, 14]);
+        assert!(parse_presents("1x2").is_err());
+    }
+}
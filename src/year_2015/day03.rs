@@ -0,0 +1,73 @@
+use hashbrown::HashSet;
+
+use crate::aoc_lib::prelude::*;
+
+fn parse_moves(input: &str) -> Result<Vec<Point2>, String> {
+    input
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '^' => Ok(Point2::new(0, 1)),
+            'v' => Ok(Point2::new(0, -1)),
+            '<' => Ok(Point2::new(-1, 0)),
+            '>' => Ok(Point2::new(1, 0)),
+            _ => Err(format!("'{}' is not a move", c)),
+        })
+        .collect()
+}
+
+// Houses getting at least a present when the moves are dealt in turns to
+// `deliverers` of them, all starting from the same house
+fn visited_houses(moves: &[Point2], deliverers: usize) -> usize {
+    let mut positions = vec![Point2::default(); deliverers];
+    let mut visited: HashSet<Point2> = HashSet::from([Point2::default()]);
+
+    for (turn, step) in moves.iter().enumerate() {
+        let position = &mut positions[turn % deliverers];
+        *position += *step;
+        visited.insert(*position);
+    }
+
+    visited.len()
+}
+
+pub struct SphericalHouses;
+
+impl Solution for SphericalHouses {
+    type Parsed = Vec<Point2>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(parse_moves(input)?)
+    }
+
+    fn part1(moves: &Self::Parsed) -> Answer {
+        Answer::from(visited_houses(moves, 1))
+    }
+
+    fn part2(moves: &Self::Parsed) -> Answer {
+        Answer::from(visited_houses(moves, 2))
+    }
+}
+
+aoc_day!(2015, 3, solution = SphericalHouses);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deliveries() {
+        let examples = [
+            (">", 2, 2),
+            ("^v", 2, 3),
+            ("^>v<", 4, 3),
+            ("^v^v^v^v^v", 2, 11),
+        ];
+
+        for (moves, alone, together) in examples {
+            let moves_list = parse_moves(moves).unwrap();
+            assert_eq!(visited_houses(&moves_list, 1), alone, "{}", moves);
+            assert_eq!(visited_houses(&moves_list, 2), together, "{}", moves);
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use crate::aoc_lib::md5;
+use crate::aoc_lib::prelude::*;
+
+// Lowest positive number that, appended to the key, gives a digest starting
+// with `zeros` hex zeros
+fn mine(key: &str, zeros: usize) -> u64 {
+    let mut message = key.as_bytes().to_vec();
+    (1..)
+        .find(|number: &u64| {
+            message.truncate(key.len());
+            message.extend_from_slice(number.to_string().as_bytes());
+            md5::leading_zero_digits(&md5::digest(&message)) >= zeros
+        })
+        .unwrap()
+}
+
+pub struct StockingStuffer;
+
+impl Solution for StockingStuffer {
+    type Parsed = String;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        let key = input.trim();
+        if key.is_empty() {
+            return Err(AocError::Parse(String::from("The secret key is empty")));
+        }
+        Ok(key.to_string())
+    }
+
+    fn part1(key: &Self::Parsed) -> Answer {
+        Answer::from(mine(key, 5))
+    }
+
+    fn part2(key: &Self::Parsed) -> Answer {
+        Answer::from(mine(key, 6))
+    }
+}
+
+aoc_day!(2015, 4, solution = StockingStuffer);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mined_numbers() {
+        assert_eq!(mine("abcdef", 5), 609043);
+    }
+}
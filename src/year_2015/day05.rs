@@ -0,0 +1,80 @@
+use itertools::Itertools;
+
+use crate::aoc_lib::prelude::*;
+
+const FORBIDDEN: [&str; 4] = ["ab", "cd", "pq", "xy"];
+
+fn old_rules(text: &str) -> bool {
+    let vowels = text.chars().filter(|c| "aeiou".contains(*c)).count();
+    let double = text.chars().tuple_windows().any(|(a, b)| a == b);
+
+    vowels >= 3 && double && !FORBIDDEN.iter().any(|pair| text.contains(pair))
+}
+
+// A pair showing up twice without overlapping, and a letter repeating with
+// another one in between
+fn new_rules(text: &str) -> bool {
+    let letters = text.as_bytes();
+    let pair_twice =
+        (0..letters.len().saturating_sub(1)).any(|id| text[id + 2..].contains(&text[id..id + 2]));
+    let sandwich = letters.windows(3).any(|window| window[0] == window[2]);
+
+    pair_twice && sandwich
+}
+
+pub struct InternElves;
+
+impl Solution for InternElves {
+    type Parsed = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        input
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(|l| {
+                if l.chars().all(|c| c.is_ascii_lowercase()) {
+                    Ok(l.to_string())
+                } else {
+                    Err(AocError::Parse(format!(
+                        "\"{}\" is not a lowercase string",
+                        l
+                    )))
+                }
+            })
+            .collect()
+    }
+
+    fn part1(strings: &Self::Parsed) -> Answer {
+        Answer::from(strings.iter().filter(|s| old_rules(s)).count())
+    }
+
+    fn part2(strings: &Self::Parsed) -> Answer {
+        Answer::from(strings.iter().filter(|s| new_rules(s)).count())
+    }
+}
+
+aoc_day!(2015, 5, solution = InternElves);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn naughty_or_nice() {
+        assert!(old_rules("ugknbfddgicrmopn"));
+        assert!(old_rules("aaa"));
+        assert!(!old_rules("jchzalrnumimnmhp"));
+        assert!(!old_rules("haegwjzuvuyypxyu"));
+        assert!(!old_rules("dvszwmarrgswjxmb"));
+    }
+
+    #[test]
+    fn better_rules() {
+        assert!(new_rules("qjhvhtzxzqqjkmpb"));
+        assert!(new_rules("xxyxx"));
+        assert!(!new_rules("uurcxstgmygtbstg"));
+        assert!(!new_rules("ieodomkazucvgmuy"));
+        assert!(!new_rules("aaa"));
+    }
+}
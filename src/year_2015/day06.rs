@@ -0,0 +1,151 @@
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, u32};
+use nom::combinator::{all_consuming, map, value};
+use nom::sequence::{separated_pair, tuple};
+use nom::IResult;
+
+use crate::aoc_lib::prelude::*;
+
+const SIDE: usize = 1000;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Action {
+    TurnOn,
+    TurnOff,
+    Toggle,
+}
+
+// Opposite corners of the rectangle, both included
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Instruction {
+    action: Action,
+    from: Position,
+    to: Position,
+}
+
+fn corner(input: &str) -> IResult<&str, Position> {
+    map(separated_pair(u32, char(','), u32), |(x, y)| {
+        (y as usize, x as usize)
+    })(input)
+}
+
+// "turn on 0,0 through 999,999"
+fn instruction(input: &str) -> IResult<&str, Instruction> {
+    map(
+        tuple((
+            alt((
+                value(Action::TurnOn, tag("turn on ")),
+                value(Action::TurnOff, tag("turn off ")),
+                value(Action::Toggle, tag("toggle ")),
+            )),
+            separated_pair(corner, tag(" through "), corner),
+        )),
+        |(action, (from, to))| Instruction { action, from, to },
+    )(input)
+}
+
+fn parse_instructions(input: &str) -> Result<Vec<Instruction>, String> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|l| {
+            let (_, parsed) = all_consuming(instruction)(l)
+                .map_err(|_| format!("\"{}\" is not an instruction", l))?;
+            let inside = |(row, column): Position| row < SIDE && column < SIDE;
+            if !inside(parsed.from) || !inside(parsed.to) {
+                return Err(format!("\"{}\" goes outside the grid", l));
+            }
+            Ok(parsed)
+        })
+        .collect()
+}
+
+// Runs every instruction on a grid of lights, then adds up their values
+fn follow(instructions: &[Instruction], update: impl Fn(Action, u32) -> u32) -> u32 {
+    let mut lights = Grid::filled(SIDE, SIDE, 0u32);
+
+    for step in instructions {
+        let (top, bottom) = (step.from.0.min(step.to.0), step.from.0.max(step.to.0));
+        let (left, right) = (step.from.1.min(step.to.1), step.from.1.max(step.to.1));
+        for row in top..=bottom {
+            for column in left..=right {
+                let light = &mut lights[(row, column)];
+                *light = update(step.action, *light);
+            }
+        }
+    }
+
+    lights.positions().map(|position| lights[position]).sum()
+}
+
+fn switch(action: Action, light: u32) -> u32 {
+    match action {
+        Action::TurnOn => 1,
+        Action::TurnOff => 0,
+        Action::Toggle => 1 - light,
+    }
+}
+
+fn brightness(action: Action, light: u32) -> u32 {
+    match action {
+        Action::TurnOn => light + 1,
+        Action::TurnOff => light.saturating_sub(1),
+        Action::Toggle => light + 2,
+    }
+}
+
+pub struct FireHazard;
+
+impl Solution for FireHazard {
+    type Parsed = Vec<Instruction>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(parse_instructions(input)?)
+    }
+
+    fn part1(instructions: &Self::Parsed) -> Answer {
+        Answer::from(follow(instructions, switch))
+    }
+
+    fn part2(instructions: &Self::Parsed) -> Answer {
+        Answer::from(follow(instructions, brightness))
+    }
+}
+
+aoc_day!(2015, 6, solution = FireHazard);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT_STRING: &str = include_str!("../../inputs/2015/day06.example.txt");
+
+    #[test]
+    fn lit_lights() {
+        let instructions = parse_instructions(INPUT_STRING).unwrap();
+
+        assert_eq!(
+            instructions[1],
+            Instruction {
+                action: Action::Toggle,
+                from: (0, 0),
+                to: (0, 999)
+            }
+        );
+        assert_eq!(follow(&instructions, switch), 1_000_000 - 1000 - 4);
+        assert!(parse_instructions("turn on 0,0 through 1000,3").is_err());
+        assert!(parse_instructions("flip 0,0 through 3,3").is_err());
+    }
+
+    #[test]
+    fn dimmer() {
+        let instructions =
+            parse_instructions("turn on 0,0 through 0,0\ntoggle 0,0 through 999,999").unwrap();
+        assert_eq!(follow(&instructions, brightness), 2_000_001);
+
+        let instructions = parse_instructions("turn off 0,0 through 9,9").unwrap();
+        assert_eq!(follow(&instructions, brightness), 0);
+    }
+}
@@ -0,0 +1,53 @@
+use crate::aoc_lib::circuit::Circuit;
+use crate::aoc_lib::prelude::*;
+
+// The signal on "a" is fed back into "b" and the whole circuit settles again
+fn rewired(circuit: &Circuit) -> Result<u16, String> {
+    let first = circuit.evaluate("a")?;
+    let mut rewired = circuit.clone();
+    rewired.set("b", first);
+    rewired.evaluate("a")
+}
+
+pub struct SomeAssemblyRequired;
+
+impl Solution for SomeAssemblyRequired {
+    type Parsed = Circuit;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(Circuit::parse(input)?)
+    }
+
+    fn part1(circuit: &Self::Parsed) -> Answer {
+        match circuit.evaluate("a") {
+            Ok(signal) => Answer::from(signal),
+            Err(error) => Answer::from(format!("The circuit doesn't settle: {}", error)),
+        }
+    }
+
+    fn part2(circuit: &Self::Parsed) -> Answer {
+        match rewired(circuit) {
+            Ok(signal) => Answer::from(signal),
+            Err(error) => Answer::from(format!("The circuit doesn't settle: {}", error)),
+        }
+    }
+}
+
+aoc_day!(2015, 7, solution = SomeAssemblyRequired);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT_STRING: &str = include_str!("../../inputs/2015/day07.example.txt");
+
+    #[test]
+    fn feedback() {
+        let circuit = Circuit::parse(INPUT_STRING).unwrap();
+
+        // d = 72, b = 114, e = !114
+        assert_eq!(circuit.evaluate("a"), Ok(72 | !114));
+        assert_eq!(rewired(&circuit), Ok(72 | !(72 | !114)));
+        assert!(rewired(&Circuit::parse("b -> c").unwrap()).is_err());
+    }
+}
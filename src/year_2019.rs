@@ -0,0 +1,15 @@
+solved_days![day01, day02, day03, day04, day05, day06, day07, day08, day09,];
+
+use crate::aoc_lib::year::{solved_days, Day, Year};
+
+pub struct Year2019;
+
+impl Year for Year2019 {
+    fn year(&self) -> u16 {
+        2019
+    }
+
+    fn solved(&self) -> &'static [Day] {
+        SOLVED
+    }
+}
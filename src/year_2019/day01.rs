@@ -0,0 +1,57 @@
+use crate::aoc_lib::prelude::*;
+
+fn parse_masses(input: &str) -> Result<Vec<u64>, String> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|l| l.parse().map_err(|_| format!("\"{}\" is not a mass", l)))
+        .collect()
+}
+
+fn fuel(mass: u64) -> u64 {
+    (mass / 3).saturating_sub(2)
+}
+
+// The fuel needs fuel too, until what's added weighs nothing
+fn total_fuel(mass: u64) -> u64 {
+    std::iter::successors(Some(fuel(mass)), |&added| Some(fuel(added)))
+        .take_while(|&added| added > 0)
+        .sum()
+}
+
+pub struct RocketEquation;
+
+impl Solution for RocketEquation {
+    type Parsed = Vec<u64>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(parse_masses(input)?)
+    }
+
+    fn part1(masses: &Self::Parsed) -> Answer {
+        let needed: u64 = masses.iter().map(|&m| fuel(m)).sum();
+        Answer::from(needed)
+    }
+
+    fn part2(masses: &Self::Parsed) -> Answer {
+        let needed: u64 = masses.iter().map(|&m| total_fuel(m)).sum();
+        Answer::from(needed)
+    }
+}
+
+aoc_day!(2019, 1, solution = RocketEquation);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn module_fuel() {
+        let fuels: Vec<u64> = [12, 14, 1969, 100756].into_iter().map(fuel).collect();
+        assert_eq!(fuels, [2, 2, 654, 33583]);
+
+        let totals: Vec<u64> = [14, 1969, 100756].into_iter().map(total_fuel).collect();
+        assert_eq!(totals, [2, 966, 50346]);
+    }
+}
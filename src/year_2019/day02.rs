@@ -0,0 +1,66 @@
+use itertools::Itertools;
+
+use crate::aoc_lib::prelude::*;
+use crate::aoc_lib::vm::intcode::{self, Machine, Stop};
+
+const TARGET: i64 = 19690720;
+
+// Value left at address 0 once the program with the given noun and verb halts
+fn run_with(program: &[i64], noun: i64, verb: i64) -> Result<i64, String> {
+    let mut machine = Machine::new(program);
+    machine.write(1, noun);
+    machine.write(2, verb);
+    match machine.run()? {
+        Stop::Halted => Ok(machine.read(0)),
+        Stop::NeedsInput => Err(String::from("The gravity assist doesn't take input")),
+    }
+}
+
+fn find_inputs(program: &[i64], target: i64) -> Option<(i64, i64)> {
+    (0..100)
+        .cartesian_product(0..100)
+        .find(|&(noun, verb)| run_with(program, noun, verb) == Ok(target))
+}
+
+pub struct ProgramAlarm;
+
+impl Solution for ProgramAlarm {
+    type Parsed = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(intcode::parse_program(input)?)
+    }
+
+    fn part1(program: &Self::Parsed) -> Answer {
+        match run_with(program, 12, 2) {
+            Ok(value) => Answer::from(value),
+            Err(error) => Answer::from(error),
+        }
+    }
+
+    fn part2(program: &Self::Parsed) -> Answer {
+        match find_inputs(program, TARGET) {
+            Some((noun, verb)) => Answer::from(100 * noun + verb),
+            None => Answer::from(format!("No noun and verb give {}", TARGET)),
+        }
+    }
+}
+
+aoc_day!(2019, 2, solution = ProgramAlarm);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn noun_and_verb() {
+        // Adds the values at the noun and verb addresses
+        let program = intcode::parse_program("1,0,0,0,99,10,20,30").unwrap();
+
+        assert_eq!(run_with(&program, 5, 6), Ok(30));
+        assert_eq!(run_with(&program, 6, 7), Ok(50));
+        let (noun, verb) = find_inputs(&program, 40).unwrap();
+        assert_eq!(run_with(&program, noun, verb), Ok(40));
+        assert_eq!(find_inputs(&program, 1000), None);
+    }
+}
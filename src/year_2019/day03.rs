@@ -0,0 +1,131 @@
+use hashbrown::HashMap;
+
+use crate::aoc_lib::prelude::*;
+
+type Wire = Vec<(Point2, u32)>;
+
+fn parse_wire(line: &str) -> Result<Wire, String> {
+    line.trim()
+        .split(',')
+        .map(|segment| {
+            let mut chars = segment.chars();
+            let direction = match chars.next() {
+                Some('U') => Point2::new(0, 1),
+                Some('D') => Point2::new(0, -1),
+                Some('L') => Point2::new(-1, 0),
+                Some('R') => Point2::new(1, 0),
+                _ => return Err(format!("\"{}\" has no direction", segment)),
+            };
+            let length = chars
+                .as_str()
+                .parse()
+                .map_err(|_| format!("\"{}\" has no length", segment))?;
+            Ok((direction, length))
+        })
+        .collect()
+}
+
+// Every point the wire goes through with the steps it took to get there the
+// first time, the port itself left out
+fn trace(wire: &Wire) -> HashMap<Point2, u32> {
+    let mut visited: HashMap<Point2, u32> = HashMap::new();
+    let mut position = Point2::default();
+    let mut steps = 0;
+
+    for &(direction, length) in wire {
+        for _ in 0..length {
+            position += direction;
+            steps += 1;
+            visited.entry(position).or_insert(steps);
+        }
+    }
+
+    visited
+}
+
+// Crossings with the steps both wires took to reach them
+fn crossings(wires: &(Wire, Wire)) -> Vec<(Point2, u32)> {
+    let first = trace(&wires.0);
+    let second = trace(&wires.1);
+
+    first
+        .iter()
+        .filter_map(|(point, steps)| second.get(point).map(|other| (*point, steps + other)))
+        .collect()
+}
+
+pub struct CrossedWires;
+
+impl Solution for CrossedWires {
+    type Parsed = (Wire, Wire);
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        let mut lines = input.lines().filter(|l| !l.trim().is_empty());
+        match (lines.next(), lines.next(), lines.next()) {
+            (Some(first), Some(second), None) => Ok((parse_wire(first)?, parse_wire(second)?)),
+            _ => Err(AocError::Parse(String::from(
+                "There should be exactly two wires",
+            ))),
+        }
+    }
+
+    fn part1(wires: &Self::Parsed) -> Answer {
+        match crossings(wires)
+            .iter()
+            .map(|(point, _)| point.manhattan(&Point2::default()))
+            .min()
+        {
+            Some(distance) => Answer::from(distance),
+            None => Answer::from("The wires never cross"),
+        }
+    }
+
+    fn part2(wires: &Self::Parsed) -> Answer {
+        match crossings(wires).iter().map(|(_, steps)| *steps).min() {
+            Some(steps) => Answer::from(steps),
+            None => Answer::from("The wires never cross"),
+        }
+    }
+}
+
+aoc_day!(2019, 3, solution = CrossedWires);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn closest(input: &str) -> (u64, u32) {
+        let wires = CrossedWires::parse(input).unwrap();
+        let found = crossings(&wires);
+
+        (
+            found
+                .iter()
+                .map(|(p, _)| p.manhattan(&Point2::default()))
+                .min()
+                .unwrap(),
+            found.iter().map(|(_, steps)| *steps).min().unwrap(),
+        )
+    }
+
+    #[test]
+    fn crossing_wires() {
+        assert_eq!(closest("R8,U5,L5,D3\nU7,R6,D4,L4"), (6, 30));
+        assert_eq!(
+            closest(
+                "R75,D30,R83,U83,L12,D49,R71,U7,L72
+                U62,R66,U55,R34,D71,R55,D58,R83"
+            ),
+            (159, 610)
+        );
+        assert_eq!(
+            closest(
+                "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51
+                U98,R91,D20,R16,D67,R40,U7,R15,U6,R7"
+            ),
+            (135, 410)
+        );
+        assert!(CrossedWires::parse("R8,X5\nU7").is_err());
+        assert!(CrossedWires::parse("R8").is_err());
+    }
+}
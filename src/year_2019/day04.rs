@@ -0,0 +1,80 @@
+use itertools::Itertools;
+
+use crate::aoc_lib::prelude::*;
+
+fn digits(password: u32) -> Vec<u8> {
+    password.to_string().bytes().map(|d| d - b'0').collect()
+}
+
+fn never_decreases(digits: &[u8]) -> bool {
+    digits.windows(2).all(|pair| pair[0] <= pair[1])
+}
+
+// Since the digits never decrease, equal digits are always next to each other
+fn runs(digits: &[u8]) -> Vec<usize> {
+    digits
+        .iter()
+        .dedup_with_count()
+        .map(|(count, _)| count)
+        .collect()
+}
+
+fn has_double(password: u32) -> bool {
+    let digits = digits(password);
+    never_decreases(&digits) && runs(&digits).iter().any(|&run| run >= 2)
+}
+
+fn has_exact_double(password: u32) -> bool {
+    let digits = digits(password);
+    never_decreases(&digits) && runs(&digits).contains(&2)
+}
+
+pub struct SecureContainer;
+
+impl Solution for SecureContainer {
+    type Parsed = (u32, u32);
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        let (low, high) = input
+            .trim()
+            .split_once('-')
+            .ok_or_else(|| format!("\"{}\" is not a range", input.trim()))?;
+        let bound = |raw: &str| {
+            raw.parse::<u32>()
+                .map_err(|_| format!("\"{}\" is not a bound", raw))
+        };
+
+        Ok((bound(low)?, bound(high)?))
+    }
+
+    fn part1(&(low, high): &Self::Parsed) -> Answer {
+        let count = (low..=high).filter(|&p| has_double(p)).count();
+        Answer::from(count)
+    }
+
+    fn part2(&(low, high): &Self::Parsed) -> Answer {
+        let count = (low..=high).filter(|&p| has_exact_double(p)).count();
+        Answer::from(count)
+    }
+}
+
+aoc_day!(2019, 4, solution = SecureContainer);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn password_rules() {
+        assert!(has_double(111111));
+        assert!(!has_double(223450));
+        assert!(!has_double(123789));
+
+        assert!(has_exact_double(112233));
+        assert!(!has_exact_double(123444));
+        assert!(has_exact_double(111122));
+
+        assert_eq!(SecureContainer::parse("100-200\n").unwrap(), (100, 200));
+        assert!(SecureContainer::parse("100").is_err());
+    }
+}
@@ -0,0 +1,68 @@
+use crate::aoc_lib::prelude::*;
+use crate::aoc_lib::vm::intcode;
+
+const AIR_CONDITIONER: i64 = 1;
+const THERMAL_RADIATOR: i64 = 5;
+
+// Every check but the last prints 0, the last value is the diagnostic code
+fn diagnostic_code(program: &[i64], system: i64) -> Result<i64, String> {
+    let output = intcode::execute(program, &[system])?;
+    let (code, checks) = output
+        .split_last()
+        .ok_or_else(|| String::from("The test didn't output anything"))?;
+
+    match checks.iter().position(|&check| check != 0) {
+        Some(failed) => Err(format!("Check {} failed with {}", failed, checks[failed])),
+        None => Ok(*code),
+    }
+}
+
+pub struct SunnyWithAsteroids;
+
+impl Solution for SunnyWithAsteroids {
+    type Parsed = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(intcode::parse_program(input)?)
+    }
+
+    fn part1(program: &Self::Parsed) -> Answer {
+        match diagnostic_code(program, AIR_CONDITIONER) {
+            Ok(code) => Answer::from(code),
+            Err(error) => Answer::from(error),
+        }
+    }
+
+    fn part2(program: &Self::Parsed) -> Answer {
+        match diagnostic_code(program, THERMAL_RADIATOR) {
+            Ok(code) => Answer::from(code),
+            Err(error) => Answer::from(error),
+        }
+    }
+}
+
+aoc_day!(2019, 5, solution = SunnyWithAsteroids);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_to_eight() {
+        // 999 below 8, 1000 for 8 and 1001 above
+        let program = intcode::parse_program(
+            "3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,
+            1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,
+            999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99",
+        )
+        .unwrap();
+
+        assert_eq!(diagnostic_code(&program, 7), Ok(999));
+        assert_eq!(diagnostic_code(&program, 8), Ok(1000));
+        assert_eq!(diagnostic_code(&program, 9), Ok(1001));
+
+        // Outputs a failed check and then the code
+        let failing = intcode::parse_program("104,3,104,42,99").unwrap();
+        assert!(diagnostic_code(&failing, 1).is_err());
+    }
+}
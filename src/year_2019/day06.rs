@@ -0,0 +1,116 @@
+use hashbrown::HashMap;
+
+use crate::aoc_lib::prelude::*;
+
+const CENTER: &str = "COM";
+
+// What every object orbits around
+pub type Orbits = HashMap<String, String>;
+
+fn parse_orbits(input: &str) -> Result<Orbits, String> {
+    let mut orbits: Orbits = HashMap::new();
+    for line in input.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let (center, object) = line
+            .split_once(')')
+            .ok_or_else(|| format!("\"{}\" is not an orbit", line))?;
+        if orbits
+            .insert(object.to_string(), center.to_string())
+            .is_some()
+        {
+            return Err(format!("{} orbits around two objects", object));
+        }
+    }
+
+    Ok(orbits)
+}
+
+// From the object all the way down to the center of mass, object excluded
+fn path_to_center<'a>(orbits: &'a Orbits, object: &'a str) -> Vec<&'a str> {
+    std::iter::successors(orbits.get(object), |center| orbits.get(center.as_str()))
+        .map(String::as_str)
+        .take(orbits.len())
+        .collect()
+}
+
+fn orbit_count(orbits: &Orbits) -> usize {
+    let mut depths: HashMap<&str, usize> = HashMap::from([(CENTER, 0)]);
+
+    fn depth<'a>(
+        orbits: &'a Orbits,
+        object: &'a str,
+        depths: &mut HashMap<&'a str, usize>,
+    ) -> usize {
+        if let Some(known) = depths.get(object) {
+            return *known;
+        }
+        let found = orbits
+            .get(object)
+            .map_or(0, |center| depth(orbits, center, depths) + 1);
+        depths.insert(object, found);
+        found
+    }
+
+    orbits
+        .keys()
+        .map(|object| depth(orbits, object, &mut depths))
+        .sum()
+}
+
+// Jumps between the objects we and Santa orbit, through the closest common one
+fn transfers(orbits: &Orbits, from: &str, to: &str) -> Option<usize> {
+    let ours = path_to_center(orbits, from);
+    let theirs = path_to_center(orbits, to);
+    let their_steps: HashMap<&str, usize> =
+        theirs.iter().enumerate().map(|(s, o)| (*o, s)).collect();
+
+    ours.iter()
+        .enumerate()
+        .find_map(|(steps, object)| their_steps.get(object).map(|other| steps + other))
+}
+
+pub struct UniversalOrbitMap;
+
+impl Solution for UniversalOrbitMap {
+    type Parsed = Orbits;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(parse_orbits(input)?)
+    }
+
+    fn part1(orbits: &Self::Parsed) -> Answer {
+        Answer::from(orbit_count(orbits))
+    }
+
+    fn part2(orbits: &Self::Parsed) -> Answer {
+        match transfers(orbits, "YOU", "SAN") {
+            Some(jumps) => Answer::from(jumps),
+            None => Answer::from("Santa can't be reached"),
+        }
+    }
+}
+
+aoc_day!(2019, 6, solution = UniversalOrbitMap);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT_STRING: &str = include_str!("../../inputs/2019/day06.example.txt");
+
+    #[test]
+    fn orbit_checksum() {
+        let orbits = parse_orbits(INPUT_STRING).unwrap();
+
+        assert_eq!(orbit_count(&orbits), 42);
+        assert_eq!(path_to_center(&orbits, "D"), ["C", "B", "COM"]);
+        assert!(parse_orbits("A)B\nC)B").is_err());
+    }
+
+    #[test]
+    fn orbital_transfers() {
+        let input = format!("{}\nK)YOU\nI)SAN", INPUT_STRING);
+        let orbits = parse_orbits(&input).unwrap();
+
+        assert_eq!(transfers(&orbits, "YOU", "SAN"), Some(4));
+    }
+}
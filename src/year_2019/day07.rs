@@ -0,0 +1,108 @@
+use itertools::Itertools;
+
+use crate::aoc_lib::prelude::*;
+use crate::aoc_lib::vm::intcode::{self, Machine};
+
+const AMPLIFIERS: i64 = 5;
+
+// One pass through the amplifiers, each one started with its phase and then
+// the previous signal
+fn chain_signal(program: &[i64], phases: &[i64]) -> Result<i64, String> {
+    phases.iter().try_fold(0, |signal, &phase| {
+        intcode::execute(program, &[phase, signal])?
+            .last()
+            .copied()
+            .ok_or_else(|| format!("The amplifier with phase {} said nothing", phase))
+    })
+}
+
+// The last amplifier feeds the first one, every machine keeps running from
+// where it stopped until the last one halts
+fn feedback_signal(program: &[i64], phases: &[i64]) -> Result<i64, String> {
+    let mut amplifiers: Vec<Machine> = phases
+        .iter()
+        .map(|&phase| Machine::with_input(program, &[phase]))
+        .collect();
+    let mut signal = 0;
+
+    loop {
+        for amplifier in amplifiers.iter_mut() {
+            amplifier.push_input(signal);
+            match amplifier.next_output()? {
+                Some(value) => signal = value,
+                None if amplifier.is_halted() => return Ok(signal),
+                None => return Err(String::from("An amplifier is waiting for more input")),
+            }
+        }
+    }
+}
+
+fn best_signal(
+    program: &[i64],
+    phases: std::ops::Range<i64>,
+    run: fn(&[i64], &[i64]) -> Result<i64, String>,
+) -> Result<i64, String> {
+    phases
+        .permutations(AMPLIFIERS as usize)
+        .map(|order| run(program, &order))
+        .fold_ok(i64::MIN, i64::max)
+}
+
+pub struct AmplificationCircuit;
+
+impl Solution for AmplificationCircuit {
+    type Parsed = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(intcode::parse_program(input)?)
+    }
+
+    fn part1(program: &Self::Parsed) -> Answer {
+        match best_signal(program, 0..AMPLIFIERS, chain_signal) {
+            Ok(signal) => Answer::from(signal),
+            Err(error) => Answer::from(error),
+        }
+    }
+
+    fn part2(program: &Self::Parsed) -> Answer {
+        match best_signal(program, AMPLIFIERS..2 * AMPLIFIERS, feedback_signal) {
+            Ok(signal) => Answer::from(signal),
+            Err(error) => Answer::from(error),
+        }
+    }
+}
+
+aoc_day!(2019, 7, solution = AmplificationCircuit);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amplifier_chain() {
+        let program =
+            intcode::parse_program("3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0").unwrap();
+
+        assert_eq!(chain_signal(&program, &[4, 3, 2, 1, 0]), Ok(43210));
+        assert_eq!(best_signal(&program, 0..5, chain_signal), Ok(43210));
+
+        let program = intcode::parse_program(
+            "3,31,3,32,1002,32,10,32,1001,31,-2,31,1007,31,0,33,
+            1002,33,7,33,1,33,31,31,1,32,31,31,4,31,99,0,0,0",
+        )
+        .unwrap();
+        assert_eq!(best_signal(&program, 0..5, chain_signal), Ok(65210));
+    }
+
+    #[test]
+    fn amplifier_feedback() {
+        let program = intcode::parse_program(
+            "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,
+            27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5",
+        )
+        .unwrap();
+
+        assert_eq!(feedback_signal(&program, &[9, 8, 7, 6, 5]), Ok(139629729));
+        assert_eq!(best_signal(&program, 5..10, feedback_signal), Ok(139629729));
+    }
+}
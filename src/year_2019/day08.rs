@@ -0,0 +1,103 @@
+use crate::aoc_lib::ocr;
+use crate::aoc_lib::prelude::*;
+
+const WIDTH: usize = 25;
+const HEIGHT: usize = 6;
+
+fn parse_layers(input: &str, size: usize) -> Result<Vec<Vec<u8>>, String> {
+    let pixels: Vec<u8> = input
+        .trim()
+        .bytes()
+        .map(|p| match p {
+            b'0'..=b'2' => Ok(p - b'0'),
+            _ => Err(format!("Unknown pixel colour '{}'", p as char)),
+        })
+        .collect::<Result<Vec<u8>, String>>()?;
+    if pixels.is_empty() || !pixels.len().is_multiple_of(size) {
+        return Err(format!("{} pixels don't make whole layers", pixels.len()));
+    }
+
+    Ok(pixels.chunks(size).map(<[u8]>::to_vec).collect())
+}
+
+fn count(layer: &[u8], colour: u8) -> usize {
+    layer.iter().filter(|&&p| p == colour).count()
+}
+
+fn checksum(layers: &[Vec<u8>]) -> usize {
+    let layer = layers.iter().min_by_key(|layer| count(layer, 0)).unwrap();
+    count(layer, 1) * count(layer, 2)
+}
+
+// The first layer that isn't transparent (2) wins, white (1) is lit
+fn flatten(layers: &[Vec<u8>]) -> Vec<bool> {
+    (0..layers[0].len())
+        .map(|id| {
+            layers
+                .iter()
+                .map(|layer| layer[id])
+                .find(|&p| p != 2)
+                .is_some_and(|p| p == 1)
+        })
+        .collect()
+}
+
+pub struct SpaceImageFormat;
+
+impl Solution for SpaceImageFormat {
+    type Parsed = Vec<Vec<u8>>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(parse_layers(input, WIDTH * HEIGHT)?)
+    }
+
+    fn part1(layers: &Self::Parsed) -> Answer {
+        Answer::from(checksum(layers))
+    }
+
+    fn part2(layers: &Self::Parsed) -> Answer {
+        match ocr::decode(&flatten(layers), WIDTH) {
+            Ok(message) => Answer::from(message),
+            Err(error) => Answer::from(error),
+        }
+    }
+}
+
+aoc_day!(2019, 8, solution = SpaceImageFormat);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_layers() {
+        let layers = parse_layers("012212000112", 6).unwrap();
+        assert_eq!(layers.len(), 2);
+        assert_eq!(checksum(&layers), 2 * 3);
+        assert!(parse_layers("1234567", 6).is_err());
+        assert!(parse_layers("12345a", 6).is_err());
+
+        let layers = parse_layers("0222112222120000", 4).unwrap();
+        assert_eq!(flatten(&layers), [false, true, true, false]);
+    }
+
+    #[test]
+    fn image_letters() {
+        // "HI" drawn on the bottom layer, hidden behind a transparent one
+        let screen = "#..#..###
+                      #..#...#.
+                      ####...#.
+                      #..#...#.
+                      #..#...#.
+                      #..#..###";
+        let bottom: String = screen
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| if c == '#' { '1' } else { '0' })
+            .collect();
+        let input = format!("{}{}", "2".repeat(bottom.len()), bottom);
+        let layers = parse_layers(&input, 9 * 6).unwrap();
+
+        assert_eq!(ocr::decode(&flatten(&layers), 9), Ok(String::from("HI")));
+    }
+}
@@ -0,0 +1,53 @@
+use crate::aoc_lib::prelude::*;
+use crate::aoc_lib::vm::intcode;
+
+const TEST_MODE: i64 = 1;
+const SENSOR_BOOST: i64 = 2;
+
+// In test mode anything but a single value is a list of broken opcodes
+fn boost(program: &[i64], mode: i64) -> Result<i64, String> {
+    match intcode::execute(program, &[mode])?[..] {
+        [keycode] => Ok(keycode),
+        ref broken => Err(format!("Malfunctioning opcodes: {:?}", broken)),
+    }
+}
+
+pub struct SensorBoost;
+
+impl Solution for SensorBoost {
+    type Parsed = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(intcode::parse_program(input)?)
+    }
+
+    fn part1(program: &Self::Parsed) -> Answer {
+        match boost(program, TEST_MODE) {
+            Ok(keycode) => Answer::from(keycode),
+            Err(error) => Answer::from(error),
+        }
+    }
+
+    fn part2(program: &Self::Parsed) -> Answer {
+        match boost(program, SENSOR_BOOST) {
+            Ok(coordinates) => Answer::from(coordinates),
+            Err(error) => Answer::from(error),
+        }
+    }
+}
+
+aoc_day!(2019, 9, solution = SensorBoost);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boost_modes() {
+        // Outputs the input times the relative base it moved to
+        let program = intcode::parse_program("109,20,203,0,21202,0,3,1,204,1,99").unwrap();
+
+        assert_eq!(boost(&program, 5), Ok(15));
+        assert!(boost(&intcode::parse_program("104,1,104,2,99").unwrap(), 1).is_err());
+    }
+}
@@ -0,0 +1,15 @@
+solved_days![day01, day02, day03, day04, day05, day06, day07, day08, day09, day10,];
+
+use crate::aoc_lib::year::{solved_days, Day, Year};
+
+pub struct Year2020;
+
+impl Year for Year2020 {
+    fn year(&self) -> u16 {
+        2020
+    }
+
+    fn solved(&self) -> &'static [Day] {
+        SOLVED
+    }
+}
@@ -0,0 +1,90 @@
+use hashbrown::HashSet;
+
+use crate::aoc_lib::prelude::*;
+
+const TARGET: u64 = 2020;
+
+fn parse_expenses(input: &str) -> Result<Vec<u64>, String> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|l| {
+            l.parse()
+                .map_err(|_| format!("\"{}\" is not an expense", l))
+        })
+        .collect()
+}
+
+// Two entries adding up to `target`, found by looking up the complement
+fn find_pair(expenses: &[u64], target: u64) -> Option<(u64, u64)> {
+    let mut seen: HashSet<u64> = HashSet::new();
+    for &expense in expenses {
+        if let Some(complement) = target.checked_sub(expense) {
+            if seen.contains(&complement) {
+                return Some((complement, expense));
+            }
+        }
+        seen.insert(expense);
+    }
+
+    None
+}
+
+fn find_triple(expenses: &[u64], target: u64) -> Option<(u64, u64, u64)> {
+    expenses.iter().enumerate().find_map(|(id, &first)| {
+        let rest = target.checked_sub(first)?;
+        find_pair(&expenses[id + 1..], rest).map(|(second, third)| (first, second, third))
+    })
+}
+
+pub struct ReportRepair;
+
+impl Solution for ReportRepair {
+    type Parsed = Vec<u64>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(parse_expenses(input)?)
+    }
+
+    fn part1(expenses: &Self::Parsed) -> Answer {
+        match find_pair(expenses, TARGET) {
+            Some((first, second)) => Answer::from(first * second),
+            None => Answer::from(format!("No two entries add up to {}", TARGET)),
+        }
+    }
+
+    fn part2(expenses: &Self::Parsed) -> Answer {
+        match find_triple(expenses, TARGET) {
+            Some((first, second, third)) => {
+                Answer::from(first * second * third)
+            }
+            None => Answer::from(format!("No three entries add up to {}", TARGET)),
+        }
+    }
+}
+
+aoc_day!(2020, 1, solution = ReportRepair);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT_STRING: &str = include_str!("../../inputs/2020/day01.example.txt");
+
+    #[test]
+    fn expense_pair() {
+        let expenses = parse_expenses(INPUT_STRING).unwrap();
+
+        assert_eq!(find_pair(&expenses, TARGET), Some((1721, 299)));
+        assert_eq!(find_pair(&expenses, 1), None);
+    }
+
+    #[test]
+    fn expense_triple() {
+        let expenses = parse_expenses(INPUT_STRING).unwrap();
+        let (first, second, third) = find_triple(&expenses, TARGET).unwrap();
+
+        assert_eq!(first * second * third, 241861950);
+    }
+}
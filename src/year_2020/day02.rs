@@ -0,0 +1,118 @@
+use nom::bytes::complete::tag;
+use nom::character::complete::{anychar, char, space0};
+use nom::combinator::{all_consuming, map, rest};
+use nom::sequence::{preceded, separated_pair, tuple};
+use nom::IResult;
+
+use crate::aoc_lib::prelude::*;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Entry {
+    low: usize,
+    high: usize,
+    letter: char,
+    password: String,
+}
+
+impl Entry {
+    // The letter shows up between `low` and `high` times
+    fn valid_for_sled_rental(&self) -> bool {
+        let count = self.password.chars().filter(|c| *c == self.letter).count();
+        (self.low..=self.high).contains(&count)
+    }
+
+    // The letter is at exactly one of the two positions, counting from 1
+    fn valid_for_toboggan(&self) -> bool {
+        let at = |position: usize| self.password.chars().nth(position - 1) == Some(self.letter);
+        at(self.low) != at(self.high)
+    }
+}
+
+// "1-3 a: abcde"
+fn entry(input: &str) -> IResult<&str, Entry> {
+    map(
+        tuple((
+            preceded(
+                space0,
+                separated_pair(jazz_parser::usize, char('-'), jazz_parser::usize),
+            ),
+            preceded(char(' '), anychar),
+            preceded(tag(": "), rest),
+        )),
+        |((low, high), letter, password): ((usize, usize), char, &str)| Entry {
+            low,
+            high,
+            letter,
+            password: password.to_string(),
+        },
+    )(input)
+}
+
+fn parse_entries(input: &str) -> Result<Vec<Entry>, String> {
+    input
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| {
+            all_consuming(entry)(l.trim_end())
+                .map(|(_, entry)| entry)
+                .map_err(|_| format!("\"{}\" is not a password entry", l.trim()))
+        })
+        .collect::<Result<Vec<Entry>, String>>()
+        .and_then(
+            |entries| match entries.iter().find(|e| e.low == 0 || e.low > e.high) {
+                Some(broken) => Err(format!("Bad policy for \"{}\"", broken.password)),
+                None => Ok(entries),
+            },
+        )
+}
+
+pub struct PasswordPhilosophy;
+
+impl Solution for PasswordPhilosophy {
+    type Parsed = Vec<Entry>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(parse_entries(input)?)
+    }
+
+    fn part1(entries: &Self::Parsed) -> Answer {
+        let valid = entries.iter().filter(|e| e.valid_for_sled_rental()).count();
+        Answer::from(valid)
+    }
+
+    fn part2(entries: &Self::Parsed) -> Answer {
+        let valid = entries.iter().filter(|e| e.valid_for_toboggan()).count();
+        Answer::from(valid)
+    }
+}
+
+aoc_day!(2020, 2, solution = PasswordPhilosophy);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT_STRING: &str = include_str!("../../inputs/2020/day02.example.txt");
+
+    #[test]
+    fn policies() {
+        let entries = parse_entries(INPUT_STRING).unwrap();
+
+        assert_eq!(
+            entries[0],
+            Entry {
+                low: 1,
+                high: 3,
+                letter: 'a',
+                password: String::from("abcde")
+            }
+        );
+        let sled: Vec<bool> = entries.iter().map(Entry::valid_for_sled_rental).collect();
+        assert_eq!(sled, [true, false, true]);
+        let toboggan: Vec<bool> = entries.iter().map(Entry::valid_for_toboggan).collect();
+        assert_eq!(toboggan, [true, false, false]);
+
+        assert!(parse_entries("3-1 a: aaa").is_err());
+        assert!(parse_entries("1-3 a abcde").is_err());
+    }
+}
@@ -0,0 +1,61 @@
+use crate::aoc_lib::prelude::*;
+
+// The pattern repeats to the right forever
+fn trees_on_slope(trees: &Grid<bool>, (right, down): (usize, usize)) -> usize {
+    (0..trees.height())
+        .step_by(down)
+        .enumerate()
+        .filter(|(step, row)| trees[(*row, (step * right) % trees.width())])
+        .count()
+}
+
+fn parse_map(input: &str) -> Result<Grid<bool>, String> {
+    Grid::parse(input, |position, c| match c {
+        '#' => Ok(true),
+        '.' => Ok(false),
+        _ => Err(format!("Unknown square '{}' at {:?}", c, position)),
+    })
+}
+
+pub struct TobogganTrajectory;
+
+impl Solution for TobogganTrajectory {
+    type Parsed = Grid<bool>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(parse_map(input)?)
+    }
+
+    fn part1(trees: &Self::Parsed) -> Answer {
+        Answer::from(trees_on_slope(trees, (3, 1)))
+    }
+
+    fn part2(trees: &Self::Parsed) -> Answer {
+        let product: usize = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)]
+            .into_iter()
+            .map(|slope| trees_on_slope(trees, slope))
+            .product();
+        Answer::from(product)
+    }
+}
+
+aoc_day!(2020, 3, solution = TobogganTrajectory);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT_STRING: &str = include_str!("../../inputs/2020/day03.example.txt");
+
+    #[test]
+    fn slopes() {
+        let trees = parse_map(INPUT_STRING).unwrap();
+        let counts: Vec<usize> = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)]
+            .into_iter()
+            .map(|slope| trees_on_slope(&trees, slope))
+            .collect();
+
+        assert_eq!(counts, [2, 7, 3, 4, 2]);
+        assert!(parse_map("..#\n.x.").is_err());
+    }
+}
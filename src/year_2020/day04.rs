@@ -0,0 +1,149 @@
+use hashbrown::HashMap;
+
+use crate::aoc_lib::prelude::*;
+
+// Every field but the country ID has to be there
+const REQUIRED_FIELDS: [&str; 7] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
+const EYE_COLOURS: [&str; 7] = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+
+pub type Passport = HashMap<String, String>;
+
+// Passports are separated by blank lines, their fields by spaces or newlines
+fn parse_passports(input: &str) -> Result<Vec<Passport>, String> {
+    let mut passports: Vec<Passport> = Vec::new();
+    let mut current: Passport = HashMap::new();
+
+    for line in input.lines().map(str::trim) {
+        if line.is_empty() {
+            if !current.is_empty() {
+                passports.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        for field in line.split_whitespace() {
+            let (key, value) = field
+                .split_once(':')
+                .ok_or_else(|| format!("\"{}\" is not a key:value field", field))?;
+            current.insert(key.to_string(), value.to_string());
+        }
+    }
+    if !current.is_empty() {
+        passports.push(current);
+    }
+
+    Ok(passports)
+}
+
+fn has_required_fields(passport: &Passport) -> bool {
+    REQUIRED_FIELDS
+        .iter()
+        .all(|field| passport.contains_key(*field))
+}
+
+fn year_between(value: &str, low: u32, high: u32) -> bool {
+    value.len() == 4 && value.parse().is_ok_and(|year| (low..=high).contains(&year))
+}
+
+fn valid_field(key: &str, value: &str) -> bool {
+    match key {
+        "byr" => year_between(value, 1920, 2002),
+        "iyr" => year_between(value, 2010, 2020),
+        "eyr" => year_between(value, 2020, 2030),
+        "hgt" => {
+            if let Some(cm) = value.strip_suffix("cm") {
+                cm.parse().is_ok_and(|h: u32| (150..=193).contains(&h))
+            } else if let Some(inches) = value.strip_suffix("in") {
+                inches.parse().is_ok_and(|h: u32| (59..=76).contains(&h))
+            } else {
+                false
+            }
+        }
+        "hcl" => value.strip_prefix('#').is_some_and(|hex| {
+            hex.len() == 6 && hex.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'))
+        }),
+        "ecl" => EYE_COLOURS.contains(&value),
+        "pid" => value.len() == 9 && value.chars().all(|c| c.is_ascii_digit()),
+        _ => true,
+    }
+}
+
+fn is_valid(passport: &Passport) -> bool {
+    has_required_fields(passport) && passport.iter().all(|(key, value)| valid_field(key, value))
+}
+
+pub struct PassportProcessing;
+
+impl Solution for PassportProcessing {
+    type Parsed = Vec<Passport>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(parse_passports(input)?)
+    }
+
+    fn part1(passports: &Self::Parsed) -> Answer {
+        let complete = passports.iter().filter(|p| has_required_fields(p)).count();
+        Answer::from(complete)
+    }
+
+    fn part2(passports: &Self::Parsed) -> Answer {
+        let valid = passports.iter().filter(|p| is_valid(p)).count();
+        Answer::from(valid)
+    }
+}
+
+aoc_day!(2020, 4, solution = PassportProcessing);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn required_fields() {
+        let input_string = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+        byr:1937 iyr:2017 cid:147 hgt:183cm
+
+        iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
+        hcl:#cfa07d byr:1929
+
+        hcl:#ae17e1 iyr:2013
+        eyr:2024
+        ecl:brn pid:760753108 byr:1931
+        hgt:179cm
+
+        hcl:#cfa07d eyr:2025 pid:166559648
+        iyr:2011 ecl:brn hgt:59in";
+
+        let passports = parse_passports(input_string).unwrap();
+        let complete: Vec<bool> = passports.iter().map(has_required_fields).collect();
+
+        assert_eq!(complete, [true, false, true, false]);
+        assert!(parse_passports("ecl gry").is_err());
+    }
+
+    #[test]
+    fn field_validation() {
+        let invalid = "eyr:1972 cid:100
+        hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926
+
+        iyr:2019
+        hcl:#602927 eyr:1967 hgt:170cm
+        ecl:grn pid:012533040 byr:1946";
+        let valid = "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980
+        hcl:#623a2f
+
+        eyr:2029 ecl:blu cid:129 byr:1989
+        iyr:2014 pid:896056539 hcl:#a97842 hgt:165cm";
+
+        assert!(parse_passports(invalid)
+            .unwrap()
+            .iter()
+            .all(|p| !is_valid(p)));
+        assert!(parse_passports(valid).unwrap().iter().all(is_valid));
+
+        assert!(valid_field("byr", "2002") && !valid_field("byr", "2003"));
+        assert!(valid_field("hgt", "60in") && !valid_field("hgt", "190in"));
+        assert!(valid_field("hcl", "#123abc") && !valid_field("hcl", "123abc"));
+        assert!(valid_field("pid", "000000001") && !valid_field("pid", "0123456789"));
+    }
+}
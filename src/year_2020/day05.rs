@@ -0,0 +1,72 @@
+use crate::aoc_lib::prelude::*;
+
+// The row and column letters are just the bits of the seat ID
+fn seat_id(pass: &str) -> Result<u16, String> {
+    if pass.len() != 10 {
+        return Err(format!("\"{}\" is not a boarding pass", pass));
+    }
+
+    pass.chars().try_fold(0u16, |id, c| match c {
+        'B' | 'R' => Ok(id << 1 | 1),
+        'F' | 'L' => Ok(id << 1),
+        _ => Err(format!("Unknown seat letter '{}' in \"{}\"", c, pass)),
+    })
+}
+
+// Ours is the only one missing with both neighbours taken
+fn find_missing_seat(sorted_ids: &[u16]) -> Option<u16> {
+    sorted_ids
+        .windows(2)
+        .find(|pair| pair[1] == pair[0] + 2)
+        .map(|pair| pair[0] + 1)
+}
+
+pub struct BinaryBoarding;
+
+impl Solution for BinaryBoarding {
+    // Sorted seat IDs
+    type Parsed = Vec<u16>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        let mut ids = input
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(seat_id)
+            .collect::<Result<Vec<u16>, String>>()?;
+        ids.sort_unstable();
+
+        Ok(ids)
+    }
+
+    fn part1(ids: &Self::Parsed) -> Answer {
+        Answer::from(ids.last().copied().unwrap_or(0))
+    }
+
+    fn part2(ids: &Self::Parsed) -> Answer {
+        match find_missing_seat(ids) {
+            Some(id) => Answer::from(id),
+            None => Answer::from("There's no free seat between two taken ones"),
+        }
+    }
+}
+
+aoc_day!(2020, 5, solution = BinaryBoarding);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seat_ids() {
+        assert_eq!(seat_id("FBFBBFFRLR"), Ok(357));
+        assert_eq!(seat_id("BFFFBBFRRR"), Ok(567));
+        assert_eq!(seat_id("FFFBBBFRRR"), Ok(119));
+        assert_eq!(seat_id("BBFFBBFRLL"), Ok(820));
+        assert!(seat_id("BBFFBBFRLX").is_err());
+        assert!(seat_id("BBFF").is_err());
+
+        assert_eq!(find_missing_seat(&[3, 4, 5, 7, 8]), Some(6));
+        assert_eq!(find_missing_seat(&[3, 4, 5]), None);
+    }
+}
@@ -0,0 +1,91 @@
+use crate::aoc_lib::prelude::*;
+
+// One bit per question answered "yes", for each person in each group
+fn parse_groups(input: &str) -> Result<Vec<Vec<u32>>, String> {
+    let mut groups: Vec<Vec<u32>> = Vec::new();
+    let mut current: Vec<u32> = Vec::new();
+
+    for line in input.lines().map(str::trim) {
+        if line.is_empty() {
+            if !current.is_empty() {
+                groups.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        let answers = line.bytes().try_fold(0u32, |answers, question| {
+            if question.is_ascii_lowercase() {
+                Ok(answers | 1 << (question - b'a'))
+            } else {
+                Err(format!("Unknown question '{}'", question as char))
+            }
+        })?;
+        current.push(answers);
+    }
+    if !current.is_empty() {
+        groups.push(current);
+    }
+
+    Ok(groups)
+}
+
+fn anyone_sum(groups: &[Vec<u32>]) -> u32 {
+    groups
+        .iter()
+        .map(|group| {
+            group
+                .iter()
+                .fold(0, |all, person| all | person)
+                .count_ones()
+        })
+        .sum()
+}
+
+fn everyone_sum(groups: &[Vec<u32>]) -> u32 {
+    groups
+        .iter()
+        .map(|group| {
+            group
+                .iter()
+                .fold(u32::MAX, |all, person| all & person)
+                .count_ones()
+        })
+        .sum()
+}
+
+pub struct CustomCustoms;
+
+impl Solution for CustomCustoms {
+    type Parsed = Vec<Vec<u32>>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(parse_groups(input)?)
+    }
+
+    fn part1(groups: &Self::Parsed) -> Answer {
+        Answer::from(anyone_sum(groups))
+    }
+
+    fn part2(groups: &Self::Parsed) -> Answer {
+        Answer::from(everyone_sum(groups))
+    }
+}
+
+aoc_day!(2020, 6, solution = CustomCustoms);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT_STRING: &str = include_str!("../../inputs/2020/day06.example.txt");
+
+    #[test]
+    fn answer_counts() {
+        let groups = parse_groups(INPUT_STRING).unwrap();
+
+        assert_eq!(groups.len(), 5);
+        assert_eq!(anyone_sum(&groups), 11);
+        assert_eq!(everyone_sum(&groups), 6);
+        assert!(parse_groups("aB").is_err());
+    }
+}
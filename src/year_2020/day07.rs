@@ -0,0 +1,163 @@
+use hashbrown::{HashMap, HashSet};
+use nom::branch::alt;
+use nom::bytes::complete::{tag, take_until};
+use nom::character::complete::char;
+use nom::combinator::{all_consuming, map, opt};
+use nom::multi::separated_list1;
+use nom::sequence::{separated_pair, terminated};
+use nom::IResult;
+
+use crate::aoc_lib::prelude::*;
+
+const OUR_BAG: &str = "shiny gold";
+
+// Colour of each bag and what goes straight inside it
+pub type Rules = HashMap<String, Vec<(u64, String)>>;
+
+// "2 muted yellow bags"
+fn contents(input: &str) -> IResult<&str, (u64, &str)> {
+    separated_pair(
+        jazz_parser::u64,
+        char(' '),
+        terminated(take_until(" bag"), terminated(tag(" bag"), opt(char('s')))),
+    )(input)
+}
+
+type RawRule<'a> = (&'a str, Vec<(u64, &'a str)>);
+
+// "light red bags contain 1 bright white bag, 2 muted yellow bags."
+fn rule(input: &str) -> IResult<&str, RawRule<'_>> {
+    terminated(
+        separated_pair(
+            take_until(" bags contain "),
+            tag(" bags contain "),
+            alt((
+                map(tag("no other bags"), |_| Vec::new()),
+                separated_list1(tag(", "), contents),
+            )),
+        ),
+        char('.'),
+    )(input)
+}
+
+fn parse_rules(input: &str) -> Result<Rules, String> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|l| {
+            let (_, (colour, inside)) =
+                all_consuming(rule)(l).map_err(|_| format!("\"{}\" is not a bag rule", l))?;
+            Ok((
+                colour.to_string(),
+                inside
+                    .into_iter()
+                    .map(|(count, inner)| (count, inner.to_string()))
+                    .collect(),
+            ))
+        })
+        .collect()
+}
+
+// Colours that end up holding `target`, however deep down
+fn containers(rules: &Rules, target: &str) -> usize {
+    let mut held_by: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (outer, inside) in rules.iter() {
+        for (_, inner) in inside {
+            held_by
+                .entry(inner.as_str())
+                .or_default()
+                .push(outer.as_str());
+        }
+    }
+
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut stack = vec![target];
+    while let Some(colour) = stack.pop() {
+        for outer in held_by.get(colour).into_iter().flatten() {
+            if seen.insert(outer) {
+                stack.push(outer);
+            }
+        }
+    }
+
+    seen.len()
+}
+
+// Bags inside `colour`, counted once per level with the results shared
+fn bags_inside<'a>(rules: &'a Rules, colour: &'a str, known: &mut HashMap<&'a str, u64>) -> u64 {
+    if let Some(count) = known.get(colour) {
+        return *count;
+    }
+
+    let count = rules
+        .get(colour)
+        .into_iter()
+        .flatten()
+        .map(|(count, inner)| count * (1 + bags_inside(rules, inner, known)))
+        .sum();
+    known.insert(colour, count);
+
+    count
+}
+
+pub struct HandyHaversacks;
+
+impl Solution for HandyHaversacks {
+    type Parsed = Rules;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(parse_rules(input)?)
+    }
+
+    fn part1(rules: &Self::Parsed) -> Answer {
+        Answer::from(containers(rules, OUR_BAG))
+    }
+
+    fn part2(rules: &Self::Parsed) -> Answer {
+        Answer::from(bags_inside(rules, OUR_BAG, &mut HashMap::new()))
+    }
+}
+
+aoc_day!(2020, 7, solution = HandyHaversacks);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT_STRING: &str = include_str!("../../inputs/2020/day07.example.txt");
+
+    #[test]
+    fn bag_rules() {
+        let rules = parse_rules(INPUT_STRING).unwrap();
+
+        assert_eq!(rules.len(), 9);
+        assert_eq!(
+            rules["muted yellow"],
+            vec![
+                (2, String::from("shiny gold")),
+                (9, String::from("faded blue"))
+            ]
+        );
+        assert!(rules["faded blue"].is_empty());
+        assert!(parse_rules("red bags contain some bags.").is_err());
+    }
+
+    #[test]
+    fn nested_bags() {
+        let rules = parse_rules(INPUT_STRING).unwrap();
+
+        assert_eq!(containers(&rules, OUR_BAG), 4);
+        assert_eq!(bags_inside(&rules, OUR_BAG, &mut HashMap::new()), 32);
+
+        let deep = "shiny gold bags contain 2 dark red bags.
+        dark red bags contain 2 dark orange bags.
+        dark orange bags contain 2 dark yellow bags.
+        dark yellow bags contain 2 dark green bags.
+        dark green bags contain 2 dark blue bags.
+        dark blue bags contain 2 dark violet bags.
+        dark violet bags contain no other bags.";
+        let rules = parse_rules(deep).unwrap();
+        assert_eq!(bags_inside(&rules, OUR_BAG, &mut HashMap::new()), 126);
+    }
+}
@@ -0,0 +1,48 @@
+use crate::aoc_lib::prelude::*;
+use crate::aoc_lib::vm::handheld::{self, Console, Instruction, Termination};
+
+pub struct HandheldHalting;
+
+impl Solution for HandheldHalting {
+    type Parsed = Vec<Instruction>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(handheld::parse_program(input)?)
+    }
+
+    fn part1(program: &Self::Parsed) -> Answer {
+        match Console::new(program.clone()).run() {
+            Termination::Looped(accumulator) => {
+                Answer::from(accumulator)
+            }
+            Termination::Halted(accumulator) => {
+                Answer::from(format!("The boot code doesn't loop, accumulator: {}", accumulator))
+            }
+        }
+    }
+
+    fn part2(program: &Self::Parsed) -> Answer {
+        match handheld::repair(program) {
+            Some(accumulator) => Answer::from(accumulator),
+            None => Answer::from("No single flip fixes the boot code"),
+        }
+    }
+}
+
+aoc_day!(2020, 8, solution = HandheldHalting);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aoc_lib::solution::answers;
+
+    static INPUT_STRING: &str = include_str!("../../inputs/2020/day08.example.txt");
+
+    #[test]
+    fn boot_code() {
+        assert_eq!(
+            answers::<HandheldHalting>(INPUT_STRING).unwrap(),
+            (Answer::Int(5), Answer::Int(8))
+        );
+    }
+}
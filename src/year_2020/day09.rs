@@ -0,0 +1,120 @@
+use hashbrown::HashSet;
+
+use crate::aoc_lib::prelude::*;
+
+const PREAMBLE: usize = 25;
+
+fn parse_numbers(input: &str) -> Result<Vec<u64>, String> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|l| l.parse().map_err(|_| format!("\"{}\" is not a number", l)))
+        .collect()
+}
+
+// The first number that isn't the sum of two different ones among the
+// `preamble` before it
+fn first_invalid(numbers: &[u64], preamble: usize) -> Option<u64> {
+    numbers.windows(preamble + 1).find_map(|window| {
+        let (previous, target) = window.split_at(preamble);
+        let target = target[0];
+        let previous: HashSet<u64> = previous.iter().copied().collect();
+        let valid = previous.iter().any(|&first| {
+            target
+                .checked_sub(first)
+                .is_some_and(|second| second != first && previous.contains(&second))
+        });
+
+        (!valid).then_some(target)
+    })
+}
+
+// A run of at least two numbers adding up to `target`. All the numbers are
+// positive, so a sliding window is enough.
+fn contiguous_sum(numbers: &[u64], target: u64) -> Option<&[u64]> {
+    let mut start = 0;
+    let mut sum = 0;
+
+    for (end, &number) in numbers.iter().enumerate() {
+        sum += number;
+        while sum > target && start < end {
+            sum -= numbers[start];
+            start += 1;
+        }
+        if sum == target && end > start {
+            return Some(&numbers[start..=end]);
+        }
+    }
+
+    None
+}
+
+fn encryption_weakness(numbers: &[u64], preamble: usize) -> Option<u64> {
+    let range = contiguous_sum(numbers, first_invalid(numbers, preamble)?)?;
+    Some(range.iter().min()? + range.iter().max()?)
+}
+
+pub struct EncodingError;
+
+impl Solution for EncodingError {
+    type Parsed = Vec<u64>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(parse_numbers(input)?)
+    }
+
+    fn part1(numbers: &Self::Parsed) -> Answer {
+        match first_invalid(numbers, PREAMBLE) {
+            Some(number) => Answer::from(number),
+            None => Answer::from("Every number follows the rule"),
+        }
+    }
+
+    fn part2(numbers: &Self::Parsed) -> Answer {
+        match encryption_weakness(numbers, PREAMBLE) {
+            Some(weakness) => Answer::from(weakness),
+            None => Answer::from("No weakness found"),
+        }
+    }
+}
+
+aoc_day!(2020, 9, solution = EncodingError);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT_STRING: &str = "35
+    20
+    15
+    25
+    47
+    40
+    62
+    55
+    65
+    95
+    102
+    117
+    150
+    182
+    127
+    219
+    299
+    277
+    309
+    576";
+
+    #[test]
+    fn xmas_weakness() {
+        let numbers = parse_numbers(INPUT_STRING).unwrap();
+
+        assert_eq!(first_invalid(&numbers, 5), Some(127));
+        assert_eq!(contiguous_sum(&numbers, 127), Some(&numbers[2..6]));
+        assert_eq!(encryption_weakness(&numbers, 5), Some(62));
+
+        // A single number is not a range
+        assert_eq!(contiguous_sum(&[1, 5, 2], 5), None);
+    }
+}
@@ -0,0 +1,91 @@
+use crate::aoc_lib::prelude::*;
+
+// The joltages in the chain, from the outlet to the device
+fn parse_chain(input: &str) -> Result<Vec<u64>, String> {
+    let mut joltages = vec![0];
+    for line in input.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        joltages.push(
+            line.parse()
+                .map_err(|_| format!("\"{}\" is not a joltage", line))?,
+        );
+    }
+    joltages.sort_unstable();
+    joltages.push(joltages.last().unwrap() + 3);
+
+    if let Some(gap) = joltages.windows(2).find(|pair| pair[1] - pair[0] > 3) {
+        return Err(format!("Can't go from {} to {} jolts", gap[0], gap[1]));
+    }
+
+    Ok(joltages)
+}
+
+fn difference_product(chain: &[u64]) -> usize {
+    let count = |step: u64| {
+        chain
+            .windows(2)
+            .filter(|pair| pair[1] - pair[0] == step)
+            .count()
+    };
+    count(1) * count(3)
+}
+
+// Ways to reach every adapter, adding up the ways to reach the ones up to
+// three jolts below it
+fn arrangements(chain: &[u64]) -> u64 {
+    let mut ways = vec![0u64; chain.len()];
+    ways[0] = 1;
+    for id in 1..chain.len() {
+        ways[id] = (id.saturating_sub(3)..id)
+            .filter(|&previous| chain[id] - chain[previous] <= 3)
+            .map(|previous| ways[previous])
+            .sum();
+    }
+
+    ways[chain.len() - 1]
+}
+
+pub struct AdapterArray;
+
+impl Solution for AdapterArray {
+    type Parsed = Vec<u64>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(parse_chain(input)?)
+    }
+
+    fn part1(chain: &Self::Parsed) -> Answer {
+        Answer::from(difference_product(chain))
+    }
+
+    fn part2(chain: &Self::Parsed) -> Answer {
+        Answer::from(arrangements(chain))
+    }
+}
+
+aoc_day!(2020, 10, solution = AdapterArray);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static SHORT_CHAIN: &str = "16 10 15 5 1 11 7 19 6 12 4";
+    static LONG_CHAIN: &str =
+        "28 33 18 42 31 14 46 20 48 47 24 23 49 45 19 38 39 11 1 32 25 35 8 17 7 9 4 2 34 10 3";
+
+    fn chain(adapters: &str) -> Vec<u64> {
+        parse_chain(&adapters.split(' ').collect::<Vec<&str>>().join("\n")).unwrap()
+    }
+
+    #[test]
+    fn joltage_differences() {
+        assert_eq!(difference_product(&chain(SHORT_CHAIN)), 7 * 5);
+        assert_eq!(difference_product(&chain(LONG_CHAIN)), 22 * 10);
+        assert!(parse_chain("1\n5").is_err());
+    }
+
+    #[test]
+    fn adapter_arrangements() {
+        assert_eq!(arrangements(&chain(SHORT_CHAIN)), 8);
+        assert_eq!(arrangements(&chain(LONG_CHAIN)), 19208);
+    }
+}
@@ -1,74 +1,71 @@
 use itertools::Itertools;
 
-pub fn part1(input: &str) {
-    let mut increase_amount = 0;
-    let mut line_input = input.split('\n');
-    let mut last_value: u32 = line_input
-        .next()
-        .expect("An empty input, really?")
-        .trim()
-        .parse::<u32>()
-        .expect("The first line is not an integer!");
+use crate::aoc_lib::prelude::*;
 
-    for curr_value in line_input {
-        let num_opt = curr_value.trim().parse::<u32>();
-        let num_value = match num_opt {
-            Ok(val) => val,
-            Err(e) => {
-                println!(
-                    "String {} doesn't seem to contain a number! Error: {}",
-                    curr_value, e
-                );
-                last_value
-            }
-        };
+// One depth per line, the blank ones don't count
+fn parse_depths(input: &str) -> Result<Vec<u32>, AocError> {
+    let depths = input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.parse::<u32>()
+                .map_err(|e| AocError::Parse(format!("\"{}\" is not a depth: {}", line, e)))
+        })
+        .collect::<Result<Vec<u32>, AocError>>()?;
 
-        if num_value > last_value {
-            increase_amount += 1;
-        }
-        last_value = num_value;
+    if depths.is_empty() {
+        return Err(AocError::Parse(String::from("No depths in the input")));
     }
+    Ok(depths)
+}
+
+fn count_increases(values: impl Iterator<Item = u32>) -> usize {
+    values.tuple_windows().filter(|(last, next)| next > last).count()
+}
+
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let depths = parse_depths(input)?;
 
-    println!("Amount of increases: {}", increase_amount);
+    Ok(Answer::from(count_increases(depths.into_iter())))
 }
 
-fn sum_window(tuple: (&str, &str, &str)) -> Option<i32> {
-    let mut final_sum = 0;
-    for value in [tuple.0, tuple.1, tuple.2].iter() {
-        let num_opt = value.trim().parse::<i32>();
-        match num_opt {
-            Ok(val) => {
-                final_sum += val;
-            }
-            Err(e) => {
-                println!(
-                    "String {} doesn't seem to contain a number! Error: {}",
-                    value, e
-                );
-                return None;
-            }
-        }
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let depths = parse_depths(input)?;
+    if depths.len() < 3 {
+        return Err(AocError::Unsolvable(String::from(
+            "Less than three depths, not even one window to sum",
+        )));
     }
+    let windows = depths
+        .into_iter()
+        .tuple_windows()
+        .map(|(first, second, third)| first + second + third);
 
-    Some(final_sum)
+    Ok(Answer::from(count_increases(windows)))
 }
 
-pub fn part2(input: &str) {
-    let mut line_input = input.split('\n').tuple_windows::<(_, _, _)>();
-    //let mut
-    let mut last_full_value =
-        sum_window(line_input.next().unwrap()).expect("I am expecting more than three values");
-    let mut increases = 0;
+aoc_day!(2021, 1, part1, part2);
 
-    for curr_value in line_input {
-        let curr_num = sum_window(curr_value);
-        if let Some(num) = curr_num {
-            if num > last_full_value {
-                increases += 1;
-            }
-            last_full_value = num;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT_STRING: &str = include_str!("../../inputs/2021/day01.example.txt");
+
+    #[test]
+    fn increases() {
+        assert_eq!(part1(INPUT_STRING).unwrap(), Answer::UInt(7));
+        assert_eq!(part2(INPUT_STRING).unwrap(), Answer::UInt(5));
     }
 
-    println!("Amount of increases: {}", increases);
+    #[test]
+    fn bad_depths() {
+        assert!(matches!(part1(""), Err(AocError::Parse(_))));
+        assert_eq!(
+            part1("199\nhello\n").unwrap_err().to_string(),
+            "Can't read the input: \"hello\" is not a depth: invalid digit found in string"
+        );
+        assert!(matches!(part2("199\n200"), Err(AocError::Unsolvable(_))));
+    }
 }
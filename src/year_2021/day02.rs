@@ -1,21 +1,30 @@
-pub fn part1(input: &str) {
+use crate::aoc_lib::prelude::*;
+
+// The direction, lowercase, and how far to go in it
+fn parse_command(line: &str) -> Result<(String, i32), AocError> {
+    let mut direction_val_split = line.split_whitespace();
+
+    let (Some(direction), Some(distance), None) = (
+        direction_val_split.next(),
+        direction_val_split.next(),
+        direction_val_split.next(),
+    ) else {
+        return Err(AocError::Parse(format!("Invalid command: \"{}\"", line)));
+    };
+    let distance = distance
+        .parse::<i32>()
+        .map_err(|e| AocError::Parse(format!("\"{}\" is not a distance: {}", distance, e)))?;
+
+    Ok((direction.to_lowercase(), distance))
+}
+
+pub fn part1(input: &str) -> Result<Answer, AocError> {
     let line_input = input.lines();
     let mut forward = 0;
     let mut depth = 0;
 
     for curr_value in line_input {
-        let mut direction_val_split = curr_value.split_whitespace();
-
-        let direction_string = direction_val_split
-            .next()
-            .expect("Where did our direction go?")
-            .to_lowercase();
-        let distance = direction_val_split
-            .next()
-            .expect("Where did the distance go?")
-            .trim()
-            .parse::<i32>()
-            .expect("Didn't receive a number");
+        let (direction_string, distance) = parse_command(curr_value)?;
         match direction_string.as_str() {
             "forward" => forward += distance,
             "down" => depth += distance,
@@ -26,33 +35,25 @@ pub fn part1(input: &str) {
                 }
             }
             _ => {
-                println!("Provided a non-handled direction: {}", direction_string);
+                return Err(AocError::Parse(format!(
+                    "Provided a non-handled direction: {}",
+                    direction_string
+                )))
             }
         }
     }
 
-    println!("Travel area: {}", forward * depth);
+    Ok(Answer::from(forward * depth))
 }
 
-pub fn part2(input: &str) {
+pub fn part2(input: &str) -> Result<Answer, AocError> {
     let line_input = input.lines();
     let mut forward = 0;
     let mut depth = 0;
     let mut angle = 0;
 
     for curr_value in line_input {
-        let mut direction_val_split = curr_value.split_whitespace();
-
-        let direction_string = direction_val_split
-            .next()
-            .expect("Where did our direction go?")
-            .to_lowercase();
-        let distance = direction_val_split
-            .next()
-            .expect("Where did the distance go?")
-            .trim()
-            .parse::<i32>()
-            .expect("Didn't receive a number");
+        let (direction_string, distance) = parse_command(curr_value)?;
         match direction_string.as_str() {
             "forward" => {
                 forward += distance;
@@ -64,10 +65,15 @@ pub fn part2(input: &str) {
             "down" => angle += distance,
             "up" => angle -= distance,
             _ => {
-                println!("Provided a non-handled direction: {}", direction_string);
+                return Err(AocError::Parse(format!(
+                    "Provided a non-handled direction: {}",
+                    direction_string
+                )))
             }
         }
     }
 
-    println!("Travel area: {}", forward * depth);
+    Ok(Answer::from(forward * depth))
 }
+
+aoc_day!(2021, 2, part1, part2);
@@ -1,26 +1,52 @@
-pub fn part1(input: &str) {
-    let mut line_input = input.lines().peekable();
-    let mut frequencies = vec![
-        0;
-        line_input
-            .peek()
-            .expect("I need at least one line, man.")
-            .len()
-    ];
-
-    for curr_value in line_input {
+use crate::aoc_lib::prelude::*;
+
+// The binary numbers, all as wide as the first one
+fn parse_report(input: &str) -> Result<Vec<&str>, AocError> {
+    let report: Vec<&str> = input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    let width = report.first().map_or(0, |line| line.len());
+    if !(1..64).contains(&width) {
+        return Err(AocError::Parse(format!(
+            "The numbers are {} bits wide, they should be 1 to 63",
+            width
+        )));
+    }
+
+    for line in report.iter() {
+        if let Some(bit) = line.chars().find(|bit| !matches!(bit, '0' | '1')) {
+            return Err(AocError::Parse(format!(
+                "Got character {} in a binary string",
+                bit
+            )));
+        }
+        if line.len() != width {
+            return Err(AocError::Parse(format!(
+                "\"{}\" is not {} bits wide like the first number",
+                line, width
+            )));
+        }
+    }
+
+    Ok(report)
+}
+
+pub fn part1(input: &str) -> Result<Solved, AocError> {
+    let report = parse_report(input)?;
+    let mut frequencies = vec![0; report[0].len()];
+
+    for curr_value in report {
         for (pos, bit) in curr_value.chars().enumerate() {
             match bit {
                 '0' => frequencies[pos] -= 1,
-                '1' => frequencies[pos] += 1,
-                _ => {
-                    println!("Got character {} in a binary string", bit)
-                }
+                _ => frequencies[pos] += 1,
             }
         }
     }
 
-    println!("Final frequencies: {:?}", frequencies);
+    let frequencies_note = format!("Final frequencies: {:?}", frequencies);
 
     let mut gamma: u64 = 0;
     let mut epsilon: u64 = 0;
@@ -35,72 +61,45 @@ pub fn part1(input: &str) {
     }
 
     let power_consumption = gamma * epsilon;
-    println!("Power consumption: {}", power_consumption);
+    Ok(Solved::from(Answer::from(power_consumption)).note(frequencies_note))
 }
 
-pub fn check_for_one(binary_value: &str, bit_pos: usize) -> Option<bool> {
-    match binary_value.chars().nth(bit_pos).unwrap_or('x') {
-        '1' => Some(true),
-        '0' => Some(false),
-        _ => None,
-    }
+pub fn check_for_one(binary_value: &str, bit_pos: usize) -> bool {
+    binary_value.as_bytes()[bit_pos] == b'1'
 }
 
-pub fn part2(input: &str) {
-    let line_input = input.lines();
-
-    let (one_data, zero_data): (Vec<&str>, Vec<&str>) = line_input.partition(|line| {
-        check_for_one(line, 0)
-            .unwrap_or_else(|| panic!("Didn't get a proper binary string! Got {}", line))
-    });
+// Keeps the numbers with the most common bit, or the least common one, in each
+// position until one is left. Numbers that are all the same count as one.
+fn rating(report: &[&str], most_common: bool) -> Option<u64> {
+    let mut data = report.to_vec();
+    let mut curr_bit = 0;
 
-    let (mut oxygen_data, mut carbon_data) = if one_data.len() >= zero_data.len() {
-        (one_data, zero_data)
-    } else {
-        (zero_data, one_data)
-    };
-
-    let mut curr_bit = 1;
-
-    while oxygen_data.len() > 1 {
-        let (one, zero): (Vec<&str>, Vec<&str>) = oxygen_data.iter().partition(|line| {
-            check_for_one(line, curr_bit)
-                .unwrap_or_else(|| panic!("Didn't get a proper binary string! Got {}", line))
-        });
+    while data.len() > 1 && curr_bit < data[0].len() {
+        let (one, zero): (Vec<&str>, Vec<&str>) =
+            data.iter().partition(|line| check_for_one(line, curr_bit));
         curr_bit += 1;
-        oxygen_data = if one.len() >= zero.len() { one } else { zero };
+        data = if (one.len() >= zero.len()) == most_common {
+            one
+        } else {
+            zero
+        };
     }
 
-    let oxygen_score = match isize::from_str_radix(oxygen_data[0], 2) {
-        Ok(score) => score,
-        Err(e) => {
-            panic!(
-                "Couldn't parse the value of {}, got error: {}",
-                oxygen_data[0], e
-            )
-        }
-    };
-
-    curr_bit = 1;
+    u64::from_str_radix(data.first()?, 2).ok()
+}
 
-    while carbon_data.len() > 1 {
-        let (one, zero): (Vec<&str>, Vec<&str>) = carbon_data.iter().partition(|line| {
-            check_for_one(line, curr_bit)
-                .unwrap_or_else(|| panic!("Didn't get a proper binary string! Got {}", line))
-        });
-        curr_bit += 1;
-        carbon_data = if one.len() >= zero.len() { zero } else { one };
-    }
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let report = parse_report(input)?;
 
-    let carbon_score = match isize::from_str_radix(carbon_data[0], 2) {
-        Ok(score) => score,
-        Err(e) => {
-            panic!(
-                "Couldn't parse the value of {}, got error: {}",
-                carbon_data[0], e
-            )
-        }
+    let oxygen_score = rating(&report, true);
+    let carbon_score = rating(&report, false);
+    let (Some(oxygen_score), Some(carbon_score)) = (oxygen_score, carbon_score) else {
+        return Err(AocError::Unsolvable(String::from(
+            "Every number has the same first bit, nothing is left for the carbon rating",
+        )));
     };
 
-    println!("Life support rating: {}", oxygen_score * carbon_score);
+    Ok(Answer::from(oxygen_score * carbon_score))
 }
+
+aoc_day!(2021, 3, part1, part2);
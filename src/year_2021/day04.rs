@@ -2,6 +2,8 @@ use hashbrown::{HashMap, HashSet};
 
 use itertools::Itertools;
 
+use crate::aoc_lib::prelude::*;
+
 #[derive(Debug, Default)]
 struct BingoBoard {
     value_to_location: HashMap<usize, (usize, usize)>,
@@ -19,11 +21,9 @@ impl BingoBoard {
         board_score * last_value
     }
 
+    // Nothing happens to a value called twice
     fn mark_value(&mut self, value: &usize) -> Option<usize> {
-        let (row, col) = self
-            .value_to_location
-            .remove(value)
-            .expect("Requested value is not present in this board!");
+        let (row, col) = self.value_to_location.remove(value)?;
 
         self.column_hits[col] += 1;
         self.row_hits[row] += 1;
@@ -44,17 +44,19 @@ impl BingoBoard {
     }
 }
 
-pub fn part1(input: &str) {
+// The scores of the first and of the last board to win, both from one game
+fn play_bingo(input: &str) -> Result<(usize, usize), AocError> {
     let mut line_input = input.lines();
     let numbers_called: Vec<usize> = line_input
         .next()
-        .expect("Please give at least one line!")
+        .unwrap_or_default()
         .split(',')
         .map(|val| {
-            val.parse::<usize>()
-                .expect("Didn't manage to parse the value!")
+            val.trim()
+                .parse::<usize>()
+                .map_err(|e| AocError::Parse(format!("\"{}\" is not a number: {}", val, e)))
         })
-        .collect();
+        .try_collect()?;
 
     let mut bingo_boards = Vec::<BingoBoard>::new();
     let mut val_to_board = HashMap::<usize, HashSet<usize>>::new();
@@ -62,17 +64,31 @@ pub fn part1(input: &str) {
     for line_chunk in line_input.chunks(6).into_iter() {
         let mut next_board: BingoBoard = BingoBoard::default();
         let board_idx = bingo_boards.len();
+        let line_chunk = line_chunk.collect_vec();
+        if line_chunk.len() != 6 || !line_chunk[0].trim().is_empty() {
+            return Err(AocError::Parse(format!(
+                "Board {} is not a blank line and five rows",
+                board_idx + 1
+            )));
+        }
 
-        for (line, data) in line_chunk.enumerate() {
+        for (line, data) in line_chunk.into_iter().enumerate() {
             if line > 0 {
-                for (col, int_str) in data
+                let row: Vec<usize> = data
                     .split_whitespace()
                     .map(|val| {
-                        val.parse::<usize>()
-                            .expect("Didn't manage to parse the value!")
+                        val.parse::<usize>().map_err(|e| {
+                            AocError::Parse(format!("\"{}\" is not a number: {}", val, e))
+                        })
                     })
-                    .enumerate()
-                {
+                    .try_collect()?;
+                if row.len() != 5 {
+                    return Err(AocError::Parse(format!(
+                        "\"{}\" is not a row of five numbers",
+                        data
+                    )));
+                }
+                for (col, int_str) in row.into_iter().enumerate() {
                     if let Some(val_set) = val_to_board.get_mut(&int_str) {
                         val_set.insert(board_idx);
                     } else {
@@ -86,6 +102,12 @@ pub fn part1(input: &str) {
             }
         }
 
+        if next_board.value_to_location.len() != 25 {
+            return Err(AocError::Parse(format!(
+                "Board {} has the same number twice",
+                board_idx + 1
+            )));
+        }
         bingo_boards.push(next_board);
     }
 
@@ -94,10 +116,10 @@ pub fn part1(input: &str) {
     let mut winning_boards = HashSet::<usize>::default();
 
     for num in numbers_called {
-        let matching_boards = val_to_board
-            .get(&num)
-            .expect("We are trying to retrieve a value that we never inserted!")
-            .difference(&winning_boards);
+        let Some(boards) = val_to_board.get(&num) else {
+            continue;
+        };
+        let matching_boards = boards.difference(&winning_boards);
         let mut curr_winning_boards = HashSet::<usize>::default();
         for board_idx in matching_boards {
             if let Some(final_score) = bingo_boards[*board_idx].mark_value(&num) {
@@ -111,9 +133,18 @@ pub fn part1(input: &str) {
         winning_boards.extend(&curr_winning_boards);
     }
 
-    println!(
-        "The first board to win has this score: {}; The last board to win has this score: {}",
-        first_score.expect("This cannot be empty, really."),
-        last_score
-    );
+    let first_score =
+        first_score.ok_or_else(|| AocError::Unsolvable(String::from("No board ever wins")))?;
+
+    Ok((first_score, last_score))
 }
+
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    Ok(Answer::from(play_bingo(input)?.0))
+}
+
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    Ok(Answer::from(play_bingo(input)?.1))
+}
+
+aoc_day!(2021, 4, part1, part2);
@@ -1,11 +1,11 @@
 use hashbrown::HashMap;
 
 use nom::bytes::complete::tag;
-use nom::combinator::map;
+use nom::combinator::{all_consuming, map, map_res};
 use nom::sequence::separated_pair;
 use nom::IResult;
 
-use crate::aoc_lib::jazz_parser;
+use crate::aoc_lib::prelude::*;
 
 #[derive(PartialEq, Debug, Clone, Copy)]
 enum Direction {
@@ -34,7 +34,7 @@ struct Line {
 }
 
 impl Line {
-    fn new(start: &Point, end: &Point) -> Line {
+    fn new(start: &Point, end: &Point) -> Result<Line, String> {
         let line_dir = if start.x == end.x {
             Direction::Vertical
         } else if start.y == end.y {
@@ -60,64 +60,52 @@ impl Line {
         };
 
         if line_dir == Direction::Diagonal {
-            let x_delta = if actual_end.x < actual_start.x {
-                actual_start.x - actual_end.x
-            } else {
-                actual_end.x - actual_start.x
-            };
-            let y_delta = if actual_end.y < actual_start.y {
-                actual_start.y - actual_end.y
-            } else {
-                actual_end.y - actual_start.y
-            };
-            assert_eq!(
-                x_delta, y_delta,
-                "Diagonal lines should have a 45 degree slope!"
-            );
+            let x_delta = actual_start.x.abs_diff(actual_end.x);
+            let y_delta = actual_start.y.abs_diff(actual_end.y);
+            if x_delta != y_delta {
+                return Err(String::from(
+                    "Diagonal lines should have a 45 degree slope!",
+                ));
+            }
         }
 
-        Line {
+        Ok(Line {
             start: *actual_start,
             end: *actual_end,
             direction: line_dir,
-        }
+        })
     }
 }
 
 fn point(input: &str) -> IResult<&str, Point> {
     let parser = separated_pair(jazz_parser::usize, tag(","), jazz_parser::usize);
-    map(parser, |s| {
-        // FIXME: unwrap() may panic if the value is out of range
-        Point::new(s.0, s.1)
-    })(input)
+    map(parser, |s| Point::new(s.0, s.1))(input)
 }
 
 fn segment(input: &str) -> IResult<&str, Line> {
     let parser = separated_pair(point, tag(" -> "), point);
-    map(parser, |s| {
-        // FIXME: unwrap() may panic if the value is out of range
-        Line::new(&s.0, &s.1)
-    })(input)
+    map_res(parser, |s| Line::new(&s.0, &s.1))(input)
 }
 
-pub fn part1(input: &str) {
-    let intersections = intersection_check(input, false);
-    println!("Number of intersections: {}", intersections);
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let intersections = intersection_check(input, false)?;
+    Ok(Answer::from(intersections))
 }
 
-pub fn part2(input: &str) {
-    let intersections = intersection_check(input, true);
-    println!("Number of intersections: {}", intersections);
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let intersections = intersection_check(input, true)?;
+    Ok(Answer::from(intersections))
 }
 
-fn intersection_check(input: &str, enable_diagonals: bool) -> i32 {
+fn intersection_check(input: &str, enable_diagonals: bool) -> Result<i32, AocError> {
     let line_input = input.lines();
 
     let mut occupation_map = HashMap::<(usize, usize), usize>::new();
     let mut intersecting_points = 0;
 
     for line in line_input {
-        let segment = segment(line).expect("Didn't manage to parse the input!").1;
+        let (_, segment) = all_consuming(segment)(line)
+            .map_err(|_| AocError::Parse(format!("Invalid segment: \"{}\"", line)))?;
 
         match segment.direction {
             Direction::Horizontal => {
@@ -155,7 +143,7 @@ fn intersection_check(input: &str, enable_diagonals: bool) -> i32 {
                         row = if row_direction {
                             row + 1
                         } else {
-                            row.checked_sub(1).unwrap_or_default()
+                            row.saturating_sub(1)
                         };
                     }
                 }
@@ -163,9 +151,11 @@ fn intersection_check(input: &str, enable_diagonals: bool) -> i32 {
         }
     }
 
-    intersecting_points
+    Ok(intersecting_points)
 }
 
+aoc_day!(2021, 5, part1, part2);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -183,7 +173,7 @@ mod tests {
 0,0 -> 8,8
 5,5 -> 8,2";
 
-        let intersections = intersection_check(input_string, false);
+        let intersections = intersection_check(input_string, false).unwrap();
 
         assert_eq!(intersections, 5);
     }
@@ -201,7 +191,7 @@ mod tests {
 0,0 -> 8,8
 5,5 -> 8,2";
 
-        let intersections = intersection_check(input_string, true);
+        let intersections = intersection_check(input_string, true).unwrap();
 
         assert_eq!(intersections, 12);
     }
@@ -1,15 +1,22 @@
 use std::{collections::VecDeque, vec};
 
-fn lanternfish_evolution(input: &str, time_horizon: u16) -> u64 {
+use crate::aoc_lib::prelude::*;
+
+fn lanternfish_evolution(input: &str, time_horizon: u16) -> Result<u64, AocError> {
     let start_lives = input.split(',');
 
     let mut starting_lifetimes_count = vec![0u64; 9];
     for lifetime in start_lives {
-        let life_num: usize = lifetime
-            .trim()
-            .parse()
-            .expect("We got a string in the CSV that's not a number");
-        starting_lifetimes_count[life_num] += 1;
+        let life_num: usize = lifetime.trim().parse().map_err(|e| {
+            AocError::Parse(format!("\"{}\" is not a lifetime: {}", lifetime.trim(), e))
+        })?;
+        let Some(count) = starting_lifetimes_count.get_mut(life_num) else {
+            return Err(AocError::Parse(format!(
+                "A lanternfish lives 8 days at most, not {}",
+                life_num
+            )));
+        };
+        *count += 1;
     }
 
     let mut lives_count: VecDeque<u64> = starting_lifetimes_count.into_iter().collect();
@@ -19,19 +26,21 @@ fn lanternfish_evolution(input: &str, time_horizon: u16) -> u64 {
         lives_count[6] += lives_count[8];
     }
 
-    lives_count.into_iter().sum()
+    Ok(lives_count.into_iter().sum())
 }
 
-pub fn part1(input: &str) {
-    let final_population = lanternfish_evolution(input, 80);
-    println!("Final population: {}", final_population);
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let final_population = lanternfish_evolution(input, 80)?;
+    Ok(Answer::from(final_population))
 }
 
-pub fn part2(input: &str) {
-    let final_population = lanternfish_evolution(input, 256);
-    println!("Final population: {}", final_population);
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let final_population = lanternfish_evolution(input, 256)?;
+    Ok(Answer::from(final_population))
 }
 
+aoc_day!(2021, 6, part1, part2);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -40,7 +49,7 @@ mod tests {
     fn normal_conditions() {
         let input_string = "3,4,3,1,2";
 
-        let population_count = lanternfish_evolution(input_string, 80);
+        let population_count = lanternfish_evolution(input_string, 80).unwrap();
 
         assert_eq!(population_count, 5934u64);
     }
@@ -49,7 +58,7 @@ mod tests {
     fn infinite_resources() {
         let input_string = "3,4,3,1,2";
 
-        let population_count = lanternfish_evolution(input_string, 256);
+        let population_count = lanternfish_evolution(input_string, 256).unwrap();
 
         assert_eq!(population_count, 26984457539u64);
     }
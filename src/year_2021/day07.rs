@@ -1,6 +1,8 @@
 use std::convert::TryInto;
 
-use itertools::sorted;
+use itertools::{sorted, Itertools};
+
+use crate::aoc_lib::prelude::*;
 
 fn midpoint_binary_search(
     sorted_positions: &[u32],
@@ -48,11 +50,7 @@ fn midpoint_binary_search(
 }
 
 fn simple_delta(start: &u32, target: &u32) -> u32 {
-    if start > target {
-        start - target
-    } else {
-        target - start
-    }
+    start.abs_diff(*target)
 }
 
 fn linear_delta(start: &u32, target: &u32) -> u32 {
@@ -61,12 +59,18 @@ fn linear_delta(start: &u32, target: &u32) -> u32 {
     (upped / 2).try_into().unwrap()
 }
 
-fn min_crab_fuel(input: &str, consumption_function: fn(&u32, &u32) -> u32) -> u64 {
-    let initial_horizontal = input.split(',').map(|hor| {
-        hor.trim()
-            .parse::<u32>()
-            .expect("Given a non-number as horizontal position!")
-    });
+fn min_crab_fuel(
+    input: &str,
+    consumption_function: fn(&u32, &u32) -> u32,
+) -> Result<u64, AocError> {
+    let initial_horizontal: Vec<u32> = input
+        .split(',')
+        .map(|hor| {
+            hor.trim().parse::<u32>().map_err(|e| {
+                AocError::Parse(format!("\"{}\" is not a position: {}", hor.trim(), e))
+            })
+        })
+        .try_collect()?;
     let sorted_horizontal: Vec<u32> = sorted(initial_horizontal).collect();
 
     let mid_point = midpoint_binary_search(&sorted_horizontal, consumption_function);
@@ -75,19 +79,21 @@ fn min_crab_fuel(input: &str, consumption_function: fn(&u32, &u32) -> u32) -> u6
         sum
     });
 
-    fuel_cost
+    Ok(fuel_cost)
 }
 
-pub fn part1(input: &str) {
-    let min_consumption = min_crab_fuel(input, simple_delta);
-    println!("Estimated minimum cost: {}", min_consumption);
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let min_consumption = min_crab_fuel(input, simple_delta)?;
+    Ok(Answer::from(min_consumption))
 }
 
-pub fn part2(input: &str) {
-    let min_consumption = min_crab_fuel(input, linear_delta);
-    println!("Estimated minimum geometric cost: {}", min_consumption);
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let min_consumption = min_crab_fuel(input, linear_delta)?;
+    Ok(Answer::from(min_consumption))
 }
 
+aoc_day!(2021, 7, part1, part2);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -96,7 +102,7 @@ mod tests {
     fn base_case() {
         let input_string = "16,1,2,0,4,2,7,1,2,14";
 
-        let min_fuel_needed = min_crab_fuel(input_string, simple_delta);
+        let min_fuel_needed = min_crab_fuel(input_string, simple_delta).unwrap();
 
         assert_eq!(min_fuel_needed, 37u64);
     }
@@ -105,7 +111,7 @@ mod tests {
     fn linear_rate() {
         let input_string = "16,1,2,0,4,2,7,1,2,14";
 
-        let min_fuel_needed = min_crab_fuel(input_string, linear_delta);
+        let min_fuel_needed = min_crab_fuel(input_string, linear_delta).unwrap();
 
         assert_eq!(min_fuel_needed, 168u64);
     }
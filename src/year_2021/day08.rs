@@ -1,27 +1,45 @@
 use hashbrown::HashSet;
+use itertools::Itertools;
 use std::convert::TryFrom;
 use std::iter::FromIterator;
 
 use nom::bytes::complete::tag;
 use nom::character::complete::{alphanumeric1, multispace1};
-use nom::combinator::map;
+use nom::combinator::{all_consuming, map};
 use nom::multi::separated_list1;
 use nom::sequence::separated_pair;
 use nom::IResult;
 
+use crate::aoc_lib::prelude::*;
+
 fn display(input: &str) -> IResult<&str, (Vec<&str>, Vec<&str>)> {
     let parser = separated_pair(
         separated_list1(multispace1, alphanumeric1),
         tag(" | "),
         separated_list1(multispace1, alphanumeric1),
     );
-    map(parser, |s| {
-        // FIXME: unwrap() may panic if the value is out of range
-        (s.0, s.1)
-    })(input)
+    map(parser, |s| (s.0, s.1))(input)
+}
+
+// The ten patterns, one per digit, and the four digits of the output
+fn parse_display(line: &str) -> Result<(Vec<&str>, Vec<&str>), AocError> {
+    let invalid = || AocError::Parse(format!("Invalid display: \"{}\"", line));
+    let (_, (cypher, digits)) = all_consuming(display)(line).map_err(|_| invalid())?;
+
+    let mut lengths: Vec<usize> = cypher.iter().map(|code| code.len()).collect();
+    lengths.sort_unstable();
+    let segments = |code: &&str| code.chars().all(|c| ('a'..='g').contains(&c));
+    if lengths != [2, 3, 4, 5, 5, 5, 6, 6, 6, 7]
+        || !cypher.iter().chain(digits.iter()).all(segments)
+    {
+        return Err(invalid());
+    }
+
+    Ok((cypher, digits))
 }
 
-fn cypher_crack(cypher: Vec<&str>, digits: Vec<&str>) -> u32 {
+// None when the patterns aren't the ten digits with the wires swapped
+fn cypher_crack(cypher: Vec<&str>, digits: Vec<&str>) -> Option<u32> {
     let mut mappings: Vec<HashSet<char>> = vec![Default::default(); 10];
 
     let mut potential_six = Vec::<HashSet<char>>::new();
@@ -41,21 +59,11 @@ fn cypher_crack(cypher: Vec<&str>, digits: Vec<&str>) -> u32 {
         }
     }
 
-    let mut nine_idx = 0usize;
-    let mut lower_left_code = HashSet::new();
-    for (idx, six_lines) in potential_six.iter().enumerate() {
-        if six_lines.is_superset(&mappings[4]) {
-            mappings[9].extend(six_lines);
-            nine_idx = idx;
-            let lower_left_set: Vec<_> = mappings[8].difference(&mappings[9]).collect();
-            assert_eq!(
-                lower_left_set.len(),
-                1,
-                "We should have a single code in the difference between 8 and 9!"
-            );
-            lower_left_code.insert(*lower_left_set[0]);
-        }
-    }
+    let nine_idx = potential_six
+        .iter()
+        .position(|six_lines| six_lines.is_superset(&mappings[4]))?;
+    mappings[9].extend(&potential_six[nine_idx]);
+    let lower_left_code: HashSet<char> = mappings[8].difference(&mappings[9]).copied().collect();
     potential_six.swap_remove(nine_idx);
 
     let (zero_idx, six_idx) = if potential_six[0].is_superset(&mappings[1]) {
@@ -66,25 +74,19 @@ fn cypher_crack(cypher: Vec<&str>, digits: Vec<&str>) -> u32 {
     mappings[0].extend(&potential_six[zero_idx]);
     mappings[6].extend(&potential_six[six_idx]);
 
-    let mut five_idx = 0usize;
-    for (idx, five_lines) in potential_five.iter().enumerate() {
-        let potential_six_encode = five_lines.union(&lower_left_code);
-        let mut potential_six_set: HashSet<char> = HashSet::new();
-        potential_six_set.extend(potential_six_encode);
-        if mappings[6].eq(&potential_six_set) {
-            mappings[5].extend(five_lines);
-            five_idx = idx;
-        }
-    }
+    let five_idx = potential_five.iter().position(|five_lines| {
+        let potential_six_set: HashSet<char> =
+            five_lines.union(&lower_left_code).copied().collect();
+        mappings[6].eq(&potential_six_set)
+    })?;
+    mappings[5].extend(&potential_five[five_idx]);
     potential_five.swap_remove(five_idx);
 
     let centre_code_data: Vec<_> = mappings[8].difference(&mappings[0]).collect();
-    assert_eq!(
-        centre_code_data.len(),
-        1,
-        "We should have a single code in the difference between 8 and 0!"
-    );
     let upper_left_data: Vec<_> = mappings[4].difference(&mappings[1]).collect();
+    if centre_code_data.len() != 1 || upper_left_data.len() != 2 {
+        return None;
+    }
     let upper_left_code = if upper_left_data[0] == centre_code_data[0] {
         *upper_left_data[1]
     } else {
@@ -107,28 +109,29 @@ fn cypher_crack(cypher: Vec<&str>, digits: Vec<&str>) -> u32 {
 
     let mut final_value = 0u32;
 
+    // The deductions only hold if every digit got its own pattern
+    if mappings.iter().tuple_combinations().any(|(left, right)| left == right) {
+        return None;
+    }
+
     for obf_digit in digits {
-        final_value *= 10;
-        let mut obf_digit_set: HashSet<char> = HashSet::new();
-        obf_digit_set.extend(obf_digit.chars());
+        final_value = final_value.checked_mul(10)?;
+        let obf_digit_set: HashSet<char> = obf_digit.chars().collect();
 
-        for (open_digit, code) in mappings.iter().enumerate() {
-            if obf_digit_set.eq(code) {
-                final_value += u32::try_from(open_digit).unwrap();
-            }
-        }
+        let open_digit = mappings.iter().position(|code| obf_digit_set.eq(code))?;
+        final_value += u32::try_from(open_digit).ok()?;
     }
 
-    final_value
+    Some(final_value)
 }
 
-fn lcd_simple_digit_count(input: &str) -> u64 {
+fn lcd_simple_digit_count(input: &str) -> Result<u64, AocError> {
     let input_lines = input.lines();
 
     let mut count = 0u64;
 
     for line in input_lines {
-        let (_, (_, digits)) = display(line.trim()).expect("Something went super wrong!");
+        let (_, digits) = parse_display(line.trim())?;
         for digit_data in digits {
             let activation_count = digit_data.len();
             if activation_count == 2
@@ -141,33 +144,37 @@ fn lcd_simple_digit_count(input: &str) -> u64 {
         }
     }
 
-    count
+    Ok(count)
 }
 
-fn output_decrypt_sum(input: &str) -> u64 {
+fn output_decrypt_sum(input: &str) -> Result<u64, AocError> {
     let input_lines = input.lines();
 
     let mut final_sum = 0u64;
 
     for line in input_lines {
-        let (_, (cypher, digits)) = display(line.trim()).expect("Something went super wrong!");
-        let encoded_value = cypher_crack(cypher, digits);
+        let (cypher, digits) = parse_display(line.trim())?;
+        let encoded_value = cypher_crack(cypher, digits).ok_or_else(|| {
+            AocError::Parse(format!("The display \"{}\" can't be decoded", line.trim()))
+        })?;
         final_sum += u64::from(encoded_value);
     }
 
-    final_sum
+    Ok(final_sum)
 }
 
-pub fn part1(input: &str) {
-    let simple_digits_count = lcd_simple_digit_count(input);
-    println!("Amount of simple digits: {}", simple_digits_count);
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let simple_digits_count = lcd_simple_digit_count(input)?;
+    Ok(Answer::from(simple_digits_count))
 }
 
-pub fn part2(input: &str) {
-    let decrypted_sum = output_decrypt_sum(input);
-    println!("Sum of all the encrypted values: {}", decrypted_sum);
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let decrypted_sum = output_decrypt_sum(input)?;
+    Ok(Answer::from(decrypted_sum))
 }
 
+aoc_day!(2021, 8, part1, part2);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -186,7 +193,7 @@ mod tests {
         egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb
         gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce";
 
-        let simple_count = lcd_simple_digit_count(input_string);
+        let simple_count = lcd_simple_digit_count(input_string).unwrap();
 
         assert_eq!(simple_count, 26u64);
     }
@@ -205,8 +212,17 @@ mod tests {
         egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb
         gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce";
 
-        let simple_count = output_decrypt_sum(input_string);
+        let simple_count = output_decrypt_sum(input_string).unwrap();
 
         assert_eq!(simple_count, 61229u64);
     }
+
+    #[test]
+    fn bad_displays() {
+        assert!(lcd_simple_digit_count("be cfbegad cbdgef | fdgacbe").is_err());
+        assert!(output_decrypt_sum(
+            "ab abc abcd abcde abcdf abcdg abcdef abcdeg abcdeg abcdefg | ab abc abcd ab"
+        )
+        .is_err());
+    }
 }
@@ -4,6 +4,8 @@ use std::fmt;
 
 use itertools::Itertools;
 
+use crate::aoc_lib::prelude::*;
+
 struct RiskGrid {
     data: Vec<u8>,
     rows: usize,
@@ -11,25 +13,35 @@ struct RiskGrid {
 }
 
 impl RiskGrid {
-    pub fn new(input: &str) -> RiskGrid {
+    pub fn new(input: &str) -> Result<RiskGrid, String> {
         let grid_rows = input.split_whitespace();
         let mut flat_data = Vec::new();
         let mut row_count = 0usize;
         let mut col_count = 0usize;
         for row in grid_rows {
+            if row_count > 0 && row.len() != col_count {
+                return Err(format!("The row \"{}\" isn't {} long", row, col_count));
+            }
             row_count += 1;
             col_count = row.len();
             for risk in row.chars() {
-                let risk_digit: u8 = risk.to_digit(10).unwrap().try_into().unwrap();
+                let risk_digit: u8 = risk
+                    .to_digit(10)
+                    .ok_or_else(|| format!("\"{}\" is not a height", risk))?
+                    .try_into()
+                    .unwrap();
                 flat_data.push(risk_digit);
             }
         }
+        if row_count == 0 {
+            return Err(String::from("There is no height map"));
+        }
 
-        RiskGrid {
+        Ok(RiskGrid {
             data: flat_data,
             rows: row_count,
             columns: col_count,
-        }
+        })
     }
 
     pub fn get_risk(&self, location: &(usize, usize)) -> u8 {
@@ -166,25 +178,27 @@ impl fmt::Display for RiskGrid {
     }
 }
 
-pub fn part1(input: &str) {
-    let risk_grid = RiskGrid::new(input);
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let risk_grid = RiskGrid::new(input)?;
     let (_, minima_risk): (Vec<_>, Vec<_>) = risk_grid.find_local_minima().iter().cloned().unzip();
     let risk_sum: u64 = minima_risk
         .iter()
         .fold(0u64, |sum, val| sum + u64::from(*val + 1));
-    println!("Sum of minima: {}", risk_sum);
+    Ok(Answer::from(risk_sum))
 }
 
-pub fn part2(input: &str) {
-    let risk_grid = RiskGrid::new(input);
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let risk_grid = RiskGrid::new(input)?;
     let (minima_locations, _): (Vec<_>, Vec<_>) =
         risk_grid.find_local_minima().iter().cloned().unzip();
     let basins = risk_grid.find_basin_sizes(&minima_locations);
     let top_basins = basins.iter().sorted().rev().take(3);
     let basin_area: u64 = top_basins.product();
-    println!("Product of top three largest basins: {}", basin_area);
+    Ok(Answer::from(basin_area))
 }
 
+aoc_day!(2021, 9, part1, part2);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -197,7 +211,7 @@ mod tests {
                                  8767896789
                                  9899965678";
 
-        let risk_grid = RiskGrid::new(input_string);
+        let risk_grid = RiskGrid::new(input_string).unwrap();
         let (_, minima_risk): (Vec<_>, Vec<_>) =
             risk_grid.find_local_minima().iter().cloned().unzip();
         let risk_sum: u64 = minima_risk
@@ -215,7 +229,7 @@ mod tests {
                                  8767896789
                                  9899965678";
 
-        let risk_grid = RiskGrid::new(input_string);
+        let risk_grid = RiskGrid::new(input_string).unwrap();
         let (minima_locations, _): (Vec<_>, Vec<_>) =
             risk_grid.find_local_minima().iter().cloned().unzip();
         let basins = risk_grid.find_basin_sizes(&minima_locations);
@@ -1,5 +1,7 @@
 use hashbrown::HashMap;
 
+use crate::aoc_lib::prelude::*;
+
 enum CheckResult {
     Wrong(char),
     Incomlete(Vec<char>),
@@ -15,10 +17,7 @@ fn syntax_line_check(line: &str) -> CheckResult {
         if parentheses_combo.contains_key(&par) {
             parentheses_stack.push(par);
         } else if let Some(pot_open) = parentheses_stack.pop() {
-            let paired_character = parentheses_combo
-                .get(&pot_open)
-                .expect("Found an invalid character in the syntax");
-            if par != *paired_character {
+            if par != parentheses_combo[&pot_open] {
                 return CheckResult::Wrong(par);
             }
         } else {
@@ -28,8 +27,9 @@ fn syntax_line_check(line: &str) -> CheckResult {
     CheckResult::Incomlete(parentheses_stack)
 }
 
-// This function takes the remaining, incomplete, part of a syntax line and computes the autocompletion score
-fn compute_autocomplete_cost(input: &[char]) -> u64 {
+// This function takes the remaining, incomplete, part of a syntax line and computes the autocompletion score.
+// None when the score doesn't fit in a u64.
+fn compute_autocomplete_cost(input: &[char]) -> Option<u64> {
     let autocomplete_costs: HashMap<char, u64> =
         [('(', 1u64), ('[', 2u64), ('{', 3u64), ('<', 4u64)]
             .iter()
@@ -39,14 +39,17 @@ fn compute_autocomplete_cost(input: &[char]) -> u64 {
     let mut autocomplete_cost = 0u64;
 
     for curr_open in autocomplete_stack {
-        autocomplete_cost *= 5;
-        autocomplete_cost += autocomplete_costs.get(curr_open).unwrap();
+        autocomplete_cost = autocomplete_cost
+            .checked_mul(5)?
+            .checked_add(autocomplete_costs[curr_open])?;
     }
 
-    autocomplete_cost
+    Some(autocomplete_cost)
 }
 
-fn compute_syntax_scores(input: &str) -> (u64, u64) {
+// The syntax error score, and the middle autocomplete score if there are
+// incomplete lines to score
+fn compute_syntax_scores(input: &str) -> Result<(u64, Option<u64>), AocError> {
     let error_score: HashMap<char, u64> =
         [(')', 3u64), ('>', 25137u64), ('}', 1197u64), (']', 57u64)]
             .iter()
@@ -55,30 +58,48 @@ fn compute_syntax_scores(input: &str) -> (u64, u64) {
     let mut syntax_score = 0u64;
     let mut autocomplete_costs = Vec::new();
     for syntax_line in input.split_whitespace() {
+        if let Some(stray) = syntax_line.chars().find(|c| !"()[]{}<>".contains(*c)) {
+            return Err(AocError::Parse(format!(
+                "\"{}\" is not a bracket, in \"{}\"",
+                stray, syntax_line
+            )));
+        }
         match syntax_line_check(syntax_line) {
-            CheckResult::Wrong(wrong_char) => syntax_score += error_score.get(&wrong_char).unwrap(),
+            CheckResult::Wrong(wrong_char) => syntax_score += error_score[&wrong_char],
             CheckResult::Incomlete(remaining_string) => {
                 autocomplete_costs.push(compute_autocomplete_cost(&remaining_string));
             }
         }
     }
 
+    let mut autocomplete_costs: Vec<u64> = match autocomplete_costs.into_iter().collect() {
+        Some(costs) => costs,
+        None => return Ok((syntax_score, None)),
+    };
     autocomplete_costs.sort_unstable();
 
-    (
+    Ok((
         syntax_score,
-        autocomplete_costs[autocomplete_costs.len() / 2],
-    )
+        autocomplete_costs.get(autocomplete_costs.len() / 2).copied(),
+    ))
+}
+
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    Ok(Answer::from(compute_syntax_scores(input)?.0))
 }
 
-pub fn part1(input: &str) {
-    let (syntax_error_score, autocomplete_cost) = compute_syntax_scores(input);
-    println!(
-        "Syntax error score: {}; Autocomplete cost: {}",
-        syntax_error_score, autocomplete_cost
-    );
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let middle = compute_syntax_scores(input)?.1.ok_or_else(|| {
+        AocError::Unsolvable(String::from(
+            "No incomplete lines to score, or a score that doesn't fit in 64 bits",
+        ))
+    })?;
+
+    Ok(Answer::from(middle))
 }
 
+aoc_day!(2021, 10, part1, part2);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -96,7 +117,7 @@ mod tests {
                             <{([([[(<>()){}]>(<<{{
                             <{([{{}}[<[[[<>{}]]]>[]]";
 
-        let (syntax_error_score, _) = compute_syntax_scores(input_string);
+        let (syntax_error_score, _) = compute_syntax_scores(input_string).unwrap();
 
         assert_eq!(syntax_error_score, 26397u64);
     }
@@ -114,8 +135,8 @@ mod tests {
                             <{([([[(<>()){}]>(<<{{
                             <{([{{}}[<[[[<>{}]]]>[]]";
 
-        let (_, autocomplete_cost) = compute_syntax_scores(input_string);
+        let (_, autocomplete_cost) = compute_syntax_scores(input_string).unwrap();
 
-        assert_eq!(autocomplete_cost, 288957u64);
+        assert_eq!(autocomplete_cost, Some(288957u64));
     }
 }
@@ -4,6 +4,8 @@ use std::fmt;
 
 use itertools::Itertools;
 
+use crate::aoc_lib::prelude::*;
+
 type Point = (usize, usize);
 
 struct OctopusGrid {
@@ -14,7 +16,7 @@ struct OctopusGrid {
 }
 
 impl OctopusGrid {
-    pub fn new(input: &str) -> OctopusGrid {
+    pub fn new(input: &str) -> Result<OctopusGrid, String> {
         let grid_rows = input.split_whitespace();
         let mut flat_data = Vec::new();
         let mut energy_to_location: [HashSet<Point>; 11] = [(); 11].map(|_| Default::default());
@@ -22,21 +24,32 @@ impl OctopusGrid {
         let mut row_count = 0usize;
         let mut col_count = 0usize;
         for (row, row_string) in grid_rows.enumerate() {
+            if row_count > 0 && row_string.len() != col_count {
+                return Err(format!("The row \"{}\" isn't {} long", row_string, col_count));
+            }
             row_count += 1;
             col_count = row_string.len();
             for (col, risk) in row_string.chars().enumerate() {
-                let risk_digit: u8 = risk.to_digit(10).unwrap().try_into().unwrap();
+                let risk_digit: u8 = risk
+                    .to_digit(10)
+                    .ok_or_else(|| format!("\"{}\" is not an energy level", risk))?
+                    .try_into()
+                    .unwrap();
                 energy_to_location[usize::from(risk_digit)].insert((row, col));
                 flat_data.push(risk_digit);
             }
         }
 
-        OctopusGrid {
+        if row_count == 0 {
+            return Err(String::from("There are no octopi"));
+        }
+
+        Ok(OctopusGrid {
             data: flat_data,
             rows: row_count,
             columns: col_count,
             energy_to_location,
-        }
+        })
     }
 
     fn compute_flat_idx(&self, location: &Point) -> usize {
@@ -141,14 +154,20 @@ impl OctopusGrid {
         (0..t).fold(0u64, |sum, _| sum + self.step())
     }
 
-    pub fn first_coordinated_flash(&mut self) -> u64 {
+    // None when the octopi go back to how they were before flashing together,
+    // then they never will
+    pub fn first_coordinated_flash(&mut self) -> Option<u64> {
         let mut step = 1u64;
         let area: u64 = (self.rows * self.columns).try_into().unwrap();
+        let mut seen: HashSet<Vec<u8>> = HashSet::new();
         while self.step() != area {
+            if !seen.insert(self.data.clone()) {
+                return None;
+            }
             step += 1;
         }
 
-        step
+        Some(step)
     }
 }
 
@@ -178,18 +197,22 @@ impl fmt::Display for OctopusGrid {
     }
 }
 
-pub fn part1(input: &str) {
-    let mut octo_grid = OctopusGrid::new(input);
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let mut octo_grid = OctopusGrid::new(input)?;
     let final_flashes = octo_grid.step_for(100);
-    println!("Flashes after 100 steps: {}", final_flashes);
+    Ok(Answer::from(final_flashes))
 }
 
-pub fn part2(input: &str) {
-    let mut octo_grid = OctopusGrid::new(input);
-    let synchronization_flash = octo_grid.first_coordinated_flash();
-    println!("First synchronized step: {}", synchronization_flash);
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let mut octo_grid = OctopusGrid::new(input)?;
+    let synchronization_flash = octo_grid.first_coordinated_flash().ok_or_else(|| {
+        AocError::Unsolvable(String::from("The octopi never flash all together"))
+    })?;
+    Ok(Answer::from(synchronization_flash))
 }
 
+aoc_day!(2021, 11, part1, part2);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -202,7 +225,7 @@ mod tests {
                             19991
                             11111";
 
-        let mut octo_grid = OctopusGrid::new(input_string);
+        let mut octo_grid = OctopusGrid::new(input_string).unwrap();
 
         assert_eq!(octo_grid.step_for(2), 9u64);
     }
@@ -220,7 +243,7 @@ mod tests {
                             4846848554
                             5283751526";
 
-        let mut octo_grid = OctopusGrid::new(input_string);
+        let mut octo_grid = OctopusGrid::new(input_string).unwrap();
 
         let ten_steps = octo_grid.step_for(10);
 
@@ -244,8 +267,9 @@ mod tests {
                             4846848554
                             5283751526";
 
-        let mut octo_grid = OctopusGrid::new(input_string);
+        let mut octo_grid = OctopusGrid::new(input_string).unwrap();
 
-        assert_eq!(octo_grid.first_coordinated_flash(), 195u64);
+        assert_eq!(octo_grid.first_coordinated_flash(), Some(195u64));
+        assert!(OctopusGrid::new("543\n27").is_err());
     }
 }
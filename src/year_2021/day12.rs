@@ -4,17 +4,16 @@ use std::convert::TryFrom;
 
 use nom::bytes::complete::tag;
 use nom::character::complete::alphanumeric1;
-use nom::combinator::map;
+use nom::combinator::{all_consuming, map};
 use nom::sequence::separated_pair;
 use nom::IResult;
 
+use crate::aoc_lib::prelude::*;
+
 // Edge parser
 fn edge(input: &str) -> IResult<&str, (&str, &str)> {
     let parser = separated_pair(alphanumeric1, tag("-"), alphanumeric1);
-    map(parser, |s| {
-        // FIXME: unwrap() may panic if the value is out of range
-        (s.0, s.1)
-    })(input)
+    map(parser, |s| (s.0, s.1))(input)
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -32,12 +31,18 @@ struct CaveNetwork {
 }
 
 impl CaveNetwork {
-    pub fn new(input: &str) -> CaveNetwork {
+    pub fn new(input: &str) -> Result<CaveNetwork, String> {
         let mut edge_map: HashMap<String, Vec<CaveType>> = HashMap::new();
         let mut small_set: HashSet<String> = HashSet::new();
 
         for line in input.lines() {
-            let (_, (start, end)) = edge(line.trim()).expect("Something went super wrong!");
+            let (_, (start, end)) = all_consuming(edge)(line.trim())
+                .map_err(|_| format!("Invalid tunnel: \"{}\"", line.trim()))?;
+            // Going back and forth between big caves never ends
+            let big = |cave: &str| cave.chars().any(|c| !c.is_lowercase());
+            if big(start) && big(end) {
+                return Err(format!("Two big caves are connected: \"{}\"", line.trim()));
+            }
             let end_type = if end.eq("start") {
                 CaveType::Start
             } else if end.eq("end") {
@@ -83,10 +88,19 @@ impl CaveNetwork {
             }
         }
 
-        CaveNetwork {
+        if !edge_map.contains_key("start") {
+            return Err(String::from("No tunnel leaves from the start"));
+        }
+
+        Ok(CaveNetwork {
             edges: edge_map,
             small_caves: small_set,
-        }
+        })
+    }
+
+    // Only the start has no tunnels back to it
+    fn next_caves(&self, cave: &str) -> &[CaveType] {
+        self.edges.get(cave).map_or(&[], Vec::as_slice)
     }
 
     fn find_unique_paths(&self, repeatable_cave: &str) -> HashSet<String> {
@@ -101,7 +115,7 @@ impl CaveNetwork {
         while let Some(current_cave) = visit_queue.pop_front() {
             match current_cave {
                 (_, CaveType::Start) => {
-                    let first_caves = self.edges.get("start").unwrap();
+                    let first_caves = self.next_caves("start");
                     for (path, next_cave) in first_caves.iter().enumerate() {
                         assert_eq!(path_small_caves_visit.len(), path);
                         path_small_caves_visit.push(HashSet::new());
@@ -132,8 +146,10 @@ impl CaveNetwork {
                             *path_repeated_cave.get_mut(path).unwrap() = true;
                         }
 
-                        let next_caves = self.edges.get(&cave_name).unwrap();
-                        visit_queue.push_back((path, next_caves.first().unwrap().clone()));
+                        let next_caves = self.next_caves(&cave_name);
+                        if let Some(next_cave) = next_caves.first() {
+                            visit_queue.push_back((path, next_cave.clone()));
+                        }
                         debug_path.get_mut(path).unwrap().push(cave_name);
 
                         for next_cave in next_caves.iter().skip(1) {
@@ -149,8 +165,10 @@ impl CaveNetwork {
                     }
                 }
                 (path, CaveType::Big(cave_name)) => {
-                    let next_caves = self.edges.get(&cave_name).unwrap();
-                    visit_queue.push_back((path, next_caves.first().unwrap().clone()));
+                    let next_caves = self.next_caves(&cave_name);
+                    if let Some(next_cave) = next_caves.first() {
+                        visit_queue.push_back((path, next_cave.clone()));
+                    }
                     debug_path.get_mut(path).unwrap().push(cave_name);
 
                     for next_cave in next_caves.iter().skip(1) {
@@ -181,21 +199,20 @@ impl CaveNetwork {
     }
 }
 
-pub fn part1(input: &str) {
-    let cave_net = CaveNetwork::new(input);
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let cave_net = CaveNetwork::new(input)?;
     let path_count = cave_net.find_paths(false);
-    println!("Amount of unique paths to the exit: {}", path_count);
+    Ok(Answer::from(path_count))
 }
 
-pub fn part2(input: &str) {
-    let cave_net = CaveNetwork::new(input);
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let cave_net = CaveNetwork::new(input)?;
     let path_count = cave_net.find_paths(true);
-    println!(
-        "Amount of unique paths to the exit considering repeating caves: {}",
-        path_count
-    );
+    Ok(Answer::from(path_count))
 }
 
+aoc_day!(2021, 12, part1, part2);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -210,7 +227,7 @@ mod tests {
                         A-end
                         b-end";
 
-        let cave_net = CaveNetwork::new(input_string);
+        let cave_net = CaveNetwork::new(input_string).unwrap();
         let path_count = cave_net.find_paths(false);
 
         assert_eq!(path_count, 10u64);
@@ -226,7 +243,7 @@ mod tests {
                         A-end
                         b-end";
 
-        let cave_net = CaveNetwork::new(input_string);
+        let cave_net = CaveNetwork::new(input_string).unwrap();
         let path_count = cave_net.find_paths(true);
 
         assert_eq!(path_count, 36u64);
@@ -245,7 +262,7 @@ mod tests {
                         kj-HN
                         kj-dc";
 
-        let cave_net = CaveNetwork::new(input_string);
+        let cave_net = CaveNetwork::new(input_string).unwrap();
         let path_count = cave_net.find_paths(false);
 
         assert_eq!(path_count, 19u64);
@@ -264,7 +281,7 @@ mod tests {
                         kj-HN
                         kj-dc";
 
-        let cave_net = CaveNetwork::new(input_string);
+        let cave_net = CaveNetwork::new(input_string).unwrap();
         let path_count = cave_net.find_paths(true);
 
         assert_eq!(path_count, 103u64);
@@ -291,7 +308,7 @@ mod tests {
                         pj-fs
                         start-RW";
 
-        let cave_net = CaveNetwork::new(input_string);
+        let cave_net = CaveNetwork::new(input_string).unwrap();
         let path_count = cave_net.find_paths(false);
 
         assert_eq!(path_count, 226u64);
@@ -318,7 +335,7 @@ mod tests {
                         pj-fs
                         start-RW";
 
-        let cave_net = CaveNetwork::new(input_string);
+        let cave_net = CaveNetwork::new(input_string).unwrap();
         let path_count = cave_net.find_paths(true);
 
         assert_eq!(path_count, 3509u64);
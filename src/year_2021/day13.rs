@@ -4,29 +4,30 @@ use std::convert::TryFrom;
 
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::character::complete::{digit1, one_of, space0};
+use nom::character::complete::{one_of, space0};
+use nom::combinator::all_consuming;
 use nom::sequence::{preceded, separated_pair};
-use nom::{IResult, ParseTo};
+use nom::IResult;
+
+use crate::aoc_lib::prelude::*;
 
 // Activation instructions parsers
 fn point_location(input: &str) -> IResult<&str, ActivationInstruction> {
-    let (rem_input, (x, y)) = separated_pair(digit1, tag(","), digit1)(input)?;
+    let (rem_input, (x, y)) =
+        separated_pair(jazz_parser::usize, tag(","), jazz_parser::usize)(input)?;
 
-    Ok((
-        rem_input,
-        ActivationInstruction::Point((x.parse_to().unwrap(), y.parse_to().unwrap())),
-    ))
+    Ok((rem_input, ActivationInstruction::Point((x, y))))
 }
 
 fn fold_instruction(input: &str) -> IResult<&str, ActivationInstruction> {
     let (useful_input, _) = tag("fold along ")(input)?;
     let (rem_input, (direction, coord)) =
-        separated_pair(one_of("xy"), tag("="), digit1)(useful_input)?;
+        separated_pair(one_of("xy"), tag("="), jazz_parser::usize)(useful_input)?;
 
     let fold = if direction == 'x' {
-        OrigamiFold::Vertical(coord.parse_to().unwrap())
+        OrigamiFold::Vertical(coord)
     } else {
-        OrigamiFold::Horizontal(coord.parse_to().unwrap())
+        OrigamiFold::Horizontal(coord)
     };
 
     Ok((rem_input, ActivationInstruction::Fold(fold)))
@@ -58,12 +59,13 @@ struct ActivationData {
 }
 
 impl ActivationData {
-    pub fn new(input: &str) -> ActivationData {
+    pub fn new(input: &str) -> Result<ActivationData, String> {
         let mut rows_init: BTreeMap<usize, HashSet<usize>> = BTreeMap::new();
         let mut cols_init: BTreeMap<usize, HashSet<usize>> = BTreeMap::new();
         let mut fold_q: VecDeque<OrigamiFold> = VecDeque::new();
         for line in input.lines().filter(|l| !l.is_empty()) {
-            let (_, parsed_line) = activation_instruction(line).unwrap();
+            let (_, parsed_line) = all_consuming(activation_instruction)(line)
+                .map_err(|_| format!("Invalid instruction: \"{}\"", line.trim()))?;
             match parsed_line {
                 ActivationInstruction::Point((x, y)) => {
                     if let Some(row_entry) = rows_init.get_mut(&y) {
@@ -84,32 +86,47 @@ impl ActivationData {
             }
         }
 
-        ActivationData {
+        if rows_init.is_empty() || fold_q.is_empty() {
+            return Err(String::from("The paper needs dots and folds"));
+        }
+
+        Ok(ActivationData {
             rows: rows_init,
             cols: cols_init,
             folding_queue: fold_q,
-        }
+        })
     }
 
-    pub fn fold_once(&mut self) {
-        match self.folding_queue.pop_front().unwrap() {
-            OrigamiFold::Horizontal(row) => self.horizontal_fold(row),
-            OrigamiFold::Vertical(col) => self.vertical_fold(col),
+    pub fn fold_once(&mut self) -> Result<(), String> {
+        match self.folding_queue.pop_front() {
+            Some(OrigamiFold::Horizontal(row)) => self.horizontal_fold(row),
+            Some(OrigamiFold::Vertical(col)) => self.vertical_fold(col),
+            None => Ok(()),
         }
     }
 
-    pub fn fold_all(&mut self) {
+    pub fn fold_all(&mut self) -> Result<(), String> {
         while !self.folding_queue.is_empty() {
-            self.fold_once();
+            self.fold_once()?;
         }
+
+        Ok(())
     }
 
-    fn vertical_fold(&mut self, col: usize) {
+    // The dots past twice the fold would end up off the paper
+    fn vertical_fold(&mut self, col: usize) -> Result<(), String> {
         let mut removed_cols: Vec<usize> = Vec::new();
         let mut moved_data_cols: HashMap<usize, Vec<usize>> = HashMap::new();
         let mut moved_data_rows: HashMap<usize, Vec<(usize, usize)>> = HashMap::new();
         let (&max_col, _) = self.cols.iter().next_back().unwrap();
 
+        if col > max_col {
+            return Ok(());
+        }
+        if max_col > col * 2 {
+            return Err(format!("Folding along x={} leaves column {} out", col, max_col));
+        }
+
         for (col_idx, points) in self.cols.range_mut(col..=max_col) {
             let target_col_idx = col * 2 - col_idx;
             moved_data_cols.insert(target_col_idx, points.iter().cloned().collect());
@@ -126,7 +143,7 @@ impl ActivationData {
         }
 
         for (new_col_idx, points) in moved_data_cols {
-            let target_col = self.cols.entry(new_col_idx).or_insert_with(HashSet::new);
+            let target_col = self.cols.entry(new_col_idx).or_default();
             target_col.extend(points.iter());
         }
 
@@ -137,14 +154,23 @@ impl ActivationData {
                 row.insert(dst);
             }
         }
+
+        Ok(())
     }
 
-    fn horizontal_fold(&mut self, row: usize) {
+    fn horizontal_fold(&mut self, row: usize) -> Result<(), String> {
         let mut removed_rows: Vec<usize> = Vec::new();
         let mut moved_data_rows: HashMap<usize, Vec<usize>> = HashMap::new();
         let mut moved_data_cols: HashMap<usize, Vec<(usize, usize)>> = HashMap::new();
         let (&max_row, _) = self.rows.iter().next_back().unwrap();
 
+        if row > max_row {
+            return Ok(());
+        }
+        if max_row > row * 2 {
+            return Err(format!("Folding along y={} leaves row {} out", row, max_row));
+        }
+
         for (row_idx, points) in self.rows.range_mut(row..=max_row) {
             let target_row_idx = row * 2 - row_idx;
             moved_data_rows.insert(target_row_idx, points.iter().cloned().collect());
@@ -161,7 +187,7 @@ impl ActivationData {
         }
 
         for (new_row_idx, points) in moved_data_rows {
-            let target_row = self.rows.entry(new_row_idx).or_insert_with(HashSet::new);
+            let target_row = self.rows.entry(new_row_idx).or_default();
             target_row.extend(points.iter());
         }
 
@@ -172,6 +198,8 @@ impl ActivationData {
                 col.insert(dst);
             }
         }
+
+        Ok(())
     }
 
     pub fn get_unique_points(self) -> u64 {
@@ -180,45 +208,47 @@ impl ActivationData {
         })
     }
 
-    pub fn print_activation(self) {
+    pub fn draw_activation(self) -> String {
         let (&max_row, _) = self.rows.iter().next_back().unwrap();
         let (&max_col, _) = self.cols.iter().next_back().unwrap();
 
+        let mut drawing = String::new();
         for row_idx in 0..=max_row {
             if let Some(row) = self.rows.get(&row_idx) {
                 for col in 0..=max_col {
                     if row.get(&col).is_some() {
-                        print!("#");
+                        drawing.push('#');
                     } else {
-                        print!(" ");
+                        drawing.push(' ');
                     }
                 }
             } else {
                 for _ in 0..max_col {
-                    print!(" ");
+                    drawing.push(' ');
                 }
             }
-            println!();
+            drawing.push('\n');
         }
+
+        drawing
     }
 }
 
-pub fn part1(input: &str) {
-    let mut data = ActivationData::new(input);
-    data.fold_once();
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let mut data = ActivationData::new(input)?;
+    data.fold_once()?;
     let point_count = data.get_unique_points();
-    println!("Amount of unique points after one fold: {}", point_count);
+    Ok(Answer::from(point_count))
 }
 
-pub fn part2(input: &str) {
-    let mut data = ActivationData::new(input);
-    data.fold_all();
-    println!("Activation paper after folding:");
-    println!();
-    data.print_activation();
-    println!();
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let mut data = ActivationData::new(input)?;
+    data.fold_all()?;
+    Ok(Answer::Grid(data.draw_activation()))
 }
 
+aoc_day!(2021, 13, part1, part2);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -247,7 +277,7 @@ mod tests {
         fold along y=7
         fold along x=5";
 
-        let data = ActivationData::new(input_string);
+        let data = ActivationData::new(input_string).unwrap();
 
         assert_eq!(data.get_unique_points(), 18u64);
     }
@@ -276,8 +306,8 @@ mod tests {
         fold along y=7
         fold along x=5";
 
-        let mut data = ActivationData::new(input_string);
-        data.fold_once();
+        let mut data = ActivationData::new(input_string).unwrap();
+        data.fold_once().unwrap();
 
         assert_eq!(data.get_unique_points(), 17u64);
     }
@@ -306,8 +336,16 @@ mod tests {
         fold along y=7
         fold along x=5";
 
-        let mut data = ActivationData::new(input_string);
-        data.fold_all();
+        let mut data = ActivationData::new(input_string).unwrap();
+        data.fold_all().unwrap();
+        assert!(ActivationData::new("6,10\n\nfold along y=3")
+            .unwrap()
+            .fold_all()
+            .is_err());
+        assert!(ActivationData::new("6,10\n\nfold along y=13")
+            .unwrap()
+            .fold_all()
+            .is_ok());
 
         assert_eq!(data.get_unique_points(), 16u64);
     }
@@ -4,9 +4,12 @@ use itertools::{Itertools, MinMaxResult};
 
 use nom::bytes::complete::tag;
 use nom::character::complete::{alpha1, space0};
+use nom::combinator::all_consuming;
 use nom::sequence::{preceded, separated_pair};
 use nom::IResult;
 
+use crate::aoc_lib::prelude::*;
+
 // Polymer evolution parser
 fn insertion_rule(input: &str) -> IResult<&str, (&str, &str)> {
     preceded(space0, separated_pair(alpha1, tag(" -> "), alpha1))(input)
@@ -20,13 +23,19 @@ struct PolymerData {
 }
 
 impl PolymerData {
-    pub fn new(input: &str) -> PolymerData {
+    pub fn new(input: &str) -> Result<PolymerData, String> {
         let mut polymer_lines = input.lines();
-        let polymer_template = polymer_lines.next().unwrap();
+        let polymer_template = polymer_lines.next().unwrap_or_default().trim();
+        if polymer_template.is_empty() || !polymer_template.chars().all(|c| c.is_alphabetic()) {
+            return Err(format!("Invalid template: \"{}\"", polymer_template));
+        }
+        if !polymer_lines.next().is_some_and(|line| line.trim().is_empty()) {
+            return Err(String::from("The template should be followed by a blank line"));
+        }
 
         let mut initial_frequencies: HashMap<String, u64> = HashMap::new();
         for (first, second) in polymer_template.chars().tuple_windows() {
-            let pair: String = vec![first, second].iter().collect();
+            let pair: String = [first, second].iter().collect();
             if let Some(freq) = initial_frequencies.get_mut(&pair) {
                 *freq += 1;
             } else {
@@ -35,16 +44,23 @@ impl PolymerData {
         }
 
         let mut rules: HashMap<String, char> = HashMap::new();
-        for line in polymer_lines.skip(1) {
-            let (_, (pair, result)) = insertion_rule(line).unwrap();
-            rules.insert(pair.to_string(), result.chars().next().unwrap());
+        for line in polymer_lines {
+            let invalid = || format!("Invalid insertion rule: \"{}\"", line.trim());
+            let (_, (pair, result)) = all_consuming(insertion_rule)(line).map_err(|_| invalid())?;
+            let (Some(inserted), 2, 1) = (result.chars().next(), pair.len(), result.len()) else {
+                return Err(invalid());
+            };
+            rules.insert(pair.to_string(), inserted);
+        }
+        if rules.is_empty() {
+            return Err(String::from("There are no insertion rules"));
         }
 
-        PolymerData {
+        Ok(PolymerData {
             template_polymer: polymer_template.to_string(),
             insertion_rules: rules,
             pair_frequencies: initial_frequencies,
-        }
+        })
     }
 
     fn step(&mut self) {
@@ -53,8 +69,8 @@ impl PolymerData {
         for (pair, freq) in self.pair_frequencies.iter() {
             if let Some(evo) = self.insertion_rules.get(pair) {
                 let (first_char, second_char) = pair.chars().next_tuple().unwrap();
-                let first_pair: String = vec![first_char, *evo].iter().collect();
-                let second_pair: String = vec![*evo, second_char].iter().collect();
+                let first_pair: String = [first_char, *evo].iter().collect();
+                let second_pair: String = [*evo, second_char].iter().collect();
                 if let Some(first_freq) = step_result.get_mut(&first_pair) {
                     *first_freq += freq;
                 } else {
@@ -95,36 +111,32 @@ impl PolymerData {
             frequencies.insert(last_element, 1u64);
         }
 
-        let delta: u64 = if let MinMaxResult::MinMax(min, max) = frequencies.values().minmax() {
-            max - min
-        } else {
-            panic!("For some reason we didn't find a min and max in the values!");
+        // A polymer of one element has nothing to compare it with
+        let delta: u64 = match frequencies.values().minmax() {
+            MinMaxResult::MinMax(min, max) => max - min,
+            _ => 0,
         };
 
         (delta, frequencies)
     }
 }
 
-pub fn part1(input: &str) {
-    let mut data = PolymerData::new(input);
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let mut data = PolymerData::new(input)?;
     data.evolve_polymer(10);
     let (elements_delta, _) = data.compute_elements_delta();
-    println!(
-        "Delta between highest and lowest frequency elements after 10 steps: {}",
-        elements_delta
-    );
+    Ok(Answer::from(elements_delta))
 }
 
-pub fn part2(input: &str) {
-    let mut data = PolymerData::new(input);
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let mut data = PolymerData::new(input)?;
     data.evolve_polymer(40);
     let (elements_delta, _) = data.compute_elements_delta();
-    println!(
-        "Delta between highest and lowest frequency elements after 40 steps: {}",
-        elements_delta
-    );
+    Ok(Answer::from(elements_delta))
 }
 
+aoc_day!(2021, 14, part1, part2);
+
 #[cfg(test)]
 mod tests {
     use std::iter::FromIterator;
@@ -152,7 +164,7 @@ mod tests {
         CC -> N
         CN -> C";
 
-        let mut data = PolymerData::new(input_string);
+        let mut data = PolymerData::new(input_string).unwrap();
         data.evolve_polymer(1);
 
         // Resulting polymer: NCNBCHB
@@ -189,7 +201,7 @@ mod tests {
         CC -> N
         CN -> C";
 
-        let mut data = PolymerData::new(input_string);
+        let mut data = PolymerData::new(input_string).unwrap();
         data.evolve_polymer(4);
 
         // Resulting polymer: NBBNBNBBCCNBCNCCNBBNBBNBBBNBBNBBCBHCBHHNHCBBCBHCB
@@ -226,7 +238,7 @@ mod tests {
         CC -> N
         CN -> C";
 
-        let mut data = PolymerData::new(input_string);
+        let mut data = PolymerData::new(input_string).unwrap();
         data.evolve_polymer(10);
 
         // Resulting polymer: ??? (Too long)
@@ -4,6 +4,8 @@ use std::collections::BinaryHeap;
 use std::convert::TryInto;
 use std::fmt;
 
+use crate::aoc_lib::prelude::*;
+
 type Point = (usize, usize);
 type GridCoord = (u8, u8);
 
@@ -43,26 +45,37 @@ struct RiskGrid {
 }
 
 impl RiskGrid {
-    pub fn new(input: &str) -> RiskGrid {
+    pub fn new(input: &str) -> Result<RiskGrid, String> {
         let grid_rows = input.split_whitespace();
         let mut flat_data = Vec::new();
 
         let mut row_count = 0usize;
         let mut col_count = 0usize;
         for row_string in grid_rows {
+            if row_count > 0 && row_string.len() != col_count {
+                return Err(format!("The row \"{}\" isn't {} long", row_string, col_count));
+            }
             row_count += 1;
             col_count = row_string.len();
             for risk in row_string.chars() {
-                let risk_digit: u8 = risk.to_digit(10).unwrap().try_into().unwrap();
+                let risk_digit: u8 = risk
+                    .to_digit(10)
+                    .ok_or_else(|| format!("\"{}\" is not a risk level", risk))?
+                    .try_into()
+                    .unwrap();
                 flat_data.push(risk_digit);
             }
         }
 
-        RiskGrid {
+        if row_count == 0 {
+            return Err(String::from("There is no cave to cross"));
+        }
+
+        Ok(RiskGrid {
             data: flat_data,
             rows: row_count,
             columns: col_count,
-        }
+        })
     }
 
     fn compute_flat_idx(&self, location: &Point) -> usize {
@@ -142,11 +155,12 @@ impl RiskGrid {
         (actual_a_star_row, actual_a_star_col)
     }
 
+    // None when the start is the end, there's no step to take
     pub fn find_lowest_risk_path(
         &self,
         start: &(Point, GridCoord),
         end: &(Point, GridCoord),
-    ) -> u64 {
+    ) -> Option<u64> {
         let mut visited_locations: HashSet<Point> = HashSet::new();
         let mut exploration_front: BinaryHeap<RiskStep> = BinaryHeap::new();
 
@@ -181,7 +195,7 @@ impl RiskGrid {
                 for ((coord, grid), risk) in neighbours {
                     let a_star_coord = self.compute_a_star_coord(&coord, &grid);
                     if coord == end.0 && grid == end.1 {
-                        return current_step.risk + u64::from(risk);
+                        return Some(current_step.risk + u64::from(risk));
                     }
 
                     let distance_cost: u64 = ((actual_a_end.0 - a_star_coord.0)
@@ -199,7 +213,7 @@ impl RiskGrid {
             }
         }
 
-        panic!("We shouldn't get here without reaching the end.");
+        None
     }
 }
 
@@ -219,29 +233,35 @@ impl fmt::Display for RiskGrid {
     }
 }
 
-pub fn part1(input: &str) {
-    let risk_grid = RiskGrid::new(input);
+fn no_step() -> AocError {
+    AocError::Unsolvable(String::from("The cave is a single position, there's no path"))
+}
+
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let risk_grid = RiskGrid::new(input)?;
     let start: Point = (0, 0);
     let end: Point = (risk_grid.rows - 1, risk_grid.columns - 1);
     let grid_coord: GridCoord = (0u8, 0u8);
-    println!(
-        "Lowest risk path sum: {}",
-        risk_grid.find_lowest_risk_path(&(start, grid_coord), &(end, grid_coord))
-    );
+    let risk = risk_grid
+        .find_lowest_risk_path(&(start, grid_coord), &(end, grid_coord))
+        .ok_or_else(no_step)?;
+    Ok(Answer::from(risk))
 }
 
-pub fn part2(input: &str) {
-    let risk_grid = RiskGrid::new(input);
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let risk_grid = RiskGrid::new(input)?;
     let start: Point = (0, 0);
     let end: Point = (risk_grid.rows - 1, risk_grid.columns - 1);
     let start_grid_coord: GridCoord = (0u8, 0u8);
     let end_grid_coord: GridCoord = (4u8, 4u8);
-    println!(
-        "Lowest risk path sum: {}",
-        risk_grid.find_lowest_risk_path(&(start, start_grid_coord), &(end, end_grid_coord))
-    );
+    let risk = risk_grid
+        .find_lowest_risk_path(&(start, start_grid_coord), &(end, end_grid_coord))
+        .ok_or_else(no_step)?;
+    Ok(Answer::from(risk))
 }
 
+aoc_day!(2021, 15, part1, part2);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -259,14 +279,14 @@ mod tests {
                             1293138521
                             2311944581";
 
-        let risk_grid = RiskGrid::new(input_string);
+        let risk_grid = RiskGrid::new(input_string).unwrap();
         let start: Point = (0, 0);
         let end: Point = (risk_grid.rows - 1, risk_grid.columns - 1);
         let grid_coord: GridCoord = (0u8, 0u8);
 
         assert_eq!(
             risk_grid.find_lowest_risk_path(&(start, grid_coord), &(end, grid_coord)),
-            40u64
+            Some(40u64)
         );
     }
 
@@ -283,7 +303,7 @@ mod tests {
                             1293138521
                             2311944581";
 
-        let risk_grid = RiskGrid::new(input_string);
+        let risk_grid = RiskGrid::new(input_string).unwrap();
         let start: Point = (0, 0);
         let end: Point = (risk_grid.rows - 1, risk_grid.columns - 1);
         let start_grid_coord: GridCoord = (0u8, 0u8);
@@ -291,7 +311,7 @@ mod tests {
 
         assert_eq!(
             risk_grid.find_lowest_risk_path(&(start, start_grid_coord), &(end, end_grid_coord)),
-            315u64
+            Some(315u64)
         );
     }
 }
@@ -1,7 +1,7 @@
-use std::iter::FromIterator;
-
 use itertools::Itertools;
 
+use crate::aoc_lib::prelude::*;
+
 #[derive(Eq, PartialEq, Debug)]
 enum Packet {
     Literal(LiteralPayload),
@@ -46,9 +46,10 @@ struct OperatorPayload {
     size: usize,
 }
 
-fn hex_payload_to_binary(input: &str) -> String {
+fn hex_payload_to_binary(input: &str) -> Result<String, String> {
     let mut binary_payload: String = String::new();
     for c in input
+        .trim()
         .to_uppercase()
         .chars()
         .take_while(|c| !c.is_whitespace())
@@ -71,63 +72,64 @@ fn hex_payload_to_binary(input: &str) -> String {
             'E' => "1110",
             'F' => "1111",
             _ => {
-                panic!("Provided a value that's not an hexadecimal digit")
+                return Err(format!(
+                    "Provided a value that's not an hexadecimal digit: {}",
+                    c
+                ))
             }
         };
         binary_payload.push_str(binary);
     }
-    binary_payload
+    Ok(binary_payload)
+}
+
+// The number in `length` bits from `start`, if the transmission is that long
+fn read_bits(input: &str, start: usize, length: usize) -> Result<u64, String> {
+    input
+        .get(start..start + length)
+        .and_then(|bits| u64::from_str_radix(bits, 2).ok())
+        .ok_or_else(|| String::from("The transmission ends in the middle of a packet"))
 }
 
-fn parse_packet(input: &str) -> Packet {
-    let type_string: String = input.chars().skip(3).take(3).collect();
-    let type_id = u8::from_str_radix(&type_string, 2).unwrap();
+fn parse_packet(input: &str) -> Result<Packet, String> {
+    let type_id = read_bits(input, 3, 3)?;
 
-    if type_id == 4u8 {
-        Packet::Literal(parse_literal(input))
+    if type_id == 4 {
+        Ok(Packet::Literal(parse_literal(input)?))
     } else {
-        Packet::Operator(parse_operator(input))
+        Ok(Packet::Operator(parse_operator(input)?))
     }
 }
 
-fn parse_literal(input: &str) -> LiteralPayload {
-    let version_string: String = input.chars().take(3).collect();
-    let version = u8::from_str_radix(&version_string, 2).unwrap();
+fn parse_literal(input: &str) -> Result<LiteralPayload, String> {
+    let version = read_bits(input, 0, 3)? as u8;
     let mut final_value = 0u64;
-    let mut last_chunk = 0usize;
-    for (chunk_idx, chunk) in input[6..]
-        .chars()
-        .chunks(5)
-        .into_iter()
-        .map(String::from_iter)
-        .enumerate()
-    {
-        let chunk_value = u64::from_str_radix(&chunk, 2).unwrap();
+    let mut chunk_idx = 0usize;
+    loop {
+        let chunk_value = read_bits(input, 6 + chunk_idx * 5, 5)?;
         let value: u64 = chunk_value & !0b10000;
         final_value <<= 4;
         final_value += value;
+        chunk_idx += 1;
         if chunk_value & 0b10000 == 0 {
-            last_chunk = chunk_idx + 1;
             break;
         }
     }
 
-    LiteralPayload {
+    Ok(LiteralPayload {
         version,
         value: final_value,
-        size: 6usize + last_chunk * 5usize,
-    }
+        size: 6usize + chunk_idx * 5usize,
+    })
 }
 
-fn parse_operator(input: &str) -> OperatorPayload {
-    let version_string: String = input.chars().take(3).collect();
-    let version = u8::from_str_radix(&version_string, 2).unwrap();
+fn parse_operator(input: &str) -> Result<OperatorPayload, String> {
+    let version = read_bits(input, 0, 3)? as u8;
 
     let actual_input = &input[6..];
     let size_in_chars = actual_input.starts_with('0');
     let size_displacement = if size_in_chars { 15usize } else { 11usize };
-    let mut remaining_data =
-        usize::from_str_radix(&actual_input[1..size_displacement + 1], 2).unwrap();
+    let mut remaining_data = read_bits(actual_input, 1, size_displacement)? as usize;
 
     let mut sub_packets: Vec<Packet> = Vec::new();
 
@@ -135,13 +137,19 @@ fn parse_operator(input: &str) -> OperatorPayload {
         let sub_size: usize = sub_packets.iter().map(Packet::get_size).sum();
         let sub_start = sub_size + size_displacement;
 
-        let sub_package = parse_packet(&actual_input[sub_start + 1..]);
+        let sub_input = actual_input
+            .get(sub_start + 1..)
+            .ok_or_else(|| String::from("The transmission ends before the sub-packets"))?;
+        let sub_package = parse_packet(sub_input)?;
 
-        remaining_data -= if size_in_chars {
+        let used = if size_in_chars {
             sub_package.get_size()
         } else {
             1
         };
+        remaining_data = remaining_data
+            .checked_sub(used)
+            .ok_or_else(|| String::from("The sub-packets are longer than their operator"))?;
 
         sub_packets.push(sub_package);
     }
@@ -155,46 +163,56 @@ fn parse_operator(input: &str) -> OperatorPayload {
     let size: usize =
         sub_packets.iter().map(Packet::get_size).sum::<usize>() + size_displacement + 7usize;
 
-    let op_string: String = input.chars().skip(3).take(3).collect();
-    let op_id = u8::from_str_radix(&op_string, 2).unwrap();
-    let value: u64 = match op_id {
-        0u8 => sub_packets.iter().map(Packet::get_value).sum(),
-        1u8 => sub_packets.iter().map(Packet::get_value).product(),
-        2u8 => sub_packets.iter().map(Packet::get_value).min().unwrap(),
-        3u8 => sub_packets.iter().map(Packet::get_value).max().unwrap(),
-        5u8 => (sub_packets[0].get_value() > sub_packets[1].get_value()) as u64,
-        6u8 => (sub_packets[0].get_value() < sub_packets[1].get_value()) as u64,
-        7u8 => (sub_packets[0].get_value() == sub_packets[1].get_value()) as u64,
+    let op_id = read_bits(input, 3, 3)?;
+    let values = sub_packets.iter().map(Packet::get_value).collect_vec();
+    let too_big = || format!("The result of operation {} doesn't fit in 64 bits", op_id);
+    let value: u64 = match (op_id, values.as_slice()) {
+        (0, _) => values
+            .iter()
+            .try_fold(0u64, |sum, value| sum.checked_add(*value))
+            .ok_or_else(too_big)?,
+        (1, _) => values
+            .iter()
+            .try_fold(1u64, |product, value| product.checked_mul(*value))
+            .ok_or_else(too_big)?,
+        (2, [_, ..]) => *values.iter().min().unwrap(),
+        (3, [_, ..]) => *values.iter().max().unwrap(),
+        (5, [left, right]) => (left > right) as u64,
+        (6, [left, right]) => (left < right) as u64,
+        (7, [left, right]) => (left == right) as u64,
         _ => {
-            panic!("Invalid operation id {}", op_id)
+            return Err(format!(
+                "Operation {} can't take {} sub-packets",
+                op_id,
+                values.len()
+            ))
         }
     };
 
-    OperatorPayload {
+    Ok(OperatorPayload {
         version,
         value,
         total_version,
         size,
-    }
+    })
 }
 
-pub fn part1(input: &str) {
-    let input_binary = hex_payload_to_binary(input);
-    let parsed_package = parse_packet(&input_binary);
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let input_binary = hex_payload_to_binary(input)?;
+    let parsed_package = parse_packet(&input_binary)?;
 
-    println!(
-        "Sum of all the version numbers: {}",
-        parsed_package.get_total_version()
-    );
+    Ok(Answer::from(parsed_package.get_total_version()))
 }
 
-pub fn part2(input: &str) {
-    let input_binary = hex_payload_to_binary(input);
-    let parsed_package = parse_packet(&input_binary);
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let input_binary = hex_payload_to_binary(input)?;
+    let parsed_package = parse_packet(&input_binary)?;
 
-    println!("Computed value: {}", parsed_package.get_value());
+    Ok(Answer::from(parsed_package.get_value()))
 }
 
+aoc_day!(2021, 16, part1, part2);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -204,7 +222,7 @@ mod tests {
         let input_string = "D2FE28";
 
         assert_eq!(
-            hex_payload_to_binary(input_string),
+            hex_payload_to_binary(input_string).unwrap(),
             "110100101111111000101000"
         )
     }
@@ -212,7 +230,7 @@ mod tests {
     #[test]
     fn literal_parse() {
         let input_string = "D2FE28";
-        let input_binary = hex_payload_to_binary(input_string);
+        let input_binary = hex_payload_to_binary(input_string).unwrap();
 
         let comparison_packet = Packet::Literal(LiteralPayload {
             version: 6u8,
@@ -220,12 +238,12 @@ mod tests {
             size: 21usize,
         });
 
-        assert_eq!(comparison_packet, parse_packet(&input_binary));
+        assert_eq!(comparison_packet, parse_packet(&input_binary).unwrap());
     }
 
     #[test]
     fn basic_operator_char_size() {
-        let input_binary = hex_payload_to_binary("38006F45291200");
+        let input_binary = hex_payload_to_binary("38006F45291200").unwrap();
         // 001 110 0 000000000011011 110 100 01010 010 100 10001 00100 0000000
         // VVV TTT I LLLLLLLLLLLLLLL AAA AAA AAAAA BBB BBB BBBBB BBBBB XXXXXXX
 
@@ -236,12 +254,12 @@ mod tests {
             size: 49usize,
         });
 
-        assert_eq!(parse_packet(&input_binary), comparison_operator);
+        assert_eq!(parse_packet(&input_binary).unwrap(), comparison_operator);
     }
 
     #[test]
     fn basic_operator_sub_size() {
-        let input_binary = hex_payload_to_binary("EE00D40C823060");
+        let input_binary = hex_payload_to_binary("EE00D40C823060").unwrap();
         // 111 011 1 00000000011 010 100 00001 100 100 00010 001 100 00011 00000
         // VVV TTT I LLLLLLLLLLL AAA AAA AAAAA BBB BBB BBBBB CCC CCC CCCCC XXXXX
 
@@ -252,72 +270,80 @@ mod tests {
             size: 51usize,
         });
 
-        assert_eq!(parse_packet(&input_binary), comparison_operator);
+        assert_eq!(parse_packet(&input_binary).unwrap(), comparison_operator);
     }
 
     #[test]
     fn version_sum() {
-        let input_binary = hex_payload_to_binary("8A004A801A8002F478");
-        let operator = parse_packet(&input_binary);
+        let input_binary = hex_payload_to_binary("8A004A801A8002F478").unwrap();
+        let operator = parse_packet(&input_binary).unwrap();
 
         assert_eq!(operator.get_total_version(), 16u64);
 
-        let input_binary = hex_payload_to_binary("620080001611562C8802118E34");
-        let operator = parse_packet(&input_binary);
+        let input_binary = hex_payload_to_binary("620080001611562C8802118E34").unwrap();
+        let operator = parse_packet(&input_binary).unwrap();
 
         assert_eq!(operator.get_total_version(), 12u64);
 
-        let input_binary = hex_payload_to_binary("C0015000016115A2E0802F182340");
-        let operator = parse_packet(&input_binary);
+        let input_binary = hex_payload_to_binary("C0015000016115A2E0802F182340").unwrap();
+        let operator = parse_packet(&input_binary).unwrap();
 
         assert_eq!(operator.get_total_version(), 23u64);
 
-        let input_binary = hex_payload_to_binary("A0016C880162017C3686B18A3D4780");
-        let operator = parse_packet(&input_binary);
+        let input_binary = hex_payload_to_binary("A0016C880162017C3686B18A3D4780").unwrap();
+        let operator = parse_packet(&input_binary).unwrap();
 
         assert_eq!(operator.get_total_version(), 31u64);
     }
 
     #[test]
     fn compute_operators_results() {
-        let input_binary = hex_payload_to_binary("C200B40A82");
-        let operator = parse_packet(&input_binary);
+        let input_binary = hex_payload_to_binary("C200B40A82").unwrap();
+        let operator = parse_packet(&input_binary).unwrap();
 
         assert_eq!(operator.get_value(), 3u64);
 
-        let input_binary = hex_payload_to_binary("04005AC33890");
-        let operator = parse_packet(&input_binary);
+        let input_binary = hex_payload_to_binary("04005AC33890").unwrap();
+        let operator = parse_packet(&input_binary).unwrap();
 
         assert_eq!(operator.get_value(), 54u64);
 
-        let input_binary = hex_payload_to_binary("880086C3E88112");
-        let operator = parse_packet(&input_binary);
+        let input_binary = hex_payload_to_binary("880086C3E88112").unwrap();
+        let operator = parse_packet(&input_binary).unwrap();
 
         assert_eq!(operator.get_value(), 7u64);
 
-        let input_binary = hex_payload_to_binary("CE00C43D881120");
-        let operator = parse_packet(&input_binary);
+        let input_binary = hex_payload_to_binary("CE00C43D881120").unwrap();
+        let operator = parse_packet(&input_binary).unwrap();
 
         assert_eq!(operator.get_value(), 9u64);
 
-        let input_binary = hex_payload_to_binary("D8005AC2A8F0");
-        let operator = parse_packet(&input_binary);
+        let input_binary = hex_payload_to_binary("D8005AC2A8F0").unwrap();
+        let operator = parse_packet(&input_binary).unwrap();
 
         assert_eq!(operator.get_value(), 1u64);
 
-        let input_binary = hex_payload_to_binary("F600BC2D8F");
-        let operator = parse_packet(&input_binary);
+        let input_binary = hex_payload_to_binary("F600BC2D8F").unwrap();
+        let operator = parse_packet(&input_binary).unwrap();
 
         assert_eq!(operator.get_value(), 0u64);
 
-        let input_binary = hex_payload_to_binary("9C005AC2F8F0");
-        let operator = parse_packet(&input_binary);
+        let input_binary = hex_payload_to_binary("9C005AC2F8F0").unwrap();
+        let operator = parse_packet(&input_binary).unwrap();
 
         assert_eq!(operator.get_value(), 0u64);
 
-        let input_binary = hex_payload_to_binary("9C0141080250320F1802104A08");
-        let operator = parse_packet(&input_binary);
+        let input_binary = hex_payload_to_binary("9C0141080250320F1802104A08").unwrap();
+        let operator = parse_packet(&input_binary).unwrap();
 
         assert_eq!(operator.get_value(), 1u64);
     }
+
+    #[test]
+    fn broken_transmissions() {
+        for transmission in ["D2FE2", "38006F452", "EE00D4", "C2G0", "E200D40C8230"] {
+            let parsed = hex_payload_to_binary(transmission).and_then(|bits| parse_packet(&bits));
+            assert!(parsed.is_err(), "{}", transmission);
+        }
+    }
 }
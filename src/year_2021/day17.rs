@@ -4,10 +4,11 @@ use std::iter::FromIterator;
 
 use itertools::Itertools;
 use nom::bytes::complete::tag;
+use nom::combinator::all_consuming;
 use nom::sequence::{preceded, separated_pair};
 use nom::IResult;
 
-use crate::aoc_lib::jazz_parser;
+use crate::aoc_lib::prelude::*;
 
 // This problem was simple in my mind, but then my tendency of screwing up loop-based algorithms with
 // "by one" errors is legendary
@@ -130,8 +131,7 @@ impl Trench {
     //        to reach the trench
     //      - Add these vectors to the set
     pub fn compute_initial_velocities(&self) -> HashSet<Point> {
-        let mut velocities: HashSet<Point> =
-            HashSet::from_iter(self.one_step_velocities().into_iter());
+        let mut velocities: HashSet<Point> = HashSet::from_iter(self.one_step_velocities());
 
         let all_initial_y_vels = self.get_potential_y_velocities();
 
@@ -173,19 +173,40 @@ fn target(input: &str) -> IResult<&str, Trench> {
     Ok((remain_str, Trench::new(&x_range, &y_range)))
 }
 
-pub fn part1(input: &str) {
-    let (_, target_trench) = target(input).unwrap();
+// The probe is shot forward and the trench is below it, like in every puzzle
+// input. The limit keeps the sums of the speeds in 32 bits.
+fn parse_trench(input: &str) -> Result<Trench, AocError> {
+    let (_, trench) = all_consuming(target)(input.trim())
+        .map_err(|_| AocError::Parse(format!("Invalid target area: \"{}\"", input.trim())))?;
+    let limit = 1..=10_000;
+    if !limit.contains(&trench.top_left.0)
+        || !limit.contains(&trench.bottom_right.0)
+        || !limit.contains(&-trench.top_left.1)
+        || !limit.contains(&-trench.bottom_right.1)
+    {
+        return Err(AocError::Parse(String::from(
+            "The target area should be ahead and below, at most 10000 away",
+        )));
+    }
+
+    Ok(trench)
+}
+
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let target_trench = parse_trench(input)?;
     let start_v = target_trench.coolest_speed();
     let max_height: i32 = start_v.1 * (start_v.1 + 1i32) / 2i32;
-    println!("Maximum height for provided trench: {}", max_height);
+    Ok(Answer::from(max_height))
 }
 
-pub fn part2(input: &str) {
-    let (_, target_trench) = target(input).unwrap();
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let target_trench = parse_trench(input)?;
     let initial_velocities: HashSet<Point> = target_trench.compute_initial_velocities();
-    println!("Amount of initial velocities: {}", initial_velocities.len());
+    Ok(Answer::from(initial_velocities.len()))
 }
 
+aoc_day!(2021, 17, part1, part2);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -361,10 +382,18 @@ mod tests {
 
         let (_, reference_velocities) = velocities(input_result).unwrap();
 
-        let reference_set: HashSet<Point> = HashSet::from_iter(reference_velocities.into_iter());
+        let reference_set: HashSet<Point> = HashSet::from_iter(reference_velocities);
 
         let initial_velocities: HashSet<Point> = target_trench.compute_initial_velocities();
 
         assert_eq!(initial_velocities, reference_set);
     }
+
+    #[test]
+    fn bad_targets() {
+        assert!(parse_trench("target area: x=20..30, y=-10..-5").is_ok());
+        assert!(parse_trench("target area: x=20..30, y=-10..").is_err());
+        assert!(parse_trench("target area: x=-30..-20, y=-10..-5").is_err());
+        assert!(parse_trench("target area: x=20..30, y=5..10").is_err());
+    }
 }
@@ -1,9 +1,12 @@
 use indextree::{Arena, NodeId};
 use nom::bytes::complete::tag;
 use nom::character::complete::{space0, u8};
+use nom::combinator::all_consuming;
 use nom::sequence::{delimited, preceded, separated_pair};
 use nom::IResult;
 
+use crate::aoc_lib::prelude::*;
+
 type SailfishArena = Arena<Option<u8>>;
 
 #[derive(Clone)]
@@ -23,13 +26,6 @@ enum SailfishOperation {
     Split(NodeId),
 }
 
-#[derive(PartialEq)]
-struct SailfishNumber {
-    data: Arena<Option<u8>>,
-    deep_nodes: Vec<NodeId>,
-    large_nodes: Vec<NodeId>,
-}
-
 fn sum(arena: &mut SailfishArena, lhs: NodeId, rhs: NodeId) -> NodeId {
     let new_root = arena.new_node(None);
     new_root.append(lhs, arena);
@@ -162,7 +158,7 @@ fn explode(arena: &mut SailfishArena, deep_node: NodeId) {
 
 fn split(arena: &mut SailfishArena, big_node: NodeId) {
     let curr_val = arena.get_mut(big_node).unwrap().get_mut().take().unwrap();
-    let (new_left, new_right) = if curr_val % 2 == 0 {
+    let (new_left, new_right) = if curr_val.is_multiple_of(2) {
         (curr_val / 2, curr_val / 2)
     } else {
         (curr_val / 2, curr_val / 2 + 1)
@@ -211,14 +207,32 @@ fn sailfish_tree(input: &str) -> IResult<&str, TempNode> {
     Ok((remain_str, TempNode { left, right }))
 }
 
-fn parse_numbers(input: &str, arena: &mut SailfishArena) -> Vec<NodeId> {
+fn parse_numbers(input: &str, arena: &mut SailfishArena) -> Result<Vec<NodeId>, String> {
     let mut roots: Vec<NodeId> = Vec::new();
-    for line in input.lines() {
-        let (_, new_number) = sailfish_tree(line).unwrap();
+    for line in input.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let (_, new_number) = all_consuming(sailfish_tree)(line)
+            .map_err(|_| format!("Invalid snailfish number: \"{}\"", line))?;
         roots.push(convert_tree(new_number, arena));
     }
 
-    roots
+    Ok(roots)
+}
+
+// The homework only has reduced numbers, the reduction relies on it: no pair
+// inside four others and no regular number above 9
+fn parse_homework(input: &str, arena: &mut SailfishArena) -> Result<Vec<NodeId>, String> {
+    let numbers = parse_numbers(input, arena)?;
+    for (line, root) in numbers.iter().enumerate() {
+        let reduced = root.descendants(arena).all(|node| match arena[node].get() {
+            Some(value) => *value <= 9 && node.ancestors(arena).count() <= 5,
+            None => true,
+        });
+        if !reduced {
+            return Err(format!("Number {} is not reduced", line + 1));
+        }
+    }
+
+    Ok(numbers)
 }
 
 fn convert_tree(from: TempNode, arena: &mut SailfishArena) -> NodeId {
@@ -254,41 +268,46 @@ fn convert_tree(from: TempNode, arena: &mut SailfishArena) -> NodeId {
     new_root
 }
 
-pub fn part1(input: &str) {
+pub fn part1(input: &str) -> Result<Answer, AocError> {
     let mut arena: SailfishArena = Arena::new();
-    let numbers = parse_numbers(input, &mut arena);
+    let numbers = parse_homework(input, &mut arena)?;
 
-    let mut total_idx = sum(&mut arena, numbers[0], numbers[1]);
-    for next_root in numbers.into_iter().skip(2) {
+    let mut total_idx = numbers[0];
+    for next_root in numbers.into_iter().skip(1) {
         total_idx = sum(&mut arena, total_idx, next_root);
     }
 
-    println!(
-        "Final magnitude of the sum: {}",
-        compute_magnitude(&arena, total_idx)
-    );
+    Ok(Answer::from(compute_magnitude(&arena, total_idx)))
 }
 
-pub fn part2(input: &str) {
+pub fn part2(input: &str) -> Result<Answer, AocError> {
     let mut arena: SailfishArena = Arena::new();
     let mut max_magnitude: u64 = 0;
 
-    for (start_idx, first) in input.lines().enumerate() {
-        for second in input.lines().skip(start_idx + 1) {
-            let numbers = parse_numbers(format!("{}\n{}", first, second).as_str(), &mut arena);
+    if parse_homework(input, &mut arena)?.len() < 2 {
+        return Err(AocError::Unsolvable(String::from(
+            "There is only one number, no two to add",
+        )));
+    }
+    let lines: Vec<&str> = input.lines().filter(|l| !l.trim().is_empty()).collect();
+    for (start_idx, first) in lines.iter().enumerate() {
+        for second in lines.iter().skip(start_idx + 1) {
+            let numbers = parse_numbers(format!("{}\n{}", first, second).as_str(), &mut arena)?;
             let f_s_root = sum(&mut arena, numbers[0], numbers[1]);
             max_magnitude = max_magnitude.max(compute_magnitude(&arena, f_s_root));
             f_s_root.remove_subtree(&mut arena);
 
-            let numbers = parse_numbers(format!("{}\n{}", first, second).as_str(), &mut arena);
+            let numbers = parse_numbers(format!("{}\n{}", first, second).as_str(), &mut arena)?;
             let s_f_root = sum(&mut arena, numbers[1], numbers[0]);
             max_magnitude = max_magnitude.max(compute_magnitude(&arena, s_f_root));
         }
     }
 
-    println!("Maximum magnitude of all the pairs: {}", max_magnitude);
+    Ok(Answer::from(max_magnitude))
 }
 
+aoc_day!(2021, 18, part1, part2);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -327,7 +346,7 @@ mod tests {
         [[[[1,3],[5,3]],[[1,3],[8,7]]],[[[4,9],[6,9]],[[8,2],[7,3]]]]";
 
         let mut test_arena: SailfishArena = Arena::new();
-        let numbers = parse_numbers(input_string, &mut test_arena);
+        let numbers = parse_numbers(input_string, &mut test_arena).unwrap();
 
         let input_lines: Vec<String> = input_string.lines().map(trim_whitespace).collect();
 
@@ -347,7 +366,7 @@ mod tests {
         [[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]";
 
         let mut test_arena: SailfishArena = Arena::new();
-        let numbers = parse_numbers(input_string, &mut test_arena);
+        let numbers = parse_numbers(input_string, &mut test_arena).unwrap();
 
         let reduced_numbers: Vec<String> = numbers
             .into_iter()
@@ -372,7 +391,7 @@ mod tests {
         let input_string = "[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]";
 
         let mut test_arena: SailfishArena = Arena::new();
-        let numbers = parse_numbers(input_string, &mut test_arena);
+        let numbers = parse_numbers(input_string, &mut test_arena).unwrap();
         let reduced_numbers: Vec<String> = numbers
             .into_iter()
             .map(|num| {
@@ -410,7 +429,7 @@ mod tests {
         let mut sum_roots: Vec<NodeId> = Vec::new();
 
         for group in input_strings.into_iter() {
-            let numbers = parse_numbers(group, &mut test_arena);
+            let numbers = parse_numbers(group, &mut test_arena).unwrap();
             let mut total_root = sum(&mut test_arena, numbers[0], numbers[1]);
             for num in numbers.into_iter().skip(2) {
                 total_root = sum(&mut test_arena, total_root, num);
@@ -459,7 +478,7 @@ mod tests {
         ]
         .into_iter();
         let mut test_arena: SailfishArena = Arena::new();
-        let numbers = parse_numbers(input_string, &mut test_arena);
+        let numbers = parse_numbers(input_string, &mut test_arena).unwrap();
         let mut total_idx = sum(&mut test_arena, numbers[0], numbers[1]);
         assert_eq!(
             print_number(&test_arena, total_idx),
@@ -484,7 +503,7 @@ mod tests {
             [[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]";
 
         let mut test_arena: SailfishArena = Arena::new();
-        let numbers = parse_numbers(input_string, &mut test_arena);
+        let numbers = parse_numbers(input_string, &mut test_arena).unwrap();
 
         let magnitudes: Vec<u64> = numbers
             .into_iter()
@@ -510,7 +529,7 @@ mod tests {
         [[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]";
 
         let mut test_arena: SailfishArena = Arena::new();
-        let numbers = parse_numbers(input_string, &mut test_arena);
+        let numbers = parse_numbers(input_string, &mut test_arena).unwrap();
 
         let mut total_idx = sum(&mut test_arena, numbers[0], numbers[1]);
         for next_root in numbers.into_iter().skip(2) {
@@ -542,13 +561,15 @@ mod tests {
         for (start_idx, first) in input_string.lines().enumerate() {
             for second in input_string.lines().skip(start_idx + 1) {
                 let numbers =
-                    parse_numbers(format!("{}\n{}", first, second).as_str(), &mut test_arena);
+                    parse_numbers(format!("{}\n{}", first, second).as_str(), &mut test_arena)
+                        .unwrap();
                 let f_s_root = sum(&mut test_arena, numbers[0], numbers[1]);
                 max_magnitude = max_magnitude.max(compute_magnitude(&test_arena, f_s_root));
                 f_s_root.remove_subtree(&mut test_arena);
 
                 let numbers =
-                    parse_numbers(format!("{}\n{}", first, second).as_str(), &mut test_arena);
+                    parse_numbers(format!("{}\n{}", first, second).as_str(), &mut test_arena)
+                        .unwrap();
                 let s_f_root = sum(&mut test_arena, numbers[1], numbers[0]);
                 max_magnitude = max_magnitude.max(compute_magnitude(&test_arena, s_f_root));
             }
@@ -556,4 +577,13 @@ mod tests {
 
         assert_eq!(max_magnitude, 3993);
     }
+
+    #[test]
+    fn homework_checks() {
+        assert_eq!(part1("[9,1]").unwrap(), Answer::from(29u64));
+        assert!(part1("[1,2]\n[1,2").is_err());
+        assert!(part1("[1,2]\n[[[[[1,2],3],4],5],6]").is_err());
+        assert!(part1("[10,2]").is_err());
+        assert!(matches!(part2("[1,2]"), Err(AocError::Unsolvable(_))));
+    }
 }
@@ -5,17 +5,20 @@ use std::env::temp_dir;
 use std::fs::File;
 use std::io::{Error, Write};
 
-use hashbrown::HashSet;
+use hashbrown::{HashMap, HashSet};
 use itertools::Itertools;
 use nom::bytes::complete::tag;
-use nom::character::complete::{digit1, i64, newline, space0};
-use nom::combinator::opt;
+use nom::character::complete::{digit1, i64, multispace0, newline, space0};
+use nom::combinator::{all_consuming, opt};
 use nom::multi::many1;
 use nom::sequence::{delimited, terminated, tuple};
 use nom::IResult;
 
 use nalgebra::{Matrix3, Point3, Vector3};
 
+use crate::aoc_lib::prelude::*;
+use crate::aoc_lib::geometry::{self, Rotation};
+
 const ROTATION_MATRICES: [[[i64; 3]; 3]; 24] = [
     [[1, 0, 0], [0, 1, 0], [0, 0, 1]],
     [[1, 0, 0], [0, 0, -1], [0, 1, 0]],
@@ -211,22 +214,26 @@ fn merge_sensors(
     beacons_set.into_iter().sorted_by(compare_points).collect()
 }
 
+// The beacons, then where the sensors are
+type BeaconMap = (Vec<Point3<i64>>, Vec<Point3<i64>>);
+
+// Fails when a pass over the sensors left places none of them, the others
+// don't overlap with the map
 fn reconstruct_beacon_map(
     base_data: &[Point3<i64>],
     sensors: &[SensorData],
-) -> (Vec<Point3<i64>>, Vec<Point3<i64>>) {
+) -> Result<BeaconMap, String> {
     let mut full_map: Vec<Point3<i64>> = base_data.to_vec();
     let mut sensors_to_parse: HashSet<usize> = (0..sensors.len()).collect();
     let mut origins: Vec<Point3<i64>> = vec![Point3::new(0, 0, 0)];
 
     while !sensors_to_parse.is_empty() {
+        let before = sensors_to_parse.len();
         let remaining_sensors: Vec<_> = sensors
             .iter()
             .enumerate()
             .filter(|(idx, _)| sensors_to_parse.contains(idx))
             .collect();
-        let remaining_idx: Vec<_> = remaining_sensors.iter().map(|(id, _)| id).collect();
-        println!("Remaining sensors to parse: {:?}", remaining_idx);
         for (sensor_idx, sensor_data) in remaining_sensors.into_iter() {
             if let Some((rot, translation)) = sensor_data.find_overlap(&full_map, 12) {
                 full_map = merge_sensors(
@@ -240,9 +247,13 @@ fn reconstruct_beacon_map(
                 sensors_to_parse.remove(&sensor_idx);
             }
         }
+        if sensors_to_parse.len() == before {
+            let lost = sensors_to_parse.iter().min().unwrap();
+            return Err(format!("Scanner {} doesn't overlap with the others", lost + 1));
+        }
     }
 
-    (full_map, origins)
+    Ok((full_map, origins))
 }
 
 fn find_farthest_pair(origins: &[Point3<i64>]) -> (usize, (Point3<i64>, Point3<i64>)) {
@@ -269,25 +280,205 @@ fn find_farthest_pair(origins: &[Point3<i64>]) -> (usize, (Point3<i64>, Point3<i
     (max_distance, (origins[farthest.0], origins[farthest.1]))
 }
 
-pub fn both_parts(input: &str) {
-    let (_, mut sensors) = full_data(input).unwrap();
+fn read_sensors(input: &str) -> Result<Vec<SensorData>, String> {
+    let (_, sensors) = all_consuming(terminated(full_data, multispace0))(input)
+        .map_err(|_| String::from("Can't read the scanner reports"))?;
+
+    Ok(sensors)
+}
+
+// Beacons seen by each scanner, in its own coordinates
+fn scanner_reports(input: &str) -> Result<Vec<Vec<geometry::Point3>>, String> {
+    let sensors = read_sensors(input)?;
+
+    Ok(sensors
+        .iter()
+        .map(|sensor| {
+            sensor
+                .beacons
+                .iter()
+                .map(|b| geometry::Point3::new(b.x, b.y, b.z))
+                .collect()
+        })
+        .collect())
+}
+
+// Beacon pairs by their squared distance. Distances don't change with the
+// scanner orientation, so two scanners sharing 12 beacons share at least the
+// 66 distances between them.
+type Fingerprint = HashMap<i64, Vec<(usize, usize)>>;
+
+const SHARED_BEACONS: usize = 12;
+const SHARED_DISTANCES: usize = SHARED_BEACONS * (SHARED_BEACONS - 1) / 2;
+
+fn fingerprint(beacons: &[geometry::Point3]) -> Fingerprint {
+    let mut pairs: Fingerprint = HashMap::new();
+    for (left, right) in (0..beacons.len()).tuple_combinations() {
+        pairs
+            .entry(beacons[left].distance_squared(&beacons[right]))
+            .or_default()
+            .push((left, right));
+    }
+
+    pairs
+}
+
+fn shared_distances(left: &Fingerprint, right: &Fingerprint) -> usize {
+    left.iter()
+        .filter_map(|(distance, pairs)| {
+            right
+                .get(distance)
+                .map(|other| pairs.len().min(other.len()))
+        })
+        .sum()
+}
+
+// How to turn and move `beacons` so at least 12 of them land on `known`, which
+// are already in the coordinates of the first scanner. Only the pairs at the
+// same distance are tried, each one gives a single translation per rotation.
+fn align(
+    known: &[geometry::Point3],
+    known_pairs: &Fingerprint,
+    beacons: &[geometry::Point3],
+    pairs: &Fingerprint,
+    rotations: &[Rotation],
+) -> Option<(Rotation, geometry::Point3)> {
+    let known_set: HashSet<geometry::Point3> = known.iter().copied().collect();
+
+    for (distance, candidates) in pairs.iter() {
+        let Some(targets) = known_pairs.get(distance) else {
+            continue;
+        };
+        for (&(first, second), &(target, other_target)) in
+            candidates.iter().cartesian_product(targets.iter())
+        {
+            for rotation in rotations {
+                for (from, to) in [(first, second), (second, first)] {
+                    let translation = known[target] - rotation.apply(beacons[from]);
+                    if rotation.apply(beacons[to]) + translation != known[other_target] {
+                        continue;
+                    }
+
+                    let overlaps = beacons
+                        .iter()
+                        .filter(|b| known_set.contains(&(rotation.apply(**b) + translation)))
+                        .count();
+                    if overlaps >= SHARED_BEACONS {
+                        return Some((*rotation, translation));
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
+
+// All the beacons and the scanner positions, in the coordinates of the first
+// scanner. Scanners are placed starting from the ones next to those already
+// placed.
+fn locate_scanners(
+    scanners: &[Vec<geometry::Point3>],
+) -> Result<(HashSet<geometry::Point3>, Vec<geometry::Point3>), String> {
+    let rotations = Rotation::all();
+    let fingerprints = scanners.iter().map(|b| fingerprint(b)).collect_vec();
+    let mut placed: Vec<Option<(Vec<geometry::Point3>, geometry::Point3)>> =
+        vec![None; scanners.len()];
+    placed[0] = Some((scanners[0].clone(), geometry::Point3::default()));
+    let mut to_visit = vec![0];
+
+    while let Some(anchor) = to_visit.pop() {
+        let known = placed[anchor].as_ref().unwrap().0.clone();
+        for candidate in 0..scanners.len() {
+            if placed[candidate].is_some()
+                || shared_distances(&fingerprints[anchor], &fingerprints[candidate])
+                    < SHARED_DISTANCES
+            {
+                continue;
+            }
+
+            if let Some((rotation, translation)) = align(
+                &known,
+                &fingerprints[anchor],
+                &scanners[candidate],
+                &fingerprints[candidate],
+                &rotations,
+            ) {
+                let moved = scanners[candidate]
+                    .iter()
+                    .map(|b| rotation.apply(*b) + translation)
+                    .collect();
+                placed[candidate] = Some((moved, translation));
+                to_visit.push(candidate);
+            }
+        }
+    }
+
+    if let Some(lost) = placed.iter().position(Option::is_none) {
+        return Err(format!("Scanner {} doesn't overlap with the others", lost));
+    }
+
+    let placed = placed.into_iter().flatten().collect_vec();
+    Ok((
+        placed.iter().flat_map(|(b, _)| b.iter().copied()).collect(),
+        placed.iter().map(|(_, origin)| *origin).collect(),
+    ))
+}
+
+fn largest_scanner_distance(origins: &[geometry::Point3]) -> u64 {
+    origins
+        .iter()
+        .tuple_combinations()
+        .map(|(left, right)| left.manhattan(right))
+        .max()
+        .unwrap_or(0)
+}
+
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let scanners = scanner_reports(input)?;
+    let (beacons, _) = locate_scanners(&scanners).map_err(AocError::Unsolvable)?;
+
+    Ok(Answer::from(beacons.len()))
+}
+
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let scanners = scanner_reports(input)?;
+    let (_, origins) = locate_scanners(&scanners).map_err(AocError::Unsolvable)?;
+
+    Ok(Answer::from(largest_scanner_distance(&origins)))
+}
+
+// Tries every rotation and translation of each scanner against the whole map
+fn brute_map(input: &str) -> Result<BeaconMap, AocError> {
+    let mut sensors = read_sensors(input)?;
 
     for sensor in sensors.iter_mut().skip(1) {
         sensor.compute_rotations();
     }
 
-    let (beacon_volume, origins) = reconstruct_beacon_map(&sensors[0].beacons, &sensors[1..]);
+    reconstruct_beacon_map(&sensors[0].beacons, &sensors[1..]).map_err(AocError::Unsolvable)
+}
+
+pub fn brute_part1(input: &str) -> Result<Answer, AocError> {
+    let (beacons, _) = brute_map(input)?;
+
+    Ok(Answer::from(beacons.len()))
+}
+
+pub fn brute_part2(input: &str) -> Result<Answer, AocError> {
+    let (_, origins) = brute_map(input)?;
     let (manhattan_distance, _) = find_farthest_pair(&origins);
 
-    println!("Total beacons count: {}", beacon_volume.len());
-    println!("Distance between farthest beacons: {}", manhattan_distance);
+    Ok(Answer::from(manhattan_distance))
 }
 
-pub fn _part2(_input: &str) {}
+aoc_day!(2021, 19, part1, part2; brute = (brute_part1, brute_part2));
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_lib::bench;
+    use crate::aoc_lib::testgen;
 
     // Utility function to generate all the rotation matrices I used to create the const data
     fn _generate_rot_matrices() -> Vec<Matrix3<i8>> {
@@ -522,142 +713,7 @@ mod tests {
 
     #[test]
     fn beacon_volume_reconstruction() {
-        let input_string = "--- scanner 0 ---
-        404,-588,-901
-        528,-643,409
-        -838,591,734
-        390,-675,-793
-        -537,-823,-458
-        -485,-357,347
-        -345,-311,381
-        -661,-816,-575
-        -876,649,763
-        -618,-824,-621
-        553,345,-567
-        474,580,667
-        -447,-329,318
-        -584,868,-557
-        544,-627,-890
-        564,392,-477
-        455,729,728
-        -892,524,684
-        -689,845,-530
-        423,-701,434
-        7,-33,-71
-        630,319,-379
-        443,580,662
-        -789,900,-551
-        459,-707,401
-
-        --- scanner 1 ---
-        686,422,578
-        605,423,415
-        515,917,-361
-        -336,658,858
-        95,138,22
-        -476,619,847
-        -340,-569,-846
-        567,-361,727
-        -460,603,-452
-        669,-402,600
-        729,430,532
-        -500,-761,534
-        -322,571,750
-        -466,-666,-811
-        -429,-592,574
-        -355,545,-477
-        703,-491,-529
-        -328,-685,520
-        413,935,-424
-        -391,539,-444
-        586,-435,557
-        -364,-763,-893
-        807,-499,-711
-        755,-354,-619
-        553,889,-390
-
-        --- scanner 2 ---
-        649,640,665
-        682,-795,504
-        -784,533,-524
-        -644,584,-595
-        -588,-843,648
-        -30,6,44
-        -674,560,763
-        500,723,-460
-        609,671,-379
-        -555,-800,653
-        -675,-892,-343
-        697,-426,-610
-        578,704,681
-        493,664,-388
-        -671,-858,530
-        -667,343,800
-        571,-461,-707
-        -138,-166,112
-        -889,563,-600
-        646,-828,498
-        640,759,510
-        -630,509,768
-        -681,-892,-333
-        673,-379,-804
-        -742,-814,-386
-        577,-820,562
-
-        --- scanner 3 ---
-        -589,542,597
-        605,-692,669
-        -500,565,-823
-        -660,373,557
-        -458,-679,-417
-        -488,449,543
-        -626,468,-788
-        338,-750,-386
-        528,-832,-391
-        562,-778,733
-        -938,-730,414
-        543,643,-506
-        -524,371,-870
-        407,773,750
-        -104,29,83
-        378,-903,-323
-        -778,-728,485
-        426,699,580
-        -438,-605,-362
-        -469,-447,-387
-        509,732,623
-        647,635,-688
-        -868,-804,481
-        614,-800,639
-        595,780,-596
-
-        --- scanner 4 ---
-        727,592,562
-        -293,-554,779
-        441,611,-461
-        -714,465,-776
-        -743,427,-804
-        -660,-479,-426
-        832,-632,460
-        927,-485,-438
-        408,393,-506
-        466,436,-512
-        110,16,151
-        -258,-428,682
-        -393,719,612
-        -211,-452,876
-        808,-476,-593
-        -575,615,604
-        -485,667,467
-        -680,325,-822
-        -627,-443,-432
-        872,-547,-609
-        833,512,582
-        807,604,487
-        839,-516,451
-        891,-625,532
-        -652,-548,-490
-        30,-46,-14";
+        let input_string = include_str!("../../inputs/2021/day19.example.txt");
 
         let (_, mut sensors) = full_data(input_string).unwrap();
 
@@ -665,7 +721,7 @@ mod tests {
             sensor.compute_rotations();
         }
 
-        let (beacon_volume, _) = reconstruct_beacon_map(&sensors[0].beacons, &sensors[1..]);
+        let (beacon_volume, _) = reconstruct_beacon_map(&sensors[0].beacons, &sensors[1..]).unwrap();
 
         let ref_volume_str = "-892,524,684
         -876,649,763
@@ -764,142 +820,7 @@ mod tests {
 
     #[test]
     fn largest_beacon_distance() {
-        let input_string = "--- scanner 0 ---
-        404,-588,-901
-        528,-643,409
-        -838,591,734
-        390,-675,-793
-        -537,-823,-458
-        -485,-357,347
-        -345,-311,381
-        -661,-816,-575
-        -876,649,763
-        -618,-824,-621
-        553,345,-567
-        474,580,667
-        -447,-329,318
-        -584,868,-557
-        544,-627,-890
-        564,392,-477
-        455,729,728
-        -892,524,684
-        -689,845,-530
-        423,-701,434
-        7,-33,-71
-        630,319,-379
-        443,580,662
-        -789,900,-551
-        459,-707,401
-
-        --- scanner 1 ---
-        686,422,578
-        605,423,415
-        515,917,-361
-        -336,658,858
-        95,138,22
-        -476,619,847
-        -340,-569,-846
-        567,-361,727
-        -460,603,-452
-        669,-402,600
-        729,430,532
-        -500,-761,534
-        -322,571,750
-        -466,-666,-811
-        -429,-592,574
-        -355,545,-477
-        703,-491,-529
-        -328,-685,520
-        413,935,-424
-        -391,539,-444
-        586,-435,557
-        -364,-763,-893
-        807,-499,-711
-        755,-354,-619
-        553,889,-390
-
-        --- scanner 2 ---
-        649,640,665
-        682,-795,504
-        -784,533,-524
-        -644,584,-595
-        -588,-843,648
-        -30,6,44
-        -674,560,763
-        500,723,-460
-        609,671,-379
-        -555,-800,653
-        -675,-892,-343
-        697,-426,-610
-        578,704,681
-        493,664,-388
-        -671,-858,530
-        -667,343,800
-        571,-461,-707
-        -138,-166,112
-        -889,563,-600
-        646,-828,498
-        640,759,510
-        -630,509,768
-        -681,-892,-333
-        673,-379,-804
-        -742,-814,-386
-        577,-820,562
-
-        --- scanner 3 ---
-        -589,542,597
-        605,-692,669
-        -500,565,-823
-        -660,373,557
-        -458,-679,-417
-        -488,449,543
-        -626,468,-788
-        338,-750,-386
-        528,-832,-391
-        562,-778,733
-        -938,-730,414
-        543,643,-506
-        -524,371,-870
-        407,773,750
-        -104,29,83
-        378,-903,-323
-        -778,-728,485
-        426,699,580
-        -438,-605,-362
-        -469,-447,-387
-        509,732,623
-        647,635,-688
-        -868,-804,481
-        614,-800,639
-        595,780,-596
-
-        --- scanner 4 ---
-        727,592,562
-        -293,-554,779
-        441,611,-461
-        -714,465,-776
-        -743,427,-804
-        -660,-479,-426
-        832,-632,460
-        927,-485,-438
-        408,393,-506
-        466,436,-512
-        110,16,151
-        -258,-428,682
-        -393,719,612
-        -211,-452,876
-        808,-476,-593
-        -575,615,604
-        -485,667,467
-        -680,325,-822
-        -627,-443,-432
-        872,-547,-609
-        833,512,582
-        807,604,487
-        839,-516,451
-        891,-625,532
-        -652,-548,-490
-        30,-46,-14";
+        let input_string = include_str!("../../inputs/2021/day19.example.txt");
 
         let (_, mut sensors) = full_data(input_string).unwrap();
 
@@ -907,7 +828,7 @@ mod tests {
             sensor.compute_rotations();
         }
 
-        let (_, sensor_origins) = reconstruct_beacon_map(&sensors[0].beacons, &sensors[1..]);
+        let (_, sensor_origins) = reconstruct_beacon_map(&sensors[0].beacons, &sensors[1..]).unwrap();
 
         let (distance, (left_origin, right_origin)) = find_farthest_pair(&sensor_origins);
 
@@ -925,4 +846,65 @@ mod tests {
         assert!(origins_overlap);
         assert_eq!(distance, 3621);
     }
+
+    static FIVE_SCANNERS: &str = include_str!("../../inputs/2021/day19.example.txt");
+
+    #[test]
+    fn fingerprint_alignment() {
+        let scanners = scanner_reports(FIVE_SCANNERS).unwrap();
+        assert_eq!(scanners.len(), 5);
+
+        // Scanners 0 and 1 share 12 beacons, 0 and 2 nothing
+        let fingerprints = scanners.iter().map(|b| fingerprint(b)).collect_vec();
+        assert!(shared_distances(&fingerprints[0], &fingerprints[1]) >= SHARED_DISTANCES);
+        assert!(shared_distances(&fingerprints[0], &fingerprints[2]) < SHARED_DISTANCES);
+
+        let (_, translation) = align(
+            &scanners[0],
+            &fingerprints[0],
+            &scanners[1],
+            &fingerprints[1],
+            &Rotation::all(),
+        )
+        .unwrap();
+        assert_eq!(translation, geometry::Point3::new(68, -1246, -43));
+
+        let (beacons, origins) = locate_scanners(&scanners).unwrap();
+        assert_eq!(beacons.len(), 79);
+        assert!(origins.contains(&geometry::Point3::new(-92, -2380, -20)));
+        assert_eq!(largest_scanner_distance(&origins), 3621);
+
+        assert!(locate_scanners(&scanners[1..3]).is_err());
+    }
+
+    #[test]
+    fn generated_scanners() {
+        let input = testgen::generate(2021, 19, 8, 3).unwrap();
+        assert_eq!(input.matches("--- scanner").count(), 8);
+
+        assert_eq!(part1(&input).unwrap(), brute_part1(&input).unwrap());
+        assert_eq!(part2(&input).unwrap(), brute_part2(&input).unwrap());
+    }
+
+    #[test]
+    #[ignore]
+    fn fingerprint_speedup() {
+        // As many scanners as the official inputs
+        let input = bench::input(2021, 19, 30);
+        bench::faster(
+            ("Brute force", || {
+                let (_, mut sensors) = full_data(&input).unwrap();
+                for sensor in sensors.iter_mut().skip(1) {
+                    sensor.compute_rotations();
+                }
+                let (beacons, _) =
+                    reconstruct_beacon_map(&sensors[0].beacons, &sensors[1..]).unwrap();
+                beacons.len()
+            }),
+            ("Fingerprints", || {
+                let (beacons, _) = locate_scanners(&scanner_reports(&input).unwrap()).unwrap();
+                beacons.len()
+            }),
+        );
+    }
 }
@@ -7,6 +7,8 @@ use itertools::Itertools;
 
 use rayon::prelude::*;
 
+use crate::aoc_lib::prelude::*;
+
 struct ImageEnhancer {
     lut: [bool; 512],
 }
@@ -104,7 +106,7 @@ impl ImageEnhancer {
                 .collect();
         }
 
-        let data = if (steps - 1) % 2 == 0 {
+        let data = if (steps - 1).is_multiple_of(2) {
             &data_flop
         } else {
             &data_flip
@@ -112,7 +114,6 @@ impl ImageEnhancer {
 
         let cropped_output: Vec<bool> = (1..enhanced_rows - 1)
             .cartesian_product(1..enhanced_cols - 1)
-            .into_iter()
             .map(|(y, x)| (y * enhanced_cols) + x)
             .map(|id| data[id])
             .collect();
@@ -182,7 +183,7 @@ impl SensorImage {
             data.extend(data_line.chars().map(|c| c == '#'));
             data.extend([false, false]);
         }
-        data.extend(row_padding.into_iter());
+        data.extend(row_padding);
 
         Self {
             rows,
@@ -206,7 +207,7 @@ impl SensorImage {
             data.extend(raw_data.iter().skip(row * cols).take(cols));
             data.extend([padding_value, padding_value]);
         }
-        data.extend(row_padding.into_iter());
+        data.extend(row_padding);
 
         Self {
             rows,
@@ -279,36 +280,54 @@ impl fmt::Display for SensorImage {
     }
 }
 
-fn parse_input(input: &str) -> (ImageEnhancer, SensorImage) {
+// The 512 pixels of the algorithm on the first line, then after a blank line
+// the rows of the image, all as long
+fn parse_input(input: &str) -> Result<(ImageEnhancer, SensorImage), String> {
     let mut lines = input.lines();
+    let pixels = |line: &str| line.chars().all(|c| c == '#' || c == '.');
 
-    let enhance_string: String = lines.next().unwrap().split_whitespace().collect();
+    let enhance_string: String = lines.next().unwrap_or_default().split_whitespace().collect();
+    if enhance_string.chars().count() != 512 || !pixels(&enhance_string) {
+        return Err(String::from(
+            "The first line should be the 512 pixels of the algorithm",
+        ));
+    }
+    if !lines.next().is_some_and(|line| line.trim().is_empty()) {
+        return Err(String::from("The algorithm should be followed by a blank line"));
+    }
 
-    let image_string: String = lines
-        .skip(1)
-        .map(|l| format!("{}\n", l.trim_start()))
-        .collect();
+    let rows: Vec<&str> = lines.map(str::trim).filter(|l| !l.is_empty()).collect();
+    let width = rows.first().map_or(0, |row| row.chars().count());
+    if width == 0 || rows.iter().any(|row| row.chars().count() != width || !pixels(row)) {
+        return Err(String::from("The image should be rows of pixels, all as long"));
+    }
+    let image_string: String = rows.iter().map(|l| format!("{}\n", l)).collect();
 
-    (
+    Ok((
         ImageEnhancer::new(&enhance_string),
         SensorImage::new(&image_string),
-    )
+    ))
+}
+
+// No count when the endless background is lit too
+fn lit_pixels(image: &SensorImage) -> Result<Answer, AocError> {
+    image
+        .get_lit_pixels()
+        .map(Answer::from)
+        .ok_or_else(|| AocError::Unsolvable(String::from("infinitely many pixels are lit")))
 }
 
-pub fn part1(input: &str) {
-    let (enhancer, picture) = parse_input(input);
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let (enhancer, picture) = parse_input(input)?;
 
     let first_pass = enhancer.enhance_picture(&picture);
     let second_pass = enhancer.enhance_picture(&first_pass);
 
-    println!(
-        "The enhanced picture has {:?} lit pixels",
-        second_pass.get_lit_pixels()
-    );
+    lit_pixels(&second_pass)
 }
 
-pub fn part2(input: &str) {
-    let (enhancer, picture) = parse_input(input);
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let (enhancer, picture) = parse_input(input)?;
 
     /*
     let mut final_image = picture;
@@ -319,12 +338,11 @@ pub fn part2(input: &str) {
 
     let final_image = enhancer.batch_enhance(&picture, 50);
 
-    println!(
-        "The enhanced picture has {:?} lit pixels",
-        final_image.get_lit_pixels()
-    );
+    lit_pixels(&final_image)
 }
 
+aoc_day!(2021, 20, part1, part2);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -346,7 +364,7 @@ mod tests {
             ..#..
             ..###";
 
-        let (enhancer, picture) = parse_input(input_string);
+        let (enhancer, picture) = parse_input(input_string).unwrap();
 
         let ref_picture = SensorImage {
             data: vec![
@@ -385,7 +403,7 @@ mod tests {
             ..#..
             ..###";
 
-        let (enhancer, picture) = parse_input(input_string);
+        let (enhancer, picture) = parse_input(input_string).unwrap();
 
         let first_pass = enhancer.enhance_picture(&picture);
 
@@ -429,7 +447,7 @@ mod tests {
             ..#..
             ..###";
 
-        let (enhancer, picture) = parse_input(input_string);
+        let (enhancer, picture) = parse_input(input_string).unwrap();
 
         let first_pass = enhancer.batch_enhance(&picture, 1);
 
@@ -473,7 +491,7 @@ mod tests {
             ..#..
             ..###";
 
-        let (enhancer, picture) = parse_input(input_string);
+        let (enhancer, picture) = parse_input(input_string).unwrap();
 
         let first_pass = enhancer.enhance_picture(&picture);
         let second_pass = enhancer.enhance_picture(&first_pass);
@@ -523,7 +541,7 @@ mod tests {
             ..#..
             ..###";
 
-        let (enhancer, picture) = parse_input(input_string);
+        let (enhancer, picture) = parse_input(input_string).unwrap();
 
         let second_pass = enhancer.batch_enhance(&picture, 2);
 
@@ -572,7 +590,7 @@ mod tests {
             ..#..
             ..###";
 
-        let (enhancer, picture) = parse_input(input_string);
+        let (enhancer, picture) = parse_input(input_string).unwrap();
 
         let mut final_image = picture;
 
@@ -600,7 +618,7 @@ mod tests {
             ..#..
             ..###";
 
-        let (enhancer, picture) = parse_input(input_string);
+        let (enhancer, picture) = parse_input(input_string).unwrap();
 
         let final_image = enhancer.batch_enhance(&picture, 50);
 
@@ -624,7 +642,7 @@ mod tests {
             ..#..
             ..###";
 
-        let (enhancer, picture) = parse_input(input_string);
+        let (enhancer, picture) = parse_input(input_string).unwrap();
 
         let first_pass = enhancer.enhance_picture(&picture);
         let second_pass = enhancer.enhance_picture(&first_pass);
@@ -674,7 +692,7 @@ mod tests {
             ..#..
             ..###";
 
-        let (enhancer, picture) = parse_input(input_string);
+        let (enhancer, picture) = parse_input(input_string).unwrap();
 
         let first_pass = enhancer.enhance_picture(&picture);
         let second_pass = enhancer.enhance_picture(&first_pass);
@@ -722,7 +740,7 @@ mod tests {
             ..#..
             ..###";
 
-        let (enhancer, picture) = parse_input(input_string);
+        let (enhancer, picture) = parse_input(input_string).unwrap();
 
         let second_pass = enhancer.batch_enhance(&picture, 2);
 
@@ -771,7 +789,7 @@ mod tests {
             ..#..
             ..###";
 
-        let (enhancer, picture) = parse_input(input_string);
+        let (enhancer, picture) = parse_input(input_string).unwrap();
 
         let second_pass = enhancer.batch_enhance(&picture, 2);
 
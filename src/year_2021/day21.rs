@@ -2,6 +2,8 @@ use std::convert::{TryFrom, TryInto};
 
 use hashbrown::HashMap;
 
+use crate::aoc_lib::prelude::*;
+
 const FIRST_PLAYER_CYCLE: [u8; 5] = [6, 4, 2, 0, 8];
 const SECOND_PLAYER_CYCLE: [u8; 5] = [5, 3, 1, 9, 7];
 
@@ -29,20 +31,27 @@ impl Player {
     }
 }
 
-fn get_positions(input: &str) -> (u8, u8) {
+fn get_positions(input: &str) -> Result<(u8, u8), String> {
     let starting_positions: Vec<u8> = input
         .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
         .map(|line| {
             line.chars()
                 .last()
-                .unwrap()
-                .to_digit(10)
-                .unwrap()
-                .try_into()
-                .unwrap()
+                .and_then(|c| c.to_digit(10))
+                .and_then(|digit| u8::try_from(digit).ok())
+                .ok_or_else(|| format!("No starting position in \"{}\"", line))
         })
-        .collect();
-    (starting_positions[0], starting_positions[1])
+        .collect::<Result<_, _>>()?;
+
+    match starting_positions[..] {
+        [first, second] => Ok((first, second)),
+        _ => Err(format!(
+            "Expected two players, found {}",
+            starting_positions.len()
+        )),
+    }
 }
 
 fn generate_score_loop(start_locations: (u8, u8)) -> (Vec<u8>, Vec<u8>) {
@@ -119,7 +128,7 @@ fn compute_final_scores(starting_positions: (u8, u8)) -> ((u16, u16), u32, u32)
         let missing_rolls =
             first_rolls - (loops * u16::try_from(second_score_loop.len()).unwrap()) - 1;
 
-        let missing_score = (0..missing_rolls).into_iter().fold(0u16, |total, idx| {
+        let missing_score = (0..missing_rolls).fold(0u16, |total, idx| {
             total
                 + u16::from(
                     second_score_loop
@@ -132,7 +141,7 @@ fn compute_final_scores(starting_positions: (u8, u8)) -> ((u16, u16), u32, u32)
         let loops = second_rolls / u16::try_from(first_score_loop.len()).unwrap();
         let missing_rolls = second_rolls - (loops * u16::try_from(first_score_loop.len()).unwrap());
 
-        let missing_score = (0..missing_rolls).into_iter().fold(0u16, |total, idx| {
+        let missing_score = (0..missing_rolls).fold(0u16, |total, idx| {
             total
                 + u16::from(
                     first_score_loop
@@ -188,16 +197,16 @@ fn rec_dirac_match(
     (in_turn_score, next_turn_score)
 }
 
-pub fn part1(input: &str) {
-    let starting_positions = get_positions(input);
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let starting_positions = get_positions(input)?;
 
     let (_, _, loser_score) = compute_final_scores(starting_positions);
 
-    println!("The loser scored: {}", loser_score);
+    Ok(Answer::from(loser_score))
 }
 
-pub fn part2(input: &str) {
-    let starting_positions = get_positions(input);
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let starting_positions = get_positions(input)?;
 
     let player_one = Player::new(starting_positions.0);
     let player_two = Player::new(starting_positions.1);
@@ -207,12 +216,11 @@ pub fn part2(input: &str) {
     let (first_universes, second_universes) =
         rec_dirac_match(&mut acceleration_structure, &player_one, &player_two, 21);
 
-    println!(
-        "The player that won in most universes won in {} universes",
-        first_universes.max(second_universes)
-    );
+    Ok(Answer::from(first_universes.max(second_universes)))
 }
 
+aoc_day!(2021, 21, part1, part2);
+
 #[cfg(test)]
 mod tests {
     use itertools::Itertools;
@@ -220,7 +228,7 @@ mod tests {
     use super::*;
 
     fn _generate_steps_triplets(dice_sides: u8) -> Vec<([u8; 3], [u8; 3])> {
-        let rolls = if (dice_sides % 6) == 0 {
+        let rolls = if dice_sides.is_multiple_of(6) {
             dice_sides
         } else {
             (dice_sides / 6 + 1) * 6
@@ -244,7 +252,7 @@ mod tests {
         let input_str = "Player 1 starting position: 4
         Player 2 starting position: 8";
 
-        let starting_positions = get_positions(input_str);
+        let starting_positions = get_positions(input_str).unwrap();
 
         assert_eq!(starting_positions, (4, 8))
     }
@@ -254,7 +262,7 @@ mod tests {
         let input_str = "Player 1 starting position: 4
         Player 2 starting position: 8";
 
-        let starting_positions = get_positions(input_str);
+        let starting_positions = get_positions(input_str).unwrap();
 
         let (results, total_rolls, loser_score) = compute_final_scores(starting_positions);
 
@@ -269,7 +277,7 @@ mod tests {
         let input_str = "Player 1 starting position: 4
         Player 2 starting position: 8";
 
-        let starting_positions = get_positions(input_str);
+        let starting_positions = get_positions(input_str).unwrap();
 
         let first_player = Player::new(starting_positions.0);
         let second_player = Player::new(starting_positions.1);
@@ -1,112 +1,83 @@
+use hashbrown::HashMap;
 use itertools::Itertools;
-use nalgebra::Point3;
+use rayon::prelude::*;
 
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::character::complete::{digit1, space0};
-use nom::combinator::opt;
+use nom::character::complete::{char, i64 as signed, one_of, space1};
+use nom::combinator::{all_consuming, map};
 use nom::multi::separated_list1;
-use nom::sequence::{delimited, pair, preceded, separated_pair};
+use nom::sequence::{preceded, separated_pair, tuple};
 use nom::IResult;
 
 use std::convert::TryFrom;
+use std::fs;
+use std::path::Path;
 
-fn power(input: &str) -> IResult<&str, bool> {
-    let (rem_str, power) = alt((tag("on"), tag("off")))(input)?;
-
-    Ok((rem_str, power == "on"))
-}
-
-fn axis_range(input: &str) -> IResult<&str, (i32, i32)> {
-    let (rem_str, (first_raw, second_raw)) = preceded(
-        alt((tag("x="), tag("y="), tag("z="))),
-        separated_pair(
-            pair(opt(tag("-")), digit1),
-            tag(".."),
-            pair(opt(tag("-")), digit1),
-        ),
-    )(input)?;
-
-    let first_value = format!("{}{}", first_raw.0.unwrap_or(""), first_raw.1);
-    let second_value = format!("{}{}", second_raw.0.unwrap_or(""), second_raw.1);
-
-    Ok((
-        rem_str,
-        (first_value.parse().unwrap(), second_value.parse().unwrap()),
-    ))
-}
-
-pub fn power_cube(input: &str) -> IResult<&str, PowerCuboid> {
-    let (rem_str, power_state) = delimited(space0, power, space0)(input)?;
+use crate::aoc_lib::config::InputKind;
+use crate::aoc_lib::cuboid::Cuboid;
+use crate::aoc_lib::error::write_error;
+use crate::aoc_lib::explain::Explanation;
+use crate::aoc_lib::viz::svg::{Shape, Svg};
+use crate::aoc_lib::viz::voxels;
+use crate::aoc_lib::prelude::*;
 
-    let (rem_str, axes) = separated_list1(tag(","), axis_range)(rem_str)?;
-
-    let x_range = (axes[0].0.min(axes[0].1), axes[0].0.max(axes[0].1) + 1);
-    let y_range = (axes[1].0.min(axes[1].1), axes[1].0.max(axes[1].1) + 1);
-    let z_range = (axes[2].0.min(axes[2].1), axes[2].0.max(axes[2].1) + 1);
-
-    Ok((
-        rem_str,
-        PowerCuboid::new(power_state, x_range, y_range, z_range),
-    ))
+fn power(input: &str) -> IResult<&str, bool> {
+    alt((map(tag("on"), |_| true), map(tag("off"), |_| false)))(input)
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
-pub struct Cuboid {
-    top_right: Point3<i32>,
-    bottom_left: Point3<i32>,
+fn axis_range(input: &str) -> IResult<&str, (char, i64, i64)> {
+    tuple((
+        one_of("xyz"),
+        preceded(char('='), signed),
+        preceded(tag(".."), signed),
+    ))(input)
 }
 
-impl Cuboid {
-    pub fn new(bottom_left: Point3<i32>, top_right: Point3<i32>) -> Self {
-        Self {
-            top_right,
-            bottom_left,
-        }
+// The grammar is loose on purpose (any number of axes, any order), so that the
+// checks below can say what is actually wrong with the line
+pub fn parse_step(line: &str) -> Result<PowerCuboid, String> {
+    let line = line.trim();
+    let (_, (power_state, axes)) = all_consuming(separated_pair(
+        power,
+        space1,
+        separated_list1(char(','), axis_range),
+    ))(line)
+    .map_err(|_| format!("Invalid reboot step: \"{}\"", line))?;
+
+    let labels: String = axes.iter().map(|(label, _, _)| label).collect();
+    if labels != "xyz" {
+        return Err(format!(
+            "Expected the x, y and z axes in this order, got \"{}\" in \"{}\"",
+            labels, line
+        ));
     }
 
-    pub fn inside_volume(&self, volume: &Cuboid) -> bool {
-        self.bottom_left >= volume.bottom_left && self.top_right <= volume.top_right
+    if let Some((label, min, max)) = axes.iter().find(|(_, min, max)| min > max) {
+        return Err(format!(
+            "Reversed {} range {}..{} in \"{}\"",
+            label, min, max, line
+        ));
     }
 
-    pub fn volume(&self) -> u64 {
-        let sizes = (self.top_right - self.bottom_left).abs();
-
-        sizes
-            .into_iter()
-            .map(|length| u64::try_from(*length).unwrap())
-            .product()
+    if let Some((label, _, _)) = axes.iter().find(|(_, _, max)| *max == i64::MAX) {
+        return Err(format!("The {} range is too large in \"{}\"", label, line));
     }
 
-    pub fn intersect(&self, other: &Self) -> Option<Self> {
-        if self.bottom_left.x > other.top_right.x || self.top_right.x < other.bottom_left.x {
-            return None;
-        }
-        if self.bottom_left.y > other.top_right.y || self.top_right.y < other.bottom_left.y {
-            return None;
-        }
-        if self.bottom_left.z > other.top_right.z || self.top_right.z < other.bottom_left.z {
-            return None;
-        }
+    let [x, y, z] = [0, 1, 2].map(|axis| axes[axis].1..=axes[axis].2);
 
-        let (min_x, max_x) = (
-            self.bottom_left.x.max(other.bottom_left.x),
-            self.top_right.x.min(other.top_right.x),
-        );
-        let (min_y, max_y) = (
-            self.bottom_left.y.max(other.bottom_left.y),
-            self.top_right.y.min(other.top_right.y),
-        );
-        let (min_z, max_z) = (
-            self.bottom_left.z.max(other.bottom_left.z),
-            self.top_right.z.min(other.top_right.z),
-        );
+    Ok(PowerCuboid::new(
+        power_state,
+        Cuboid::from_inclusive([x, y, z]),
+    ))
+}
 
-        Some(Self {
-            bottom_left: Point3::new(min_x, min_y, min_z),
-            top_right: Point3::new(max_x, max_y, max_z),
-        })
-    }
+pub fn parse_reboot(input: &str) -> Result<Vec<PowerCuboid>, String> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(parse_step)
+        .collect()
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -116,20 +87,9 @@ pub struct PowerCuboid {
 }
 
 impl PowerCuboid {
-    fn new(
-        power_state: bool,
-        x_range: (i32, i32),
-        y_range: (i32, i32),
-        z_range: (i32, i32),
-    ) -> Self {
-        let bottom_left: Point3<i32> = Point3::new(x_range.0, y_range.0, z_range.0);
-        let top_right: Point3<i32> = Point3::new(x_range.1, y_range.1, z_range.1);
-
+    fn new(power_state: bool, cuboid: Cuboid) -> Self {
         Self {
-            cuboid: Cuboid {
-                top_right,
-                bottom_left,
-            },
+            cuboid,
             power_state,
         }
     }
@@ -138,6 +98,7 @@ impl PowerCuboid {
         self.cuboid.inside_volume(volume)
     }
 
+    #[cfg(test)]
     pub fn intersect(&self, other: &Self) -> Option<PowerCuboid> {
         let intersection_cuboid = self.cuboid.intersect(&other.cuboid)?;
 
@@ -147,13 +108,16 @@ impl PowerCuboid {
         })
     }
 
-    fn compute_on_volume(&self, other_cuboids: &[PowerCuboid]) -> u64 {
+    // The original inclusion-exclusion recursion, kept as a reference for the
+    // tests and the benchmark
+    #[cfg(test)]
+    fn compute_on_volume(&self, other_cuboids: &[PowerCuboid]) -> u128 {
         let conflicts = other_cuboids
             .iter()
             .filter_map(|c| self.intersect(c))
             .collect_vec();
 
-        let confict_volume: u64 = conflicts
+        let confict_volume: u128 = conflicts
             .iter()
             .enumerate()
             .map(|(idx, cube)| cube.compute_on_volume(&conflicts[idx + 1..]))
@@ -163,55 +127,457 @@ impl PowerCuboid {
     }
 }
 
-pub fn part1(input: &str) {
-    let target_volume = Cuboid::new(Point3::new(-50, -50, -50), Point3::new(51, 51, 51));
+// Every step cancels what it overlaps by adding each existing correction's
+// intersection with the opposite sign, then "on" steps add themselves. Equal
+// cuboids are merged into a single weight, so heavily overlapping steps don't
+// make the list explode the way the recursion over all conflicts does, and the
+// steps never need to be kept around.
+#[derive(Debug, Default, Clone)]
+pub struct Reactor {
+    corrections: HashMap<Cuboid, i64>,
+}
+
+impl Reactor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The intersections are independent from each other, only merging them
+    // back has to be sequential
+    pub fn apply_step(&mut self, step: &PowerCuboid) {
+        let cancellations: Vec<(Cuboid, i64)> = self
+            .corrections
+            .par_iter()
+            .filter_map(|(cuboid, weight)| {
+                cuboid
+                    .intersect(&step.cuboid)
+                    .map(|overlap| (overlap, -weight))
+            })
+            .collect();
+
+        for (cuboid, weight) in cancellations {
+            *self.corrections.entry(cuboid).or_insert(0) += weight;
+        }
+        if step.power_state {
+            *self.corrections.entry(step.cuboid.clone()).or_insert(0) += 1;
+        }
+        self.corrections.retain(|_, weight| *weight != 0);
+    }
+
+    #[cfg(test)]
+    pub fn corrections(&self) -> usize {
+        self.corrections.len()
+    }
+
+    // Corrections can be much larger than the final answer, so every step of
+    // the sum is checked instead of trusting the total to fit
+    pub fn on_volume(&self) -> Result<u128, String> {
+        let total: Option<i128> = self
+            .corrections
+            .par_iter()
+            .map(|(cuboid, weight)| {
+                let volume = i128::try_from(cuboid.checked_volume()?).ok()?;
+                volume.checked_mul(i128::from(*weight))
+            })
+            .try_reduce(|| 0, |a, b| a.checked_add(b));
+
+        total
+            .and_then(|total| u128::try_from(total).ok())
+            .ok_or_else(|| String::from("Reactor volume overflow"))
+    }
+}
+
+// The volume as a number, when it fits in one
+fn volume_answer(volume: Result<u128, AocError>) -> Result<Answer, AocError> {
+    let volume = u64::try_from(volume?).map_err(|_| {
+        AocError::Unsolvable(String::from("the lit volume doesn't fit in 64 bits"))
+    })?;
+
+    Ok(Answer::UInt(volume))
+}
+
+aoc_day!(
+    2021,
+    22,
+    |input| volume_answer(part1(input)),
+    |input| volume_answer(part2(input));
+    octree = (
+        |input| volume_answer(octree_part1(input)),
+        |input| volume_answer(octree_part2(input))
+    ),
+    render = render,
+    explain = explain,
+);
+
+#[cfg(test)]
+fn on_volume(cubes: &[PowerCuboid]) -> u128 {
+    let mut reactor = Reactor::new();
+    for step in cubes {
+        reactor.apply_step(step);
+    }
+
+    reactor.on_volume().unwrap()
+}
 
-    let cubes = input
+// Folds the steps in as they are parsed, without collecting them first
+fn run_reboot(input: &str, keep: impl Fn(&PowerCuboid) -> bool) -> Result<Reactor, String> {
+    input
         .lines()
-        .filter_map(|line| {
-            let (_, cube) = power_cube(line).unwrap();
-            if cube.inside_volume(&target_volume) {
-                Some(cube)
-            } else {
-                None
+        .filter(|line| !line.trim().is_empty())
+        .map(parse_step)
+        .try_fold(Reactor::new(), |mut reactor, step| {
+            let step = step?;
+            if keep(&step) {
+                reactor.apply_step(&step);
             }
+            Ok(reactor)
         })
+}
+
+// Alternative to the signed corrections: the region is split in octants until
+// the last step touching a region covers it completely, so its power state is
+// the state of the whole region. The split point on each axis is the median of
+// the step faces crossing the region instead of the midpoint, otherwise the
+// faces would have to be followed down to single cubes.
+fn octree_count(region: &Cuboid, steps: &[&PowerCuboid]) -> u128 {
+    let touching = steps
+        .iter()
+        .filter(|step| step.cuboid.intersect(region).is_some())
+        .copied()
         .collect_vec();
 
-    assert_eq!(cubes.len(), 20);
+    let Some(last) = touching.last() else {
+        return 0;
+    };
+    if region.inside_volume(&last.cuboid) {
+        return if last.power_state { region.volume() } else { 0 };
+    }
+    // Nothing left on in here
+    if touching.iter().all(|step| !step.power_state) {
+        return 0;
+    }
+
+    let mut octants = vec![region.clone()];
+    for axis in 0..3 {
+        let (min, max) = (region.bottom_left()[axis], region.top_right()[axis]);
+        let faces = touching
+            .iter()
+            .flat_map(|step| {
+                [
+                    step.cuboid.bottom_left()[axis],
+                    step.cuboid.top_right()[axis],
+                ]
+            })
+            .filter(|face| *face > min && *face < max)
+            .sorted()
+            .dedup()
+            .collect_vec();
+        let Some(&middle) = faces.get(faces.len() / 2) else {
+            continue;
+        };
 
-    let final_volume: u64 = cubes
+        octants = octants
+            .iter()
+            .flat_map(|octant| {
+                let (lower, upper) = octant.split(axis, middle);
+                lower.into_iter().chain(upper)
+            })
+            .collect();
+    }
+
+    octants
         .iter()
-        .enumerate()
-        .filter(|(_, c)| c.power_state)
-        .map(|(idx, c)| c.compute_on_volume(&cubes[idx + 1..]))
-        .sum();
+        .map(|octant| octree_count(octant, &touching))
+        .sum()
+}
 
-    println!("Number of on voxels: {}", final_volume);
+fn octree_volume(cubes: &[PowerCuboid]) -> u128 {
+    let Some(first) = cubes.first() else {
+        return 0;
+    };
+    let bounds = cubes.iter().fold(first.cuboid.clone(), |bounds, step| {
+        Cuboid::new(
+            bounds.bottom_left().inf(step.cuboid.bottom_left()),
+            bounds.top_right().sup(step.cuboid.top_right()),
+        )
+    });
+
+    octree_count(&bounds, &cubes.iter().collect_vec())
 }
 
-pub fn part2(input: &str) {
-    let cubes = input
-        .lines()
-        .map(|line| {
-            let (_, cube) = power_cube(line).unwrap();
-            cube
-        })
-        .collect_vec();
+// The on-region as disjoint boxes: every step carves itself out of what is on,
+// then "on" steps add themselves back
+fn lit_cuboids(cubes: &[PowerCuboid]) -> Vec<Cuboid> {
+    cubes.iter().fold(Vec::new(), |lit, step| {
+        let mut lit = lit
+            .iter()
+            .flat_map(|piece| piece.subtract(&step.cuboid))
+            .collect_vec();
+        if step.power_state {
+            lit.push(step.cuboid.clone());
+        }
+        lit
+    })
+}
 
-    let final_volume: u64 = cubes
+const SLICES: i64 = 5;
+
+// Cuts the lit cuboids at a few heights spread over the whole reactor and draws
+// the x, y sections side by side, each slice in its own layer
+fn slices_svg(lit: &[Cuboid]) -> Svg {
+    let mut svg = Svg::new();
+    let lit = lit.iter().filter(|c| !c.is_empty()).collect_vec();
+    let (Some(low), Some(high)) = (
+        lit.iter().map(|c| *c.bottom_left()).reduce(|a, b| a.inf(&b)),
+        lit.iter().map(|c| *c.top_right()).reduce(|a, b| a.sup(&b)),
+    ) else {
+        return svg;
+    };
+
+    let (width, height) = ((high.x - low.x) as f64, (high.y - low.y) as f64);
+    let gap = width.max(height) / 10.0;
+    let heights = (0..SLICES)
+        .map(|slice| low.z + (high.z - low.z) * (2 * slice + 1) / (2 * SLICES))
+        .dedup();
+    for (column, z) in heights.enumerate() {
+        let offset = column as f64 * (width + gap);
+        let cut = lit.iter().filter(|c| c.ranges()[2].contains(&z)).collect_vec();
+
+        // The outline of the reactor, a polyline so it isn't filled in
+        svg.layer(&format!("z = {}", z), "gold", "darkgoldenrod", 0.8)
+            .add(
+                Shape::Polyline(vec![
+                    (offset, 0.0),
+                    (offset + width, 0.0),
+                    (offset + width, height),
+                    (offset, height),
+                    (offset, 0.0),
+                ]),
+                Some(&format!("Slice at z = {}, {} lit cuboids", z, cut.len())),
+            );
+        for cuboid in cut {
+            let [x, y, z] = cuboid.inclusive_ranges();
+            svg.add(
+                Shape::Rect {
+                    corner: (
+                        offset + (x.start() - low.x) as f64,
+                        (y.start() - low.y) as f64,
+                    ),
+                    width: (x.end() - x.start() + 1) as f64,
+                    height: (y.end() - y.start() + 1) as f64,
+                },
+                Some(&format!(
+                    "x={}..{}, y={}..{}, z={}..{}",
+                    x.start(),
+                    x.end(),
+                    y.start(),
+                    y.end(),
+                    z.start(),
+                    z.end()
+                )),
+            );
+        }
+    }
+
+    svg
+}
+
+// The surface of the lit cuboids as an OBJ model, or as a glTF one when the path
+// ends in .gltf. Slices in an SVG when it ends in .svg.
+pub fn render(input: &str, _kind: InputKind, path: &Path) -> Result<(), AocError> {
+    let cubes = parse_reboot(input)?;
+    let lit = lit_cuboids(&cubes);
+    let extension = path.extension().and_then(|extension| extension.to_str());
+    let picture = match extension {
+        Some("svg") => slices_svg(&lit).to_string(),
+        Some("gltf") => voxels::to_gltf(&voxels::surface(&lit)),
+        _ => voxels::to_obj(&voxels::surface(&lit)),
+    };
+
+    fs::write(path, picture).map_err(|e| write_error(path, e))
+}
+
+// The steps as the puzzle writes them
+fn describe_step(step: &PowerCuboid) -> String {
+    let [x, y, z] = step.cuboid.inclusive_ranges();
+    format!(
+        "{} x={}..{},y={}..{},z={}..{}",
+        if step.power_state { "on" } else { "off" },
+        x.start(),
+        x.end(),
+        y.start(),
+        y.end(),
+        z.start(),
+        z.end()
+    )
+}
+
+// How many cubes every step turned on or off, and how many are on after it
+fn explain_reboot(title: &str, steps: &[&PowerCuboid]) -> Result<Explanation, String> {
+    let mut reactor = Reactor::new();
+    let mut lit = 0;
+    let mut details = Vec::new();
+    for (id, step) in steps.iter().enumerate() {
+        reactor.apply_step(step);
+        let now = reactor.on_volume()?;
+        let change = if now >= lit {
+            format!("+{}", now - lit)
+        } else {
+            format!("-{}", lit - now)
+        };
+        details.push(Explanation::new(format!(
+            "Step {}, {}: {}, {} on",
+            id + 1,
+            describe_step(step),
+            change,
+            now
+        )));
+        lit = now;
+    }
+
+    let mut explanation = Explanation::new(format!("{}: {} cubes on", title, lit));
+    explanation.details = details;
+    Ok(explanation)
+}
+
+pub fn explain(input: &str, _kind: InputKind) -> Result<Explanation, AocError> {
+    let steps = parse_reboot(input)?;
+    let target_volume = Cuboid::from_inclusive([-50..=50, -50..=50, -50..=50]);
+    let initialization = steps
         .iter()
-        .enumerate()
-        .filter(|(_, c)| c.power_state)
-        .map(|(idx, c)| c.compute_on_volume(&cubes[idx + 1..]))
-        .sum();
+        .filter(|step| step.inside_volume(&target_volume))
+        .collect_vec();
+
+    Ok(
+        Explanation::new(format!("Reboot in {} steps", steps.len()))
+            .detail(explain_reboot(
+                "Initialization, the steps inside -50..50",
+                &initialization,
+            )?)
+            .detail(explain_reboot("Full reboot", &steps.iter().collect_vec())?),
+    )
+}
+
+// Only the steps fully inside -50..=50 on every axis
+pub fn part1(input: &str) -> Result<u128, AocError> {
+    let target_volume = Cuboid::from_inclusive([-50..=50, -50..=50, -50..=50]);
+    let reactor = run_reboot(input, |step| step.inside_volume(&target_volume))?;
+
+    reactor.on_volume().map_err(AocError::Unsolvable)
+}
+
+pub fn part2(input: &str) -> Result<u128, AocError> {
+    let reactor = run_reboot(input, |_| true)?;
+
+    reactor.on_volume().map_err(AocError::Unsolvable)
+}
+
+pub fn octree_part1(input: &str) -> Result<u128, AocError> {
+    let target_volume = Cuboid::from_inclusive([-50..=50, -50..=50, -50..=50]);
+
+    let cubes = parse_reboot(input)?
+        .into_iter()
+        .filter(|cube| cube.inside_volume(&target_volume))
+        .collect_vec();
+
+    Ok(octree_volume(&cubes))
+}
+
+pub fn octree_part2(input: &str) -> Result<u128, AocError> {
+    let cubes = parse_reboot(input)?;
 
-    println!("Number of on voxels: {}", final_volume);
+    Ok(octree_volume(&cubes))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_lib::bench;
+    use crate::aoc_lib::random::cross_check;
+    use crate::aoc_lib::testgen;
+    use nalgebra::Point3;
+
+    static SMALL_REBOOT: &str = include_str!("../../inputs/2021/day22.example.txt");
+
+    static FULL_REBOOT: &str = "on x=-5..47,y=-31..22,z=-19..33
+    on x=-44..5,y=-27..21,z=-14..35
+    on x=-49..-1,y=-11..42,z=-10..38
+    on x=-20..34,y=-40..6,z=-44..1
+    off x=26..39,y=40..50,z=-2..11
+    on x=-41..5,y=-41..6,z=-36..8
+    off x=-43..-33,y=-45..-28,z=7..25
+    on x=-33..15,y=-32..19,z=-34..11
+    off x=35..47,y=-46..-34,z=-11..5
+    on x=-14..36,y=-6..44,z=-16..29
+    on x=-57795..-6158,y=29564..72030,z=20435..90618
+    on x=36731..105352,y=-21140..28532,z=16094..90401
+    on x=30999..107136,y=-53464..15513,z=8553..71215
+    on x=13528..83982,y=-99403..-27377,z=-24141..23996
+    on x=-72682..-12347,y=18159..111354,z=7391..80950
+    on x=-1060..80757,y=-65301..-20884,z=-103788..-16709
+    on x=-83015..-9461,y=-72160..-8347,z=-81239..-26856
+    on x=-52752..22273,y=-49450..9096,z=54442..119054
+    on x=-29982..40483,y=-108474..-28371,z=-24328..38471
+    on x=-4958..62750,y=40422..118853,z=-7672..65583
+    on x=55694..108686,y=-43367..46958,z=-26781..48729
+    on x=-98497..-18186,y=-63569..3412,z=1232..88485
+    on x=-726..56291,y=-62629..13224,z=18033..85226
+    on x=-110886..-34664,y=-81338..-8658,z=8914..63723
+    on x=-55829..24974,y=-16897..54165,z=-121762..-28058
+    on x=-65152..-11147,y=22489..91432,z=-58782..1780
+    on x=-120100..-32970,y=-46592..27473,z=-11695..61039
+    on x=-18631..37533,y=-124565..-50804,z=-35667..28308
+    on x=-57817..18248,y=49321..117703,z=5745..55881
+    on x=14781..98692,y=-1341..70827,z=15753..70151
+    on x=-34419..55919,y=-19626..40991,z=39015..114138
+    on x=-60785..11593,y=-56135..2999,z=-95368..-26915
+    on x=-32178..58085,y=17647..101866,z=-91405..-8878
+    on x=-53655..12091,y=50097..105568,z=-75335..-4862
+    on x=-111166..-40997,y=-71714..2688,z=5609..50954
+    on x=-16602..70118,y=-98693..-44401,z=5197..76897
+    on x=16383..101554,y=4615..83635,z=-44907..18747
+    off x=-95822..-15171,y=-19987..48940,z=10804..104439
+    on x=-89813..-14614,y=16069..88491,z=-3297..45228
+    on x=41075..99376,y=-20427..49978,z=-52012..13762
+    on x=-21330..50085,y=-17944..62733,z=-112280..-30197
+    on x=-16478..35915,y=36008..118594,z=-7885..47086
+    off x=-98156..-27851,y=-49952..43171,z=-99005..-8456
+    off x=2032..69770,y=-71013..4824,z=7471..94418
+    on x=43670..120875,y=-42068..12382,z=-24787..38892
+    off x=37514..111226,y=-45862..25743,z=-16714..54663
+    off x=25699..97951,y=-30668..59918,z=-15349..69697
+    off x=-44271..17935,y=-9516..60759,z=49131..112598
+    on x=-61695..-5813,y=40978..94975,z=8655..80240
+    off x=-101086..-9439,y=-7088..67543,z=33935..83858
+    off x=18020..114017,y=-48931..32606,z=21474..89843
+    off x=-77139..10506,y=-89994..-18797,z=-80..59318
+    off x=8476..79288,y=-75520..11602,z=-96624..-24783
+    on x=-47488..-1262,y=24338..100707,z=16292..72967
+    off x=-84341..13987,y=2429..92914,z=-90671..-1318
+    off x=-37810..49457,y=-71013..-7894,z=-105357..-13188
+    off x=-27365..46395,y=31009..98017,z=15428..76570
+    off x=-70369..-16548,y=22648..78696,z=-1892..86821
+    on x=-53470..21291,y=-120233..-33476,z=-44150..38147
+    off x=-93533..-4276,y=-16170..68771,z=-104985..-24507";
+
+    #[test]
+    fn explained_steps() {
+        let tiny = "on x=10..12,y=10..12,z=10..12
+on x=11..13,y=11..13,z=11..13
+off x=9..11,y=9..11,z=9..11
+on x=10..10,y=10..10,z=10..10";
+        let explanation = explain(tiny, InputKind::Real).unwrap().to_string();
+        let lines: Vec<&str> = explanation.lines().collect();
+
+        assert_eq!(lines[0], "Reboot in 4 steps");
+        assert_eq!(lines[1], "  Initialization, the steps inside -50..50: 39 cubes on");
+        assert_eq!(lines[2], "    Step 1, on x=10..12,y=10..12,z=10..12: +27, 27 on");
+        assert_eq!(lines[3], "    Step 2, on x=11..13,y=11..13,z=11..13: +19, 46 on");
+        assert_eq!(lines[4], "    Step 3, off x=9..11,y=9..11,z=9..11: -8, 38 on");
+        assert_eq!(lines[5], "    Step 4, on x=10..10,y=10..10,z=10..10: +1, 39 on");
+        assert_eq!(lines[6], "  Full reboot: 39 cubes on");
+        assert_eq!(lines.len(), 11);
+    }
 
     #[test]
     fn input_parsing() {
@@ -222,39 +588,24 @@ mod tests {
 
         let cubes: Vec<PowerCuboid> = input_string
             .lines()
-            .map(|line| {
-                let (_, cube) = power_cube(line).unwrap();
-                cube
-            })
+            .map(|line| parse_step(line).unwrap())
             .collect();
 
         let ref_cubes = vec![
             PowerCuboid {
-                cuboid: Cuboid {
-                    top_right: Point3::new(13, 13, 13),
-                    bottom_left: Point3::new(10, 10, 10),
-                },
+                cuboid: Cuboid::new(Point3::new(10, 10, 10), Point3::new(13, 13, 13)),
                 power_state: true,
             },
             PowerCuboid {
-                cuboid: Cuboid {
-                    top_right: Point3::new(14, 14, 14),
-                    bottom_left: Point3::new(11, 11, 11),
-                },
+                cuboid: Cuboid::new(Point3::new(11, 11, 11), Point3::new(14, 14, 14)),
                 power_state: true,
             },
             PowerCuboid {
-                cuboid: Cuboid {
-                    top_right: Point3::new(12, 12, 12),
-                    bottom_left: Point3::new(9, 9, 9),
-                },
+                cuboid: Cuboid::new(Point3::new(9, 9, 9), Point3::new(12, 12, 12)),
                 power_state: false,
             },
             PowerCuboid {
-                cuboid: Cuboid {
-                    top_right: Point3::new(11, 11, 11),
-                    bottom_left: Point3::new(10, 10, 10),
-                },
+                cuboid: Cuboid::new(Point3::new(10, 10, 10), Point3::new(11, 11, 11)),
                 power_state: true,
             },
         ];
@@ -283,23 +634,17 @@ mod tests {
 
         let cubes: Vec<PowerCuboid> = input_string
             .lines()
-            .map(|line| {
-                let (_, cube) = power_cube(line).unwrap();
-                cube
-            })
+            .map(|line| parse_step(line).unwrap())
             .collect();
 
         let intersection = cubes[0].intersect(&cubes[1]).unwrap();
 
         let ref_intersection = PowerCuboid {
-            cuboid: Cuboid {
-                top_right: Point3::new(13, 13, 13),
-                bottom_left: Point3::new(11, 11, 11),
-            },
+            cuboid: Cuboid::new(Point3::new(11, 11, 11), Point3::new(13, 13, 13)),
             power_state: true,
         };
 
-        let final_volume: u64 = cubes
+        let final_volume: u128 = cubes
             .iter()
             .enumerate()
             .filter(|(_, c)| c.power_state)
@@ -316,17 +661,11 @@ mod tests {
 
         let cubes: Vec<PowerCuboid> = input_string
             .lines()
-            .map(|line| {
-                let (_, cube) = power_cube(line).unwrap();
-                cube
-            })
+            .map(|line| parse_step(line).unwrap())
             .collect();
 
         let far_cube = PowerCuboid {
-            cuboid: Cuboid {
-                top_right: Point3::new(16, 16, 16),
-                bottom_left: Point3::new(15, 15, 15),
-            },
+            cuboid: Cuboid::new(Point3::new(15, 15, 15), Point3::new(16, 16, 16)),
             power_state: true,
         };
 
@@ -339,10 +678,7 @@ mod tests {
 
         let cubes: Vec<PowerCuboid> = input_string
             .lines()
-            .map(|line| {
-                let (_, cube) = power_cube(line).unwrap();
-                cube
-            })
+            .map(|line| parse_step(line).unwrap())
             .collect();
 
         let intersection = cubes[0].intersect(&cubes[0]).unwrap();
@@ -358,23 +694,17 @@ mod tests {
 
         let cubes: Vec<PowerCuboid> = input_string
             .lines()
-            .map(|line| {
-                let (_, cube) = power_cube(line).unwrap();
-                cube
-            })
+            .map(|line| parse_step(line).unwrap())
             .collect();
 
         let intersection = cubes[0].intersect(&cubes[1]).unwrap();
 
         let ref_intersection = PowerCuboid {
-            cuboid: Cuboid {
-                top_right: Point3::new(13, 13, 13),
-                bottom_left: Point3::new(11, 11, 11),
-            },
+            cuboid: Cuboid::new(Point3::new(11, 11, 11), Point3::new(13, 13, 13)),
             power_state: false,
         };
 
-        let final_volume: u64 = cubes
+        let final_volume: u128 = cubes
             .iter()
             .enumerate()
             .filter(|(_, c)| c.power_state)
@@ -393,13 +723,10 @@ mod tests {
 
         let cubes: Vec<PowerCuboid> = input_string
             .lines()
-            .map(|line| {
-                let (_, cube) = power_cube(line).unwrap();
-                cube
-            })
+            .map(|line| parse_step(line).unwrap())
             .collect();
 
-        let final_volume: u64 = cubes
+        let final_volume: u128 = cubes
             .iter()
             .enumerate()
             .filter(|(_, c)| c.power_state)
@@ -418,13 +745,10 @@ mod tests {
 
         let cubes: Vec<PowerCuboid> = input_string
             .lines()
-            .map(|line| {
-                let (_, cube) = power_cube(line).unwrap();
-                cube
-            })
+            .map(|line| parse_step(line).unwrap())
             .collect();
 
-        let final_volume: u64 = cubes
+        let final_volume: u128 = cubes
             .iter()
             .enumerate()
             .filter(|(_, c)| c.power_state)
@@ -436,35 +760,12 @@ mod tests {
 
     #[test]
     fn full_centre_power_cycle() {
-        let input_string = "on x=-20..26,y=-36..17,z=-47..7
-        on x=-20..33,y=-21..23,z=-26..28
-        on x=-22..28,y=-29..23,z=-38..16
-        on x=-46..7,y=-6..46,z=-50..-1
-        on x=-49..1,y=-3..46,z=-24..28
-        on x=2..47,y=-22..22,z=-23..27
-        on x=-27..23,y=-28..26,z=-21..29
-        on x=-39..5,y=-6..47,z=-3..44
-        on x=-30..21,y=-8..43,z=-13..34
-        on x=-22..26,y=-27..20,z=-29..19
-        off x=-48..-32,y=26..41,z=-47..-37
-        on x=-12..35,y=6..50,z=-50..-2
-        off x=-48..-32,y=-32..-16,z=-15..-5
-        on x=-18..26,y=-33..15,z=-7..46
-        off x=-40..-22,y=-38..-28,z=23..41
-        on x=-16..35,y=-41..10,z=-47..6
-        off x=-32..-23,y=11..30,z=-14..3
-        on x=-49..-5,y=-3..45,z=-29..18
-        off x=18..30,y=-20..-8,z=-3..13
-        on x=-41..9,y=-7..43,z=-33..15
-        on x=-54112..-39298,y=-85059..-49293,z=-27449..7877
-        on x=967..23432,y=45373..81175,z=27513..53682";
-
-        let target_volume = Cuboid::new(Point3::new(-50, -50, -50), Point3::new(51, 51, 51));
-
-        let cubes = input_string
+        let target_volume = Cuboid::from_inclusive([-50..=50, -50..=50, -50..=50]);
+
+        let cubes = SMALL_REBOOT
             .lines()
             .filter_map(|line| {
-                let (_, cube) = power_cube(line).unwrap();
+                let cube = parse_step(line).unwrap();
                 if cube.inside_volume(&target_volume) {
                     Some(cube)
                 } else {
@@ -475,7 +776,7 @@ mod tests {
 
         assert_eq!(cubes.len(), 20);
 
-        let final_volume: u64 = cubes
+        let final_volume: u128 = cubes
             .iter()
             .enumerate()
             .filter(|(_, c)| c.power_state)
@@ -487,76 +788,12 @@ mod tests {
 
     #[test]
     fn full_on_bonkers_activation() {
-        let input_string = "on x=-5..47,y=-31..22,z=-19..33
-        on x=-44..5,y=-27..21,z=-14..35
-        on x=-49..-1,y=-11..42,z=-10..38
-        on x=-20..34,y=-40..6,z=-44..1
-        off x=26..39,y=40..50,z=-2..11
-        on x=-41..5,y=-41..6,z=-36..8
-        off x=-43..-33,y=-45..-28,z=7..25
-        on x=-33..15,y=-32..19,z=-34..11
-        off x=35..47,y=-46..-34,z=-11..5
-        on x=-14..36,y=-6..44,z=-16..29
-        on x=-57795..-6158,y=29564..72030,z=20435..90618
-        on x=36731..105352,y=-21140..28532,z=16094..90401
-        on x=30999..107136,y=-53464..15513,z=8553..71215
-        on x=13528..83982,y=-99403..-27377,z=-24141..23996
-        on x=-72682..-12347,y=18159..111354,z=7391..80950
-        on x=-1060..80757,y=-65301..-20884,z=-103788..-16709
-        on x=-83015..-9461,y=-72160..-8347,z=-81239..-26856
-        on x=-52752..22273,y=-49450..9096,z=54442..119054
-        on x=-29982..40483,y=-108474..-28371,z=-24328..38471
-        on x=-4958..62750,y=40422..118853,z=-7672..65583
-        on x=55694..108686,y=-43367..46958,z=-26781..48729
-        on x=-98497..-18186,y=-63569..3412,z=1232..88485
-        on x=-726..56291,y=-62629..13224,z=18033..85226
-        on x=-110886..-34664,y=-81338..-8658,z=8914..63723
-        on x=-55829..24974,y=-16897..54165,z=-121762..-28058
-        on x=-65152..-11147,y=22489..91432,z=-58782..1780
-        on x=-120100..-32970,y=-46592..27473,z=-11695..61039
-        on x=-18631..37533,y=-124565..-50804,z=-35667..28308
-        on x=-57817..18248,y=49321..117703,z=5745..55881
-        on x=14781..98692,y=-1341..70827,z=15753..70151
-        on x=-34419..55919,y=-19626..40991,z=39015..114138
-        on x=-60785..11593,y=-56135..2999,z=-95368..-26915
-        on x=-32178..58085,y=17647..101866,z=-91405..-8878
-        on x=-53655..12091,y=50097..105568,z=-75335..-4862
-        on x=-111166..-40997,y=-71714..2688,z=5609..50954
-        on x=-16602..70118,y=-98693..-44401,z=5197..76897
-        on x=16383..101554,y=4615..83635,z=-44907..18747
-        off x=-95822..-15171,y=-19987..48940,z=10804..104439
-        on x=-89813..-14614,y=16069..88491,z=-3297..45228
-        on x=41075..99376,y=-20427..49978,z=-52012..13762
-        on x=-21330..50085,y=-17944..62733,z=-112280..-30197
-        on x=-16478..35915,y=36008..118594,z=-7885..47086
-        off x=-98156..-27851,y=-49952..43171,z=-99005..-8456
-        off x=2032..69770,y=-71013..4824,z=7471..94418
-        on x=43670..120875,y=-42068..12382,z=-24787..38892
-        off x=37514..111226,y=-45862..25743,z=-16714..54663
-        off x=25699..97951,y=-30668..59918,z=-15349..69697
-        off x=-44271..17935,y=-9516..60759,z=49131..112598
-        on x=-61695..-5813,y=40978..94975,z=8655..80240
-        off x=-101086..-9439,y=-7088..67543,z=33935..83858
-        off x=18020..114017,y=-48931..32606,z=21474..89843
-        off x=-77139..10506,y=-89994..-18797,z=-80..59318
-        off x=8476..79288,y=-75520..11602,z=-96624..-24783
-        on x=-47488..-1262,y=24338..100707,z=16292..72967
-        off x=-84341..13987,y=2429..92914,z=-90671..-1318
-        off x=-37810..49457,y=-71013..-7894,z=-105357..-13188
-        off x=-27365..46395,y=31009..98017,z=15428..76570
-        off x=-70369..-16548,y=22648..78696,z=-1892..86821
-        on x=-53470..21291,y=-120233..-33476,z=-44150..38147
-        off x=-93533..-4276,y=-16170..68771,z=-104985..-24507";
-
-        let cubes = input_string
+        let cubes = FULL_REBOOT
             .lines()
-            .map(|line| {
-                let (_, cube) = power_cube(line).unwrap();
-                cube
-            })
+            .map(|line| parse_step(line).unwrap())
             .collect_vec();
 
-        let final_volume: u64 = cubes
+        let final_volume: u128 = cubes
             .iter()
             .enumerate()
             .filter(|(_, c)| c.power_state)
@@ -565,4 +802,284 @@ mod tests {
 
         assert_eq!(final_volume, 2758514936282235);
     }
+
+    #[test]
+    fn registered_parts() {
+        assert_eq!(part1(SMALL_REBOOT).unwrap(), 590784);
+        assert_eq!(part1(FULL_REBOOT).unwrap(), 474140);
+        assert_eq!(part2(FULL_REBOOT).unwrap(), 2758514936282235);
+
+        let solved = (DAY.parts.1.unwrap())(FULL_REBOOT, InputKind::Real).unwrap();
+        assert!(matches!(solved.answer, Answer::UInt(2758514936282235)));
+    }
+
+    #[test]
+    fn signed_matches_recursive() {
+        let cubes = FULL_REBOOT
+            .lines()
+            .map(|line| parse_step(line).unwrap())
+            .collect_vec();
+
+        for length in [1, 5, 20, 40, cubes.len()] {
+            let steps = &cubes[..length];
+            let recursive: u128 = steps
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| c.power_state)
+                .map(|(idx, c)| c.compute_on_volume(&steps[idx + 1..]))
+                .sum();
+
+            assert_eq!(on_volume(steps), recursive);
+        }
+    }
+
+    #[test]
+    fn touching_cuboids() {
+        let cubes = [
+            PowerCuboid::new(true, Cuboid::from_half_open([0..2, 0..2, 0..2])),
+            PowerCuboid::new(true, Cuboid::from_half_open([2..4, 0..2, 0..2])),
+        ];
+
+        assert!(cubes[0].intersect(&cubes[1]).is_none());
+        assert_eq!(on_volume(&cubes), 16);
+    }
+
+    #[test]
+    fn heavy_overlap() {
+        let cubes = overlapping_reboot(12);
+        let recursive: u128 = cubes
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.power_state)
+            .map(|(idx, c)| c.compute_on_volume(&cubes[idx + 1..]))
+            .sum();
+
+        assert_eq!(on_volume(&cubes), recursive);
+    }
+
+    // Lots of steps all overlapping around the origin, the recursion goes
+    // through every subset of them
+    fn overlapping_reboot(steps: usize) -> Vec<PowerCuboid> {
+        let mut seed: u64 = 0x2021_0022;
+        let mut next = |range: i64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((seed >> 33) % range as u64) as i64
+        };
+
+        (0..steps)
+            .map(|id| {
+                let mut axis = || -1 - next(20)..1 + next(20);
+                let ranges = [axis(), axis(), axis()];
+                PowerCuboid::new(id % 3 != 2, Cuboid::from_half_open(ranges))
+            })
+            .collect()
+    }
+
+    #[test]
+    #[ignore]
+    fn signed_speedup() {
+        // The generated steps seldom overlap more than a few deep, where the
+        // recursion is still quick, so these all share the origin
+        let cubes = overlapping_reboot(24);
+        bench::faster(
+            ("Recursive", || {
+                cubes
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, c)| c.power_state)
+                    .map(|(idx, c)| c.compute_on_volume(&cubes[idx + 1..]))
+                    .sum::<u128>()
+            }),
+            ("Signed", || on_volume(&cubes)),
+        );
+    }
+
+    #[test]
+    fn malformed_steps() {
+        let cases = [
+            ("toggle x=1..2,y=1..2,z=1..2", "Invalid reboot step"),
+            ("on x=1..2,y=1..2", "Expected the x, y and z axes"),
+            ("on x=1..2,z=1..2,y=1..2", "Expected the x, y and z axes"),
+            (
+                "on x=1..2,y=1..2,z=1..2,x=1..2",
+                "Expected the x, y and z axes",
+            ),
+            ("off x=1..2,y=5..-5,z=1..2", "Reversed y range 5..-5"),
+            (
+                "on x=1..2,y=1..2,z=1..99999999999999999999",
+                "Invalid reboot step",
+            ),
+            ("on x=1..2,y=1..2,z=1..2 trailing", "Invalid reboot step"),
+        ];
+
+        for (line, error) in cases {
+            let message = parse_step(line).unwrap_err();
+            assert!(message.starts_with(error), "{}: {}", line, message);
+        }
+    }
+
+    #[test]
+    fn reboot_skips_blank_lines() {
+        let steps =
+            parse_reboot("on x=0..1,y=0..1,z=0..1\n\n  off x=0..0,y=0..0,z=0..0\n").unwrap();
+
+        assert_eq!(steps.len(), 2);
+        assert!(parse_reboot("on x=0..1,y=0..1,z=0..1\non").is_err());
+    }
+
+    #[test]
+    fn csg_reboot() {
+        // The same reboot built only out of subtractions
+        let cubes = parse_reboot(FULL_REBOOT).unwrap();
+        let lit = lit_cuboids(&cubes);
+
+        let on_cuboids = cubes
+            .iter()
+            .filter(|c| c.power_state)
+            .map(|c| c.cuboid.clone())
+            .collect_vec();
+
+        assert_eq!(
+            lit.iter().map(|c| c.volume()).sum::<u128>(),
+            on_volume(&cubes)
+        );
+        assert!(Cuboid::union_volume(&on_cuboids) >= on_volume(&cubes));
+    }
+
+    #[test]
+    fn octree_matches_signed() {
+        assert_eq!(octree_part1(SMALL_REBOOT).unwrap(), 590784);
+        assert_eq!(
+            octree_volume(&overlapping_reboot(40)),
+            on_volume(&overlapping_reboot(40))
+        );
+        assert_eq!(octree_volume(&[]), 0);
+    }
+
+    #[test]
+    fn octree_full_reboot() {
+        assert_eq!(octree_part2(FULL_REBOOT).unwrap(), 2758514936282235);
+    }
+
+    #[test]
+    fn broken_reboot() {
+        assert!(part1("on x=1..2").is_err());
+        assert!(octree_part2("hello world").is_err());
+    }
+
+    #[test]
+    fn incremental_reactor() {
+        let mut reactor = Reactor::new();
+        let steps = parse_reboot(
+            "on x=10..12,y=10..12,z=10..12
+            on x=11..13,y=11..13,z=11..13
+            off x=9..11,y=9..11,z=9..11
+            on x=10..10,y=10..10,z=10..10",
+        )
+        .unwrap();
+
+        let mut volumes = Vec::new();
+        for step in steps.iter() {
+            reactor.apply_step(step);
+            volumes.push(reactor.on_volume().unwrap());
+        }
+
+        assert_eq!(volumes, vec![27, 46, 38, 39]);
+        // Turning everything off leaves nothing to remember
+        reactor.apply_step(&parse_step("off x=0..20,y=0..20,z=0..20").unwrap());
+        assert_eq!(reactor.on_volume(), Ok(0));
+        assert_eq!(reactor.corrections(), 0);
+    }
+
+    #[test]
+    fn streaming_errors() {
+        assert!(run_reboot("on x=0..1,y=0..1,z=0..1\noff x=0..1", |_| true).is_err());
+    }
+
+    #[test]
+    fn big_coordinates() {
+        let steps =
+            "on x=-3000000000..2999999999,y=-3000000000..2999999999,z=-3000000000..2999999999
+        off x=0..2999999999,y=-3000000000..2999999999,z=-3000000000..2999999999
+        on x=5000000000..5000000000,y=0..0,z=0..0";
+
+        assert_eq!(part2(steps).unwrap(), 108000000000000000000000000001);
+        assert_eq!(octree_part2(steps).unwrap(), 108000000000000000000000000001);
+        let too_big = volume_answer(part2(steps));
+        assert!(matches!(too_big, Err(AocError::Unsolvable(_))));
+        assert!(parse_step("on x=0..9223372036854775807,y=0..1,z=0..1").is_err());
+    }
+
+    #[test]
+    fn render_mesh() {
+        let path = std::env::temp_dir().join("aoc_day22_render.obj");
+        render(
+            "on x=10..12,y=10..12,z=10..12\noff x=11..13,y=11..13,z=11..13",
+            InputKind::Real,
+            &path,
+        )
+        .unwrap();
+
+        let obj = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        // Carving the corner leaves three slabs, but only the outside is drawn:
+        // the three faces of the notch and the six of the cube, two of them
+        // L-shaped and cut in two
+        assert!(obj.starts_with("# 12 quads\n"));
+        assert_eq!(obj.lines().filter(|l| l.starts_with("f ")).count(), 12);
+        assert!(render("on x=1..2", InputKind::Real, &path).is_err());
+
+        let path = path.with_extension("gltf");
+        render("on x=10..12,y=10..12,z=10..12", InputKind::Real, &path).unwrap();
+        let gltf = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(gltf.contains(r#""min": [10,10,10], "max": [13,13,13]"#));
+    }
+
+    #[test]
+    fn render_slices() {
+        let path = std::env::temp_dir().join("aoc_day22_render.svg");
+        render(
+            "on x=10..12,y=10..12,z=10..12\noff x=11..13,y=11..13,z=11..13",
+            InputKind::Real,
+            &path,
+        )
+        .unwrap();
+
+        let svg = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        // Only three heights to cut at, the bottom one is the whole square
+        assert_eq!(svg.matches("inkscape:groupmode=\"layer\"").count(), 3);
+        assert!(svg.contains("<title>Slice at z = 10, 3 lit cuboids</title>"));
+        assert!(svg.contains("<title>Slice at z = 12, 2 lit cuboids</title>"));
+        assert!(svg.contains("<title>x=11..12, y=11..12, z=10..10</title>"));
+        assert!(slices_svg(&[]).is_empty());
+    }
+
+    #[test]
+    fn random_reboots() {
+        cross_check(
+            0x2021_2200,
+            300,
+            |rng| {
+                let steps = rng.below(9) as usize;
+                testgen::reboot_steps(rng, steps)
+            },
+            &[
+                ("signed", |input| {
+                    (part1(input).map_err(|e| e.to_string()), part2(input).map_err(|e| e.to_string()))
+                }),
+                ("octree", |input| {
+                    (
+                        octree_part1(input).map_err(|e| e.to_string()),
+                        octree_part2(input).map_err(|e| e.to_string()),
+                    )
+                }),
+            ],
+        );
+    }
 }
@@ -0,0 +1,1351 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::iter;
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering as AtomicOrdering};
+
+use hashbrown::HashMap;
+use itertools::Itertools;
+use rayon::prelude::*;
+
+use crate::aoc_lib::config::InputKind;
+use crate::aoc_lib::error::write_error;
+use crate::aoc_lib::progress::Progress;
+use crate::aoc_lib::viz::{self, Frame, Visualize};
+use crate::aoc_lib::prelude::*;
+
+const HALLWAY_LENGTH: usize = 11;
+const ROOMS: usize = 4;
+// Hallway cells right outside the rooms, nobody can stop there
+const ROOM_COLUMNS: [usize; ROOMS] = [2, 4, 6, 8];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+enum AmphiType {
+    Amber = 1,
+    Bronze = 10,
+    Copper = 100,
+    Desert = 1000,
+}
+
+impl AmphiType {
+    const ALL: [AmphiType; 4] = [
+        AmphiType::Amber,
+        AmphiType::Bronze,
+        AmphiType::Copper,
+        AmphiType::Desert,
+    ];
+
+    fn from_char(amphi_char: char) -> Option<Self> {
+        match amphi_char {
+            'A' => Some(AmphiType::Amber),
+            'B' => Some(AmphiType::Bronze),
+            'C' => Some(AmphiType::Copper),
+            'D' => Some(AmphiType::Desert),
+            _ => None,
+        }
+    }
+
+    fn to_char(self) -> char {
+        match self {
+            AmphiType::Amber => 'A',
+            AmphiType::Bronze => 'B',
+            AmphiType::Copper => 'C',
+            AmphiType::Desert => 'D',
+        }
+    }
+
+    fn target_room(self) -> usize {
+        match self {
+            AmphiType::Amber => 0,
+            AmphiType::Bronze => 1,
+            AmphiType::Copper => 2,
+            AmphiType::Desert => 3,
+        }
+    }
+}
+
+// Nodes below HALLWAY_LENGTH are the hallway cells from left to right, the
+// others are room slots numbered row by row starting from the one next to the
+// hallway
+fn room_node(row: usize, room: usize) -> usize {
+    HALLWAY_LENGTH + row * ROOMS + room
+}
+
+fn room_slot(node: usize) -> Option<(usize, usize)> {
+    let slot = node.checked_sub(HALLWAY_LENGTH)?;
+    Some((slot / ROOMS, slot % ROOMS))
+}
+
+fn hallway_stops() -> impl Iterator<Item = usize> {
+    (0..HALLWAY_LENGTH).filter(|cell| !ROOM_COLUMNS.contains(cell))
+}
+
+// The burrow as a graph of cells, with the path between every pair of them so
+// that moves only have to check that nobody is standing on it
+struct BurrowMap {
+    // paths[start][target] lists the cells walked through, start excluded
+    paths: Vec<Vec<Vec<usize>>>,
+}
+
+impl BurrowMap {
+    fn new(depth: usize) -> Self {
+        let cells = HALLWAY_LENGTH + depth * ROOMS;
+        let mut neighbours: Vec<Vec<usize>> = vec![Vec::new(); cells];
+        let mut connect = |a: usize, b: usize| {
+            neighbours[a].push(b);
+            neighbours[b].push(a);
+        };
+
+        for cell in 1..HALLWAY_LENGTH {
+            connect(cell - 1, cell);
+        }
+        for (room, column) in ROOM_COLUMNS.iter().enumerate() {
+            connect(*column, room_node(0, room));
+            for row in 1..depth {
+                connect(room_node(row - 1, room), room_node(row, room));
+            }
+        }
+
+        Self {
+            paths: (0..cells)
+                .map(|start| Self::paths_from(&neighbours, start))
+                .collect(),
+        }
+    }
+
+    // Breadth first, the burrow is a tree so every path is the only one
+    fn paths_from(neighbours: &[Vec<usize>], start: usize) -> Vec<Vec<usize>> {
+        let mut parents: Vec<Option<usize>> = vec![None; neighbours.len()];
+        let mut queue = VecDeque::from([start]);
+        while let Some(cell) = queue.pop_front() {
+            for next in neighbours[cell].iter() {
+                if *next != start && parents[*next].is_none() {
+                    parents[*next] = Some(cell);
+                    queue.push_back(*next);
+                }
+            }
+        }
+
+        (0..neighbours.len())
+            .map(|target| {
+                let mut path = Vec::new();
+                let mut cell = target;
+                while let Some(parent) = parents[cell] {
+                    path.push(cell);
+                    cell = parent;
+                }
+                path.reverse();
+                path
+            })
+            .collect()
+    }
+
+    fn path(&self, start: usize, target: usize) -> &[usize] {
+        &self.paths[start][target]
+    }
+
+    fn distance(&self, start: usize, target: usize) -> u32 {
+        self.path(start, target).len() as u32
+    }
+}
+
+// Readable form of the burrow, the one the parser builds and that gets printed
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Burrow {
+    hallway: [Option<AmphiType>; HALLWAY_LENGTH],
+    // From the row next to the hallway down
+    rooms: Vec<[Option<AmphiType>; ROOMS]>,
+}
+
+impl Burrow {
+    fn depth(&self) -> usize {
+        self.rooms.len()
+    }
+}
+
+impl fmt::Display for Burrow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let to_char = |cell: &Option<AmphiType>| cell.map_or('.', AmphiType::to_char);
+
+        writeln!(f, "#############")?;
+        writeln!(
+            f,
+            "#{}#",
+            self.hallway.iter().map(to_char).collect::<String>()
+        )?;
+        for (row, cells) in self.rooms.iter().enumerate() {
+            let border = if row == 0 { "###" } else { "  #" };
+            let cells = cells.iter().map(to_char).format("#");
+            writeln!(f, "{}{}{}", border, cells, border.trim())?;
+        }
+        write!(f, "  #########")
+    }
+}
+
+// Every node takes 3 bits: 0 when empty, the amphipod kind plus one otherwise.
+// A u128 fits the hallway and rooms up to MAX_DEPTH deep. Unused bits are
+// always zero, so equal burrows always have equal states and the derived
+// ordering and hashing can be used as they are.
+// The depth of the rooms is part of the type: both parts run the same solver,
+// built once for rooms 2 deep and once for rooms 4 deep, and any other depth
+// the parser accepts gets its own copy too.
+const NODE_BITS: usize = 3;
+const MAX_DEPTH: usize = (128 / NODE_BITS - HALLWAY_LENGTH) / ROOMS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct BurrowState<const DEPTH: usize> {
+    nodes: u128,
+}
+
+impl<const DEPTH: usize> BurrowState<DEPTH> {
+    const CELLS: usize = HALLWAY_LENGTH + DEPTH * ROOMS;
+    const FITS: () = assert!(DEPTH <= MAX_DEPTH, "Burrow too deep to pack");
+
+    fn new() -> Self {
+        let () = Self::FITS;
+        Self { nodes: 0 }
+    }
+
+    fn get(&self, node: usize) -> Option<AmphiType> {
+        let kind = (self.nodes >> (node * NODE_BITS)) & 0b111;
+        (kind != 0).then(|| AmphiType::ALL[kind as usize - 1])
+    }
+
+    fn set(&mut self, node: usize, amphipod: Option<AmphiType>) {
+        let kind = amphipod.map_or(0, |race| race.target_room() as u128 + 1);
+        self.nodes &= !(0b111 << (node * NODE_BITS));
+        self.nodes |= kind << (node * NODE_BITS);
+    }
+
+    fn with_move(&self, start: usize, target: usize) -> Self {
+        let mut moved = *self;
+        moved.set(target, self.get(start));
+        moved.set(start, None);
+        moved
+    }
+
+    // A room that only contains its own kind, so it can take amphipods in
+    fn room_ready(&self, room: usize) -> bool {
+        (0..DEPTH)
+            .filter_map(|row| self.get(room_node(row, room)))
+            .all(|race| race.target_room() == room)
+    }
+
+    fn is_solved(&self) -> bool {
+        (0..ROOMS).all(|room| {
+            (0..DEPTH).all(|row| {
+                self.get(room_node(row, room))
+                    .is_some_and(|race| race.target_room() == room)
+            })
+        })
+    }
+
+    // Already in its room with only its own kind below, it never moves again
+    fn is_settled(&self, row: usize, room: usize) -> bool {
+        (row..DEPTH).all(|below| {
+            self.get(room_node(below, room))
+                .is_some_and(|race| race.target_room() == room)
+        })
+    }
+
+    // Lower bound of the energy still needed, as if nobody was in the way.
+    // Each amphipod walks to the top of its room: the ones already there but
+    // with a stranger below have to step out and back in. Then the rooms are
+    // filled from the shallowest slot, which can only be cheaper than the
+    // deepest free one.
+    pub fn heuristic(&self, map: &BurrowMap) -> u32 {
+        let mut entering = [0u32; ROOMS];
+        let mut cost = 0;
+
+        for node in 0..Self::CELLS {
+            let Some(race) = self.get(node) else {
+                continue;
+            };
+            let target = race.target_room();
+            let distance = match room_slot(node) {
+                Some((row, room)) if room == target && self.is_settled(row, room) => continue,
+                Some((row, room)) if room == target => row as u32 + 4,
+                _ => map.distance(node, room_node(0, target)),
+            };
+
+            cost += distance * race as u32;
+            entering[target] += 1;
+        }
+
+        cost + entering
+            .iter()
+            .zip(AmphiType::ALL)
+            .map(|(count, race)| count * count.saturating_sub(1) / 2 * race as u32)
+            .sum::<u32>()
+    }
+
+    // Where the amphipod that moved to get to `after` came from and went
+    fn moved_cells(&self, after: &Self) -> Option<(usize, usize)> {
+        let cells = 0..Self::CELLS;
+        let left = |a: &Self, b: &Self, cell: usize| b.get(cell).is_none() && a.get(cell).is_some();
+
+        let from = cells.clone().find(|c| left(self, after, *c))?;
+        let to = cells.clone().find(|c| left(after, self, *c))?;
+        Some((from, to))
+    }
+
+    fn is_clear(&self, path: &[usize]) -> bool {
+        path.iter().all(|cell| self.get(*cell).is_none())
+    }
+
+    // Amphipods in the hallway can only go straight to their room, all the
+    // others can only leave their room if it's not done yet
+    fn moves(&self, map: &BurrowMap) -> Vec<(Self, u32)> {
+        let mut moves: Vec<(Self, u32)> = Vec::new();
+
+        for start in hallway_stops() {
+            let Some(race) = self.get(start) else {
+                continue;
+            };
+            let room = race.target_room();
+            if !self.room_ready(room) {
+                continue;
+            }
+            let target = (0..DEPTH)
+                .rev()
+                .map(|row| room_node(row, room))
+                .find(|target| self.get(*target).is_none());
+            if let Some(target) = target.filter(|target| self.is_clear(map.path(start, *target))) {
+                let cost = map.distance(start, target) * race as u32;
+                moves.push((self.with_move(start, target), cost));
+            }
+        }
+
+        for room in (0..ROOMS).filter(|room| !self.room_ready(*room)) {
+            let Some((start, race)) = (0..DEPTH)
+                .map(|row| room_node(row, room))
+                .find_map(|node| self.get(node).map(|race| (node, race)))
+            else {
+                continue;
+            };
+
+            for target in hallway_stops() {
+                if self.is_clear(map.path(start, target)) {
+                    let cost = map.distance(start, target) * race as u32;
+                    moves.push((self.with_move(start, target), cost));
+                }
+            }
+        }
+
+        moves
+    }
+}
+
+impl<const DEPTH: usize> From<&Burrow> for BurrowState<DEPTH> {
+    fn from(burrow: &Burrow) -> Self {
+        assert_eq!(burrow.depth(), DEPTH, "Burrow packed at the wrong depth");
+
+        let mut state = Self::new();
+        for (cell, amphipod) in burrow.hallway.iter().enumerate() {
+            state.set(cell, *amphipod);
+        }
+        for (row, cells) in burrow.rooms.iter().enumerate() {
+            for (room, amphipod) in cells.iter().enumerate() {
+                state.set(room_node(row, room), *amphipod);
+            }
+        }
+
+        state
+    }
+}
+
+impl<const DEPTH: usize> From<BurrowState<DEPTH>> for Burrow {
+    fn from(state: BurrowState<DEPTH>) -> Self {
+        Self {
+            hallway: std::array::from_fn(|cell| state.get(cell)),
+            rooms: (0..DEPTH)
+                .map(|row| std::array::from_fn(|room| state.get(room_node(row, room))))
+                .collect(),
+        }
+    }
+}
+
+impl<const DEPTH: usize> fmt::Display for BurrowState<DEPTH> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Burrow::from(*self).fmt(f)
+    }
+}
+
+fn parse_cell(cell: char) -> Result<Option<AmphiType>, ()> {
+    match cell {
+        '.' => Ok(None),
+        _ => AmphiType::from_char(cell).map(Some).ok_or(()),
+    }
+}
+
+// A room row is four cells between walls: "###B#C#B#D###" for the first one,
+// "#A#D#C#A#" for the others
+fn parse_room_row(row: &str) -> Option<[Option<AmphiType>; ROOMS]> {
+    let cells = row
+        .trim_matches('#')
+        .split('#')
+        .map(|cell| match cell.chars().collect_vec()[..] {
+            [single] => parse_cell(single).ok(),
+            _ => None,
+        })
+        .collect::<Option<Vec<Option<AmphiType>>>>()?;
+
+    cells.try_into().ok()
+}
+
+// Any diagram with the usual walls, rooms as deep as the input goes and any
+// indentation. Amphipods can already be in the hallway, as long as they are
+// not standing right outside a room.
+fn parse_input(input: &str) -> Result<Burrow, String> {
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .map(|(id, line)| (id + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty());
+    let mut next_line = |what: &str| {
+        lines
+            .next()
+            .ok_or_else(|| format!("Diagram ended while looking for {}", what))
+    };
+    let is_wall = |line: &str| !line.is_empty() && line.chars().all(|c| c == '#');
+
+    let (id, line) = next_line("the top wall")?;
+    if line.len() != HALLWAY_LENGTH + 2 || !is_wall(line) {
+        return Err(format!(
+            "Line {}: expected the top wall, got \"{}\"",
+            id, line
+        ));
+    }
+
+    let (id, line) = next_line("the hallway")?;
+    let hallway = line
+        .strip_prefix('#')
+        .and_then(|l| l.strip_suffix('#'))
+        .map(|cells| cells.chars().map(parse_cell).collect_vec())
+        .filter(|cells| cells.len() == HALLWAY_LENGTH)
+        .and_then(|cells| cells.into_iter().collect::<Result<Vec<_>, ()>>().ok())
+        .ok_or_else(|| format!("Line {}: invalid hallway \"{}\"", id, line))?;
+    if ROOM_COLUMNS.iter().any(|column| hallway[*column].is_some()) {
+        return Err(format!(
+            "Line {}: amphipods can't stop outside a room in \"{}\"",
+            id, line
+        ));
+    }
+
+    let mut burrow = Burrow {
+        hallway: hallway.try_into().unwrap(),
+        rooms: Vec::new(),
+    };
+    loop {
+        let (id, line) = next_line("the bottom wall")?;
+        if is_wall(line) {
+            break;
+        }
+        let row = parse_room_row(line)
+            .ok_or_else(|| format!("Line {}: invalid room row \"{}\"", id, line))?;
+        burrow.rooms.push(row);
+    }
+    if let Some((id, line)) = lines.next() {
+        return Err(format!(
+            "Line {}: unexpected \"{}\" after the diagram",
+            id, line
+        ));
+    }
+
+    let depth = burrow.depth();
+    if depth == 0 {
+        return Err(String::from("The burrow has no rooms"));
+    }
+    if depth > MAX_DEPTH {
+        return Err(format!("Rooms deeper than {} are not supported", MAX_DEPTH));
+    }
+    let cells = burrow.hallway.iter().chain(burrow.rooms.iter().flatten());
+    let counts = cells.flatten().counts_by(|race| race.target_room());
+    if (0..ROOMS).any(|room| counts.get(&room) != Some(&depth)) {
+        return Err(format!("Expected {} amphipods of each kind", depth));
+    }
+
+    Ok(burrow)
+}
+
+// Calls `$run` with the burrow packed in a state as deep as its rooms, and any
+// other arguments after it
+macro_rules! at_depth {
+    ($burrow:expr, $run:expr $(, $argument:expr)*) => {
+        match $burrow.depth() {
+            1 => $run(BurrowState::<1>::from($burrow) $(, $argument)*),
+            2 => $run(BurrowState::<2>::from($burrow) $(, $argument)*),
+            3 => $run(BurrowState::<3>::from($burrow) $(, $argument)*),
+            4 => $run(BurrowState::<4>::from($burrow) $(, $argument)*),
+            5 => $run(BurrowState::<5>::from($burrow) $(, $argument)*),
+            6 => $run(BurrowState::<6>::from($burrow) $(, $argument)*),
+            7 => $run(BurrowState::<7>::from($burrow) $(, $argument)*),
+            depth => unreachable!("The parser lets rooms {} deep through", depth),
+        }
+    };
+}
+
+// A* ordered by cost plus the heuristic. The heuristic is admissible but not
+// always consistent, so states found again with a lower cost get queued again
+// and the stale entries are skipped when they are popped.
+// With `record` set the predecessor of every state is kept too, and the states
+// leading to the solution are returned along with the cost.
+fn search<const DEPTH: usize>(
+    start: BurrowState<DEPTH>,
+    map: &BurrowMap,
+    heuristic: impl Fn(&BurrowState<DEPTH>) -> u32,
+    record: bool,
+) -> Option<(u32, Vec<BurrowState<DEPTH>>)> {
+    let bound = AtomicU32::new(u32::MAX);
+
+    bounded_search(start, 0, map, heuristic, record, &bound)
+}
+
+// The search above, starting after `start_cost` energy was already spent.
+// Nothing is explored once it can't beat `bound`, and every solution found
+// lowers it, so searches running at the same time prune each other.
+fn bounded_search<const DEPTH: usize>(
+    start: BurrowState<DEPTH>,
+    start_cost: u32,
+    map: &BurrowMap,
+    heuristic: impl Fn(&BurrowState<DEPTH>) -> u32,
+    record: bool,
+    bound: &AtomicU32,
+) -> Option<(u32, Vec<BurrowState<DEPTH>>)> {
+    let progress = Progress::attached();
+    let mut queue: BinaryHeap<Reverse<(u32, u32, BurrowState<DEPTH>)>> = BinaryHeap::new();
+    let mut best: HashMap<BurrowState<DEPTH>, u32> = HashMap::new();
+    let mut predecessors: HashMap<BurrowState<DEPTH>, BurrowState<DEPTH>> = HashMap::new();
+    queue.push(Reverse((start_cost + heuristic(&start), start_cost, start)));
+    best.insert(start, start_cost);
+
+    while let Some(Reverse((estimate, cost, burrow))) = queue.pop() {
+        // The queue is sorted, nothing after this can do better either
+        if estimate >= bound.load(AtomicOrdering::Relaxed) {
+            return None;
+        }
+        if burrow.is_solved() {
+            if cost < bound.fetch_min(cost, AtomicOrdering::Relaxed) {
+                progress.set_message(format!("cheapest so far {}", cost));
+            }
+
+            let mut path = vec![burrow];
+            while let Some(previous) = predecessors.get(path.last().unwrap()) {
+                path.push(*previous);
+            }
+            path.reverse();
+
+            return Some((cost, if record { path } else { Vec::new() }));
+        }
+        if best.get(&burrow).is_some_and(|known| *known < cost) {
+            continue;
+        }
+        progress.tick();
+
+        for (next, move_cost) in burrow.moves(map) {
+            let next_cost = cost + move_cost;
+            if best.get(&next).is_none_or(|known| next_cost < *known) {
+                best.insert(next, next_cost);
+                if record {
+                    predecessors.insert(next, burrow);
+                }
+                queue.push(Reverse((next_cost + heuristic(&next), next_cost, next)));
+            }
+        }
+    }
+
+    None
+}
+
+fn compute_cost<const DEPTH: usize>(start: BurrowState<DEPTH>) -> Option<u32> {
+    let map = BurrowMap::new(DEPTH);
+
+    search(start, &map, |burrow| burrow.heuristic(&map), false).map(|(cost, _)| cost)
+}
+
+// Every first move gets its own search on the thread pool, they all share the
+// cheapest cost found so far
+fn parallel_cost<const DEPTH: usize>(start: BurrowState<DEPTH>) -> Option<u32> {
+    if start.is_solved() {
+        return Some(0);
+    }
+    let map = BurrowMap::new(DEPTH);
+    let bound = AtomicU32::new(u32::MAX);
+
+    start
+        .moves(&map)
+        .into_par_iter()
+        .for_each(|(next, move_cost)| {
+            let heuristic = |burrow: &BurrowState<DEPTH>| burrow.heuristic(&map);
+            bounded_search(next, move_cost, &map, heuristic, false, &bound);
+        });
+
+    let cost = bound.into_inner();
+    (cost != u32::MAX).then_some(cost)
+}
+
+fn describe_cell(cell: usize) -> String {
+    match room_slot(cell) {
+        Some((row, room)) => format!("room {} slot {}", AmphiType::ALL[room].to_char(), row + 1),
+        None => format!("hallway {}", cell),
+    }
+}
+
+// The cheapest way to sort the burrow, every move described and followed by
+// the burrow after it
+struct Plan<const DEPTH: usize> {
+    start: BurrowState<DEPTH>,
+    moves: Vec<(String, BurrowState<DEPTH>)>,
+    cost: u32,
+}
+
+fn plan<const DEPTH: usize>(start: BurrowState<DEPTH>) -> Result<Plan<DEPTH>, String> {
+    let map = BurrowMap::new(DEPTH);
+    let (cost, path) = search(start, &map, |burrow| burrow.heuristic(&map), true)
+        .ok_or_else(|| String::from("The amphipods can't be sorted"))?;
+
+    let mut moves = Vec::new();
+    let mut total = 0;
+    for (step, (before, after)) in path.iter().tuple_windows().enumerate() {
+        let Some((from, to)) = before.moved_cells(after) else {
+            return Err(format!("Step {} doesn't move anybody", step + 1));
+        };
+        let race = after.get(to).unwrap();
+        let energy = map.distance(from, to) * race as u32;
+        total += energy;
+
+        let description = format!(
+            "Step {}: {} from {} to {}, {} energy ({} total)",
+            step + 1,
+            race.to_char(),
+            describe_cell(from),
+            describe_cell(to),
+            energy,
+            total
+        );
+        moves.push((description, *after));
+    }
+
+    Ok(Plan { start, moves, cost })
+}
+
+// Every step of the cheapest plan with the diagram after the move
+fn replay<const DEPTH: usize>(start: BurrowState<DEPTH>) -> Result<String, String> {
+    let plan = plan(start)?;
+
+    let mut output = format!("{}\n", plan.start);
+    for (description, after) in &plan.moves {
+        writeln!(output, "\n{}\n{}", description, after).unwrap();
+    }
+    writeln!(output, "\nMinimum cost: {}", plan.cost).unwrap();
+
+    Ok(output)
+}
+
+// The cost of the cheapest plan, with its moves and diagrams as notes
+fn plan_notes<const DEPTH: usize>(start: BurrowState<DEPTH>) -> Result<Solved, String> {
+    let plan = plan(start)?;
+
+    let mut replayed = Solved::from(Answer::from(plan.cost)).note(plan.start.to_string());
+    for (description, after) in &plan.moves {
+        replayed = replayed.note(format!("\n{}\n{}", description, after));
+    }
+
+    Ok(replayed)
+}
+
+fn show_plan<const DEPTH: usize>(
+    start: BurrowState<DEPTH>,
+    cast: Option<&Path>,
+) -> Result<(), AocError> {
+    viz::show(&plan(start)?, cast)
+}
+
+impl<const DEPTH: usize> Visualize for Plan<DEPTH> {
+    fn frames(&self) -> impl Iterator<Item = Frame> {
+        let start = Frame {
+            caption: format!("Cheapest plan: {} energy", self.cost),
+            picture: self.start.to_string(),
+        };
+
+        iter::once(start).chain(self.moves.iter().map(|(description, after)| Frame {
+            caption: description.clone(),
+            picture: after.to_string(),
+        }))
+    }
+}
+
+// Part 2 unfolds the diagram, adding two rows between the ones in the input
+fn augment_input(input: &str) -> String {
+    let start = input.lines().take(3).join("\n");
+    let end = input.lines().skip(3).join("\n");
+
+    format!("{}\n{}\n{}\n{}", start, "#D#C#B#A#", "#D#B#A#C#", end)
+}
+
+fn minimum_cost(input: &str) -> Result<u32, String> {
+    let burrow = parse_input(input)?;
+
+    at_depth!(&burrow, compute_cost).ok_or_else(|| String::from("The amphipods can't be sorted"))
+}
+
+fn cost_answer(cost: Result<u32, String>) -> Result<Answer, AocError> {
+    Progress::attached().finish();
+    Ok(Answer::from(cost?))
+}
+
+fn replay_input(input: &str) -> Result<String, String> {
+    let burrow = parse_input(input)?;
+
+    at_depth!(&burrow, replay)
+}
+
+fn replay_notes(input: &str) -> Result<Solved, AocError> {
+    let burrow = parse_input(input)?;
+
+    Ok(at_depth!(&burrow, plan_notes)?)
+}
+
+pub fn replay_part1(input: &str) -> Result<Solved, AocError> {
+    replay_notes(input)
+}
+
+pub fn replay_part2(input: &str) -> Result<Solved, AocError> {
+    replay_notes(&augment_input(input))
+}
+
+// The cheapest plan of the first part, move by move
+pub fn visualize(input: &str, _kind: InputKind, cast: Option<&Path>) -> Result<(), AocError> {
+    let burrow = parse_input(input)?;
+
+    at_depth!(&burrow, show_plan, cast)
+}
+
+// Both plans, one after the other
+pub fn render(input: &str, _kind: InputKind, path: &Path) -> Result<(), AocError> {
+    let plans = format!(
+        "Part 1\n\n{}\nPart 2\n\n{}",
+        replay_input(input)?,
+        replay_input(&augment_input(input))?
+    );
+
+    fs::write(path, plans).map_err(|e| write_error(path, e))
+}
+
+// Cells as typed while playing: "h3" is the fourth hallway cell from the left,
+// "c1" the top of room C
+fn cell_name(cell: usize) -> String {
+    match room_slot(cell) {
+        Some((row, room)) => format!("{}{}", AmphiType::ALL[room].to_char(), row + 1),
+        None => format!("h{}", cell),
+    }
+    .to_lowercase()
+}
+
+fn parse_cell_name(name: &str, depth: usize) -> Option<usize> {
+    let mut chars = name.chars();
+    let kind = chars.next()?.to_ascii_uppercase();
+    let number: usize = chars.as_str().parse().ok()?;
+
+    match (kind, AmphiType::from_char(kind)) {
+        ('H', _) => (number < HALLWAY_LENGTH).then_some(number),
+        (_, Some(race)) => (1..=depth)
+            .contains(&number)
+            .then(|| room_node(number - 1, race.target_room())),
+        _ => None,
+    }
+}
+
+// Play the burrow by hand, only the moves the solver would consider are
+// allowed. Commands:
+//   s <cell>          show where the amphipod in the cell can go
+//   m [<cell>] <cell> move the selected amphipod (or the one in the first cell)
+//   u                 undo the last move
+//   q                 quit
+struct Game<const DEPTH: usize> {
+    map: BurrowMap,
+    state: BurrowState<DEPTH>,
+    energy: u32,
+    history: Vec<(BurrowState<DEPTH>, u32)>,
+    selected: Option<usize>,
+}
+
+impl<const DEPTH: usize> Game<DEPTH> {
+    fn new(start: BurrowState<DEPTH>) -> Self {
+        Self {
+            map: BurrowMap::new(DEPTH),
+            state: start,
+            energy: 0,
+            history: Vec::new(),
+            selected: None,
+        }
+    }
+
+    // Destinations of the amphipod in `from`, with the energy needed
+    fn destinations(&self, from: usize) -> Vec<(usize, u32, BurrowState<DEPTH>)> {
+        self.state
+            .moves(&self.map)
+            .into_iter()
+            .filter_map(|(next, cost)| match self.state.moved_cells(&next) {
+                Some((start, target)) if start == from => Some((target, cost, next)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn status(&self) -> String {
+        format!("{}\nEnergy: {}", self.state, self.energy)
+    }
+
+    fn select(&mut self, argument: &str) -> String {
+        let Some(cell) = parse_cell_name(argument, DEPTH) else {
+            return format!("Invalid cell: \"{}\"", argument);
+        };
+        let Some(race) = self.state.get(cell) else {
+            return format!("Nobody in {}", cell_name(cell));
+        };
+
+        self.selected = Some(cell);
+        let destinations = self.destinations(cell);
+        if destinations.is_empty() {
+            return format!("{} in {} can't move", race.to_char(), cell_name(cell));
+        }
+        destinations
+            .iter()
+            .map(|(target, cost, _)| format!("{}: {} energy", cell_name(*target), cost))
+            .join("\n")
+    }
+
+    fn move_to(&mut self, argument: &str) -> String {
+        let cells = argument
+            .split_whitespace()
+            .map(|name| parse_cell_name(name, DEPTH).ok_or(name))
+            .collect::<Result<Vec<usize>, &str>>();
+        let (from, to) = match (cells.as_deref(), self.selected) {
+            (Ok([from, to]), _) => (*from, *to),
+            (Ok([to]), Some(from)) => (from, *to),
+            (Ok([_]), None) => return String::from("Select an amphipod first"),
+            (Err(name), _) => return format!("Invalid cell: \"{}\"", name),
+            _ => return String::from("Usage: m [<from>] <to>"),
+        };
+
+        match self
+            .destinations(from)
+            .into_iter()
+            .find(|(t, _, _)| *t == to)
+        {
+            Some((_, cost, next)) => {
+                self.history.push((self.state, self.energy));
+                self.state = next;
+                self.energy += cost;
+                self.selected = None;
+                if self.state.is_solved() {
+                    format!("{}\nAll amphipods are home!", self.status())
+                } else {
+                    self.status()
+                }
+            }
+            None => format!("Can't move from {} to {}", cell_name(from), cell_name(to)),
+        }
+    }
+
+    fn play(&mut self, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+        writeln!(output, "{}", self.status())?;
+
+        for line in input.lines() {
+            let line = line?;
+            let line = line.trim();
+            let (command, argument) = line.split_once(' ').unwrap_or((line, ""));
+            let argument = argument.trim();
+
+            let reply = match command {
+                "s" | "select" => self.select(argument),
+                "m" | "move" => self.move_to(argument),
+                "u" | "undo" => match self.history.pop() {
+                    Some((state, energy)) => {
+                        (self.state, self.energy) = (state, energy);
+                        self.status()
+                    }
+                    None => String::from("Nothing to undo"),
+                },
+                "q" | "quit" => break,
+                "" => continue,
+                _ => format!("Unknown command: \"{}\"", command),
+            };
+            writeln!(output, "{}", reply)?;
+        }
+
+        Ok(())
+    }
+}
+
+// The energy spent by the time the player quits
+fn play_game<const DEPTH: usize>(
+    start: BurrowState<DEPTH>,
+    commands: &mut dyn BufRead,
+    replies: &mut dyn Write,
+) -> io::Result<u32> {
+    let mut game = Game::new(start);
+    game.play(commands, replies)?;
+
+    Ok(game.energy)
+}
+
+fn play_input(
+    input: &str,
+    commands: &mut dyn BufRead,
+    replies: &mut dyn Write,
+) -> Result<Answer, AocError> {
+    let burrow = parse_input(input)?;
+
+    Ok(Answer::from(at_depth!(
+        &burrow, play_game, commands, replies
+    )?))
+}
+
+pub fn play_part1(
+    input: &str,
+    commands: &mut dyn BufRead,
+    replies: &mut dyn Write,
+) -> Result<Answer, AocError> {
+    play_input(input, commands, replies)
+}
+
+pub fn play_part2(
+    input: &str,
+    commands: &mut dyn BufRead,
+    replies: &mut dyn Write,
+) -> Result<Answer, AocError> {
+    play_input(&augment_input(input), commands, replies)
+}
+
+fn parallel_minimum_cost(input: &str) -> Result<u32, String> {
+    let burrow = parse_input(input)?;
+
+    at_depth!(&burrow, parallel_cost).ok_or_else(|| String::from("The amphipods can't be sorted"))
+}
+
+pub fn parallel_part1(input: &str) -> Result<Answer, AocError> {
+    cost_answer(parallel_minimum_cost(input))
+}
+
+pub fn parallel_part2(input: &str) -> Result<Answer, AocError> {
+    cost_answer(parallel_minimum_cost(&augment_input(input)))
+}
+
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    cost_answer(minimum_cost(input))
+}
+
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    cost_answer(minimum_cost(&augment_input(input)))
+}
+
+aoc_day!(
+    2021,
+    23,
+    part1,
+    part2;
+    replay = (replay_part1, replay_part2),
+    parallel = (parallel_part1, parallel_part2),
+    play = interactive(play_part1, play_part2),
+    render = render,
+    visualize = visualize,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aoc_lib::random::{cross_check, fuzz, Xorshift};
+    use crate::aoc_lib::testgen;
+
+    static EXAMPLE: &str = include_str!("../../inputs/2021/day23.example.txt");
+
+    fn state<const DEPTH: usize>(input: &str) -> BurrowState<DEPTH> {
+        BurrowState::from(&parse_input(input).unwrap())
+    }
+
+    #[test]
+    fn parse() {
+        let burrow = state::<2>(EXAMPLE);
+
+        assert_eq!(burrow.get(room_node(0, 0)), Some(AmphiType::Bronze));
+        assert_eq!(burrow.get(room_node(1, 3)), Some(AmphiType::Amber));
+        assert_eq!(burrow.get(0), None);
+        assert_eq!(
+            burrow.to_string(),
+            "#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########"
+        );
+    }
+
+    #[test]
+    fn parse_unfolded() {
+        let burrow = state::<4>(&augment_input(EXAMPLE));
+
+        assert!(burrow
+            .to_string()
+            .contains("  #D#C#B#A#\n  #D#B#A#C#\n  #A#D#C#A#"));
+        assert!((0..4).all(|room| !burrow.room_ready(room)));
+    }
+
+    #[test]
+    fn moves() {
+        let burrow = state::<2>(EXAMPLE);
+        let map = BurrowMap::new(2);
+        let moves = burrow.moves(&map);
+
+        // Every top amphipod can reach every hallway stop
+        assert_eq!(moves.len(), 4 * 7);
+        let (first, cost) = moves[0];
+        assert_eq!(first.get(0), Some(AmphiType::Bronze));
+        assert_eq!(cost, 30);
+
+        let solved =
+            state::<2>("#############\n#...........#\n###A#B#C#D###\n  #A#B#C#D#\n  #########");
+        assert!(solved.is_solved());
+        assert!(solved.moves(&map).is_empty());
+        assert_eq!(compute_cost(solved), Some(0));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(parse_input("#############\n#...........#\n###B#C#B###").is_err());
+        assert!(parse_input("#############\n#...........#\n###B#C#X#D###").is_err());
+        assert!(parse_input("#############\n#...........#\n#########").is_err());
+        assert!(parse_input(&"#A#B#C#D#\n".repeat(MAX_DEPTH + 3)).is_err());
+
+        let cases = [
+            ("", "Diagram ended while looking for the top wall"),
+            ("#####\n#...........#", "Line 1: expected the top wall"),
+            (
+                "#############\n\n#....#",
+                "Line 3: invalid hallway \"#....#\"",
+            ),
+            (
+                "#############\n#..A........#\n###B#C#B#D###",
+                "Line 2: amphipods can't stop outside a room",
+            ),
+            (
+                "#############\n#...........#\n###B#C#B#D###\n  #A#D#C#A#",
+                "Diagram ended while looking for the bottom wall",
+            ),
+            (
+                "#############\n#...........#\n###B#C#B#D###\n  #A#DC#A#\n  #########",
+                "Line 4: invalid room row \"#A#DC#A#\"",
+            ),
+            (
+                "#############\n#...........#\n###B#C#B#D###\n  #A#D#C#A#\n  #########\nextra",
+                "Line 6: unexpected \"extra\"",
+            ),
+            (
+                "#############\n#...........#\n###B#C#B#D###\n  #A#D#C#B#\n  #########",
+                "Expected 2 amphipods of each kind",
+            ),
+        ];
+        for (input, error) in cases {
+            let message = parse_input(input).unwrap_err();
+            assert!(message.starts_with(error), "{:?}: {}", input, message);
+        }
+    }
+
+    #[test]
+    fn parse_any_diagram() {
+        let messy = "
+        #############
+    #.A.........#
+        ###B#C#B#D###
+#.#D#C#A#
+              #########
+        ";
+        let burrow = parse_input(messy).unwrap();
+
+        assert_eq!(burrow.depth(), 2);
+        assert_eq!(burrow.hallway[1], Some(AmphiType::Amber));
+        assert_eq!(burrow.rooms[1][0], None);
+        // The example with the bottom A stepped out, it only has to walk back
+        assert_eq!(
+            compute_cost(BurrowState::<2>::from(&burrow)),
+            Some(12521 + 3)
+        );
+    }
+
+    #[test]
+    fn paths() {
+        let map = BurrowMap::new(4);
+
+        assert_eq!(map.path(room_node(1, 0), 0), [room_node(0, 0), 2, 1, 0]);
+        assert_eq!(map.path(0, room_node(0, 0)), [1, 2, room_node(0, 0)]);
+        assert_eq!(map.distance(6, room_node(1, 1)), 4);
+        assert_eq!(map.distance(room_node(3, 3), 1), 11);
+        assert_eq!(map.distance(room_node(0, 1), room_node(0, 2)), 4);
+    }
+
+    #[test]
+    fn hallway_blocking() {
+        let mut burrow = state::<2>(EXAMPLE);
+        let map = BurrowMap::new(2);
+        // Right between the second and third room
+        burrow = burrow.with_move(room_node(0, 2), 5);
+
+        let reachable = |room: usize| {
+            burrow
+                .moves(&map)
+                .into_iter()
+                .filter(|(next, _)| next.get(room_node(0, room)).is_none())
+                .map(|(next, _)| (0..HALLWAY_LENGTH).find(|c| next.get(*c) != burrow.get(*c)))
+                .collect_vec()
+        };
+
+        assert_eq!(reachable(1), [Some(0), Some(1), Some(3)]);
+        assert_eq!(reachable(3), [Some(7), Some(9), Some(10)]);
+    }
+
+    #[test]
+    fn simple_run() {
+        let input_str = "#############
+        #...........#
+        ###A#C#B#D###
+          #A#B#C#D#
+          #########";
+
+        assert_eq!(minimum_cost(input_str).unwrap(), 460);
+    }
+
+    #[test]
+    fn less_simple_run() {
+        let input_str = "#############
+        #...........#
+        ###D#C#B#A###
+          #A#B#C#D#
+          #########";
+
+        assert_eq!(minimum_cost(input_str).unwrap(), 8470);
+    }
+
+    #[test]
+    fn full_run() {
+        assert_eq!(minimum_cost(EXAMPLE).unwrap(), 12521);
+    }
+
+    #[test]
+    fn full_run_unfolded() {
+        assert_eq!(minimum_cost(&augment_input(EXAMPLE)).unwrap(), 44169);
+    }
+
+    #[test]
+    fn three_deep() {
+        // The example with only the first of the two rows part 2 adds
+        let input_str = "#############
+        #...........#
+        ###B#C#B#D###
+          #D#C#B#A#
+          #A#D#C#A#
+          #########";
+        let exact = search(state::<3>(input_str), &BurrowMap::new(3), |_| 0, false);
+
+        assert_eq!(minimum_cost(input_str).unwrap(), 25266);
+        assert_eq!(exact.map(|(cost, _)| cost), minimum_cost(input_str).ok());
+        assert_eq!(parallel_minimum_cost(input_str), minimum_cost(input_str));
+    }
+
+    #[test]
+    fn heuristic_admissible() {
+        let solved =
+            state::<2>("#############\n#...........#\n###A#B#C#D###\n  #A#B#C#D#\n  #########");
+        let map = BurrowMap::new(2);
+        assert_eq!(solved.heuristic(&map), 0);
+
+        // Checked against plain Dijkstra from every position one move away
+        // from the example
+        let start = state::<2>(EXAMPLE);
+        assert!(start.heuristic(&map) <= 12521);
+        for (burrow, _) in start.moves(&map) {
+            let exact = search(burrow, &map, |_| 0, false).unwrap().0;
+            assert!(burrow.heuristic(&map) <= exact, "{}", burrow);
+        }
+    }
+
+    #[test]
+    fn heuristic_matches_dijkstra() {
+        let unfolded = state::<4>(&augment_input(EXAMPLE));
+        let map = BurrowMap::new(4);
+
+        assert!(unfolded.heuristic(&map) <= 44169);
+        assert_eq!(
+            search(unfolded, &map, |_| 0, false).map(|(cost, _)| cost),
+            compute_cost(unfolded)
+        );
+    }
+
+    #[test]
+    fn packed_states() {
+        let burrow = parse_input(&augment_input(EXAMPLE)).unwrap();
+        let packed = BurrowState::<4>::from(&burrow);
+
+        assert_eq!(Burrow::from(packed), burrow);
+        assert_eq!(packed.to_string(), burrow.to_string());
+
+        // Moving there and back gives the very same state
+        let map = BurrowMap::new(4);
+        let (moved, _) = packed.moves(&map)[0];
+        assert_ne!(moved, packed);
+        assert_eq!(moved.with_move(0, room_node(0, 0)), packed);
+
+        let mut seen: HashMap<BurrowState<4>, u32> = HashMap::new();
+        seen.insert(packed, 0);
+        assert!(seen.contains_key(&BurrowState::from(&Burrow::from(packed))));
+    }
+
+    #[test]
+    fn replay_plan() {
+        let input_str = "#############
+        #...........#
+        ###A#C#B#D###
+          #A#B#C#D#
+          #########";
+        let plan = replay(state::<2>(input_str)).unwrap();
+        let steps = plan.lines().filter(|l| l.starts_with("Step")).collect_vec();
+
+        // Both have to step out of the way once
+        assert_eq!(steps.len(), 4);
+        assert!(steps[0].starts_with("Step 1: B from room C slot 1 to hallway "));
+        assert!(steps[3].ends_with("(460 total)"));
+        assert!(plan.ends_with("###A#B#C#D###\n  #A#B#C#D#\n  #########\n\nMinimum cost: 460\n"));
+    }
+
+    #[test]
+    fn plan_frames() {
+        let frames: Vec<Frame> = plan(state::<2>(EXAMPLE)).unwrap().frames().collect();
+        let last = frames.last().unwrap();
+
+        assert_eq!(frames[0].caption, "Cheapest plan: 12521 energy");
+        assert_eq!(frames[0].picture, state::<2>(EXAMPLE).to_string());
+        assert!(last.caption.ends_with("(12521 total)"));
+        assert!(last.picture.contains("###A#B#C#D###\n  #A#B#C#D#"));
+    }
+
+    #[test]
+    fn render_both_plans() {
+        let path = std::env::temp_dir().join("aoc_day23_plan.txt");
+        render(EXAMPLE, InputKind::Example, &path).unwrap();
+
+        let plans = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(plans.starts_with("Part 1\n"));
+        assert!(plans.contains("Minimum cost: 12521\n\nPart 2\n"));
+        assert!(plans.ends_with("Minimum cost: 44169\n"));
+    }
+
+    #[test]
+    fn parallel_search() {
+        let less_simple = "#############
+        #...........#
+        ###D#C#B#A###
+          #A#B#C#D#
+          #########";
+
+        assert_eq!(parallel_minimum_cost(less_simple).unwrap(), 8470);
+        assert_eq!(parallel_minimum_cost(EXAMPLE).unwrap(), 12521);
+        assert_eq!(parallel_minimum_cost(&augment_input(EXAMPLE)).unwrap(), 44169);
+        assert_eq!(
+            parallel_cost(state::<2>(
+                "#############\n#...........#\n###A#B#C#D###\n  #A#B#C#D#\n  #########"
+            )),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn cell_names() {
+        assert_eq!(parse_cell_name("h3", 2), Some(3));
+        assert_eq!(parse_cell_name("C1", 2), Some(room_node(0, 2)));
+        assert_eq!(parse_cell_name("a4", 4), Some(room_node(3, 0)));
+        assert_eq!(parse_cell_name("a3", 2), None);
+        assert_eq!(parse_cell_name("h11", 2), None);
+        assert_eq!(parse_cell_name("x1", 2), None);
+        assert!((0..HALLWAY_LENGTH + 2 * ROOMS)
+            .all(|cell| parse_cell_name(&cell_name(cell), 2) == Some(cell)));
+    }
+
+    #[test]
+    fn play_session() {
+        let mut game = Game::new(state::<2>(
+            "#############
+            #...........#
+            ###A#C#B#D###
+              #A#B#C#D#
+              #########",
+        ));
+        let commands = "s c1\nm h5\ns h5\nm b1 h7\nm c1 h0\nu\nm c1 h3\nm b1 h5\nx\nm h5 c1\ns h3\nm b1\nq\nu\n";
+        let mut output: Vec<u8> = Vec::new();
+
+        game.play(commands.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.contains("h3: 40 energy\nh5: 20 energy\nh7: 20 energy"));
+        // Can't go home yet, room B still has a C in it
+        assert!(output.contains("B in h5 can't move"));
+        assert!(output.contains("Can't move from b1 to h7"));
+        assert!(output.contains("Can't move from c1 to h0"));
+        assert!(output.contains("Unknown command: \"x\""));
+        assert!(output.ends_with("Energy: 460\nAll amphipods are home!\n"));
+        assert!(game.state.is_solved());
+    }
+
+    // Diagrams that parse have to be usable by the solver too
+    fn heuristic_at<const DEPTH: usize>(state: BurrowState<DEPTH>) -> u32 {
+        state.heuristic(&BurrowMap::new(DEPTH))
+    }
+
+    fn parse_and_convert(input: &str) {
+        if let Ok(burrow) = parse_input(input) {
+            at_depth!(&burrow, heuristic_at);
+        }
+    }
+
+    #[test]
+    fn random_diagrams() {
+        fuzz(0x2021_2300, 2000, "#.ABCD \n", parse_and_convert);
+
+        // Random text hardly gets past the walls, so the example gets mangled
+        // one cell at a time too
+        let mut rng = Xorshift::new(0x2021_2301);
+        let cells = ['#', '.', 'A', 'B', 'C', 'D', ' ', '\n', 'x'];
+        for _ in 0..2000 {
+            let mut diagram: Vec<char> = EXAMPLE.chars().collect();
+            for _ in 0..=rng.below(3) {
+                let cell = rng.below(diagram.len() as u64) as usize;
+                let replacement = cells[rng.below(cells.len() as u64) as usize];
+                match rng.below(3) {
+                    0 => diagram[cell] = replacement,
+                    1 => diagram.insert(cell, replacement),
+                    _ => {
+                        diagram.remove(cell);
+                    }
+                }
+            }
+            let diagram: String = diagram.into_iter().collect();
+            parse_and_convert(&diagram);
+            parse_and_convert(&augment_input(&diagram));
+        }
+    }
+
+    #[test]
+    fn random_burrows() {
+        cross_check(
+            0x2021_2302,
+            20,
+            |rng| testgen::burrow(rng, 2),
+            &[("search", minimum_cost), ("parallel", parallel_minimum_cost)],
+        );
+    }
+}
@@ -1,2 +0,0 @@
-pub mod basic;
-pub mod complex;
@@ -3,6 +3,8 @@ use itertools::Itertools;
 
 use std::fmt;
 
+use crate::aoc_lib::prelude::*;
+
 type Point = (usize, usize);
 
 #[derive(Debug, PartialEq, Eq)]
@@ -28,13 +30,19 @@ struct SeaFloor {
 }
 
 impl SeaFloor {
-    fn new(input: &str) -> Self {
+    fn new(input: &str) -> Result<Self, String> {
         let height = input.lines().count();
-        let width = input.lines().next().unwrap().len();
+        let width = input.lines().next().map_or(0, |line| line.trim().len());
+        if width == 0 {
+            return Err(String::from("There is no sea floor"));
+        }
 
         let mut cucumbers: Vec<SeaCucumber> = Vec::new();
 
         for (row, raw_data) in input.lines().enumerate() {
+            if raw_data.trim().len() != width {
+                return Err(format!("The row \"{}\" isn't {} long", raw_data.trim(), width));
+            }
             for (col, cell_data) in raw_data
                 .trim()
                 .char_indices()
@@ -49,7 +57,7 @@ impl SeaFloor {
                         direction: Direction::Down,
                         position: (row, col),
                     }),
-                    _ => panic!("We got some strange runaway character: {}", cell_data),
+                    _ => return Err(format!("We got some strange runaway character: {}", cell_data)),
                 }
             }
         }
@@ -81,14 +89,14 @@ impl SeaFloor {
             }
         }
 
-        Self {
+        Ok(Self {
             width,
             height,
             cucumbers,
             by_position,
             could_move,
             timestep: 0,
-        }
+        })
     }
 
     pub fn find_final_state(&mut self) -> Result<(), &'static str> {
@@ -97,7 +105,7 @@ impl SeaFloor {
         }
 
         if self.timestep >= 1000 {
-            Err("Didn't manage to find a stable state (1000 timesteps")
+            Err("Didn't manage to find a stable state (1000 timesteps)")
         } else {
             Ok(())
         }
@@ -233,17 +241,18 @@ impl fmt::Display for SeaFloor {
     }
 }
 
-pub fn part1(input: &str) {
-    let mut sea_floor = SeaFloor::new(input);
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let mut sea_floor = SeaFloor::new(input)?;
 
-    sea_floor.find_final_state().unwrap();
+    sea_floor
+        .find_final_state()
+        .map_err(|message| AocError::Unsolvable(String::from(message)))?;
 
-    println!(
-        "The cucumbers stop moving at timestep {}",
-        sea_floor.timestep + 1
-    );
+    Ok(Answer::from(sea_floor.timestep + 1))
 }
 
+aoc_day!(2021, 25, part1);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -329,7 +338,7 @@ mod tests {
         .......>..
         ..........";
 
-        let sea_floor = SeaFloor::new(input_string);
+        let sea_floor = SeaFloor::new(input_string).unwrap();
 
         let ref_floor = SeaFloor {
             width: 10,
@@ -364,14 +373,14 @@ mod tests {
     fn single_line() {
         let input_string = "...>>>>>...";
 
-        let mut sea_floor = SeaFloor::new(input_string);
+        let mut sea_floor = SeaFloor::new(input_string).unwrap();
 
         let ref_data = ["...>>>>.>..", "...>>>.>.>."];
 
         for raw_state in ref_data {
             sea_floor.step();
 
-            let ref_floor = SeaFloor::new(raw_state);
+            let ref_floor = SeaFloor::new(raw_state).unwrap();
 
             for cucumber in sea_floor.cucumbers.iter() {
                 let ref_cucumber_id = ref_floor.by_position.get(&cucumber.position).unwrap();
@@ -393,7 +402,7 @@ mod tests {
         .......
         ..vvv..";
 
-        let mut sea_floor = SeaFloor::new(input_string);
+        let mut sea_floor = SeaFloor::new(input_string).unwrap();
 
         let ref_input = vec![
             "..vv>..
@@ -427,7 +436,7 @@ mod tests {
         ];
 
         for input_data in ref_input {
-            let ref_state = SeaFloor::new(input_data);
+            let ref_state = SeaFloor::new(input_data).unwrap();
             sea_floor.step();
 
             println!("Status at step {}", sea_floor.timestep);
@@ -456,12 +465,12 @@ mod tests {
         v.v..>>v.v
         ....v..v.>";
 
-        let mut sea_floor = SeaFloor::new(input_string);
+        let mut sea_floor = SeaFloor::new(input_string).unwrap();
 
         sea_floor.find_final_state().unwrap();
         assert_eq!(sea_floor.timestep + 1, 58);
 
-        let mut sea_floor = SeaFloor::new(input_string);
+        let mut sea_floor = SeaFloor::new(input_string).unwrap();
 
         let ref_data = vec![
             (
@@ -635,7 +644,7 @@ mod tests {
         ];
 
         for (step, step_ref_data) in ref_data {
-            let ref_state = SeaFloor::new(step_ref_data);
+            let ref_state = SeaFloor::new(step_ref_data).unwrap();
 
             while sea_floor.timestep < step {
                 sea_floor.step();
@@ -1,113 +1,101 @@
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
 
-fn find_max_cals(input: &str) -> u64 {
-    let mut max_calories: u64 = 0;
-    let mut curr_elf_calories: u64 = 0;
-
-    for calories_raw in input.lines() {
-        if let Ok(calories) = calories_raw.trim().parse::<u64>() {
-            curr_elf_calories += calories;
-        } else {
-            max_calories = max_calories.max(curr_elf_calories);
-            curr_elf_calories = 0;
+use crate::aoc_lib::prelude::*;
+
+// Calories carried by each elf, the groups are separated by blank lines
+fn elf_totals(input: &str) -> Result<Vec<u64>, String> {
+    let mut totals: Vec<u64> = Vec::new();
+    let mut curr_elf_calories: Option<u64> = None;
+
+    for (line_id, calories_raw) in input.lines().map(str::trim).enumerate() {
+        if calories_raw.is_empty() {
+            totals.extend(curr_elf_calories.take());
+            continue;
         }
+
+        let calories: u64 = calories_raw.parse().map_err(|_| {
+            format!(
+                "Line {}: \"{}\" is not a calorie count",
+                line_id + 1,
+                calories_raw
+            )
+        })?;
+        *curr_elf_calories.get_or_insert(0) += calories;
     }
+    totals.extend(curr_elf_calories);
 
-    max_calories
+    Ok(totals)
 }
 
-fn find_top_cals(input: &str, top_n: usize) -> u64 {
-    let mut curr_elf_calories: u64 = 0;
-    let mut calories_heap: BinaryHeap<Reverse<u64>> = BinaryHeap::new();
+fn find_max_cals(totals: &[u64]) -> u64 {
+    totals.iter().copied().max().unwrap_or(0)
+}
 
-    for calories_raw in input.lines() {
-        if let Ok(calories) = calories_raw.trim().parse::<u64>() {
-            curr_elf_calories += calories;
-        } else {
-            if calories_heap.len() >= top_n {
-                if calories_heap.peek().unwrap().0 < curr_elf_calories {
-                    let _ = calories_heap.pop();
-                    calories_heap.push(Reverse(curr_elf_calories));
-                }
-            } else {
-                calories_heap.push(Reverse(curr_elf_calories));
-            }
-
-            curr_elf_calories = 0;
-        }
-    }
+fn find_top_cals(totals: &[u64], top_n: usize) -> u64 {
+    let mut calories_heap: BinaryHeap<Reverse<u64>> = BinaryHeap::new();
 
-    if calories_heap.len() >= top_n {
-        if calories_heap.peek().unwrap().0 < curr_elf_calories {
-            let _ = calories_heap.pop();
-            calories_heap.push(Reverse(curr_elf_calories));
+    for &calories in totals {
+        if calories_heap.len() < top_n {
+            calories_heap.push(Reverse(calories));
+        } else if calories_heap
+            .peek()
+            .is_some_and(|smallest| smallest.0 < calories)
+        {
+            calories_heap.pop();
+            calories_heap.push(Reverse(calories));
         }
-    } else {
-        calories_heap.push(Reverse(curr_elf_calories));
     }
 
     calories_heap.into_iter().map(|r| r.0).sum()
 }
 
-pub fn part1(input: &str) {
-    let max_calories = find_max_cals(input);
-    println!("Largest amount of carried calories: {}", max_calories);
-}
+pub struct CalorieCounting;
+
+impl Solution for CalorieCounting {
+    type Parsed = Vec<u64>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(elf_totals(input)?)
+    }
+
+    fn part1(totals: &Self::Parsed) -> Answer {
+        Answer::from(find_max_cals(totals))
+    }
 
-pub fn part2(input: &str) {
-    let max_calories = find_top_cals(input, 3);
-    println!(
-        "Sum of the calories carried by the top three elves: {}",
-        max_calories
-    );
+    fn part2(totals: &Self::Parsed) -> Answer {
+        Answer::from(find_top_cals(totals, 3))
+    }
 }
 
+aoc_day!(2022, 1, solution = CalorieCounting);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn simple_calories_count() {
-        let input_string = "1000
-            2000
-            3000
-
-            4000
-
-            5000
-            6000
+        let input_string = include_str!("../../inputs/2022/day01.example.txt");
 
-            7000
-            8000
-            9000
-
-            10000";
-
-        let max_calories = find_max_cals(input_string);
+        let max_calories = find_max_cals(&elf_totals(input_string).unwrap());
 
         assert_eq!(max_calories, 24000u64);
     }
 
     #[test]
     fn top_three_sum() {
-        let input_string = "1000
-            2000
-            3000
-
-            4000
+        let input_string = include_str!("../../inputs/2022/day01.example.txt");
 
-            5000
-            6000
-
-            7000
-            8000
-            9000
-
-            10000";
-
-        let max_calories = find_top_cals(input_string, 3);
+        let max_calories = find_top_cals(&elf_totals(input_string).unwrap(), 3);
 
         assert_eq!(max_calories, 45000u64);
     }
+
+    #[test]
+    fn calorie_groups() {
+        assert_eq!(elf_totals("1\n2\n\n\n3\n"), Ok(vec![3, 3]));
+        assert_eq!(elf_totals(""), Ok(vec![]));
+        assert!(elf_totals("1\nlots").is_err());
+    }
 }
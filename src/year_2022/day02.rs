@@ -3,8 +3,10 @@ use std::convert::From;
 
 use itertools::Itertools;
 
+use crate::aoc_lib::prelude::*;
+
 #[derive(Eq, PartialEq, Debug, Clone, Copy)]
-enum JanKen {
+pub enum JanKen {
     Rock,
     Paper,
     Scissors,
@@ -63,113 +65,132 @@ fn compute_score(opponent: &JanKen, ours: &JanKen) -> u8 {
         }
 }
 
-fn parse_line_straigth(line: &str) -> (JanKen, JanKen) {
-    let choices: Vec<char> = line
-        .split_whitespace()
-        .map(|c| c.chars().next().unwrap())
-        .collect_vec();
-    let theirs = match choices[0] {
-        'A' => JanKen::Rock,
-        'B' => JanKen::Paper,
-        'C' => JanKen::Scissors,
-        _ => panic!("What's this choice?!?"),
+// The second column of the guide, its meaning depends on the part
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Hint {
+    X,
+    Y,
+    Z,
+}
+
+pub type Round = (JanKen, Hint);
+
+fn parse_round(line: &str) -> Result<Round, String> {
+    let (theirs, hint) = match line.split_whitespace().collect_vec()[..] {
+        [theirs, hint] => (theirs, hint),
+        _ => return Err(format!("\"{}\" is not a round", line)),
+    };
+    let theirs = match theirs {
+        "A" => JanKen::Rock,
+        "B" => JanKen::Paper,
+        "C" => JanKen::Scissors,
+        _ => return Err(format!("What's this choice?!? \"{}\"", theirs)),
     };
-    let ours = match choices[1] {
-        'X' => JanKen::Rock,
-        'Y' => JanKen::Paper,
-        'Z' => JanKen::Scissors,
-        _ => panic!("What's this choice?!?"),
+    let hint = match hint {
+        "X" => Hint::X,
+        "Y" => Hint::Y,
+        "Z" => Hint::Z,
+        _ => return Err(format!("What's this choice?!? \"{}\"", hint)),
     };
 
-    (theirs, ours)
+    Ok((theirs, hint))
 }
 
-fn parse_line_strategy(line: &str) -> (JanKen, JanKen) {
-    let choices: Vec<char> = line
-        .split_whitespace()
-        .map(|c| c.chars().next().unwrap())
-        .collect_vec();
-    let theirs = match choices[0] {
-        'A' => JanKen::Rock,
-        'B' => JanKen::Paper,
-        'C' => JanKen::Scissors,
-        _ => panic!("What's this choice?!?"),
-    };
-    let ours = match choices[1] {
-        'X' => match theirs {
+fn parse_guide(input: &str) -> Result<Vec<Round>, String> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(parse_round)
+        .collect()
+}
+
+fn straight_choice(_theirs: JanKen, hint: Hint) -> JanKen {
+    match hint {
+        Hint::X => JanKen::Rock,
+        Hint::Y => JanKen::Paper,
+        Hint::Z => JanKen::Scissors,
+    }
+}
+
+fn strategic_choice(theirs: JanKen, hint: Hint) -> JanKen {
+    match hint {
+        Hint::X => match theirs {
             JanKen::Rock => JanKen::Scissors,
             JanKen::Paper => JanKen::Rock,
             JanKen::Scissors => JanKen::Paper,
         },
-        'Y' => theirs,
-        'Z' => match theirs {
+        Hint::Y => theirs,
+        Hint::Z => match theirs {
             JanKen::Rock => JanKen::Paper,
             JanKen::Paper => JanKen::Scissors,
             JanKen::Scissors => JanKen::Rock,
         },
-        _ => panic!("What's this choice?!?"),
-    };
-
-    (theirs, ours)
+    }
 }
 
-fn compute_straight_choices(input: &str) -> u64 {
-    input
-        .lines()
-        .map(|entry| parse_line_straigth(entry.trim()))
-        .fold(0u64, |acc, (theirs, ours)| {
-            acc + u64::from(compute_score(&theirs, &ours))
-        })
+fn total_score(rounds: &[Round], choose: fn(JanKen, Hint) -> JanKen) -> u64 {
+    rounds
+        .iter()
+        .map(|&(theirs, hint)| u64::from(compute_score(&theirs, &choose(theirs, hint))))
+        .sum()
 }
 
-fn compute_strategic_choices(input: &str) -> u64 {
-    input
-        .lines()
-        .map(|entry| parse_line_strategy(entry.trim()))
-        .fold(0u64, |acc, (theirs, ours)| {
-            acc + u64::from(compute_score(&theirs, &ours))
-        })
+fn compute_straight_choices(rounds: &[Round]) -> u64 {
+    total_score(rounds, straight_choice)
 }
 
-pub fn part1(input: &str) {
-    let straight_score = compute_straight_choices(input);
-    println!(
-        "Score if we follow the manual as if it reported the direct choice: {}",
-        straight_score
-    );
+fn compute_strategic_choices(rounds: &[Round]) -> u64 {
+    total_score(rounds, strategic_choice)
 }
 
-pub fn part2(input: &str) {
-    let strategy_score = compute_strategic_choices(input);
-    println!(
-        "Score if we follow the manual as if it reported the outcome: {}",
-        strategy_score
-    );
+pub struct RockPaperScissors;
+
+impl Solution for RockPaperScissors {
+    type Parsed = Vec<Round>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(parse_guide(input)?)
+    }
+
+    fn part1(rounds: &Self::Parsed) -> Answer {
+        Answer::from(compute_straight_choices(rounds))
+    }
+
+    fn part2(rounds: &Self::Parsed) -> Answer {
+        Answer::from(compute_strategic_choices(rounds))
+    }
 }
 
+aoc_day!(2022, 2, solution = RockPaperScissors);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn simple_score() {
-        let input_string = "A Y
-        B X
-        C Z";
+        let input_string = include_str!("../../inputs/2022/day02.example.txt");
 
-        let straight_choices = compute_straight_choices(input_string);
+        let straight_choices = compute_straight_choices(&parse_guide(input_string).unwrap());
 
         assert_eq!(straight_choices, 15u64);
     }
 
     #[test]
     fn strategic_score() {
-        let input_string = "A Y
-        B X
-        C Z";
+        let input_string = include_str!("../../inputs/2022/day02.example.txt");
 
-        let strategic_scores = compute_strategic_choices(input_string);
+        let strategic_scores = compute_strategic_choices(&parse_guide(input_string).unwrap());
 
         assert_eq!(strategic_scores, 12u64);
     }
+
+    #[test]
+    fn broken_guide() {
+        assert_eq!(parse_round("C X"), Ok((JanKen::Scissors, Hint::X)));
+        assert!(parse_round("D X").is_err());
+        assert!(parse_round("A W").is_err());
+        assert!(parse_round("A").is_err());
+    }
 }
@@ -3,8 +3,10 @@ use std::convert::From;
 use hashbrown::HashSet;
 use itertools::Itertools;
 
+use crate::aoc_lib::prelude::*;
+
 #[derive(Eq, PartialEq, Debug, Clone)]
-struct Rucksack {
+pub struct Rucksack {
     full_contents: HashSet<char>,
     front: HashSet<char>,
     back: HashSet<char>,
@@ -12,9 +14,14 @@ struct Rucksack {
 }
 
 impl Rucksack {
-    fn new(raw_data: &str) -> Self {
+    fn new(raw_data: &str) -> Result<Self, String> {
         let contents_amount = raw_data.chars().count();
-        assert_eq!(contents_amount % 2, 0);
+        if !contents_amount.is_multiple_of(2) {
+            return Err(format!("\"{}\" can't be split in two halves", raw_data));
+        }
+        if !raw_data.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(format!("\"{}\" has something that isn't an item", raw_data));
+        }
         let half_data = contents_amount / 2;
         let mut front: HashSet<char> = HashSet::new();
 
@@ -29,12 +36,12 @@ impl Rucksack {
 
         let shared_stuff: HashSet<char> = find_shared_contents(&front, &back);
 
-        Self {
+        Ok(Self {
             full_contents: front.union(&back).copied().collect(),
             front,
             back,
             shared_stuff,
-        }
+        })
     }
 
     pub fn compute_priority(&self) -> u64 {
@@ -64,21 +71,20 @@ fn find_group_priority(rucks: [&Rucksack; 3]) -> char {
     total_intersection.into_iter().next().unwrap()
 }
 
-pub fn part1(input: &str) {
-    let rucks = input
+fn parse_rucksacks(input: &str) -> Result<Vec<Rucksack>, String> {
+    input
         .lines()
-        .map(|line| Rucksack::new(line.trim()))
-        .collect_vec();
-    let priority_score = rucks.iter().fold(0u64, |acc, r| acc + r.compute_priority());
-    println!("Sum of the priorities: {}", priority_score);
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(Rucksack::new)
+        .collect()
 }
 
-pub fn part2(input: &str) {
-    let rucks = input
-        .lines()
-        .map(|line| Rucksack::new(line.trim()))
-        .collect_vec();
+fn priorities_sum(rucks: &[Rucksack]) -> u64 {
+    rucks.iter().map(Rucksack::compute_priority).sum()
+}
 
+fn group_priorities_sum(rucks: &[Rucksack]) -> u64 {
     let mut group_scores = 0u64;
 
     for group in &rucks.iter().chunks(3) {
@@ -86,26 +92,47 @@ pub fn part2(input: &str) {
         let shared_content = find_group_priority([group_rucks[0], group_rucks[1], group_rucks[2]]);
         group_scores += compute_priority_score(&shared_content);
     }
-    println!("Group scores: {}", group_scores);
+
+    group_scores
+}
+
+pub struct RucksackReorganization;
+
+impl Solution for RucksackReorganization {
+    type Parsed = Vec<Rucksack>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        let rucks = parse_rucksacks(input)?;
+        if !rucks.len().is_multiple_of(3) {
+            return Err(AocError::Parse(format!(
+                "{} rucksacks can't be split in groups of three",
+                rucks.len()
+            )));
+        }
+
+        Ok(rucks)
+    }
+
+    fn part1(rucks: &Self::Parsed) -> Answer {
+        Answer::from(priorities_sum(rucks))
+    }
+
+    fn part2(rucks: &Self::Parsed) -> Answer {
+        Answer::from(group_priorities_sum(rucks))
+    }
 }
 
+aoc_day!(2022, 3, solution = RucksackReorganization);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    static INPUT_STRING: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
-    jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
-    PmmdzqPrVvPwwTWBwg
-    wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
-    ttgJtRGJQctTZtZT
-    CrZsJsPPZsGzwwsLwLmpwMDw";
+    static INPUT_STRING: &str = include_str!("../../inputs/2022/day03.example.txt");
 
     #[test]
     fn simple_priority() {
-        let rucks = INPUT_STRING
-            .lines()
-            .map(|line| Rucksack::new(line.trim()))
-            .collect_vec();
+        let rucks = parse_rucksacks(INPUT_STRING).unwrap();
         let priority_score = rucks.iter().fold(0u64, |acc, r| acc + r.compute_priority());
 
         assert_eq!(priority_score, 157u64);
@@ -113,10 +140,7 @@ mod tests {
 
     #[test]
     fn group_priorities() {
-        let rucks = INPUT_STRING
-            .lines()
-            .map(|line| Rucksack::new(line.trim()))
-            .collect_vec();
+        let rucks = parse_rucksacks(INPUT_STRING).unwrap();
 
         let mut group_scores = 0u64;
 
@@ -129,4 +153,11 @@ mod tests {
 
         assert_eq!(group_scores, 70u64);
     }
+
+    #[test]
+    fn broken_rucksacks() {
+        assert!(Rucksack::new("abc").is_err());
+        assert!(Rucksack::new("ab1c").is_err());
+        assert!(RucksackReorganization::parse("abab\ncdcd").is_err());
+    }
 }
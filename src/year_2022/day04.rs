@@ -1,10 +1,13 @@
 use nom::bytes::complete::tag;
 use nom::character::complete::{digit1, space0};
+use nom::combinator::all_consuming;
 use nom::sequence::{preceded, separated_pair};
 use nom::{IResult, ParseTo};
 
+use crate::aoc_lib::prelude::*;
+
 #[derive(Eq, PartialEq, Debug, Clone)]
-struct Range {
+pub struct Range {
     start: usize,
     end: usize,
 }
@@ -40,60 +43,78 @@ fn assignments_line(input: &str) -> IResult<&str, (Range, Range)> {
     )(input)
 }
 
-fn find_fully_contained(input: &str) -> u64 {
+fn parse_assignments(input: &str) -> Result<Vec<(Range, Range)>, String> {
     input
         .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
         .map(|l| {
-            let (_, ranges) = assignments_line(l).unwrap();
-            ranges
+            all_consuming(assignments_line)(l)
+                .map(|(_, ranges)| ranges)
+                .map_err(|_| format!("\"{}\" is not a pair of assignments", l))
         })
-        .map(|(first, second)| first.is_contained_into(&second) || second.is_contained_into(&first))
-        .fold(0u64, |acc, contained| acc + (contained as u64))
+        .collect()
 }
 
-fn find_overlaps(input: &str) -> u64 {
-    input
-        .lines()
-        .map(|l| {
-            let (_, ranges) = assignments_line(l).unwrap();
-            ranges
+fn find_fully_contained(pairs: &[(Range, Range)]) -> u64 {
+    pairs
+        .iter()
+        .filter(|(first, second)| {
+            first.is_contained_into(second) || second.is_contained_into(first)
         })
-        .map(|(first, second)| first.is_overlapping(&second) || second.is_overlapping(&first))
-        .fold(0u64, |acc, contained| acc + (contained as u64))
+        .count() as u64
 }
 
-pub fn part1(input: &str) {
-    let fully_contained_count = find_fully_contained(input);
-    println!("Fully contained pairs: {}", fully_contained_count);
+fn find_overlaps(pairs: &[(Range, Range)]) -> u64 {
+    pairs
+        .iter()
+        .filter(|(first, second)| first.is_overlapping(second) || second.is_overlapping(first))
+        .count() as u64
 }
 
-pub fn part2(input: &str) {
-    let overlaps_count = find_overlaps(input);
-    println!("Overlapping pairs: {}", overlaps_count);
+pub struct CampCleanup;
+
+impl Solution for CampCleanup {
+    type Parsed = Vec<(Range, Range)>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(parse_assignments(input)?)
+    }
+
+    fn part1(pairs: &Self::Parsed) -> Answer {
+        Answer::from(find_fully_contained(pairs))
+    }
+
+    fn part2(pairs: &Self::Parsed) -> Answer {
+        Answer::from(find_overlaps(pairs))
+    }
 }
 
+aoc_day!(2022, 4, solution = CampCleanup);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    static INPUT_STRING: &str = "2-4,6-8
-    2-3,4-5
-    5-7,7-9
-    2-8,3-7
-    6-6,4-6
-    2-6,4-8";
+    static INPUT_STRING: &str = include_str!("../../inputs/2022/day04.example.txt");
 
     #[test]
     fn full_contained() {
-        let fully_contained_count = find_fully_contained(INPUT_STRING);
+        let fully_contained_count = find_fully_contained(&parse_assignments(INPUT_STRING).unwrap());
 
         assert_eq!(fully_contained_count, 2u64);
     }
 
     #[test]
     fn simple_overlaps() {
-        let overlaps_count = find_overlaps(INPUT_STRING);
+        let overlaps_count = find_overlaps(&parse_assignments(INPUT_STRING).unwrap());
 
         assert_eq!(overlaps_count, 4u64);
     }
+
+    #[test]
+    fn broken_assignments() {
+        assert!(parse_assignments("2-4,6").is_err());
+        assert!(parse_assignments("2-4,6-8,1-2").is_err());
+    }
 }
@@ -1,145 +1,255 @@
-use itertools::Itertools;
-use nom::branch::alt;
-use nom::bytes::complete::{tag, take};
-use nom::character::complete::{char, digit1};
-use nom::multi::separated_list1;
-use nom::sequence::{delimited, preceded, separated_pair};
+use nom::bytes::complete::tag;
+use nom::combinator::{all_consuming, map};
+use nom::sequence::{preceded, tuple};
 use nom::IResult;
 
-// Box parser
-fn box_line(input: &str) -> IResult<&str, Vec<Option<char>>> {
-    let (rem_input, boxes) = separated_list1(
-        char(' '),
-        alt((delimited(char('['), take(1u8), char(']')), tag("   "))),
-    )(input)?;
-
-    let parsed_boxes = boxes
-        .into_iter()
-        .map(|val| {
-            if val == "   " {
-                None
-            } else {
-                Some(val.chars().next().unwrap())
-            }
-        })
-        .collect_vec();
+use crate::aoc_lib::prelude::*;
+use crate::aoc_lib::jazz_parser::usize;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Crane {
+    // Moves one crate at a time, so the moved ones end up reversed
+    CrateMover9000,
+    // Moves all of them at once, keeping their order
+    CrateMover9001,
+}
 
-    Ok((rem_input, parsed_boxes))
+// Stacks are numbered from 0 here, the instructions from 1
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Move {
+    pub count: usize,
+    pub from: usize,
+    pub to: usize,
 }
 
 // Instructions parser
-fn move_instruction(input: &str) -> IResult<&str, (usize, (usize, usize))> {
-    let (rem_input, (qty, (from, to))) = preceded(
-        tag("move "),
-        separated_pair(
-            digit1,
-            tag(" from "),
-            separated_pair(digit1, tag(" to "), digit1),
-        ),
-    )(input)?;
-
-    Ok((
-        rem_input,
-        (
-            qty.parse::<usize>().unwrap(),
-            (
-                from.parse::<usize>().unwrap() - 1,
-                to.parse::<usize>().unwrap() - 1,
-            ),
-        ),
-    ))
+fn move_instruction(input: &str) -> IResult<&str, Move> {
+    map(
+        tuple((
+            preceded(tag("move "), usize),
+            preceded(tag(" from "), usize),
+            preceded(tag(" to "), usize),
+        )),
+        |(count, from, to)| Move {
+            count,
+            from: from.wrapping_sub(1),
+            to: to.wrapping_sub(1),
+        },
+    )(input)
 }
 
+// Every stack goes from the bottom crate to the top one
 #[derive(Eq, PartialEq, Debug, Clone)]
-struct ShipCargo {
-    box_stacks: Vec<Vec<char>>,
+pub struct Stacks {
+    stacks: Vec<Vec<u8>>,
 }
 
-impl ShipCargo {
-    fn new(input: &str) -> Self {
-        let mut box_stacks: Vec<Vec<char>> = Vec::new();
+impl Stacks {
+    // The crate of stack `n` is always at column 1 + 4 * n, so the diagram is
+    // read straight from the bytes of each line, from the bottom row up
+    fn from_diagram(diagram: &[&str]) -> Result<Self, String> {
+        let mut rows = diagram.iter().rev();
+        let labels = rows
+            .next()
+            .ok_or_else(|| String::from("The crate diagram is empty"))?;
+        let count = labels.split_whitespace().count();
+        if count == 0 {
+            return Err(String::from("The diagram has no stack numbers"));
+        }
 
-        for (_, box_row) in input.lines().filter_map(|l| box_line(l).ok()) {
-            if box_row.len() > box_stacks.len() {
-                box_stacks.resize_with(box_row.len(), Default::default);
-            }
-            for (idx, box_content) in box_row.into_iter().enumerate() {
-                if let Some(content) = box_content {
-                    box_stacks[idx].push(content);
+        let mut stacks = vec![Vec::new(); count];
+        for (height, row) in rows.enumerate() {
+            let bytes = row.as_bytes();
+            for (id, stack) in stacks.iter_mut().enumerate() {
+                match bytes.get(1 + 4 * id) {
+                    // Stacks can't have holes, every row below needs a crate
+                    Some(label) if label.is_ascii_uppercase() && stack.len() == height => {
+                        stack.push(*label)
+                    }
+                    Some(label) if label.is_ascii_uppercase() => {
+                        return Err(format!("Crate {} is floating", *label as char))
+                    }
+                    Some(b' ') | None => {}
+                    Some(other) => {
+                        return Err(format!("Unexpected '{}' in \"{}\"", *other as char, row))
+                    }
                 }
             }
         }
 
-        for stack in box_stacks.iter_mut() {
-            stack.reverse();
-        }
-
-        Self { box_stacks }
+        Ok(Stacks { stacks })
     }
 
-    pub fn apply_instructions(&mut self, input: &str, is_cratemover9001: bool) -> String {
-        for (_, (qty, (from, to))) in input
-            .lines()
-            .filter_map(|l| move_instruction(l.trim()).ok())
-        {
-            let starting_id = self.box_stacks[from].len() - qty;
-            let boxes_to_move = self.box_stacks[from].drain(starting_id..).collect_vec();
-            if is_cratemover9001 {
-                self.box_stacks[to].extend(boxes_to_move.into_iter());
-            } else {
-                self.box_stacks[to].extend(boxes_to_move.into_iter().rev());
+    pub fn apply(&mut self, step: Move, crane: Crane) -> Result<(), String> {
+        let invalid = || format!("Can't move {:?}", step);
+        let height = self.stacks.get(step.from).ok_or_else(invalid)?.len();
+        if step.to >= self.stacks.len() || step.count > height {
+            return Err(invalid());
+        }
+        let split = height - step.count;
+
+        if step.from == step.to {
+            // Only the one crate at a time crane changes anything
+            if crane == Crane::CrateMover9000 {
+                self.stacks[step.from][split..].reverse();
             }
+            return Ok(());
+        }
+
+        let [from, to] = self
+            .stacks
+            .get_disjoint_mut([step.from, step.to])
+            .map_err(|_| invalid())?;
+        match crane {
+            Crane::CrateMover9000 => to.extend(from[split..].iter().rev()),
+            Crane::CrateMover9001 => to.extend_from_slice(&from[split..]),
         }
+        from.truncate(split);
 
-        self.box_stacks
+        Ok(())
+    }
+
+    pub fn tops(&self) -> String {
+        self.stacks
             .iter()
             .filter_map(|stack| stack.last())
+            .map(|top| *top as char)
             .collect()
     }
 }
 
-pub fn part1(input: &str) {
-    let mut ship_stacks = ShipCargo::new(input);
-    let final_top = ship_stacks.apply_instructions(input, false);
-    println!("Final cargo state: {}", final_top);
+fn parse_cargo(input: &str) -> Result<(Stacks, Vec<Move>), String> {
+    let lines: Vec<&str> = input.lines().collect();
+    let split = lines
+        .iter()
+        .position(|line| line.trim().is_empty())
+        .ok_or_else(|| String::from("Expected an empty line after the diagram"))?;
+
+    let stacks = Stacks::from_diagram(&lines[..split])?;
+    let moves = lines[split + 1..]
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            all_consuming(move_instruction)(line)
+                .map(|(_, step)| step)
+                .map_err(|_| format!("Invalid move: \"{}\"", line))
+        })
+        .collect::<Result<Vec<Move>, String>>()?;
+
+    Ok((stacks, moves))
 }
 
-pub fn part2(input: &str) {
-    let mut ship_stacks = ShipCargo::new(input);
-    let final_top = ship_stacks.apply_instructions(input, true);
-    println!(
-        "Final cargo state using the motherfucking CrateMover 9001: {}",
-        final_top
-    );
+fn rearrange(input: &str, crane: Crane) -> Result<String, String> {
+    let (mut stacks, moves) = parse_cargo(input)?;
+    for step in moves {
+        stacks.apply(step, crane)?;
+    }
+
+    Ok(stacks.tops())
+}
+
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let final_top = rearrange(input, Crane::CrateMover9000)?;
+    Ok(Answer::from(final_top))
 }
 
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let final_top = rearrange(input, Crane::CrateMover9001)?;
+    Ok(Answer::from(final_top))
+}
+
+aoc_day!(2022, 5, part1, part2);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    static INPUT_STRING: &str = "    [D]
-[N] [C]
-[Z] [M] [P]
- 1   2   3
-
-move 1 from 2 to 1
-move 3 from 1 to 3
-move 2 from 2 to 1
-move 1 from 1 to 2";
+    static INPUT_STRING: &str = include_str!("../../inputs/2022/day05.example.txt");
 
     #[test]
     fn simple_moves() {
-        let mut ship_stacks = ShipCargo::new(INPUT_STRING);
-        let final_top = ship_stacks.apply_instructions(INPUT_STRING, false);
+        let final_top = rearrange(INPUT_STRING, Crane::CrateMover9000).unwrap();
 
         assert_eq!(final_top, "CMZ".to_string());
     }
 
     #[test]
     fn simple_cratemover9001() {
-        let mut ship_stacks = ShipCargo::new(INPUT_STRING);
-        let final_top = ship_stacks.apply_instructions(INPUT_STRING, true);
+        let final_top = rearrange(INPUT_STRING, Crane::CrateMover9001).unwrap();
 
         assert_eq!(final_top, "MCD".to_string());
     }
+
+    #[test]
+    fn diagram() {
+        let (stacks, moves) = parse_cargo(INPUT_STRING).unwrap();
+
+        assert_eq!(
+            stacks.stacks,
+            vec![b"ZN".to_vec(), b"MCD".to_vec(), b"P".to_vec()]
+        );
+        assert_eq!(
+            moves[1],
+            Move {
+                count: 3,
+                from: 0,
+                to: 2
+            }
+        );
+        assert!(parse_cargo("[A] [b]\n 1   2\n\nmove 1 from 1 to 2").is_err());
+        assert!(parse_cargo("[A]\n 1\n\nmove one from 1 to 2").is_err());
+        assert!(parse_cargo("[A] [B]\n[C]\n 1   2\n\n").is_err());
+    }
+
+    #[test]
+    fn single_moves() {
+        let (stacks, _) = parse_cargo(INPUT_STRING).unwrap();
+        let step = Move {
+            count: 2,
+            from: 1,
+            to: 1,
+        };
+
+        let mut one_by_one = stacks.clone();
+        one_by_one.apply(step, Crane::CrateMover9000).unwrap();
+        assert_eq!(one_by_one.stacks[1], b"MDC");
+
+        let mut all_at_once = stacks.clone();
+        all_at_once.apply(step, Crane::CrateMover9001).unwrap();
+        assert_eq!(all_at_once, stacks);
+
+        let mut broken = stacks.clone();
+        assert!(broken
+            .apply(
+                Move {
+                    count: 2,
+                    from: 2,
+                    to: 0
+                },
+                Crane::CrateMover9000
+            )
+            .is_err());
+        assert!(broken
+            .apply(
+                Move {
+                    count: 1,
+                    from: 0,
+                    to: 3
+                },
+                Crane::CrateMover9000
+            )
+            .is_err());
+        assert!(broken
+            .apply(
+                Move {
+                    count: 1,
+                    from: usize::MAX,
+                    to: 0
+                },
+                Crane::CrateMover9000
+            )
+            .is_err());
+        assert_eq!(broken, stacks);
+    }
 }
@@ -1,39 +1,76 @@
-use itertools::Itertools;
-
-fn find_comms_start(stream: &str, length: usize) -> Result<usize, String> {
-    for start in (length - 1)..stream.len() {
-        let unique_chars = stream[start - (length - 1)..=start]
-            .chars()
-            .unique()
-            .count();
-        if unique_chars == length {
-            return Ok(start + 1);
+use crate::aoc_lib::prelude::*;
+
+// Slides a window over the stream keeping one bit per letter, flipped every
+// time a letter enters or leaves the window. Letters seen an even number of
+// times cancel out, so the window has no repeats exactly when it has `length`
+// bits set. None if no window is ever all different.
+fn find_comms_start(stream: &str, length: usize) -> Result<Option<usize>, String> {
+    let stream = stream.trim().as_bytes();
+    if let Some(bad) = stream.iter().find(|c| !c.is_ascii_lowercase()) {
+        return Err(format!("Unexpected '{}' in the stream", *bad as char));
+    }
+    if length == 0 || length > 26 {
+        return Err(format!(
+            "A marker of length {} can't be all different",
+            length
+        ));
+    }
+
+    let bit = |c: u8| 1u32 << (c - b'a');
+    let mut window: u32 = 0;
+    for (end, c) in stream.iter().enumerate() {
+        window ^= bit(*c);
+        if end >= length {
+            window ^= bit(stream[end - length]);
+        }
+        if window.count_ones() as usize == length {
+            return Ok(Some(end + 1));
         }
     }
 
-    Err(format!(
-        "Didn't find a starting pattern of length {}!",
-        length
-    ))
+    Ok(None)
 }
 
-pub fn part1(input: &str) {
-    match find_comms_start(input, 4) {
-        Ok(stream_start) => println!("Stream starts at: {}", stream_start),
-        Err(why) => println!("{}", why),
-    }
+fn comms_answer(input: &str, length: usize) -> Result<Answer, AocError> {
+    let start = find_comms_start(input, length)?.ok_or_else(|| {
+        AocError::Unsolvable(format!(
+            "Didn't find a starting pattern of length {}!",
+            length
+        ))
+    })?;
+
+    Ok(Answer::from(start))
 }
 
-pub fn part2(input: &str) {
-    match find_comms_start(input, 14) {
-        Ok(stream_start) => println!("Message starts at: {}", stream_start),
-        Err(why) => println!("{}", why),
-    }
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    comms_answer(input, 4)
+}
+
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    comms_answer(input, 14)
 }
 
+aoc_day!(2022, 6, part1, part2);
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_lib::bench;
+
+    use itertools::Itertools;
+
+    // The original scan, collecting every window into a set
+    fn unique_window_start(stream: &str, length: usize) -> Option<usize> {
+        ((length - 1)..stream.len())
+            .find(|start| {
+                stream[start - (length - 1)..=*start]
+                    .chars()
+                    .unique()
+                    .count()
+                    == length
+            })
+            .map(|start| start + 1)
+    }
 
     #[test]
     fn simple_start() {
@@ -45,7 +82,7 @@ mod tests {
         ];
 
         for (input, start) in input_start.into_iter() {
-            assert_eq!(find_comms_start(input, 4).unwrap(), start);
+            assert_eq!(find_comms_start(input, 4).unwrap(), Some(start));
         }
     }
 
@@ -60,7 +97,28 @@ mod tests {
         ];
 
         for (input, start) in input_start.into_iter() {
-            assert_eq!(find_comms_start(input, 14).unwrap(), start);
+            assert_eq!(find_comms_start(input, 14).unwrap(), Some(start));
         }
     }
+
+    #[test]
+    fn odd_streams() {
+        // Three of a kind leave a bit set, that must not look like a new letter
+        assert_eq!(find_comms_start("aaab", 2), Ok(Some(4)));
+        assert_eq!(find_comms_start("abcabcabcd\n", 4), Ok(Some(10)));
+        assert_eq!(find_comms_start("aaaa", 2), Ok(None));
+        assert!(find_comms_start("abCd", 2).is_err());
+        assert!(find_comms_start("abcd", 27).is_err());
+    }
+
+    #[test]
+    #[ignore]
+    fn mask_speedup() {
+        // 8MB with the markers only at the end
+        let stream = bench::input(2022, 6, 8 << 20);
+        bench::faster(
+            ("Sets", || unique_window_start(&stream, 14)),
+            ("Mask", || find_comms_start(&stream, 14).unwrap()),
+        );
+    }
 }
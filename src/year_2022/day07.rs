@@ -1,212 +1,426 @@
+use std::fmt::Write;
+use std::fs;
+use std::iter;
+use std::path::Path;
+
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::character::complete::{char, digit1};
-use nom::combinator::{map, rest};
+use nom::character::complete::{char, u64};
+use nom::combinator::{all_consuming, map, rest};
 use nom::sequence::{preceded, separated_pair};
 use nom::IResult;
 use petgraph::graph::{DiGraph, NodeIndex};
-use petgraph::visit::DfsPostOrder;
-use std::cmp::Reverse;
-use std::collections::BinaryHeap;
 
-enum CLILogLine {
-    Entry(FSType),
-    Command(CLICommand),
+use crate::aoc_lib::config::InputKind;
+use crate::aoc_lib::error::write_error;
+use crate::aoc_lib::dot::{DotStyle, ToDot};
+use crate::aoc_lib::prelude::*;
+
+enum CLILogLine<'a> {
+    Entry(FSType<'a>),
+    Command(CLICommand<'a>),
 }
 
-enum FSType {
-    File((usize, String)),
-    Directory(String),
+enum FSType<'a> {
+    File(u64, &'a str),
+    Directory(&'a str),
 }
 
-enum CLICommand {
-    Cd(String),
+enum CLICommand<'a> {
+    Cd(&'a str),
     List,
 }
 
 // File parses
-fn file_entry(input: &str) -> IResult<&str, FSType> {
-    map(
-        separated_pair(digit1, char(' '), rest),
-        |(file_size, file_name): (&str, &str)| {
-            FSType::File((file_size.parse().unwrap(), file_name.to_string()))
-        },
-    )(input)
-}
-
-fn folder_entry(input: &str) -> IResult<&str, FSType> {
-    map(preceded(tag("dir "), rest), |folder: &str| {
-        FSType::Directory(folder.to_string())
+fn file_entry(input: &str) -> IResult<&str, FSType<'_>> {
+    map(separated_pair(u64, char(' '), rest), |(size, name)| {
+        FSType::File(size, name)
     })(input)
 }
 
-fn cd_command(input: &str) -> IResult<&str, CLICommand> {
-    map(preceded(tag("cd "), rest), |folder: &str| {
-        CLICommand::Cd(folder.to_string())
-    })(input)
+fn folder_entry(input: &str) -> IResult<&str, FSType<'_>> {
+    map(preceded(tag("dir "), rest), FSType::Directory)(input)
 }
 
-fn ls_command(input: &str) -> IResult<&str, CLICommand> {
-    let (rem_input, _) = tag("ls")(input)?;
+fn cd_command(input: &str) -> IResult<&str, CLICommand<'_>> {
+    map(preceded(tag("cd "), rest), CLICommand::Cd)(input)
+}
 
-    Ok((rem_input, CLICommand::List))
+fn ls_command(input: &str) -> IResult<&str, CLICommand<'_>> {
+    map(tag("ls"), |_| CLICommand::List)(input)
 }
 
-fn cli_line(input: &str) -> IResult<&str, CLILogLine> {
-    if let Ok((rem_input, command)) = preceded(tag("$ "), alt((ls_command, cd_command)))(input) {
-        return Ok((rem_input, CLILogLine::Command(command)));
-    }
+fn cli_line(input: &str) -> IResult<&str, CLILogLine<'_>> {
+    all_consuming(alt((
+        map(
+            preceded(tag("$ "), alt((ls_command, cd_command))),
+            CLILogLine::Command,
+        ),
+        map(alt((file_entry, folder_entry)), CLILogLine::Entry),
+    )))(input)
+}
 
-    map(alt((file_entry, folder_entry)), |entry: FSType| {
-        CLILogLine::Entry(entry)
-    })(input)
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum NodeKind {
+    File(u64),
+    // The children follow each other through `next_sibling`, in the order they
+    // were seen
+    Directory {
+        first_child: Option<Id>,
+        last_child: Option<Id>,
+    },
+}
+
+const EMPTY_DIRECTORY: NodeKind = NodeKind::Directory {
+    first_child: None,
+    last_child: None,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Node<'a> {
+    pub name: &'a str,
+    pub parent: Option<Id>,
+    pub next_sibling: Option<Id>,
+    pub kind: NodeKind,
+}
+
+// Every file and directory lives in the `nodes` arena and points at the others
+// by id, so there is no list of children to allocate for every directory. The
+// root is always the first node and children always come after their parent.
+// The names are the ones in the transcript, not copies.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FileSystem<'a> {
+    pub nodes: Arena<Node<'a>>,
+    pub root: Id,
 }
 
-fn build_fs_tree(input: &str) -> DiGraph<(String, usize), ()> {
-    let mut fs_tree: DiGraph<(String, usize), ()> = DiGraph::new();
+impl<'a> FileSystem<'a> {
+    fn new() -> Self {
+        let mut nodes = Arena::new();
+        let root = nodes.alloc(Node {
+            name: "/",
+            parent: None,
+            next_sibling: None,
+            kind: EMPTY_DIRECTORY,
+        });
+
+        FileSystem { nodes, root }
+    }
 
-    let mut tree_stack: Vec<(String, NodeIndex)> = Vec::new();
-    let mut roots: Vec<NodeIndex> = Vec::new();
+    pub fn from_transcript(input: &'a str) -> Result<Self, String> {
+        let mut file_system = FileSystem::new();
+        let mut current = file_system.root;
 
-    for line in input.lines().map(|l| l.trim()) {
-        if let Ok((_, log_line)) = cli_line(line) {
+        for (line_id, line) in input
+            .lines()
+            .map(str::trim)
+            .enumerate()
+            .filter(|(_, l)| !l.is_empty())
+        {
+            let (_, log_line) = cli_line(line)
+                .map_err(|_| format!("Line {}: can't read \"{}\"", line_id + 1, line))?;
             match log_line {
-                CLILogLine::Command(CLICommand::Cd(path)) => {
-                    if path == ".." {
-                        let _ = tree_stack.pop();
-                    } else {
-                        let new_dir = fs_tree.add_node((path.clone(), 0));
-                        if let Some((_, parent_folder)) = tree_stack.last() {
-                            fs_tree.add_edge(*parent_folder, new_dir, ());
-                        } else {
-                            roots.push(new_dir);
-                        }
-                        tree_stack.push((path, new_dir));
-                    }
+                CLILogLine::Command(CLICommand::Cd("/")) => current = file_system.root,
+                CLILogLine::Command(CLICommand::Cd("..")) => {
+                    current = file_system.nodes[current]
+                        .parent
+                        .ok_or_else(|| format!("Line {}: the root has no parent", line_id + 1))?
                 }
-                CLILogLine::Entry(FSType::File((size, name))) => {
-                    let (_, curr_dir) = tree_stack.last().unwrap();
-                    let new_file = fs_tree.add_node((name, size));
-                    fs_tree.add_edge(*curr_dir, new_file, ());
+                CLILogLine::Command(CLICommand::Cd(name)) => {
+                    current = file_system.child(current, name, EMPTY_DIRECTORY)?
+                }
+                CLILogLine::Command(CLICommand::List) => {}
+                CLILogLine::Entry(FSType::Directory(name)) => {
+                    file_system.child(current, name, EMPTY_DIRECTORY)?;
+                }
+                CLILogLine::Entry(FSType::File(size, name)) => {
+                    file_system.child(current, name, NodeKind::File(size))?;
                 }
-                _ => {}
             }
         }
+
+        Ok(file_system)
     }
 
-    for root in roots.into_iter() {
-        let mut dfs_post = DfsPostOrder::new(&fs_tree, root);
-        while let Some(node) = dfs_post.next(&fs_tree) {
-            if fs_tree.neighbors(node).count() > 0 {
-                let mut node_size: usize = 0;
-                for child in fs_tree.neighbors(node) {
-                    node_size += fs_tree[child].1;
-                }
-                fs_tree[node].1 = node_size;
+    pub fn children(&self, directory: Id) -> impl Iterator<Item = Id> + '_ {
+        let first = match self.nodes[directory].kind {
+            NodeKind::Directory { first_child, .. } => first_child,
+            NodeKind::File(_) => None,
+        };
+
+        iter::successors(first, |child| self.nodes[*child].next_sibling)
+    }
+
+    // The child of `parent` called `name`, created with `kind` if it's not
+    // there yet, so listing a directory twice doesn't count its files twice
+    fn child(&mut self, parent: Id, name: &'a str, kind: NodeKind) -> Result<Id, String> {
+        let NodeKind::Directory {
+            first_child,
+            last_child,
+        } = self.nodes[parent].kind
+        else {
+            return Err(format!(
+                "\"{}\" is not a directory",
+                self.nodes[parent].name
+            ));
+        };
+        if let Some(existing) = self
+            .children(parent)
+            .find(|id| self.nodes[*id].name == name)
+        {
+            return Ok(existing);
+        }
+
+        let id = self.nodes.alloc(Node {
+            name,
+            parent: Some(parent),
+            next_sibling: None,
+            kind,
+        });
+        if let Some(last) = last_child {
+            self.nodes[last].next_sibling = Some(id);
+        }
+        self.nodes[parent].kind = NodeKind::Directory {
+            first_child: first_child.or(Some(id)),
+            last_child: Some(id),
+        };
+
+        Ok(id)
+    }
+
+    // Size of every node with everything below it, by the index of its id
+    pub fn subtree_sizes(&self) -> Vec<u64> {
+        let mut sizes: Vec<u64> = self
+            .nodes
+            .iter()
+            .map(|(_, node)| match node.kind {
+                NodeKind::File(size) => size,
+                NodeKind::Directory { .. } => 0,
+            })
+            .collect();
+
+        // Children come after their parents, so going backwards every node is
+        // complete by the time it's added to its parent
+        for (id, node) in self.nodes.iter().rev() {
+            if let Some(parent) = node.parent {
+                sizes[parent.index()] += sizes[id.index()];
             }
         }
+
+        sizes
     }
 
-    fs_tree
-}
+    pub fn directory_sizes(&self) -> Vec<u64> {
+        self.subtree_sizes()
+            .into_iter()
+            .zip(self.nodes.iter())
+            .filter(|(_, (_, node))| matches!(node.kind, NodeKind::Directory { .. }))
+            .map(|(size, _)| size)
+            .collect()
+    }
+
+    // Drawn like the listing in the puzzle, children in the order they were seen
+    pub fn render(&self) -> String {
+        let sizes = self.subtree_sizes();
+        let mut output = String::new();
+        let mut stack = vec![(self.root, 0)];
 
-fn small_folders_sum(fs_tree: &DiGraph<(String, usize), ()>) -> usize {
-    let mut sum: usize = 0;
-    for node in fs_tree.node_indices() {
-        if fs_tree.neighbors(node).count() > 0 {
-            let folder_size = fs_tree[node].1;
-            if folder_size <= 100000 {
-                sum += folder_size;
+        while let Some((id, depth)) = stack.pop() {
+            let node = &self.nodes[id];
+            let indent = "  ".repeat(depth);
+            match &node.kind {
+                NodeKind::File(size) => {
+                    writeln!(output, "{}- {} (file, size={})", indent, node.name, size).unwrap()
+                }
+                NodeKind::Directory { .. } => {
+                    writeln!(
+                        output,
+                        "{}- {} (dir, total={})",
+                        indent,
+                        node.name,
+                        sizes[id.index()]
+                    )
+                    .unwrap();
+                    let children: Vec<Id> = self.children(id).collect();
+                    stack.extend(children.into_iter().rev().map(|child| (child, depth + 1)));
+                }
             }
         }
+
+        output
     }
+}
 
-    sum
+// A file or a directory as a node of the tree, with the size of everything in it
+struct Entry<'a> {
+    name: &'a str,
+    size: u64,
+    directory: bool,
 }
 
-fn find_folder_to_delete(
-    fs_tree: &DiGraph<(String, usize), ()>,
-    update_size: usize,
-    total_space: usize,
-) -> Result<usize, &str> {
-    let mut folders_heap: BinaryHeap<Reverse<usize>> = BinaryHeap::new();
-    let mut used_space: usize = 0;
-
-    for node in fs_tree.node_indices() {
-        if fs_tree.neighbors(node).count() > 0 {
-            folders_heap.push(Reverse(fs_tree[node].1));
-            used_space = used_space.max(fs_tree[node].1);
+// Directories small enough for the first part stand out from the others
+impl DotStyle for Entry<'_> {
+    fn label(&self) -> Option<String> {
+        Some(format!("{}\n{}", self.name, self.size))
+    }
+
+    fn colour(&self) -> Option<String> {
+        match (self.directory, self.size <= 100000) {
+            (true, true) => Some(String::from("palegreen")),
+            (true, false) => Some(String::from("lightblue")),
+            (false, _) => None,
         }
     }
+}
 
-    while let Some(next_size) = folders_heap.pop() {
-        let new_free_space = used_space - next_size.0;
-        if (total_space - new_free_space) >= update_size {
-            return Ok(next_size.0);
+impl<'a> FileSystem<'a> {
+    // The same arena as a graph, nodes keep their index
+    fn tree(&self) -> DiGraph<Entry<'a>, ()> {
+        let sizes = self.subtree_sizes();
+        let mut tree = DiGraph::new();
+        for ((_, node), size) in self.nodes.iter().zip(sizes) {
+            tree.add_node(Entry {
+                name: node.name,
+                size,
+                directory: matches!(node.kind, NodeKind::Directory { .. }),
+            });
         }
+        for (id, node) in self.nodes.iter() {
+            if let Some(parent) = node.parent {
+                tree.add_edge(
+                    NodeIndex::new(parent.index()),
+                    NodeIndex::new(id.index()),
+                    (),
+                );
+            }
+        }
+
+        tree
     }
+}
+
+pub fn graph(input: &str) -> Result<String, AocError> {
+    Ok(FileSystem::from_transcript(input)?.tree().to_dot())
+}
 
-    Err("Couldn't find a folder big enough to free enough space. Format the system.")
+fn small_folders_sum(file_system: &FileSystem) -> u64 {
+    file_system
+        .directory_sizes()
+        .into_iter()
+        .filter(|size| *size <= 100000)
+        .sum()
 }
 
-pub fn part1(input: &str) {
-    let tree = build_fs_tree(input);
-    let smol_sum = small_folders_sum(&tree);
+fn find_folder_to_delete(
+    file_system: &FileSystem,
+    update_size: u64,
+    total_space: u64,
+) -> Result<u64, &'static str> {
+    let used_space = file_system.subtree_sizes()[file_system.root.index()];
+    let needed = (used_space + update_size).saturating_sub(total_space);
 
-    println!("Sum of all the folders with size <= 100k: {}", smol_sum);
+    file_system
+        .directory_sizes()
+        .into_iter()
+        .filter(|size| *size >= needed)
+        .min()
+        .ok_or("Couldn't find a folder big enough to free enough space. Format the system.")
 }
 
-pub fn part2(input: &str) {
-    let tree = build_fs_tree(input);
-    let smallest_folder_to_delete = find_folder_to_delete(&tree, 30000000, 70000000).unwrap();
+pub fn render(input: &str, _kind: InputKind, path: &Path) -> Result<(), AocError> {
+    let file_system = FileSystem::from_transcript(input)?;
 
-    println!(
-        "Size of the smallest folder to delete if we want to install update: {}",
-        smallest_folder_to_delete
-    );
+    fs::write(path, file_system.render()).map_err(|e| write_error(path, e))
 }
 
+pub struct NoSpaceLeft;
+
+impl<'a> SolutionBorrowed<'a> for NoSpaceLeft {
+    type Parsed = FileSystem<'a>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, AocError> {
+        Ok(FileSystem::from_transcript(input)?)
+    }
+
+    fn part1(file_system: &Self::Parsed) -> Answer {
+        Answer::from(small_folders_sum(file_system))
+    }
+
+    fn part2(file_system: &Self::Parsed) -> Answer {
+        match find_folder_to_delete(file_system, 30000000, 70000000) {
+            Ok(smallest_folder_to_delete) => Answer::from(smallest_folder_to_delete),
+            Err(why) => Answer::from(why),
+        }
+    }
+}
+
+aoc_day!(2022, 7, solution_borrowed = NoSpaceLeft; render = render, graph = graph);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    static INPUT_STRING: &str = "$ cd /
-    $ ls
-    dir a
-    14848514 b.txt
-    8504156 c.dat
-    dir d
-    $ cd a
-    $ ls
-    dir e
-    29116 f
-    2557 g
-    62596 h.lst
-    $ cd e
-    $ ls
-    584 i
-    $ cd ..
-    $ cd ..
-    $ cd d
-    $ ls
-    4060174 j
-    8033020 d.log
-    5626152 d.ext
-    7214296 k";
+    static INPUT_STRING: &str = include_str!("../../inputs/2022/day07.example.txt");
 
     #[test]
     fn simple_folder_size() {
-        let tree = build_fs_tree(INPUT_STRING);
+        let tree = FileSystem::from_transcript(INPUT_STRING).unwrap();
         let smol_sum = small_folders_sum(&tree);
 
-        assert_eq!(smol_sum, 95437usize);
+        assert_eq!(smol_sum, 95437);
     }
 
     #[test]
     fn update_removal_size() {
-        let tree = build_fs_tree(INPUT_STRING);
+        let tree = FileSystem::from_transcript(INPUT_STRING).unwrap();
         let smallest_folder_to_delete = find_folder_to_delete(&tree, 30000000, 70000000).unwrap();
 
-        assert_eq!(smallest_folder_to_delete, 24933642usize);
+        assert_eq!(smallest_folder_to_delete, 24933642);
+    }
+
+    #[test]
+    fn arena_tree() {
+        let tree = FileSystem::from_transcript(INPUT_STRING).unwrap();
+
+        assert_eq!(tree.nodes.len(), 14);
+        assert_eq!(tree.subtree_sizes()[tree.root.index()], 48381165);
+        let (e, _) = tree.nodes.iter().find(|(_, n)| n.name == "e").unwrap();
+        assert_eq!(tree.nodes[tree.nodes[e].parent.unwrap()].name, "a");
+        assert_eq!(tree.subtree_sizes()[e.index()], 584);
+        let names: Vec<&str> = tree.children(tree.root).map(|id| tree.nodes[id].name).collect();
+        assert_eq!(names, ["a", "b.txt", "c.dat", "d"]);
+
+        // Listing again and jumping back to the root changes nothing
+        let repeated = format!("{}\n$ cd /\n$ ls\ndir a\n14848514 b.txt", INPUT_STRING);
+        assert_eq!(FileSystem::from_transcript(&repeated), Ok(tree));
+        assert!(FileSystem::from_transcript("$ cd ..").is_err());
+        assert!(FileSystem::from_transcript("$ rm -rf /").is_err());
+        assert!(FileSystem::from_transcript("$ ls\n12 a\n$ cd a\n$ ls\n3 b").is_err());
+    }
+
+    #[test]
+    fn tree_listing() {
+        let tree = FileSystem::from_transcript(INPUT_STRING).unwrap();
+        let listing = tree.render();
+        let lines: Vec<&str> = listing.lines().collect();
+
+        assert_eq!(lines.len(), 14);
+        assert_eq!(lines[0], "- / (dir, total=48381165)");
+        assert_eq!(lines[1], "  - a (dir, total=94853)");
+        assert_eq!(lines[2], "    - e (dir, total=584)");
+        assert_eq!(lines[3], "      - i (file, size=584)");
+        assert_eq!(lines[13], "    - k (file, size=7214296)");
+    }
+
+    #[test]
+    fn tree_graph() {
+        let dot = graph(INPUT_STRING).unwrap();
+
+        assert!(dot.starts_with("digraph {\n"));
+        assert_eq!(dot.matches(" -> ").count(), 13);
+        assert!(dot.contains("    n0 [label=\"/\\n48381165\", fillcolor=\"lightblue\"]\n"));
+        assert!(dot.contains("[label=\"e\\n584\", fillcolor=\"palegreen\"]"));
+        assert!(dot.contains("[label=\"i\\n584\"]"));
+        assert!(graph("$ cd ..").is_err());
     }
 }
@@ -1,162 +1,126 @@
-use nalgebra::DMatrix;
-use take_until::TakeUntilExt;
+use crate::aoc_lib::prelude::*;
 
 #[derive(Eq, PartialEq, Debug, Clone)]
 struct Forest {
-    tree_heights: DMatrix<u8>,
+    tree_heights: Grid<u8>,
 }
 
 impl Forest {
-    fn new(input: &str) -> Self {
-        let rows = input.lines().count();
-        let columns = input.lines().next().unwrap().len();
-        let tree_heights: DMatrix<u8> = DMatrix::from_row_iterator(
-            rows,
-            columns,
-            input
-                .chars()
-                .filter_map(|c| c.to_digit(10).and_then(|d| d.try_into().ok())),
-        );
-
-        Self { tree_heights }
+    fn new(input: &str) -> Result<Self, String> {
+        let tree_heights = Grid::parse(input, |_, c| {
+            c.to_digit(10)
+                .map(|height| height as u8)
+                .ok_or_else(|| format!("'{}' is not a tree height", c))
+        })?;
+
+        Ok(Self { tree_heights })
     }
 
-    pub fn visible_trees(&self) -> usize {
-        let mut visible_matix: DMatrix<bool> =
-            DMatrix::from_element(self.tree_heights.nrows(), self.tree_heights.ncols(), false);
-
-        for cell in visible_matix.row_mut(0).iter_mut() {
-            *cell = true;
-        }
-
-        for cell in visible_matix.row_mut(visible_matix.nrows() - 1).iter_mut() {
-            *cell = true;
-        }
-
-        for cell in visible_matix.column_mut(0).iter_mut() {
-            *cell = true;
-        }
-
-        for cell in visible_matix
-            .column_mut(visible_matix.ncols() - 1)
-            .iter_mut()
-        {
-            *cell = true;
-        }
-
-        for (row_id, row) in self.tree_heights.row_iter().enumerate() {
-            let mut max_height = row[0];
-            for (col_id, cell) in row.iter().enumerate().skip(1) {
-                if *cell > max_height {
-                    visible_matix[(row_id, col_id)] |= true;
-                    max_height = *cell;
-                }
-            }
+    // Every row and column, walked from both ends, so that looking back along
+    // a line is looking towards one of the four edges
+    fn sight_lines(&self) -> Vec<Vec<Position>> {
+        self.tree_heights
+            .rows()
+            .chain(self.tree_heights.columns())
+            .flat_map(|line| {
+                let reversed = line.iter().rev().copied().collect();
+                [line, reversed]
+            })
+            .collect()
+    }
 
-            max_height = row[row.len() - 1];
-            for (col_id, cell) in row.iter().enumerate().rev().skip(1) {
-                if *cell > max_height {
-                    visible_matix[(row_id, col_id)] |= true;
-                    max_height = *cell;
+    // A tree is visible from the edge behind it if it's taller than the
+    // tallest one seen so far along the line
+    pub fn visible_trees(&self) -> usize {
+        let heights = &self.tree_heights;
+        let mut visible = Grid::filled(heights.width(), heights.height(), false);
+
+        for line in self.sight_lines() {
+            let mut tallest: Option<u8> = None;
+            for position in line {
+                if tallest.is_none_or(|tallest| heights[position] > tallest) {
+                    visible[position] = true;
+                    tallest = Some(heights[position]);
                 }
             }
         }
 
-        for (col_id, col) in self.tree_heights.column_iter().enumerate() {
-            let mut max_height = col[0];
-            for (row_id, cell) in col.iter().enumerate().skip(1) {
-                if *cell > max_height {
-                    visible_matix[(row_id, col_id)] |= true;
-                    max_height = *cell;
-                }
-            }
+        visible.positions().filter(|p| visible[*p]).count()
+    }
 
-            max_height = col[col.len() - 1];
-            for (row_id, cell) in col.iter().enumerate().rev().skip(1) {
-                if *cell > max_height {
-                    visible_matix[(row_id, col_id)] |= true;
-                    max_height = *cell;
-                }
+    // Heights only go from 0 to 9, so remembering where the last tree of each
+    // height was is enough to know how far back the view reaches
+    pub fn scenic_score(&self) -> usize {
+        let heights = &self.tree_heights;
+        let mut scores = Grid::filled(heights.width(), heights.height(), 1usize);
+
+        for line in self.sight_lines() {
+            // Index 0 is the edge itself
+            let mut last_seen = [0usize; 10];
+            for (id, position) in line.into_iter().enumerate() {
+                let height = usize::from(heights[position]);
+                let blocker = last_seen[height..].iter().max().unwrap();
+                scores[position] *= id - blocker;
+                last_seen[height] = id;
             }
         }
 
-        visible_matix.iter().filter(|&&v| v).count()
+        scores.positions().map(|p| scores[p]).max().unwrap_or(0)
     }
 
-    pub fn scenic_score(&self) -> usize {
-        let mut max_scenic: usize = 0;
-
-        for (cell_id, cell) in self.tree_heights.iter().enumerate() {
-            let (row, col) = self.tree_heights.vector_to_matrix_index(cell_id);
-
-            let left_view: usize = self
-                .tree_heights
-                .row(row)
-                .iter()
-                .take(col)
-                .rev()
-                .take_until(|&h| *h >= *cell)
-                .count();
-
-            let right_view: usize = self
-                .tree_heights
-                .row(row)
-                .iter()
-                .skip(col + 1)
-                .take_until(|&h| *h >= *cell)
-                .count();
-
-            let top_view: usize = self
-                .tree_heights
-                .column(col)
-                .iter()
-                .take(row)
-                .rev()
-                .take_until(|&h| *h >= *cell)
-                .count();
-
-            let bottom_view: usize = self
-                .tree_heights
-                .column(col)
-                .iter()
-                .skip(row + 1)
-                .take_until(|&h| *h >= *cell)
-                .count();
-
-            let scenic_score = left_view * right_view * top_view * bottom_view;
-
-            max_scenic = max_scenic.max(scenic_score);
-        }
-
-        max_scenic
+    // Looks from every tree towards every edge, kept to check the scans
+    #[cfg(test)]
+    fn ray_cast_scenic_score(&self) -> usize {
+        use take_until::TakeUntilExt;
+
+        let heights = &self.tree_heights;
+        heights
+            .positions()
+            .map(|(row, col)| {
+                let height = heights[(row, col)];
+                let view = |trees: Vec<Position>| {
+                    trees
+                        .into_iter()
+                        .take_until(|p| heights[*p] >= height)
+                        .count()
+                };
+
+                view((0..col).rev().map(|c| (row, c)).collect())
+                    * view((col + 1..heights.width()).map(|c| (row, c)).collect())
+                    * view((0..row).rev().map(|r| (r, col)).collect())
+                    * view((row + 1..heights.height()).map(|r| (r, col)).collect())
+            })
+            .max()
+            .unwrap_or(0)
     }
 }
 
-pub fn part1(input: &str) {
-    let forest = Forest::new(input);
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let forest = Forest::new(input)?;
     let visible_trees = forest.visible_trees();
-    println!("Visible trees: {}", visible_trees);
+    Ok(Answer::from(visible_trees))
 }
 
-pub fn part2(input: &str) {
-    let forest = Forest::new(input);
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let forest = Forest::new(input)?;
     let maximum_scenic_score = forest.scenic_score();
-    println!("Maximum scenic score: {}", maximum_scenic_score);
+    Ok(Answer::from(maximum_scenic_score))
 }
 
+aoc_day!(2022, 8, part1, part2);
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_lib::bench;
+    use crate::aoc_lib::random::Xorshift;
+    use crate::aoc_lib::testgen;
 
-    static INPUT_STRING: &str = "30373
-    25512
-    65332
-    33549
-    35390";
+    static INPUT_STRING: &str = include_str!("../../inputs/2022/day08.example.txt");
 
     #[test]
     fn simple_visible() {
-        let forest = Forest::new(INPUT_STRING);
+        let forest = Forest::new(INPUT_STRING).unwrap();
         let visible_trees = forest.visible_trees();
 
         assert_eq!(visible_trees, 21);
@@ -164,9 +128,35 @@ mod tests {
 
     #[test]
     fn simple_scenic() {
-        let forest = Forest::new(INPUT_STRING);
+        let forest = Forest::new(INPUT_STRING).unwrap();
         let maximum_scenic_score = forest.scenic_score();
 
         assert_eq!(maximum_scenic_score, 8);
     }
+
+    #[test]
+    fn scans_match_rays() {
+        let forest = Forest::new(&testgen::forest(&mut Xorshift::new(8), 40)).unwrap();
+
+        assert_eq!(forest.scenic_score(), forest.ray_cast_scenic_score());
+        assert!(Forest::new("303\n2x5").is_err());
+    }
+
+    // Doubling the side of the forest should take about four times as long
+    #[test]
+    #[ignore]
+    fn scan_speedup() {
+        let timed = |size: usize| {
+            let forest = Forest::new(&bench::input(2022, 8, size)).unwrap();
+            let (_, took) = bench::timed(|| (forest.visible_trees(), forest.scenic_score()));
+            took
+        };
+
+        let small = timed(500);
+        let large = timed(1000);
+
+        println!("500x500: {:?}, 1000x1000: {:?}", small, large);
+        // Cubic would be eight times slower, leave some room for noise
+        assert!(large < small * 6);
+    }
 }
@@ -1,241 +1,323 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
 use hashbrown::HashSet;
 use itertools::Itertools;
-use nom::character::complete::{char, digit1, one_of};
+use nom::character::complete::{char, one_of, u32};
+use nom::combinator::{all_consuming, map};
 use nom::sequence::separated_pair;
 use nom::IResult;
 
-type Point = (i64, i64);
+use crate::aoc_lib::config::InputKind;
+use crate::aoc_lib::error::write_error;
+use crate::aoc_lib::prelude::*;
+use crate::aoc_lib::trace::Recorder;
+use crate::aoc_lib::viz::svg::{Shape, Svg};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    fn vector(self) -> Point2 {
+        match self {
+            Direction::Up => Point2::new(0, 1),
+            Direction::Down => Point2::new(0, -1),
+            Direction::Left => Point2::new(-1, 0),
+            Direction::Right => Point2::new(1, 0),
+        }
+    }
+}
 
-enum Direction {
-    Up(i64),
-    Down(i64),
-    Left(i64),
-    Right(i64),
+fn instruction_line(input: &str) -> IResult<&str, (Direction, u32)> {
+    all_consuming(separated_pair(
+        map(one_of("UDLR"), |direction| match direction {
+            'U' => Direction::Up,
+            'D' => Direction::Down,
+            'L' => Direction::Left,
+            _ => Direction::Right,
+        }),
+        char(' '),
+        u32,
+    ))(input)
 }
 
-fn instruction_line(input: &str) -> IResult<&str, Direction> {
-    let (rem_input, (direction, steps)) = separated_pair(one_of("UDLR"), char(' '), digit1)(input)?;
-    let steps_num: i64 = steps.parse().unwrap();
-    let instruction = match direction {
-        'U' => Direction::Up(steps_num),
-        'D' => Direction::Down(steps_num),
-        'L' => Direction::Left(steps_num),
-        'R' => Direction::Right(steps_num),
-        _ => panic!("We should never get here!"),
-    };
-
-    Ok((rem_input, instruction))
+fn parse_instructions(input: &str) -> Result<Vec<(Direction, u32)>, String> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            instruction_line(line)
+                .map(|(_, instruction)| instruction)
+                .map_err(|_| format!("Invalid move: \"{}\"", line))
+        })
+        .collect()
 }
 
 #[derive(Eq, PartialEq, Debug, Clone)]
-struct Rope {
-    knot_locations: Vec<Point>,
-    visited: HashSet<Point>,
+pub struct Rope {
+    knots: Vec<Point2>,
+    visited: HashSet<Point2>,
 }
 
 impl Rope {
-    fn new(knots: usize) -> Self {
+    // `length` counts every knot, head and tail included
+    pub fn new(length: usize) -> Self {
+        assert!(length > 0, "A rope needs at least a head");
+
         Self {
-            knot_locations: vec![(0, 0); knots + 1],
-            visited: HashSet::from([(0, 0)]),
+            knots: vec![Point2::default(); length],
+            visited: HashSet::from([Point2::default()]),
         }
     }
 
-    fn update_knot_position(&mut self, parent_knot_id: usize) -> bool {
-        let (parent_y, parent_x) = self.knot_locations[parent_knot_id];
-        let (knot_y, knot_x) = self.knot_locations[parent_knot_id + 1];
-
-        let delta_y = parent_y.abs_diff(knot_y);
-        let delta_x = parent_x.abs_diff(knot_x);
-
-        if delta_y < 2 && delta_x < 2 {
-            false
-        } else {
-            let mut new_location = (knot_y, knot_x);
-            new_location.0 += match parent_y.cmp(&knot_y) {
-                std::cmp::Ordering::Equal => 0,
-                std::cmp::Ordering::Greater => 1,
-                std::cmp::Ordering::Less => -1,
-            };
-            new_location.1 += match parent_x.cmp(&knot_x) {
-                std::cmp::Ordering::Equal => 0,
-                std::cmp::Ordering::Greater => 1,
-                std::cmp::Ordering::Less => -1,
-            };
-            self.knot_locations[parent_knot_id + 1] = new_location;
-            true
+    pub fn knots(&self) -> &[Point2] {
+        &self.knots
+    }
+
+    pub fn tail_visited(&self) -> usize {
+        self.visited.len()
+    }
+
+    // Moves the head by one and drags the rest of the rope behind it
+    pub fn step(&mut self, direction: Direction) {
+        self.knots[0] += direction.vector();
+        for id in 1..self.knots.len() {
+            let moved = self.knots[id].follow(&self.knots[id - 1]);
+            if moved == self.knots[id] {
+                // The knots behind this one didn't move either
+                return;
+            }
+            self.knots[id] = moved;
         }
+
+        self.visited.insert(*self.knots.last().unwrap());
     }
 
-    fn update_knots(&mut self) {
-        let mut parent_id: usize = 0;
-        let parents_qty = self.knot_locations.len() - 1;
-        while parent_id < parents_qty && self.update_knot_position(parent_id) {
-            parent_id += 1;
+    pub fn move_head(&mut self, (direction, steps): (Direction, u32)) {
+        for _ in 0..steps {
+            self.step(direction);
         }
+    }
+}
 
-        if parent_id == parents_qty {
-            self.visited.insert(*self.knot_locations.last().unwrap());
+// The trail left by the tail with the rope on top of it, y grows upwards
+impl fmt::Display for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let everything = self.visited.iter().chain(self.knots()).collect_vec();
+        let (min_x, max_x) = everything
+            .iter()
+            .map(|p| p.x)
+            .minmax()
+            .into_option()
+            .unwrap();
+        let (min_y, max_y) = everything
+            .iter()
+            .map(|p| p.y)
+            .minmax()
+            .into_option()
+            .unwrap();
+
+        for y in (min_y..=max_y).rev() {
+            let line: String = (min_x..=max_x)
+                .map(|x| {
+                    let point = Point2::new(x, y);
+                    match self.knots().iter().position(|knot| *knot == point) {
+                        Some(0) => 'H',
+                        Some(id) => char::from_digit(id as u32 % 36, 36).unwrap(),
+                        None if point == Point2::default() => 's',
+                        None if self.visited.contains(&point) => '#',
+                        None => '.',
+                    }
+                })
+                .collect();
+            writeln!(f, "{}", line)?;
         }
+
+        Ok(())
     }
+}
 
-    pub fn move_head(&mut self, instruction: Direction) {
-        match instruction {
-            Direction::Up(steps) => {
-                let starting_y = self.knot_locations.first().unwrap().0;
-                for y in starting_y + 1..=starting_y + steps {
-                    self.knot_locations.first_mut().unwrap().0 = y;
-                    self.update_knots();
-                }
-            }
-            Direction::Down(steps) => {
-                let starting_y = self.knot_locations.first().unwrap().0;
-                for y in (starting_y - steps..starting_y).rev() {
-                    self.knot_locations.first_mut().unwrap().0 = y;
-                    self.update_knots();
-                }
-            }
-            Direction::Right(steps) => {
-                let starting_x = self.knot_locations.first().unwrap().1;
-                for x in starting_x + 1..=starting_x + steps {
-                    self.knot_locations.first_mut().unwrap().1 = x;
-                    self.update_knots();
+// SVG has y growing downwards, the centre of the cell is on the point
+fn on_screen(point: &Point2) -> (f64, f64) {
+    (point.x as f64, -point.y as f64)
+}
+
+// Both ropes in their own layers: the cells the tail went through, the path it
+// took to get there and where the knots stopped. The head takes the same path
+// in both parts, so it's drawn once under everything else.
+fn trails_svg(instructions: &[(Direction, u32)]) -> Svg {
+    let mut svg = Svg::new();
+    let mut head_path = vec![on_screen(&Point2::default())];
+    let mut ropes = Vec::new();
+    for (part, length, colour) in [(1, 2, "steelblue"), (2, 10, "darkorange")] {
+        let mut rope = Rope::new(length);
+        let mut tail_path = vec![on_screen(&Point2::default())];
+        for (direction, steps) in instructions.iter() {
+            for _ in 0..*steps {
+                rope.step(*direction);
+                let tail = on_screen(rope.knots().last().unwrap());
+                if tail_path.last() != Some(&tail) {
+                    tail_path.push(tail);
                 }
-            }
-            Direction::Left(steps) => {
-                let starting_x = self.knot_locations.first().unwrap().1;
-                for x in (starting_x - steps..starting_x).rev() {
-                    self.knot_locations.first_mut().unwrap().1 = x;
-                    self.update_knots();
+                if part == 1 {
+                    head_path.push(on_screen(&rope.knots()[0]));
                 }
             }
         }
+        ropes.push((part, colour, rope, tail_path));
     }
 
-    pub fn _print_moves(&self) {
-        let knots_max_y = self.knot_locations.iter().max_by_key(|(y, _)| y).unwrap().0 + 1;
-        let knots_min_y = self.knot_locations.iter().min_by_key(|(y, _)| y).unwrap().0;
-        let knots_max_x = self.knot_locations.iter().max_by_key(|(_, x)| x).unwrap().1 + 1;
-        let knots_min_x = self.knot_locations.iter().min_by_key(|(_, x)| x).unwrap().1;
-
-        let max_y = self.visited.iter().max_by_key(|(y, _)| y).unwrap().0 + 1;
-        let min_y = self.visited.iter().min_by_key(|(y, _)| y).unwrap().0;
-        let max_x = self.visited.iter().max_by_key(|(_, x)| x).unwrap().1 + 1;
-        let min_x = self.visited.iter().min_by_key(|(_, x)| x).unwrap().1;
+    svg.layer("Head path", "none", "grey", 0.6).add(
+        Shape::Polyline(head_path),
+        Some(&format!("The head moves {} times", instructions.len())),
+    );
+    for (part, colour, rope, tail_path) in ropes {
+        svg.layer(&format!("Part {} tail cells", part), colour, "none", 0.4);
+        for cell in rope.visited.iter().sorted() {
+            let (x, y) = on_screen(cell);
+            svg.add(
+                Shape::Rect {
+                    corner: (x - 0.5, y - 0.5),
+                    width: 1.0,
+                    height: 1.0,
+                },
+                Some(&format!("x={}, y={}", cell.x, cell.y)),
+            );
+        }
 
-        let bottom_left = (min_y.min(knots_min_y), min_x.min(knots_min_x));
-        let top_right = (max_y.max(knots_max_y), max_x.max(knots_max_x));
+        svg.layer(&format!("Part {} tail path", part), "none", colour, 1.0)
+            .add(
+                Shape::Polyline(tail_path),
+                Some(&format!(
+                    "Part {}: the tail visits {} positions",
+                    part,
+                    rope.tail_visited()
+                )),
+            );
+
+        svg.layer(&format!("Part {} knots", part), colour, "black", 1.0);
+        for (id, knot) in rope.knots().iter().enumerate() {
+            let tooltip = match id {
+                0 => format!("Head at x={}, y={}", knot.x, knot.y),
+                _ => format!("Knot {} at x={}, y={}", id, knot.x, knot.y),
+            };
+            svg.add(
+                Shape::Circle {
+                    centre: on_screen(knot),
+                    radius: 0.3,
+                },
+                Some(&tooltip),
+            );
+        }
+    }
 
-        let y_range: usize = (top_right.0 - bottom_left.0).try_into().unwrap();
-        let x_range: usize = (top_right.1 - bottom_left.1).try_into().unwrap();
+    svg
+}
 
-        let mut visit_matrix = vec![false; x_range * y_range];
-        for visited_point in self.visited.iter() {
-            let linear_id = _compute_linear_id(visited_point, &bottom_left, &top_right);
-            visit_matrix[linear_id] = true;
-        }
+// Replays the moves one step at a time and draws where the tail went, as text
+// or as an SVG with layers when the path ends in .svg
+pub fn render(input: &str, _kind: InputKind, path: &Path) -> Result<(), AocError> {
+    let instructions = parse_instructions(input)?;
+    if path.extension().is_some_and(|extension| extension == "svg") {
+        return fs::write(path, trails_svg(&instructions).to_string()).map_err(|e| write_error(path, e));
+    }
 
-        let mut map_data: Vec<char> = Vec::new();
-        println!(
-            "Printing from [{}, {}] to [{}, {}]",
-            bottom_left.0, bottom_left.1, top_right.0, top_right.1
-        );
-        for grid_location in (bottom_left.0..top_right.0)
-            .rev()
-            .cartesian_product(bottom_left.1..top_right.1)
-            .map(|(y, x)| (y, x))
-        {
-            let linear_id = _compute_linear_id(&grid_location, &bottom_left, &top_right);
-            if visit_matrix[linear_id] {
-                map_data.push('#');
-            } else {
-                map_data.push('.');
+    let mut pictures = String::new();
+    for (part, length) in [(1, 2), (2, 10)] {
+        let mut rope = Rope::new(length);
+        for (direction, steps) in instructions.iter() {
+            for _ in 0..*steps {
+                rope.step(*direction);
             }
         }
-        let head_point = self.knot_locations.first().unwrap();
-        let head_linear = _compute_linear_id(head_point, &bottom_left, &top_right);
-        map_data[head_linear] = 'H';
-        for (knot_id, knot_point) in self.knot_locations.iter().enumerate().skip(1).rev() {
-            let lin_id = _compute_linear_id(knot_point, &bottom_left, &top_right);
-            map_data[lin_id] = char::from_digit(knot_id.try_into().unwrap(), 16).unwrap();
-        }
+        pictures.push_str(&format!("Part {}\n\n{}\n", part, rope));
+    }
+
+    fs::write(path, pictures).map_err(|e| write_error(path, e))
+}
 
-        for line in &map_data.iter().chunks(x_range) {
-            let text_line: String = line.collect();
-            println!("{}", text_line);
+// The long rope of the second part, a step for every move of the head with the
+// knots that followed it
+pub fn trace(input: &str, recorder: &mut Recorder) -> Result<(), AocError> {
+    let mut rope = Rope::new(10);
+    for (direction, steps) in parse_instructions(input)? {
+        for _ in 0..steps {
+            recorder.next_step();
+            let before = rope.knots().to_vec();
+            let visited = rope.tail_visited();
+            rope.step(direction);
+
+            for (id, (from, to)) in before.iter().zip(rope.knots()).enumerate() {
+                if from == to {
+                    break;
+                }
+                let name = match id {
+                    0 => String::from("head"),
+                    _ => format!("knot {}", id),
+                };
+                recorder.moved(&name, (from.x, from.y), (to.x, to.y));
+            }
+            if rope.tail_visited() > visited {
+                recorder.metric("tail visited", rope.tail_visited() as f64);
+            }
         }
     }
-}
 
-fn _compute_linear_id(location: &Point, bottom_left: &Point, top_right: &Point) -> usize {
-    let x_range: usize = (top_right.1 - bottom_left.1).try_into().unwrap();
-    let normalized_y: usize = (top_right.0 - location.0 - 1).try_into().unwrap();
-    let normalized_x: usize = (location.1 - bottom_left.1).try_into().unwrap();
-    normalized_y * x_range + normalized_x
+    Ok(())
 }
 
-pub fn part1(input: &str) {
-    let mut rope = Rope::new(1);
-    for line in input.lines().map(|l| l.trim()) {
-        let (_, direction) = instruction_line(line).unwrap();
-        rope.move_head(direction);
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let mut rope = Rope::new(2);
+    for instruction in parse_instructions(input)? {
+        rope.move_head(instruction);
     }
 
-    println!("Visited locations: {}", rope.visited.len());
+    Ok(Answer::from(rope.tail_visited()))
 }
 
-pub fn part2(input: &str) {
-    let mut rope = Rope::new(9);
-    for line in input.lines().map(|l| l.trim()) {
-        let (_, direction) = instruction_line(line).unwrap();
-        rope.move_head(direction);
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let mut rope = Rope::new(10);
+    for instruction in parse_instructions(input)? {
+        rope.move_head(instruction);
     }
 
-    println!("Visited locations by the 9th knot: {}", rope.visited.len());
+    Ok(Answer::from(rope.tail_visited()))
 }
 
+aoc_day!(2022, 9, part1, part2; render = render, trace = trace);
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_lib::trace::{read, Event};
+
+    static SHORT_MOVES: &str = include_str!("../../inputs/2022/day09.example.txt");
 
     #[test]
     fn simple_follow() {
-        let input: &str = "R 4
-        U 4
-        L 3
-        D 1
-        R 4
-        D 1
-        L 5
-        R 2";
-
-        let mut test_rope = Rope::new(1);
-        for line in input.lines().map(|l| l.trim()) {
-            let (_, direction) = instruction_line(line).unwrap();
-            test_rope.move_head(direction);
+        let mut test_rope = Rope::new(2);
+        for instruction in parse_instructions(SHORT_MOVES).unwrap() {
+            test_rope.move_head(instruction);
         }
 
-        assert_eq!(test_rope.visited.len(), 13);
+        assert_eq!(test_rope.tail_visited(), 13);
     }
 
     #[test]
     fn simple_long_follow() {
-        let input = "R 4
-        U 4
-        L 3
-        D 1
-        R 4
-        D 1
-        L 5
-        R 2";
-
-        let mut test_rope = Rope::new(9);
-        for line in input.lines().map(|l| l.trim()) {
-            let (_, direction) = instruction_line(line).unwrap();
-            test_rope.move_head(direction);
+        let mut test_rope = Rope::new(10);
+        for instruction in parse_instructions(SHORT_MOVES).unwrap() {
+            test_rope.move_head(instruction);
         }
 
-        assert_eq!(test_rope.visited.len(), 1);
+        assert_eq!(test_rope.tail_visited(), 1);
     }
 
     #[test]
@@ -249,12 +331,95 @@ mod tests {
         L 25
         U 20";
 
-        let mut test_rope = Rope::new(9);
-        for line in input.lines().map(|l| l.trim()) {
-            let (_, direction) = instruction_line(line).unwrap();
-            test_rope.move_head(direction);
+        let mut test_rope = Rope::new(10);
+        for instruction in parse_instructions(input).unwrap() {
+            test_rope.move_head(instruction);
+        }
+
+        assert_eq!(test_rope.tail_visited(), 36);
+        assert_eq!(
+            parse_instructions("R 4\nX 2"),
+            Err(String::from("Invalid move: \"X 2\""))
+        );
+    }
+
+    #[test]
+    fn trail_picture() {
+        let mut test_rope = Rope::new(2);
+        for instruction in parse_instructions(SHORT_MOVES).unwrap() {
+            test_rope.move_head(instruction);
+        }
+
+        assert_eq!(test_rope.to_string(), "..##.\n...##\n.1H##\n....#\ns###.\n");
+    }
+
+    #[test]
+    fn traced_moves() {
+        let mut output = Vec::new();
+        let mut recorder = Recorder::new(&mut output);
+        trace(SHORT_MOVES, &mut recorder).unwrap();
+        assert_eq!(recorder.step(), 24);
+        recorder.finish().unwrap();
+
+        let lines = String::from_utf8(output).unwrap();
+        let head_moves = read(&lines)
+            .unwrap()
+            .into_iter()
+            .filter(|record| matches!(&record.event, Event::Move { id, .. } if id == "head"))
+            .count();
+        assert_eq!(head_moves, 24);
+        assert!(lines.starts_with(
+            "{\"step\":1,\"event\":\"move\",\"id\":\"head\",\"from\":[0,0],\"to\":[1,0]}\n"
+        ));
+        // The tail of the long rope never leaves the start
+        assert!(!lines.contains("\"knot 9\""));
+        assert!(!lines.contains("tail visited"));
+    }
+
+    #[test]
+    fn trails_drawing() {
+        let svg = trails_svg(&parse_instructions(SHORT_MOVES).unwrap()).to_string();
+
+        let layers: Vec<&str> = svg
+            .lines()
+            .filter_map(|line| line.strip_prefix("<g id=\""))
+            .map(|line| line.split('"').next().unwrap())
+            .collect();
+        assert_eq!(
+            layers,
+            [
+                "head-path",
+                "part-1-tail-cells",
+                "part-1-tail-path",
+                "part-1-knots",
+                "part-2-tail-cells",
+                "part-2-tail-path",
+                "part-2-knots"
+            ]
+        );
+        // A cell for every place the tails visited, and one circle per knot
+        assert_eq!(svg.matches("<rect ").count(), 13 + 1);
+        assert_eq!(svg.matches("<circle ").count(), 2 + 10);
+        assert!(svg.contains("<title>Part 1: the tail visits 13 positions</title>"));
+        assert!(svg.contains("<title>Head at x=2, y=2</title>"));
+        assert!(svg.contains(r#"<rect x="2.5" y="-4.5" width="1" height="1""#));
+    }
+
+    #[test]
+    fn single_steps() {
+        let mut test_rope = Rope::new(3);
+        for _ in 0..3 {
+            test_rope.step(Direction::Right);
         }
+        test_rope.step(Direction::Up);
+        test_rope.step(Direction::Up);
 
-        assert_eq!(test_rope.visited.len(), 36);
+        assert_eq!(
+            test_rope.knots(),
+            &[Point2::new(3, 2), Point2::new(3, 1), Point2::new(2, 1)]
+        );
+        assert_eq!(test_rope.tail_visited(), 3);
+        assert!(instruction_line("X 3").is_err());
+        assert!(instruction_line("R 3 4").is_err());
     }
 }
@@ -1,12 +1,17 @@
 use itertools::Itertools;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::character::complete::{char, digit1};
-use nom::combinator::{map, opt};
-use nom::sequence::{pair, preceded};
+use nom::character::complete::i64;
+use nom::combinator::{all_consuming, map};
+use nom::sequence::preceded;
 use nom::IResult;
 use std::fmt;
 
+use crate::aoc_lib::ocr;
+use crate::aoc_lib::prelude::*;
+
+const CRT_WIDTH: usize = 40;
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 enum Operation {
     Addx(i64),
@@ -16,17 +21,7 @@ enum Operation {
 fn asm_op(input: &str) -> IResult<&str, Operation> {
     alt((
         map(tag("noop"), |_| Operation::Nop),
-        map(
-            preceded(tag("addx "), pair(opt(char::<&str, _>('-')), digit1)),
-            |(sign, value)| {
-                let numeric_value: i64 = value.parse().unwrap();
-                let numeric_sign: i64 = match sign {
-                    Some(_) => -1,
-                    None => 1,
-                };
-                Operation::Addx(numeric_value * numeric_sign)
-            },
-        ),
+        map(preceded(tag("addx "), i64), Operation::Addx),
     ))(input)
 }
 
@@ -38,29 +33,31 @@ struct SimpleCpu {
 }
 
 impl SimpleCpu {
-    fn new(program: &str) -> Self {
+    fn new(program: &str) -> Result<Self, String> {
         let mut unrolled_executions: Vec<Operation> = Vec::new();
-        for line in program.lines().map(|l| l.trim()) {
-            let (_, op) = asm_op(line).unwrap();
+        for line in program.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+            let (_, op) = all_consuming(asm_op)(line)
+                .map_err(|_| format!("Invalid instruction: \"{}\"", line))?;
             unrolled_executions.push(Operation::Nop);
             if op != Operation::Nop {
                 unrolled_executions.push(op);
             }
         }
 
-        Self {
+        Ok(Self {
             unrolled_executions,
             rax: 1,
             signal_sum: 0,
             crt_out: [false; 240],
-        }
+        })
     }
 
     pub fn execute_program(&mut self) {
         for (clock, op) in self.unrolled_executions.iter().enumerate() {
-            let crt_row_delta = self.rax.abs_diff(((clock) % 40).try_into().unwrap());
-            if crt_row_delta <= 1 {
-                self.crt_out[clock] = true;
+            // The screen is done after 240 cycles, the signal goes on
+            let crt_row_delta = self.rax.abs_diff((clock % CRT_WIDTH).try_into().unwrap());
+            if let (Some(pixel), true) = (self.crt_out.get_mut(clock), crt_row_delta <= 1) {
+                *pixel = true;
             }
 
             let signal_clock = clock + 1;
@@ -77,7 +74,7 @@ impl SimpleCpu {
 
 impl fmt::Display for SimpleCpu {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for crt_line in &self.crt_out.iter().chunks(40) {
+        for crt_line in &self.crt_out.iter().chunks(CRT_WIDTH) {
             let output_line: String = crt_line.map(|on| if *on { '#' } else { '.' }).collect();
             writeln!(f, "{}", output_line)?;
         }
@@ -85,171 +82,64 @@ impl fmt::Display for SimpleCpu {
     }
 }
 
-pub fn only_part(input: &str) {
-    let mut cpu = SimpleCpu::new(input);
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let mut cpu = SimpleCpu::new(input)?;
+    cpu.execute_program();
+
+    Ok(Answer::from(cpu.signal_sum))
+}
+
+// The CRT draws eight letters, read them instead of printing the screen
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let mut cpu = SimpleCpu::new(input)?;
     cpu.execute_program();
 
-    println!("Signal pulse sum: {}", cpu.signal_sum);
-    println!("CRT out: ");
-    println!("{}", cpu);
+    ocr::decode(&cpu.crt_out, CRT_WIDTH)
+        .map(Answer::from)
+        .map_err(|error| AocError::Unsolvable(format!("{}\nCRT out:\n{}", error, cpu)))
 }
 
+aoc_day!(2022, 10, part1, part2);
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_lib::snapshot::assert_snapshot;
 
-    static INPUT_STRING: &str = "addx 15
-    addx -11
-    addx 6
-    addx -3
-    addx 5
-    addx -1
-    addx -8
-    addx 13
-    addx 4
-    noop
-    addx -1
-    addx 5
-    addx -1
-    addx 5
-    addx -1
-    addx 5
-    addx -1
-    addx 5
-    addx -1
-    addx -35
-    addx 1
-    addx 24
-    addx -19
-    addx 1
-    addx 16
-    addx -11
-    noop
-    noop
-    addx 21
-    addx -15
-    noop
-    noop
-    addx -3
-    addx 9
-    addx 1
-    addx -3
-    addx 8
-    addx 1
-    addx 5
-    noop
-    noop
-    noop
-    noop
-    noop
-    addx -36
-    noop
-    addx 1
-    addx 7
-    noop
-    noop
-    noop
-    addx 2
-    addx 6
-    noop
-    noop
-    noop
-    noop
-    noop
-    addx 1
-    noop
-    noop
-    addx 7
-    addx 1
-    noop
-    addx -13
-    addx 13
-    addx 7
-    noop
-    addx 1
-    addx -33
-    noop
-    noop
-    noop
-    addx 2
-    noop
-    noop
-    noop
-    addx 8
-    noop
-    addx -1
-    addx 2
-    addx 1
-    noop
-    addx 17
-    addx -9
-    addx 1
-    addx 1
-    addx -3
-    addx 11
-    noop
-    noop
-    addx 1
-    noop
-    addx 1
-    noop
-    noop
-    addx -13
-    addx -19
-    addx 1
-    addx 3
-    addx 26
-    addx -30
-    addx 12
-    addx -1
-    addx 3
-    addx 1
-    noop
-    noop
-    noop
-    addx -9
-    addx 18
-    addx 1
-    addx 2
-    noop
-    noop
-    addx 9
-    noop
-    noop
-    noop
-    addx -1
-    addx 2
-    addx -37
-    addx 1
-    addx 3
-    noop
-    addx 15
-    addx -21
-    addx 22
-    addx -6
-    addx 1
-    noop
-    addx 2
-    addx 1
-    noop
-    addx -10
-    noop
-    noop
-    addx 20
-    addx 1
-    addx 2
-    addx 2
-    addx -6
-    addx -11
-    noop
-    noop
-    noop";
+    static INPUT_STRING: &str = include_str!("../../inputs/2022/day10.example.txt");
 
     #[test]
     fn simple_signals() {
-        let mut cpu = SimpleCpu::new(INPUT_STRING);
+        let mut cpu = SimpleCpu::new(INPUT_STRING).unwrap();
         cpu.execute_program();
 
         assert_eq!(cpu.signal_sum, 13140);
+
+        // Past the screen the signal still adds up
+        let mut long = SimpleCpu::new(&"noop\n".repeat(300)).unwrap();
+        long.execute_program();
+        assert_eq!(long.signal_sum, 20 + 60 + 100 + 140 + 180 + 220 + 260 + 300);
+        assert!(SimpleCpu::new("addx 3\naddx").is_err());
+    }
+
+    #[test]
+    fn crt_picture() {
+        let mut cpu = SimpleCpu::new(INPUT_STRING).unwrap();
+        cpu.execute_program();
+
+        assert_eq!(
+            cpu.to_string().lines().next(),
+            Some("##..##..##..##..##..##..##..##..##..##..")
+        );
+        // The example draws stripes, not letters
+        assert!(ocr::decode(&cpu.crt_out, CRT_WIDTH).is_err());
+    }
+
+    #[test]
+    fn crt_render() {
+        let mut cpu = SimpleCpu::new(INPUT_STRING).unwrap();
+        cpu.execute_program();
+
+        assert_snapshot("2022_day10_example_crt", &cpu.to_string());
     }
 }
@@ -1,72 +1,109 @@
 use itertools::Itertools;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::character::complete::digit1;
-use nom::combinator::{map, rest};
+use nom::character::complete::u64;
+use nom::combinator::{all_consuming, map};
 use nom::multi::separated_list1;
 use nom::sequence::preceded;
 use nom::IResult;
 
 use std::collections::VecDeque;
 
+use crate::aoc_lib::prelude::*;
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-enum Operation {
-    Sum(usize),
-    Mul(usize),
-    Square,
+pub enum Operand {
+    Old,
+    Value(u64),
+}
+
+impl Operand {
+    fn value(self, old: u64) -> u64 {
+        match self {
+            Operand::Old => old,
+            Operand::Value(value) => value,
+        }
+    }
+}
+
+// What "new = old <op> <operand>" does to the worry level
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Operation {
+    Add(Operand),
+    Mul(Operand),
+}
+
+impl Operation {
+    // None when the new worry level doesn't fit in a u64
+    pub fn apply(self, old: u64) -> Option<u64> {
+        match self {
+            Operation::Add(operand) => old.checked_add(operand.value(old)),
+            Operation::Mul(operand) => old.checked_mul(operand.value(old)),
+        }
+    }
+}
+
+// How the worry levels are kept from growing after an inspection
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Relief {
+    // The item wasn't damaged, so the worry drops to a third
+    Divide(u64),
+    // Nothing drops, but every test is a divisibility check so only the
+    // remainder by a multiple of all the divisors matters
+    Modulo(u64),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-struct Monkey {
-    inspection_queue: VecDeque<usize>,
-    worry_mod: Operation,
-    mod_value: usize,
-    next_monkeys: (usize, usize),
-    items_inspected: usize,
+pub struct Monkey {
+    pub items: VecDeque<u64>,
+    pub operation: Operation,
+    pub divisor: u64,
+    pub targets: (usize, usize),
+    pub inspected: usize,
 }
 
 impl Monkey {
-    fn from_configuration(config: [&str; 5]) -> Self {
-        let inspection_queue = parse_queue(config[0]).unwrap().1;
-        let worry_mod = parse_operation(config[1]).unwrap().1;
-        let mod_value = parse_test(config[2]).unwrap().1;
-        let next_monkeys = (
-            parse_next(config[3]).unwrap().1,
-            parse_next(config[4]).unwrap().1,
-        );
-
-        Self {
-            inspection_queue,
-            worry_mod,
-            mod_value,
-            next_monkeys,
-            items_inspected: 0,
+    fn from_configuration(config: [&str; 5]) -> Result<Self, String> {
+        let items = read(parse_queue, config[0])?;
+        let operation = read(parse_operation, config[1])?;
+        let divisor = read(parse_test, config[2])?;
+        if divisor == 0 {
+            return Err(String::from("Nothing is divisible by 0"));
         }
+        let targets = (read(parse_next, config[3])?, read(parse_next, config[4])?);
+
+        Ok(Self {
+            items,
+            operation,
+            divisor,
+            targets,
+            inspected: 0,
+        })
     }
 
-    pub fn inspect(&mut self, is_worrisome: bool) -> Vec<(usize, usize)> {
-        let mut inspection_results: Vec<(usize, usize)> = Vec::new();
-        while let Some(next_worry) = self.inspection_queue.pop_front() {
-            let mut new_worry = match self.worry_mod {
-                Operation::Sum(val) => next_worry + val,
-                Operation::Mul(val) => next_worry * val,
-                Operation::Square => next_worry * next_worry,
+    // Where every item goes and with which worry level
+    pub fn inspect(&mut self, relief: Relief) -> Vec<(usize, u64)> {
+        let mut inspection_results: Vec<(usize, u64)> = Vec::new();
+        while let Some(worry) = self.items.pop_front() {
+            let new_worry = self
+                .operation
+                .apply(worry)
+                .expect("The worry level doesn't fit in 64 bits");
+            let new_worry = match relief {
+                Relief::Divide(by) => new_worry / by,
+                Relief::Modulo(modulus) => new_worry % modulus,
             };
 
-            if !is_worrisome {
-                new_worry /= 3;
-            }
-
-            let next_monkey: usize = if { new_worry % self.mod_value } == 0 {
-                self.next_monkeys.0
+            let next_monkey = if new_worry % self.divisor == 0 {
+                self.targets.0
             } else {
-                self.next_monkeys.1
+                self.targets.1
             };
 
             inspection_results.push((next_monkey, new_worry));
         }
 
-        self.items_inspected += inspection_results.len();
+        self.inspected += inspection_results.len();
 
         inspection_results
     }
@@ -75,211 +112,193 @@ impl Monkey {
 #[derive(Debug)]
 struct InspectionPlant {
     inspectors: Vec<Monkey>,
-    mcm: usize,
+    modulus: u64,
 }
 
 impl InspectionPlant {
     fn new() -> Self {
         Self {
             inspectors: Vec::new(),
-            mcm: 0,
+            modulus: 1,
         }
     }
 
     pub fn add_inspector(&mut self, new_inspector: Monkey) {
+        self.modulus = num::integer::lcm(self.modulus, new_inspector.divisor);
         self.inspectors.push(new_inspector);
-        self.update_mcm();
     }
 
-    fn inspection_round(&mut self, is_worrisome: bool) {
+    fn inspection_round(&mut self, relief: Relief) {
         for insp_id in 0..self.inspectors.len() {
-            let inspection_results = self.inspectors[insp_id].inspect(is_worrisome);
+            let inspection_results = self.inspectors[insp_id].inspect(relief);
             for (next_monkey, worry) in inspection_results.into_iter() {
-                self.inspectors[next_monkey]
-                    .inspection_queue
-                    .push_back(worry % self.mcm);
+                self.inspectors[next_monkey].items.push_back(worry);
             }
         }
     }
 
-    fn update_mcm(&mut self) {
-        self.mcm = self
-            .inspectors
-            .iter()
-            .map(|insp| insp.mod_value)
-            .unique()
-            .product1()
-            .unwrap();
-    }
-
-    pub fn run_inspection(&mut self, rounds: usize) {
-        let is_worrisome = rounds > 20;
+    // Without relief the worry levels are kept below the least common multiple
+    // of the divisors, which is their product when they are all primes
+    pub fn run_inspection(&mut self, rounds: usize, relieved: bool) {
+        let relief = if relieved {
+            Relief::Divide(3)
+        } else {
+            Relief::Modulo(self.modulus)
+        };
         for _ in 0..rounds {
-            self.inspection_round(is_worrisome);
+            self.inspection_round(relief);
         }
     }
+
+    pub fn monkey_business(&self) -> usize {
+        self.inspectors
+            .iter()
+            .map(|insp| insp.inspected)
+            .sorted()
+            .rev()
+            .take(2)
+            .product()
+    }
 }
 
-fn parse_queue(input: &str) -> IResult<&str, VecDeque<usize>> {
+fn parse_queue(input: &str) -> IResult<&str, VecDeque<u64>> {
     map(
-        preceded(tag("Starting items: "), separated_list1(tag(", "), digit1)),
-        |raw_values: Vec<&str>| {
-            raw_values
-                .into_iter()
-                .map(|val| val.parse().unwrap())
-                .collect()
-        },
+        preceded(tag("Starting items: "), separated_list1(tag(", "), u64)),
+        VecDeque::from,
     )(input)
 }
 
-fn parse_operation(input: &str) -> IResult<&str, Operation> {
-    let (_, juicy_bits) = preceded(tag("Operation: new = old "), rest)(input)?;
-    alt((
-        map(tag("* old"), |_| Operation::Square),
-        map(preceded(tag("+ "), digit1), |raw_val: &str| {
-            Operation::Sum(raw_val.parse().unwrap())
-        }),
-        map(preceded(tag("* "), digit1), |raw_val: &str| {
-            Operation::Mul(raw_val.parse().unwrap())
-        }),
-    ))(juicy_bits)
+fn parse_operand(input: &str) -> IResult<&str, Operand> {
+    alt((map(tag("old"), |_| Operand::Old), map(u64, Operand::Value)))(input)
 }
 
-fn parse_test(input: &str) -> IResult<&str, usize> {
-    map(
-        preceded(tag("Test: divisible by "), digit1),
-        |raw_val: &str| raw_val.parse().unwrap(),
+fn parse_operation(input: &str) -> IResult<&str, Operation> {
+    preceded(
+        tag("Operation: new = old "),
+        alt((
+            map(preceded(tag("+ "), parse_operand), Operation::Add),
+            map(preceded(tag("* "), parse_operand), Operation::Mul),
+        )),
     )(input)
 }
 
+fn parse_test(input: &str) -> IResult<&str, u64> {
+    preceded(tag("Test: divisible by "), u64)(input)
+}
+
 fn parse_next(input: &str) -> IResult<&str, usize> {
-    map(
-        preceded(
-            alt((tag("If true: "), tag("If false: "))),
-            preceded(tag("throw to monkey "), digit1),
-        ),
-        |raw_val: &str| raw_val.parse().unwrap(),
+    preceded(
+        alt((tag("If true: "), tag("If false: "))),
+        preceded(tag("throw to monkey "), jazz_parser::usize),
     )(input)
 }
 
-fn initialize_inspection(input: &str) -> InspectionPlant {
+fn read<T>(parser: impl FnMut(&str) -> IResult<&str, T>, line: &str) -> Result<T, String> {
+    all_consuming(parser)(line)
+        .map(|(_, value)| value)
+        .map_err(|_| format!("Invalid monkey line: \"{}\"", line))
+}
+
+fn initialize_inspection(input: &str) -> Result<InspectionPlant, String> {
     let mut plant = InspectionPlant::new();
     for config_group in &input.lines().map(|l| l.trim()).chunks(7) {
-        let config_vec = config_group.skip(1).take(5).collect_vec();
+        let config_vec = config_group.collect_vec();
+        let [header, items, operation, test, if_true, if_false, ..] = config_vec[..] else {
+            return Err(format!("Incomplete monkey: \"{}\"", config_vec.join("\n")));
+        };
+        if !header.starts_with("Monkey ") {
+            return Err(format!("Not a monkey: \"{}\"", header));
+        }
         plant.add_inspector(Monkey::from_configuration([
-            config_vec[0],
-            config_vec[1],
-            config_vec[2],
-            config_vec[3],
-            config_vec[4],
-        ]));
+            items, operation, test, if_true, if_false,
+        ])?);
     }
 
-    plant
-}
-
-pub fn part1(input: &str) {
-    let mut plant = initialize_inspection(input);
-    plant.run_inspection(20);
-
-    let most_inspections: Vec<usize> = plant
+    let monkeys = plant.inspectors.len();
+    if monkeys == 0 {
+        return Err(String::from("There are no monkeys"));
+    }
+    let lost = plant
         .inspectors
         .iter()
-        .map(|insp| insp.items_inspected)
-        .sorted()
-        .rev()
-        .take(2)
-        .collect_vec();
+        .flat_map(|monkey| [monkey.targets.0, monkey.targets.1])
+        .find(|target| *target >= monkeys);
+    if let Some(target) = lost {
+        return Err(format!("There is no monkey {}", target));
+    }
+
+    Ok(plant)
+}
 
-    let monkey_business = most_inspections[0] * most_inspections[1];
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let mut plant = initialize_inspection(input)?;
+    plant.run_inspection(20, true);
 
-    println!("Monkey business after 20 turns: {}", monkey_business)
+    Ok(Answer::from(plant.monkey_business()))
 }
 
-pub fn part2(input: &str) {
-    let mut plant = initialize_inspection(input);
-    plant.run_inspection(10000);
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let mut plant = initialize_inspection(input)?;
+    plant.run_inspection(10000, false);
 
-    let most_inspections: Vec<usize> = plant
-        .inspectors
-        .iter()
-        .map(|insp| insp.items_inspected)
-        .sorted()
-        .rev()
-        .take(2)
-        .collect_vec();
-
-    let monkey_business = most_inspections[0] * most_inspections[1];
-
-    println!(
-        "Monkey business after 10000 sweaty turns: {}",
-        monkey_business
-    )
+    Ok(Answer::from(plant.monkey_business()))
 }
 
+aoc_day!(2022, 11, part1, part2);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    static INPUT_STRING: &str = "Monkey 0:
-    Starting items: 79, 98
-    Operation: new = old * 19
-    Test: divisible by 23
-      If true: throw to monkey 2
-      If false: throw to monkey 3
-
-  Monkey 1:
-    Starting items: 54, 65, 75, 74
-    Operation: new = old + 6
-    Test: divisible by 19
-      If true: throw to monkey 2
-      If false: throw to monkey 0
-
-  Monkey 2:
-    Starting items: 79, 60, 97
-    Operation: new = old * old
-    Test: divisible by 13
-      If true: throw to monkey 1
-      If false: throw to monkey 3
-
-  Monkey 3:
-    Starting items: 74
-    Operation: new = old + 3
-    Test: divisible by 17
-      If true: throw to monkey 0
-      If false: throw to monkey 1";
+    static INPUT_STRING: &str = include_str!("../../inputs/2022/day11.example.txt");
 
     #[test]
     fn simple_monkeys() {
-        let mut plant = initialize_inspection(INPUT_STRING);
-        plant.run_inspection(20);
-
-        let most_inspections: Vec<usize> = plant
-            .inspectors
-            .iter()
-            .map(|insp| insp.items_inspected)
-            .sorted()
-            .rev()
-            .take(2)
-            .collect_vec();
+        let mut plant = initialize_inspection(INPUT_STRING).unwrap();
+        plant.run_inspection(20, true);
 
-        assert_eq!(most_inspections[0] * most_inspections[1], 10605);
+        assert_eq!(plant.monkey_business(), 10605);
     }
 
     #[test]
     fn long_monkeys() {
-        let mut plant = initialize_inspection(INPUT_STRING);
-        plant.run_inspection(10000);
+        let mut plant = initialize_inspection(INPUT_STRING).unwrap();
+        plant.run_inspection(10000, false);
 
-        let most_inspections: Vec<usize> = plant
-            .inspectors
-            .iter()
-            .map(|insp| insp.items_inspected)
-            .sorted()
-            .rev()
-            .take(2)
-            .collect_vec();
+        assert_eq!(plant.monkey_business(), 2713310158);
+    }
+
+    #[test]
+    fn parse_operations() {
+        assert_eq!(
+            parse_operation("Operation: new = old * old").unwrap().1,
+            Operation::Mul(Operand::Old)
+        );
+        assert_eq!(
+            parse_operation("Operation: new = old + 6").unwrap().1,
+            Operation::Add(Operand::Value(6))
+        );
+        assert!(parse_operation("Operation: new = old - 6").is_err());
+        assert!(initialize_inspection(&INPUT_STRING.replace("monkey 3", "monkey 4")).is_err());
+        assert!(initialize_inspection(&INPUT_STRING.replace("old * 19", "old ^ 19")).is_err());
+        assert_eq!(Operation::Add(Operand::Old).apply(21), Some(42));
+        assert_eq!(Operation::Mul(Operand::Old).apply(u64::MAX), None);
+    }
 
-        assert_eq!(most_inspections[0] * most_inspections[1], 2713310158);
+    #[test]
+    fn worry_fits_in_u64() {
+        let mut plant = initialize_inspection(INPUT_STRING).unwrap();
+        assert_eq!(plant.modulus, 23 * 19 * 13 * 17);
+
+        // Squaring the biggest reduced worry level still fits
+        assert!((plant.modulus - 1).checked_mul(plant.modulus - 1).is_some());
+        for _ in 0..10000 {
+            plant.inspection_round(Relief::Modulo(plant.modulus));
+            assert!(plant
+                .inspectors
+                .iter()
+                .flat_map(|monkey| monkey.items.iter())
+                .all(|worry| *worry < plant.modulus));
+        }
     }
 }
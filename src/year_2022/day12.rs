@@ -1,170 +1,229 @@
-use itertools::Itertools;
-use petgraph::algo::dijkstra;
-use petgraph::graph::{DiGraph, NodeIndex};
+use hashbrown::HashMap;
+use petgraph::graph::DiGraph;
 
-type Point = (usize, usize);
+use std::fs;
+use std::path::Path;
+
+use crate::aoc_lib::config::InputKind;
+use crate::aoc_lib::error::write_error;
+use crate::aoc_lib::prelude::*;
+use crate::aoc_lib::dot::{DotStyle, ToDot};
+use crate::aoc_lib::search::{a_star_explored, bfs, bfs_explored, dijkstra_explored};
+use crate::aoc_lib::viz::heatmap::heatmap;
+use crate::aoc_lib::viz::svg::Svg;
 
 struct HillsRange {
-    hill_graph: DiGraph<char, ()>,
-    rows: usize,
-    columns: usize,
-    start_node: NodeIndex,
-    goal_node: NodeIndex,
+    heights: Grid<u8>,
+    start: Position,
+    goal: Position,
 }
 
 impl HillsRange {
-    fn new() -> Self {
-        HillsRange {
-            hill_graph: DiGraph::new(),
-            rows: 0,
-            columns: 0,
-            start_node: NodeIndex::new(0),
-            goal_node: NodeIndex::new(0),
-        }
-    }
-
-    fn from_grid(input: &str) -> Self {
-        let mut new_range = HillsRange::new();
-
-        new_range.rows = input.lines().count();
-        new_range.columns = input.lines().next().unwrap().trim().len();
-
-        for (cell_idx, height) in input.chars().filter(|c| c.is_alphabetic()).enumerate() {
-            let current_height = match height {
-                'S' => {
-                    new_range.start_node = NodeIndex::new(cell_idx);
-                    'a'
-                }
-                'E' => {
-                    new_range.goal_node = NodeIndex::new(cell_idx);
-                    'z'
-                }
-                _ => height,
-            };
-            new_range.hill_graph.add_node(current_height);
-        }
-
-        for cell_coords in (0..new_range.rows).cartesian_product(0..new_range.columns) {
-            let current_idx = NodeIndex::new(new_range.linear_id(&cell_coords).unwrap());
+    fn from_grid(input: &str) -> Result<Self, String> {
+        let mut start = None;
+        let mut goal = None;
 
-            if cell_coords.1 > 0 {
-                let left_coord = (cell_coords.0, cell_coords.1 - 1);
-                let left_idx = NodeIndex::new(new_range.linear_id(&left_coord).unwrap());
-                if new_range.is_neighbour(&current_idx, &left_idx) {
-                    new_range.hill_graph.add_edge(current_idx, left_idx, ());
-                }
+        let heights = Grid::parse(input, |position, height| match height {
+            'S' => {
+                start = Some(position);
+                Ok(b'a')
             }
-
-            if cell_coords.1 < new_range.columns - 1 {
-                let right_coord = (cell_coords.0, cell_coords.1 + 1);
-                let right_idx = NodeIndex::new(new_range.linear_id(&right_coord).unwrap());
-                if new_range.is_neighbour(&current_idx, &right_idx) {
-                    new_range.hill_graph.add_edge(current_idx, right_idx, ());
-                }
+            'E' => {
+                goal = Some(position);
+                Ok(b'z')
             }
+            'a'..='z' => Ok(height as u8),
+            _ => Err(format!("Unexpected '{}' in the height map", height)),
+        })?;
 
-            if cell_coords.0 > 0 {
-                let up_coord = (cell_coords.0 - 1, cell_coords.1);
-                let up_idx = NodeIndex::new(new_range.linear_id(&up_coord).unwrap());
-                if new_range.is_neighbour(&current_idx, &up_idx) {
-                    new_range.hill_graph.add_edge(current_idx, up_idx, ());
-                }
-            }
+        Ok(HillsRange {
+            heights,
+            start: start.ok_or_else(|| String::from("There is no start"))?,
+            goal: goal.ok_or_else(|| String::from("There is no goal"))?,
+        })
+    }
 
-            if cell_coords.0 < new_range.rows - 1 {
-                let down_coord = (cell_coords.0 + 1, cell_coords.1);
-                let down_idx = NodeIndex::new(new_range.linear_id(&down_coord).unwrap());
-                if new_range.is_neighbour(&current_idx, &down_idx) {
-                    new_range.hill_graph.add_edge(current_idx, down_idx, ());
-                }
-            }
-        }
+    // Searching from the top the steps are reversed: it's possible to go from
+    // `from` to `to` if the climb from `to` to `from` is at most one.
+    fn descend(&self) -> impl FnMut(&Position) -> Vec<Position> + '_ {
+        self.heights.moves(|from, to| *from <= to + 1)
+    }
 
-        new_range
+    pub fn find_shortest_path(&self) -> Option<usize> {
+        bfs(self.goal, self.descend(), |position| {
+            *position == self.start
+        })
+        .map(|(steps, _)| steps)
     }
 
-    fn linear_id(&self, coords: &Point) -> Result<usize, &str> {
-        if coords.1 >= self.columns || coords.0 >= self.rows {
-            Err("Requested coordinate is out of bounds!")
-        } else {
-            Ok(coords.0 * self.columns + coords.1)
-        }
+    // A single search down from the top stops at the closest lowest point, so
+    // there is no need to try every one of them as a start
+    pub fn find_shortestest_path(&self) -> Option<usize> {
+        bfs(self.goal, self.descend(), |position| {
+            self.heights[*position] == b'a'
+        })
+        .map(|(steps, _)| steps)
+    }
+
+    // One climb from every lowest point, kept as a reference for the benchmark
+    #[cfg(test)]
+    fn climb_from_every_start(&self) -> Option<usize> {
+        self.heights
+            .positions()
+            .filter(|position| self.heights[*position] == b'a')
+            .filter_map(|start| {
+                let climb = self.heights.moves(|from, to| *to <= from + 1);
+                bfs(start, climb, |position| *position == self.goal)
+            })
+            .map(|(steps, _)| steps)
+            .min()
     }
+}
+
+// A square of the map, pinned where it is on the map so neato keeps the grid
+struct Square {
+    position: Position,
+    height: char,
+    colour: Option<&'static str>,
+}
 
-    fn is_neighbour(&self, start: &NodeIndex, end: &NodeIndex) -> bool {
-        let start_height = self.hill_graph[*start];
-        let end_height = self.hill_graph[*end];
-        end_height <= start_height || end_height == char::from_u32(start_height as u32 + 1).unwrap()
+impl DotStyle for Square {
+    fn label(&self) -> Option<String> {
+        Some(self.height.to_string())
     }
 
-    pub fn find_shortest_path(&self) -> Option<usize> {
-        let path_cost = dijkstra(
-            &self.hill_graph,
-            self.start_node,
-            Some(self.goal_node),
-            |_| 1,
-        );
+    fn colour(&self) -> Option<String> {
+        self.colour.map(String::from)
+    }
 
-        path_cost
-            .get(&self.goal_node)
-            .and_then(|goal_cost| usize::try_from(*goal_cost).ok())
+    fn position(&self) -> Option<(f64, f64)> {
+        let (row, column) = self.position;
+        Some((column as f64, -(row as f64)))
     }
+}
 
-    pub fn find_shortestest_path(&self) -> Option<usize> {
-        let starting_points: Vec<NodeIndex> = self
-            .hill_graph
-            .node_indices()
-            .filter(|idx| self.hill_graph[*idx] == 'a')
-            .collect_vec();
-
-        let mut shortestest_path: Option<usize> = None;
-        for start in starting_points.into_iter() {
-            let path_cost = dijkstra(&self.hill_graph, start, Some(self.goal_node), |_| 1);
-            if let Some(current_shortest_path) = path_cost
-                .get(&self.goal_node)
-                .and_then(|goal_cost| usize::try_from(*goal_cost).ok())
-            {
-                if let Some(shortestest) = shortestest_path {
-                    shortestest_path = Some(shortestest.min(current_shortest_path));
-                } else {
-                    shortestest_path = Some(current_shortest_path);
-                }
+impl HillsRange {
+    // An edge for every step that can be climbed. The start and the goal are
+    // coloured, and the lowest squares are a shade apart.
+    fn elevation_graph(&self) -> DiGraph<Square, ()> {
+        let mut graph = DiGraph::new();
+        let nodes: HashMap<Position, _> = self
+            .heights
+            .positions()
+            .map(|position| {
+                let height = self.heights[position];
+                let (height, colour) = match position {
+                    _ if position == self.start => ('S', Some("palegreen")),
+                    _ if position == self.goal => ('E', Some("gold")),
+                    _ if height == b'a' => ('a', Some("lightblue")),
+                    _ => (height as char, None),
+                };
+                let node = graph.add_node(Square {
+                    position,
+                    height,
+                    colour,
+                });
+                (position, node)
+            })
+            .collect();
+
+        let mut climb = self.heights.moves(|from, to| *to <= from + 1);
+        for position in self.heights.positions() {
+            for next in climb(&position) {
+                graph.add_edge(nodes[&position], nodes[&next], ());
             }
         }
 
-        shortestest_path
+        graph
     }
 }
 
-pub fn part1(input: &str) {
-    let hills = HillsRange::from_grid(input);
+impl HillsRange {
+    // Every step up costs one, for the weighted searches
+    fn climb(&self) -> impl FnMut(&Position) -> Vec<(Position, u64)> + '_ {
+        let mut climb = self.heights.moves(|from, to| *to <= from + 1);
+        move |position| climb(position).into_iter().map(|next| (next, 1)).collect()
+    }
+
+    // The climb from the start three ways: the steps to every square a breadth
+    // first search reached, then the squares Dijkstra and A* expanded on their
+    // way to the top. A* guesses with the distance to the top, the squares it
+    // spares are the ones only in the Dijkstra layer.
+    fn search_heatmap(&self) -> Svg {
+        let is_goal = |position: &Position| *position == self.goal;
+        let (row, column) = self.goal;
+        let distance = |position: &Position| (position.0.abs_diff(row) + position.1.abs_diff(column)) as u64;
+
+        let climb = self.heights.moves(|from, to| *to <= from + 1);
+        let (_, breadth) = bfs_explored(self.start, climb, is_goal);
+        let (_, blind) = dijkstra_explored(self.start, self.climb(), is_goal);
+        let (_, guided) = a_star_explored(self.start, self.climb(), distance, is_goal);
+
+        let steps: HashMap<Position, u64> = breadth.costs.iter().map(|(position, steps)| (*position, *steps as u64)).collect();
+        let expanded = |expansions: HashMap<Position, usize>| -> HashMap<Position, u64> {
+            expansions.into_iter().map(|(position, times)| (position, times as u64)).collect()
+        };
+        let (blind, guided) = (expanded(blind.expansions), expanded(guided.expansions));
+
+        // Greys from dark in the valleys to light at the top
+        let shade = |height: &u8| {
+            let grey = 40 + (height - b'a') * 8;
+            format!("#{:02x}{:02x}{:02x}", grey, grey, grey)
+        };
+        heatmap(
+            &self.heights,
+            shade,
+            &[
+                ("Steps from the start", &steps),
+                ("Expanded by Dijkstra", &blind),
+                ("Expanded by A*", &guided),
+            ],
+        )
+    }
+}
+
+// Heatmaps of how the searches went through the map, as an SVG
+pub fn render(input: &str, _kind: InputKind, path: &Path) -> Result<(), AocError> {
+    let svg = HillsRange::from_grid(input)?.search_heatmap();
+
+    fs::write(path, svg.to_string()).map_err(|e| write_error(path, e))
+}
+
+pub fn graph(input: &str) -> Result<String, AocError> {
+    Ok(HillsRange::from_grid(input)?.elevation_graph().to_dot())
+}
+
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let hills = HillsRange::from_grid(input)?;
     let shortest_path = hills
         .find_shortest_path()
-        .expect("We should have a shortest path, man!");
+        .ok_or_else(|| AocError::Unsolvable(String::from("the goal can't be reached from the start")))?;
 
-    println!("Shortest path to the top: {}", shortest_path);
+    Ok(Answer::from(shortest_path))
 }
 
-pub fn part2(input: &str) {
-    let hills_range = HillsRange::from_grid(input);
-    let shortestest = hills_range.find_shortestest_path().unwrap();
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let hills_range = HillsRange::from_grid(input)?;
+    let shortestest = hills_range
+        .find_shortestest_path()
+        .ok_or_else(|| AocError::Unsolvable(String::from("the goal can't be reached from any 'a'")))?;
 
-    println!("Shortestest path to the top: {}", shortestest);
+    Ok(Answer::from(shortestest))
 }
 
+aoc_day!(2022, 12, part1, part2; render = render, graph = graph);
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_lib::bench;
+    use crate::aoc_lib::testgen;
 
-    static INPUT_STRING: &str = "Sabqponm
-    abcryxxl
-    accszExk
-    acctuvwj
-    abdefghi";
+    static INPUT_STRING: &str = include_str!("../../inputs/2022/day12.example.txt");
 
     #[test]
     fn simple_climb() {
-        let hills_range = HillsRange::from_grid(INPUT_STRING);
+        let hills_range = HillsRange::from_grid(INPUT_STRING).unwrap();
         let shortest = hills_range.find_shortest_path().unwrap();
 
         assert_eq!(shortest, 31usize);
@@ -172,9 +231,87 @@ mod tests {
 
     #[test]
     fn simple_shortestest() {
-        let hills_range = HillsRange::from_grid(INPUT_STRING);
+        let hills_range = HillsRange::from_grid(INPUT_STRING).unwrap();
         let shortestest = hills_range.find_shortestest_path().unwrap();
 
         assert_eq!(shortestest, 29usize);
+        assert_eq!(hills_range.climb_from_every_start(), Some(29));
+    }
+
+    #[test]
+    fn generated_climb() {
+        // The row of the start climbs straight to the top
+        let input = testgen::generate(2022, 12, 30, 5).unwrap();
+        let hills_range = HillsRange::from_grid(&input).unwrap();
+        assert_eq!(hills_range.find_shortest_path(), Some(29));
+        assert_eq!(
+            hills_range.find_shortestest_path(),
+            hills_range.climb_from_every_start()
+        );
+    }
+
+    #[test]
+    fn climbing_graph() {
+        let dot = graph(INPUT_STRING).unwrap();
+
+        assert!(dot.starts_with("digraph {\n"));
+        assert_eq!(dot.matches("pos=").count(), 40);
+        assert!(dot.contains("    n0 [label=\"S\", fillcolor=\"palegreen\", pos=\"0,0!\"]\n"));
+        assert!(dot.contains(" [label=\"E\", fillcolor=\"gold\", pos=\"5,-2!\"]\n"));
+        // S can go right and down, the a below it can go back up
+        assert!(dot.contains("    n0 -> n1\n"));
+        assert!(dot.contains("    n8 -> n0\n"));
+        assert!(graph("Sab\nbE").is_err());
+    }
+
+    #[test]
+    fn search_heatmaps() {
+        let hills_range = HillsRange::from_grid(INPUT_STRING).unwrap();
+        let svg = hills_range.search_heatmap().to_string();
+
+        assert_eq!(svg.matches("inkscape:groupmode=\"layer\"").count(), 4);
+        // Everything is reachable, the top is the farthest
+        assert!(svg.contains("<title>row 2, column 5: 31</title>"));
+        // Cells in every layer, the first piece is the header
+        let layers = |svg: &str| -> Vec<usize> {
+            svg.split("inkscape:groupmode")
+                .map(|layer| layer.matches("<title>").count())
+                .collect()
+        };
+        // The spiral leaves no shortcut, every search goes everywhere
+        assert_eq!(layers(&svg), vec![0, 0, 40, 40, 40]);
+
+        // A straight ramp next to a valley: Dijkstra wanders down into it, A*
+        // knows the valley only leads away from the top
+        let ramp = format!("SbcdefghijklmnopqrstuvwxyE\n{}\n{}", "a".repeat(26), "a".repeat(26));
+        let ramp = HillsRange::from_grid(&ramp).unwrap().search_heatmap().to_string();
+        let [_, _, _, blind, guided] = layers(&ramp)[..] else {
+            panic!("Not 4 layers");
+        };
+        assert_eq!(guided, 26);
+        assert!(blind > 70);
+
+        let path = std::env::temp_dir().join("aoc_2022_day12_render.svg");
+        render(INPUT_STRING, InputKind::Example, &path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), svg);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn broken_maps() {
+        assert!(HillsRange::from_grid("Sab\nbcd").is_err());
+        assert!(HillsRange::from_grid("SaE\nab1").is_err());
+        assert!(HillsRange::from_grid("Sab\nbE").is_err());
+    }
+
+    // Needs the real input in inputs/2022/day12.txt
+    #[test]
+    #[ignore]
+    fn single_search_speedup() {
+        let hills_range = HillsRange::from_grid(&bench::input(2022, 12, 60)).unwrap();
+        bench::faster(
+            ("Every start", || hills_range.climb_from_every_start()),
+            ("Single search", || hills_range.find_shortestest_path()),
+        );
     }
 }
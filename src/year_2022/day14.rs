@@ -1,14 +1,27 @@
-use ansi_term::Colour;
+use ansi_term::{Colour, Style};
 use itertools::Itertools;
 use nom::bytes::complete::tag;
-use nom::character::complete::{char, digit1};
-use nom::combinator::map;
+use nom::character::complete::char;
+use nom::combinator::{all_consuming, map};
 use nom::multi::separated_list1;
 use nom::sequence::separated_pair;
 use nom::IResult;
 
+use std::iter;
+use std::path::Path;
+#[cfg(feature = "images")]
+use std::path::PathBuf;
+
 use hashbrown::HashSet;
 
+use crate::aoc_lib::config::InputKind;
+use crate::aoc_lib::prelude::*;
+#[cfg(feature = "images")]
+use crate::aoc_lib::images::{Exporter, Palette};
+use crate::aoc_lib::trace::Recorder;
+use crate::aoc_lib::viz::dots::{self, Resolution};
+use crate::aoc_lib::viz::{self, Frame, SideBySide, Visualize};
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 enum CellType {
     Empty,
@@ -30,11 +43,8 @@ struct Segment {
 
 fn parse_point(input: &str) -> IResult<&str, Point> {
     map(
-        separated_pair(digit1, char(','), digit1),
-        |(x, y): (&str, &str)| Point {
-            x: x.parse().unwrap(),
-            y: y.parse().unwrap(),
-        },
+        separated_pair(jazz_parser::usize, char(','), jazz_parser::usize),
+        |(x, y)| Point { x, y },
     )(input)
 }
 
@@ -51,7 +61,7 @@ fn parse_rock_sequence(input: &str) -> IResult<&str, Vec<Segment>> {
     })(input)
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Cave {
     cells: Vec<CellType>,
     bottom_left: Point,
@@ -72,41 +82,51 @@ impl Cave {
         Ok(norm_x * (self.bottom_left.y + 1) + coordinate.y)
     }
 
-    fn from_raw_segments(input: &str, infinite: bool) -> Self {
-        let mut rock_segments: HashSet<Segment> = input
-            .lines()
-            .flat_map(|l| parse_rock_sequence(l.trim()).unwrap().1)
-            .collect();
+    fn from_raw_segments(input: &str, infinite: bool) -> Result<Self, String> {
+        let mut rock_segments: HashSet<Segment> = HashSet::new();
+        for line in input.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let (_, segments) = all_consuming(parse_rock_sequence)(line)
+                .map_err(|_| format!("Invalid rock path: \"{}\"", line))?;
+            rock_segments.extend(segments);
+        }
 
         let important_points: HashSet<Point> = rock_segments
             .iter()
             .flat_map(|seg| [seg.start, seg.end])
             .collect();
 
-        let bottom = important_points
-            .iter()
-            .max_by(|left, right| left.y.cmp(&right.y))
-            .unwrap()
-            .y
-            + if infinite { 0 } else { 2 };
-        let leftmost_point = important_points
-            .iter()
-            .min_by(|left, right| left.x.cmp(&right.x))
-            .unwrap()
-            .x;
-        let rightmost_point = important_points
+        if let Some(seg) = rock_segments
             .iter()
-            .max_by(|left, right| left.x.cmp(&right.x))
-            .unwrap()
-            .x;
+            .find(|seg| seg.start.x != seg.end.x && seg.start.y != seg.end.y)
+        {
+            return Err(format!("The rock path {:?} isn't straight", seg));
+        }
+        let Some(deepest) = important_points.iter().map(|point| point.y).max() else {
+            return Err(String::from("There are no rock paths"));
+        };
+        let bottom = deepest + if infinite { 0 } else { 2 };
+        // On the floor the sand spreads as wide as the cave is deep, with one
+        // more column on each side
+        if bottom >= 500 {
+            return Err(format!("The cave is too deep: {}", deepest));
+        }
+        let leftmost_point = important_points.iter().map(|point| point.x).min().unwrap();
+        let rightmost_point = important_points.iter().map(|point| point.x).max().unwrap();
+        if leftmost_point == 0 || rightmost_point > 1000 {
+            return Err(format!(
+                "The rocks go from {} to {}, too far from the source",
+                leftmost_point, rightmost_point
+            ));
+        }
 
+        // The source is in the cave even when the rocks are all on one side
         let left = if infinite {
-            leftmost_point - 1
+            leftmost_point.min(500) - 1
         } else {
             (500 - bottom).min(leftmost_point) - 1
         };
         let right = if infinite {
-            rightmost_point + 1
+            rightmost_point.max(500) + 1
         } else {
             (500 + bottom).max(rightmost_point) + 1
         };
@@ -146,10 +166,18 @@ impl Cave {
             }
         }
 
-        new_cave
+        Ok(new_cave)
     }
 
+    // Returns false once sand stops coming to rest, either because it fell into
+    // the abyss or because it buried the source
     fn simulate_one_step(&mut self) -> bool {
+        self.drop_grain()
+            .is_some_and(|rest| !(rest.x == 500 && rest.y == 0))
+    }
+
+    // Where the grain came to rest, if it didn't fall into the abyss
+    fn drop_grain(&mut self) -> Option<Point> {
         let mut sand_coord = Point { x: 500, y: 0 };
         let mut maybe_flow: HashSet<Point> = HashSet::new();
 
@@ -175,14 +203,27 @@ impl Cave {
                 sand_coord.y = new_y;
                 let sand_id = self.compute_linear_id(sand_coord).unwrap();
                 self.cells[sand_id] = CellType::Sand;
-                return !(sand_coord.x == 500 && sand_coord.y == 0);
+                return Some(sand_coord);
             }
         }
 
         maybe_flow
             .extend((sand_coord.y..=self.bottom_left.y).map(|y| Point { x: sand_coord.x, y }));
         self.final_flow = Some(maybe_flow);
-        false
+        None
+    }
+
+    // Cells with something in them, column by column
+    fn filled(&self, kind: CellType) -> impl Iterator<Item = Point> + '_ {
+        let height = self.bottom_left.y + 1;
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(move |(_, cell)| **cell == kind)
+            .map(move |(id, _)| Point {
+                x: self.bottom_left.x + id / height,
+                y: id % height,
+            })
     }
 
     pub fn simulate(&mut self, steps: Option<usize>) {
@@ -207,45 +248,278 @@ impl Cave {
             .map(|y| start.y + y - 1)
     }
 
-    fn print_cave_visual(&self) {
+    // With a floor the sand ends up on every cell it can reach from the source
+    // falling straight or diagonally, so those can be counted directly
+    pub fn fill_count(&self) -> usize {
+        let source = Point { x: 500, y: 0 };
+        let mut reached = vec![false; self.cells.len()];
+        let mut stack = vec![source];
+        reached[self.compute_linear_id(source).unwrap()] = true;
+        let mut count = 0;
+
+        while let Some(point) = stack.pop() {
+            count += 1;
+            for x in [point.x - 1, point.x, point.x + 1] {
+                let below = Point { x, y: point.y + 1 };
+                let Ok(below_id) = self.compute_linear_id(below) else {
+                    continue;
+                };
+                if self.cells[below_id] == CellType::Empty && !reached[below_id] {
+                    reached[below_id] = true;
+                    stack.push(below);
+                }
+            }
+        }
+
+        count
+    }
+
+    // The cave as text, painted with terminal colours when `paint` is set
+    fn draw_cave(&self, paint: bool) -> String {
+        let style = |style: Style, text: &str| match paint {
+            true => style.paint(text).to_string(),
+            false => text.to_string(),
+        };
+        let border = Colour::White.bold();
+        let sand = Colour::RGB(255, 184, 108).normal();
+
+        let mut output = String::new();
         for point in (0..=self.bottom_left.y)
             .cartesian_product(self.bottom_left.x..=self.top_right.x)
             .map(|(y, x)| Point { x, y })
         {
             if point.x == self.bottom_left.x {
-                print!(
-                    "{}",
-                    Colour::White.bold().paint(format!("{:4} | ", point.y))
-                );
+                output += &style(border, &format!("{:4} | ", point.y));
             }
 
             let point_id = self.compute_linear_id(point).unwrap();
-            let mut cell_char = match self.cells[point_id] {
-                CellType::Empty => " ".to_string(),
-                CellType::Rock => Colour::RGB(248, 248, 242).paint("#").to_string(),
-                CellType::Sand => Colour::RGB(255, 184, 108).paint("o").to_string(),
+            let flowing = self
+                .final_flow
+                .as_ref()
+                .is_some_and(|flow| flow.contains(&point));
+            let (cell_style, cell) = match self.cells[point_id] {
+                _ if point.x == 500 && point.y == 0 => (Colour::Cyan.normal(), "+"),
+                _ if flowing => (sand, "~"),
+                CellType::Empty => (Style::new(), " "),
+                CellType::Rock => (Colour::RGB(248, 248, 242).normal(), "#"),
+                CellType::Sand => (sand, "o"),
             };
-            if let Some(flow) = self.final_flow.as_ref() {
-                if flow.contains(&point) {
-                    cell_char = Colour::RGB(255, 184, 108).paint("~").to_string();
-                }
+            output += &style(cell_style, cell);
+
+            if point.x == self.top_right.x {
+                output += &style(border, " |");
+                output.push('\n');
             }
-            if point.x == 500 && point.y == 0 {
-                cell_char = Colour::Cyan.paint("+").to_string();
+        }
+
+        output
+    }
+
+    // The cave as colour indices for the image export and the packed pictures:
+    // empty, rock, sand, the source and the sand flowing out
+    fn colour_grid(&self) -> Grid<u8> {
+        let width = self.top_right.x - self.bottom_left.x + 1;
+        let mut grid = Grid::filled(width, self.bottom_left.y + 1, 0);
+        for (row, column) in grid.positions().collect_vec() {
+            let point = Point {
+                x: self.bottom_left.x + column,
+                y: row,
+            };
+            let flowing = self
+                .final_flow
+                .as_ref()
+                .is_some_and(|flow| flow.contains(&point));
+            grid[(row, column)] = match self.cells[self.compute_linear_id(point).unwrap()] {
+                _ if point.x == 500 && point.y == 0 => 3,
+                _ if flowing => 4,
+                CellType::Empty => 0,
+                CellType::Rock => 1,
+                CellType::Sand => 2,
+            };
+        }
+
+        grid
+    }
+
+    // What the animations show, packed tighter when asked to with --dots
+    fn picture(&self) -> String {
+        match dots::chosen() {
+            Resolution::Cells => self.draw_cave(true),
+            resolution => dots::pack(resolution, &self.colour_grid(), terminal_colour, true),
+        }
+    }
+}
+
+// The colours of `draw_cave` for the indices of `colour_grid`
+fn terminal_colour(index: &u8) -> Option<Colour> {
+    match index {
+        1 => Some(Colour::RGB(248, 248, 242)),
+        2 | 4 => Some(Colour::RGB(255, 184, 108)),
+        3 => Some(Colour::Cyan),
+        _ => None,
+    }
+}
+
+// A frame for every grain of sand, until one falls into the abyss or the
+// source is buried
+impl Visualize for Cave {
+    fn frames(&self) -> impl Iterator<Item = Frame> {
+        let mut cave = self.clone();
+        let mut grains = 0;
+        let mut pouring = true;
+
+        let start = Frame {
+            caption: String::from("Sand starts pouring from the source"),
+            picture: cave.picture(),
+        };
+        iter::once(start).chain(iter::from_fn(move || {
+            if !pouring {
+                return None;
             }
+            pouring = cave.simulate_one_step();
+            grains += 1;
+
+            let caption = match (pouring, &cave.final_flow) {
+                (true, _) => format!("Grain {} comes to rest", grains),
+                (false, Some(_)) => format!(
+                    "Grain {} falls into the abyss, {} are at rest",
+                    grains,
+                    grains - 1
+                ),
+                (false, None) => format!("Grain {} buries the source", grains),
+            };
+            Some(Frame {
+                caption,
+                picture: cave.picture(),
+            })
+        }))
+    }
+}
 
-            print!("{}", cell_char);
+// How `fill_part2` sees the cave: every cell the sand reaches on a row is
+// below or next to one it reached on the row above, so a whole row settles at
+// once
+struct Flood(Cave);
 
-            if point.x == self.top_right.x {
-                println!("{}", Colour::White.bold().paint(" |"));
+impl Visualize for Flood {
+    fn frames(&self) -> impl Iterator<Item = Frame> {
+        let mut cave = self.0.clone();
+        let mut row: Option<Vec<Point>> = None;
+        let mut grains = 0;
+
+        let start = Frame {
+            caption: String::from("Sand starts pouring from the source"),
+            picture: cave.picture(),
+        };
+        iter::once(start).chain(iter::from_fn(move || {
+            let next = match &row {
+                None => vec![Point { x: 500, y: 0 }],
+                Some(row) => row
+                    .iter()
+                    .flat_map(|point| {
+                        [point.x - 1, point.x, point.x + 1].map(|x| Point { x, y: point.y + 1 })
+                    })
+                    .filter(|below| {
+                        cave.compute_linear_id(*below)
+                            .is_ok_and(|id| cave.cells[id] == CellType::Empty)
+                    })
+                    .sorted_by_key(|below| below.x)
+                    .dedup()
+                    .collect(),
+            };
+            if next.is_empty() {
+                return None;
             }
+
+            for point in next.iter() {
+                let id = cave.compute_linear_id(*point).unwrap();
+                cave.cells[id] = CellType::Sand;
+            }
+            grains += next.len();
+            let caption = format!("Row {} filled, {} grains at rest", next[0].y, grains);
+            row = Some(next);
+
+            Some(Frame {
+                caption,
+                picture: cave.picture(),
+            })
+        }))
+    }
+}
+
+// The second part grain by grain, racing the row by row count of `fill`
+pub fn compare(input: &str, _kind: InputKind, cast: Option<&Path>) -> Result<(), AocError> {
+    let cave = Cave::from_raw_segments(input, false)?;
+    let race = SideBySide {
+        left: ("grains", cave.clone()),
+        right: ("fill", Flood(cave)),
+    };
+
+    viz::show(&race, cast)
+}
+
+// The sand of the first part, grain by grain
+pub fn visualize(input: &str, _kind: InputKind, cast: Option<&Path>) -> Result<(), AocError> {
+    viz::show(&Cave::from_raw_segments(input, true)?, cast)
+}
+
+// The rocks in step 0, then a step for every grain of the first part with the
+// cell it came to rest on
+pub fn trace(input: &str, recorder: &mut Recorder) -> Result<(), AocError> {
+    let mut cave = Cave::from_raw_segments(input, true)?;
+    let position = |point: Point| (point.x as i64, point.y as i64);
+    for rock in cave.filled(CellType::Rock) {
+        recorder.set_cell(position(rock), "rock");
+    }
+
+    let mut at_rest = 0;
+    loop {
+        recorder.next_step();
+        let Some(rest) = cave.drop_grain() else {
+            recorder.metric("fell into the abyss", 1.0);
+            break;
+        };
+        at_rest += 1;
+        recorder.set_cell(position(rest), "sand");
+        recorder.metric("sand at rest", at_rest as f64);
+        if rest == (Point { x: 500, y: 0 }) {
+            break;
         }
-        println!();
     }
+
+    Ok(())
+}
+
+// Every grain of the first part as an animation, and both caves once full
+#[cfg(feature = "images")]
+pub fn export(input: &str) -> Result<Vec<PathBuf>, AocError> {
+    let palette = Palette::new(&[
+        [40, 42, 54],
+        [248, 248, 242],
+        [255, 184, 108],
+        [139, 233, 253],
+        [255, 121, 198],
+    ])?;
+    let exporter = Exporter::new(2022, 14, palette).scale(4).delay(4);
+
+    let mut abyss = Cave::from_raw_segments(input, true)?;
+    let mut frames = vec![abyss.colour_grid()];
+    while abyss.simulate_one_step() {
+        frames.push(abyss.colour_grid());
+    }
+    frames.push(abyss.colour_grid());
+    let mut floor = Cave::from_raw_segments(input, false)?;
+    floor.simulate(None);
+
+    Ok(vec![
+        exporter.gif("sand.gif", frames, |colour| *colour)?,
+        exporter.png("abyss.png", &abyss.colour_grid(), |colour| *colour)?,
+        exporter.png("floor.png", &floor.colour_grid(), |colour| *colour)?,
+    ])
 }
 
-pub fn part1(input: &str) {
-    let mut cave = Cave::from_raw_segments(input, true);
+pub fn part1(input: &str) -> Result<Solved, AocError> {
+    let mut cave = Cave::from_raw_segments(input, true)?;
 
     cave.simulate(None);
 
@@ -255,13 +529,12 @@ pub fn part1(input: &str) {
         .filter(|&&cell| cell == CellType::Sand)
         .count();
 
-    cave.print_cave_visual();
-
-    println!("Amount of sand in an unlimited cave: {}", sand_amount);
+    // The cave at rest goes above the answer
+    Ok(Solved::from(Answer::from(sand_amount)).note(cave.draw_cave(true)))
 }
 
-pub fn part2(input: &str) {
-    let mut cave = Cave::from_raw_segments(input, false);
+pub fn part2(input: &str) -> Result<Solved, AocError> {
+    let mut cave = Cave::from_raw_segments(input, false)?;
 
     cave.simulate(None);
 
@@ -271,21 +544,43 @@ pub fn part2(input: &str) {
         .filter(|&&cell| cell == CellType::Sand)
         .count();
 
-    cave.print_cave_visual();
+    // The cave at rest goes above the answer
+    Ok(Solved::from(Answer::from(sand_amount)).note(cave.draw_cave(true)))
+}
+
+// Counts the cells the sand can reach instead of dropping it grain by grain
+pub fn fill_part2(input: &str) -> Result<Answer, AocError> {
+    let cave = Cave::from_raw_segments(input, false)?;
 
-    println!("Amount of sand in a cave with floor: {}", sand_amount);
+    Ok(Answer::from(cave.fill_count()))
 }
 
+aoc_day!(
+    2022,
+    14,
+    part1,
+    part2;
+    fill = (part1, fill_part2),
+    visualize = visualize,
+    compare = compare,
+    trace = trace,
+    #[cfg(feature = "images")]
+    export = export,
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_lib::bench;
+    use crate::aoc_lib::random::cross_check;
+    use crate::aoc_lib::testgen;
+    use crate::aoc_lib::snapshot::assert_snapshot;
 
-    static INPUT_STRING: &str = "498,4 -> 498,6 -> 496,6
-    503,4 -> 502,4 -> 502,9 -> 494,9";
+    static INPUT_STRING: &str = include_str!("../../inputs/2022/day14.example.txt");
 
     #[test]
     fn simple_tops() {
-        let cave = Cave::from_raw_segments(INPUT_STRING, true);
+        let cave = Cave::from_raw_segments(INPUT_STRING, true).unwrap();
         let tops = (cave.bottom_left.x..=cave.top_right.x)
             .map(|x| cave.find_furthest_free_y(Point { x, y: 0 }))
             .collect_vec();
@@ -310,7 +605,7 @@ mod tests {
 
     #[test]
     fn simple_flow() {
-        let mut cave = Cave::from_raw_segments(INPUT_STRING, true);
+        let mut cave = Cave::from_raw_segments(INPUT_STRING, true).unwrap();
 
         cave.simulate(None);
 
@@ -325,7 +620,7 @@ mod tests {
 
     #[test]
     fn simple_floor() {
-        let mut cave = Cave::from_raw_segments(INPUT_STRING, false);
+        let mut cave = Cave::from_raw_segments(INPUT_STRING, false).unwrap();
 
         cave.simulate(None);
 
@@ -337,4 +632,163 @@ mod tests {
 
         assert_eq!(sand_amount, 93);
     }
+
+    #[test]
+    fn simple_fill() {
+        let cave = Cave::from_raw_segments(INPUT_STRING, false).unwrap();
+
+        assert_eq!(cave.fill_count(), 93);
+    }
+
+    #[test]
+    #[ignore]
+    fn fill_speedup() {
+        let cave = Cave::from_raw_segments(&bench::input(2022, 14, 80), false).unwrap();
+        bench::faster(
+            ("Simulation", || {
+                let mut cave = cave.clone();
+                cave.simulate(None);
+                cave.cells
+                    .iter()
+                    .filter(|&&cell| cell == CellType::Sand)
+                    .count()
+            }),
+            ("Fill", || cave.clone().fill_count()),
+        );
+    }
+
+    #[test]
+    fn flooding_rows() {
+        let cave = Cave::from_raw_segments(INPUT_STRING, false).unwrap();
+        let flood: Vec<Frame> = Flood(cave.clone()).frames().collect();
+
+        // The floor is on row 11, so the last sand is on row 10
+        assert_eq!(flood.len(), 12);
+        let last = flood.last().unwrap();
+        assert_eq!(last.caption, "Row 10 filled, 93 grains at rest");
+        let mut dropped = cave.clone();
+        dropped.simulate(None);
+        assert_eq!(last.picture, dropped.draw_cave(true));
+
+        // The grains take a frame each, the flood waits for them at the end
+        let race = SideBySide {
+            left: ("grains", cave.clone()),
+            right: ("fill", Flood(cave)),
+        };
+        let frames: Vec<Frame> = race.frames().collect();
+        assert_eq!(frames.len(), 94);
+        assert!(frames[93].picture.contains("fill, done: Row 10 filled"));
+        assert!(frames[93].picture.starts_with("grains: Grain 93 buries the source"));
+    }
+
+    #[test]
+    fn cave_renders() {
+        let mut cave = Cave::from_raw_segments(INPUT_STRING, true).unwrap();
+        assert_snapshot("2022_day14_empty_cave", &cave.draw_cave(false));
+        cave.simulate(None);
+        assert_snapshot("2022_day14_overflowing_cave", &cave.draw_cave(false));
+
+        let mut cave = Cave::from_raw_segments(INPUT_STRING, false).unwrap();
+        cave.simulate(None);
+        assert_snapshot("2022_day14_cave_with_floor", &cave.draw_cave(false));
+    }
+
+    #[test]
+    fn traced_sand() {
+        let mut output = Vec::new();
+        let mut recorder = Recorder::new(&mut output);
+        trace(INPUT_STRING, &mut recorder).unwrap();
+        // 24 grains at rest and the one that falls
+        assert_eq!(recorder.step(), 25);
+        recorder.finish().unwrap();
+
+        let lines = String::from_utf8(output).unwrap();
+        let records = crate::aoc_lib::trace::read(&lines).unwrap();
+        let rocks = records.iter().take_while(|record| record.step == 0).count();
+        assert_eq!(rocks, 20);
+        assert!(lines.contains(r#"{"step":1,"event":"set_cell","at":[500,8],"value":"sand"}"#));
+        assert!(lines.ends_with(
+            "{\"step\":25,\"event\":\"metric\",\"name\":\"fell into the abyss\",\"value\":1}\n"
+        ));
+    }
+
+    #[test]
+    fn sand_frames() {
+        let frames: Vec<Frame> = Cave::from_raw_segments(INPUT_STRING, true)
+            .unwrap()
+            .frames()
+            .collect();
+
+        // The empty cave, the 24 grains at rest and the one that falls through
+        assert_eq!(frames.len(), 26);
+        assert_eq!(frames[1].caption, "Grain 1 comes to rest");
+        assert_eq!(
+            frames[25].caption,
+            "Grain 25 falls into the abyss, 24 are at rest"
+        );
+
+        let buried: Vec<Frame> = Cave::from_raw_segments(INPUT_STRING, false)
+            .unwrap()
+            .frames()
+            .collect();
+        assert_eq!(buried.len(), 94);
+        assert_eq!(buried[93].caption, "Grain 93 buries the source");
+    }
+
+    #[test]
+    fn colour_grid() {
+        let mut cave = Cave::from_raw_segments(INPUT_STRING, true).unwrap();
+        cave.simulate(None);
+        let grid = cave.colour_grid();
+
+        let count = |colour: u8| grid.positions().filter(|p| grid[*p] == colour).count();
+        assert_eq!((grid.width(), grid.height()), (12, 10));
+        assert_eq!(grid[(0, 7)], 3);
+        assert_eq!(count(2), 24);
+        assert_eq!(count(1), 20);
+        assert!(count(4) > 0);
+    }
+
+    #[test]
+    fn random_caves() {
+        cross_check(
+            0x2022_1400,
+            200,
+            |rng| {
+                let paths = 1 + rng.below(4) as usize;
+                testgen::rock_paths(rng, paths)
+            },
+            &[
+                ("simulation", |input| {
+                    let mut cave = Cave::from_raw_segments(input, false).unwrap();
+                    cave.simulate(None);
+                    cave.cells
+                        .iter()
+                        .filter(|&&cell| cell == CellType::Sand)
+                        .count()
+                }),
+                ("fill", |input| {
+                    Cave::from_raw_segments(input, false).unwrap().fill_count()
+                }),
+            ],
+        );
+    }
+
+    #[test]
+    fn bad_caves() {
+        for cave in [
+            "498,4 -> 498,6 -> ",
+            "498,4 -> 496,6",
+            "0,4 -> 2,4",
+            "498,4 -> 498,600",
+            "",
+        ] {
+            assert!(Cave::from_raw_segments(cave, false).is_err(), "{}", cave);
+        }
+
+        // The rocks don't have to be under the source
+        let mut cave = Cave::from_raw_segments("10,4 -> 12,4", true).unwrap();
+        cave.simulate(None);
+        assert_eq!(cave.filled(CellType::Sand).count(), 0);
+    }
 }
@@ -1,12 +1,17 @@
-use hashbrown::HashSet;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+use crate::aoc_lib::config::{DayConfig, InputKind};
+use crate::aoc_lib::error::write_error;
+use crate::aoc_lib::prelude::*;
+use crate::aoc_lib::jazz_parser::i32;
+use crate::aoc_lib::viz::svg::{Shape, Svg};
 use itertools::Itertools;
 use nom::bytes::complete::tag;
 use nom::combinator::map;
 use nom::sequence::{preceded, separated_pair};
 use nom::IResult;
-use rayon::prelude::*;
-
-use crate::aoc_lib::jazz_parser::i32;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 struct Point {
@@ -14,12 +19,6 @@ struct Point {
     y: i32,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-struct Segment {
-    start: Point,
-    end: Point,
-}
-
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 struct Sensor {
     location: Point,
@@ -27,46 +26,36 @@ struct Sensor {
 }
 
 impl Sensor {
-    pub fn coverage_at(&self, location: Point) -> Result<Option<Segment>, &str> {
-        if self.location.x != location.x && self.location.y != location.y {
-            return Err("Location not on the same axis!");
-        }
+    fn distance(&self, point: Point) -> u32 {
+        self.location.x.abs_diff(point.x) + self.location.y.abs_diff(point.y)
+    }
+
+    pub fn covers(&self, point: Point) -> bool {
+        self.distance(point) <= self.range
+    }
 
-        if self.location.x == location.x {
-            let delta_y = self.location.y.abs_diff(location.y);
-            if delta_y > self.range {
-                Ok(None)
-            } else {
-                let x_range = self.range - delta_y;
-                Ok(Some(Segment {
-                    start: Point {
-                        x: location.x - i32::try_from(x_range).unwrap(),
-                        y: location.y,
-                    },
-                    end: Point {
-                        x: location.x + i32::try_from(x_range).unwrap(),
-                        y: location.y,
-                    },
-                }))
-            }
-        } else {
-            let delta_x = self.location.x.abs_diff(location.x);
-            if delta_x > self.range {
-                Ok(None)
-            } else {
-                let y_range = self.range - delta_x;
-                Ok(Some(Segment {
-                    start: Point {
-                        x: location.x,
-                        y: location.y - i32::try_from(y_range).unwrap(),
-                    },
-                    end: Point {
-                        x: location.x,
-                        y: location.y + i32::try_from(y_range).unwrap(),
-                    },
-                }))
-            }
+    // The columns of row `y` that are within the range of the sensor
+    pub fn coverage_at(&self, y: i32) -> Option<RangeInclusive<i64>> {
+        let delta_y = self.location.y.abs_diff(y);
+        if delta_y > self.range {
+            return None;
         }
+
+        let x_range = i64::from(self.range - delta_y);
+        let x = i64::from(self.location.x);
+        Some(x - x_range..=x + x_range)
+    }
+
+    // The edge just outside the range is on two lines x + y = a and two lines
+    // x - y = b. Returns the (a, b) constants of those lines.
+    pub fn boundary(&self) -> ([i64; 2], [i64; 2]) {
+        let (x, y) = (i64::from(self.location.x), i64::from(self.location.y));
+        let outside = i64::from(self.range) + 1;
+
+        (
+            [x + y - outside, x + y + outside],
+            [x - y - outside, x - y + outside],
+        )
     }
 }
 
@@ -101,151 +90,208 @@ fn parse_sensor(input: &str) -> IResult<&str, (Sensor, Point)> {
     )(input)
 }
 
-fn y_coverage(
-    y: i32,
-    sensors: &[Sensor],
-    beacons: &[Point],
-    limit: Option<i32>,
-) -> (usize, Option<Vec<i32>>) {
-    let coverage_parts: Vec<Segment> = sensors
+fn y_coverage(y: i32, sensors: &[Sensor], beacons: &[Point]) -> u64 {
+    let coverage: IntervalSet = sensors
         .iter()
-        .filter_map(|sens| {
-            sens.coverage_at(Point {
-                x: sens.location.x,
-                y,
-            })
-            .unwrap()
-        })
-        .sorted_by(|left, right| left.start.x.cmp(&right.start.x))
-        .collect_vec();
-
-    let mut merged_coverage: Vec<Segment> = Vec::new();
-    let mut current_segment: Segment = *coverage_parts.first().unwrap();
-
-    for seg in coverage_parts.iter() {
-        if seg.start.x > current_segment.end.x {
-            merged_coverage.push(current_segment);
-            current_segment = *seg;
-        } else {
-            current_segment.end.x = current_segment.end.x.max(seg.end.x);
-        }
-    }
-
-    merged_coverage.push(current_segment);
+        .filter_map(|sensor| sensor.coverage_at(y))
+        .collect();
 
-    if let Some(x_limit) = limit {
-        for segment in merged_coverage.iter_mut() {
-            segment.start.x = segment.start.x.clamp(0, x_limit);
-            segment.end.x = segment.end.x.clamp(0, x_limit);
-        }
-    }
+    // A beacon is there, so it can't be counted as a spot without one
+    let line_beacons = beacons
+        .iter()
+        .filter(|beacon| beacon.y == y && coverage.contains(i64::from(beacon.x)))
+        .unique()
+        .count();
 
-    let line_beacons: HashSet<Point> = beacons.iter().filter(|&b| b.y == y).cloned().collect();
-    let empty_spots = merged_coverage.iter().fold(0usize, |acc, &seg| {
-        acc + usize::try_from(seg.end.x.abs_diff(seg.start.x)).unwrap()
-            + (seg.start.x <= 0 && seg.end.x > 0) as usize
-    });
+    coverage.count() - line_beacons as u64
+}
 
-    let non_empty: HashSet<Point> = merged_coverage
+// The only free spot is just outside the range of some sensors, so it sits
+// where their boundary lines cross. Against a side of the square it only
+// needs the sensors on one side of it, so it can be on a single boundary line:
+// where those meet the sides and the corners are checked too.
+fn find_distress_point(max: i32, sensors: &[Sensor]) -> Option<Point> {
+    let (rising, falling): (Vec<[i64; 2]>, Vec<[i64; 2]>) =
+        sensors.iter().map(Sensor::boundary).unzip();
+    let rising = rising.into_iter().flatten().unique().collect_vec();
+    let falling = falling.into_iter().flatten().unique().collect_vec();
+    let side = i64::from(max);
+
+    let crossings = rising
+        .iter()
+        .cartesian_product(falling.iter())
+        // x + y = a and x - y = b only meet on a cell when a + b is even
+        .filter(|(a, b)| (*a + *b) % 2 == 0)
+        .map(|(a, b)| ((a + b) / 2, (a - b) / 2));
+    let on_sides = rising
         .iter()
-        .flat_map(|seg| {
-            line_beacons
+        .flat_map(|a| [(0, *a), (side, a - side), (*a, 0), (a - side, side)])
+        .chain(
+            falling
                 .iter()
-                .filter(|&b| b.x >= seg.start.x && b.x <= seg.end.x)
+                .flat_map(|b| [(0, -b), (side, side - b), (*b, 0), (b + side, side)]),
+        );
+    let corners = [(0, 0), (side, 0), (0, side), (side, side)];
+
+    crossings
+        .chain(on_sides)
+        .chain(corners)
+        .filter(|(x, y)| (0..=side).contains(x) && (0..=side).contains(y))
+        .map(|(x, y)| Point {
+            x: x as i32,
+            y: y as i32,
         })
-        .cloned()
-        .collect();
+        .find(|point| sensors.iter().all(|sensor| !sensor.covers(*point)))
+}
 
-    if limit.is_none() {
-        (empty_spots - non_empty.len(), None)
-    } else {
-        let possible_points: HashSet<Point> = merged_coverage
-            .iter()
-            .tuple_windows()
-            .flat_map(|(left, right)| (left.end.x + 1..right.start.x).map(|x| Point { x, y }))
-            .collect();
-        let actual_points: Vec<i32> = possible_points
-            .difference(&non_empty)
-            .map(|point| point.x)
-            .collect_vec();
-        (empty_spots - non_empty.len(), Some(actual_points))
+// The row part 1 counts on, and the side of the square the distress beacon is
+// in, smaller for the example
+const ROW: DayConfig<i32> = DayConfig::new(10, 2000000);
+const SEARCH_AREA: DayConfig<i32> = DayConfig::new(20, 4000000);
+
+// The area of every sensor as a diamond, with the beacons, the square up to
+// `max` where the distress beacon is and, if there is one, the distress beacon
+// itself
+fn sensors_svg(sensors: &[(Sensor, Point)], max: i32) -> Svg {
+    // Dots big enough to see next to the largest diamond
+    let dot = sensors
+        .iter()
+        .map(|(sensor, _)| f64::from(sensor.range))
+        .fold(f64::from(max), f64::max)
+        / 150.0;
+    let centre = |point: &Point| (f64::from(point.x), f64::from(point.y));
+
+    let mut svg = Svg::new();
+    svg.layer("Search area", "none", "black", 1.0).add(
+        Shape::Rect {
+            corner: (0.0, 0.0),
+            width: f64::from(max),
+            height: f64::from(max),
+        },
+        Some(&format!("The distress beacon is within 0..={} on both axes", max)),
+    );
+
+    svg.layer("Coverage", "seagreen", "darkgreen", 0.4);
+    for (sensor, beacon) in sensors {
+        let (x, y) = centre(&sensor.location);
+        let range = f64::from(sensor.range);
+        svg.add(
+            Shape::Polygon(vec![
+                (x, y - range),
+                (x + range, y),
+                (x, y + range),
+                (x - range, y),
+            ]),
+            Some(&format!(
+                "Sensor at x={}, y={}, closest beacon at x={}, y={}, range {}",
+                sensor.location.x, sensor.location.y, beacon.x, beacon.y, sensor.range
+            )),
+        );
     }
-}
 
-fn find_distress_point(max: i32, sensors: &[Sensor], beacons: &[Point]) -> Point {
-    let single_line = (0..=max)
-        .into_par_iter()
-        .map(|y| (y, y_coverage(y, sensors, beacons, Some(max)).1))
-        .reduce(
-            || (0, None),
-            |a, b| {
-                if let Some(possible_points) = a.1.as_ref() {
-                    if possible_points.len() == 1 {
-                        a
-                    } else {
-                        b
-                    }
-                } else {
-                    b
-                }
+    svg.layer("Sensors", "darkgreen", "none", 1.0);
+    for (sensor, _) in sensors {
+        svg.add(
+            Shape::Circle {
+                centre: centre(&sensor.location),
+                radius: dot,
             },
+            Some(&format!(
+                "Sensor at x={}, y={}",
+                sensor.location.x, sensor.location.y
+            )),
         );
-
-    Point {
-        x: single_line.1.unwrap_or_else(|| vec![-1])[0],
-        y: single_line.0,
     }
-}
 
-pub fn part1(input: &str) {
-    let mut sensors: Vec<Sensor> = Vec::new();
-    let mut beacons: Vec<Point> = Vec::new();
+    svg.layer("Beacons", "royalblue", "none", 1.0);
+    for beacon in sensors.iter().map(|(_, beacon)| beacon).unique() {
+        svg.add(
+            Shape::Circle {
+                centre: centre(beacon),
+                radius: dot,
+            },
+            Some(&format!("Beacon at x={}, y={}", beacon.x, beacon.y)),
+        );
+    }
 
-    for (s, b) in input.lines().map(|l| parse_sensor(l.trim()).unwrap().1) {
-        sensors.push(s);
-        beacons.push(b);
+    let only_sensors = sensors.iter().map(|(sensor, _)| *sensor).collect_vec();
+    if let Some(point) = find_distress_point(max, &only_sensors) {
+        svg.layer("Distress beacon", "crimson", "black", 1.0).add(
+            Shape::Circle {
+                centre: centre(&point),
+                radius: 2.0 * dot,
+            },
+            Some(&format!(
+                "Distress beacon at x={}, y={}, tuning frequency {}",
+                point.x,
+                point.y,
+                i64::from(point.x) * 4000000 + i64::from(point.y)
+            )),
+        );
     }
 
-    let (empty_cells, _) = y_coverage(2000000, &sensors, &beacons, None);
+    svg
+}
 
-    println!("Amount of empty spots on line 2000000: {}", empty_cells);
+// Every sensor with the beacon closest to it
+fn parse_input(input: &str) -> Result<Vec<(Sensor, Point)>, String> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            parse_sensor(line)
+                .map(|(_, sensor)| sensor)
+                .map_err(|e| format!("Error while parsing \"{}\": {}", line, e))
+        })
+        .collect()
 }
 
-pub fn part2(input: &str) {
-    let mut sensors: Vec<Sensor> = Vec::new();
-    let mut beacons: Vec<Point> = Vec::new();
+pub fn render(input: &str, kind: InputKind, path: &Path) -> Result<(), AocError> {
+    let sensors = parse_input(input)?;
 
-    for (s, b) in input.lines().map(|l| parse_sensor(l.trim()).unwrap().1) {
-        sensors.push(s);
-        beacons.push(b);
-    }
+    fs::write(path, sensors_svg(&sensors, SEARCH_AREA.pick(kind)).to_string()).map_err(|e| write_error(path, e))
+}
 
-    let point = find_distress_point(4000000, &sensors, &beacons);
+pub fn part1(input: &str, kind: InputKind) -> Result<Answer, AocError> {
+    let (sensors, beacons): (Vec<Sensor>, Vec<Point>) =
+        parse_input(input).map_err(AocError::Parse)?.into_iter().unzip();
 
+    let empty_cells = y_coverage(ROW.pick(kind), &sensors, &beacons);
+
+    Ok(Answer::from(empty_cells))
+}
+
+pub fn part2(input: &str, kind: InputKind) -> Result<Answer, AocError> {
+    let sensors: Vec<Sensor> = parse_input(input)
+        .map_err(AocError::Parse)?
+        .into_iter()
+        .map(|(sensor, _)| sensor)
+        .collect();
+
+    let max = SEARCH_AREA.pick(kind);
+    let point = find_distress_point(max, &sensors).ok_or_else(|| {
+        AocError::Unsolvable(format!(
+            "every point between 0 and {} is in range of a sensor",
+            max
+        ))
+    })?;
+
+    // The point is in the search area, which starts at zero
     let disdress_frequency =
-        usize::try_from(point.x).unwrap() * 4000000 + usize::try_from(point.y).unwrap();
+        u64::from(point.x.unsigned_abs()) * 4000000 + u64::from(point.y.unsigned_abs());
 
-    println!("Distress signal frequency: {}", disdress_frequency);
+    Ok(Answer::from(disdress_frequency))
 }
 
+aoc_day!(2022, 15, input_kind = (part1, part2); render = render);
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_lib::DayFn;
 
-    static INPUT_STRING: &str = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
-    Sensor at x=9, y=16: closest beacon is at x=10, y=16
-    Sensor at x=13, y=2: closest beacon is at x=15, y=3
-    Sensor at x=12, y=14: closest beacon is at x=10, y=16
-    Sensor at x=10, y=20: closest beacon is at x=10, y=16
-    Sensor at x=14, y=17: closest beacon is at x=10, y=16
-    Sensor at x=8, y=7: closest beacon is at x=2, y=10
-    Sensor at x=2, y=0: closest beacon is at x=2, y=10
-    Sensor at x=0, y=11: closest beacon is at x=2, y=10
-    Sensor at x=20, y=14: closest beacon is at x=25, y=17
-    Sensor at x=17, y=20: closest beacon is at x=21, y=22
-    Sensor at x=16, y=7: closest beacon is at x=15, y=3
-    Sensor at x=14, y=3: closest beacon is at x=15, y=3
-    Sensor at x=20, y=1: closest beacon is at x=15, y=3";
+    static INPUT_STRING: &str = include_str!("../../inputs/2022/day15.example.txt");
 
     #[test]
     fn simple_coverage() {
@@ -260,7 +306,7 @@ mod tests {
             beacons.push(b);
         }
 
-        let (empty_cells, _) = y_coverage(10, &sensors, &beacons, None);
+        let empty_cells = y_coverage(ROW.example, &sensors, &beacons);
 
         assert_eq!(empty_cells, 26);
     }
@@ -278,9 +324,67 @@ mod tests {
             beacons.push(b);
         }
 
-        let point = find_distress_point(20, &sensors, &beacons);
+        let point = find_distress_point(SEARCH_AREA.example, &sensors).unwrap();
 
         assert_eq!(point, Point { x: 14, y: 11 });
         assert_eq!(point.x * 4000000 + point.y, 56000011);
     }
+
+    #[test]
+    fn example_or_real() {
+        let (part1, part2) = (DAY.parts.0.unwrap(), DAY.parts.1.unwrap());
+        let example = |part: DayFn| part(INPUT_STRING, InputKind::Example).unwrap().answer;
+        assert_eq!(example(part1).to_string(), "26");
+        assert_eq!(example(part2).to_string(), "56000011");
+
+        // The row of the real inputs is far below the example's sensors
+        let real = part1(INPUT_STRING, InputKind::Real).unwrap().answer;
+        assert_eq!(real.to_string(), "0");
+    }
+
+    #[test]
+    fn sensors_drawing() {
+        let path = std::env::temp_dir().join("aoc_2022_day15_render.svg");
+        render(INPUT_STRING, InputKind::Example, &path).unwrap();
+        let svg = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(svg.matches("<polygon ").count(), 14);
+        // Four sensors share the beacon at 10, 16
+        assert_eq!(svg.matches("<title>Beacon at").count(), 6);
+        assert!(svg.contains(
+            r#"<polygon points="8,-2 17,7 8,16 -1,7" vector-effect="non-scaling-stroke"><title>Sensor at x=8, y=7, closest beacon at x=2, y=10, range 9</title></polygon>"#
+        ));
+        assert!(svg.contains("<title>Distress beacon at x=14, y=11, tuning frequency 56000011</title>"));
+        assert!(svg.contains("within 0..=20 on both axes"));
+        assert!(render("Sensor at x=2, y=18", InputKind::Example, &path).is_err());
+
+        // The same sensors searched like a real input
+        render(INPUT_STRING, InputKind::Real, &path).unwrap();
+        let svg = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(svg.contains("within 0..=4000000 on both axes"));
+    }
+
+    #[test]
+    fn bad_inputs() {
+        let garbage = "Sensor at x=2, y=18";
+        assert!(matches!(part1(garbage, InputKind::Real), Err(AocError::Parse(_))));
+        let everywhere = "Sensor at x=0, y=0: closest beacon is at x=0, y=9000000";
+        let solved = part2(everywhere, InputKind::Real);
+        assert!(matches!(solved, Err(AocError::Unsolvable(_))));
+    }
+
+    #[test]
+    fn distress_in_a_corner() {
+        // One sensor reaching every spot but the corner away from it
+        let near = "Sensor at x=20, y=20: closest beacon is at x=20, y=59";
+        let solved = part2(near, InputKind::Example).unwrap();
+        assert_eq!(solved.to_string(), "0");
+
+        let far = "Sensor at x=0, y=0: closest beacon is at x=0, y=39";
+        let sensors = parse_input(far).unwrap().into_iter().map(|(s, _)| s).collect_vec();
+        let point = find_distress_point(SEARCH_AREA.example, &sensors);
+        assert_eq!(point, Some(Point { x: 20, y: 20 }));
+    }
 }
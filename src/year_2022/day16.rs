@@ -3,13 +3,17 @@ use itertools::Itertools;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::character::complete::alpha1;
+use nom::combinator::all_consuming;
 use nom::multi::separated_list1;
 use nom::sequence::{preceded, tuple};
 use nom::IResult;
 use petgraph::algo::dijkstra;
 use petgraph::graph::{NodeIndex, UnGraph};
 
-use crate::aoc_lib::jazz_parser;
+use crate::aoc_lib::config::InputKind;
+use crate::aoc_lib::prelude::*;
+use crate::aoc_lib::dot::{DotStyle, ToDot};
+use crate::aoc_lib::explain::Explanation;
 
 type SteamPath = Vec<(NodeIndex, u8)>;
 type GeneratedPaths = Vec<(u64, SteamPath)>;
@@ -45,14 +49,37 @@ impl VolcanoNetwork {
         }
     }
 
-    fn from_description(input: &str) -> Self {
+    fn from_description(input: &str) -> Result<Self, String> {
         let mut new_volcano = VolcanoNetwork::new();
 
         let mut valve_lookup: HashMap<String, NodeIndex> = HashMap::new();
 
-        for (valve_name, valve_value, neigh) in
-            input.lines().map(|l| parse_valve(l.trim()).unwrap().1)
-        {
+        let descriptions: Vec<(&str, u64, Vec<&str>)> = input
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(|l| {
+                all_consuming(parse_valve)(l)
+                    .map(|(_, valve)| valve)
+                    .map_err(|_| format!("Invalid valve: \"{}\"", l))
+            })
+            .try_collect()?;
+        let names: HashSet<&str> = descriptions.iter().map(|(name, _, _)| *name).collect();
+        if names.len() < descriptions.len() {
+            return Err(String::from("A valve is described twice"));
+        }
+        if !names.contains("AA") {
+            return Err(String::from("There is no valve AA to start from"));
+        }
+        let lost = descriptions
+            .iter()
+            .flat_map(|(_, _, neigh)| neigh)
+            .find(|name| !names.contains(*name));
+        if let Some(name) = lost {
+            return Err(format!("A tunnel leads to {}, which isn't described", name));
+        }
+
+        for (valve_name, valve_value, neigh) in descriptions {
             let curr_id = new_volcano
                 .valve_graph
                 .add_node((valve_name.to_string(), valve_value));
@@ -86,7 +113,27 @@ impl VolcanoNetwork {
             );
         }
 
-        new_volcano
+        let reachable = &new_volcano.min_distance[&new_volcano.root_node];
+        let stuck = new_volcano
+            .non_zero_nodes
+            .iter()
+            .find(|id| !reachable.contains_key(*id));
+        if let Some(id) = stuck {
+            return Err(format!(
+                "Valve {} can't be reached from AA",
+                new_volcano.valve_graph[*id].0
+            ));
+        }
+        let valves = new_volcano
+            .non_zero_nodes
+            .iter()
+            .filter(|id| new_volcano.valve_graph[**id].1 > 0)
+            .count();
+        if valves >= 32 {
+            return Err(String::from("Too many valves for the subset masks"));
+        }
+
+        Ok(new_volcano)
     }
 
     pub fn compute_max_steam(&self, max_time: u8) -> (u64, SteamPath) {
@@ -107,6 +154,11 @@ impl VolcanoNetwork {
     }
 
     pub fn compute_dual_max(&self, max_time: u8) -> u64 {
+        self.best_pair(max_time).0
+    }
+
+    // The two paths opening different valves that release the most together
+    fn best_pair(&self, max_time: u8) -> (u64, SteamPath, SteamPath) {
         let mut active_nodes: HashSet<NodeIndex> = HashSet::new();
 
         let all_paths = self
@@ -135,26 +187,139 @@ impl VolcanoNetwork {
         }
 
         let mut result: u64 = 0;
+        let mut pair = None;
 
         for (my_id, (my_steam, my_set)) in path_sets.iter().enumerate() {
-            let sets_to_test = path_sets
-                .iter()
-                .skip(my_id + 1)
-                .filter(|(steam, set)| {
-                    if (steam + my_steam) > result {
-                        my_set.is_disjoint(set)
-                    } else {
-                        false
-                    }
-                })
-                .collect_vec();
+            for (elephant_id, (elephant_steam, elephant_set)) in
+                path_sets.iter().enumerate().skip(my_id + 1)
+            {
+                if my_steam + elephant_steam > result && my_set.is_disjoint(elephant_set) {
+                    result = my_steam + elephant_steam;
+                    pair = Some((my_id, elephant_id));
+                }
+            }
+        }
+
+        match pair {
+            Some((mine, elephant)) => (
+                result,
+                all_paths[mine].1.clone(),
+                all_paths[elephant].1.clone(),
+            ),
+            None => (result, Vec::new(), Vec::new()),
+        }
+    }
+
+    // Every valve of the path that releases something, with when it's opened
+    // and how much it releases until the end
+    fn explain_path(&self, title: &str, max_time: u8, path: &SteamPath) -> Explanation {
+        let mut explanation = Explanation::new(title);
+        for (valve, remaining) in path.iter() {
+            let (name, flow) = &self.valve_graph[*valve];
+            if *flow == 0 {
+                continue;
+            }
+            explanation.push(Explanation::new(format!(
+                "Minute {}: open {}, {} x {} minutes = {}",
+                max_time - remaining,
+                name,
+                flow,
+                remaining,
+                flow * u64::from(*remaining)
+            )));
+        }
+        explanation
+    }
+
+    // Valves worth opening, their position is their bit in the subset masks
+    fn valves(&self) -> Vec<NodeIndex> {
+        let valves = self
+            .non_zero_nodes
+            .iter()
+            .filter(|id| self.valve_graph[**id].1 > 0)
+            .copied()
+            .sorted()
+            .collect_vec();
+        assert!(valves.len() < 32, "Too many valves for the subset masks");
+
+        valves
+    }
+
+    // Best pressure released opening exactly the valves in each subset, walking
+    // every order the valves can be opened in within the time. Subsets that
+    // can't be opened in time stay at zero.
+    fn pressure_per_subset(&self, max_time: u8) -> Vec<u64> {
+        let valves = self.valves();
+        let mut best = vec![0u64; 1 << valves.len()];
+
+        self.open_valves(
+            &valves,
+            &mut best,
+            self.root_node,
+            0,
+            i16::from(max_time),
+            0,
+        );
+
+        best
+    }
+
+    fn open_valves(
+        &self,
+        valves: &[NodeIndex],
+        best: &mut [u64],
+        position: NodeIndex,
+        open: usize,
+        minutes_remaining: i16,
+        pressure: u64,
+    ) {
+        best[open] = best[open].max(pressure);
+
+        let distances = &self.min_distance[&position];
+        for (bit, valve) in valves.iter().enumerate() {
+            let next_remaining = minutes_remaining - i16::from(distances[valve]) - 1;
+            if open & (1 << bit) != 0 || next_remaining <= 0 {
+                continue;
+            }
+
+            let released = self.valve_graph[*valve].1 * next_remaining as u64;
+            self.open_valves(
+                valves,
+                best,
+                *valve,
+                open | (1 << bit),
+                next_remaining,
+                pressure + released,
+            );
+        }
+    }
 
-            for (elephant_steam, _) in sets_to_test.into_iter() {
-                result = result.max(my_steam + elephant_steam);
+    pub fn subset_max_steam(&self, max_time: u8) -> u64 {
+        self.pressure_per_subset(max_time)
+            .into_iter()
+            .max()
+            .unwrap_or(0)
+    }
+
+    // Me and the elephant open disjoint sets of valves. After spreading every
+    // subset's best to its supersets, the best split is just the best of each
+    // subset plus its complement.
+    pub fn subset_dual_max(&self, max_time: u8) -> u64 {
+        let mut best = self.pressure_per_subset(max_time);
+        let full = best.len() - 1;
+
+        for bit in 0..full.count_ones() {
+            for mask in 0..best.len() {
+                if mask & (1 << bit) != 0 {
+                    best[mask] = best[mask].max(best[mask ^ (1 << bit)]);
+                }
             }
         }
 
-        result
+        (0..best.len())
+            .map(|mask| best[mask] + best[full ^ mask])
+            .max()
+            .unwrap_or(0)
     }
 
     fn compute_steam(
@@ -196,7 +361,7 @@ impl VolcanoNetwork {
             if let Some(next_paths) = self.compute_steam(active_nodes, next_id, next_remaining) {
                 for (next_steam, next_path) in next_paths {
                     let mut full_next = base_path.clone();
-                    full_next.extend(next_path.into_iter());
+                    full_next.extend(next_path);
                     paths_from_here.push((node_contribution + next_steam, full_next));
                 }
             }
@@ -207,48 +372,201 @@ impl VolcanoNetwork {
     }
 }
 
-pub fn part1(input: &str) {
-    let volcano = VolcanoNetwork::from_description(input);
+struct Valve {
+    name: String,
+    flow: u64,
+}
+
+// The valves worth opening stand out, and so does the one everybody starts from
+impl DotStyle for Valve {
+    fn label(&self) -> Option<String> {
+        Some(format!("{}\nflow {}", self.name, self.flow))
+    }
+
+    fn colour(&self) -> Option<String> {
+        match (self.name.as_str(), self.flow) {
+            ("AA", _) => Some(String::from("lightblue")),
+            (_, 0) => None,
+            _ => Some(String::from("orange")),
+        }
+    }
+}
+
+pub fn graph(input: &str) -> Result<String, AocError> {
+    let volcano = VolcanoNetwork::from_description(input)?;
+    let valves = volcano.valve_graph.map(
+        |_, (name, flow)| Valve {
+            name: name.clone(),
+            flow: *flow,
+        },
+        |_, _| (),
+    );
+
+    Ok(valves.to_dot())
+}
+
+// The valves opened in both parts, in order. The order comes from the path
+// solver, the subset one only knows which valves are open.
+pub fn explain(input: &str, _kind: InputKind) -> Result<Explanation, AocError> {
+    let volcano = VolcanoNetwork::from_description(input)?;
+    let (alone, path) = volcano.compute_max_steam(30);
+    let (together, mine, elephant) = volcano.best_pair(26);
+
+    Ok(Explanation::new(format!(
+        "{} valves, {} of them worth opening",
+        volcano.valve_graph.node_count(),
+        volcano.valves().len()
+    ))
+    .detail(volcano.explain_path(&format!("Alone in 30 minutes: {} released", alone), 30, &path))
+    .detail(
+        Explanation::new(format!("With the elephant in 26 minutes: {} released", together))
+            .detail(volcano.explain_path("You", 26, &mine))
+            .detail(volcano.explain_path("The elephant", 26, &elephant)),
+    ))
+}
+
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let volcano = VolcanoNetwork::from_description(input)?;
+    let max_steam = volcano.subset_max_steam(30);
+
+    Ok(Answer::from(max_steam))
+}
+
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let volcano = VolcanoNetwork::from_description(input)?;
+    let max_steam = volcano.subset_dual_max(26);
+
+    Ok(Answer::from(max_steam))
+}
+
+// The original solver, which lists every path and then pairs up the disjoint ones
+pub fn paths_part1(input: &str) -> Result<Answer, AocError> {
+    let volcano = VolcanoNetwork::from_description(input)?;
     let (max_steam, _) = volcano.compute_max_steam(30);
 
-    println!("Max steam released: {}", max_steam);
+    Ok(Answer::from(max_steam))
 }
 
-pub fn part2(input: &str) {
-    let volcano = VolcanoNetwork::from_description(input);
+pub fn paths_part2(input: &str) -> Result<Answer, AocError> {
+    let volcano = VolcanoNetwork::from_description(input)?;
     let max_steam = volcano.compute_dual_max(26);
 
-    println!("Max steam when running two agents: {}", max_steam);
+    Ok(Answer::from(max_steam))
 }
 
+aoc_day!(
+    2022,
+    16,
+    part1,
+    part2;
+    paths = (paths_part1, paths_part2),
+    graph = graph,
+    explain = explain,
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_lib::random::cross_check;
+    use crate::aoc_lib::testgen;
 
-    static INPUT_STRING: &str = "Valve BB has flow rate=13; tunnels lead to valves CC, AA
-    Valve CC has flow rate=2; tunnels lead to valves DD, BB
-    Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
-    Valve EE has flow rate=3; tunnels lead to valves FF, DD
-    Valve HH has flow rate=22; tunnel leads to valve GG
-    Valve JJ has flow rate=21; tunnel leads to valve II
-    Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
-    Valve FF has flow rate=0; tunnels lead to valves EE, GG
-    Valve GG has flow rate=0; tunnels lead to valves FF, HH
-    Valve II has flow rate=0; tunnels lead to valves AA, JJ";
+    static INPUT_STRING: &str = include_str!("../../inputs/2022/day16.example.txt");
 
     #[test]
     fn simple_volcano() {
-        let volcano = VolcanoNetwork::from_description(INPUT_STRING);
+        let volcano = VolcanoNetwork::from_description(INPUT_STRING).unwrap();
         let (max_steam, _) = volcano.compute_max_steam(30);
 
         assert_eq!(max_steam, 1651);
     }
 
+    #[test]
+    fn valve_graph() {
+        let dot = graph(INPUT_STRING).unwrap();
+
+        assert!(dot.starts_with("graph {\n"));
+        assert_eq!(dot.matches(" -- ").count(), 10);
+        assert_eq!(dot.matches("orange").count(), 6);
+        assert!(dot.contains(" [label=\"AA\\nflow 0\", fillcolor=\"lightblue\"]\n"));
+    }
+
     #[test]
     fn simple_dual() {
-        let volcano = VolcanoNetwork::from_description(INPUT_STRING);
+        let volcano = VolcanoNetwork::from_description(INPUT_STRING).unwrap();
         let max_steam = volcano.compute_dual_max(26);
 
         assert_eq!(max_steam, 1707);
     }
+
+    #[test]
+    fn explained_valves() {
+        let explanation = explain(INPUT_STRING, InputKind::Example).unwrap().to_string();
+        let lines: Vec<&str> = explanation.lines().collect();
+
+        assert_eq!(lines[0], "10 valves, 6 of them worth opening");
+        assert_eq!(lines[1], "  Alone in 30 minutes: 1651 released");
+        assert_eq!(lines[2], "    Minute 2: open DD, 20 x 28 minutes = 560");
+        assert!(explanation.contains("\n  With the elephant in 26 minutes: 1707 released\n"));
+        assert!(explanation.contains("\n    The elephant\n"));
+        // Every valve worth opening gets opened, once
+        assert_eq!(explanation.matches(": open ").count(), 12);
+    }
+
+    #[test]
+    fn subset_dp() {
+        let volcano = VolcanoNetwork::from_description(INPUT_STRING).unwrap();
+
+        assert_eq!(volcano.subset_max_steam(30), 1651);
+        assert_eq!(volcano.subset_dual_max(26), 1707);
+        // Not enough time to even open a valve
+        assert_eq!(volcano.subset_dual_max(1), 0);
+    }
+
+    #[test]
+    fn flowing_start() {
+        // The starting valve still takes a minute to open
+        let volcano = VolcanoNetwork::from_description(
+            "Valve AA has flow rate=10; tunnel leads to valve BB
+            Valve BB has flow rate=1; tunnel leads to valve AA",
+        )
+        .unwrap();
+
+        assert_eq!(volcano.subset_max_steam(3), 10 * 2);
+        assert_eq!(volcano.subset_dual_max(3), 10 * 2 + 1);
+    }
+
+    #[test]
+    fn bad_volcanoes() {
+        for volcano in [
+            "Valve AA has flow rate=10; tunnel leads to valve BB",
+            "Valve BB has flow rate=10; tunnel leads to valve BB",
+            "Valve AA has flow rate=10; tunnel leads to valve AA
+            Valve BB has flow rate=1; tunnel leads to valve BB",
+            "Valve AA has flow rate=10; tunnel leads to val",
+        ] {
+            assert!(VolcanoNetwork::from_description(volcano).is_err(), "{}", volcano);
+        }
+    }
+
+    #[test]
+    fn random_volcanoes() {
+        cross_check(
+            0x2022_1600,
+            60,
+            |rng| {
+                let valves = 2 + rng.below(5) as usize;
+                testgen::volcano(rng, valves)
+            },
+            &[
+                ("subsets", |input| {
+                    let volcano = VolcanoNetwork::from_description(input).unwrap();
+                    (volcano.subset_max_steam(30), volcano.subset_dual_max(26))
+                }),
+                ("paths", |input| {
+                    let volcano = VolcanoNetwork::from_description(input).unwrap();
+                    (volcano.compute_max_steam(30).0, volcano.compute_dual_max(26))
+                }),
+            ],
+        );
+    }
 }
@@ -1,6 +1,12 @@
 use ansi_term::Colour;
 use itertools::Itertools;
+
+use crate::aoc_lib::config::InputKind;
+use crate::aoc_lib::prelude::*;
+use crate::aoc_lib::viz::dots;
+use crate::aoc_lib::viz::{self, Frame, Visualize};
 use std::ops::{Add, AddAssign};
+use std::path::Path;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 enum Direction {
@@ -131,44 +137,40 @@ static TETROMINO_ORDER: [TetrominoShape; 5] = [
     TetrominoShape::Square,
 ];
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct StoneTetris {
     max_height: i64,
     occupation_grid: Vec<bool>,
     tetromino_id: usize,
     steam_directions: Vec<Direction>,
     steam_id: usize,
+    // Highest occupied cell of every column, -1 when it's still empty
+    column_tops: [i64; 7],
 }
 
 impl StoneTetris {
-    fn new(input: &str) -> Self {
-        Self {
-            max_height: -1,
-            occupation_grid: vec![false; 7 * 6],
-            tetromino_id: 0,
-            steam_directions: input
-                .trim()
-                .chars()
-                .map(|c| {
-                    if c == '<' {
-                        Direction::Left
-                    } else {
-                        Direction::Right
-                    }
-                })
-                .collect_vec(),
-            steam_id: 0,
+    fn new(input: &str) -> Result<Self, String> {
+        let steam_directions = input
+            .trim()
+            .chars()
+            .map(|c| match c {
+                '<' => Ok(Direction::Left),
+                '>' => Ok(Direction::Right),
+                _ => Err(format!("Unknown jet direction '{}'", c)),
+            })
+            .collect::<Result<Vec<Direction>, String>>()?;
+        if steam_directions.is_empty() {
+            return Err(String::from("No jets of hot gas in the input"));
         }
-    }
 
-    fn validation_copy(&self) -> Self {
-        Self {
+        Ok(Self {
             max_height: -1,
             occupation_grid: vec![false; 7 * 6],
             tetromino_id: 0,
-            steam_directions: self.steam_directions.clone(),
+            steam_directions,
             steam_id: 0,
-        }
+            column_tops: [-1; 7],
+        })
     }
 
     pub fn reset(&mut self) {
@@ -176,6 +178,7 @@ impl StoneTetris {
         self.occupation_grid = vec![false; 7 * 6];
         self.tetromino_id = 0;
         self.steam_id = 0;
+        self.column_tops = [-1; 7];
     }
 
     fn linear_id(&self, location: &Point) -> usize {
@@ -193,29 +196,36 @@ impl StoneTetris {
         })
     }
 
-    pub fn find_max_height(&mut self, turn: usize) -> i64 {
-        let (base, rep, height) = self.find_repetition(false);
-        let full_reps = i64::try_from((turn - base) / rep).unwrap();
-        let easy_height = full_reps * height;
+    // What the next drops depend on: the next rock, the next jet and how deep
+    // every column is below the top. Once it repeats the tower keeps growing by
+    // the same height every loop.
+    fn fingerprint(&self) -> (usize, usize, [i64; 7]) {
+        (
+            self.tetromino_id,
+            self.steam_id,
+            self.column_tops.map(|top| self.max_height - top),
+        )
+    }
+
+    pub fn find_max_height(&mut self, turns: usize) -> i64 {
         self.reset();
-        let rem_turns = (turn - base) % rep;
-        self.simulate(base + rem_turns);
-        let rem_height = self.max_height;
 
-        println!(
-            "Height of {} reps of size {}: {}",
-            full_reps, rep, easy_height
-        );
-        println!(
-            "Height of base {} + remainder of {} % {} = {}: {}",
-            base,
-            (turn - base),
-            rep,
-            rem_turns,
-            rem_height
-        );
+        let mut heights: Vec<i64> = Vec::new();
+        let states = std::iter::from_fn(|| {
+            if heights.len() > turns {
+                return None;
+            }
+            if !heights.is_empty() {
+                self.drop_next();
+            }
+            heights.push(self.max_height);
+            Some(self.fingerprint())
+        });
 
-        easy_height + rem_height
+        match find_cycle(states) {
+            Some(cycle) => cycle.extrapolate(&heights, turns),
+            None => heights[turns],
+        }
     }
 
     fn tick_once(&self, tetro: &mut Tetromino) -> bool {
@@ -233,96 +243,6 @@ impl StoneTetris {
         }
     }
 
-    fn simulate(&mut self, ticks: usize) -> Vec<(TetrominoShape, (usize, usize))> {
-        let mut turn_steam: Vec<(TetrominoShape, (usize, usize))> = Vec::new();
-        for _ in 0..ticks {
-            let dropped_tetromino = TETROMINO_ORDER[self.tetromino_id];
-            let steam_range = self.drop_next();
-            turn_steam.push((dropped_tetromino, steam_range));
-        }
-
-        turn_steam
-    }
-
-    fn find_repetition(&mut self, print_results: bool) -> (usize, usize, i64) {
-        let mut directional_patterns: Vec<(TetrominoShape, (usize, usize))> = Vec::new();
-        let mut heights: Vec<i64> = Vec::new();
-
-        let mut turn_found: usize = 0;
-        let mut repetition_start_found: bool = false;
-
-        for _ in 0..2000 {
-            let dropped_tetromino = TETROMINO_ORDER[self.tetromino_id];
-            let curr_checked_pattern = (dropped_tetromino, self.drop_next());
-            directional_patterns.push(curr_checked_pattern);
-
-            heights.push(self.max_height);
-        }
-
-        let mut turn: usize = 1999;
-        while !repetition_start_found {
-            let dropped_tetromino = TETROMINO_ORDER[self.tetromino_id];
-            let curr_checked_pattern = (dropped_tetromino, self.drop_next());
-
-            heights.push(self.max_height);
-            for (id, pattern) in directional_patterns.iter().enumerate().rev() {
-                if *pattern == curr_checked_pattern {
-                    let mut validation_tetris = self.validation_copy();
-                    validation_tetris.simulate(id);
-                    let base_height = validation_tetris.max_height;
-
-                    let potential_rep = turn - id;
-                    let mut valid_pattern = true;
-
-                    validation_tetris.simulate(potential_rep);
-
-                    for check_round in 0u32..6 {
-                        validation_tetris.simulate(2usize.pow(check_round) * potential_rep);
-                        let max_height = validation_tetris.max_height;
-                        let mid_height = base_height + ((max_height - base_height) / 2);
-
-                        let base_id = validation_tetris.linear_id(&Point {
-                            x: 0,
-                            y: base_height + 1,
-                        });
-                        let max_first = validation_tetris.linear_id(&Point {
-                            x: 6,
-                            y: mid_height,
-                        });
-                        let max_second = validation_tetris.linear_id(&Point {
-                            x: 6,
-                            y: max_height,
-                        });
-
-                        valid_pattern &= validation_tetris.occupation_grid[base_id..=max_first]
-                            == validation_tetris.occupation_grid[max_first + 1..=max_second];
-                    }
-
-                    if valid_pattern {
-                        repetition_start_found = true;
-                        turn_found = id;
-                    }
-                }
-            }
-            directional_patterns.push(curr_checked_pattern);
-            turn += 1;
-        }
-
-        // Let's verify?
-
-        let repetition_range = turn - turn_found - 1;
-
-        if print_results {
-            self.print_diff(turn_found, repetition_range, repetition_range * 2);
-        }
-
-        (
-            turn_found,
-            repetition_range,
-            heights[turn_found + repetition_range] - heights[turn_found],
-        )
-    }
-
     fn drop_next(&mut self) -> (usize, usize) {
         let mut curr_tetromino = Tetromino::new(self.tetromino_id, self.max_height);
         self.tetromino_id = (self.tetromino_id + 1) % TETROMINO_ORDER.len();
@@ -346,7 +266,9 @@ impl StoneTetris {
 
         for tetro_point in curr_tetromino.world_points() {
             let point_id = self.linear_id(&tetro_point);
-            self.occupation_grid[point_id] = true
+            self.occupation_grid[point_id] = true;
+            let top = &mut self.column_tops[usize::try_from(tetro_point.x).unwrap()];
+            *top = (*top).max(tetro_point.y);
         }
 
         // Need to advance one last time
@@ -355,171 +277,128 @@ impl StoneTetris {
         (start_steam_id, self.steam_id)
     }
 
-    pub fn _print_state(&self) {
-        let mut tetris_lines: Vec<String> = Vec::new();
-        for line in &self.occupation_grid.iter().chunks(7) {
-            let formatted_line = line
-                .map(|occupied| {
-                    if *occupied {
-                        Colour::RGB(255, 184, 108).paint("#").to_string()
-                    } else {
-                        Colour::RGB(248, 248, 242).paint(".").to_string()
-                    }
-                })
-                .join("");
-            tetris_lines.push(formatted_line);
-        }
-
-        let mut y: usize = tetris_lines.len();
-        while let Some(line) = tetris_lines.pop() {
-            y -= 1;
-            if y % 10 == 0 {
-                print!("{:5} ", y);
-            } else {
-                print!("      ");
-            }
-            println!("|{}|", line);
-        }
-        println!("      +-------+");
-    }
-
-    pub fn print_diff(&self, base: usize, first_time: usize, second_time: usize) {
-        println!();
-        println!();
-        let mut diff_tetris = self.validation_copy();
-
-        diff_tetris.simulate(base);
-        let base_height = diff_tetris.max_height;
-        diff_tetris.simulate(first_time);
-        let first_max_height = diff_tetris.max_height;
-        diff_tetris.simulate(second_time - first_time);
-        let second_max_height = diff_tetris.max_height;
-
-        let base_id = diff_tetris.linear_id(&Point {
-            x: 0,
-            y: base_height + 1,
-        });
-        let max_first = diff_tetris.linear_id(&Point {
-            x: 6,
-            y: first_max_height,
-        });
-        let max_second = diff_tetris.linear_id(&Point {
-            x: 6,
-            y: second_max_height,
-        });
-
-        let first_half = &diff_tetris.occupation_grid[base_id..=max_first];
-        let mut first_strings: Vec<String> = Vec::new();
-        for line in &first_half.iter().chunks(7) {
-            let formatted_line = line
-                .map(|occupied| {
-                    if *occupied {
-                        Colour::RGB(255, 184, 108).paint("#").to_string()
-                    } else {
-                        Colour::RGB(248, 248, 242).paint(".").to_string()
-                    }
-                })
-                .join("");
-            first_strings.push(formatted_line);
-        }
+    // The stack of rocks from the top down, painted with terminal colours
+    // when `paint` is set. Only for debugging and the snapshots, the solution
+    // never shows the stack
+    #[allow(dead_code)]
+    fn draw(&self, paint: bool) -> String {
+        let style = |colour: Colour, text: &str| match paint {
+            true => colour.paint(text).to_string(),
+            false => text.to_string(),
+        };
 
-        let second_half = &diff_tetris.occupation_grid[max_first + 1..=max_second];
-        let mut second_strings: Vec<String> = Vec::new();
-        for line in &second_half.iter().chunks(7) {
-            let formatted_line = line
-                .map(|occupied| {
-                    if *occupied {
-                        Colour::RGB(255, 184, 108).paint("#").to_string()
-                    } else {
-                        Colour::RGB(248, 248, 242).paint(".").to_string()
-                    }
+        let mut output = String::new();
+        let lines = self.occupation_grid.chunks(7).enumerate().rev();
+        for (y, line) in lines {
+            let formatted_line: String = line
+                .iter()
+                .map(|occupied| match occupied {
+                    true => style(Colour::RGB(255, 184, 108), "#"),
+                    false => style(Colour::RGB(248, 248, 242), "."),
                 })
-                .join("");
-            second_strings.push(formatted_line);
+                .collect();
+            match y.is_multiple_of(10) {
+                true => output += &format!("{:5} ", y),
+                false => output += "      ",
+            }
+            output += &format!("|{}|\n", formatted_line);
         }
+        output += "      +-------+\n";
 
-        let mut right_chunks: Vec<Vec<bool>> = Vec::new();
-        for chunk in &second_half.iter().chunks(7) {
-            right_chunks.push(chunk.cloned().collect_vec());
-        }
+        output
+    }
 
-        let mut diff_strings: Vec<String> = Vec::new();
-        for left_data in &first_half.iter().chunks(7) {
-            let mut formatted_line: String = String::new();
-            for (left, right) in left_data.zip(right_chunks[diff_strings.len()].iter()) {
-                let next_diff = if left == right {
-                    if *left {
-                        Colour::Cyan.paint("#").to_string()
-                    } else {
-                        ".".to_string()
-                    }
-                } else if *left {
-                    Colour::Green.paint("#").to_string()
-                } else {
-                    Colour::Red.paint("#").to_string()
-                };
-                formatted_line.push_str(&next_diff);
+    // The top `rows` rows of the stack between the walls, the highest first.
+    // The floor shows up once the whole stack fits.
+    fn top_rows(&self, rows: usize) -> Grid<Option<Colour>> {
+        let wall = Some(Colour::RGB(248, 248, 242));
+        let height = usize::try_from(self.max_height + 1).unwrap();
+        let shown = rows.min(height);
+        let floor = usize::from(shown == height);
+
+        let mut grid = Grid::filled(9, shown + floor, None);
+        for row in 0..shown {
+            let y = height - 1 - row;
+            grid[(row, 0)] = wall;
+            grid[(row, 8)] = wall;
+            for x in 0..7 {
+                if self.occupation_grid[y * 7 + x] {
+                    grid[(row, x + 1)] = Some(Colour::RGB(255, 184, 108));
+                }
             }
-            diff_strings.push(formatted_line);
         }
-
-        let max_height = first_strings.len().max(second_strings.len());
-        first_strings.resize(max_height, "       ".to_string());
-        diff_strings.resize(max_height, "       ".to_string());
-        second_strings.resize(max_height, "       ".to_string());
-        for line_id in (0..max_height).rev() {
-            if line_id % 10 == 0 {
-                print!("{:5} ", line_id);
-            } else {
-                print!("      ");
-            }
-            print!("|{}|", first_strings[line_id]);
-            if line_id % 10 == 0 {
-                print!(" ------- ");
-            } else {
-                print!("         ");
-            }
-            print!("|{}|", diff_strings[line_id]);
-            if line_id % 10 == 0 {
-                print!(" ------- ");
-            } else {
-                print!("         ");
+        if floor == 1 {
+            for column in 0..9 {
+                grid[(shown, column)] = wall;
             }
-            println!("|{}|", second_strings[line_id]);
         }
-        println!("      +-------+         +-------+         +-------+");
 
-        println!();
-        println!();
+        grid
     }
 }
 
-pub fn part1(input: &str) {
-    let mut tetris = StoneTetris::new(input);
+// Terminal lines the tower gets, the tighter the resolution the more rows of
+// it fit
+const VIEW_LINES: usize = 40;
+
+// A frame for every rock of the first part, with the top of the tower
+impl Visualize for StoneTetris {
+    fn frames(&self) -> impl Iterator<Item = Frame> {
+        let mut tetris = self.clone();
+        tetris.reset();
+        let resolution = dots::chosen();
+        let (_, down) = resolution.cells_per_character();
+
+        (1..=2022).map(move |rock| {
+            tetris.drop_next();
+            Frame {
+                caption: format!(
+                    "Rock {} comes to rest, the tower is {} high",
+                    rock,
+                    tetris.max_height + 1
+                ),
+                picture: dots::pack(
+                    resolution,
+                    &tetris.top_rows(VIEW_LINES * down),
+                    |cell| *cell,
+                    true,
+                ),
+            }
+        })
+    }
+}
+
+pub fn visualize(input: &str, _kind: InputKind, cast: Option<&Path>) -> Result<(), AocError> {
+    viz::show(&StoneTetris::new(input)?, cast)
+}
+
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let mut tetris = StoneTetris::new(input)?;
     let max_height = tetris.find_max_height(2022);
 
-    println!("Highest rock at: {}", max_height + 1);
+    Ok(Answer::from(max_height + 1))
 }
 
-pub fn part2(input: &str) {
-    let mut tetris = StoneTetris::new(input);
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let mut tetris = StoneTetris::new(input)?;
     let max_height = tetris.find_max_height(1000000000000);
 
-    println!(
-        "Highest rock after an unreasonable amount of time: {}",
-        max_height
-    );
+    Ok(Answer::from(max_height + 1))
 }
 
+aoc_day!(2022, 17, part1, part2; visualize = visualize);
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_lib::snapshot::assert_snapshot;
+    use crate::aoc_lib::viz::dots::Resolution;
 
-    static INPUT_STRING: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+    static INPUT_STRING: &str = include_str!("../../inputs/2022/day17.example.txt");
 
     #[test]
     fn simple_repetition() {
-        let mut tetris = StoneTetris::new(INPUT_STRING);
+        let mut tetris = StoneTetris::new(INPUT_STRING).unwrap();
         let max_height = tetris.find_max_height(1000000000000);
 
         assert_eq!(max_height + 1, 1514285714288);
@@ -527,9 +406,52 @@ mod tests {
 
     #[test]
     fn simple_fall() {
-        let mut tetris = StoneTetris::new(INPUT_STRING);
+        let mut tetris = StoneTetris::new(INPUT_STRING).unwrap();
         let max_height = tetris.find_max_height(2022);
 
         assert_eq!(max_height + 1, 3068);
     }
+
+    #[test]
+    fn stack_render() {
+        let mut tetris = StoneTetris::new(INPUT_STRING).unwrap();
+        for _ in 0..10 {
+            tetris.drop_next();
+        }
+
+        assert_snapshot("2022_day17_ten_rocks", &tetris.draw(false));
+    }
+
+    #[test]
+    fn packed_tower() {
+        let mut tetris = StoneTetris::new(INPUT_STRING).unwrap();
+        tetris.drop_next();
+        let one_rock = tetris.top_rows(VIEW_LINES);
+
+        // The rock on top of the floor, in another colour than the walls
+        assert_eq!(
+            dots::pack(Resolution::HalfBlocks, &one_rock, |cell| *cell, false),
+            "█▄▄▀▀▀▀▄█\n"
+        );
+
+        for _ in 1..2022 {
+            tetris.drop_next();
+        }
+        // Only the top of the tower, without the floor
+        let top = dots::pack(Resolution::Braille, &tetris.top_rows(160), |cell| *cell, false);
+        assert_eq!(top.lines().count(), 40);
+        assert!(top.lines().all(|line| line.chars().count() == 5));
+
+        let frames: Vec<Frame> = tetris.frames().take(3).collect();
+        assert_eq!(frames[2].caption, "Rock 3 comes to rest, the tower is 6 high");
+    }
+
+    #[test]
+    fn broken_jets() {
+        assert!(StoneTetris::new("").is_err());
+        assert_eq!(
+            StoneTetris::new(">><x>").err(),
+            Some(String::from("Unknown jet direction 'x'"))
+        );
+    }
 }
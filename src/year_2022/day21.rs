@@ -0,0 +1,311 @@
+use hashbrown::HashMap;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::{alpha1, i64 as signed, one_of, space1};
+use nom::combinator::{all_consuming, map};
+use nom::sequence::{separated_pair, tuple};
+use nom::IResult;
+
+use crate::aoc_lib::prelude::*;
+
+const ROOT: &str = "root";
+const HUMAN: &str = "humn";
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Operator {
+    fn apply(self, left: i64, right: i64) -> Result<i64, String> {
+        match self {
+            Operator::Add => Ok(left + right),
+            Operator::Sub => Ok(left - right),
+            Operator::Mul => Ok(left * right),
+            Operator::Div if right == 0 => Err(format!("Division of {} by zero", left)),
+            Operator::Div if left % right != 0 => {
+                Err(format!("{} is not a multiple of {}", left, right))
+            }
+            Operator::Div => Ok(left / right),
+        }
+    }
+}
+
+// What a monkey yells, the monkeys it waits for are named in the input and
+// linked by their id in the arena once every monkey is known
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum Job<M> {
+    Number(i64),
+    Operation(M, Operator, M),
+}
+
+fn job(input: &str) -> IResult<&str, Job<&str>> {
+    alt((
+        map(signed, Job::Number),
+        map(
+            tuple((alpha1, space1, one_of("+-*/"), space1, alpha1)),
+            |(left, _, operator, _, right)| {
+                let operator = match operator {
+                    '+' => Operator::Add,
+                    '-' => Operator::Sub,
+                    '*' => Operator::Mul,
+                    _ => Operator::Div,
+                };
+                Job::Operation(left, operator, right)
+            },
+        ),
+    ))(input)
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Monkey<'a> {
+    name: &'a str,
+    job: Job<Id>,
+}
+
+// Every monkey in a single arena, so the expression tree is walked by index
+// instead of looking every name up again
+struct Troop<'a> {
+    monkeys: Arena<Monkey<'a>>,
+    ids: HashMap<&'a str, Id>,
+}
+
+impl Troop<'_> {
+    fn id(&self, name: &str) -> Result<Id, String> {
+        self.ids
+            .get(name)
+            .copied()
+            .ok_or_else(|| format!("Nobody is called \"{}\"", name))
+    }
+
+    fn job(&self, monkey: Id) -> &Job<Id> {
+        &self.monkeys[monkey].job
+    }
+
+    fn is_human(&self, monkey: Id) -> bool {
+        self.monkeys[monkey].name == HUMAN
+    }
+}
+
+fn parse_monkeys(input: &str) -> Result<Troop<'_>, String> {
+    let named = input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            all_consuming(separated_pair(alpha1, tag(": "), job))(line)
+                .map(|(_, monkey)| monkey)
+                .map_err(|_| format!("Invalid monkey: \"{}\"", line))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    // Everybody gets an id first, the operations can only be linked after
+    let mut troop = Troop {
+        monkeys: Arena::with_capacity(named.len()),
+        ids: HashMap::with_capacity(named.len()),
+    };
+    for (name, _) in &named {
+        let id = troop.monkeys.alloc(Monkey {
+            name,
+            job: Job::Number(0),
+        });
+        troop.ids.insert(name, id);
+    }
+    for (name, job) in named {
+        let linked = match job {
+            Job::Number(value) => Job::Number(value),
+            Job::Operation(left, operator, right) => {
+                Job::Operation(troop.id(left)?, operator, troop.id(right)?)
+            }
+        };
+        let id = troop.ids[name];
+        troop.monkeys[id].job = linked;
+    }
+
+    Ok(troop)
+}
+
+fn evaluate(troop: &Troop, monkey: Id) -> Result<i64, String> {
+    match troop.job(monkey) {
+        Job::Number(value) => Ok(*value),
+        Job::Operation(left, operator, right) => {
+            operator.apply(evaluate(troop, *left)?, evaluate(troop, *right)?)
+        }
+    }
+}
+
+fn depends_on_human(troop: &Troop, monkey: Id) -> bool {
+    if troop.is_human(monkey) {
+        return true;
+    }
+
+    match troop.job(monkey) {
+        Job::Number(_) => false,
+        Job::Operation(left, _, right) => {
+            depends_on_human(troop, *left) || depends_on_human(troop, *right)
+        }
+    }
+}
+
+// Walks down from `monkey`, which has to yield `target`, to the human. At every
+// operation one side doesn't depend on the human and can be evaluated, so the
+// operation is inverted to get what the other side has to yield.
+fn solve_for_human(troop: &Troop, monkey: Id, target: i64) -> Result<i64, String> {
+    if troop.is_human(monkey) {
+        return Ok(target);
+    }
+
+    let name = troop.monkeys[monkey].name;
+    let Job::Operation(left, operator, right) = *troop.job(monkey) else {
+        return Err(format!("\"{}\" doesn't depend on the human", name));
+    };
+    let (unknown, known, unknown_on_left) = match (
+        depends_on_human(troop, left),
+        depends_on_human(troop, right),
+    ) {
+        (true, false) => (left, evaluate(troop, right)?, true),
+        (false, true) => (right, evaluate(troop, left)?, false),
+        (true, true) => return Err(format!("Both sides of \"{}\" use the human", name)),
+        (false, false) => return Err(format!("\"{}\" doesn't depend on the human", name)),
+    };
+
+    let next_target = match (operator, unknown_on_left) {
+        // x + k = t, k + x = t
+        (Operator::Add, _) => Operator::Sub.apply(target, known)?,
+        // x * k = t, k * x = t
+        (Operator::Mul, _) => Operator::Div.apply(target, known)?,
+        // x - k = t
+        (Operator::Sub, true) => Operator::Add.apply(target, known)?,
+        // k - x = t
+        (Operator::Sub, false) => Operator::Sub.apply(known, target)?,
+        // x / k = t
+        (Operator::Div, true) => Operator::Mul.apply(target, known)?,
+        // k / x = t
+        (Operator::Div, false) => Operator::Div.apply(known, target)?,
+    };
+
+    solve_for_human(troop, unknown, next_target)
+}
+
+// The root checks for equality, so the side with the human has to yield what
+// the other side does
+fn human_number(troop: &Troop) -> Result<i64, String> {
+    let Job::Operation(left, _, right) = *troop.job(troop.id(ROOT)?) else {
+        return Err(String::from("The root monkey has to compare two monkeys"));
+    };
+
+    if depends_on_human(troop, left) {
+        solve_for_human(troop, left, evaluate(troop, right)?)
+    } else {
+        solve_for_human(troop, right, evaluate(troop, left)?)
+    }
+}
+
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let troop = parse_monkeys(input)?;
+    let root = troop.id(ROOT)?;
+
+    Ok(Answer::from(
+        evaluate(&troop, root).map_err(AocError::Unsolvable)?,
+    ))
+}
+
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let troop = parse_monkeys(input)?;
+    troop.id(ROOT)?;
+    troop.id(HUMAN)?;
+
+    Ok(Answer::from(
+        human_number(&troop).map_err(AocError::Unsolvable)?,
+    ))
+}
+
+aoc_day!(2022, 21, part1, part2);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT_STRING: &str = include_str!("../../inputs/2022/day21.example.txt");
+
+    #[test]
+    fn parse() {
+        let troop = parse_monkeys(INPUT_STRING).unwrap();
+        let id = |name| troop.id(name).unwrap();
+
+        assert_eq!(troop.monkeys.len(), 15);
+        assert_eq!(troop.job(id("dbpl")), &Job::Number(5));
+        assert_eq!(
+            troop.job(id("ptdq")),
+            &Job::Operation(id("humn"), Operator::Sub, id("dvpt"))
+        );
+        assert!(parse_monkeys("root: pppw % sjmn").is_err());
+        assert_eq!(
+            parse_monkeys("root: pppw + sjmn\npppw: 1").err(),
+            Some(String::from("Nobody is called \"sjmn\""))
+        );
+    }
+
+    #[test]
+    fn root_number() {
+        let troop = parse_monkeys(INPUT_STRING).unwrap();
+
+        assert_eq!(evaluate(&troop, troop.id(ROOT).unwrap()), Ok(152));
+    }
+
+    #[test]
+    fn human_yell() {
+        let mut troop = parse_monkeys(INPUT_STRING).unwrap();
+        let answer = human_number(&troop).unwrap();
+        assert_eq!(answer, 301);
+
+        // Both sides of the root really are equal with that number
+        let human = troop.id(HUMAN).unwrap();
+        troop.monkeys[human].job = Job::Number(answer);
+        let Job::Operation(left, _, right) = *troop.job(troop.id(ROOT).unwrap()) else {
+            unreachable!()
+        };
+        assert_eq!(evaluate(&troop, left), evaluate(&troop, right));
+    }
+
+    #[test]
+    fn every_inversion() {
+        // The human on both sides of every operator
+        for (expression, expected) in [
+            ("humn + four", 6),
+            ("four + humn", 6),
+            ("humn - four", 14),
+            ("four - humn", -6),
+            ("humn * five", 2),
+            ("humn / four", 40),
+            ("forty / humn", 4),
+        ] {
+            let input = format!(
+                "root: left + ten\nleft: {}\nten: 10\nfour: 4\nfive: 5\nforty: 40\nhumn: 0",
+                expression
+            );
+            let troop = parse_monkeys(&input).unwrap();
+            assert_eq!(human_number(&troop), Ok(expected), "{}", expression);
+        }
+    }
+
+    #[test]
+    fn unsolvable() {
+        let troop =
+            parse_monkeys("root: left + one\nleft: humn + humn\none: 1\nhumn: 1").unwrap();
+        assert!(human_number(&troop).is_err());
+
+        let troop =
+            parse_monkeys("root: left + ten\nleft: humn * four\nten: 10\nfour: 4\nhumn: 1")
+                .unwrap();
+        assert!(human_number(&troop).is_err());
+
+        let twice = "root: left + one\nleft: humn + humn\none: 1\nhumn: 1";
+        assert!(matches!(part2(twice), Err(AocError::Unsolvable(_))));
+        // Without a human there is nobody to ask
+        assert!(matches!(part2("root: one + one\none: 1"), Err(AocError::Parse(_))));
+    }
+}
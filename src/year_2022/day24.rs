@@ -0,0 +1,194 @@
+use itertools::Itertools;
+use num::integer::lcm;
+
+use crate::aoc_lib::prelude::*;
+
+type Point = (i32, i32);
+
+const MOVES: [Point; 5] = [(0, 0), (0, 1), (1, 0), (0, -1), (-1, 0)];
+
+// Only the inside of the walls is kept, with (0, 0) as the top left free cell,
+// so the entrance is at row -1 and the exit at row `height`
+struct Basin {
+    blizzards: Vec<Vec<char>>,
+    width: i32,
+    height: i32,
+    start: Point,
+    goal: Point,
+    // Every blizzard is back where it started after this many minutes
+    period: i32,
+}
+
+impl Basin {
+    // Instead of moving the blizzards around, look back at where the ones that
+    // could be in the cell at `time` started from
+    fn is_free(&self, (row, col): Point, time: i32) -> bool {
+        if (row, col) == self.start || (row, col) == self.goal {
+            return true;
+        }
+        if !(0..self.height).contains(&row) || !(0..self.width).contains(&col) {
+            return false;
+        }
+
+        let at = |row: i32, col: i32| {
+            self.blizzards[row.rem_euclid(self.height) as usize]
+                [col.rem_euclid(self.width) as usize]
+        };
+        at(row, col - time) != '>'
+            && at(row, col + time) != '<'
+            && at(row - time, col) != 'v'
+            && at(row + time, col) != '^'
+    }
+
+    // Minutes needed to get from start to target leaving at `time`. The search
+    // runs on (position, time in the period) since the blizzards repeat.
+    fn crossing(&self, start: Point, target: Point, time: i32) -> Option<i32> {
+        let neighbours = |(position, time): &(Point, i32)| {
+            let next_time = (time + 1) % self.period;
+            MOVES
+                .iter()
+                .map(move |(dr, dc)| (position.0 + dr, position.1 + dc))
+                .filter(move |next| self.is_free(*next, next_time))
+                .map(move |next| (next, next_time))
+                .collect_vec()
+        };
+
+        bfs((start, time % self.period), neighbours, |(position, _)| {
+            *position == target
+        })
+        .map(|(steps, _)| steps as i32)
+    }
+}
+
+fn parse_basin(input: &str) -> Result<Basin, String> {
+    let lines = input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect_vec();
+    if lines.len() < 3 {
+        return Err(String::from(
+            "The basin needs at least one row inside its walls",
+        ));
+    }
+
+    let blizzards = lines[1..lines.len() - 1]
+        .iter()
+        .map(|line| {
+            let inside = line
+                .strip_prefix('#')
+                .and_then(|l| l.strip_suffix('#'))
+                .ok_or_else(|| format!("Missing walls in \"{}\"", line))?;
+            match inside.chars().find(|c| !".<>^v".contains(*c)) {
+                Some(c) => Err(format!("Unexpected '{}' in \"{}\"", c, line)),
+                None => Ok(inside.chars().collect_vec()),
+            }
+        })
+        .collect::<Result<Vec<Vec<char>>, String>>()?;
+
+    let width = blizzards[0].len() as i32;
+    let height = blizzards.len() as i32;
+    if blizzards.iter().any(|row| row.len() as i32 != width) {
+        return Err(String::from("All the rows must be as wide"));
+    }
+    if width == 0 {
+        return Err(String::from("The basin needs at least one column inside its walls"));
+    }
+
+    let opening = |line: &str| -> Result<i32, String> {
+        if line.chars().count() as i32 != width + 2 || line.chars().any(|c| !"#.".contains(c)) {
+            return Err(format!("Expected a wall as wide as the basin in \"{}\"", line));
+        }
+        match line.chars().positions(|c| c == '.').collect_vec()[..] {
+            [column] => Ok(column as i32 - 1),
+            _ => Err(format!("Expected a single opening in \"{}\"", line)),
+        }
+    };
+    let start_column = opening(lines[0])?;
+    let goal_column = opening(lines[lines.len() - 1])?;
+
+    Ok(Basin {
+        blizzards,
+        width,
+        height,
+        start: (-1, start_column),
+        goal: (height, goal_column),
+        period: lcm(width, height),
+    })
+}
+
+// The minutes to get from `from` to `to`, leaving at `time`
+fn cross(basin: &Basin, from: Point, to: Point, time: i32) -> Result<i32, AocError> {
+    basin
+        .crossing(from, to, time)
+        .ok_or_else(|| AocError::Unsolvable(String::from("the blizzards never let anyone across")))
+}
+
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    let basin = parse_basin(input)?;
+    let minutes = cross(&basin, basin.start, basin.goal, 0)?;
+
+    Ok(Answer::from(minutes))
+}
+
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    let basin = parse_basin(input)?;
+    let there = cross(&basin, basin.start, basin.goal, 0)?;
+    let back = there + cross(&basin, basin.goal, basin.start, there)?;
+    let again = back + cross(&basin, basin.start, basin.goal, back)?;
+
+    Ok(Answer::from(again))
+}
+
+aoc_day!(2022, 24, part1, part2);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT_STRING: &str = include_str!("../../inputs/2022/day24.example.txt");
+
+    #[test]
+    fn parse() {
+        let basin = parse_basin(INPUT_STRING).unwrap();
+
+        assert_eq!((basin.width, basin.height), (6, 4));
+        assert_eq!(basin.start, (-1, 0));
+        assert_eq!(basin.goal, (4, 5));
+        assert_eq!(basin.period, 12);
+        assert!(parse_basin("#.##\n#.x#\n##.#").is_err());
+        assert!(parse_basin("#..#\n#..#\n##.#").is_err());
+        // Walls and nothing in between
+        assert!(parse_basin("#.#\n##\n#.#").is_err());
+    }
+
+    #[test]
+    fn blizzard_movement() {
+        let basin =
+            parse_basin("#.#####\n#.....#\n#.>...#\n#.....#\n#...v.#\n#.....#\n#####.#").unwrap();
+
+        assert!(!basin.is_free((1, 1), 0));
+        assert!(basin.is_free((1, 1), 1));
+        assert!(!basin.is_free((1, 4), 3));
+        // Wraps around to the other side
+        assert!(!basin.is_free((1, 0), 4));
+        assert!(!basin.is_free((0, 3), 2));
+        assert!(!basin.is_free((3, 3), basin.period));
+        assert!(!basin.is_free((-1, 1), 0));
+    }
+
+    #[test]
+    fn single_trip() {
+        let basin = parse_basin(INPUT_STRING).unwrap();
+
+        assert_eq!(basin.crossing(basin.start, basin.goal, 0), Some(18));
+    }
+
+    #[test]
+    fn forgotten_snacks() {
+        let basin = parse_basin(INPUT_STRING).unwrap();
+
+        assert_eq!(basin.crossing(basin.goal, basin.start, 18), Some(23));
+        assert_eq!(basin.crossing(basin.start, basin.goal, 41), Some(13));
+    }
+}
@@ -0,0 +1,57 @@
+use crate::aoc_lib::numbers::BalancedBase;
+use crate::aoc_lib::prelude::*;
+
+// SNAFU is balanced base five with '=' for -2 and '-' for -1
+const SNAFU_DIGITS: &str = "=-012";
+
+fn fuel_sum(input: &str) -> Result<String, String> {
+    let snafu = BalancedBase::new(SNAFU_DIGITS);
+
+    let total = input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .try_fold(0i64, |total, line| {
+            total
+                .checked_add(snafu.decode(line)?)
+                .ok_or_else(|| String::from("The fuel requirements don't fit in 64 bits"))
+        })?;
+
+    Ok(snafu.encode(total))
+}
+
+pub fn only_part(input: &str) -> Result<Answer, AocError> {
+    Ok(Answer::from(fuel_sum(input)?))
+}
+
+aoc_day!(2022, 25, only_part);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT_STRING: &str = include_str!("../../inputs/2022/day25.example.txt");
+
+    #[test]
+    fn snafu_numbers() {
+        let snafu = BalancedBase::new(SNAFU_DIGITS);
+
+        for (decimal, text) in [
+            (1, "1"),
+            (3, "1="),
+            (8, "2="),
+            (2022, "1=11-2"),
+            (12345, "1-0---0"),
+            (314159265, "1121-1110-1=0"),
+        ] {
+            assert_eq!(snafu.encode(decimal), text);
+            assert_eq!(snafu.decode(text), Ok(decimal));
+        }
+    }
+
+    #[test]
+    fn requirements_sum() {
+        assert_eq!(fuel_sum(INPUT_STRING), Ok(String::from("2=-1=0")));
+        assert!(fuel_sum("1=\n3").is_err());
+    }
+}
@@ -1,6 +1,8 @@
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
 
+use crate::aoc_lib::prelude::*;
+
 #[derive(PartialEq, Eq)]
 struct CalibrationDigit {
     value: u8,
@@ -19,13 +21,16 @@ impl PartialOrd for CalibrationDigit {
     }
 }
 
-fn find_calibration(data: &str, spelled_support: bool) -> u64 {
+fn find_calibration(data: &str, spelled_support: bool) -> Result<u64, String> {
     let char_numbers = ["1", "2", "3", "4", "5", "6", "7", "8", "9"];
     let spelled_numbers = [
         "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
     ];
     let mut final_calibration = 0u64;
-    for entry in data.lines() {
+    for (line_id, entry) in data.lines().enumerate() {
+        if entry.trim().is_empty() {
+            continue;
+        }
         let mut line_data = BinaryHeap::new();
 
         for (value, character) in char_numbers.iter().enumerate() {
@@ -61,47 +66,37 @@ fn find_calibration(data: &str, spelled_support: bool) -> u64 {
         }
 
         let final_data = line_data.into_sorted_vec();
+        let (Some(first), Some(last)) = (final_data.first(), final_data.last()) else {
+            return Err(format!("No digits on line {}: \"{}\"", line_id + 1, entry.trim()));
+        };
 
-        final_calibration += u64::from(
-            final_data
-                .first()
-                .and_then(|entry_data| Some(entry_data.value))
-                .unwrap()
-                * 10
-                + final_data
-                    .last()
-                    .and_then(|entry_data| Some(entry_data.value))
-                    .unwrap(),
-        );
+        final_calibration += u64::from(first.value * 10 + last.value);
     }
 
-    return final_calibration;
+    Ok(final_calibration)
 }
 
-pub fn part1(input: &str) {
-    let calibration_value = find_calibration(input, false);
-    println!("Final calibration value: {}", calibration_value);
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    Ok(Answer::from(find_calibration(input, false)?))
 }
 
-pub fn part2(input: &str) {
-    let calibration_value = find_calibration(input, true);
-    println!("Final calibration value: {}", calibration_value);
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    Ok(Answer::from(find_calibration(input, true)?))
 }
 
+aoc_day!(2023, 1, part1, part2);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn simple_calibration() {
-        let input_string = "1abc2
-        pqr3stu8vwx
-        a1b2c3d4e5f
-        treb7uchet";
+        let input_string = include_str!("../../inputs/2023/day01.example.txt");
 
         let calibration = find_calibration(input_string, false);
 
-        assert_eq!(calibration, 142u64);
+        assert_eq!(calibration, Ok(142u64));
     }
 
     #[test]
@@ -116,6 +111,17 @@ mod tests {
 
         let calibration = find_calibration(input_string, true);
 
-        assert_eq!(calibration, 281u64);
+        assert_eq!(calibration, Ok(281u64));
+    }
+
+    #[test]
+    fn lines_without_digits() {
+        assert_eq!(
+            find_calibration("1abc2\ntrebuchet", false),
+            Err(String::from("No digits on line 2: \"trebuchet\""))
+        );
+        // Only spelled out, so fine with the spelled digits
+        assert_eq!(find_calibration("1abc2\nthree", true), Ok(45));
+        assert_eq!(find_calibration("", false), Ok(0));
     }
 }
@@ -1,38 +1,36 @@
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::character::complete::{digit1, space0};
-use nom::combinator::map_res;
+use nom::combinator::{all_consuming, map_res, value};
 use nom::multi::separated_list1;
-use nom::sequence::{preceded, separated_pair};
+use nom::sequence::{preceded, separated_pair, terminated};
 use nom::IResult;
 
+use crate::aoc_lib::prelude::*;
+
+#[derive(Clone)]
 enum GemAmount {
     Red(u8),
     Green(u8),
     Blue(u8),
 }
 
+// Wider than a single amount, a round can name the same color many times
 struct GameScore {
-    red: u8,
-    green: u8,
-    blue: u8,
+    red: u32,
+    green: u32,
+    blue: u32,
 }
 
 fn gem_entry(input: &str) -> IResult<&str, GemAmount> {
-    let (rem_input, (amount, color)) = separated_pair(
-        map_res(digit1, |s: &str| s.parse::<u8>()),
-        space0,
-        alt((tag("red"), tag("green"), tag("blue"))),
-    )(input)?;
-
-    let amount_out = match color {
-        "red" => GemAmount::Red(amount),
-        "green" => GemAmount::Green(amount),
-        "blue" => GemAmount::Blue(amount),
-        _ => panic!("We should not have gotten this strange color: {}", color),
-    };
-
-    Ok((rem_input, amount_out))
+    let (rem_input, amount) =
+        terminated(map_res(digit1, |s: &str| s.parse::<u8>()), space0)(input)?;
+
+    alt((
+        value(GemAmount::Red(amount), tag("red")),
+        value(GemAmount::Green(amount), tag("green")),
+        value(GemAmount::Blue(amount), tag("blue")),
+    ))(rem_input)
 }
 
 fn round_result(input: &str) -> IResult<&str, GameScore> {
@@ -45,9 +43,9 @@ fn round_result(input: &str) -> IResult<&str, GameScore> {
     };
     for amount in gem_amount {
         match amount {
-            GemAmount::Red(qty) => round_scores.red += qty,
-            GemAmount::Green(qty) => round_scores.green += qty,
-            GemAmount::Blue(qty) => round_scores.blue += qty,
+            GemAmount::Red(qty) => round_scores.red += u32::from(qty),
+            GemAmount::Green(qty) => round_scores.green += u32::from(qty),
+            GemAmount::Blue(qty) => round_scores.blue += u32::from(qty),
         }
     }
 
@@ -61,6 +59,7 @@ fn game_max(input: &str) -> IResult<&str, (u32, GameScore)> {
         separated_list1(tag(";"), preceded(space0, round_result)),
     )(input)?;
 
+    // At least one round, or separated_list1 would have failed
     let final_score = scores
         .into_iter()
         .reduce(|acc, game| GameScore {
@@ -73,64 +72,85 @@ fn game_max(input: &str) -> IResult<&str, (u32, GameScore)> {
     Ok((rem_input, (game_id, final_score)))
 }
 
-fn check_max(input: &str) -> u32 {
+fn parse_games(input: &str) -> Result<Vec<(u32, GameScore)>, String> {
     input
         .lines()
-        .map(|game| game_max(game.trim()).unwrap().1)
-        .fold(0u32, |acc, (game_id, result)| {
-            if result.red > 12 || result.green > 13 || result.blue > 14 {
-                acc
-            } else {
-                acc + game_id
-            }
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|game| {
+            all_consuming(game_max)(game)
+                .map(|(_, result)| result)
+                .map_err(|_| format!("\"{}\" is not a game", game))
         })
+        .collect()
 }
 
-fn check_power(input: &str) -> u64 {
-    input
-        .lines()
-        .map(|game| game_max(game.trim()).unwrap().1 .1)
-        .map(|maximum| u64::from(maximum.red) * u64::from(maximum.green) * u64::from(maximum.blue))
-        .sum()
+fn check_max(input: &str) -> Result<u32, String> {
+    let games = parse_games(input)?;
+
+    Ok(games
+        .iter()
+        .filter(|(_, result)| result.red <= 12 && result.green <= 13 && result.blue <= 14)
+        .map(|(game_id, _)| game_id)
+        .sum())
 }
 
-pub fn part1(input: &str) {
-    let id_sum = check_max(input);
-    println!("Final id sum: {}", id_sum);
+fn check_power(input: &str) -> Result<u64, String> {
+    let games = parse_games(input)?;
+
+    Ok(games
+        .iter()
+        .map(|(_, maximum)| {
+            u64::from(maximum.red) * u64::from(maximum.green) * u64::from(maximum.blue)
+        })
+        .sum())
 }
 
-pub fn part2(input: &str) {
-    let id_sum = check_power(input);
-    println!("Final id sum: {}", id_sum);
+pub fn part1(input: &str) -> Result<Answer, AocError> {
+    Ok(Answer::from(check_max(input)?))
 }
 
+pub fn part2(input: &str) -> Result<Answer, AocError> {
+    Ok(Answer::from(check_power(input)?))
+}
+
+aoc_day!(2023, 2, part1, part2);
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_lib::random::fuzz;
 
     #[test]
     fn simple_max() {
-        let input_string = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-        Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-        Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-        Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-        Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+        let input_string = include_str!("../../inputs/2023/day02.example.txt");
 
-        let id_sum = check_max(input_string);
+        let id_sum = check_max(input_string).unwrap();
 
         assert_eq!(id_sum, 8u32);
     }
 
     #[test]
     fn simple_power() {
-        let input_string = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-        Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-        Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-        Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-        Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+        let input_string = include_str!("../../inputs/2023/day02.example.txt");
 
-        let power = check_power(input_string);
+        let power = check_power(input_string).unwrap();
 
         assert_eq!(power, 2286u64);
     }
+
+    #[test]
+    fn broken_games() {
+        assert!(check_max("Game 1: 3 purple").is_err());
+        assert!(check_max("Game 1: 300 red").is_err());
+        assert!(check_power("Game 1:").is_err());
+        assert!(check_power("Game 1: 3 red; 2 blue trailing").is_err());
+    }
+
+    #[test]
+    fn random_text() {
+        fuzz(0x2023_0200, 2000, "Game 0123456789:;, redgrenblu\n", |input| {
+            let _ = (check_max(input), check_power(input));
+        });
+    }
 }
@@ -0,0 +1,106 @@
+// Helpers shared by the suites that drive the solver binary, not every suite
+// uses all of them
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use advent_of_code::aoc_lib::Answer;
+
+pub fn solver() -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_advent_of_code"));
+    command.current_dir(env!("CARGO_MANIFEST_DIR"));
+    command
+}
+
+// Year, day and alternative solvers of everything in the registry, as listed
+// by the binary
+pub fn listed_days() -> Vec<(u16, u8, Vec<String>)> {
+    let output = solver().arg("list").output().expect("Can't run the solver");
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| {
+            let mut fields = line.split_whitespace();
+            let year = fields.next().and_then(|y| y.parse().ok());
+            let day = fields.next().and_then(|d| d.parse().ok());
+            let (year, day) = year
+                .zip(day)
+                .unwrap_or_else(|| panic!("\"{}\" is not a day", line));
+            (year, day, fields.map(String::from).collect())
+        })
+        .collect()
+}
+
+pub fn solved_days() -> Vec<(u16, u8)> {
+    listed_days()
+        .into_iter()
+        .map(|(year, day, _)| (year, day))
+        .collect()
+}
+
+pub fn input_exists(year: u16, file: &str) -> bool {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("inputs")
+        .join(year.to_string())
+        .join(file)
+        .is_file()
+}
+
+// The lines of inputs/YEAR/`file` that should show up in the output, if the
+// file is there
+pub fn expected_answers(year: u16, file: &str) -> Option<Vec<String>> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("inputs")
+        .join(year.to_string())
+        .join(file);
+    let expected = fs::read_to_string(path).ok()?;
+
+    Some(
+        expected
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(String::from)
+            .collect(),
+    )
+}
+
+// Everything a day prints, `extra` goes straight to the command line
+pub fn run(year: u16, day: u8, extra: &[&str]) -> String {
+    let output = solver()
+        .args([day.to_string(), "-y".into(), year.to_string()])
+        .args(extra)
+        .output()
+        .expect("Can't run the solver");
+
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+// Whether a printed line gives the accepted `answer`, compared as answers. An
+// accepted line with a label also matches its value printed on its own, for
+// the days that answer with a bare number now.
+fn gives(line: &str, answer: &str) -> bool {
+    let line = Answer::from(line);
+    line == answer
+        || answer
+            .rsplit_once(": ")
+            .is_some_and(|(_, value)| line == value)
+}
+
+// The expected answers that are not a line of `printed`
+pub fn missing_from<'a>(printed: &str, expected: &'a [String]) -> Vec<&'a str> {
+    expected
+        .iter()
+        .filter(|answer| !printed.lines().any(|line| gives(line, answer)))
+        .map(String::as_str)
+        .collect()
+}
+
+// Runs a day and returns the answers that didn't get printed
+pub fn missing_answers(year: u16, day: u8, extra: &[&str], expected: &[String]) -> Vec<String> {
+    missing_from(&run(year, day, extra), expected)
+        .into_iter()
+        .map(|answer| format!("{} day {}: \"{}\" not printed", year, day, answer))
+        .collect()
+}
@@ -0,0 +1,68 @@
+// Runs every solver of the solved days twice on the same input and compares
+// what they print, timings aside. Each run is its own process, so both the std
+// and the hashbrown maps get new hash seeds: an answer that depends on the
+// iteration order of a map shows up as two different outputs. The examples are
+// always checked, the real inputs only with AOC_REAL_INPUTS=1.
+mod common;
+
+use std::env;
+
+use rayon::prelude::*;
+
+// Everything but the timings, which change on every run anyway
+fn answers(printed: &str) -> Vec<&str> {
+    printed
+        .lines()
+        .filter(|line| !line.starts_with("Took ") && !line.starts_with("Parsing took "))
+        .collect()
+}
+
+fn differences(year: u16, day: u8, extra: &[&str]) -> Option<String> {
+    let mut args = vec!["--algo", "all"];
+    args.extend(extra);
+
+    let first = common::run(year, day, &args);
+    let second = common::run(year, day, &args);
+    let (first, second) = (answers(&first), answers(&second));
+    if first == second {
+        return None;
+    }
+
+    let line = first
+        .iter()
+        .zip(&second)
+        .position(|(left, right)| left != right)
+        .unwrap_or(first.len().min(second.len()));
+    Some(format!(
+        "{} day {} {}: line {} went from \"{}\" to \"{}\"",
+        year,
+        day,
+        extra.join(" "),
+        line + 1,
+        first.get(line).unwrap_or(&""),
+        second.get(line).unwrap_or(&"")
+    ))
+}
+
+#[test]
+fn same_answers_every_run() {
+    let real_inputs = env::var("AOC_REAL_INPUTS").as_deref() == Ok("1");
+
+    let mut runs: Vec<(u16, u8, Vec<&str>)> = Vec::new();
+    for (year, day) in common::solved_days() {
+        if common::input_exists(year, &format!("day{:02}.example.txt", day)) {
+            runs.push((year, day, vec!["--example"]));
+        }
+        if real_inputs && common::input_exists(year, &format!("day{:02}.txt", day)) {
+            runs.push((year, day, Vec::new()));
+        }
+    }
+
+    let failures: Vec<String> = runs
+        .par_iter()
+        .filter_map(|(year, day, extra)| differences(*year, *day, extra))
+        .collect();
+
+    println!("Ran {} inputs twice", runs.len());
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
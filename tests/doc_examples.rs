@@ -0,0 +1,62 @@
+// The shared modules any day can pick up have an example in the documentation
+// of every public item, run by cargo test as doctests of the library. This
+// makes sure new items come with one too; missing_docs in the modules already
+// asks for the documentation itself.
+use std::fs;
+use std::path::Path;
+
+const DOCUMENTED: &[&str] = &[
+    "grid.rs",
+    "search.rs",
+    "jazz_parser.rs",
+    "numbers.rs",
+    "geometry.rs",
+    "arena.rs",
+];
+
+// Public items, fields aside, whose documentation has no code block
+fn without_examples(source: &str) -> Vec<&str> {
+    let lines: Vec<&str> = source.lines().map(str::trim).collect();
+
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.starts_with("pub ") && !line.ends_with(','))
+        .filter(|(id, _)| {
+            !lines[..*id]
+                .iter()
+                .rev()
+                .skip_while(|line| line.starts_with("#["))
+                .take_while(|line| line.starts_with("///"))
+                .any(|line| line.starts_with("/// ```"))
+        })
+        .map(|(_, line)| *line)
+        .collect()
+}
+
+#[test]
+fn public_items_have_examples() {
+    let modules = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/aoc_lib");
+
+    let mut failures = Vec::new();
+    for module in DOCUMENTED {
+        let source = fs::read_to_string(modules.join(module))
+            .unwrap_or_else(|e| panic!("Can't read {}: {}", module, e));
+        for item in without_examples(&source) {
+            failures.push(format!("{}: {}", module, item));
+        }
+    }
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+fn items_without_examples() {
+    let source = "/// Documented\n///\n/// ```\n/// assert!(true);\n/// ```\n#[derive(Debug)]\n\
+        pub struct Shown {\n    pub field: u8,\n}\n\n/// Only words\npub fn told() {}\n\npub fn bare() {}";
+
+    assert_eq!(
+        without_examples(source),
+        ["pub fn told() {}", "pub fn bare() {}"]
+    );
+}
@@ -0,0 +1,47 @@
+// Runs every solver of the solved days on the examples from the puzzle texts
+// and compares their answers with the ones listed in examples.toml, the same
+// check as the check-examples subcommand.
+use std::fs;
+use std::path::Path;
+
+use advent_of_code::aoc_lib::examples::{self, Example};
+use advent_of_code::registry;
+use rayon::prelude::*;
+
+fn read(path: &Path) -> String {
+    fs::read_to_string(path).unwrap_or_else(|e| panic!("Can't read {}: {}", path.display(), e))
+}
+
+#[test]
+fn published_examples() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let table = examples::parse(&read(&root.join("examples.toml"))).unwrap();
+    let listed: Vec<&Example> = registry()
+        .iter()
+        .flat_map(|year| year.solved())
+        .filter_map(|day| {
+            table
+                .iter()
+                .find(|example| (example.year, example.day) == (day.year, day.day))
+        })
+        .collect();
+
+    let failures: Vec<String> = listed
+        .par_iter()
+        .flat_map(|example| {
+            let path = root
+                .join("inputs")
+                .join(example.year.to_string())
+                .join(&example.input);
+            let checked = examples::check(example, &read(&path)).unwrap();
+            checked
+                .iter()
+                .filter_map(|part| part.failure(example))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    println!("Checked the examples of {} days", listed.len());
+    assert!(!listed.is_empty());
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
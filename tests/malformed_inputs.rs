@@ -0,0 +1,226 @@
+// Runs every solver of every solved day on inputs that can't be right: an empty
+// file, the example cut in the middle of a line, a line of garbage and the
+// example of another day. Each has to come back as an `AocError::Parse` saying
+// what's wrong, instead of a panic, an answer or a run that never ends. The few
+// broken inputs that still make a valid puzzle are in STILL_VALID with why,
+// those only have to be solved or found unsolvable.
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use advent_of_code::aoc_lib::config::InputKind;
+use advent_of_code::aoc_lib::year::Parts;
+use advent_of_code::aoc_lib::{AocError, DayFn};
+use advent_of_code::{find_part, registry};
+use rayon::prelude::*;
+
+use Case::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Case {
+    Empty,
+    Truncated,
+    Garbage,
+    WrongDay,
+}
+
+// Debug builds are slow, this is only there to catch the endless loops
+const TIME_LIMIT: Duration = Duration::from_secs(60);
+
+const STILL_VALID: &[(u16, u8, Case, &str)] = &[
+    (2015, 1, Truncated, "any run of brackets is a route"),
+    (2015, 3, Truncated, "any run of arrows is a route"),
+    (2015, 4, Garbage, "any text is a secret key"),
+    (2015, 5, Truncated, "half a word is still a word to check"),
+    (2019, 1, Truncated, "half a mass is still a mass"),
+    (2020, 1, Truncated, "half an entry is still an entry"),
+    (
+        2020,
+        6,
+        Truncated,
+        "half the answers of a person are still answers",
+    ),
+    (
+        2020,
+        9,
+        WrongDay,
+        "the adapters of day 10 are a list of numbers too",
+    ),
+    (2021, 1, Truncated, "half a depth is still a depth"),
+    (
+        2022,
+        1,
+        Truncated,
+        "half the calories of an item are still calories",
+    ),
+    (2022, 6, Truncated, "any run of letters is a datastream"),
+    (2022, 17, Truncated, "any run of jets is a pattern"),
+    (2022, 25, Truncated, "half a SNAFU number is still one"),
+    (
+        2023,
+        1,
+        Truncated,
+        "any line with a digit has a calibration value",
+    ),
+    (
+        2023,
+        1,
+        WrongDay,
+        "the games of day 2 have digits on every line",
+    ),
+];
+
+// Any text is a secret key to mine with, a long one takes minutes in debug
+const SKIPPED: &[(u16, u8, Case)] = &[(2015, 4, WrongDay)];
+
+fn example(year: u16, day: u8) -> Option<String> {
+    let file = format!("day{:02}.example.txt", day);
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("inputs")
+        .join(year.to_string())
+        .join(file);
+    fs::read_to_string(path).ok()
+}
+
+// The first half of the lines, then half of the next line long enough to cut
+fn truncated(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let long_enough = |id: &usize| lines[*id].chars().count() > 1;
+    let Some(cut) = (lines.len() / 2..lines.len()).find(long_enough) else {
+        return text.chars().take(text.chars().count() / 2).collect();
+    };
+
+    let half = lines[cut].chars().count() / 2;
+    let mut kept: Vec<String> = lines[..cut].iter().map(|line| line.to_string()).collect();
+    kept.push(lines[cut].chars().take(half).collect());
+    kept.join("\n")
+}
+
+// The malformed inputs for every day. The wrong day gets the example of the
+// next day that has one, in the order of the registry.
+fn cases() -> Vec<(u16, u8, Case, String)> {
+    let days: Vec<(u16, u8)> = registry()
+        .iter()
+        .flat_map(|year| year.solved())
+        .map(|entry| (entry.year, entry.day))
+        .collect();
+    let examples: Vec<(u16, u8, String)> = days
+        .iter()
+        .filter_map(|(year, day)| example(*year, *day).map(|text| (*year, *day, text)))
+        .collect();
+
+    let mut cases = Vec::new();
+    for (id, (year, day)) in days.iter().enumerate() {
+        cases.push((*year, *day, Empty, String::new()));
+        cases.push((*year, *day, Garbage, String::from("hello world\n")));
+        if let Some(text) = example(*year, *day) {
+            cases.push((*year, *day, Truncated, truncated(&text)));
+        }
+        let next = days[id + 1..]
+            .iter()
+            .chain(&days[..id])
+            .find_map(|other| examples.iter().find(|(y, d, _)| (*y, *d) == *other));
+        if let Some((_, _, text)) = next {
+            cases.push((*year, *day, WrongDay, text.clone()));
+        }
+    }
+
+    cases
+        .into_iter()
+        .filter(|(year, day, case, _)| !SKIPPED.contains(&(*year, *day, *case)))
+        .collect()
+}
+
+// The parts of the day and of its alternative solvers, by name
+fn solvers(year: u16, day: u8) -> Vec<(String, DayFn)> {
+    let mut solvers: Vec<(String, DayFn)> = (1..=2)
+        .filter_map(|part| Some((format!("part {}", part), find_part(year, day, part).ok()?)))
+        .collect();
+
+    let found = registry()
+        .iter()
+        .find(|entry| entry.year() == year)
+        .unwrap();
+    for variant in found.variants(day) {
+        if let Parts::Solve((part1, part2)) = variant.parts {
+            let parts = [(1, part1), (2, part2)];
+            solvers.extend(parts.into_iter().filter_map(|(part, run)| {
+                Some((format!("\"{}\" part {}", variant.name, part), run?))
+            }));
+        }
+    }
+
+    solvers
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    match (
+        payload.downcast_ref::<&str>(),
+        payload.downcast_ref::<String>(),
+    ) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => String::from("no message"),
+    }
+}
+
+// What's wrong with how `run` took `input`, if anything
+fn failure(run: DayFn, input: &str, valid: bool) -> Option<String> {
+    let (sender, receiver) = mpsc::channel();
+    let input = input.to_string();
+    // A day stuck in a loop is left behind, the test ends with it
+    thread::spawn(move || {
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| run(&input, InputKind::Real)));
+        let _ = sender.send(outcome);
+    });
+
+    let outcome = match receiver.recv_timeout(TIME_LIMIT) {
+        Ok(outcome) => outcome,
+        Err(_) => return Some(String::from("still running after a minute")),
+    };
+    match (outcome, valid) {
+        (Err(payload), _) => Some(format!("panics: {}", panic_message(payload.as_ref()))),
+        (Ok(Err(AocError::Parse(message))), false) if !message.trim().is_empty() => None,
+        (Ok(Err(AocError::Parse(_))), false) => Some(String::from("no message")),
+        (Ok(Ok(_) | Err(AocError::Unsolvable(_))), true) => None,
+        (Ok(Ok(solved)), false) => Some(format!("answers {}", solved.answer)),
+        (Ok(Err(error)), _) => Some(format!("{:?}", error)),
+    }
+}
+
+#[test]
+fn malformed_inputs() {
+    // The panics are reported with the rest
+    panic::set_hook(Box::new(|_| ()));
+
+    // The days waiting on their solvers get their own pool, the solvers that
+    // use rayon would be stuck behind them in the global one
+    let pool = rayon::ThreadPoolBuilder::new().build().unwrap();
+    let cases = cases();
+    let problems: Vec<String> = pool.install(|| {
+        cases
+            .par_iter()
+            .flat_map(|(year, day, case, input)| {
+                let valid = STILL_VALID
+                    .iter()
+                    .any(|(y, d, c, _)| (*y, *d, *c) == (*year, *day, *case));
+                solvers(*year, *day)
+                    .into_iter()
+                    .filter_map(|(name, run)| {
+                        let failure = failure(run, input, valid)?;
+                        Some(format!(
+                            "{} day {} {}, {:?}: {}",
+                            year, day, name, case, failure
+                        ))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    });
+
+    let _ = panic::take_hook();
+    assert!(problems.is_empty(), "\n{}", problems.join("\n"));
+}
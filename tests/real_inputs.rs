@@ -0,0 +1,40 @@
+// Runs the solved days on the personal puzzle inputs and checks them against
+// the answers that were accepted, to catch changes to the shared code that
+// break a day. Inputs and answers are not part of the repository, so this only
+// runs with AOC_REAL_INPUTS=1 and the files in place: inputs/YEAR/dayNN.txt and
+// the accepted answers in inputs/YEAR/dayNN.expected, one per line as printed.
+// Some days are slow in debug, `cargo test --release` helps.
+mod common;
+
+use std::env;
+
+use rayon::prelude::*;
+
+#[test]
+fn accepted_answers() {
+    if env::var("AOC_REAL_INPUTS").as_deref() != Ok("1") {
+        println!("Set AOC_REAL_INPUTS=1 to check the real inputs");
+        return;
+    }
+
+    let days = common::solved_days();
+    let with_answers: Vec<(u16, u8, Vec<String>)> = days
+        .iter()
+        .filter(|&&(year, day)| common::input_exists(year, &format!("day{:02}.txt", day)))
+        .filter_map(|&(year, day)| {
+            let expected = common::expected_answers(year, &format!("day{:02}.expected", day))?;
+            Some((year, day, expected))
+        })
+        .collect();
+    let failures: Vec<String> = with_answers
+        .par_iter()
+        .flat_map(|(year, day, expected)| common::missing_answers(*year, *day, &[], expected))
+        .collect();
+
+    println!(
+        "Checked {} of {} solved days against their accepted answers",
+        with_answers.len(),
+        days.len()
+    );
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
@@ -0,0 +1,194 @@
+// Runs days and the shared helpers on inputs around ten times the size of the
+// official ones, to catch the accidental quadratic blowups a refactor can bring
+// in. Slow, so only when asked for, and in release:
+// cargo test --release --test stress -- --ignored
+// The time limits are generous, and ten times more so in a debug build.
+mod common;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+use advent_of_code::aoc_lib::config::InputKind;
+use advent_of_code::aoc_lib::cycles::find_cycle;
+use advent_of_code::aoc_lib::grid::{Grid, Position};
+use advent_of_code::aoc_lib::intervals::IntervalSet;
+use advent_of_code::aoc_lib::random::Xorshift;
+use advent_of_code::aoc_lib::search::{a_star, bfs, dijkstra};
+use advent_of_code::aoc_lib::testgen;
+
+// Year, day and size of the generated input, see aoc_lib::testgen. The burrows
+// and the volcano are left out, they grow exponentially by design.
+const GENERATED: &[(u16, u8, usize)] = &[
+    (2021, 22, 4200),
+    (2022, 7, 2000),
+    (2022, 14, 1500),
+    (2022, 21, 30000),
+];
+
+// Counts the allocations made by each thread, so a test can tell how hard a
+// day leans on the allocator while the others run next to it
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+fn allocations<T>(run: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = run();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+fn timed<T>(what: &str, seconds: u64, run: impl FnOnce() -> T) -> T {
+    let limit = Duration::from_secs(if cfg!(debug_assertions) {
+        10 * seconds
+    } else {
+        seconds
+    });
+
+    let start = Instant::now();
+    let result = run();
+    let elapsed = start.elapsed();
+
+    println!("{}: {:?}", what, elapsed);
+    assert!(
+        elapsed <= limit,
+        "{} took {:?}, more than {:?}",
+        what,
+        elapsed,
+        limit
+    );
+    result
+}
+
+#[test]
+#[ignore]
+fn generated_inputs() {
+    for (year, day, size) in GENERATED {
+        let what = format!("{} day {} on {} units", year, day, size);
+        let size = size.to_string();
+        let printed = timed(&what, 30, || {
+            let args = ["--generated", &size, "--seed", "1", "--algo", "all"];
+            common::run(*year, *day, &args)
+        });
+
+        // A part that panics never gets to print how long it took
+        let parts = printed.matches("Running Part").count();
+        assert!(parts > 0, "{}: nothing ran", what);
+        assert_eq!(printed.matches("\nTook ").count(), parts, "{}", printed);
+    }
+}
+
+// Random risk levels from 1 to 9, like the cave of 2021 day 15
+fn risk_grid(size: usize) -> Grid<u64> {
+    let mut rng = Xorshift::new(15);
+    let mut grid = Grid::filled(size, size, 0);
+    for position in grid.positions().collect::<Vec<_>>() {
+        grid[position] = rng.range(1..=9) as u64;
+    }
+    grid
+}
+
+#[test]
+#[ignore]
+fn grid_searches() {
+    // A fifth of the cells are walls, the first column and the last row are
+    // kept open so there's always a way across
+    let size = 1000;
+    let mut rng = Xorshift::new(12);
+    let mut open = Grid::filled(size, size, true);
+    for (row, column) in open.positions().collect::<Vec<_>>() {
+        open[(row, column)] = column == 0 || row == size - 1 || rng.below(5) != 0;
+    }
+    let corner = (size - 1, size - 1);
+    let steps = timed("BFS on 1000x1000", 10, || {
+        bfs((0, 0), open.moves(|_, to| *to), |p| *p == corner)
+    });
+    assert!(steps.is_some_and(|(steps, _)| steps >= 2 * (size - 1)));
+
+    let risk = risk_grid(500);
+    let end = (499, 499);
+    let weighted = |p: &Position| -> Vec<(Position, u64)> {
+        risk.neighbours(*p).map(|next| (next, risk[next])).collect()
+    };
+    let plain = timed("Dijkstra on 500x500", 10, || {
+        dijkstra((0, 0), weighted, |p| *p == end)
+    });
+    let guided = timed("A* on 500x500", 10, || {
+        let manhattan = |(row, column): &Position| ((end.0 - row) + (end.1 - column)) as u64;
+        a_star((0, 0), weighted, manhattan, |p| *p == end)
+    });
+    assert_eq!(plain.map(|(cost, _)| cost), guided.map(|(cost, _)| cost));
+}
+
+#[test]
+#[ignore]
+fn interval_sets() {
+    // Mostly disjoint, so the set keeps tens of thousands of ranges
+    let mut rng = Xorshift::new(15);
+    let ranges: Vec<(i64, i64)> = (0..20_000)
+        .map(|_| {
+            let start = rng.range(-10_000_000..=10_000_000);
+            (start, start + rng.range(0..=300))
+        })
+        .collect();
+
+    let set = timed("20000 ranges in an interval set", 10, || {
+        ranges
+            .iter()
+            .map(|(start, end)| *start..=*end)
+            .collect::<IntervalSet>()
+    });
+    let inside = timed("20000 lookups", 10, || {
+        ranges
+            .iter()
+            .filter(|(start, end)| set.contains(*start) && set.contains(*end))
+            .count()
+    });
+    assert_eq!(inside, ranges.len());
+}
+
+#[test]
+#[ignore]
+fn arena_days() {
+    // The directory tree and the monkeys' expression tree keep their nodes in
+    // an arena, so the allocations grow with the logarithm of the nodes and not
+    // with the nodes themselves
+    for (day, size) in [(7, 20_000), (21, 60_000)] {
+        let input = testgen::generate(2022, day, size, 1).unwrap();
+        let what = format!("2022 day {} on {} units", day, size);
+        let (answer, count) = timed(&what, 10, || {
+            allocations(|| advent_of_code::solve(2022, day, 2, &input, InputKind::Real))
+        });
+        assert!(answer.is_ok(), "{}: {:?}", what, answer);
+        assert!(count < 100, "{}: {} allocations", what, count);
+    }
+}
+
+#[test]
+#[ignore]
+fn long_cycles() {
+    // 300000 steps before a loop 700000 long
+    let states = (0usize..).map(|step| match step {
+        0..=999_999 => step,
+        _ => 300_000 + (step - 300_000) % 700_000,
+    });
+
+    let cycle = timed("Cycle of a million states", 10, || find_cycle(states)).unwrap();
+    assert_eq!((cycle.start, cycle.length), (300_000, 700_000));
+}
@@ -0,0 +1,77 @@
+// Runs the days with alternative solvers through every one of them with
+// --algo all on the real inputs, each solver has to print the accepted answers
+// (see real_inputs.rs for where they go). The examples are checked in-process
+// by examples.rs. Needs AOC_REAL_INPUTS=1, interactive solvers are skipped by
+// the binary itself.
+mod common;
+
+use std::env;
+
+use rayon::prelude::*;
+
+// The output of --algo all, split at the header of each solver
+fn solver_outputs(printed: &str) -> Vec<(String, String)> {
+    let mut outputs = vec![(String::from("default"), String::new())];
+    for line in printed.lines() {
+        if line.ends_with("is interactive, skipped") {
+            continue;
+        }
+        match line.strip_prefix("Solver ") {
+            Some(name) => outputs.push((name.trim_matches('"').to_string(), String::new())),
+            None => {
+                let (_, output) = outputs.last_mut().unwrap();
+                output.push_str(line);
+                output.push('\n');
+            }
+        }
+    }
+
+    outputs
+}
+
+fn disagreements(year: u16, day: u8, extra: &[&str], expected: &[String]) -> Vec<String> {
+    let mut args = vec!["--algo", "all"];
+    args.extend(extra);
+
+    solver_outputs(&common::run(year, day, &args))
+        .iter()
+        .flat_map(|(name, output)| {
+            common::missing_from(output, expected)
+                .into_iter()
+                .map(move |answer| {
+                    format!(
+                        "{} day {}, solver \"{}\": \"{}\" not printed",
+                        year, day, name, answer
+                    )
+                })
+        })
+        .collect()
+}
+
+#[test]
+fn solvers_agree() {
+    if env::var("AOC_REAL_INPUTS").as_deref() != Ok("1") {
+        println!("Set AOC_REAL_INPUTS=1 to compare the solvers on the real inputs");
+        return;
+    }
+
+    let mut runs: Vec<(u16, u8, Vec<String>)> = Vec::new();
+    for (year, day, variants) in common::listed_days() {
+        let input = format!("day{:02}.txt", day);
+        let accepted = format!("day{:02}.expected", day);
+        if variants.is_empty() || !common::input_exists(year, &input) {
+            continue;
+        }
+        if let Some(expected) = common::expected_answers(year, &accepted) {
+            runs.push((year, day, expected));
+        }
+    }
+
+    let failures: Vec<String> = runs
+        .par_iter()
+        .flat_map(|(year, day, expected)| disagreements(*year, *day, &[], expected))
+        .collect();
+
+    println!("Compared the solvers of {} days", runs.len());
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
@@ -0,0 +1,39 @@
+// The solvers built for WebAssembly, with the text going in and out of the
+// module's memory hidden behind solve(). See the README for how to build
+// advent_of_code.wasm next to this file.
+export async function load(url = "advent_of_code.wasm") {
+  const module = await WebAssembly.compileStreaming(fetch(url));
+  let solvers = await WebAssembly.instantiate(module, {});
+
+  return {
+    // The same JSON the serve command answers with, the answer and its notes
+    // or an error. The browser gives the module no clock, so the time it took
+    // is measured here.
+    async solve(year, day, part, input) {
+      const wasm = solvers.exports;
+      const bytes = new TextEncoder().encode(input);
+      const room = wasm.wasm_alloc(bytes.length);
+      new Uint8Array(wasm.memory.buffer, room, bytes.length).set(bytes);
+
+      const start = performance.now();
+      let length;
+      try {
+        length = wasm.wasm_solve(year, day, part, room, bytes.length);
+      } catch (crash) {
+        // A panic aborts the whole module, the next solve gets a fresh one
+        solvers = await WebAssembly.instantiate(module, {});
+        return { error: "The solver crashed on this input" };
+      }
+      const micros = Math.round((performance.now() - start) * 1000);
+      wasm.wasm_free(room, bytes.length);
+
+      const reply = new Uint8Array(wasm.memory.buffer, wasm.wasm_reply(), length);
+      const solved = JSON.parse(new TextDecoder().decode(reply));
+      if ("answer" in solved) {
+        solved.micros = micros;
+        solved.took = `${(micros / 1000).toFixed(3)}ms`;
+      }
+      return solved;
+    },
+  };
+}